@@ -0,0 +1,71 @@
+//! Arrow round-trip vs. JSON serialization for a numeric dataset.
+//!
+//! Run with: `cargo bench --features arrow --bench arrow_roundtrip`
+//! Row count defaults to 1,000,000; override with `ARROW_BENCH_ROWS`.
+
+use arrow_schema::{DataType, Field, Schema};
+use oxide_flow::types::Data;
+use std::time::{Duration, Instant};
+
+fn dataset(rows: usize) -> Data {
+    let records = (0..rows)
+        .map(|i| {
+            serde_json::json!({
+                "id": i as i64,
+                "value": i as f64 * 0.5,
+                "bucket": (i % 100) as i64,
+            })
+        })
+        .collect();
+    Data::Json(serde_json::Value::Array(records))
+}
+
+fn time<T>(label: &str, f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    println!("  {label:<28} {:>10.1} ms", elapsed.as_secs_f64() * 1000.0);
+    (result, elapsed)
+}
+
+fn main() {
+    let rows = std::env::var("ARROW_BENCH_ROWS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1_000_000);
+    let data = dataset(rows);
+    let schema = Schema::new(vec![
+        Field::new("bucket", DataType::Int64, false),
+        Field::new("id", DataType::Int64, false),
+        Field::new("value", DataType::Float64, false),
+    ]);
+
+    println!("Arrow vs JSON round-trip, {rows} numeric rows\n");
+
+    println!("JSON:");
+    let json = data.as_json().unwrap();
+    let (bytes, json_encode) = time("serialize", || serde_json::to_vec(json).unwrap());
+    let (_, json_decode) = time("deserialize", || {
+        serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+    });
+    println!("  {:<28} {:>10.1} MB", "encoded size", mb(bytes.len()));
+
+    println!("\nArrow:");
+    let (batch, arrow_encode) = time("Data::to_arrow", || data.to_arrow(&schema).unwrap());
+    let arrow_bytes = batch.get_array_memory_size();
+    let (_, arrow_decode) = time("Data::from_arrow", || Data::from_arrow(batch).unwrap());
+    println!("  {:<28} {:>10.1} MB", "columnar size", mb(arrow_bytes));
+
+    let json_total = json_encode + json_decode;
+    let arrow_total = arrow_encode + arrow_decode;
+    println!(
+        "\nTotal: JSON {:.1} ms, Arrow {:.1} ms; Arrow batch is {:.1}x smaller than JSON text",
+        json_total.as_secs_f64() * 1000.0,
+        arrow_total.as_secs_f64() * 1000.0,
+        bytes.len() as f64 / arrow_bytes as f64
+    );
+}
+
+fn mb(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
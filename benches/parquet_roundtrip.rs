@@ -0,0 +1,102 @@
+//! Parquet vs. JSON and CSV round-trip for a dataset with mixed column types.
+//!
+//! Run with: `cargo bench --features parquet --bench parquet_roundtrip`
+//! Row count defaults to 100,000; override with `PARQUET_BENCH_ROWS`.
+
+use oxide_flow::types::OxiData;
+use std::time::{Duration, Instant};
+
+const COLUMNS: [&str; 6] = ["id", "name", "score", "active", "country", "note"];
+const COUNTRIES: [&str; 5] = ["DE", "FR", "NZ", "US", "JP"];
+
+fn dataset(rows: usize) -> serde_json::Value {
+    let records = (0..rows)
+        .map(|i| {
+            serde_json::json!({
+                "id": i as i64,
+                "name": format!("user-{i:06}"),
+                "score": (i % 1000) as f64 * 0.25,
+                "active": i % 3 != 0,
+                "country": COUNTRIES[i % COUNTRIES.len()],
+                "note": if i % 10 == 0 { serde_json::Value::Null } else { format!("note {}", i % 97).into() },
+            })
+        })
+        .collect();
+    serde_json::Value::Array(records)
+}
+
+fn time<T>(label: &str, f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    println!("  {label:<28} {:>10.1} ms", elapsed.as_secs_f64() * 1000.0);
+    (result, elapsed)
+}
+
+fn csv_encode(records: &serde_json::Value) -> Vec<u8> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(COLUMNS).unwrap();
+    for record in records.as_array().unwrap() {
+        let row = COLUMNS.map(|column| match &record[column] {
+            serde_json::Value::Null => String::new(),
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        });
+        writer.write_record(&row).unwrap();
+    }
+    writer.into_inner().unwrap()
+}
+
+fn csv_decode(bytes: &[u8]) -> Vec<csv::StringRecord> {
+    csv::Reader::from_reader(bytes)
+        .records()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+fn main() {
+    let rows = std::env::var("PARQUET_BENCH_ROWS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(100_000);
+    let records = dataset(rows);
+
+    println!("Parquet vs JSON vs CSV round-trip, {rows} mixed-type rows\n");
+
+    println!("JSON:");
+    let (json, json_encode) = time("serialize", || serde_json::to_vec(&records).unwrap());
+    let (_, json_decode) = time("deserialize", || {
+        serde_json::from_slice::<serde_json::Value>(&json).unwrap()
+    });
+    println!("  {:<28} {:>10.2} MB", "encoded size", mb(json.len()));
+
+    println!("\nCSV:");
+    let (csv, csv_encode) = time("write", || csv_encode(&records));
+    let (_, csv_decode) = time("read (untyped strings)", || csv_decode(&csv));
+    println!("  {:<28} {:>10.2} MB", "encoded size", mb(csv.len()));
+
+    println!("\nParquet (Snappy):");
+    let data = OxiData::from_json(records);
+    let (parquet, parquet_encode) = time("OxiData::to_parquet", || data.to_parquet().unwrap());
+    let (_, parquet_decode) = time("OxiData::from_parquet", || {
+        OxiData::from_parquet(&parquet).unwrap()
+    });
+    println!("  {:<28} {:>10.2} MB", "encoded size", mb(parquet.len()));
+
+    let total = |encode: Duration, decode: Duration| (encode + decode).as_secs_f64() * 1000.0;
+    println!(
+        "\nTotal: JSON {:.1} ms, CSV {:.1} ms, Parquet {:.1} ms",
+        total(json_encode, json_decode),
+        total(csv_encode, csv_decode),
+        total(parquet_encode, parquet_decode)
+    );
+    println!(
+        "Parquet file is {:.1}x smaller than JSON and {:.1}x smaller than CSV",
+        json.len() as f64 / parquet.len() as f64,
+        csv.len() as f64 / parquet.len() as f64
+    );
+}
+
+fn mb(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
@@ -0,0 +1,229 @@
+//! Apache Arrow interop (enabled with the `arrow` feature)
+//!
+//! Record batches convert to `Data::Json` arrays of row objects and back, and
+//! `OxiSchema` maps onto Arrow schemas so typed columns survive the trip.
+
+use crate::types::{Data, FieldSchema, FieldType, OxiSchema};
+use anyhow::{anyhow, Context, Result};
+use arrow_array::RecordBatch;
+use arrow_schema::{DataType, Field, Fields, Schema, TimeUnit};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+impl Data {
+    /// Convert a record batch into a JSON array with one object per row
+    pub fn from_arrow(batch: RecordBatch) -> Result<Data> {
+        let mut writer = arrow_json::WriterBuilder::new()
+            .with_explicit_nulls(true)
+            .build::<_, arrow_json::writer::JsonArray>(Vec::new());
+        writer
+            .write(&batch)
+            .context("Failed to encode record batch as JSON")?;
+        writer.finish()?;
+
+        let bytes = writer.into_inner();
+        if bytes.is_empty() {
+            // The writer emits nothing for an empty batch
+            return Ok(Data::Json(serde_json::Value::Array(Vec::new())));
+        }
+        Ok(Data::Json(serde_json::from_slice(&bytes)?))
+    }
+
+    /// Build a record batch with the given schema from JSON rows
+    ///
+    /// Accepts an array of objects or a single object (one row).
+    pub fn to_arrow(&self, schema: &Schema) -> Result<RecordBatch> {
+        let json = self.as_json().map_err(|_| {
+            anyhow!(
+                "Arrow conversion requires JSON data, got {}",
+                self.data_type()
+            )
+        })?;
+        let rows: Vec<&serde_json::Value> = match json {
+            serde_json::Value::Array(items) => items.iter().collect(),
+            object @ serde_json::Value::Object(_) => vec![object],
+            other => return Err(anyhow!("Expected JSON objects for Arrow rows, got {other}")),
+        };
+
+        let schema = Arc::new(schema.clone());
+        let mut decoder = arrow_json::ReaderBuilder::new(schema.clone())
+            .with_batch_size(rows.len().max(1))
+            .build_decoder()?;
+        decoder
+            .serialize(&rows)
+            .context("JSON rows do not match the Arrow schema")?;
+
+        Ok(decoder
+            .flush()?
+            .unwrap_or_else(|| RecordBatch::new_empty(schema)))
+    }
+}
+
+impl OxiSchema {
+    /// Map an Arrow schema onto Oxi field types
+    pub fn from_arrow_schema(schema: &Schema) -> Self {
+        let mut oxi_schema = Self::empty();
+        oxi_schema.metadata.created_by = "arrow".to_string();
+        for field in schema.fields() {
+            oxi_schema.add_field(field.name().clone(), field_schema_from_arrow(field));
+        }
+        oxi_schema
+    }
+
+    /// Map Oxi field types onto an Arrow schema (fields sorted by name)
+    pub fn to_arrow_schema(&self) -> Schema {
+        Schema::new(arrow_fields(&self.fields))
+    }
+}
+
+fn field_schema_from_arrow(field: &Field) -> FieldSchema {
+    let mut schema = FieldSchema::new(field_type_from_arrow(field.data_type()));
+    schema.nullable = field.is_nullable();
+    schema
+}
+
+fn field_type_from_arrow(data_type: &DataType) -> FieldType {
+    match data_type {
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::UInt8
+        | DataType::UInt16
+        | DataType::UInt32
+        | DataType::UInt64 => FieldType::Integer,
+        DataType::Float16
+        | DataType::Float32
+        | DataType::Float64
+        | DataType::Decimal128(_, _)
+        | DataType::Decimal256(_, _) => FieldType::Float,
+        DataType::Boolean => FieldType::Boolean,
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => FieldType::String,
+        DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => FieldType::DateTime,
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => {
+            FieldType::Binary
+        }
+        DataType::List(item) | DataType::LargeList(item) | DataType::FixedSizeList(item, _) => {
+            FieldType::Array(Box::new(field_type_from_arrow(item.data_type())))
+        }
+        DataType::Struct(fields) => FieldType::Object(
+            fields
+                .iter()
+                .map(|f| (f.name().clone(), field_schema_from_arrow(f)))
+                .collect(),
+        ),
+        _ => FieldType::Unknown,
+    }
+}
+
+fn arrow_fields(fields: &HashMap<String, FieldSchema>) -> Vec<Field> {
+    let mut names: Vec<&String> = fields.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let field = &fields[name];
+            Field::new(name, arrow_type(&field.field_type), field.nullable)
+        })
+        .collect()
+}
+
+fn arrow_type(field_type: &FieldType) -> DataType {
+    match field_type {
+        FieldType::String => DataType::Utf8,
+        FieldType::Integer => DataType::Int64,
+        FieldType::Float => DataType::Float64,
+        FieldType::Boolean => DataType::Boolean,
+        // An offset rather than "UTC": named zones need arrow's chrono-tz feature
+        FieldType::DateTime => DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        FieldType::Binary => DataType::Binary,
+        FieldType::Array(item) => {
+            DataType::List(Arc::new(Field::new("item", arrow_type(item), true)))
+        }
+        FieldType::Object(fields) => DataType::Struct(Fields::from(arrow_fields(fields))),
+        // Arrow has no dynamic type; keep values as text
+        FieldType::Unknown | FieldType::Mixed => DataType::Utf8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{Array, Float64Array, Int64Array, StringArray};
+    use serde_json::json;
+
+    #[test]
+    fn test_json_arrow_round_trip() {
+        let data = Data::Json(json!([
+            {"id": 1, "name": "alice", "score": 9.5},
+            {"id": 2, "name": null, "score": 7.25}
+        ]));
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("score", DataType::Float64, false),
+        ]);
+
+        let batch = data.to_arrow(&schema).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        let ids = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(ids.value(1), 2);
+        let names = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert!(names.is_null(1));
+        let scores = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(scores.value(0), 9.5);
+
+        let restored = Data::from_arrow(batch).unwrap();
+        assert_eq!(restored.as_json().unwrap(), data.as_json().unwrap());
+    }
+
+    #[test]
+    fn test_empty_and_mismatched_rows() {
+        let schema = Schema::new(vec![Field::new("id", DataType::Int64, false)]);
+
+        let empty = Data::Json(json!([])).to_arrow(&schema).unwrap();
+        assert_eq!(empty.num_rows(), 0);
+        assert_eq!(
+            Data::from_arrow(empty).unwrap().as_json().unwrap(),
+            &json!([])
+        );
+
+        assert!(Data::Json(json!([{"id": "not a number"}]))
+            .to_arrow(&schema)
+            .is_err());
+        assert!(Data::Text("id\n1".to_string()).to_arrow(&schema).is_err());
+    }
+
+    #[test]
+    fn test_schema_mapping() {
+        let mut oxi = OxiSchema::empty();
+        oxi.add_field("id".to_string(), FieldSchema::new(FieldType::Integer));
+        let mut tags = FieldSchema::new(FieldType::Array(Box::new(FieldType::String)));
+        tags.nullable = true;
+        oxi.add_field("tags".to_string(), tags);
+        oxi.add_field("seen".to_string(), FieldSchema::new(FieldType::DateTime));
+
+        let arrow = oxi.to_arrow_schema();
+        let names: Vec<&str> = arrow.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, vec!["id", "seen", "tags"]);
+        assert_eq!(arrow.field(0).data_type(), &DataType::Int64);
+        assert!(arrow.field(2).is_nullable());
+
+        let back = OxiSchema::from_arrow_schema(&arrow);
+        assert_eq!(back.fields["id"].field_type, FieldType::Integer);
+        assert_eq!(back.fields["seen"].field_type, FieldType::DateTime);
+        assert_eq!(back.fields["tags"], oxi.fields["tags"]);
+    }
+}
@@ -0,0 +1,406 @@
+//! Repeated timed runs of a pipeline for `pipeline benchmark`
+//!
+//! A benchmark feeds the pipeline synthetic records shaped like the input of
+//! its first step after any sources, runs it a few times to warm up, then
+//! times a number of runs. Each run tracks state in a fresh in-memory
+//! `StateManager`, so state files never show up in the timings. Leading
+//! source steps (`read_*`) are replaced by the synthetic records and sink
+//! steps (`write_*`) pass their input through, so the runs measure the
+//! pipeline's processing rather than its I/O.
+
+use crate::config_resolver::ConfigResolver;
+use crate::error::OxiError;
+use crate::pipeline::{Pipeline, StepOxi};
+use crate::state::manager::{duration_percentile, StateManager};
+use crate::types::{
+    FieldSchema, FieldType, OxiConfig, OxiData, OxiSchema, ProcessingLimits, SchemaStrategy,
+};
+use crate::Oxi;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Records generated when no data size is requested
+pub const DEFAULT_BENCHMARK_RECORDS: usize = 1000;
+
+/// Seed of the synthetic input, fixed so runs of a benchmark are comparable
+const BENCHMARK_SEED: u64 = 42;
+
+/// Two-sided 95% critical values of Student's t for 1 to 30 degrees of freedom
+const T_CRITICAL_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+/// Two-sided 95% critical value of Student's t with `df` degrees of freedom
+///
+/// Exact from the table up to 30, then the next lower tabulated value (40,
+/// 60, 120), which keeps the interval conservative, and the normal 1.96 for
+/// very large samples.
+fn t_critical_95(df: usize) -> f64 {
+    match df {
+        0 => f64::NAN,
+        1..=30 => T_CRITICAL_95[df - 1],
+        31..=40 => 2.042,
+        41..=60 => 2.021,
+        61..=120 => 2.000,
+        121..=1000 => 1.980,
+        _ => 1.960,
+    }
+}
+
+/// Timing statistics of a benchmark's measured runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkStats {
+    pub iterations: usize,
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    /// Sample standard deviation (n - 1 denominator)
+    pub std_dev: Duration,
+    /// Half-width of the 95% confidence interval of the mean; `None` for a single run
+    pub ci95: Option<Duration>,
+    /// Input records per run
+    pub records: usize,
+    /// Input records per second at the mean run time
+    pub throughput: f64,
+}
+
+impl BenchmarkStats {
+    /// Statistics of `samples`, the durations of runs over `records` input records each
+    ///
+    /// The confidence interval uses Student's t rather than the normal
+    /// distribution, so it stays honest for the handful of iterations a
+    /// benchmark usually has. Returns `None` without samples.
+    pub fn from_samples(samples: &[Duration], records: usize) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+
+        let n = sorted.len() as f64;
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / n;
+        let variance = if sorted.len() > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let std_dev = variance.sqrt();
+        let ci95 = (sorted.len() > 1)
+            .then(|| Duration::from_secs_f64(t_critical_95(sorted.len() - 1) * std_dev / n.sqrt()));
+
+        Some(Self {
+            iterations: sorted.len(),
+            min,
+            max,
+            mean: Duration::from_secs_f64(mean),
+            median: duration_percentile(&sorted, 0.5)?,
+            p95: duration_percentile(&sorted, 0.95)?,
+            std_dev: Duration::from_secs_f64(std_dev),
+            ci95,
+            records,
+            throughput: if mean > 0.0 {
+                records as f64 / mean
+            } else {
+                0.0
+            },
+        })
+    }
+
+    /// The statistics as a Markdown table of `name`, value and unit rows
+    ///
+    /// Each row is one `github-action-benchmark` entry (`name`, `value`,
+    /// `unit`), named `<benchmark>/<metric>`.
+    pub fn to_markdown(&self, benchmark: &str) -> String {
+        let ms = |d: Duration| format!("{:.3}", d.as_secs_f64() * 1000.0);
+        let mut rows = vec![
+            ("min", ms(self.min), "ms"),
+            ("max", ms(self.max), "ms"),
+            ("mean", ms(self.mean), "ms"),
+            ("median", ms(self.median), "ms"),
+            ("p95", ms(self.p95), "ms"),
+            ("std_dev", ms(self.std_dev), "ms"),
+        ];
+        if let Some(ci95) = self.ci95 {
+            rows.push(("mean_ci95", ms(ci95), "± ms"));
+        }
+        rows.push(("throughput", format!("{:.1}", self.throughput), "records/s"));
+
+        let mut table = String::from("| name | value | unit |\n|------|-------|------|\n");
+        for (metric, value, unit) in rows {
+            table.push_str(&format!("| {benchmark}/{metric} | {value} | {unit} |\n"));
+        }
+        table
+    }
+}
+
+/// Schema of the records a pipeline's first step after its sources expects
+///
+/// The step's `required_input_schema` when it declares one, otherwise a
+/// generic record of an id, a name, a score, a flag and a timestamp.
+pub fn input_schema(pipeline: &Pipeline, resolver: &ConfigResolver) -> OxiSchema {
+    let first = pipeline.pipeline.iter().find(|step| !is_source(&step.name));
+    let declared = first.and_then(|step| {
+        let config = step
+            .to_oxi_config(resolver)
+            .unwrap_or_else(|_| step.to_oxi_config_simple());
+        step.resolve_oxi()?.required_input_schema(&config)
+    });
+    if let Some(schema) = declared.filter(|schema| !schema.fields.is_empty()) {
+        return schema;
+    }
+
+    let mut schema = OxiSchema::empty();
+    for (name, field_type) in [
+        ("id", FieldType::Integer),
+        ("name", FieldType::String),
+        ("score", FieldType::Float),
+        ("active", FieldType::Boolean),
+        ("created_at", FieldType::DateTime),
+    ] {
+        schema.add_field(name.to_string(), FieldSchema::new(field_type));
+    }
+    schema
+}
+
+/// Synthetic records for `schema`, about `data_size_mb` of JSON or the default count
+pub fn synthetic_input(schema: &OxiSchema, data_size_mb: Option<f64>) -> Result<OxiData> {
+    let count = match data_size_mb {
+        None => DEFAULT_BENCHMARK_RECORDS,
+        Some(mb) => {
+            // Size a sample to estimate the bytes per record
+            const SAMPLE: usize = 100;
+            let sample = schema.generate_test_data(SAMPLE, BENCHMARK_SEED)?;
+            let bytes = serde_json::to_vec(sample.data().as_json()?)?.len();
+            let per_record = (bytes as f64 / SAMPLE as f64).max(1.0);
+            ((mb * 1024.0 * 1024.0 / per_record).ceil() as usize).max(1)
+        }
+    };
+    schema.generate_test_data(count, BENCHMARK_SEED)
+}
+
+/// Whether the Oxi named `name` reads its records from outside the pipeline
+fn is_source(name: &str) -> bool {
+    name.starts_with("read_")
+}
+
+/// Whether the Oxi named `name` writes its records outside the pipeline
+fn is_sink(name: &str) -> bool {
+    name.starts_with("write_")
+}
+
+/// Stand-in for a source or sink step, so benchmark runs do no I/O
+///
+/// A source stub returns the synthetic records in place of what it would
+/// have read; a sink stub hands its input on without writing it.
+struct IoStub {
+    name: String,
+    output: Option<OxiData>,
+}
+
+#[async_trait]
+impl Oxi for IoStub {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn process(&self, input: OxiData, _config: &OxiConfig) -> Result<OxiData, OxiError> {
+        Ok(self.output.clone().unwrap_or(input))
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Passthrough
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        ProcessingLimits {
+            max_batch_size: None,
+            max_memory_mb: None,
+            max_processing_time_ms: None,
+            ..ProcessingLimits::default()
+        }
+    }
+
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+}
+
+/// `pipeline` with its leading sources returning `input` and its sinks writing nothing
+///
+/// Readers ignore their input, so a benchmark would otherwise time the real
+/// file or stdin read instead of the synthetic records. Sources after the
+/// first other step (lookups joined in later) still run. Sinks would print
+/// to stdout alongside the results table, or write their output files once
+/// per iteration.
+pub fn without_io(pipeline: &Pipeline, input: &OxiData) -> Pipeline {
+    let mut pipeline = pipeline.clone();
+    let mut leading = true;
+    for step in &mut pipeline.pipeline {
+        leading &= is_source(&step.name);
+        let output = if leading {
+            Some(input.clone())
+        } else if is_sink(&step.name) {
+            None
+        } else {
+            continue;
+        };
+        step.oxi = Some(StepOxi(Arc::new(IoStub {
+            name: step.name.clone(),
+            output,
+        })));
+    }
+    pipeline
+}
+
+/// Run `pipeline` on `input` `warmup + iterations` times, returning the measured durations
+///
+/// Sources and sinks are stubbed out with [`without_io`] and warm-up runs
+/// are discarded. A failed run fails the benchmark, since its time says nothing
+/// about the pipeline's speed.
+pub async fn run_benchmark(
+    pipeline: &Pipeline,
+    resolver: &ConfigResolver,
+    input: &OxiData,
+    iterations: u32,
+    warmup: u32,
+) -> Result<Vec<Duration>> {
+    let pipeline = without_io(pipeline, input);
+    let mut samples = Vec::with_capacity(iterations as usize);
+    for run in 0..warmup + iterations {
+        let state_manager = StateManager::new_memory();
+        let started = Instant::now();
+        let result = pipeline
+            .execute_with_state_tracking(input.clone(), resolver, Some(state_manager))
+            .await;
+        let elapsed = started.elapsed();
+
+        if !result.success {
+            anyhow::bail!(
+                "Benchmark run {} failed with {} failed steps",
+                run + 1,
+                result.steps_failed
+            );
+        }
+        if run >= warmup {
+            samples.push(elapsed);
+        }
+    }
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_millis(v)).collect()
+    }
+
+    #[test]
+    fn test_stats_use_student_t_for_small_samples() {
+        let stats = BenchmarkStats::from_samples(&ms(&[12, 10, 11, 13, 14]), 1000).unwrap();
+
+        assert_eq!(stats.iterations, 5);
+        assert_eq!(stats.min, Duration::from_millis(10));
+        assert_eq!(stats.max, Duration::from_millis(14));
+        assert_eq!(stats.mean, Duration::from_millis(12));
+        assert_eq!(stats.median, Duration::from_millis(12));
+        // Sample standard deviation of 10..=14 is sqrt(2.5)
+        let std_dev = stats.std_dev.as_secs_f64() * 1000.0;
+        assert!((std_dev - 2.5f64.sqrt()).abs() < 1e-6, "{std_dev}");
+        // t(4) = 2.776, not the normal 1.96
+        let ci95 = stats.ci95.unwrap().as_secs_f64() * 1000.0;
+        assert!(
+            (ci95 - 2.776 * 2.5f64.sqrt() / 5f64.sqrt()).abs() < 1e-6,
+            "{ci95}"
+        );
+        assert!((stats.throughput - 1000.0 / 0.012).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_single_run_has_no_interval() {
+        let stats = BenchmarkStats::from_samples(&ms(&[20]), 10).unwrap();
+        assert_eq!(stats.p95, Duration::from_millis(20));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+        assert!(stats.ci95.is_none());
+        assert!(!stats.to_markdown("orders").contains("ci95"));
+        assert!(BenchmarkStats::from_samples(&[], 10).is_none());
+    }
+
+    #[test]
+    fn test_markdown_has_one_row_per_metric() {
+        let stats = BenchmarkStats::from_samples(&ms(&[10, 20]), 100).unwrap();
+        let table = stats.to_markdown("orders");
+
+        assert!(table.starts_with("| name | value | unit |\n"));
+        assert!(table.contains("| orders/mean | 15.000 | ms |"));
+        assert!(table.contains("| orders/throughput | 6666.7 | records/s |"));
+        assert_eq!(table.lines().count(), 2 + 8);
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_runs_skip_sources_and_sinks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path_config = |name: &str| {
+            let path = temp_dir.path().join(name);
+            OxiConfig::builder()
+                .set("path", path.to_string_lossy().to_string())
+                .build()
+        };
+        let pipeline = crate::pipeline::builder::PipelineBuilder::new("io")
+            .step("reader", "read_file", path_config("missing.json"))
+            .step("fields", "flatten", OxiConfig::default())
+            .step("writer", "write_file", path_config("out.json"))
+            .build();
+        let input = OxiData::from_json(serde_json::json!([{ "id": 1, "tags": { "a": 2 } }]));
+
+        // The missing input file is never read and the output never written
+        let samples = run_benchmark(&pipeline, &ConfigResolver::default(), &input, 2, 1)
+            .await
+            .unwrap();
+        assert_eq!(samples.len(), 2);
+        assert!(!temp_dir.path().join("out.json").exists());
+        // The pipeline itself is left alone
+        assert!(pipeline.pipeline.iter().all(|step| step.oxi.is_none()));
+
+        // The synthetic records reach the first real step
+        let result = without_io(&pipeline, &input)
+            .execute_with_state_tracking(OxiData::empty(), &ConfigResolver::default(), None)
+            .await;
+        let output = result.final_data.unwrap();
+        assert_eq!(
+            output.data().as_json().unwrap(),
+            &serde_json::json!([{ "id": 1, "tags_a": 2 }])
+        );
+    }
+
+    #[test]
+    fn test_synthetic_input_is_sized_in_megabytes() {
+        let pipeline = crate::pipeline::builder::PipelineBuilder::new("empty").build();
+        let schema = input_schema(&pipeline, &ConfigResolver::default());
+        assert!(schema.fields.contains_key("id"));
+
+        let input = synthetic_input(&schema, Some(0.25)).unwrap();
+        let bytes = serde_json::to_vec(input.data().as_json().unwrap())
+            .unwrap()
+            .len() as f64;
+        let target = 0.25 * 1024.0 * 1024.0;
+        assert!((bytes - target).abs() / target < 0.2, "{bytes}");
+        assert_eq!(
+            synthetic_input(&schema, None)
+                .unwrap()
+                .data()
+                .as_json()
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .len(),
+            DEFAULT_BENCHMARK_RECORDS
+        );
+    }
+}
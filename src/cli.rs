@@ -1,4 +1,7 @@
+use crate::pipeline_manager::OutputFormat;
+use crate::state::types::StateSortKey;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -7,6 +10,10 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
+    /// Load variables from this .env file (real environment variables take precedence)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub env_file: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -22,6 +29,18 @@ pub enum Commands {
         /// Target directory (defaults to current directory)
         #[arg(short, long)]
         directory: Option<String>,
+
+        /// Project template: minimal, example or etl
+        #[arg(short, long, default_value = "minimal")]
+        template: String,
+
+        /// Initialize even if the target directory is not empty
+        #[arg(long)]
+        force: bool,
+
+        /// List available project templates and exit
+        #[arg(long)]
+        list_templates: bool,
     },
     /// Run a pipeline from a YAML file
     Run {
@@ -32,6 +51,31 @@ pub enum Commands {
         /// Path to configuration file
         #[arg(short, long)]
         config: Option<String>,
+
+        /// Start even if another run of this pipeline appears to be active
+        #[arg(long)]
+        force: bool,
+
+        /// Re-run whenever the pipeline file or its input files change
+        #[arg(short, long)]
+        watch: bool,
+
+        /// Output the step timing summary as JSON (for CI), with progress on stderr
+        #[arg(long)]
+        json: bool,
+    },
+    /// Validate a pipeline and report each check, for CI
+    Validate {
+        /// Pipeline name to validate
+        pipeline: String,
+
+        /// Treat warnings as errors (non-zero exit code)
+        #[arg(long)]
+        strict: bool,
+
+        /// Output format: human, json, sarif or junit
+        #[arg(long = "format", default_value = "human")]
+        output_format: OutputFormat,
     },
     /// Manage pipelines (list, add, test, info)
     Pipeline {
@@ -48,6 +92,32 @@ pub enum Commands {
         #[command(subcommand)]
         action: WorkerAction,
     },
+    /// Inspect Oxi plugins (info)
+    Plugin {
+        #[command(subcommand)]
+        action: PluginAction,
+    },
+    /// Browse the Oxis pipeline steps can use (list, describe)
+    Oxi {
+        #[command(subcommand)]
+        action: OxiAction,
+    },
+    /// Work with pipeline data schemas (export)
+    Schema {
+        #[command(subcommand)]
+        action: SchemaAction,
+    },
+    /// Generate a shell completion script
+    Completions {
+        /// Target shell
+        shell: clap_complete::Shell,
+    },
+    /// Print pipeline names for shell completion
+    #[command(name = "__complete-pipelines", hide = true)]
+    CompletePipelines,
+    /// Print pipeline state ids for shell completion
+    #[command(name = "__complete-states", hide = true)]
+    CompleteStates,
 }
 
 #[derive(Subcommand, Debug)]
@@ -82,6 +152,62 @@ pub enum PipelineAction {
         /// Pipeline author
         #[arg(short, long)]
         author: Option<String>,
+
+        /// Set a template variable (repeatable), e.g. --set source_table=users
+        #[arg(long = "set", value_name = "VAR=VALUE", value_parser = parse_template_var)]
+        set: Vec<(String, String)>,
+    },
+    /// Delete a pipeline (optionally with its state)
+    Delete {
+        /// Name of the pipeline to delete
+        name: String,
+
+        /// Skip confirmation prompts and delete associated state
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Rename a pipeline, its metadata name, and its state
+    Rename {
+        /// Current pipeline name
+        name: String,
+
+        /// New pipeline name (snake_case)
+        new_name: String,
+    },
+    /// Duplicate a pipeline under a new name
+    Copy {
+        /// Pipeline to copy
+        name: String,
+
+        /// Name of the copy (snake_case)
+        new_name: String,
+    },
+    /// Print a pipeline's YAML, or package it with its requirements as a bundle
+    Export {
+        /// Name of the pipeline to export
+        name: String,
+
+        /// Write a .tar.gz bundle with a manifest of required env vars and Oxis
+        #[arg(long)]
+        bundle: bool,
+
+        /// Output file (default: stdout, or <name>.tar.gz with --bundle)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import a pipeline bundle after checking its requirements
+    Import {
+        /// Bundle created by `pipeline export --bundle`
+        bundle: PathBuf,
+
+        /// Overwrite an existing pipeline file
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Browse pipeline templates (built-in and project)
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
     },
     /// Test/validate a pipeline
     Test {
@@ -103,16 +229,68 @@ pub enum PipelineAction {
         /// Validate against schemas only
         #[arg(long)]
         schema: bool,
+
+        /// Write the validation findings as SARIF to this file (for code scanning)
+        #[arg(long, value_name = "PATH")]
+        sarif: Option<String>,
+
+        /// Fail on warnings as well as errors
+        #[arg(long)]
+        strict: bool,
+    },
+    /// Check pipelines for style and robustness issues
+    Lint {
+        /// Name of the pipeline to lint
+        name: Option<String>,
+
+        /// Lint every pipeline in the project
+        #[arg(long, conflicts_with = "name")]
+        all: bool,
+
+        /// Output findings as JSON (for CI)
+        #[arg(long)]
+        json: bool,
+    },
+    /// Visualize pipeline steps as a graph
+    Graph {
+        /// Name of the pipeline
+        name: String,
+
+        /// Output format: dot (Graphviz) or mermaid
+        #[arg(short, long, default_value = "dot")]
+        format: String,
+
+        /// Color steps by status from the latest recorded run
+        #[arg(long)]
+        from_state: bool,
+    },
+    /// Show how pipelines depend on each other via `metadata.depends_on`
+    Deps {
+        /// Output format: tree, mermaid, or order (run order, one pipeline per line)
+        #[arg(short, long, default_value = "tree")]
+        format: String,
     },
     /// Show detailed pipeline information
     Info {
         /// Name of the pipeline
         name: String,
 
-        /// Show configuration schema for all steps
+        /// Show the input and output schema of every step
         #[arg(long)]
         schema: bool,
 
+        /// Sample input file whose inferred schema seeds --schema (JSON or text)
+        #[arg(long, requires = "schema")]
+        sample: Option<String>,
+
+        /// List past runs with their pipeline hashes, statuses and durations
+        #[arg(long)]
+        history: bool,
+
+        /// Print a step's config after merging the project's defaults, before resolving references
+        #[arg(long, value_name = "STEP")]
+        show_effective_config: Option<String>,
+
         /// Output in JSON format
         #[arg(long)]
         json: bool,
@@ -121,6 +299,71 @@ pub enum PipelineAction {
         #[arg(long)]
         yaml: bool,
     },
+    /// Time repeated runs on synthetic input and print a Markdown table
+    Benchmark {
+        /// Name of the pipeline to benchmark
+        name: String,
+
+        /// Number of measured runs
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        iterations: u32,
+
+        /// Number of untimed runs before measuring
+        #[arg(long, default_value_t = 2)]
+        warmup: u32,
+
+        /// Approximate size of the synthetic input in MB (default: 1000 records)
+        #[arg(long, value_name = "MB")]
+        data_size_mb: Option<f64>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateAction {
+    /// List available templates
+    List,
+    /// Print a template's content and variables
+    Show {
+        /// Template name
+        name: String,
+    },
+}
+
+/// Parse a `var=value` template variable assignment
+fn parse_template_var(s: &str) -> Result<(String, String), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected VAR=VALUE, got '{s}'"))?;
+    if key.is_empty() {
+        return Err(format!("missing variable name in '{s}'"));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Parse a `state list --tag`; a bare key matches the tag with any value
+fn parse_state_tag(s: &str) -> Result<(String, Option<String>), String> {
+    let (key, value) = match s.split_once('=') {
+        Some((key, value)) => (key, Some(value.to_string())),
+        None => (s, None),
+    };
+    if key.is_empty() {
+        return Err(format!("missing tag name in '{s}'"));
+    }
+    Ok((key.to_string(), value))
+}
+
+/// Parse a `state list --sort` key
+fn parse_state_sort(s: &str) -> Result<StateSortKey, String> {
+    match s {
+        "pipeline" => Ok(StateSortKey::PipelineId),
+        "started" => Ok(StateSortKey::StartedAt),
+        "updated" => Ok(StateSortKey::UpdatedAt),
+        "heartbeat" => Ok(StateSortKey::LastHeartbeat),
+        "records" => Ok(StateSortKey::RecordsProcessed),
+        other => Err(format!(
+            "unknown sort key '{other}' (expected pipeline, started, updated, heartbeat or records)"
+        )),
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -130,6 +373,14 @@ pub enum StateAction {
         /// Pipeline name
         pipeline: String,
 
+        /// Only show the state of this step
+        #[arg(long, value_name = "ID")]
+        step: Option<String>,
+
+        /// Show every recorded error, including those archived out of the state
+        #[arg(long, conflicts_with = "step")]
+        errors_all: bool,
+
         /// Output in JSON format
         #[arg(long)]
         json: bool,
@@ -156,6 +407,30 @@ pub enum StateAction {
         #[arg(long)]
         completed: bool,
 
+        /// Only pipelines whose name starts with this
+        #[arg(long)]
+        prefix: Option<String>,
+
+        /// Only runs with this tag, as key=value or a bare key for any value (repeatable)
+        #[arg(long = "tag", value_name = "KEY[=VALUE]", value_parser = parse_state_tag)]
+        tags: Vec<(String, Option<String>)>,
+
+        /// Only runs recorded in this environment
+        #[arg(long)]
+        env: Option<String>,
+
+        /// Sort by pipeline, started, updated, heartbeat or records
+        #[arg(long, default_value = "pipeline", value_parser = parse_state_sort)]
+        sort: StateSortKey,
+
+        /// Sort in descending order
+        #[arg(long)]
+        desc: bool,
+
+        /// Show at most this many states
+        #[arg(long)]
+        limit: Option<usize>,
+
         /// Output in JSON format
         #[arg(long)]
         json: bool,
@@ -164,6 +439,8 @@ pub enum StateAction {
         #[arg(short, long)]
         verbose: bool,
     },
+    /// Rebuild the index `state list` reads states from
+    Reindex,
     /// Clean up old/stale states
     Cleanup {
         /// Remove only stale states (no active workers)
@@ -182,6 +459,57 @@ pub enum StateAction {
         #[arg(short, long)]
         force: bool,
     },
+    /// Delete completed pipeline states (locked states are kept)
+    Purge {
+        /// Only purge states completed more than this many days ago
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u32>,
+
+        /// Dry run - show what would be purged
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Purge without confirmation
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Remove orphaned lock, backup and temporary files from the state directory
+    Gc {
+        /// Dry run - show what would be removed
+        #[arg(long)]
+        dry_run: bool,
+
+        /// List every file removed
+        #[arg(short, long)]
+        verbose: bool,
+    },
+    /// Check stored states for corruption and validation errors
+    Verify {
+        /// Only check this pipeline's state (default: every state file)
+        pipeline: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+    /// Repair a pipeline's state, or restore it from a backup
+    Repair {
+        /// Pipeline name
+        pipeline: String,
+
+        /// Show the issues that would be repaired without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Restore this backup instead of repairing in place
+        #[arg(long, value_name = "BACKUP_ID")]
+        from_backup: Option<String>,
+    },
+    /// List backups of a pipeline's state
+    Backups {
+        /// Pipeline name
+        pipeline: String,
+    },
     /// Export state to JSON/YAML file
     Export {
         /// Pipeline name
@@ -208,6 +536,56 @@ pub enum StateAction {
         #[arg(short, long)]
         force: bool,
     },
+    /// Export every pipeline's state and lock to one gzipped archive
+    ExportAll {
+        /// Output file path
+        #[arg(short, long)]
+        output: String,
+    },
+    /// Import the states in an archive from `state export-all`
+    ImportAll {
+        /// Input file path
+        #[arg(short, long)]
+        input: String,
+
+        /// Overwrite pipelines that already have state
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Check state backend health
+    Health {
+        /// Sample health repeatedly and show the series with its trend
+        #[arg(long)]
+        history: bool,
+
+        /// Number of samples to take with --history
+        #[arg(long, default_value = "5")]
+        samples: usize,
+
+        /// Seconds between samples with --history
+        #[arg(long, default_value = "1")]
+        interval_secs: u64,
+    },
+    /// Stream state and lock changes as they happen (Ctrl+C to stop)
+    Watch {
+        /// Only show events for this pipeline
+        #[arg(short, long)]
+        pipeline: Option<String>,
+
+        /// Output one JSON event per line
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show run counts and duration estimates per pipeline
+    Stats {
+        /// Only show this pipeline
+        #[arg(short, long)]
+        pipeline: Option<String>,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -218,6 +596,14 @@ pub enum WorkerAction {
         #[arg(short, long)]
         pipeline: Option<String>,
 
+        /// Only show workers with a recent heartbeat
+        #[arg(long)]
+        active_only: bool,
+
+        /// Minutes without a heartbeat before a worker counts as stale
+        #[arg(long, default_value_t = 5)]
+        stale_minutes: u32,
+
         /// Output in JSON format
         #[arg(long)]
         json: bool,
@@ -225,6 +611,25 @@ pub enum WorkerAction {
         /// Show detailed information
         #[arg(short, long)]
         verbose: bool,
+
+        /// List held pipeline locks and how long they have been held instead
+        #[arg(long)]
+        locks: bool,
+    },
+    /// Register as a pipeline's worker and send heartbeats until interrupted
+    ///
+    /// For runners outside oxide_flow, so `worker list` tracks them like its own workers.
+    Heartbeat {
+        /// Pipeline name
+        pipeline: String,
+
+        /// Worker ID to register as
+        #[arg(long)]
+        worker_id: String,
+
+        /// Seconds between heartbeats
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        interval_secs: u64,
     },
     /// Stop a specific worker
     Stop {
@@ -236,3 +641,60 @@ pub enum WorkerAction {
         force: bool,
     },
 }
+
+#[derive(Subcommand, Debug)]
+pub enum PluginAction {
+    /// Show documentation for an Oxi
+    Info {
+        /// Oxi name as used in pipeline steps (e.g. read_file)
+        name: String,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum OxiAction {
+    /// List built-in Oxis and the project's plugins
+    List {
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show an Oxi's configuration options and an example step
+    Describe {
+        /// Oxi name as used in pipeline steps (e.g. read_file)
+        name: String,
+
+        /// Output in JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SchemaAction {
+    /// Export a step's output schema as JSON Schema (draft 2020-12)
+    Export {
+        /// Name of the pipeline
+        pipeline: String,
+
+        /// Step whose output schema to export (defaults to the last step)
+        #[arg(long)]
+        step: Option<String>,
+
+        /// Sample input file whose inferred schema seeds the pipeline (JSON or text)
+        #[arg(long)]
+        sample: Option<String>,
+
+        /// Add the value ranges, lengths and enums seen in the sample as constraints
+        #[arg(long, requires = "sample")]
+        infer_constraints: bool,
+
+        /// Write the schema to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
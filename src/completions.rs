@@ -0,0 +1,187 @@
+//! Shell completion scripts with dynamic pipeline name completion
+//!
+//! clap_complete produces the static part of each script. For bash, zsh and
+//! fish we add a small wrapper that completes pipeline arguments by calling the
+//! hidden `__complete-pipelines` / `__complete-states` subcommands, so names
+//! reflect the project the user is in at the time they press Tab.
+
+use crate::cli::Cli;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::io::Write;
+
+/// `pipeline` subcommands whose first argument is a pipeline name
+const PIPELINE_NAME_ACTIONS: &[&str] =
+    &["test", "lint", "graph", "info", "delete", "rename", "copy"];
+
+/// `state` subcommands whose first argument is a state pipeline id
+const STATE_ID_ACTIONS: &[&str] = &["show", "export", "import"];
+
+/// Write the completion script for `shell` to `out`
+pub fn generate(shell: Shell, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut command = Cli::command();
+    let bin = command.get_name().to_string();
+
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut command, bin.clone(), &mut script);
+    let script = String::from_utf8_lossy(&script).into_owned();
+
+    let script = match shell {
+        Shell::Bash => format!("{script}\n{}", bash_dynamic(&bin)),
+        Shell::Zsh => zsh_dynamic(&script, &bin),
+        Shell::Fish => format!("{script}\n{}", fish_dynamic(&bin)),
+        // PowerShell and Elvish get the static completions only
+        _ => script,
+    };
+
+    out.write_all(script.as_bytes())
+}
+
+/// Pipeline names in the current project (empty outside a project)
+pub fn pipeline_names() -> Vec<String> {
+    crate::project::ProjectConfig::load()
+        .and_then(|config| config.pipeline_names())
+        .unwrap_or_default()
+}
+
+/// Pipeline ids that have saved state
+pub async fn state_ids() -> Vec<String> {
+    match crate::state::cli::open_cli_state_manager().await {
+        Ok(manager) => {
+            let mut ids = manager.list_pipelines().await.unwrap_or_default();
+            ids.sort();
+            ids
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+fn bash_dynamic(bin: &str) -> String {
+    let func = bin.replace('-', "_");
+    format!(
+        r#"_{func}_dynamic() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" kind=""
+    if [[ "$cur" != -* ]]; then
+        case "${{COMP_WORDS[1]}}" in
+            run|validate) [[ $COMP_CWORD -eq 2 ]] && kind=pipelines ;;
+            pipeline)
+                case "${{COMP_WORDS[2]}}" in
+                    {pipeline_actions}) [[ $COMP_CWORD -eq 3 ]] && kind=pipelines ;;
+                esac ;;
+            state)
+                case "${{COMP_WORDS[2]}}" in
+                    {state_actions}) [[ $COMP_CWORD -eq 3 ]] && kind=states ;;
+                esac ;;
+        esac
+    fi
+    if [[ -n "$kind" ]]; then
+        COMPREPLY=( $(compgen -W "$({bin} __complete-$kind 2>/dev/null)" -- "$cur") )
+        return 0
+    fi
+    _{func} "$@"
+}}
+
+if [[ "${{BASH_VERSINFO[0]}}" -eq 4 && "${{BASH_VERSINFO[1]}}" -ge 4 || "${{BASH_VERSINFO[0]}}" -gt 4 ]]; then
+    complete -F _{func}_dynamic -o nosort -o bashdefault -o default {bin}
+else
+    complete -F _{func}_dynamic -o bashdefault -o default {bin}
+fi
+"#,
+        pipeline_actions = PIPELINE_NAME_ACTIONS.join("|"),
+        state_actions = STATE_ID_ACTIONS.join("|"),
+    )
+}
+
+fn zsh_dynamic(script: &str, bin: &str) -> String {
+    let func = bin.replace('-', "_");
+    let wrapper = format!(
+        r#"_{func}_dynamic() {{
+    local kind
+    if [[ $PREFIX != -* ]]; then
+        case $words[2] in
+            run|validate) (( CURRENT == 3 )) && kind=pipelines ;;
+            pipeline) [[ $words[3] == ({pipeline_actions}) ]] && (( CURRENT == 4 )) && kind=pipelines ;;
+            state) [[ $words[3] == ({state_actions}) ]] && (( CURRENT == 4 )) && kind=states ;;
+        esac
+    fi
+    if [[ -n $kind ]]; then
+        local -a names
+        names=(${{(f)"$({bin} __complete-$kind 2>/dev/null)"}})
+        compadd -a names
+        return
+    fi
+    _{func} "$@"
+}}
+
+if [ "$funcstack[1]" = "_{func}" ]; then
+    _{func}_dynamic "$@"
+else
+    compdef _{func}_dynamic {bin}
+fi
+"#,
+        pipeline_actions = PIPELINE_NAME_ACTIONS.join("|"),
+        state_actions = STATE_ID_ACTIONS.join("|"),
+    );
+
+    // Swap clap's trailing dispatch block for one that goes through the wrapper
+    let dispatch = format!("if [ \"$funcstack[1]\" = \"_{func}\" ]; then");
+    match script.rfind(&dispatch) {
+        Some(index) => format!("{}{wrapper}", &script[..index]),
+        None => format!("{script}\n{wrapper}"),
+    }
+}
+
+fn fish_dynamic(bin: &str) -> String {
+    let mut lines = vec![format!(
+        "complete -c {bin} -n \"__fish_seen_subcommand_from run validate\" -f -a \"({bin} __complete-pipelines 2>/dev/null)\""
+    )];
+    for action in PIPELINE_NAME_ACTIONS {
+        lines.push(format!(
+            "complete -c {bin} -n \"__fish_seen_subcommand_from pipeline; and __fish_seen_subcommand_from {action}\" -f -a \"({bin} __complete-pipelines 2>/dev/null)\""
+        ));
+    }
+    for action in STATE_ID_ACTIONS {
+        lines.push(format!(
+            "complete -c {bin} -n \"__fish_seen_subcommand_from state; and __fish_seen_subcommand_from {action}\" -f -a \"({bin} __complete-states 2>/dev/null)\""
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_for_each_shell() {
+        for shell in [
+            Shell::Bash,
+            Shell::Zsh,
+            Shell::Fish,
+            Shell::PowerShell,
+            Shell::Elvish,
+        ] {
+            let mut out = Vec::new();
+            generate(shell, &mut out).unwrap();
+            let script = String::from_utf8(out).unwrap();
+            assert!(script.contains("oxide_flow"), "{shell} script is empty");
+
+            if matches!(shell, Shell::Bash | Shell::Zsh | Shell::Fish) {
+                assert!(
+                    script.contains("oxide_flow __complete-"),
+                    "{shell} script lacks dynamic pipeline completion"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_zsh_dispatch_goes_through_wrapper() {
+        let mut out = Vec::new();
+        generate(Shell::Zsh, &mut out).unwrap();
+        let script = String::from_utf8(out).unwrap();
+
+        assert!(script.contains("compdef _oxide_flow_dynamic oxide_flow"));
+        assert!(!script.contains("compdef _oxide_flow oxide_flow"));
+    }
+}
@@ -1,4 +1,5 @@
-use crate::types::OxiConfig;
+use crate::secrets::{EnvSecretResolver, SecretResolver};
+use crate::types::{Data, OxiConfig, OxiData};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -24,6 +25,12 @@ pub enum ConfigError {
 
     #[error("Environment variable not found: {0}")]
     EnvVarNotFound(String),
+
+    #[error("Secret resolution failed: {0}")]
+    SecretError(String),
+
+    #[error("Invalid env file: {0}")]
+    EnvFileError(String),
 }
 
 /// Schema for an individual Oxi configuration
@@ -60,6 +67,92 @@ pub struct PropertySchema {
     pub properties: Option<HashMap<String, PropertySchema>>,
 }
 
+impl OxiConfigSchema {
+    /// Build from the JSON-Schema-style YAML returned by `Oxi::config_schema`
+    ///
+    /// Both a top-level `required` list and per-property `required: true` are honored.
+    /// Properties without a `type` are reported as `any`.
+    pub fn from_yaml_schema(value: &serde_yaml::Value) -> Self {
+        let description = value
+            .get("description")
+            .and_then(|d| d.as_str())
+            .map(|d| d.to_string());
+
+        let mut required: Vec<String> = value
+            .get("required")
+            .and_then(|r| r.as_sequence())
+            .map(|seq| {
+                seq.iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut properties = HashMap::new();
+        if let Some(props) = value.get("properties").and_then(|p| p.as_mapping()) {
+            for (key, prop) in props {
+                let Some(key) = key.as_str() else { continue };
+                if prop.get("required").and_then(|r| r.as_bool()) == Some(true)
+                    && !required.iter().any(|r| r == key)
+                {
+                    required.push(key.to_string());
+                }
+                properties.insert(key.to_string(), PropertySchema::from_yaml_schema(prop));
+            }
+        }
+
+        OxiConfigSchema {
+            description,
+            properties,
+            required,
+        }
+    }
+}
+
+impl PropertySchema {
+    /// Build a property schema from a JSON-Schema-style YAML property definition
+    pub fn from_yaml_schema(value: &serde_yaml::Value) -> Self {
+        let enum_values = value.get("enum").and_then(|e| e.as_sequence()).map(|seq| {
+            seq.iter()
+                .map(|v| match v {
+                    serde_yaml::Value::String(s) => s.clone(),
+                    other => serde_yaml::to_string(other)
+                        .map(|s| s.trim().to_string())
+                        .unwrap_or_default(),
+                })
+                .collect()
+        });
+
+        let properties = value
+            .get("properties")
+            .and_then(|p| p.as_mapping())
+            .map(|props| {
+                props
+                    .iter()
+                    .filter_map(|(k, v)| {
+                        k.as_str()
+                            .map(|k| (k.to_string(), PropertySchema::from_yaml_schema(v)))
+                    })
+                    .collect()
+            });
+
+        PropertySchema {
+            property_type: value
+                .get("type")
+                .and_then(|t| t.as_str())
+                .unwrap_or("any")
+                .to_string(),
+            description: value
+                .get("description")
+                .and_then(|d| d.as_str())
+                .map(|d| d.to_string()),
+            default: value.get("default").cloned(),
+            enum_values,
+            properties,
+        }
+    }
+}
+
 /// Global configuration for Oxide Flow
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -155,23 +248,34 @@ impl Config {
     pub fn get_oxi_defaults(&self, oxi_name: &str) -> Option<&serde_yaml::Value> {
         self.defaults.get(oxi_name)
     }
+}
 
-    /// Merge default and instance configurations for an Oxi
-    pub fn merge_oxi_config(
-        &self,
-        oxi_name: &str,
-        instance_config: &serde_yaml::Value,
-    ) -> serde_yaml::Value {
-        if let Some(defaults) = self.get_oxi_defaults(oxi_name) {
-            merge_yaml_values(defaults, instance_config)
-        } else {
-            instance_config.clone()
+/// Load `KEY=value` lines from a `.env` file into the process environment
+///
+/// Variables already set in the environment keep their value, so real
+/// environment variables override the file. Returns how many were set.
+pub fn load_env_file<P: AsRef<Path>>(path: P) -> Result<usize, ConfigError> {
+    let path = path.as_ref();
+    let entries = dotenvy::from_path_iter(path).map_err(|e| match e {
+        dotenvy::Error::Io(e) => ConfigError::IoError(e),
+        e => ConfigError::EnvFileError(format!("{}: {e}", path.display())),
+    })?;
+
+    let mut loaded = 0;
+    for entry in entries {
+        let (key, value) =
+            entry.map_err(|e| ConfigError::EnvFileError(format!("{}: {e}", path.display())))?;
+        if env::var_os(&key).is_none() {
+            env::set_var(key, value);
+            loaded += 1;
         }
     }
+    Ok(loaded)
 }
 
 /// Substitute environment variables in the configuration
 /// Environment variables are specified as ${ENV_VAR} or ${ENV_VAR:-default}
+/// and `${secret:NAME}` is read through the default (environment) secret resolver.
 /// Also supports references to other pipeline step outputs using ${step_id.property.path}
 pub fn substitute_env_vars(content: &str) -> Result<String, ConfigError> {
     let mut result = String::with_capacity(content.len());
@@ -190,7 +294,12 @@ pub fn substitute_env_vars(content: &str) -> Result<String, ConfigError> {
         last_end = full_match.end();
 
         // Handle different reference types
-        if var_expr.contains('.') {
+        if let Some(name) = var_expr.strip_prefix("secret:") {
+            let value = EnvSecretResolver
+                .resolve(name.trim())
+                .map_err(|e| ConfigError::SecretError(e.to_string()))?;
+            result.push_str(&value);
+        } else if var_expr.contains('.') {
             // This is likely a reference to a pipeline step output
             // For now, just keep it as is (will be resolved later in the pipeline execution)
             result.push_str(full_match.as_str());
@@ -267,7 +376,10 @@ fn validate_config(config: &Config) -> Result<(), ConfigError> {
 }
 
 /// Merge two YAML values, with the right value taking precedence
-fn merge_yaml_values(base: &serde_yaml::Value, overlay: &serde_yaml::Value) -> serde_yaml::Value {
+pub(crate) fn merge_yaml_values(
+    base: &serde_yaml::Value,
+    overlay: &serde_yaml::Value,
+) -> serde_yaml::Value {
     match (base, overlay) {
         (serde_yaml::Value::Mapping(base_map), serde_yaml::Value::Mapping(overlay_map)) => {
             let mut result = base_map.clone();
@@ -350,6 +462,23 @@ impl PipelineContext {
         self.step_metadata.insert(alias.to_string(), metadata);
     }
 
+    /// Record a step's `OxiData`: JSON data becomes its output and
+    /// `OxiData::metadata` its step metadata
+    pub fn add_oxi_output(&mut self, alias: &str, output: &OxiData) {
+        if let Data::Json(value) = &output.data {
+            if let Ok(value) = serde_yaml::to_value(value) {
+                self.add_step_output(alias, value);
+            }
+        }
+
+        let metadata = output
+            .metadata
+            .iter()
+            .filter_map(|(key, value)| Some((key.clone(), serde_yaml::to_value(value).ok()?)))
+            .collect();
+        self.add_step_metadata(alias, metadata);
+    }
+
     /// Resolve step references in a string (e.g., ${reader.output.path})
     pub fn resolve_step_references(&self, input: &str) -> Result<String, ConfigError> {
         let re = Regex::new(r"\$\{([a-zA-Z0-9_]+)\.([a-zA-Z0-9_.]+)\}").unwrap();
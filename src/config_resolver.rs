@@ -1,15 +1,54 @@
-use crate::types::{Data, OxiData};
+use crate::secrets::{EnvSecretResolver, SecretResolver};
+use crate::types::{Data, OxiConfig, OxiData};
 use regex::Regex;
 use std::collections::HashMap;
 use std::env;
+use std::sync::{Arc, OnceLock};
+
+/// A `${VAR}` environment variable reference in a config string
+pub struct EnvReference<'a> {
+    /// The whole `${...}` expression
+    pub full: &'a str,
+    pub name: &'a str,
+    /// Fallback from `${VAR:-default}`; `${VAR}` and `${VAR:anything}` have none
+    pub default: Option<&'a str>,
+}
+
+/// Environment variable references in `text`, in the order they appear
+///
+/// Shared with the bundle manifest so both agree on which variables are required.
+pub fn env_references(text: &str) -> impl Iterator<Item = EnvReference<'_>> {
+    static ENV_REF: OnceLock<Regex> = OnceLock::new();
+    let env_ref =
+        ENV_REF.get_or_init(|| Regex::new(r"\$\{([A-Z_][A-Z0-9_]*)(?::(-)?([^}]*))?\}").unwrap());
+
+    env_ref.captures_iter(text).map(|cap| EnvReference {
+        full: cap.get(0).unwrap().as_str(),
+        name: cap.get(1).unwrap().as_str(),
+        default: cap.get(2).map(|_| cap.get(3).map_or("", |m| m.as_str())),
+    })
+}
 
 /// Resolves dynamic references in configuration values
+#[derive(Clone)]
 pub struct ConfigResolver {
     /// Environment variables cache
     env_vars: HashMap<String, String>,
 
     /// Step outputs from previous pipeline steps
     step_outputs: HashMap<String, OxiData>,
+
+    /// `OxiData::metadata` reported by previous steps, for `${step.metadata.key}`
+    step_metadata: HashMap<String, HashMap<String, serde_json::Value>>,
+
+    /// Source for `${secret:NAME}` references
+    secrets: Arc<dyn SecretResolver>,
+
+    /// Values from the pipeline's saved state, for `${state.NAME}` references
+    state_values: HashMap<String, String>,
+
+    /// Record buckets reported by `partition` steps, for `${partition.NAME}`
+    partitions: HashMap<String, serde_json::Value>,
 }
 
 impl ConfigResolver {
@@ -18,14 +57,62 @@ impl ConfigResolver {
         Self {
             env_vars: HashMap::new(),
             step_outputs: HashMap::new(),
+            step_metadata: HashMap::new(),
+            secrets: Arc::new(EnvSecretResolver),
+            state_values: HashMap::new(),
+            partitions: HashMap::new(),
         }
     }
 
+    /// Provide a value for `${state.NAME}` references (e.g. `last_processed_id`)
+    pub fn set_state_value(&mut self, name: &str, value: String) {
+        self.state_values.insert(name.to_string(), value);
+    }
+
+    /// Replace the resolver used for `${secret:NAME}` references
+    pub fn set_secret_resolver(&mut self, resolver: Arc<dyn SecretResolver>) {
+        self.secrets = resolver;
+    }
+
     /// Add a step output for future reference
     pub fn add_step_output(&mut self, step_id: String, output: OxiData) {
         self.step_outputs.insert(step_id, output);
     }
 
+    /// Builder form of `add_step_output`
+    pub fn with_step_output(mut self, alias: &str, output: OxiData) -> Self {
+        self.add_step_output(alias.to_string(), output);
+        self
+    }
+
+    /// Record the metadata a step reported, without keeping its data
+    ///
+    /// Buckets under a `partitions` key become `${partition.NAME}`
+    /// references; a later partition step replaces buckets of the same name.
+    pub fn add_step_metadata(
+        &mut self,
+        step_id: String,
+        metadata: HashMap<String, serde_json::Value>,
+    ) {
+        if let Some(serde_json::Value::Object(partitions)) = metadata.get("partitions") {
+            for (name, records) in partitions {
+                self.partitions.insert(name.clone(), records.clone());
+            }
+        }
+        self.step_metadata.insert(step_id, metadata);
+    }
+
+    /// Resolve every reference in a step's configuration, including nested values
+    pub fn resolve_config(&self, config: &OxiConfig) -> anyhow::Result<OxiConfig> {
+        let mut resolved = OxiConfig::default();
+        for (key, value) in &config.values {
+            resolved
+                .values
+                .insert(key.clone(), self.resolve_value(value)?);
+        }
+        Ok(resolved)
+    }
+
     /// Resolve all dynamic references in a configuration value
     pub fn resolve_value(&self, value: &serde_yaml::Value) -> anyhow::Result<serde_yaml::Value> {
         match value {
@@ -53,51 +140,114 @@ impl ConfigResolver {
         }
     }
 
-    /// Resolve string references like ${ENV_VAR} and ${step.metadata.path}
+    /// Resolve string references like ${ENV_VAR}, ${state.NAME}, ${partition.NAME},
+    /// ${step.metadata.path} and ${secret:NAME}
     fn resolve_string_references(&self, text: &str) -> anyhow::Result<String> {
         let mut result = text.to_string();
 
         // Environment variable substitution: ${ENV_VAR}
         result = self.resolve_env_vars(&result)?;
 
+        // Saved state: ${state.last_processed_id}
+        result = self.resolve_state_references(&result)?;
+
+        // Partition buckets: ${partition.invalid}
+        result = self.resolve_partition_references(&result)?;
+
         // Step reference substitution: ${step_id.field.path}
         result = self.resolve_step_references(&result)?;
 
+        // Secrets go last so their values are never re-scanned for references
+        result = crate::secrets::substitute_secrets(&result, self.secrets.as_ref())?;
+
         Ok(result)
     }
 
     /// Resolve environment variable references
     fn resolve_env_vars(&self, text: &str) -> anyhow::Result<String> {
-        // Support both ${VAR} and ${VAR:-default} syntax
-        let env_regex = Regex::new(r"\$\{([A-Z_][A-Z0-9_]*)(?::(-)?([^}]*))?\}").unwrap();
         let mut result = text.to_string();
 
-        for cap in env_regex.captures_iter(text) {
-            let full_match = &cap[0];
-            let var_name = &cap[1];
-            let has_default = cap.get(2).is_some();
-            let default_value = cap.get(3).map(|m| m.as_str()).unwrap_or("");
-
+        for reference in env_references(text) {
             // Try to get from cache first, then from environment
-            let value = if let Some(cached_value) = self.env_vars.get(var_name) {
+            let value = if let Some(cached_value) = self.env_vars.get(reference.name) {
                 cached_value.clone()
             } else {
-                match env::var(var_name) {
+                match env::var(reference.name) {
                     Ok(val) => val,
-                    Err(_) => {
-                        if has_default {
-                            default_value.to_string()
-                        } else {
+                    Err(_) => match reference.default {
+                        Some(default_value) => default_value.to_string(),
+                        None => {
                             return Err(anyhow::anyhow!(
                                 "Environment variable '{}' not found",
-                                var_name
+                                reference.name
                             ));
                         }
-                    }
+                    },
                 }
             };
 
-            result = result.replace(full_match, &value);
+            result = result.replace(reference.full, &value);
+        }
+
+        Ok(result)
+    }
+
+    /// Resolve `${state.NAME}` references
+    ///
+    /// A pipeline with no prior state has an empty `last_processed_id`, so it
+    /// behaves like a first run. Names the state does not provide are an error.
+    fn resolve_state_references(&self, text: &str) -> anyhow::Result<String> {
+        let state_regex = Regex::new(r"\$\{state\.([a-zA-Z0-9_]+)\}").unwrap();
+        let mut result = text.to_string();
+
+        for cap in state_regex.captures_iter(text) {
+            let Some(value) = self.state_values.get(&cap[1]) else {
+                let mut known: Vec<&str> = self.state_values.keys().map(String::as_str).collect();
+                known.sort();
+                return Err(anyhow::anyhow!(
+                    "State value '{}' not found; known state values: {}",
+                    &cap[1],
+                    if known.is_empty() {
+                        "(none, is state tracking enabled?)".to_string()
+                    } else {
+                        known.join(", ")
+                    }
+                ));
+            };
+            result = result.replace(&cap[0], value);
+        }
+
+        Ok(result)
+    }
+
+    /// Resolve `${partition.NAME}` to the bucket's records as a JSON array
+    ///
+    /// Left alone when a step is itself called `partition`, so its own
+    /// references keep working.
+    fn resolve_partition_references(&self, text: &str) -> anyhow::Result<String> {
+        let partition_regex = Regex::new(r"\$\{partition\.([a-zA-Z0-9_]+)\}").unwrap();
+        let mut result = text.to_string();
+
+        for cap in partition_regex.captures_iter(text) {
+            let Some(records) = self.partitions.get(&cap[1]) else {
+                if self.step_metadata.contains_key("partition")
+                    || self.step_outputs.contains_key("partition")
+                {
+                    continue;
+                }
+                let mut known: Vec<&str> = self.partitions.keys().map(String::as_str).collect();
+                known.sort();
+                return Err(anyhow::anyhow!(
+                    "Partition '{}' not found; known partitions: {}",
+                    &cap[1],
+                    if known.is_empty() {
+                        "(none)".to_string()
+                    } else {
+                        known.join(", ")
+                    }
+                ));
+            };
+            result = result.replace(&cap[0], &records.to_string());
         }
 
         Ok(result)
@@ -114,6 +264,15 @@ impl ConfigResolver {
             let step_id = &cap[1];
             let field_path = cap.get(3).map(|m| m.as_str());
 
+            // Metadata the step reported takes precedence over its data
+            if let Some(value) = field_path
+                .and_then(|path| path.strip_prefix("metadata."))
+                .and_then(|path| self.lookup_step_metadata(step_id, path))
+            {
+                result = result.replace(full_match, &value);
+                continue;
+            }
+
             // Get step output
             let step_output = self
                 .step_outputs
@@ -134,6 +293,26 @@ impl ConfigResolver {
         Ok(result)
     }
 
+    /// Find `path` in a step's metadata, from the executor or a stored output
+    fn lookup_step_metadata(&self, step_id: &str, path: &str) -> Option<String> {
+        let metadata = self.step_metadata.get(step_id).or_else(|| {
+            self.step_outputs
+                .get(step_id)
+                .map(|output| &output.metadata)
+        })?;
+
+        let mut fields = path.split('.');
+        let mut current = metadata.get(fields.next()?)?;
+        for field in fields {
+            current = current.get(field)?;
+        }
+
+        Some(match current {
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+
     /// Extract a specific field from step output using dot notation
     fn extract_field_from_output(
         &self,
@@ -230,6 +409,22 @@ mod tests {
         env::remove_var("TEST_VAR");
     }
 
+    #[test]
+    fn test_secret_reference_uses_injected_resolver() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("api_key"), "k-123").unwrap();
+
+        let mut resolver = ConfigResolver::new();
+        resolver.set_secret_resolver(Arc::new(crate::secrets::FileSecretResolver::new(
+            dir.path(),
+        )));
+        let value = serde_yaml::Value::String("Bearer ${secret:api_key}".to_string());
+        assert_eq!(
+            resolver.resolve_value(&value).unwrap(),
+            serde_yaml::Value::String("Bearer k-123".to_string())
+        );
+    }
+
     #[test]
     fn test_step_reference_substitution() {
         let mut resolver = ConfigResolver::new();
@@ -254,6 +449,97 @@ mod tests {
         assert_eq!(result, "Size: 1024");
     }
 
+    #[test]
+    fn test_resolve_config_with_step_outputs() {
+        let resolver = ConfigResolver::new()
+            .with_step_output(
+                "settings",
+                OxiData::from_json(serde_json::json!({"region": "eu", "limits": {"rows": 500}})),
+            )
+            .with_step_output("notes", OxiData::from_text("plain".to_string()));
+
+        let config = OxiConfig::from_yaml(
+            serde_yaml::from_str(
+                r#"
+                path: "out/${settings.region}.json"
+                options:
+                  max_rows: "${settings.limits.rows}"
+                  tags: ["${notes}", fixed]
+                retries: 3
+                "#,
+            )
+            .unwrap(),
+        );
+
+        let resolved = resolver.resolve_config(&config).unwrap();
+        assert_eq!(resolved.get_string("path").unwrap(), "out/eu.json");
+        assert_eq!(
+            resolved.values["options"],
+            serde_yaml::from_str::<serde_yaml::Value>(
+                r#"{max_rows: "500", tags: ["plain", fixed]}"#
+            )
+            .unwrap()
+        );
+        assert_eq!(resolved.values["retries"], serde_yaml::Value::from(3));
+
+        let missing = OxiConfig::builder().set("path", "${loader.path}").build();
+        let err = resolver.resolve_config(&missing).unwrap_err();
+        assert!(err.to_string().contains("Step 'loader' output not found"));
+    }
+
+    #[test]
+    fn test_state_reference_substitution() {
+        let mut resolver = ConfigResolver::new();
+        assert!(resolver
+            .resolve_string_references("${state.last_processed_id}")
+            .unwrap_err()
+            .to_string()
+            .contains("State value 'last_processed_id' not found"));
+
+        // An empty watermark is a first run, not a missing value
+        resolver.set_state_value("last_processed_id", String::new());
+        assert_eq!(
+            resolver
+                .resolve_string_references("since=${state.last_processed_id}")
+                .unwrap(),
+            "since="
+        );
+        assert!(resolver
+            .resolve_string_references("${state.cursor}")
+            .unwrap_err()
+            .to_string()
+            .contains("known state values: last_processed_id"));
+    }
+
+    #[test]
+    fn test_partition_reference_substitution() {
+        let mut resolver = ConfigResolver::new();
+        assert!(resolver
+            .resolve_string_references("${partition.eu}")
+            .unwrap_err()
+            .to_string()
+            .contains("known partitions: (none)"));
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "partitions".to_string(),
+            serde_json::json!({"eu": [{"id": 1}], "unmatched": []}),
+        );
+        resolver.add_step_metadata("split".to_string(), metadata);
+
+        let result = resolver
+            .resolve_string_references("${partition.eu}")
+            .unwrap();
+        assert_eq!(result, r#"[{"id":1}]"#);
+        let err = resolver
+            .resolve_string_references("${partition.us}")
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("known partitions: eu, unmatched"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_nested_config_resolution() {
         env::set_var("BASE_PATH", "/data");
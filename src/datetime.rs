@@ -0,0 +1,162 @@
+//! Parsing of the timestamp representations found in pipeline data
+//!
+//! Used by schema validation, schema inference and `coerce_types`, so that a
+//! `DateTime` field accepts the same strings everywhere. Only `coerce_types`
+//! reads numbers as epochs; validation and inference take strings alone.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use serde_json::Value;
+
+/// Patterns tried after RFC3339 and RFC2822, before any user-provided format
+pub const COMMON_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d"];
+
+/// Epoch numbers at least this large are read as milliseconds
+///
+/// 1e11 seconds is the year 5138, while 1e11 milliseconds is March 1973, so
+/// the two units cannot be confused for any plausible timestamp.
+const EPOCH_MILLIS_THRESHOLD: f64 = 1e11;
+
+/// Parse a JSON value as a point in time
+///
+/// Strings may be RFC3339, RFC2822, one of [`COMMON_FORMATS`] or match one of
+/// `formats` (strftime syntax). Formats without an offset are read as UTC and
+/// date-only formats as midnight. Numbers are unix seconds, or milliseconds
+/// when they are too large to be seconds.
+pub fn parse_datetime(value: &Value, formats: &[String]) -> Option<DateTime<Utc>> {
+    match value {
+        Value::Number(number) => from_epoch(number.as_f64()?),
+        Value::String(text) => parse_datetime_str(text.trim(), formats),
+        _ => None,
+    }
+}
+
+/// Parse a string as a point in time; see [`parse_datetime`]
+pub fn parse_datetime_str(text: &str, formats: &[String]) -> Option<DateTime<Utc>> {
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(text) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc2822(text) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+
+    COMMON_FORMATS
+        .iter()
+        .copied()
+        .chain(formats.iter().map(String::as_str))
+        .find_map(|format| parse_with_format(text, format))
+}
+
+/// The canonical text form of a timestamp: RFC3339 in UTC with a `Z` suffix
+pub fn format_datetime(datetime: &DateTime<Utc>) -> String {
+    datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Whether a field name suggests it holds timestamps (`created_at`, `event_time`, `birthdate`)
+pub fn is_datetime_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.ends_with("_at") || name.ends_with("_time") || name.ends_with("date")
+}
+
+fn from_epoch(number: f64) -> Option<DateTime<Utc>> {
+    if !number.is_finite() {
+        return None;
+    }
+    let millis = if number.abs() >= EPOCH_MILLIS_THRESHOLD {
+        number
+    } else {
+        number * 1000.0
+    };
+    DateTime::from_timestamp_millis(millis.round() as i64)
+}
+
+fn parse_with_format(text: &str, format: &str) -> Option<DateTime<Utc>> {
+    if let Ok(parsed) = DateTime::parse_from_str(text, format) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(parsed) = NaiveDateTime::parse_from_str(text, format) {
+        return Some(parsed.and_utc());
+    }
+    NaiveDate::parse_from_str(text, format)
+        .ok()?
+        .and_hms_opt(0, 0, 0)
+        .map(|midnight| midnight.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parsed(value: Value, formats: &[&str]) -> Option<String> {
+        let formats: Vec<String> = formats.iter().map(|f| f.to_string()).collect();
+        parse_datetime(&value, &formats).map(|dt| format_datetime(&dt))
+    }
+
+    #[test]
+    fn test_parse_every_representation() {
+        let expected = Some("2024-01-05T13:00:00Z".to_string());
+        assert_eq!(parsed(json!("2024-01-05T14:00:00+01:00"), &[]), expected);
+        assert_eq!(
+            parsed(json!("Fri, 05 Jan 2024 13:00:00 +0000"), &[]),
+            expected
+        );
+        assert_eq!(parsed(json!("2024-01-05 13:00:00"), &[]), expected);
+        assert_eq!(parsed(json!(" 2024-01-05T13:00:00 "), &[]), expected);
+        assert_eq!(parsed(json!(1704459600), &[]), expected);
+        assert_eq!(parsed(json!(1704459600000_i64), &[]), expected);
+        assert_eq!(
+            parsed(json!(1704459600.25), &[]),
+            Some("2024-01-05T13:00:00.250Z".to_string())
+        );
+        assert_eq!(
+            parsed(json!("2024-01-05 13:00:00.5"), &[]),
+            Some("2024-01-05T13:00:00.500Z".to_string())
+        );
+        assert_eq!(
+            parsed(json!("2024-01-05"), &[]),
+            Some("2024-01-05T00:00:00Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_with_user_formats() {
+        assert_eq!(parsed(json!("05/01/2024 13:00"), &[]), None);
+        assert_eq!(
+            parsed(json!("05/01/2024 13:00"), &["%m/%d/%Y", "%d/%m/%Y %H:%M"]),
+            Some("2024-01-05T13:00:00Z".to_string())
+        );
+        assert_eq!(
+            parsed(json!("05.01.2024"), &["%d.%m.%Y"]),
+            Some("2024-01-05T00:00:00Z".to_string())
+        );
+        assert_eq!(
+            parsed(json!("2024-01-05 14:00 +0100"), &["%Y-%m-%d %H:%M %z"]),
+            Some("2024-01-05T13:00:00Z".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unparseable_values() {
+        assert_eq!(parsed(json!("yesterday"), &[]), None);
+        assert_eq!(parsed(json!("2024-13-45"), &[]), None);
+        assert_eq!(parsed(json!(true), &[]), None);
+        assert_eq!(parsed(json!(null), &[]), None);
+        assert_eq!(parsed(json!({"at": 1}), &[]), None);
+    }
+
+    #[test]
+    fn test_datetime_names() {
+        for name in [
+            "created_at",
+            "event_time",
+            "birthdate",
+            "Start_Date",
+            "date",
+        ] {
+            assert!(is_datetime_name(name), "{name}");
+        }
+        for name in ["format", "timestamps", "updated", "date_format"] {
+            assert!(!is_datetime_name(name), "{name}");
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::state::types::ErrorType;
+use std::io::ErrorKind;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -85,4 +87,239 @@ pub enum OxiError {
         oxi_name: String,
         input_type: String,
     },
+
+    #[error("'{oxi_name}' {limit} exceeded: got {observed}, allowed {allowed}; {hint}")]
+    LimitExceeded {
+        oxi_name: String,
+        /// Name of the `ProcessingLimits` field
+        limit: String,
+        observed: String,
+        allowed: String,
+        hint: String,
+    },
+
+    #[error("Timeout after {}s in step '{step}'; raise its timeout_seconds or give it less data per run", *elapsed_ms as f64 / 1000.0)]
+    Timeout { step: String, elapsed_ms: u64 },
+
+    #[error("Execution was cancelled before it finished; run the pipeline again to complete it")]
+    Cancelled,
+
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
+    #[error("Circuit open for step '{step}' after {failures} consecutive network failures; failing fast for another {retry_in_ms}ms")]
+    CircuitOpen {
+        step: String,
+        failures: u32,
+        retry_in_ms: u64,
+    },
+
+    #[error("{details}: {message}")]
+    Io {
+        details: String,
+        kind: ErrorKind,
+        message: String,
+    },
+
+    #[error("Step '{step}' failed while {details}: {source}")]
+    WithContext {
+        step: String,
+        details: String,
+        source: Box<OxiError>,
+    },
+
+    /// An error whose retryability was set explicitly with [`OxiError::retryable`]
+    #[error("{source}")]
+    Classified {
+        source: Box<OxiError>,
+        retryable: bool,
+    },
+}
+
+impl OxiError {
+    /// A failure talking to a remote system; retryable unless overridden
+    pub fn network(message: impl Into<String>) -> Self {
+        OxiError::NetworkError(message.into())
+    }
+
+    /// An I/O failure, classified by the kind of `error`
+    ///
+    /// `details` says what was being done, e.g. "Failed to read file 'a.csv'".
+    pub fn io(details: impl Into<String>, error: &std::io::Error) -> Self {
+        OxiError::Io {
+            details: details.into(),
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+
+    /// Override whether retrying the failed step could help
+    pub fn retryable(self, retryable: bool) -> Self {
+        let source = match self {
+            OxiError::Classified { source, .. } => source,
+            other => Box::new(other),
+        };
+        OxiError::Classified { source, retryable }
+    }
+
+    /// Name the step and what it was doing when the error happened
+    pub fn with_context(self, step_id: impl Into<String>, details: impl Into<String>) -> Self {
+        OxiError::WithContext {
+            step: step_id.into(),
+            details: details.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Kind of failure, as recorded in run state
+    pub fn error_type(&self) -> ErrorType {
+        self.classify().0
+    }
+
+    /// Whether the same step might succeed if run again
+    ///
+    /// Network failures and timeouts are; cancellations, bad configuration,
+    /// exceeded limits and bad data are not.
+    pub fn is_retryable(&self) -> bool {
+        self.classify().1
+    }
+
+    fn classify(&self) -> (ErrorType, bool) {
+        match self {
+            OxiError::Classified { source, retryable } => (source.error_type(), *retryable),
+            OxiError::WithContext { source, .. } => source.classify(),
+            OxiError::StdInReadError(error) => classify_io(error.kind()),
+            OxiError::Io { kind, .. } => classify_io(*kind),
+            // A step that ran out of its time allowance, see `timeout_seconds`
+            OxiError::Timeout { .. } => (ErrorType::Resource, true),
+            OxiError::ProcessingTimeout { .. } => (ErrorType::Timeout, true),
+            OxiError::Cancelled => (ErrorType::Cancelled, false),
+            OxiError::NetworkError(_) | OxiError::CircuitOpen { .. } => (ErrorType::Network, true),
+            OxiError::ConfigError(_)
+            | OxiError::MissingConfig(_)
+            | OxiError::UnknownOxi(_)
+            | OxiError::ChainingError(_) => (ErrorType::Configuration, false),
+            OxiError::BatchSizeExceeded { .. }
+            | OxiError::MemoryLimitExceeded { .. }
+            | OxiError::LimitExceeded { .. } => (ErrorType::Resource, false),
+            _ => (ErrorType::Processing, false),
+        }
+    }
+}
+
+/// Missing files and refused permissions won't fix themselves; dropped
+/// connections might. Other local I/O failures are processing errors.
+fn classify_io(kind: ErrorKind) -> (ErrorType, bool) {
+    match kind {
+        ErrorKind::NotFound
+        | ErrorKind::PermissionDenied
+        | ErrorKind::AlreadyExists
+        | ErrorKind::InvalidInput => (ErrorType::Configuration, false),
+        ErrorKind::OutOfMemory | ErrorKind::StorageFull => (ErrorType::Resource, false),
+        ErrorKind::TimedOut => (ErrorType::Timeout, true),
+        ErrorKind::ConnectionRefused
+        | ErrorKind::ConnectionReset
+        | ErrorKind::ConnectionAborted
+        | ErrorKind::NotConnected
+        | ErrorKind::AddrInUse
+        | ErrorKind::AddrNotAvailable => (ErrorType::Network, true),
+        _ => (ErrorType::Processing, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_classification_per_variant() {
+        let cases = [
+            (OxiError::network("reset"), ErrorType::Network, true),
+            (
+                OxiError::Timeout {
+                    step: "fetch".to_string(),
+                    elapsed_ms: 10,
+                },
+                ErrorType::Resource,
+                true,
+            ),
+            (OxiError::Cancelled, ErrorType::Cancelled, false),
+            (
+                OxiError::ConfigError("bad".to_string()),
+                ErrorType::Configuration,
+                false,
+            ),
+            (
+                OxiError::MemoryLimitExceeded {
+                    actual_mb: 2,
+                    max_mb: 1,
+                    oxi_name: "x".to_string(),
+                },
+                ErrorType::Resource,
+                false,
+            ),
+            (
+                OxiError::ExecutionError("boom".to_string()),
+                ErrorType::Processing,
+                false,
+            ),
+        ];
+        for (error, error_type, retryable) in cases {
+            assert_eq!(error.error_type(), error_type, "{error}");
+            assert_eq!(error.is_retryable(), retryable, "{error}");
+        }
+    }
+
+    #[test]
+    fn test_io_errors_classified_by_kind() {
+        let missing = std::io::Error::from(ErrorKind::NotFound);
+        let error = OxiError::io("Failed to read file 'a.csv'", &missing);
+        assert_eq!(error.error_type(), ErrorType::Configuration);
+        assert!(!error.is_retryable());
+        assert!(error
+            .to_string()
+            .starts_with("Failed to read file 'a.csv': "));
+
+        let reset = std::io::Error::from(ErrorKind::ConnectionReset);
+        assert_eq!(
+            OxiError::io("Failed to read", &reset).error_type(),
+            ErrorType::Network
+        );
+        assert!(OxiError::io("Failed to read", &reset).is_retryable());
+        assert!(OxiError::StdInReadError(reset).is_retryable());
+
+        // Local failures are neither network errors nor retried
+        for kind in [
+            ErrorKind::BrokenPipe,
+            ErrorKind::Interrupted,
+            ErrorKind::Unsupported,
+            ErrorKind::Other,
+            ErrorKind::InvalidData,
+        ] {
+            let error = OxiError::io("Failed to write", &std::io::Error::from(kind));
+            assert_eq!(error.error_type(), ErrorType::Processing, "{kind:?}");
+            assert!(!error.is_retryable(), "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn test_retryable_override_and_context_keep_the_type() {
+        let error = OxiError::network("503 from upstream").retryable(false);
+        assert_eq!(error.error_type(), ErrorType::Network);
+        assert!(!error.is_retryable());
+        assert_eq!(error.to_string(), "Network error: 503 from upstream");
+        assert!(error.retryable(true).is_retryable());
+
+        let error = OxiError::ValidationError {
+            details: "locked row".to_string(),
+        }
+        .retryable(true)
+        .with_context("load", "writing batch 3");
+        assert_eq!(error.error_type(), ErrorType::Processing);
+        assert!(error.is_retryable());
+        assert_eq!(
+            error.to_string(),
+            "Step 'load' failed while writing batch 3: Schema validation failed: locked row"
+        );
+    }
 }
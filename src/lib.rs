@@ -1,14 +1,27 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod benchmark;
 pub mod cli;
+pub mod completions;
 pub mod config;
 pub mod config_resolver;
+pub mod datetime;
 pub mod error;
 pub mod oxis;
+#[cfg(feature = "parquet")]
+pub mod parquet;
 pub mod pipeline;
+pub mod pipeline_bundle;
+pub mod pipeline_lint;
 pub mod pipeline_manager;
 pub mod project;
+pub mod run_history;
 pub mod schema;
+pub mod secrets;
 pub mod state;
+pub mod test_data;
 pub mod types;
+pub mod watch;
 
 use async_trait::async_trait;
 
@@ -40,11 +53,62 @@ pub trait Oxi {
         types::ProcessingLimits::default()
     }
 
+    /// Optional: Opt out of batch splitting
+    ///
+    /// JSON arrays larger than `max_batch_size` are normally processed in
+    /// chunks and the output arrays concatenated. Oxis whose output depends
+    /// on seeing every record at once (aggregations, sinks, text formatters)
+    /// return true to receive the whole array.
+    fn requires_full_batch(&self) -> bool {
+        false
+    }
+
+    /// Optional: Data types this Oxi produces (defaults to the accepted input types)
+    fn output_types(&self) -> Vec<types::OxiDataType> {
+        self.processing_limits().supported_input_types
+    }
+
+    /// Describe this Oxi for documentation and config validation
+    fn describe(&self) -> types::OxiDescriptor {
+        types::OxiDescriptor::from_oxi(self)
+    }
+
+    /// Optional: Files this Oxi reads, watched by `run --watch` to trigger re-runs
+    fn watched_paths(&self, _config: &types::OxiConfig) -> Vec<std::path::PathBuf> {
+        Vec::new()
+    }
+
+    /// Optional: Id of the newest record in `output`, for incremental sources
+    ///
+    /// When state tracking is on, the pipeline saves the last watermark of a
+    /// successful run as `last_processed_id`, which the next run can read
+    /// back through `${state.last_processed_id}`.
+    fn watermark(&self, _output: &types::OxiData, _config: &types::OxiConfig) -> Option<String> {
+        None
+    }
+
+    /// Optional: Reject step config keys missing from `config_schema`
+    ///
+    /// Strict Oxis fail a step whose config has a key they do not declare,
+    /// which catches typos that would otherwise be silently ignored.
+    fn strict_config_validation(&self) -> bool {
+        false
+    }
+
     /// Optional: Validate input data before processing
     fn validate_input(&self, _input: &types::OxiData) -> Result<(), error::OxiError> {
         Ok(()) // Default: accept all inputs
     }
 
+    /// Optional: Fields this Oxi needs in its input, checked by `pipeline test`
+    ///
+    /// Each field must be present upstream with a compatible type; a
+    /// non-nullable field also flags upstream fields that may be null.
+    /// `None` means the Oxi accepts any record shape.
+    fn required_input_schema(&self, _config: &types::OxiConfig) -> Option<types::OxiSchema> {
+        None
+    }
+
     /// Determine output schema given input schema and configuration
     fn output_schema(
         &self,
@@ -1,13 +1,63 @@
 use clap::Parser;
 use oxide_flow::{
-    cli::{Cli, Commands, PipelineAction},
+    benchmark::{self, BenchmarkStats},
+    cli::{Cli, Commands, OxiAction, PipelineAction, PluginAction, SchemaAction, TemplateAction},
+    completions,
     config_resolver::ConfigResolver,
-    pipeline::Pipeline,
-    pipeline_manager::PipelineManager,
+    oxis::{
+        self,
+        registry::{OxiRegistry, RegisteredOxi},
+    },
+    pipeline::{Pipeline, PipelineResult},
+    pipeline_lint::format_lint_reports,
+    pipeline_manager::{
+        build_pipeline_graph, dependency_mermaid, dependency_tree, format_schema_chain,
+        infer_schema_chain, GraphFormat, OutputFormat, PipelineManager, TemplateSource,
+    },
+    progress,
     project::{self, ProjectConfig},
-    state::cli::{handle_state_command, handle_worker_command},
-    types::{Data, OxiData},
+    run_history::{
+        format_history, format_throughput, RunHistory, RunReport, ThroughputSummary,
+        DEFAULT_RUNS_DIR,
+    },
+    state::cli::{handle_state_command, handle_worker_command, open_cli_state_manager},
+    types::{Data, InferenceOptions, OxiData, OxiDescriptor, OxiSchema},
 };
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+/// Prints the library's `log` records to stderr, warnings and errors marked like the CLI's own
+struct StderrLogger;
+
+impl StderrLogger {
+    fn install(level: log::LevelFilter) {
+        static LOGGER: StderrLogger = StderrLogger;
+        if log::set_logger(&LOGGER).is_ok() {
+            log::set_max_level(level);
+        }
+    }
+}
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            log::Level::Error => eprintln!("❌ {}", record.args()),
+            log::Level::Warn => eprintln!("⚠️  {}", record.args()),
+            _ => eprintln!("{}", record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
 
 #[tokio::main]
 async fn main() {
@@ -17,23 +67,77 @@ async fn main() {
     if cli.verbose {
         println!("Verbose mode enabled");
     }
+    StderrLogger::install(if cli.verbose {
+        log::LevelFilter::Debug
+    } else {
+        log::LevelFilter::Warn
+    });
+
+    // Before any config is read, so `${VAR}` references can use the file
+    if let Err(e) = load_env_file(cli.env_file.as_deref()) {
+        eprintln!("❌ Failed to load env file: {e}");
+        std::process::exit(1);
+    }
 
     // Handle commands
     match cli.command {
-        Commands::Init { name, directory } => match project::init_project(name, directory) {
-            Ok(_) => {}
-            Err(e) => {
+        Commands::Init {
+            list_templates: true,
+            ..
+        } => print!("{}", project::format_project_templates()),
+        Commands::Init {
+            name,
+            directory,
+            template,
+            force,
+            list_templates: false,
+        } => {
+            let result = template
+                .parse()
+                .and_then(|template| project::init_project(name, directory, template, force));
+            if let Err(e) = result {
                 eprintln!("Failed to initialize project: {e}");
                 std::process::exit(1);
             }
-        },
+        }
+        Commands::Run {
+            pipeline,
+            config: _,
+            force,
+            watch: true,
+            ..
+        } => {
+            if let Err(e) = watch_pipeline_by_name(&pipeline, force).await {
+                eprintln!("❌ Watch mode failed: {e}");
+                std::process::exit(1);
+            }
+        }
         Commands::Run {
             pipeline,
             config: _,
-        } => match run_pipeline_by_name(&pipeline).await {
-            Ok(_) => println!("✅ Pipeline execution completed successfully!"),
+            force,
+            watch: false,
+            json,
+        } => {
+            // Keep stdout for the JSON summary
+            oxide_flow::pipeline::set_progress_to_stderr(json);
+            match run_pipeline_by_name(&pipeline, force, json).await {
+                Ok(_) => progress!("✅ Pipeline execution completed successfully!"),
+                Err(e) => {
+                    eprintln!("❌ Pipeline execution failed: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Validate {
+            pipeline,
+            strict,
+            output_format,
+        } => match validate_pipeline(&pipeline, strict, output_format) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
             Err(e) => {
-                eprintln!("❌ Pipeline execution failed: {e}");
+                eprintln!("❌ Pipeline validation failed: {e}");
                 std::process::exit(1);
             }
         },
@@ -58,11 +162,72 @@ async fn main() {
                 std::process::exit(1);
             }
         },
+        Commands::Plugin { action } => match handle_plugin_command(action) {
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("❌ Plugin command failed: {e}");
+                std::process::exit(1);
+            }
+        },
+        Commands::Oxi { action } => match handle_oxi_command(action) {
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("❌ Oxi command failed: {e}");
+                std::process::exit(1);
+            }
+        },
+        Commands::Schema { action } => match handle_schema_command(action) {
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("❌ Schema command failed: {e}");
+                std::process::exit(1);
+            }
+        },
+        Commands::Completions { shell } => {
+            if let Err(e) = completions::generate(shell, &mut std::io::stdout()) {
+                eprintln!("❌ Failed to write completions: {e}");
+                std::process::exit(1);
+            }
+        }
+        // Completion helpers stay silent on errors so shells just offer nothing
+        Commands::CompletePipelines => {
+            for name in completions::pipeline_names() {
+                println!("{name}");
+            }
+        }
+        Commands::CompleteStates => {
+            for id in completions::state_ids().await {
+                println!("{id}");
+            }
+        }
     }
 }
 
+/// Load `--env-file`, or else the project's `settings.env_file` if it exists
+fn load_env_file(cli_path: Option<&Path>) -> anyhow::Result<()> {
+    let path = match cli_path {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let project_file = ProjectConfig::load()
+                .ok()
+                .and_then(|project| project.settings.env_file);
+            match project_file {
+                // A checked-in setting must not break checkouts without the file
+                Some(path) if Path::new(&path).exists() => PathBuf::from(path),
+                _ => return Ok(()),
+            }
+        }
+    };
+    oxide_flow::config::load_env_file(&path)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+    Ok(())
+}
+
+/// Heartbeat age after which a `Running` state is treated as a crashed run
+const ACTIVE_RUN_STALE_MS: u64 = 5 * 60 * 1000;
+
 /// Run a pipeline by name using project configuration for discovery
-async fn run_pipeline_by_name(pipeline_name: &str) -> anyhow::Result<()> {
+async fn run_pipeline_by_name(pipeline_name: &str, force: bool, json: bool) -> anyhow::Result<()> {
     // Load project configuration
     let project_config = ProjectConfig::load()
         .map_err(|e| anyhow::anyhow!("Failed to load project configuration: {}", e))?;
@@ -70,46 +235,165 @@ async fn run_pipeline_by_name(pipeline_name: &str) -> anyhow::Result<()> {
     // Find the pipeline file
     let pipeline_path = project_config.find_pipeline(pipeline_name)?;
 
-    println!(
+    progress!(
         "🔍 Running pipeline '{}' from: {}",
         pipeline_name,
         pipeline_path.display()
     );
 
     // Run the pipeline with state tracking
-    run_pipeline_from_yaml_with_state(pipeline_path.to_str().unwrap(), &project_config).await
+    run_pipeline_from_yaml_with_state(
+        pipeline_path.to_str().unwrap(),
+        &project_config,
+        force,
+        json,
+    )
+    .await
+}
+
+/// Print a pipeline's validation checks in `format`; returns whether it passed
+fn validate_pipeline(name: &str, strict: bool, format: OutputFormat) -> anyhow::Result<bool> {
+    let manager = PipelineManager::new()?;
+    let mut result = manager.test_pipeline(name, false, false, false, false)?;
+    result.strict = strict;
+
+    match format {
+        OutputFormat::Human => println!("{}", manager.format_validation_result(&result, false)),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&result.to_json())?),
+        OutputFormat::Sarif => println!("{}", serde_json::to_string_pretty(&result.to_sarif())?),
+        OutputFormat::Junit => print!("{}", result.to_junit_xml()),
+    }
+    Ok(result.is_valid())
+}
+
+/// Run a pipeline once, then again whenever it or one of its input files changes
+async fn watch_pipeline_by_name(pipeline_name: &str, force: bool) -> anyhow::Result<()> {
+    let project_config = ProjectConfig::load()
+        .map_err(|e| anyhow::anyhow!("Failed to load project configuration: {}", e))?;
+    let pipeline_path = project_config.find_pipeline(pipeline_name)?;
+
+    progress!("👀 Watching pipeline '{pipeline_name}' (Ctrl-C to stop)");
+    for target in oxide_flow::watch::watch_targets(&pipeline_path) {
+        progress!("   {}", target.display());
+    }
+
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    let runs = oxide_flow::watch::watch_pipeline(
+        &pipeline_path,
+        oxide_flow::watch::DEFAULT_DEBOUNCE,
+        shutdown,
+        || async {
+            let time = chrono::Local::now().format("%H:%M:%S");
+            // Failures are reported and the watcher keeps going
+            match execute_pipeline_file(&pipeline_path.to_string_lossy(), &project_config, force)
+                .await
+            {
+                Ok((result, _)) if result.success => progress!(
+                    "🔁 [{time}] ✅ {} steps in {}ms{}",
+                    result.steps_executed,
+                    result.total_duration_ms,
+                    result
+                        .run_id
+                        .map(|id| format!(" (run {id})"))
+                        .unwrap_or_default()
+                ),
+                Ok((result, _)) => progress!(
+                    "🔁 [{time}] ❌ {} of {} steps failed{}",
+                    result.steps_failed,
+                    result.step_results.len(),
+                    result
+                        .run_id
+                        .map(|id| format!(" (run {id})"))
+                        .unwrap_or_default()
+                ),
+                Err(e) => progress!("🔁 [{time}] ❌ {e}"),
+            }
+        },
+    )
+    .await?;
+
+    progress!("\n👋 Stopped watching after {runs} run(s)");
+    Ok(())
 }
 
 /// Run a pipeline from a YAML file with state tracking support
 async fn run_pipeline_from_yaml_with_state(
     pipeline_path: &str,
     project_config: &ProjectConfig,
+    force: bool,
+    json: bool,
 ) -> anyhow::Result<()> {
-    // Load pipeline
-    let pipeline = Pipeline::load_from_file(pipeline_path)?;
+    let (result, summary) = execute_pipeline_file(pipeline_path, project_config, force).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        progress!("\n⏱️  Step timings:");
+        print!("{}", format_throughput(&summary));
+    }
+
+    if result.cancelled {
+        return Err(anyhow::anyhow!(
+            "Pipeline interrupted{}",
+            if result.state_tracking_enabled {
+                "; its state was saved as Paused"
+            } else {
+                ""
+            }
+        ));
+    }
+    if result.success {
+        if let Some(final_data) = result.final_data {
+            progress!("Final Result: {}", final_data.data.summary(200));
+        }
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Pipeline execution failed with {} failed steps",
+            result.steps_failed
+        ))
+    }
+}
+
+/// Load and execute a pipeline file, tracking state when the project configures it
+async fn execute_pipeline_file(
+    pipeline_path: &str,
+    project_config: &ProjectConfig,
+    force: bool,
+) -> anyhow::Result<(PipelineResult, ThroughputSummary)> {
+    // Load pipeline, instantiating its steps from the built-ins and the project's plugins
+    let mut pipeline = Pipeline::load_from_file(pipeline_path)?;
+    pipeline.apply_defaults(&project_config.defaults);
+    pipeline.set_run_context(
+        project_config.active_environment(),
+        &project_config.project.tags,
+    );
+    pipeline.attach_registry(&project_registry(project_config)?);
 
-    println!("Running pipeline: {}", pipeline.name());
+    progress!("Running pipeline: {}", pipeline.name());
     if let Some(desc) = pipeline.description() {
-        println!("Description: {desc}");
+        progress!("Description: {desc}");
     }
-    println!("Steps: {}", pipeline.step_count());
+    progress!("Steps: {}", pipeline.step_count());
 
     // Create configuration resolver for dynamic references
-    let resolver = ConfigResolver::default();
-
-    // Create state manager if configured
-    let state_manager = if project_config.state_manager.is_some() {
-        match oxide_flow::state::manager::StateManager::new(
-            project_config.create_state_manager_config(),
-        )
-        .await
-        {
-            Ok(manager) => {
-                println!("📊 State tracking enabled");
+    let mut resolver = ConfigResolver::default();
+    resolver.set_secret_resolver(project_config.secret_resolver());
+
+    // Create state manager if the project or the pipeline configures one
+    let state_manager = if project_config.state_manager.is_some() || pipeline.state.is_some() {
+        let config =
+            project_config.create_pipeline_state_manager_config(pipeline.state.as_ref())?;
+        match oxide_flow::state::manager::StateManager::new(config).await {
+            Ok(mut manager) => {
+                progress!("📊 State tracking enabled");
+                manager.register_notifications(&project_config.project.notifications)?;
                 Some(manager)
             }
             Err(e) => {
-                println!("⚠️  Failed to initialize state tracking: {e}");
+                progress!("⚠️  Failed to initialize state tracking: {e}");
                 None
             }
         }
@@ -117,41 +401,97 @@ async fn run_pipeline_from_yaml_with_state(
         None
     };
 
-    // Use enhanced execution with optional state tracking
-    let result = pipeline
-        .execute_with_state_tracking(OxiData::empty(), &resolver, state_manager)
-        .await;
-
-    if result.success {
-        if let Some(final_data) = result.final_data {
-            // Display final result
-            match &final_data.data {
-                Data::Text(text) => {
-                    let preview = if text.len() > 200 {
-                        format!("{}... ({} characters)", &text[..200], text.len())
-                    } else {
-                        text.clone()
-                    };
-                    println!("Final Result: Text data - {preview}");
-                }
-                Data::Json(_) => {
-                    println!("Final Result: JSON data");
+    // Opt-in: fail or requeue runs left behind by crashed workers
+    if let Some(manager) = state_manager
+        .as_ref()
+        .filter(|m| m.config().recover_stale_runs)
+    {
+        match manager.recover_stale_runs().await {
+            Ok(recovery) => {
+                for id in &recovery.failed_ids {
+                    progress!("🩹 Marked stale run of '{id}' as failed (worker lost)");
                 }
-                Data::Binary(data) => {
-                    println!("Final Result: Binary data ({} bytes)", data.len());
+                for id in &recovery.requeued_ids {
+                    progress!("🩹 Requeued stale run of '{id}' (worker lost)");
                 }
-                Data::Empty => {
-                    println!("Final Result: Empty data");
+                for error in &recovery.errors {
+                    progress!("⚠️  Stale run recovery: {error}");
                 }
             }
+            Err(e) => progress!("⚠️  Stale run recovery failed: {e}"),
         }
-        Ok(())
-    } else {
-        Err(anyhow::anyhow!(
-            "Pipeline execution failed with {} failed steps",
-            result.steps_failed
-        ))
     }
+
+    // Guard against double-processing by a concurrent run of the same pipeline
+    if let (Some(manager), false) = (&state_manager, force) {
+        if let Err(e) = manager
+            .ensure_no_active_run(&pipeline.name(), ACTIVE_RUN_STALE_MS)
+            .await
+        {
+            return Err(anyhow::anyhow!("{e}. Use --force to start anyway"));
+        }
+    }
+
+    // Use enhanced execution with optional state tracking
+    let cancel = CancellationToken::new();
+    let interrupts = tokio::spawn(cancel_on_interrupt(cancel.clone()));
+    let result = pipeline
+        .execute_cancellable(OxiData::empty(), &resolver, state_manager.clone(), cancel)
+        .await;
+    interrupts.abort();
+
+    // Step states carry record counts for chunked steps, so prefer them
+    let tracked_state = match &state_manager {
+        Some(manager) => manager.load_state(&pipeline.name()).await.ok(),
+        None => None,
+    };
+    let summary = match tracked_state {
+        Some(state) => ThroughputSummary::from_state(&pipeline, &state),
+        None => ThroughputSummary::from_result(&pipeline, &result),
+    };
+
+    let mut report = RunReport::from_result(&pipeline, &result);
+    report.state_backend = state_manager
+        .as_ref()
+        .map(|manager| manager.config().backend.clone());
+    if let Err(e) = RunHistory::new(DEFAULT_RUNS_DIR).record(&report) {
+        progress!("⚠️  Failed to record run history: {e}");
+    }
+
+    // Webhook deliveries run in the background and would die with the process
+    if let Some(manager) = &state_manager {
+        manager.flush_observers().await;
+    }
+
+    Ok((result, summary))
+}
+
+/// Cancel `cancel` on the first Ctrl-C and exit immediately on the second
+///
+/// Cancelling lets the running pipeline checkpoint its state as `Paused` and
+/// release its locks before the process exits.
+async fn cancel_on_interrupt(cancel: CancellationToken) {
+    if tokio::signal::ctrl_c().await.is_err() {
+        return;
+    }
+    eprintln!("\n⏸️  Interrupted, saving state before exiting (Ctrl-C again to exit immediately)");
+    cancel.cancel();
+
+    if tokio::signal::ctrl_c().await.is_ok() {
+        eprintln!("\n🛑 Exiting without saving state");
+        std::process::exit(130);
+    }
+}
+
+/// Ask a yes/no question on stdin, defaulting to no
+fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    use std::io::Write;
+
+    print!("{prompt}");
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    Ok(input.trim().to_lowercase().starts_with('y'))
 }
 
 /// Handle pipeline management commands
@@ -186,6 +526,7 @@ async fn handle_pipeline_command(action: PipelineAction) -> anyhow::Result<()> {
             template,
             description,
             author,
+            set,
         } => {
             let manager = PipelineManager::new()?;
 
@@ -193,25 +534,180 @@ async fn handle_pipeline_command(action: PipelineAction) -> anyhow::Result<()> {
             println!("📝 Creating new pipeline: {name}");
             println!("  Template: {template}");
 
-            manager.create_pipeline(&name, &template, description.as_deref(), author.as_deref())?;
+            let mut variables: HashMap<String, String> = set.into_iter().collect();
+            if std::io::stdin().is_terminal() {
+                manager.prompt_template_variables(&template, &mut variables)?;
+            }
+
+            manager.create_pipeline(
+                &name,
+                &template,
+                description.as_deref(),
+                author.as_deref(),
+                &variables,
+            )?;
             println!("✅ Pipeline '{name}' created successfully!");
 
             Ok(())
         }
+        PipelineAction::Delete { name, force } => {
+            let manager = PipelineManager::new()?;
+
+            if !force && !confirm(&format!("❓ Delete pipeline '{name}'? (y/N): "))? {
+                println!("❌ Delete cancelled");
+                return Ok(());
+            }
+
+            let deleted = manager.delete_pipeline(&name)?;
+            println!("🗑️  Deleted {}", deleted.file_path.display());
+
+            // State is keyed by the pipeline's metadata name
+            let state_manager = open_cli_state_manager().await?;
+            if state_manager.load_state(&deleted.name).await.is_ok()
+                && (force || confirm("❓ Also delete its saved state? (y/N): ")?)
+            {
+                state_manager.delete_state(&deleted.name).await?;
+                println!("🗑️  Deleted state for '{}'", deleted.name);
+            }
+
+            Ok(())
+        }
+        PipelineAction::Rename { name, new_name } => {
+            let manager = PipelineManager::new()?;
+            let (old, new) = manager.rename_pipeline(&name, &new_name)?;
+            println!(
+                "✅ Renamed {} → {}",
+                old.file_path.display(),
+                new.file_path.display()
+            );
+
+            let state_manager = open_cli_state_manager().await?;
+            if state_manager.load_state(&old.name).await.is_ok() {
+                match state_manager.rename_pipeline(&old.name, &new.name).await {
+                    Ok(()) => println!("🔄 Moved state '{}' → '{}'", old.name, new.name),
+                    Err(e) => eprintln!(
+                        "⚠️  Pipeline renamed but its state was not moved: {e}\n   State remains under '{}'",
+                        old.name
+                    ),
+                }
+            }
+
+            Ok(())
+        }
+        PipelineAction::Copy { name, new_name } => {
+            let manager = PipelineManager::new()?;
+            let path = manager.copy_pipeline(&name, &new_name)?;
+            println!("✅ Copied '{name}' to {}", path.display());
+            Ok(())
+        }
+        PipelineAction::Export {
+            name,
+            bundle: false,
+            output,
+        } => {
+            let manager = PipelineManager::new()?;
+            let metadata = manager.find_pipeline_metadata(&name)?;
+            let content = std::fs::read_to_string(&metadata.file_path)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, content)?;
+                    println!("✅ Exported '{name}' to {}", path.display());
+                }
+                None => print!("{content}"),
+            }
+            Ok(())
+        }
+        PipelineAction::Export {
+            name,
+            bundle: true,
+            output,
+        } => {
+            let manager = PipelineManager::new()?;
+            let output = output.unwrap_or_else(|| PathBuf::from(format!("{name}.tar.gz")));
+            let manifest = manager.export_bundle(&name, &output)?;
+            println!("📦 Bundled '{name}' into {}", output.display());
+            println!("   Oxis: {}", manifest.oxis.join(", "));
+            if !manifest.env_vars.is_empty() {
+                let vars: Vec<&str> = manifest.env_vars.iter().map(|v| v.name.as_str()).collect();
+                println!("   Environment variables: {}", vars.join(", "));
+            }
+            if !manifest.secrets.is_empty() {
+                println!("   Secrets: {}", manifest.secrets.join(", "));
+            }
+            Ok(())
+        }
+        PipelineAction::Import { bundle, force } => {
+            let manager = PipelineManager::new()?;
+            let registry = project_registry(&ProjectConfig::load()?)?;
+            let (manifest, path) = manager.import_bundle(&bundle, &registry, force)?;
+            println!("✅ Imported '{}' to {}", manifest.pipeline, path.display());
+            if !manifest.secrets.is_empty() {
+                println!(
+                    "💡 Make sure these secrets are available: {}",
+                    manifest.secrets.join(", ")
+                );
+            }
+            Ok(())
+        }
+        PipelineAction::Template { action } => {
+            let manager = PipelineManager::new()?;
+
+            match action {
+                TemplateAction::List => {
+                    let templates = manager.list_templates()?;
+                    println!("📄 Pipeline Templates ({}):", templates.len());
+                    for template in templates {
+                        let source = match &template.source {
+                            TemplateSource::Builtin => "built-in".to_string(),
+                            TemplateSource::Project(path) => path.display().to_string(),
+                        };
+                        println!(
+                            "  {:<12} - {} ({source})",
+                            template.name, template.description
+                        );
+                    }
+                }
+                TemplateAction::Show { name } => {
+                    let template = manager.get_template(&name)?;
+                    let variables = template.variables();
+                    println!("# Template: {}", template.name);
+                    if !variables.is_empty() {
+                        println!("# Variables: {}", variables.join(", "));
+                    }
+                    println!("{}", template.content);
+                }
+            }
+
+            Ok(())
+        }
         PipelineAction::Test {
             name,
             dry_run,
             verbose,
             fix,
             schema,
+            sarif,
+            strict,
         } => {
             let manager = PipelineManager::new()?;
 
             match manager.test_pipeline(&name, dry_run, verbose, fix, schema) {
-                Ok(result) => {
+                Ok(mut result) => {
+                    result.strict = strict;
                     let output = manager.format_validation_result(&result, verbose);
                     println!("{output}");
 
+                    if let Some(path) = sarif {
+                        std::fs::write(&path, serde_json::to_string_pretty(&result.to_sarif())?)?;
+                        println!("📄 SARIF report written to {path}");
+                    }
+
+                    if schema && result.is_valid() {
+                        let trace = infer_schema_chain(&manager.load_pipeline(&name)?, None)?;
+                        println!("\n🔧 Schema Flow:");
+                        print!("{}", format_schema_chain(&trace));
+                    }
+
                     if !result.is_valid() {
                         std::process::exit(1);
                     }
@@ -224,72 +720,410 @@ async fn handle_pipeline_command(action: PipelineAction) -> anyhow::Result<()> {
 
             Ok(())
         }
+        PipelineAction::Lint { name, all, json } => {
+            if name.is_none() && !all {
+                return Err(anyhow::anyhow!(
+                    "Specify a pipeline name or use --all to lint every pipeline"
+                ));
+            }
+
+            let manager = PipelineManager::new()?;
+            let reports = manager.lint_pipelines(name.as_deref())?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&reports)?);
+            } else {
+                println!("{}", format_lint_reports(&reports));
+            }
+
+            if reports.iter().any(|r| r.has_errors()) {
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+        PipelineAction::Graph {
+            name,
+            format,
+            from_state,
+        } => {
+            let format: GraphFormat = format.parse()?;
+            let manager = PipelineManager::new()?;
+            let pipeline = manager.load_pipeline(&name)?;
+
+            let state = if from_state {
+                let state_manager = open_cli_state_manager().await?;
+                match state_manager.load_state(&pipeline.name()).await {
+                    Ok(state) => Some(state),
+                    Err(_) => {
+                        eprintln!(
+                            "⚠️  No recorded state for '{}', showing structure only",
+                            pipeline.name()
+                        );
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            let graph = build_pipeline_graph(&pipeline, state.as_ref());
+            println!("{}", graph.render(format));
+
+            Ok(())
+        }
+        PipelineAction::Deps { format } => {
+            let manager = PipelineManager::new()?;
+            let graph = manager.build_dependency_graph()?;
+            match format.to_lowercase().as_str() {
+                "tree" => print!("{}", dependency_tree(&graph)),
+                "mermaid" => print!("{}", dependency_mermaid(&graph)),
+                "order" => {
+                    for pipeline in manager.topological_sort()? {
+                        println!("{}", pipeline.name);
+                    }
+                }
+                other => {
+                    return Err(anyhow::anyhow!(
+                        "Unknown deps format '{other}'. Supported formats: tree, mermaid, order"
+                    ))
+                }
+            }
+            Ok(())
+        }
         PipelineAction::Info {
             name,
             schema,
+            sample,
+            history,
+            show_effective_config,
             json,
             yaml,
         } => {
             // Use pipeline manager to find and display pipeline info
             let manager = PipelineManager::new()?;
-            let pipelines = manager.discover_pipelines()?;
-
-            // Find the pipeline by name (check both display name and filename)
-            if let Some(pipeline) = pipelines.iter().find(|p| {
-                p.name == name
-                    || p.file_path
-                        .file_stem()
-                        .and_then(|stem| stem.to_str())
-                        .map(|stem| stem == name)
-                        .unwrap_or(false)
-            }) {
+
+            if let Some(step_id) = show_effective_config {
+                let pipeline = manager.load_pipeline(&name)?;
+                let step = pipeline
+                    .pipeline
+                    .iter()
+                    .find(|step| step.get_id() == step_id)
+                    .ok_or_else(|| anyhow::anyhow!("Pipeline '{name}' has no step '{step_id}'"))?;
+                let config: std::collections::BTreeMap<_, _> = step.config.iter().collect();
                 if json {
-                    // Output as JSON
-                    let json_output = serde_json::to_string_pretty(pipeline)?;
-                    println!("{json_output}");
-                } else if yaml {
-                    // Output as YAML
-                    let yaml_output = serde_yaml::to_string(pipeline)?;
-                    println!("{yaml_output}");
+                    println!("{}", serde_json::to_string_pretty(&config)?);
                 } else {
-                    // Standard formatted output
-                    println!("📋 Pipeline Information: {}\n", pipeline.name);
+                    print!("{}", serde_yaml::to_string(&config)?);
+                }
+                return Ok(());
+            }
 
-                    println!("📝 Metadata:");
-                    if let Some(description) = &pipeline.description {
-                        println!("   Description: {description}");
-                    }
-                    if let Some(version) = &pipeline.version {
-                        println!("   Version: {version}");
-                    }
-                    if let Some(author) = &pipeline.author {
-                        println!("   Author: {author}");
-                    }
-                    if let Some(tags) = &pipeline.tags {
-                        println!("   Tags: {}", tags.join(", "));
-                    }
-                    if let Some(created) = &pipeline.created {
-                        println!("   Created: {created}");
-                    }
-                    println!("   Location: {}", pipeline.file_path.display());
+            let schema_chain = if schema {
+                let initial = sample
+                    .as_deref()
+                    .map(|path| sample_schema(path, false))
+                    .transpose()?;
+                Some(infer_schema_chain(&manager.load_pipeline(&name)?, initial)?)
+            } else {
+                None
+            };
 
-                    println!("\n⚙️  Configuration:");
-                    if pipeline.step_names.is_empty() {
-                        println!("   Steps: {} total", pipeline.step_count);
-                    } else {
-                        println!(
-                            "   Steps: {} ({})",
-                            pipeline.step_count,
-                            pipeline.step_names.join(" → ")
-                        );
-                    }
+            if let (Some(chain), true) = (&schema_chain, json) {
+                let output = serde_json::json!({ "pipeline": name, "steps": chain });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            let runs = if history {
+                let pipeline_name = manager.load_pipeline(&name)?.name();
+                Some(RunHistory::new(DEFAULT_RUNS_DIR).list(&pipeline_name)?)
+            } else {
+                None
+            };
+
+            if let (Some(runs), true) = (&runs, json) {
+                let output = serde_json::json!({ "pipeline": name, "runs": runs });
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            // Find the pipeline by namespaced, bare or display name
+            let mut pipeline = manager.find_pipeline_metadata(&name)?;
+
+            // Duration estimates come from completed runs in the state store
+            let mut p95 = None;
+            if std::path::Path::new(".oxiflow/state").exists() {
+                let state_manager = open_cli_state_manager().await?;
+                let state_id = manager.load_pipeline(&name)?.name();
+                pipeline.estimated_duration =
+                    manager.estimate_duration(&state_id, &state_manager).await?;
+                p95 = manager.p95_duration(&state_id, &state_manager).await?;
+            }
+
+            if json {
+                // Output as JSON
+                let json_output = serde_json::to_string_pretty(&pipeline)?;
+                println!("{json_output}");
+            } else if yaml {
+                // Output as YAML
+                let yaml_output = serde_yaml::to_string(&pipeline)?;
+                println!("{yaml_output}");
+            } else {
+                // Standard formatted output
+                println!("📋 Pipeline Information: {}\n", pipeline.name);
+
+                println!("📝 Metadata:");
+                if let Some(description) = &pipeline.description {
+                    println!("   Description: {description}");
+                }
+                if let Some(version) = &pipeline.version {
+                    println!("   Version: {version}");
+                }
+                if let Some(author) = &pipeline.author {
+                    println!("   Author: {author}");
+                }
+                if let Some(tags) = &pipeline.tags {
+                    println!("   Tags: {}", tags.join(", "));
+                }
+                if let Some(created) = &pipeline.created {
+                    println!("   Created: {created}");
+                }
+                println!("   Location: {}", pipeline.file_path.display());
+
+                println!("\n⚙️  Configuration:");
+                if pipeline.step_names.is_empty() {
+                    println!("   Steps: {} total", pipeline.step_count);
+                } else {
+                    println!(
+                        "   Steps: {} ({})",
+                        pipeline.step_count,
+                        pipeline.step_names.join(" → ")
+                    );
+                }
 
-                    if schema {
-                        println!("\n🔧 Schema information will be implemented in Phase 4");
+                if let Some(median) = pipeline.estimated_duration {
+                    println!("\n⏱️  Estimated Duration:");
+                    println!("   Median: {:.1}s", median.as_secs_f64());
+                    if let Some(p95) = p95 {
+                        println!("   p95: {:.1}s", p95.as_secs_f64());
                     }
                 }
+
+                if let Some(chain) = &schema_chain {
+                    println!("\n🔧 Schema:");
+                    print!("{}", format_schema_chain(chain));
+                }
+
+                if let Some(runs) = &runs {
+                    println!("\n🕘 Run History:");
+                    print!("{}", format_history(runs));
+                }
+            }
+
+            Ok(())
+        }
+        PipelineAction::Benchmark {
+            name,
+            iterations,
+            warmup,
+            data_size_mb,
+        } => {
+            let project_config = ProjectConfig::load()
+                .map_err(|e| anyhow::anyhow!("Failed to load project configuration: {}", e))?;
+            let pipeline_path = project_config.find_pipeline(&name)?;
+            let mut pipeline = Pipeline::load_from_file(&pipeline_path.to_string_lossy())?;
+            pipeline.apply_defaults(&project_config.defaults);
+            pipeline.attach_registry(&project_registry(&project_config)?);
+
+            let mut resolver = ConfigResolver::default();
+            resolver.set_secret_resolver(project_config.secret_resolver());
+
+            let schema = benchmark::input_schema(&pipeline, &resolver);
+            let input = benchmark::synthetic_input(&schema, data_size_mb)?;
+            let records = input.data().as_json()?.as_array().map_or(1, Vec::len);
+
+            // Progress goes to stderr so stdout is just the table
+            eprintln!(
+                "⏱️  Benchmarking '{}': {warmup} warm-up + {iterations} measured runs on {records} records",
+                pipeline.name()
+            );
+            let samples =
+                benchmark::run_benchmark(&pipeline, &resolver, &input, iterations, warmup).await?;
+            let stats = BenchmarkStats::from_samples(&samples, records)
+                .ok_or_else(|| anyhow::anyhow!("Benchmark recorded no runs"))?;
+
+            print!("{}", stats.to_markdown(&pipeline.name()));
+            Ok(())
+        }
+    }
+}
+
+/// Schema inferred from a sample input file, read as JSON when it parses
+fn sample_schema(path: &str, infer_constraints: bool) -> anyhow::Result<OxiSchema> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read sample '{}': {}", path, e))?;
+    let data = serde_json::from_str(&content)
+        .map(Data::Json)
+        .unwrap_or(Data::Text(content));
+    let options = InferenceOptions {
+        infer_constraints,
+        ..InferenceOptions::default()
+    };
+    Ok(OxiSchema::infer_from_data_with(&data, options)?)
+}
+
+fn handle_schema_command(action: SchemaAction) -> anyhow::Result<()> {
+    match action {
+        SchemaAction::Export {
+            pipeline,
+            step,
+            sample,
+            infer_constraints,
+            output,
+        } => {
+            let manager = PipelineManager::new()?;
+            let initial = sample
+                .as_deref()
+                .map(|path| sample_schema(path, infer_constraints))
+                .transpose()?;
+            let trace = infer_schema_chain(&manager.load_pipeline(&pipeline)?, initial)?;
+
+            let entry = match &step {
+                Some(id) => trace
+                    .iter()
+                    .find(|entry| &entry.step_id == id)
+                    .ok_or_else(|| {
+                        let known: Vec<&str> = trace.iter().map(|e| e.step_id.as_str()).collect();
+                        anyhow::anyhow!(
+                            "Step '{}' not found in pipeline '{}'; known steps: {}",
+                            id,
+                            pipeline,
+                            known.join(", ")
+                        )
+                    })?,
+                None => trace
+                    .last()
+                    .ok_or_else(|| anyhow::anyhow!("Pipeline '{}' has no steps", pipeline))?,
+            };
+            let schema = entry.output_schema.as_ref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Output schema of step '{}' could not be determined: {}",
+                    entry.step_id,
+                    entry
+                        .error
+                        .as_deref()
+                        .unwrap_or("an earlier step's schema is unknown")
+                )
+            })?;
+
+            let document = serde_json::to_string_pretty(&schema.to_json_schema())?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, format!("{document}\n"))
+                        .map_err(|e| anyhow::anyhow!("Failed to write '{}': {}", path, e))?;
+                    println!("✅ Exported schema of step '{}' to {}", entry.step_id, path);
+                }
+                None => println!("{document}"),
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Built-in Oxis plus the plugins `project_config` declares
+fn project_registry(project_config: &ProjectConfig) -> anyhow::Result<OxiRegistry> {
+    let mut registry = OxiRegistry::with_builtins();
+    registry.register_plugins(&oxis::plugin::load_plugins(&project_config.plugins)?);
+    Ok(registry)
+}
+
+fn handle_oxi_command(action: OxiAction) -> anyhow::Result<()> {
+    // Outside a project only the built-ins are available
+    let registry = if std::path::Path::new("oxiflow.yaml").exists() {
+        project_registry(&ProjectConfig::load()?)?
+    } else {
+        OxiRegistry::with_builtins()
+    };
+
+    match action {
+        OxiAction::List { json } => {
+            let rows: Vec<(&RegisteredOxi, OxiDescriptor)> = registry
+                .entries()
+                .map(|entry| (entry, entry.describe()))
+                .collect();
+
+            if json {
+                let listing: Vec<serde_json::Value> = rows
+                    .iter()
+                    .map(|(entry, descriptor)| {
+                        serde_json::json!({
+                            "name": entry.name,
+                            "category": entry.category,
+                            "description": descriptor.description,
+                            "schema_strategy": descriptor.schema_strategy.label(),
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&listing)?);
+                return Ok(());
+            }
+
+            println!("🧩 Available Oxis ({}):", rows.len());
+            println!(
+                "   {:<16} {:<10} {:<12} Description",
+                "Name", "Category", "Schema"
+            );
+            for (entry, descriptor) in &rows {
+                println!(
+                    "   {:<16} {:<10} {:<12} {}",
+                    entry.name,
+                    entry.category,
+                    descriptor.schema_strategy.label(),
+                    descriptor.description
+                );
+            }
+            println!("\nRun 'oxide_flow oxi describe <name>' for an Oxi's options.");
+            Ok(())
+        }
+        OxiAction::Describe { name, json } => {
+            let entry = registry.get(&name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown Oxi '{}'. Available: {}",
+                    name,
+                    registry.names().collect::<Vec<_>>().join(", ")
+                )
+            })?;
+            let descriptor = entry.describe();
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&descriptor)?);
+            } else {
+                print!("{}", descriptor.format_reference());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Handle Oxi plugin inspection commands
+fn handle_plugin_command(action: PluginAction) -> anyhow::Result<()> {
+    match action {
+        PluginAction::Info { name, json } => {
+            let oxi = oxis::builtin(&name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown Oxi '{}'. Available: {}",
+                    name,
+                    oxis::BUILTIN_OXIS.join(", ")
+                )
+            })?;
+            let descriptor = oxi.describe();
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&descriptor)?);
             } else {
-                return Err(anyhow::anyhow!("Pipeline '{}' not found", name));
+                println!("{}", descriptor.format_card());
             }
 
             Ok(())
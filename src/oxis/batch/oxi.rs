@@ -86,6 +86,11 @@ impl Oxi for Batch {
         SchemaStrategy::Passthrough
     }
 
+    // Batches must be cut from the whole record stream
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
     fn processing_limits(&self) -> ProcessingLimits {
         ProcessingLimits {
             max_batch_size: Some(10000), // Allow large batches but with reasonable limit
@@ -260,7 +265,7 @@ impl Batch {
         let batched_data: Vec<u8> = batches.into_iter().flatten().collect();
 
         Ok(OxiData::with_schema(
-            Data::Binary(batched_data),
+            Data::from_binary(batched_data),
             input.schema().clone(),
         ))
     }
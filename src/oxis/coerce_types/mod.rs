@@ -0,0 +1,2 @@
+pub mod oxi;
+pub use oxi::CoerceTypes;
@@ -0,0 +1,437 @@
+use crate::oxis::prelude::*;
+use crate::types::{FieldSchema, FieldType, OxiSchema};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// CoerceTypes converts JSON fields to the types declared by a target schema
+pub struct CoerceTypes;
+
+#[async_trait]
+impl Oxi for CoerceTypes {
+    fn name(&self) -> &str {
+        "coerce_types"
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Modify {
+            description: "Coerces fields to the configured target schema".to_string(),
+        }
+    }
+
+    fn watched_paths(&self, config: &OxiConfig) -> Vec<std::path::PathBuf> {
+        config
+            .get_string("schema_file")
+            .map(Into::into)
+            .into_iter()
+            .collect()
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        ProcessingLimits {
+            supported_input_types: vec![OxiDataType::Json],
+            ..ProcessingLimits::default()
+        }
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              schema:
+                type: object
+                description: "Inline target schema: field name -> type (string, integer, float, boolean, datetime) or {type, nullable, formats}; formats lists extra strftime patterns for datetime fields"
+              schema_file:
+                type: string
+                description: "Path to a YAML/JSON file containing the target schema (used when 'schema' is not set)"
+              on_error:
+                type: string
+                enum: ["fail", "keep"]
+                description: "fail: error listing every uncoercible value, keep: leave those values unchanged"
+                default: "fail"
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn output_schema(
+        &self,
+        _input_schema: Option<&OxiSchema>,
+        config: &OxiConfig,
+    ) -> anyhow::Result<OxiSchema> {
+        Ok(target_schema(config)?)
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let schema = target_schema(config)?;
+        let on_error = config.get_string_or("on_error", "fail");
+
+        let mut value = input
+            .data()
+            .as_json()
+            .map_err(|_| OxiError::TypeMismatch {
+                expected: "JSON".to_string(),
+                actual: input.data().data_type().to_string(),
+                step: "coerce_types".to_string(),
+            })?
+            .clone();
+
+        let mut errors = Vec::new();
+        match &mut value {
+            Value::Array(items) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    coerce_record(item, &schema, &format!("[{i}]"), &mut errors);
+                }
+            }
+            record => coerce_record(record, &schema, "", &mut errors),
+        }
+
+        if !errors.is_empty() && on_error != "keep" {
+            return Err(OxiError::ValidationError {
+                details: format!(
+                    "{} value(s) could not be coerced: {}",
+                    errors.len(),
+                    errors.join("; ")
+                ),
+            });
+        }
+
+        Ok(OxiData::with_schema(Data::Json(value), schema))
+    }
+}
+
+/// Load the target schema from `schema` (inline) or `schema_file`
+pub fn target_schema(config: &OxiConfig) -> Result<OxiSchema, OxiError> {
+    let definition = if let Ok(inline) = config.get_structured("schema") {
+        inline
+    } else if let Ok(path) = config.get_string("schema_file") {
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            OxiError::ConfigError(format!("Failed to read schema file '{path}': {e}"))
+        })?;
+        serde_yaml::from_str(&content)?
+    } else {
+        return Err(OxiError::MissingConfig(
+            "coerce_types requires 'schema' or 'schema_file'".to_string(),
+        ));
+    };
+
+    // A full serialized OxiSchema, or the short field -> type form
+    if let Ok(schema) = serde_yaml::from_value::<OxiSchema>(definition.clone()) {
+        return Ok(schema);
+    }
+    parse_field_map(&definition)
+}
+
+fn parse_field_map(definition: &serde_yaml::Value) -> Result<OxiSchema, OxiError> {
+    let fields = definition.as_mapping().ok_or_else(|| {
+        OxiError::ConfigError("Target schema must be a mapping of field names".to_string())
+    })?;
+
+    let mut schema = OxiSchema::empty();
+    schema.metadata.created_by = "coerce_types".to_string();
+
+    for (name, spec) in fields {
+        let name = name
+            .as_str()
+            .ok_or_else(|| OxiError::ConfigError("Field names must be strings".to_string()))?;
+        let (type_name, nullable, formats) = match spec {
+            serde_yaml::Value::String(type_name) => (type_name.as_str(), false, None),
+            serde_yaml::Value::Mapping(map) => (
+                map.get("type").and_then(|t| t.as_str()).ok_or_else(|| {
+                    OxiError::ConfigError(format!("Field '{name}' is missing a type"))
+                })?,
+                map.get("nullable")
+                    .and_then(|n| n.as_bool())
+                    .unwrap_or(false),
+                map.get("formats")
+                    .map(|formats| serde_yaml::from_value::<Vec<String>>(formats.clone()))
+                    .transpose()
+                    .map_err(|_| {
+                        OxiError::ConfigError(format!(
+                            "Field '{name}' formats must be a list of strings"
+                        ))
+                    })?,
+            ),
+            _ => {
+                return Err(OxiError::ConfigError(format!(
+                    "Field '{name}' must be a type name or {{type, nullable, formats}}"
+                )))
+            }
+        };
+
+        let mut field = FieldSchema::new(parse_field_type(type_name).ok_or_else(|| {
+            OxiError::ConfigError(format!("Unknown type '{type_name}' for field '{name}'"))
+        })?);
+        field.nullable = nullable;
+        field.datetime_formats = formats;
+        schema.add_field(name.to_string(), field);
+    }
+
+    Ok(schema)
+}
+
+fn parse_field_type(name: &str) -> Option<FieldType> {
+    match name.to_lowercase().as_str() {
+        "string" => Some(FieldType::String),
+        "integer" | "int" => Some(FieldType::Integer),
+        "float" | "number" => Some(FieldType::Float),
+        "boolean" | "bool" => Some(FieldType::Boolean),
+        "datetime" => Some(FieldType::DateTime),
+        _ => None,
+    }
+}
+
+/// Coerce the schema's fields in one object, collecting failures into `errors`
+fn coerce_record(record: &mut Value, schema: &OxiSchema, path: &str, errors: &mut Vec<String>) {
+    let Value::Object(map) = record else {
+        errors.push(format!("{path}: expected an object"));
+        return;
+    };
+
+    for (name, field) in &schema.fields {
+        // Missing fields are left for schema validation to report
+        let Some(value) = map.get_mut(name) else {
+            continue;
+        };
+        let field_path = if path.is_empty() {
+            name.clone()
+        } else {
+            format!("{path}.{name}")
+        };
+
+        if value.is_null() {
+            if !field.nullable {
+                errors.push(format!("{field_path}: null is not allowed"));
+            }
+            continue;
+        }
+
+        let coerced = match (&field.field_type, &field.datetime_formats) {
+            (FieldType::DateTime, Some(formats)) => coerce_datetime(value, formats),
+            (field_type, _) => coerce_value(value, field_type),
+        };
+        match coerced {
+            Ok(coerced) => *value = coerced,
+            Err(reason) => errors.push(format!("{field_path}: {reason}")),
+        }
+    }
+}
+
+/// Normalize a timestamp string or epoch number to RFC3339 in UTC
+fn coerce_datetime(value: &Value, formats: &[String]) -> Result<Value, String> {
+    crate::datetime::parse_datetime(value, formats)
+        .map(|datetime| Value::String(crate::datetime::format_datetime(&datetime)))
+        .ok_or_else(|| format!("{value} is not a recognized datetime"))
+}
+
+/// Convert a JSON value to the given field type where a lossless conversion exists
+pub fn coerce_value(value: &Value, field_type: &FieldType) -> Result<Value, String> {
+    match (field_type, value) {
+        (FieldType::String, Value::String(_)) => Ok(value.clone()),
+        (FieldType::String, Value::Number(n)) => Ok(Value::String(n.to_string())),
+        (FieldType::String, Value::Bool(b)) => Ok(Value::String(b.to_string())),
+
+        (FieldType::Integer, Value::Number(n)) if n.is_i64() || n.is_u64() => Ok(value.clone()),
+        (FieldType::Integer, Value::Number(n)) => n
+            .as_f64()
+            .and_then(whole_i64)
+            .map(Value::from)
+            .ok_or_else(|| format!("{n} is not a whole number in integer range")),
+        (FieldType::Integer, Value::String(s)) => {
+            let trimmed = s.trim();
+            trimmed.parse::<i64>().map(Value::from).or_else(|_| {
+                trimmed
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(whole_i64)
+                    .map(Value::from)
+                    .ok_or_else(|| format!("cannot parse '{s}' as integer"))
+            })
+        }
+
+        (FieldType::Float, Value::Number(_)) => Ok(value.clone()),
+        (FieldType::Float, Value::String(s)) => s
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("cannot parse '{s}' as float")),
+
+        (FieldType::Boolean, Value::Bool(_)) => Ok(value.clone()),
+        (FieldType::Boolean, Value::String(s)) => match s.trim().to_lowercase().as_str() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => Err(format!("cannot parse '{s}' as boolean")),
+        },
+
+        (FieldType::DateTime, Value::String(_) | Value::Number(_)) => coerce_datetime(value, &[]),
+
+        (
+            FieldType::String
+            | FieldType::Integer
+            | FieldType::Float
+            | FieldType::Boolean
+            | FieldType::DateTime,
+            other,
+        ) => Err(format!("cannot coerce {other} to {field_type:?}")),
+
+        // Complex and open types are left untouched
+        _ => Ok(value.clone()),
+    }
+}
+
+/// `f` as an `i64` when it is finite, whole and in range; `as` would saturate
+fn whole_i64(f: f64) -> Option<i64> {
+    // i64::MAX rounds up to 2^63 as f64, so the upper bound is exclusive
+    const BOUND: f64 = 9_223_372_036_854_775_808.0;
+    (f.is_finite() && f.fract() == 0.0 && (-BOUND..BOUND).contains(&f)).then_some(f as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_coerces_numeric_and_boolean_strings() {
+        let config = OxiConfig::from_yaml_str(
+            r#"
+            schema:
+              age: integer
+              score: float
+              active: boolean
+              joined: datetime
+            "#,
+        );
+        let input = OxiData::from_json(json!([
+            {"age": "42", "score": " 9.5", "active": "TRUE", "joined": "2024-03-01T10:00:00+02:00"},
+            {"age": 7, "score": 3, "active": "false", "joined": "2024-03-01T08:00:00Z"}
+        ]));
+
+        let output = CoerceTypes.process(input, &config).await.unwrap();
+        let records = output.data().as_json().unwrap();
+
+        assert_eq!(records[0]["age"], json!(42));
+        assert_eq!(records[0]["score"], json!(9.5));
+        assert_eq!(records[0]["active"], json!(true));
+        assert_eq!(records[0]["joined"], json!("2024-03-01T08:00:00Z"));
+        assert_eq!(records[1]["active"], json!(false));
+        assert_eq!(records[1]["joined"], records[0]["joined"]);
+        assert_eq!(output.schema().fields["age"].field_type, FieldType::Integer);
+        assert!(output.schema().validate_data(output.data()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_uncoercible_value_is_reported() {
+        let input = OxiData::from_json(json!([
+            {"age": "forty", "active": "yes"},
+            {"age": "12", "active": "true"}
+        ]));
+
+        let err = CoerceTypes
+            .process(
+                input.clone(),
+                &OxiConfig::from_yaml_str("schema: {age: integer, active: boolean}"),
+            )
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("2 value(s)"), "{err}");
+        assert!(
+            err.contains("[0].age: cannot parse 'forty' as integer"),
+            "{err}"
+        );
+        assert!(err.contains("[0].active"), "{err}");
+
+        // keep mode leaves bad values in place and coerces the rest
+        let output = CoerceTypes
+            .process(
+                input,
+                &OxiConfig::from_yaml_str(
+                    "{schema: {age: integer, active: boolean}, on_error: keep}",
+                ),
+            )
+            .await
+            .unwrap();
+        let records = output.data().as_json().unwrap();
+        assert_eq!(records[0]["age"], json!("forty"));
+        assert_eq!(records[1]["age"], json!(12));
+    }
+
+    #[test]
+    fn test_integer_rejects_out_of_range_and_fractional_values() {
+        assert_eq!(
+            coerce_value(&json!("1e3"), &FieldType::Integer),
+            Ok(json!(1000))
+        );
+        assert_eq!(coerce_value(&json!(2.0), &FieldType::Integer), Ok(json!(2)));
+        for bad in [
+            json!("1e30"),
+            json!(1e30),
+            json!("2.5"),
+            json!(-1.5),
+            json!("inf"),
+        ] {
+            assert!(
+                coerce_value(&bad, &FieldType::Integer).is_err(),
+                "{bad} should not coerce"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_datetimes_normalized_from_any_representation() {
+        let config = OxiConfig::from_yaml_str(
+            r#"
+            schema:
+              seen: datetime
+              billed: {type: datetime, formats: ["%d/%m/%Y %H:%M"]}
+            "#,
+        );
+        let input = OxiData::from_json(json!([
+            {"seen": "2024-01-05 13:00:00", "billed": "05/01/2024 13:00"},
+            {"seen": 1704459600000_i64, "billed": "Fri, 05 Jan 2024 13:00:00 +0000"}
+        ]));
+
+        let output = CoerceTypes.process(input, &config).await.unwrap();
+        assert_eq!(
+            output.data().as_json().unwrap(),
+            &json!([
+                {"seen": "2024-01-05T13:00:00Z", "billed": "2024-01-05T13:00:00Z"},
+                {"seen": "2024-01-05T13:00:00Z", "billed": "2024-01-05T13:00:00Z"}
+            ])
+        );
+
+        let err = CoerceTypes
+            .process(
+                OxiData::from_json(json!({"seen": "05/01/2024 13:00"})),
+                &config,
+            )
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("seen: \"05/01/2024 13:00\" is not a recognized datetime"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_output_schema_is_target_schema() {
+        let schema = CoerceTypes
+            .output_schema(
+                None,
+                &OxiConfig::from_yaml_str(
+                    "schema: {id: integer, note: {type: string, nullable: true}}",
+                ),
+            )
+            .unwrap();
+        assert_eq!(schema.fields["id"].field_type, FieldType::Integer);
+        assert!(schema.fields["note"].nullable);
+
+        assert!(CoerceTypes
+            .output_schema(None, &OxiConfig::from_yaml_str("{}"))
+            .is_err());
+    }
+}
@@ -0,0 +1,608 @@
+use crate::oxis::prelude::*;
+use crate::types::{FieldSchema, FieldType, OxiSchema};
+use async_trait::async_trait;
+use serde_json::{Number, Value};
+
+/// ComputeField adds fields calculated from each record's other fields
+///
+/// Each entry of `fields` names a field and an expression such as
+/// `price * qty` or `first + " " + last`. Entries are evaluated in order, so
+/// an expression may use a field computed by an earlier entry. A missing
+/// field, a division by zero or an operator applied to the wrong types
+/// yields `null`, or fails the step when `strict` is set. A field that is
+/// present but `null` makes the result `null` either way.
+pub struct ComputeField;
+
+/// Options accepted by [`ComputeField`]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ComputeFieldConfig {
+    fields: Vec<FieldSpec>,
+    /// Fail instead of writing `null` when an expression can't be evaluated
+    strict: bool,
+}
+
+/// One `{field, expression}` entry
+#[derive(Debug, Deserialize)]
+struct FieldSpec {
+    field: String,
+    expression: String,
+}
+
+impl ComputeFieldConfig {
+    /// Entries with their expressions parsed
+    fn parse(config: &OxiConfig) -> Result<(Vec<(String, Expr)>, bool), OxiError> {
+        let options: Self = config
+            .deserialize_into()
+            .map_err(|e| OxiError::ConfigError(e.to_string()))?;
+        if options.fields.is_empty() {
+            return Err(OxiError::MissingConfig(
+                "compute_field requires at least one entry in 'fields'".to_string(),
+            ));
+        }
+        let fields = options
+            .fields
+            .into_iter()
+            .map(|spec| {
+                let expr = Expr::parse(&spec.expression).map_err(|e| {
+                    OxiError::ConfigError(format!(
+                        "Invalid expression for field '{}': {e}",
+                        spec.field
+                    ))
+                })?;
+                Ok((spec.field, expr))
+            })
+            .collect::<Result<_, OxiError>>()?;
+        Ok((fields, options.strict))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Op {
+    fn symbol(self) -> char {
+        match self {
+            Op::Add => '+',
+            Op::Sub => '-',
+            Op::Mul => '*',
+            Op::Div => '/',
+            Op::Rem => '%',
+        }
+    }
+}
+
+/// Parsed expression: literals, field references, `+ - * / %`, unary minus and parentheses
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Value),
+    /// Dotted path into the record; numeric segments index arrays
+    Field(Vec<String>),
+    Neg(Box<Expr>),
+    Binary(Box<Expr>, Op, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(Value),
+    Str(String),
+    Ident(String),
+    Op(Op),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '+' | '-' | '*' | '/' | '%' => {
+                chars.next();
+                tokens.push(Token::Op(match c {
+                    '+' => Op::Add,
+                    '-' => Op::Sub,
+                    '*' => Op::Mul,
+                    '/' => Op::Div,
+                    _ => Op::Rem,
+                }));
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            }
+            '"' | '\'' | '`' => {
+                chars.next();
+                let mut literal = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '\\')) if c != '`' => match chars.next() {
+                            Some((_, 'n')) => literal.push('\n'),
+                            Some((_, 't')) => literal.push('\t'),
+                            Some((_, escaped)) => literal.push(escaped),
+                            None => return Err("unterminated string".to_string()),
+                        },
+                        Some((_, end)) if end == c => break,
+                        Some((_, other)) => literal.push(other),
+                        None if c == '`' => return Err("unterminated `field`".to_string()),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                tokens.push(if c == '`' {
+                    Token::Ident(literal)
+                } else {
+                    Token::Str(literal)
+                });
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                let literal = &text[start..end];
+                let number = serde_json::from_str::<Number>(literal)
+                    .map_err(|_| format!("invalid number '{literal}'"))?;
+                tokens.push(Token::Number(Value::Number(number)));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token::Ident(text[start..end].to_string()));
+            }
+            other => return Err(format!("unexpected '{other}'")),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over the token list
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next_op(&mut self, ops: &[Op]) -> Option<Op> {
+        match self.tokens.get(self.position) {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                self.position += 1;
+                Some(*op)
+            }
+            _ => None,
+        }
+    }
+
+    /// `term (('+' | '-') term)*`
+    fn expression(&mut self) -> Result<Expr, String> {
+        let mut expr = self.term()?;
+        while let Some(op) = self.next_op(&[Op::Add, Op::Sub]) {
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.term()?));
+        }
+        Ok(expr)
+    }
+
+    /// `unary (('*' | '/' | '%') unary)*`
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while let Some(op) = self.next_op(&[Op::Mul, Op::Div, Op::Rem]) {
+            expr = Expr::Binary(Box::new(expr), op, Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.next_op(&[Op::Sub]).is_some() {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or("expression ends too early")?;
+        self.position += 1;
+        Ok(match token {
+            Token::Number(number) => Expr::Literal(number),
+            Token::Str(text) => Expr::Literal(Value::String(text)),
+            Token::Ident(name) => match name.as_str() {
+                "true" => Expr::Literal(Value::Bool(true)),
+                "false" => Expr::Literal(Value::Bool(false)),
+                "null" => Expr::Literal(Value::Null),
+                _ => Expr::Field(name.split('.').map(str::to_string).collect()),
+            },
+            Token::Open => {
+                let expr = self.expression()?;
+                if self.tokens.get(self.position) != Some(&Token::Close) {
+                    return Err("missing ')'".to_string());
+                }
+                self.position += 1;
+                expr
+            }
+            Token::Close => return Err("unexpected ')'".to_string()),
+            Token::Op(op) => return Err(format!("unexpected '{}'", op.symbol())),
+        })
+    }
+}
+
+impl Expr {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            position: 0,
+        };
+        let expr = parser.expression()?;
+        if parser.position < parser.tokens.len() {
+            return Err(format!("unexpected input after '{text}'"));
+        }
+        Ok(expr)
+    }
+
+    /// Value of the expression for `record`; `Err` explains why there is none
+    fn eval(&self, record: &Value) -> Result<Value, String> {
+        match self {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Field(path) => path
+                .iter()
+                .try_fold(record, |current, segment| match current {
+                    Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+                    _ => current.get(segment),
+                })
+                .cloned()
+                .ok_or_else(|| format!("field '{}' is missing", path.join("."))),
+            Expr::Neg(inner) => match inner.eval(record)? {
+                Value::Null => Ok(Value::Null),
+                Value::Number(n) => match n.as_i64().and_then(i64::checked_neg) {
+                    Some(negated) => Ok(negated.into()),
+                    None => float(-n.as_f64().unwrap_or(f64::NAN)),
+                },
+                other => Err(format!("cannot negate {}", type_name(&other))),
+            },
+            Expr::Binary(left, op, right) => apply(*op, left.eval(record)?, right.eval(record)?),
+        }
+    }
+
+    /// Type the expression produces given the fields of `schema`
+    fn infer_type(&self, schema: &OxiSchema) -> FieldType {
+        match self {
+            Expr::Literal(Value::Number(n)) if n.is_i64() || n.is_u64() => FieldType::Integer,
+            Expr::Literal(Value::Number(_)) => FieldType::Float,
+            Expr::Literal(Value::String(_)) => FieldType::String,
+            Expr::Literal(Value::Bool(_)) => FieldType::Boolean,
+            Expr::Literal(_) => FieldType::Unknown,
+            Expr::Field(path) => match path.as_slice() {
+                [name] => schema
+                    .fields
+                    .get(name)
+                    .map_or(FieldType::Unknown, |field| field.field_type.clone()),
+                _ => FieldType::Unknown,
+            },
+            Expr::Neg(inner) => inner.infer_type(schema),
+            Expr::Binary(left, op, right) => {
+                let (left, right) = (left.infer_type(schema), right.infer_type(schema));
+                match (op, &left, &right) {
+                    (Op::Add, FieldType::String, _) | (Op::Add, _, FieldType::String) => {
+                        FieldType::String
+                    }
+                    (Op::Div, _, _) => FieldType::Float,
+                    (_, FieldType::Integer, FieldType::Integer) => FieldType::Integer,
+                    (
+                        _,
+                        FieldType::Integer | FieldType::Float,
+                        FieldType::Integer | FieldType::Float,
+                    ) => FieldType::Float,
+                    _ => FieldType::Unknown,
+                }
+            }
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn float(value: f64) -> Result<Value, String> {
+    Number::from_f64(value)
+        .map(Value::Number)
+        .ok_or_else(|| format!("result {value} is not a finite number"))
+}
+
+/// Apply `op`; `null` on either side gives `null`
+///
+/// `+` concatenates when either side is a string. Integer arithmetic stays
+/// integral unless it overflows, and `/` always gives a float.
+fn apply(op: Op, left: Value, right: Value) -> Result<Value, String> {
+    match (&left, &right) {
+        (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+        (Value::String(_), _) | (_, Value::String(_)) if op == Op::Add => {
+            Ok(Value::String(concat_text(&left) + &concat_text(&right)))
+        }
+        (Value::Number(l), Value::Number(r)) => {
+            if matches!(op, Op::Div | Op::Rem) && r.as_f64() == Some(0.0) {
+                return Err("division by zero".to_string());
+            }
+            if let (Some(l), Some(r)) = (l.as_i64(), r.as_i64()) {
+                let exact = match op {
+                    Op::Add => l.checked_add(r),
+                    Op::Sub => l.checked_sub(r),
+                    Op::Mul => l.checked_mul(r),
+                    Op::Rem => l.checked_rem(r),
+                    Op::Div => None,
+                };
+                if let Some(result) = exact {
+                    return Ok(result.into());
+                }
+            }
+            let (l, r) = (
+                l.as_f64().unwrap_or(f64::NAN),
+                r.as_f64().unwrap_or(f64::NAN),
+            );
+            float(match op {
+                Op::Add => l + r,
+                Op::Sub => l - r,
+                Op::Mul => l * r,
+                Op::Div => l / r,
+                Op::Rem => l % r,
+            })
+        }
+        _ => Err(format!(
+            "cannot apply '{}' to {} and {}",
+            op.symbol(),
+            type_name(&left),
+            type_name(&right)
+        )),
+    }
+}
+
+/// Text of a value joined by `+`: strings unquoted, anything else as JSON
+fn concat_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[async_trait]
+impl Oxi for ComputeField {
+    fn name(&self) -> &str {
+        "compute_field"
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              fields:
+                type: array
+                required: true
+                description: "Entries of {field, expression}, e.g. {field: total, expression: \"price * qty\"}; later entries may use earlier ones"
+              strict:
+                type: boolean
+                default: false
+                description: "Fail the step on a missing field, division by zero or type error instead of writing null"
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        ProcessingLimits {
+            supported_input_types: vec![OxiDataType::Json],
+            ..ProcessingLimits::default()
+        }
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Modify {
+            description: "Adds the computed fields".to_string(),
+        }
+    }
+
+    fn output_schema(
+        &self,
+        input_schema: Option<&OxiSchema>,
+        config: &OxiConfig,
+    ) -> anyhow::Result<OxiSchema> {
+        let (fields, _) = ComputeFieldConfig::parse(config)?;
+        let mut schema = input_schema.cloned().unwrap_or_else(OxiSchema::empty);
+        for (name, expr) in fields {
+            let mut field = FieldSchema::new(expr.infer_type(&schema));
+            // Null operands and non-strict failures produce null
+            field.nullable = true;
+            schema.add_field(name, field);
+        }
+        Ok(schema)
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let (fields, strict) = ComputeFieldConfig::parse(config)?;
+
+        let mut value = input
+            .data()
+            .as_json()
+            .map_err(|_| OxiError::TypeMismatch {
+                expected: "JSON array of records".to_string(),
+                actual: input.data().data_type().to_string(),
+                step: "compute_field".to_string(),
+            })?
+            .clone();
+        let records = match &mut value {
+            Value::Array(records) => records.iter_mut().collect(),
+            record => vec![record],
+        };
+
+        for (index, record) in records.into_iter().enumerate() {
+            let Value::Object(_) = record else {
+                continue;
+            };
+            for (name, expr) in &fields {
+                let computed = match expr.eval(record) {
+                    Ok(computed) => computed,
+                    Err(e) if strict => {
+                        return Err(OxiError::ExecutionError(format!(
+                            "compute_field '{name}' failed on record {index}: {e}"
+                        )))
+                    }
+                    Err(_) => Value::Null,
+                };
+                if let Value::Object(object) = record {
+                    object.insert(name.clone(), computed);
+                }
+            }
+        }
+
+        let schema = if input.schema().fields.is_empty() {
+            OxiSchema::infer_from_data(&Data::Json(value.clone()))?
+        } else {
+            self.output_schema(Some(input.schema()), config)
+                .map_err(|e| OxiError::ConfigError(e.to_string()))?
+        };
+        Ok(OxiData::with_schema(Data::Json(value), schema))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_numeric_product() {
+        let input = OxiData::from_json(json!([
+            {"price": 2.5, "qty": 4},
+            {"price": 3, "qty": 2},
+            {"price": 1, "qty": null}
+        ]));
+
+        let output = ComputeField
+            .process(
+                input,
+                &OxiConfig::from_yaml_str(
+                    "fields:\n  - {field: total, expression: \"price * qty\"}",
+                ),
+            )
+            .await
+            .unwrap();
+
+        let totals: Vec<&Value> = output
+            .data()
+            .as_json()
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|record| &record["total"])
+            .collect();
+        assert_eq!(totals, [&json!(10.0), &json!(6), &Value::Null]);
+    }
+
+    #[tokio::test]
+    async fn test_string_concatenation_and_chained_fields() {
+        let input = OxiData::from_json(json!([{"first": "Ada", "last": "Lovelace", "born": 1815}]));
+        let yaml = r#"
+fields:
+  - field: name
+    expression: first + " " + last
+  - field: label
+    expression: "name + ' (' + born + ')'"
+"#;
+
+        let output = ComputeField
+            .process(input, &OxiConfig::from_yaml_str(yaml))
+            .await
+            .unwrap();
+
+        let record = &output.data().as_json().unwrap()[0];
+        assert_eq!(record["name"], "Ada Lovelace");
+        assert_eq!(record["label"], "Ada Lovelace (1815)");
+        assert_eq!(
+            output.schema().fields["label"].field_type,
+            FieldType::String
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_fields_and_division_by_zero() {
+        let input = || OxiData::from_json(json!([{"a": 1, "b": 0}]));
+        let yaml =
+            "fields:\n  - {field: ratio, expression: a / b}\n  - {field: sum, expression: a + c}\n";
+
+        let output = ComputeField
+            .process(input(), &OxiConfig::from_yaml_str(yaml))
+            .await
+            .unwrap();
+        assert_eq!(
+            output.data().as_json().unwrap(),
+            &json!([{"a": 1, "b": 0, "ratio": null, "sum": null}])
+        );
+
+        let err = ComputeField
+            .process(
+                input(),
+                &OxiConfig::from_yaml_str(&format!("{yaml}strict: true")),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("'ratio' failed on record 0: division by zero"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_parse_and_infer() {
+        let mut schema = OxiSchema::empty();
+        schema.add_field("qty".to_string(), FieldSchema::new(FieldType::Integer));
+        schema.add_field("price".to_string(), FieldSchema::new(FieldType::Float));
+        let infer = |text: &str| Expr::parse(text).unwrap().infer_type(&schema);
+
+        assert_eq!(infer("qty * 2 - 1"), FieldType::Integer);
+        assert_eq!(infer("price * qty"), FieldType::Float);
+        assert_eq!(infer("qty / 2"), FieldType::Float);
+        assert_eq!(infer("'#' + qty"), FieldType::String);
+        assert_eq!(infer("missing * 2"), FieldType::Unknown);
+
+        let record = json!({"qty": 3, "odd name": 1, "line": {"price": 2}});
+        let eval = |text: &str| Expr::parse(text).unwrap().eval(&record).unwrap();
+        assert_eq!(eval("-(qty + 1) * 2 % 5"), json!(-3));
+        assert_eq!(eval("line.price * qty"), json!(6));
+        assert_eq!(eval("7 / 2"), json!(3.5));
+        assert_eq!(eval("`odd name` + 1"), json!(2));
+
+        assert!(Expr::parse("qty *").is_err());
+        assert!(Expr::parse("(qty + 1").is_err());
+        assert!(Expr::parse("qty qty").is_err());
+        assert!(Expr::parse("'open").is_err());
+    }
+}
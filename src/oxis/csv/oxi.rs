@@ -11,6 +11,10 @@ impl Oxi for ParseCsv {
         "parse_csv"
     }
 
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Json]
+    }
+
     fn config_schema(&self) -> serde_yaml::Value {
         serde_yaml::from_str(
             r#"
@@ -138,6 +142,10 @@ impl Oxi for FormatCsv {
         "format_csv"
     }
 
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Text]
+    }
+
     fn config_schema(&self) -> serde_yaml::Value {
         serde_yaml::from_str(
             r#"
@@ -162,6 +170,11 @@ impl Oxi for FormatCsv {
         }
     }
 
+    // One CSV document with a single header row
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
     async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
         // Get JSON array from input
         let json_array = input
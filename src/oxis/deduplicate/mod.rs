@@ -0,0 +1,3 @@
+pub mod oxi;
+
+pub use oxi::Deduplicate;
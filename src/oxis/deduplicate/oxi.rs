@@ -0,0 +1,161 @@
+use crate::oxis::prelude::*;
+use crate::types::{json_hash, OxiSchema};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Deduplicate drops records whose content hash was already seen in the batch
+///
+/// Records are compared by [`OxiData::compute_hash`]: the whole record, or
+/// only the configured `fields`. The first occurrence is kept and the output
+/// keeps the input order.
+pub struct Deduplicate;
+
+/// Options accepted by [`Deduplicate`]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DeduplicateConfig {
+    /// Top-level fields that identify a record; empty means the whole record
+    fields: Vec<String>,
+}
+
+#[async_trait]
+impl Oxi for Deduplicate {
+    fn name(&self) -> &str {
+        "deduplicate"
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              fields:
+                type: array
+                items:
+                  type: string
+                description: "Fields that identify a record; records agreeing on all of them are duplicates. Omit to compare whole records"
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        ProcessingLimits {
+            supported_input_types: vec![OxiDataType::Json],
+            ..ProcessingLimits::default()
+        }
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Passthrough
+    }
+
+    // Duplicates in different chunks would otherwise both survive
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    fn output_schema(
+        &self,
+        input_schema: Option<&OxiSchema>,
+        _config: &OxiConfig,
+    ) -> anyhow::Result<OxiSchema> {
+        Ok(input_schema.cloned().unwrap_or_else(OxiSchema::empty))
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let options: DeduplicateConfig = config
+            .deserialize_into()
+            .map_err(|e| OxiError::ConfigError(e.to_string()))?;
+        let fields: Vec<&str> = options.fields.iter().map(String::as_str).collect();
+        let fields = (!fields.is_empty()).then_some(fields.as_slice());
+
+        let records = match input.data().as_json() {
+            Ok(Value::Array(records)) => records,
+            // A single record has nothing to duplicate
+            Ok(Value::Object(_)) => return Ok(input),
+            _ => {
+                return Err(OxiError::TypeMismatch {
+                    expected: "JSON array of records".to_string(),
+                    actual: input.data().data_type().to_string(),
+                    step: "deduplicate".to_string(),
+                })
+            }
+        };
+
+        let mut seen = HashSet::with_capacity(records.len());
+        let unique: Vec<Value> = records
+            .iter()
+            .filter(|record| seen.insert(json_hash(record, fields)))
+            .cloned()
+            .collect();
+        let removed = records.len() - unique.len();
+
+        let mut output = OxiData::with_schema(Data::Json(Value::Array(unique)), input.schema);
+        output.set_meta("duplicates_removed", removed);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn events() -> OxiData {
+        OxiData::from_json(json!([
+            {"id": 1, "user": "ada", "amount": 10.0},
+            {"amount": 10, "user": "ada", "id": 1},
+            {"id": 2, "user": "ada", "amount": 12.5},
+            {"id": 3, "user": "grace", "amount": 10}
+        ]))
+    }
+
+    fn ids(output: &OxiData) -> Vec<i64> {
+        output
+            .data()
+            .as_json()
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|record| record["id"].as_i64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_whole_record_duplicates_ignore_key_order_and_float_form() {
+        let output = Deduplicate
+            .process(events(), &OxiConfig::default())
+            .await
+            .unwrap();
+
+        assert_eq!(ids(&output), [1, 2, 3]);
+        assert_eq!(output.get_meta("duplicates_removed"), Some(&json!(1)));
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_by_fields() {
+        let config = OxiConfig::builder()
+            .set("fields", vec!["user".to_string()])
+            .build();
+
+        let output = Deduplicate.process(events(), &config).await.unwrap();
+
+        assert_eq!(ids(&output), [1, 3]);
+        assert_eq!(output.get_meta("duplicates_removed"), Some(&json!(2)));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_text() {
+        let err = Deduplicate
+            .process(
+                OxiData::from_text("a\na".to_string()),
+                &OxiConfig::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("JSON array"), "{err}");
+    }
+}
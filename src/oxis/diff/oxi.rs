@@ -0,0 +1,168 @@
+use crate::oxis::prelude::*;
+use crate::types::{DataDiff, OxiSchema};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+/// DiffOxi reports what changed between an earlier payload and the step input
+///
+/// The earlier payload is `before`, usually a `${step_id}` reference to the
+/// step whose output the input should be compared with. The output is the
+/// [`DataDiff`] as JSON, so later steps can filter or write it.
+pub struct DiffOxi;
+
+/// Options accepted by [`DiffOxi`]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DiffConfig {
+    /// The earlier payload, as JSON text, plain text or inline YAML
+    before: Option<serde_yaml::Value>,
+    /// Field matching records of JSON arrays; records are matched by position without it
+    key: Option<String>,
+}
+
+/// A configured payload: referenced step outputs arrive as text, JSON or not
+fn before_payload(value: serde_yaml::Value) -> Result<OxiData, OxiError> {
+    match value {
+        serde_yaml::Value::String(text) => Ok(match serde_json::from_str(&text) {
+            Ok(json) => OxiData::from_json(json),
+            Err(_) => OxiData::from_text(text),
+        }),
+        serde_yaml::Value::Null => Ok(OxiData::empty()),
+        other => serde_json::to_value(other)
+            .map(OxiData::from_json)
+            .map_err(|e| {
+                OxiError::ConfigError(format!("diff 'before' is not JSON-compatible: {e}"))
+            }),
+    }
+}
+
+/// The diff as the JSON the step outputs
+fn diff_json(diff: &DataDiff) -> Value {
+    let modified: Vec<Value> = diff
+        .modified_fields
+        .iter()
+        .map(|(path, before, after)| json!({"path": path, "before": before, "after": after}))
+        .collect();
+    json!({
+        "summary": diff.summary_line(),
+        "added_fields": diff.added_fields,
+        "removed_fields": diff.removed_fields,
+        "modified_fields": modified,
+        "record_count_delta": diff.record_count_delta,
+    })
+}
+
+#[async_trait]
+impl Oxi for DiffOxi {
+    fn name(&self) -> &str {
+        "diff"
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              before:
+                description: "Payload the step input is compared with, typically a \"${step_id}\" reference to an earlier step"
+              key:
+                type: string
+                description: "Field identifying records of JSON arrays; records are matched by position when omitted"
+            required: ["before"]
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Modify {
+            description: "Replaces the data with a diff report".to_string(),
+        }
+    }
+
+    // A diff of each chunk would only cover part of the payload
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    // A misspelled `key` would silently fall back to matching records by position
+    fn strict_config_validation(&self) -> bool {
+        true
+    }
+
+    fn output_schema(
+        &self,
+        _input_schema: Option<&OxiSchema>,
+        _config: &OxiConfig,
+    ) -> anyhow::Result<OxiSchema> {
+        Ok(OxiSchema::infer_from_data(&Data::Json(diff_json(
+            &DataDiff::default(),
+        )))?)
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let options: DiffConfig = config
+            .deserialize_into()
+            .map_err(|e| OxiError::ConfigError(e.to_string()))?;
+        let before = options
+            .before
+            .ok_or_else(|| OxiError::ConfigError("diff requires 'before'".to_string()))
+            .and_then(before_payload)?;
+
+        let diff = OxiData::diff_by_key(&before, &input, options.key.as_deref());
+        let mut output = OxiData::from_json(diff_json(&diff));
+        output.set_meta("diff_empty", diff.is_empty());
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_diff_against_a_referenced_payload_by_key() {
+        let before = json!([
+            {"id": 1, "name": "Ada", "plan": "free"},
+            {"id": 2, "name": "Grace", "plan": "pro"}
+        ])
+        .to_string();
+        let input = OxiData::from_json(json!([
+            {"id": 2, "name": "Grace", "plan": "team", "seats": 5},
+            {"id": 3, "name": "Linus", "plan": "free"}
+        ]));
+
+        let mut config = OxiConfig::from_yaml_str("key: id");
+        config.values.insert("before".to_string(), before.into());
+        let output = DiffOxi.process(input, &config).await.unwrap();
+
+        assert_eq!(
+            output.data().as_json().unwrap(),
+            &json!({
+                "summary": "2 added, 1 removed, 1 modified, +0 records",
+                "added_fields": ["[id=2].seats", "[id=3]"],
+                "removed_fields": ["[id=1]"],
+                "modified_fields": [{"path": "[id=2].plan", "before": "pro", "after": "team"}],
+                "record_count_delta": 0
+            })
+        );
+        assert_eq!(output.get_meta("diff_empty"), Some(&json!(false)));
+    }
+
+    #[tokio::test]
+    async fn test_diff_of_identical_text_is_empty() {
+        let output = DiffOxi
+            .process(
+                OxiData::from_text("hello".to_string()),
+                &OxiConfig::from_yaml_str("before: hello"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output.get_meta("diff_empty"), Some(&json!(true)));
+        assert!(DiffOxi
+            .process(OxiData::empty(), &OxiConfig::default())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,331 @@
+use crate::oxis::prelude::*;
+use crate::types::{FieldSchema, FieldType, OxiSchema};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// ExplodeOxi turns each element of an array field into a record of its own
+///
+/// Every output record carries the parent's other fields. Object elements are
+/// spread into it as `<prefix><key>` fields (`items.sku` by default); any
+/// other element is stored under the field's own name. A value that isn't an
+/// array counts as a single element, and a missing or `null` field as an
+/// empty array.
+pub struct ExplodeOxi;
+
+/// Options accepted by [`ExplodeOxi`]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ExplodeConfig {
+    /// Array-valued field to explode
+    field: Option<String>,
+    /// Prefix of the fields an object element is spread into; `<field>.` if unset
+    prefix: Option<String>,
+    /// What happens to records whose array is empty or missing
+    empty: EmptyMode,
+}
+
+impl ExplodeConfig {
+    fn parse(config: &OxiConfig) -> Result<(String, String, EmptyMode), OxiError> {
+        let options: ExplodeConfig = config
+            .deserialize_into()
+            .map_err(|e| OxiError::ConfigError(e.to_string()))?;
+        let field = options
+            .field
+            .filter(|field| !field.is_empty())
+            .ok_or_else(|| OxiError::ConfigError("explode requires 'field'".to_string()))?;
+        let prefix = options.prefix.unwrap_or_else(|| format!("{field}."));
+        Ok((field, prefix, options.empty))
+    }
+}
+
+/// Handling of records with nothing to explode
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum EmptyMode {
+    /// The record is left out of the output
+    #[default]
+    Drop,
+    /// The record is kept once, with the element fields set to `null`
+    Keep,
+}
+
+#[async_trait]
+impl Oxi for ExplodeOxi {
+    fn name(&self) -> &str {
+        "explode"
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              field:
+                type: string
+                required: true
+                description: "Array-valued field to explode into one record per element"
+              prefix:
+                type: string
+                description: "Prefix of the fields object elements are spread into (default: \"<field>.\")"
+              empty:
+                type: string
+                enum: ["drop", "keep"]
+                default: "drop"
+                description: "Records with an empty or missing array: drop them, or keep them once with null element fields"
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        ProcessingLimits {
+            supported_input_types: vec![OxiDataType::Json],
+            ..ProcessingLimits::default()
+        }
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Modify {
+            description: "Replaces the array field with the fields of its elements".to_string(),
+        }
+    }
+
+    fn output_schema(
+        &self,
+        input_schema: Option<&OxiSchema>,
+        config: &OxiConfig,
+    ) -> anyhow::Result<OxiSchema> {
+        let (field, prefix, empty) = ExplodeConfig::parse(config)?;
+        let mut schema = input_schema.cloned().unwrap_or_else(OxiSchema::empty);
+        let Some(exploded) = schema.fields.remove(&field) else {
+            return Ok(schema);
+        };
+        let keep_nulls = empty == EmptyMode::Keep || exploded.nullable;
+
+        let element = match exploded.field_type {
+            FieldType::Array(element) => *element,
+            other => other,
+        };
+        match element {
+            FieldType::Object(fields) => {
+                for (key, mut element_field) in fields {
+                    element_field.nullable |= keep_nulls;
+                    schema.add_field(format!("{prefix}{key}"), element_field);
+                }
+            }
+            element => {
+                let mut element_field = FieldSchema::new(element);
+                element_field.nullable = keep_nulls;
+                schema.add_field(field, element_field);
+            }
+        }
+        Ok(schema)
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let (field, prefix, empty) = ExplodeConfig::parse(config)?;
+
+        let records = match input.data().as_json() {
+            Ok(Value::Array(records)) => records.as_slice(),
+            Ok(record @ Value::Object(_)) => std::slice::from_ref(record),
+            _ => {
+                return Err(OxiError::TypeMismatch {
+                    expected: "JSON array of records".to_string(),
+                    actual: input.data().data_type().to_string(),
+                    step: "explode".to_string(),
+                })
+            }
+        };
+
+        // Kept records get a null for every field an object element filled in
+        let mut element_keys: Vec<String> = Vec::new();
+        for record in records {
+            for element in elements(record.get(&field)) {
+                if let Value::Object(element) = element {
+                    for key in element.keys() {
+                        let key = format!("{prefix}{key}");
+                        if !element_keys.contains(&key) {
+                            element_keys.push(key);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut exploded = Vec::with_capacity(records.len());
+        let mut dropped = 0;
+        for (index, record) in records.iter().enumerate() {
+            let Value::Object(record) = record else {
+                return Err(OxiError::ValidationError {
+                    details: format!("explode: record {index} is not an object"),
+                });
+            };
+            let elements = elements(record.get(&field));
+            let mut parent = record.clone();
+            parent.remove(&field);
+
+            if elements.is_empty() {
+                if empty == EmptyMode::Drop {
+                    dropped += 1;
+                    continue;
+                }
+                if element_keys.is_empty() {
+                    parent.insert(field.clone(), Value::Null);
+                }
+                for key in &element_keys {
+                    parent.insert(key.clone(), Value::Null);
+                }
+                exploded.push(Value::Object(parent));
+                continue;
+            }
+
+            for element in elements {
+                let mut output = parent.clone();
+                match element {
+                    Value::Object(element) => {
+                        for (key, value) in element {
+                            output.insert(format!("{prefix}{key}"), value.clone());
+                        }
+                    }
+                    element => {
+                        output.insert(field.clone(), element.clone());
+                    }
+                }
+                exploded.push(Value::Object(output));
+            }
+        }
+
+        let mut output = OxiData::from_json(Value::Array(exploded));
+        output.set_meta("records_dropped", dropped);
+        Ok(output)
+    }
+}
+
+/// The elements to explode a field value into
+fn elements(value: Option<&Value>) -> &[Value] {
+    match value {
+        None | Some(Value::Null) => &[],
+        Some(Value::Array(items)) => items,
+        Some(other) => std::slice::from_ref(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn orders() -> OxiData {
+        OxiData::from_json(json!([
+            {
+                "order_id": 1,
+                "customer": "ada",
+                "items": [
+                    {"sku": "A-1", "qty": 2},
+                    {"sku": "B-7", "qty": 1}
+                ]
+            },
+            {"order_id": 2, "customer": "grace", "items": [{"sku": "C-3", "qty": 5}]},
+            {"order_id": 3, "customer": "linus", "items": []},
+            {"order_id": 4, "customer": "barbara"}
+        ]))
+    }
+
+    fn config(field: &str) -> OxiConfig {
+        OxiConfig::builder().set("field", field).build()
+    }
+
+    #[tokio::test]
+    async fn test_explode_line_items() {
+        let output = ExplodeOxi
+            .process(orders(), &config("items"))
+            .await
+            .unwrap();
+
+        let records = output.data().as_json().unwrap().as_array().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[0],
+            json!({"order_id": 1, "customer": "ada", "items.sku": "A-1", "items.qty": 2})
+        );
+        assert_eq!(records[1]["items.sku"], "B-7");
+        assert_eq!(records[1]["customer"], "ada");
+        assert_eq!(records[2]["order_id"], 2);
+        assert!(records.iter().all(|record| record.get("items").is_none()));
+        assert_eq!(output.get_meta("records_dropped"), Some(&json!(2)));
+
+        let schema = output.schema();
+        assert!(schema.fields.contains_key("items.sku"));
+        assert!(!schema.fields.contains_key("items"));
+    }
+
+    #[tokio::test]
+    async fn test_explode_keeps_empty_arrays_with_nulls() {
+        let config = OxiConfig::builder()
+            .set("field", "items")
+            .set("prefix", "item_")
+            .set("empty", "keep")
+            .build();
+
+        let output = ExplodeOxi.process(orders(), &config).await.unwrap();
+
+        let records = output.data().as_json().unwrap().as_array().unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!(
+            records[3],
+            json!({"order_id": 3, "customer": "linus", "item_sku": null, "item_qty": null})
+        );
+        assert_eq!(records[4]["order_id"], 4);
+        assert_eq!(records[4]["item_sku"], Value::Null);
+        assert_eq!(output.get_meta("records_dropped"), Some(&json!(0)));
+    }
+
+    #[tokio::test]
+    async fn test_explode_scalar_elements() {
+        let input = OxiData::from_json(json!([
+            {"id": 1, "tags": ["a", "b"]},
+            {"id": 2, "tags": "c"},
+            {"id": 3, "tags": []}
+        ]));
+        let config = OxiConfig::builder()
+            .set("field", "tags")
+            .set("empty", "keep")
+            .build();
+
+        let output = ExplodeOxi.process(input, &config).await.unwrap();
+
+        assert_eq!(
+            output.data().as_json().unwrap(),
+            &json!([
+                {"id": 1, "tags": "a"},
+                {"id": 1, "tags": "b"},
+                {"id": 2, "tags": "c"},
+                {"id": 3, "tags": null}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_explode_output_schema_merges_element_fields() {
+        let input_schema = OxiSchema::infer_from_data(orders().data()).unwrap();
+
+        let schema = ExplodeOxi
+            .output_schema(Some(&input_schema), &config("items"))
+            .unwrap();
+
+        assert!(schema.fields.contains_key("order_id"));
+        assert!(schema.fields.contains_key("customer"));
+        assert!(!schema.fields.contains_key("items"));
+        assert_eq!(schema.fields["items.qty"].field_type, FieldType::Integer);
+        assert_eq!(schema.fields["items.sku"].field_type, FieldType::String);
+    }
+
+    #[tokio::test]
+    async fn test_explode_requires_field() {
+        let err = ExplodeOxi
+            .process(orders(), &OxiConfig::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("field"), "{err}");
+    }
+}
@@ -1,9 +1,14 @@
 use crate::oxis::prelude::*;
+use crate::types::DataFormat;
 use async_trait::async_trait;
 use std::fs;
 use std::path::Path;
 
 /// ReadFile reads content from a file
+///
+/// The payload type follows the file's contents: JSON and NDJSON are parsed,
+/// UTF-8 text is kept as text and anything else is binary. A `format` other
+/// than `auto` skips detection.
 pub struct ReadFile;
 
 #[async_trait]
@@ -12,6 +17,10 @@ impl Oxi for ReadFile {
         "read_file"
     }
 
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Json, OxiDataType::Text, OxiDataType::Binary]
+    }
+
     fn config_schema(&self) -> serde_yaml::Value {
         serde_yaml::from_str(
             r#"
@@ -25,6 +34,11 @@ impl Oxi for ReadFile {
                 type: string
                 description: "File encoding (utf-8, etc.)"
                 default: "utf-8"
+              format:
+                type: string
+                description: "Content type; auto detects it from the extension and leading bytes"
+                enum: ["auto", "json", "jsonl", "text", "binary"]
+                default: "auto"
         "#,
         )
         .unwrap()
@@ -34,6 +48,19 @@ impl Oxi for ReadFile {
         SchemaStrategy::Infer
     }
 
+    // A source ignores its input; splitting would read the file once per chunk
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    fn watched_paths(&self, config: &OxiConfig) -> Vec<std::path::PathBuf> {
+        config
+            .get_string("path")
+            .map(Into::into)
+            .into_iter()
+            .collect()
+    }
+
     async fn process(&self, _input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
         // Get file path from config
         let path = config
@@ -41,30 +68,30 @@ impl Oxi for ReadFile {
             .map_err(|e| OxiError::ValidationError {
                 details: format!("Missing required 'path' config: {e}"),
             })?;
+        let format_name = config.get_string_or("format", "auto");
+        let format = match format_name.as_str() {
+            "auto" => None,
+            name => Some(DataFormat::from_name(name).ok_or_else(|| {
+                OxiError::ConfigError(format!(
+                    "Unknown format '{name}' (expected auto, json, jsonl, text or binary)"
+                ))
+            })?),
+        };
 
-        // Check if file exists
-        if !Path::new(&path).exists() {
-            return Err(OxiError::ValidationError {
-                details: format!("File not found: {path}"),
-            });
-        }
-
-        // Read file content
-        let content = fs::read_to_string(&path).map_err(|e| OxiError::ValidationError {
-            details: format!("Failed to read file '{path}': {e}"),
-        })?;
+        let bytes = fs::read(&path)
+            .map_err(|e| OxiError::io(format!("Failed to read file '{path}'"), &e))?;
+        let format = format.unwrap_or_else(|| DataFormat::detect(Path::new(&path), &bytes));
 
-        // Create JSON output with content and metadata
-        let result = serde_json::json!({
-            "content": content,
-            "metadata": {
-                "path": path,
-                "size": content.len(),
-                "type": "text"
+        let bytes_read = bytes.len();
+        let mut output = OxiData::from_reader(bytes.as_slice(), format).map_err(|e| {
+            OxiError::ValidationError {
+                details: format!("Failed to read '{path}' as {format:?}: {e}"),
             }
-        });
-
-        Ok(OxiData::from_json(result))
+        })?;
+        output.set_meta("source_path", path);
+        output.set_meta("bytes_read", bytes_read);
+        output.set_meta("content_type", format!("{format:?}").to_lowercase());
+        Ok(output)
     }
 }
 
@@ -94,6 +121,10 @@ impl Oxi for WriteFile {
                 type: boolean
                 description: "Append to file instead of overwriting"
                 default: false
+              pretty:
+                type: boolean
+                description: "Write JSON data indented rather than compact"
+                default: false
         "#,
         )
         .unwrap()
@@ -103,6 +134,11 @@ impl Oxi for WriteFile {
         SchemaStrategy::Passthrough
     }
 
+    // Each chunk would overwrite the previous one
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
     async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
         // Get file path from config
         let path = config
@@ -112,12 +148,13 @@ impl Oxi for WriteFile {
             })?;
         let create_dirs = config.get_bool_or("create_dirs", true);
         let append = config.get_bool_or("append", false);
+        let pretty = config.get_bool_or("pretty", false);
 
         // Create parent directories if needed
         if create_dirs {
             if let Some(parent) = Path::new(&path).parent() {
-                fs::create_dir_all(parent).map_err(|e| OxiError::ValidationError {
-                    details: format!("Failed to create directories for '{path}': {e}"),
+                fs::create_dir_all(parent).map_err(|e| {
+                    OxiError::io(format!("Failed to create directories for '{path}'"), &e)
                 })?;
             }
         }
@@ -125,20 +162,18 @@ impl Oxi for WriteFile {
         // Convert input to text
         let content = input
             .data()
-            .to_text()
+            .to_text_with(pretty)
             .map_err(|e| OxiError::ValidationError {
                 details: format!("Failed to convert input to text: {e}"),
             })?;
 
         // Write to file
         if append {
-            fs::write(&path, content).map_err(|e| OxiError::ValidationError {
-                details: format!("Failed to append to file '{path}': {e}"),
-            })?;
+            fs::write(&path, content)
+                .map_err(|e| OxiError::io(format!("Failed to append to file '{path}'"), &e))?;
         } else {
-            fs::write(&path, content).map_err(|e| OxiError::ValidationError {
-                details: format!("Failed to write to file '{path}': {e}"),
-            })?;
+            fs::write(&path, content)
+                .map_err(|e| OxiError::io(format!("Failed to write to file '{path}'"), &e))?;
         }
 
         // Return the input unchanged for potential chaining (passthrough schema strategy)
@@ -172,9 +207,109 @@ mod tests {
 
         let result = oxi.process(OxiData::empty(), &config).await.unwrap();
 
-        // ReadFile returns JSON with content and metadata
-        let json_result = result.data.as_json().unwrap();
-        assert_eq!(json_result["content"].as_str().unwrap(), content);
+        // A .txt file is read as text
+        assert_eq!(result.data.as_text().unwrap(), content);
+        assert_eq!(result.get_meta("content_type").unwrap(), "text");
+        assert_eq!(
+            result.get_meta("source_path").unwrap(),
+            file_path.to_string_lossy().as_ref()
+        );
+        assert_eq!(result.get_meta("bytes_read").unwrap(), content.len());
+    }
+
+    fn read_config(path: &Path, format: Option<&str>) -> OxiConfig {
+        let mut config = OxiConfig::default();
+        config.values.insert(
+            "path".to_string(),
+            serde_yaml::Value::String(path.to_string_lossy().to_string()),
+        );
+        if let Some(format) = format {
+            config.values.insert(
+                "format".to_string(),
+                serde_yaml::Value::String(format.to_string()),
+            );
+        }
+        config
+    }
+
+    #[tokio::test]
+    async fn test_read_file_detects_content_type() {
+        let dir = tempdir().unwrap();
+        let write = |name: &str, bytes: &[u8]| {
+            let path = dir.path().join(name);
+            fs::write(&path, bytes).unwrap();
+            path
+        };
+
+        let json = ReadFile
+            .process(
+                OxiData::empty(),
+                &read_config(&write("orders.json", br#"[{"id": 1, "name": "a"}]"#), None),
+            )
+            .await
+            .unwrap();
+        assert_eq!(json.data.as_json().unwrap()[0]["id"], 1);
+        assert!(json.schema.fields.contains_key("name"));
+        assert_eq!(json.get_meta("content_type").unwrap(), "json");
+
+        let csv = ReadFile
+            .process(
+                OxiData::empty(),
+                &read_config(&write("orders.csv", b"id,name\n1,a\n"), None),
+            )
+            .await
+            .unwrap();
+        assert_eq!(csv.data.as_text().unwrap(), "id,name\n1,a\n");
+        assert!(csv.schema.fields.contains_key("value"));
+
+        // Without a known extension the leading bytes decide
+        let sniffed = ReadFile
+            .process(
+                OxiData::empty(),
+                &read_config(&write("export", br#"  {"ok": true}"#), None),
+            )
+            .await
+            .unwrap();
+        assert_eq!(sniffed.data.as_json().unwrap()["ok"], true);
+
+        let blob = ReadFile
+            .process(
+                OxiData::empty(),
+                &read_config(&write("blob.dat", &[0x89, 0x00, 0xff, 0x10]), None),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            blob.data.as_binary().unwrap(),
+            &vec![0x89, 0x00, 0xff, 0x10]
+        );
+        assert!(blob.schema.fields.contains_key("data"));
+        assert_eq!(blob.get_meta("content_type").unwrap(), "binary");
+    }
+
+    #[tokio::test]
+    async fn test_read_file_format_override() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("orders.json");
+        fs::write(&path, br#"{"id": 1}"#).unwrap();
+
+        let text = ReadFile
+            .process(OxiData::empty(), &read_config(&path, Some("text")))
+            .await
+            .unwrap();
+        assert_eq!(text.data.as_text().unwrap(), r#"{"id": 1}"#);
+
+        let binary = ReadFile
+            .process(OxiData::empty(), &read_config(&path, Some("binary")))
+            .await
+            .unwrap();
+        assert_eq!(binary.data.data_type(), "Binary");
+
+        let err = ReadFile
+            .process(OxiData::empty(), &read_config(&path, Some("yaml")))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown format 'yaml'"));
     }
 
     #[tokio::test]
@@ -201,4 +336,27 @@ mod tests {
         // Verify input was passed through
         assert_eq!(result.data.as_text().unwrap(), content);
     }
+
+    #[tokio::test]
+    async fn test_write_file_json_compact_unless_pretty() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("output.json");
+        let path = file_path.to_string_lossy().to_string();
+        let input = || OxiData::from_json(serde_json::json!([{"id": 1, "name": "a"}]));
+
+        let config = OxiConfig::builder().set("path", &path).build();
+        WriteFile.process(input(), &config).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            r#"[{"id":1,"name":"a"}]"#
+        );
+
+        let config = OxiConfig::builder()
+            .set("path", &path)
+            .set("pretty", true)
+            .build();
+        WriteFile.process(input(), &config).await.unwrap();
+        let written = fs::read_to_string(&file_path).unwrap();
+        assert!(written.contains("\n    \"id\": 1"), "{written}");
+    }
 }
@@ -1,27 +1,67 @@
 use crate::oxis::prelude::*;
 use async_trait::async_trait;
+use serde::Deserialize;
 
 /// Flatten transforms nested structured data into a flattened format
 pub struct Flatten;
 
+/// Options accepted by [`Flatten`]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FlattenConfig {
+    separator: Option<String>,
+    /// Older name for `separator`
+    delimiter: Option<String>,
+    array_mode: ArrayMode,
+}
+
+impl FlattenConfig {
+    fn separator(&self) -> &str {
+        self.separator
+            .as_deref()
+            .or(self.delimiter.as_deref())
+            .unwrap_or("_")
+    }
+}
+
+/// How arrays are represented in the flattened output
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ArrayMode {
+    /// `key[0]`, `key[1]`, ... (reversible with unflatten)
+    Index,
+    /// Items joined with commas into a single string
+    #[default]
+    Explode,
+    /// Arrays are dropped
+    Ignore,
+}
+
 #[async_trait]
 impl Oxi for Flatten {
     fn name(&self) -> &str {
         "flatten"
     }
 
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Json]
+    }
+
     fn config_schema(&self) -> serde_yaml::Value {
         serde_yaml::from_str(r#"
             type: object
             properties:
-              delimiter:
+              separator:
                 type: string
-                description: "Delimiter to use when flattening nested keys"
+                description: "Separator to use when joining nested keys"
                 default: "_"
+              delimiter:
+                type: string
+                description: "Deprecated alias for separator"
               array_mode:
                 type: string
                 enum: ["index", "explode", "ignore"]
-                description: "How to handle arrays (index: include indices, explode: create row per item, ignore: skip arrays)"
+                description: "How to handle arrays (index: key[0], key[1], ..., explode: join items with commas, ignore: skip arrays)"
                 default: "explode"
         "#).unwrap()
     }
@@ -33,9 +73,11 @@ impl Oxi for Flatten {
     }
 
     async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
-        // Get configuration
-        let delimiter = config.get_string_or("delimiter", "_");
-        let array_mode = config.get_string_or("array_mode", "explode");
+        let options: FlattenConfig = config
+            .deserialize_into()
+            .map_err(|e| OxiError::ConfigError(e.to_string()))?;
+        let delimiter = options.separator();
+        let array_mode = options.array_mode;
 
         // Get JSON data from input
         let value = input
@@ -47,12 +89,16 @@ impl Oxi for Flatten {
                 step: "flatten".to_string(),
             })?;
 
+        if array_mode == ArrayMode::Index {
+            return Ok(input.flatten(delimiter));
+        }
+
         // Flatten the structure
         let flattened_result = if let serde_json::Value::Array(array) = &value {
             // Process array of objects
             let mut flattened_objects = Vec::new();
             for item in array {
-                let flattened = flatten_json_value(item, &delimiter, &array_mode).map_err(|e| {
+                let flattened = flatten_json_value(item, delimiter, array_mode).map_err(|e| {
                     OxiError::ValidationError {
                         details: format!("Failed to flatten array item: {e}"),
                     }
@@ -62,7 +108,7 @@ impl Oxi for Flatten {
             serde_json::Value::Array(flattened_objects)
         } else {
             // Process single object
-            flatten_json_value(value, &delimiter, &array_mode).map_err(|e| {
+            flatten_json_value(value, delimiter, array_mode).map_err(|e| {
                 OxiError::ValidationError {
                     details: format!("Failed to flatten object: {e}"),
                 }
@@ -78,7 +124,7 @@ impl Oxi for Flatten {
 fn flatten_json_value(
     value: &serde_json::Value,
     delimiter: &str,
-    array_mode: &str,
+    array_mode: ArrayMode,
 ) -> anyhow::Result<serde_json::Value> {
     let mut result = serde_json::Map::new();
     flatten_json_recursive(value, "", delimiter, array_mode, &mut result)?;
@@ -90,7 +136,7 @@ fn flatten_json_recursive(
     value: &serde_json::Value,
     prefix: &str,
     delimiter: &str,
-    array_mode: &str,
+    array_mode: ArrayMode,
     result: &mut serde_json::Map<String, serde_json::Value>,
 ) -> anyhow::Result<()> {
     match value {
@@ -106,12 +152,7 @@ fn flatten_json_recursive(
             }
         }
         serde_json::Value::Array(arr) => {
-            if array_mode == "index" {
-                for (i, item) in arr.iter().enumerate() {
-                    let new_prefix = format!("{prefix}{delimiter}{i}");
-                    flatten_json_recursive(item, &new_prefix, delimiter, array_mode, result)?;
-                }
-            } else if array_mode == "explode" {
+            if array_mode == ArrayMode::Explode {
                 // For explode mode, we join array values as a comma-separated string
                 let values: Vec<String> = arr
                     .iter()
@@ -183,4 +224,94 @@ mod tests {
             panic!("Expected JSON data");
         }
     }
+
+    #[tokio::test]
+    async fn test_flatten_indexes_arrays_and_round_trips() {
+        let oxi = Flatten;
+        let nested = serde_json::json!([
+            {"id": 1, "user": {"name": "Ann", "tags": ["admin", "ops"]}},
+            {"id": 2, "user": {"name": "Bo", "tags": []}}
+        ]);
+
+        let config = OxiConfig::builder()
+            .set("separator", ".")
+            .set("array_mode", "index")
+            .build();
+
+        let result = oxi
+            .process(OxiData::from_json(nested.clone()), &config)
+            .await
+            .unwrap();
+
+        let records = result.data().as_json().unwrap();
+        assert_eq!(records[0]["user.tags[0]"], "admin");
+        assert_eq!(records[0]["user.tags[1]"], "ops");
+        assert_eq!(records[1]["user.tags"], serde_json::json!([]));
+        assert!(result.schema().fields.contains_key("user.name"));
+
+        let restored = result.unflatten(".").unwrap();
+        assert_eq!(restored.data().as_json().unwrap(), &nested);
+    }
+
+    #[tokio::test]
+    async fn test_flatten_legacy_delimiter_and_explode() {
+        let oxi = Flatten;
+        let mut config = OxiConfig::default();
+        config
+            .values
+            .insert("delimiter".to_string(), serde_yaml::Value::from("_"));
+        config
+            .values
+            .insert("array_mode".to_string(), serde_yaml::Value::from("explode"));
+
+        let input = OxiData::from_json(serde_json::json!({"a": {"b": [1, 2]}}));
+        let result = oxi.process(input, &config).await.unwrap();
+
+        assert_eq!(result.data().as_json().unwrap()["a_b"], "1,2");
+    }
+
+    #[tokio::test]
+    async fn test_flatten_rejects_unknown_array_mode() {
+        let config = OxiConfig::builder().set("array_mode", "zip").build();
+        let input = OxiData::from_json(serde_json::json!({"a": [1]}));
+
+        let err = Flatten.process(input, &config).await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("zip"), "{message}");
+        assert!(message.contains("explode"), "{message}");
+    }
+
+    #[test]
+    fn test_unflatten_rejects_conflicting_keys() {
+        let flat = OxiData::from_json(serde_json::json!({"a": 1, "a.b": 2}));
+        assert!(flat.unflatten(".").is_err());
+
+        // An explicit null is a value too, not an empty slot to merge into
+        for conflicting in [
+            serde_json::json!({"a": null, "a.b": 2}),
+            serde_json::json!({"a[0]": null, "a[0].x": null}),
+        ] {
+            let flat = OxiData::from_json(conflicting.clone());
+            assert!(flat.unflatten(".").is_err(), "{conflicting}");
+        }
+
+        let sparse = OxiData::from_json(serde_json::json!({"a[1]": 1, "a[0]": null}));
+        assert_eq!(
+            sparse.unflatten(".").unwrap().data().as_json().unwrap(),
+            &serde_json::json!({"a": [null, 1]})
+        );
+    }
+
+    #[test]
+    fn test_flatten_keeps_scalar_array_items() {
+        let scalars = serde_json::json!([1, "two", null]);
+        let flat = OxiData::from_json(scalars.clone()).flatten(".");
+        assert_eq!(flat.data().as_json().unwrap(), &scalars);
+
+        let mixed = OxiData::from_json(serde_json::json!([{"a": {"b": 1}}, 2]));
+        assert_eq!(
+            mixed.flatten(".").data().as_json().unwrap(),
+            &serde_json::json!([{"a.b": 1}, 2])
+        );
+    }
 }
@@ -10,6 +10,10 @@ impl Oxi for FormatJson {
         "format_json"
     }
 
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Text]
+    }
+
     fn config_schema(&self) -> serde_yaml::Value {
         serde_yaml::from_str(
             r#"
@@ -18,7 +22,7 @@ impl Oxi for FormatJson {
               pretty:
                 type: boolean
                 description: "Whether to format JSON with indentation"
-                default: true
+                default: false
               indent:
                 type: integer
                 description: "Number of spaces for indentation"
@@ -32,6 +36,11 @@ impl Oxi for FormatJson {
         SchemaStrategy::Passthrough
     }
 
+    // Text output cannot be concatenated back into an array
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
     async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
         // Get JSON data from input
         let json_value = input
@@ -45,10 +54,11 @@ impl Oxi for FormatJson {
 
         // Get configuration
         let pretty = config.get_bool_or("pretty", false);
+        let indent = config.get_i64_or("indent", 2).max(0) as usize;
 
         // Format as JSON string
         let json_string = if pretty {
-            serde_json::to_string_pretty(json_value)
+            to_string_indented(json_value, indent)
         } else {
             serde_json::to_string(json_value)
         }
@@ -64,6 +74,17 @@ impl Oxi for FormatJson {
     }
 }
 
+/// Pretty-print `value` with `indent` spaces per level
+fn to_string_indented(value: &serde_json::Value, indent: usize) -> serde_json::Result<String> {
+    let indent = vec![b' '; indent];
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(&indent);
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    value.serialize(&mut serializer)?;
+    // serde_json only writes valid UTF-8
+    Ok(String::from_utf8(out).expect("serde_json output is UTF-8"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,4 +111,20 @@ mod tests {
             panic!("Expected text data");
         }
     }
+
+    #[tokio::test]
+    async fn test_format_json_pretty_indent() {
+        let config = OxiConfig::builder()
+            .set("pretty", true)
+            .set("indent", 4)
+            .build();
+        let input = OxiData::from_json(serde_json::json!({"name": "test"}));
+
+        let result = FormatJson.process(input, &config).await.unwrap();
+
+        assert_eq!(
+            result.data().as_text().unwrap(),
+            "{\n    \"name\": \"test\"\n}"
+        );
+    }
 }
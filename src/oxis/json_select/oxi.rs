@@ -11,6 +11,10 @@ impl Oxi for JsonSelect {
         "json_select"
     }
 
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Json, OxiDataType::Empty]
+    }
+
     fn schema_strategy(&self) -> SchemaStrategy {
         SchemaStrategy::Modify {
             description:
@@ -19,6 +23,11 @@ impl Oxi for JsonSelect {
         }
     }
 
+    // Paths such as `[0]` address the whole document
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
     fn config_schema(&self) -> serde_yaml::Value {
         serde_yaml::from_str(r#"
             type: object
@@ -1,10 +1,61 @@
 pub mod batch;
+pub mod coerce_types;
+pub mod compute_field;
 pub mod csv;
+pub mod deduplicate;
+pub mod diff;
+pub mod explode;
 pub mod file;
 pub mod flatten;
 pub mod format_json;
 pub mod json_select;
+#[cfg(feature = "parquet")]
+pub mod parquet;
 pub mod parse_json;
+pub mod partition;
+pub mod plugin;
 pub mod prelude;
+pub mod read_since;
 pub mod read_stdin;
+pub mod registry;
+pub mod template;
+pub mod throttle;
+pub mod union;
 pub mod write_stdout;
+
+use crate::Oxi;
+use std::sync::Arc;
+
+/// Names of the Oxis compiled into this binary
+pub const BUILTIN_OXIS: &[&str] = &[
+    "batch",
+    "coerce_types",
+    "compute_field",
+    "deduplicate",
+    "diff",
+    "explode",
+    "flatten",
+    "format_csv",
+    "format_json",
+    "json_select",
+    "parse_csv",
+    "parse_json",
+    "partition",
+    "read_file",
+    #[cfg(feature = "parquet")]
+    "read_parquet",
+    "read_since",
+    "read_stdin",
+    "template",
+    "throttle",
+    "union",
+    "write_file",
+    #[cfg(feature = "parquet")]
+    "write_parquet",
+    "write_stdout",
+];
+
+/// Instantiate a built-in Oxi by its pipeline step name
+pub fn builtin(name: &str) -> Option<Arc<dyn Oxi + Send + Sync>> {
+    registry::OxiRegistry::builtins().create(name)
+}
@@ -0,0 +1,217 @@
+use crate::oxis::prelude::*;
+use crate::types::OxiSchema;
+use ::parquet::file::reader::{FileReader, SerializedFileReader};
+use async_trait::async_trait;
+use std::fs;
+use std::path::Path;
+
+fn required_path(config: &OxiConfig) -> Result<String, OxiError> {
+    config
+        .get_string("path")
+        .map_err(|e| OxiError::ValidationError {
+            details: format!("Missing required 'path' config: {e}"),
+        })
+}
+
+/// ReadParquet loads a Parquet file as a JSON array of records
+pub struct ReadParquet;
+
+#[async_trait]
+impl Oxi for ReadParquet {
+    fn name(&self) -> &str {
+        "read_parquet"
+    }
+
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Json]
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              path:
+                type: string
+                description: "Path to the Parquet file to read"
+                required: true
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Infer
+    }
+
+    // A source ignores its input; splitting would read the file once per chunk
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    fn watched_paths(&self, config: &OxiConfig) -> Vec<std::path::PathBuf> {
+        config
+            .get_string("path")
+            .map(Into::into)
+            .into_iter()
+            .collect()
+    }
+
+    /// The file's footer declares its columns, so the schema is known
+    /// without reading any rows
+    fn output_schema(
+        &self,
+        _input_schema: Option<&OxiSchema>,
+        config: &OxiConfig,
+    ) -> anyhow::Result<OxiSchema> {
+        let path = required_path(config)?;
+        let Ok(file) = fs::File::open(&path) else {
+            // Not written yet, e.g. by an earlier run of the same pipeline
+            return Ok(OxiSchema::empty());
+        };
+        let reader = SerializedFileReader::new(file)?;
+        OxiSchema::from_parquet_schema(reader.metadata().file_metadata().schema_descr())
+    }
+
+    async fn process(&self, _input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let path = required_path(config)?;
+        let bytes = fs::read(&path)
+            .map_err(|e| OxiError::io(format!("Failed to read file '{path}'"), &e))?;
+        let mut output = OxiData::from_parquet(&bytes).map_err(|e| {
+            OxiError::ExecutionError(format!("Failed to decode Parquet file '{path}': {e:#}"))
+        })?;
+
+        output.set_meta("source_path", path);
+        output.set_meta("bytes_read", bytes.len());
+        Ok(output)
+    }
+}
+
+/// WriteParquet writes a JSON array of records to a Parquet file
+pub struct WriteParquet;
+
+#[async_trait]
+impl Oxi for WriteParquet {
+    fn name(&self) -> &str {
+        "write_parquet"
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              path:
+                type: string
+                description: "Path to the output Parquet file"
+                required: true
+              create_dirs:
+                type: boolean
+                description: "Create parent directories if they don't exist"
+                default: true
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        ProcessingLimits {
+            supported_input_types: vec![OxiDataType::Json],
+            ..ProcessingLimits::default()
+        }
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Passthrough
+    }
+
+    // A Parquet file has one footer; chunks would overwrite each other
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let path = required_path(config)?;
+        let create_dirs = config.get_bool_or("create_dirs", true);
+
+        let bytes = input.to_parquet().map_err(|e| {
+            OxiError::ExecutionError(format!("Failed to encode Parquet for '{path}': {e:#}"))
+        })?;
+
+        if create_dirs {
+            if let Some(parent) = Path::new(&path).parent() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    OxiError::io(format!("Failed to create directories for '{path}'"), &e)
+                })?;
+            }
+        }
+        fs::write(&path, &bytes)
+            .map_err(|e| OxiError::io(format!("Failed to write to file '{path}'"), &e))?;
+
+        let mut output = input;
+        output.set_meta("bytes_written", bytes.len());
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FieldType;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn path_config(path: &Path) -> OxiConfig {
+        OxiConfig::builder()
+            .set("path", path.to_string_lossy())
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_write_then_read_parquet() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("out/users.parquet");
+        let config = path_config(&path);
+        let records = json!([{"id": 1, "name": "ada"}, {"id": 2, "name": null}]);
+
+        let written = WriteParquet
+            .process(OxiData::from_json(records.clone()), &config)
+            .await
+            .unwrap();
+        assert_eq!(written.data.as_json().unwrap(), &records);
+        assert!(path.exists());
+
+        let schema = ReadParquet.output_schema(None, &config).unwrap();
+        assert_eq!(schema.fields["id"].field_type, FieldType::Integer);
+        assert!(schema.fields["name"].nullable);
+
+        let read = ReadParquet
+            .process(OxiData::empty(), &config)
+            .await
+            .unwrap();
+        assert_eq!(read.data.as_json().unwrap(), &records);
+        assert_eq!(
+            read.get_meta("source_path").unwrap(),
+            path.to_string_lossy().as_ref()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parquet_errors() {
+        let dir = tempdir().unwrap();
+        let missing = path_config(&dir.path().join("missing.parquet"));
+        assert!(ReadParquet
+            .process(OxiData::empty(), &missing)
+            .await
+            .is_err());
+        assert!(ReadParquet
+            .output_schema(None, &missing)
+            .unwrap()
+            .fields
+            .is_empty());
+
+        let text = OxiData::from_text("not records".to_string());
+        let err = WriteParquet.process(text, &missing).await.unwrap_err();
+        assert!(err.to_string().contains("JSON array"), "{err}");
+    }
+}
@@ -10,6 +10,10 @@ impl Oxi for ParseJson {
         "parse_json"
     }
 
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Json]
+    }
+
     fn config_schema(&self) -> serde_yaml::Value {
         serde_yaml::from_str(
             r#"
@@ -19,6 +23,9 @@ impl Oxi for ParseJson {
                 type: boolean
                 description: "Whether to flatten nested JSON objects"
                 default: false
+              content:
+                type: string
+                description: "JSON text to parse instead of the input, e.g. ${partition.invalid}"
         "#,
         )
         .unwrap()
@@ -30,7 +37,20 @@ impl Oxi for ParseJson {
         }
     }
 
-    async fn process(&self, input: OxiData, _config: &OxiConfig) -> Result<OxiData, OxiError> {
+    // Input text is parsed as a whole, and `content` replaces the input entirely
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        if let Ok(content) = config.get_string("content") {
+            let json_value: serde_json::Value =
+                serde_json::from_str(&content).map_err(|e| OxiError::ValidationError {
+                    details: format!("Failed to parse 'content' as JSON: {e}"),
+                })?;
+            return Ok(OxiData::from_json(json_value));
+        }
+
         match &input.data {
             Data::Text(text) => {
                 // Parse JSON from text
@@ -42,24 +62,8 @@ impl Oxi for ParseJson {
                 // Create new data with inferred schema
                 Ok(OxiData::from_json(json_value))
             }
-            Data::Json(json) => {
-                // If it's a structured object with content field, extract just the content
-                if let Some(content) = json.get("content") {
-                    if let Some(content_str) = content.as_str() {
-                        let json_value: serde_json::Value = serde_json::from_str(content_str)
-                            .map_err(|e| OxiError::ValidationError {
-                                details: format!("Failed to parse nested JSON: {e}"),
-                            })?;
-                        Ok(OxiData::from_json(json_value))
-                    } else {
-                        // Content is already JSON
-                        Ok(OxiData::from_json(content.clone()))
-                    }
-                } else {
-                    // No content field, return as-is
-                    Ok(input) // Pass through with existing schema
-                }
-            }
+            // Already parsed, e.g. by read_file on a .json file
+            Data::Json(_) => Ok(input),
             _ => Err(OxiError::TypeMismatch {
                 expected: "Text or JSON".to_string(),
                 actual: input.data().data_type().to_string(),
@@ -88,4 +92,38 @@ mod tests {
             panic!("Expected JSON data");
         }
     }
+
+    #[tokio::test]
+    async fn test_read_file_output_with_content_field_passes_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("post.json");
+        std::fs::write(&path, r#"{"title":"x","content":"hello world"}"#).unwrap();
+
+        let mut read_config = OxiConfig::default();
+        read_config.values.insert(
+            "path".to_string(),
+            serde_yaml::Value::String(path.to_string_lossy().to_string()),
+        );
+        let read = crate::oxis::file::oxi::ReadFile
+            .process(OxiData::empty(), &read_config)
+            .await
+            .unwrap();
+
+        let result = ParseJson
+            .process(read, &OxiConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            result.data().as_json().unwrap(),
+            &serde_json::json!({"title": "x", "content": "hello world"})
+        );
+
+        // A `content` object keeps its sibling fields too
+        let nested = OxiData::from_json(serde_json::json!({"id": 1, "content": {"a": 2}}));
+        let result = ParseJson
+            .process(nested, &OxiConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(result.data().as_json().unwrap()["id"], 1);
+    }
 }
@@ -0,0 +1,467 @@
+use crate::oxis::prelude::*;
+use crate::types::OxiSchema;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// Partition routes the records of a JSON array into named buckets
+///
+/// Each rule is a `{name: condition}` pair; records no rule claims go to the
+/// default bucket, which is also the step's output. Every bucket, including
+/// the default, is reported under the `partitions` metadata key and can be
+/// referenced later as `${partition.NAME}`.
+pub struct Partition;
+
+/// Options accepted by [`Partition`]
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct PartitionConfig {
+    /// `{name: condition}` pairs, tried in the order written
+    rules: serde_yaml::Mapping,
+    /// Shorthand for a single rule named `matched`
+    predicate: Option<String>,
+    #[serde(rename = "match")]
+    match_mode: MatchMode,
+    /// Bucket for records no rule matches
+    default: String,
+}
+
+impl Default for PartitionConfig {
+    fn default() -> Self {
+        Self {
+            rules: serde_yaml::Mapping::new(),
+            predicate: None,
+            match_mode: MatchMode::First,
+            default: "unmatched".to_string(),
+        }
+    }
+}
+
+/// What happens to a record that satisfies several rules
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum MatchMode {
+    /// Only the first matching rule, in declaration order, gets the record
+    #[default]
+    First,
+    /// Every matching rule gets a copy of the record
+    All,
+}
+
+impl PartitionConfig {
+    /// Rules in declaration order, parsed
+    fn rules(&self) -> Result<Vec<(String, Condition)>, OxiError> {
+        let mut rules = Vec::new();
+        if let Some(predicate) = &self.predicate {
+            rules.push(("matched".to_string(), predicate.clone()));
+        }
+        for (name, condition) in &self.rules {
+            let (Some(name), Some(condition)) = (name.as_str(), condition.as_str()) else {
+                return Err(OxiError::ConfigError(
+                    "partition rules must map bucket names to condition strings".to_string(),
+                ));
+            };
+            rules.push((name.to_string(), condition.to_string()));
+        }
+
+        if rules.is_empty() {
+            return Err(OxiError::MissingConfig(
+                "partition requires 'rules' or 'predicate'".to_string(),
+            ));
+        }
+        rules
+            .into_iter()
+            .map(|(name, text)| {
+                if name == self.default {
+                    return Err(OxiError::ConfigError(format!(
+                        "Rule '{name}' has the same name as the default bucket"
+                    )));
+                }
+                let condition = Condition::parse(&text).map_err(|e| {
+                    OxiError::ConfigError(format!("Invalid condition for partition '{name}': {e}"))
+                })?;
+                Ok((name, condition))
+            })
+            .collect()
+    }
+}
+
+/// Comparison a condition applies to a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+    Exists,
+    Missing,
+}
+
+/// `<field> <operator> <value>`, e.g. `region == "eu"` or `email exists`
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    /// Dotted path into the record; numeric segments index arrays
+    path: Vec<String>,
+    operator: Operator,
+    value: Value,
+}
+
+impl Condition {
+    fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (field, rest) = text
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("expected '<field> <operator> [value]', got \"{text}\""))?;
+        let rest = rest.trim_start();
+        let (operator, literal) = rest
+            .split_once(char::is_whitespace)
+            .map(|(op, literal)| (op, literal.trim()))
+            .unwrap_or((rest, ""));
+
+        let operator = match operator {
+            "==" => Operator::Eq,
+            "!=" => Operator::Ne,
+            ">" => Operator::Gt,
+            ">=" => Operator::Ge,
+            "<" => Operator::Lt,
+            "<=" => Operator::Le,
+            "contains" => Operator::Contains,
+            "exists" => Operator::Exists,
+            "missing" => Operator::Missing,
+            other => {
+                return Err(format!(
+                    "unknown operator '{other}' (expected ==, !=, >, >=, <, <=, contains, exists or missing)"
+                ))
+            }
+        };
+
+        let unary = matches!(operator, Operator::Exists | Operator::Missing);
+        if unary != literal.is_empty() {
+            return Err(if unary {
+                format!("'{field} {rest}' takes no value")
+            } else {
+                format!("'{field} {rest}' needs a value to compare with")
+            });
+        }
+
+        Ok(Self {
+            path: field.split('.').map(str::to_string).collect(),
+            operator,
+            value: parse_literal(literal),
+        })
+    }
+
+    fn matches(&self, record: &Value) -> bool {
+        let field = self
+            .path
+            .iter()
+            .try_fold(record, |current, segment| match current {
+                Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+                _ => current.get(segment),
+            });
+        let field = field.filter(|value| !value.is_null());
+
+        match (self.operator, field) {
+            (Operator::Exists, field) => field.is_some(),
+            (Operator::Missing, field) => field.is_none(),
+            (Operator::Ne, field) => !field.is_some_and(|value| values_equal(value, &self.value)),
+            (_, None) => false,
+            (Operator::Eq, Some(value)) => values_equal(value, &self.value),
+            (Operator::Contains, Some(Value::String(text))) => {
+                self.value.as_str().is_some_and(|part| text.contains(part))
+            }
+            (Operator::Contains, Some(Value::Array(items))) => {
+                items.iter().any(|item| values_equal(item, &self.value))
+            }
+            (Operator::Contains, Some(_)) => false,
+            (operator, Some(value)) => {
+                let Some(ordering) = compare_values(value, &self.value) else {
+                    return false;
+                };
+                match operator {
+                    Operator::Gt => ordering == Ordering::Greater,
+                    Operator::Ge => ordering != Ordering::Less,
+                    Operator::Lt => ordering == Ordering::Less,
+                    _ => ordering != Ordering::Greater,
+                }
+            }
+        }
+    }
+}
+
+/// JSON literal (`42`, `"eu"`, `true`, `null`), a single-quoted string, or a bare word
+fn parse_literal(literal: &str) -> Value {
+    if let Some(inner) = literal
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+    {
+        return Value::String(inner.to_string());
+    }
+    serde_json::from_str(literal).unwrap_or_else(|_| Value::String(literal.to_string()))
+}
+
+/// Equality that treats `1` and `1.0` as the same number
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left.as_f64(), right.as_f64()) {
+        (Some(l), Some(r)) => l == r,
+        _ => left == right,
+    }
+}
+
+/// Numbers compare numerically and strings lexically; other pairs don't compare
+fn compare_values(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(_), Value::Number(_)) => left.as_f64()?.partial_cmp(&right.as_f64()?),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl Oxi for Partition {
+    fn name(&self) -> &str {
+        "partition"
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              rules:
+                type: object
+                description: "Bucket name -> condition such as 'region == \"eu\"', 'amount >= 100' or 'email missing', tried in order"
+              predicate:
+                type: string
+                description: "Single condition; matching records go to the 'matched' bucket"
+              match:
+                type: string
+                enum: ["first", "all"]
+                description: "first: a record goes to the first matching rule only, all: to every matching rule"
+                default: "first"
+              default:
+                type: string
+                description: "Bucket for records no rule matches, which is also the step's output"
+                default: "unmatched"
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        ProcessingLimits {
+            supported_input_types: vec![OxiDataType::Json],
+            ..ProcessingLimits::default()
+        }
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Passthrough
+    }
+
+    // Buckets are reported as metadata, where chunk outputs would overwrite each other
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    fn output_schema(
+        &self,
+        input_schema: Option<&OxiSchema>,
+        _config: &OxiConfig,
+    ) -> anyhow::Result<OxiSchema> {
+        // The default bucket holds a subset of the input records
+        Ok(input_schema.cloned().unwrap_or_else(OxiSchema::empty))
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let options: PartitionConfig = config
+            .deserialize_into()
+            .map_err(|e| OxiError::ConfigError(e.to_string()))?;
+        let rules = options.rules()?;
+
+        let records = match input.data().as_json() {
+            Ok(Value::Array(records)) => records.clone(),
+            Ok(record @ Value::Object(_)) => vec![record.clone()],
+            _ => {
+                return Err(OxiError::TypeMismatch {
+                    expected: "JSON array of records".to_string(),
+                    actual: input.data().data_type().to_string(),
+                    step: "partition".to_string(),
+                })
+            }
+        };
+
+        let mut buckets: Vec<Vec<Value>> = vec![Vec::new(); rules.len()];
+        let mut unmatched = Vec::new();
+        for record in records {
+            let mut matched = false;
+            for (index, (_, condition)) in rules.iter().enumerate() {
+                if !condition.matches(&record) {
+                    continue;
+                }
+                matched = true;
+                buckets[index].push(record.clone());
+                if options.match_mode == MatchMode::First {
+                    break;
+                }
+            }
+            if !matched {
+                unmatched.push(record);
+            }
+        }
+
+        let mut partitions = serde_json::Map::new();
+        let mut counts = serde_json::Map::new();
+        for ((name, _), records) in rules.into_iter().zip(buckets) {
+            counts.insert(name.clone(), records.len().into());
+            partitions.insert(name, Value::Array(records));
+        }
+        counts.insert(options.default.clone(), unmatched.len().into());
+        partitions.insert(options.default, Value::Array(unmatched.clone()));
+
+        let mut output = OxiData::with_schema(Data::Json(Value::Array(unmatched)), input.schema);
+        output.set_meta("partitions", partitions);
+        output.set_meta("partition_counts", counts);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn orders() -> OxiData {
+        OxiData::from_json(json!([
+            {"id": 1, "region": "eu", "amount": 250},
+            {"id": 2, "region": "us", "amount": 40},
+            {"id": 3, "region": "eu", "amount": 15.5},
+            {"id": 4, "region": "apac", "amount": 900},
+            {"id": 5, "amount": 70}
+        ]))
+    }
+
+    fn ids(records: &Value) -> Vec<i64> {
+        records
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|record| record["id"].as_i64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_two_way_split_by_predicate() {
+        let output = Partition
+            .process(
+                orders(),
+                &OxiConfig::from_yaml_str("predicate: amount >= 100\ndefault: small"),
+            )
+            .await
+            .unwrap();
+
+        let partitions = output.get_meta("partitions").unwrap();
+        assert_eq!(ids(&partitions["matched"]), [1, 4]);
+        assert_eq!(ids(&partitions["small"]), [2, 3, 5]);
+        assert_eq!(ids(output.data.as_json().unwrap()), [2, 3, 5]);
+        assert_eq!(
+            output.get_meta("partition_counts").unwrap(),
+            &json!({"matched": 2, "small": 3})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rules_with_unmatched_default_bucket() {
+        let rules = "rules:\n  europe: region == 'eu'\n  americas: region == \"us\"\n";
+
+        let output = Partition
+            .process(orders(), &OxiConfig::from_yaml_str(rules))
+            .await
+            .unwrap();
+
+        let partitions = output.get_meta("partitions").unwrap();
+        assert_eq!(ids(&partitions["europe"]), [1, 3]);
+        assert_eq!(ids(&partitions["americas"]), [2]);
+        // apac and the record without a region fall through to the default
+        assert_eq!(ids(&partitions["unmatched"]), [4, 5]);
+        assert_eq!(ids(output.data.as_json().unwrap()), [4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_first_match_versus_all() {
+        let rules = "rules:\n  large: amount > 200\n  europe: region == eu\n";
+
+        let first = Partition
+            .process(orders(), &OxiConfig::from_yaml_str(rules))
+            .await
+            .unwrap();
+        let partitions = first.get_meta("partitions").unwrap();
+        assert_eq!(ids(&partitions["large"]), [1, 4]);
+        assert_eq!(ids(&partitions["europe"]), [3]);
+
+        let all = Partition
+            .process(
+                orders(),
+                &OxiConfig::from_yaml_str(&format!("{rules}match: all\n")),
+            )
+            .await
+            .unwrap();
+        let partitions = all.get_meta("partitions").unwrap();
+        assert_eq!(ids(&partitions["large"]), [1, 4]);
+        assert_eq!(ids(&partitions["europe"]), [1, 3]);
+        assert_eq!(ids(&partitions["unmatched"]), [2, 5]);
+    }
+
+    #[test]
+    fn test_conditions() {
+        let record =
+            json!({"name": "Ada", "tags": ["vip"], "address": {"zip": "10115"}, "note": null});
+        let check = |text: &str| Condition::parse(text).unwrap().matches(&record);
+
+        assert!(check("tags contains vip"));
+        assert!(check("name contains 'd'"));
+        assert!(check("address.zip == '10115'"));
+        assert!(check("tags.0 == vip"));
+        assert!(check("note missing"));
+        assert!(check("name != Bob"));
+        assert!(check("name > Ab"));
+        assert!(!check("name >= 5"));
+        assert!(check("address exists"));
+        assert!(!check("address missing"));
+
+        assert!(Condition::parse("amount").is_err());
+        assert!(Condition::parse("amount ~= 3").is_err());
+        assert!(Condition::parse("amount >").is_err());
+        assert!(Condition::parse("email exists now").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_config_errors() {
+        let err = Partition
+            .process(orders(), &OxiConfig::default())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("'rules' or 'predicate'"), "{err}");
+
+        let err = Partition
+            .process(
+                orders(),
+                &OxiConfig::from_yaml_str("rules:\n  unmatched: amount > 1"),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("default bucket"), "{err}");
+
+        let err = Partition
+            .process(
+                orders(),
+                &OxiConfig::from_yaml_str("rules:\n  big: amount => 1"),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("partition 'big'"), "{err}");
+    }
+}
@@ -0,0 +1,162 @@
+//! Plugins loaded from shared libraries
+//!
+//! A library exports `oxide_flow_plugin_register`, returning a [`PluginVTable`]
+//! whose functions exchange the module's JSON documents as C strings:
+//!
+//! ```ignore
+//! #[no_mangle]
+//! pub extern "C" fn oxide_flow_plugin_register() -> PluginVTable {
+//!     PluginVTable {
+//!         abi_version: PLUGIN_ABI_VERSION,
+//!         describe,
+//!         process,
+//!         free_string,
+//!     }
+//! }
+//! ```
+//!
+//! Strings returned by `describe` and `process` are handed back to the
+//! library's `free_string`, so they may come from any allocator.
+
+use super::{
+    decode_response, encode_request, plugin_limits, timeout_error, PluginConfig, PluginDescription,
+};
+use crate::oxis::prelude::*;
+use std::ffi::{c_char, CStr, CString};
+use std::sync::Arc;
+
+/// ABI version this build understands; a library reporting another is rejected
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Symbol looked up in each plugin library
+pub const REGISTER_SYMBOL: &str = "oxide_flow_plugin_register";
+
+/// Entry points a plugin library registers
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PluginVTable {
+    pub abi_version: u32,
+    /// Returns the `--describe` document
+    pub describe: unsafe extern "C" fn() -> *mut c_char,
+    /// Takes a request document and returns a response document
+    pub process: unsafe extern "C" fn(request: *const c_char) -> *mut c_char,
+    /// Releases a string returned by `describe` or `process`
+    pub free_string: unsafe extern "C" fn(value: *mut c_char),
+}
+
+type RegisterFn = unsafe extern "C" fn() -> PluginVTable;
+
+/// An Oxi backed by a plugin library
+///
+/// Calls run on the blocking thread pool. A call that outlives the plugin's
+/// timeout fails the step, but the library call itself cannot be interrupted
+/// and finishes in the background.
+pub struct DylibOxi {
+    config: PluginConfig,
+    vtable: PluginVTable,
+    // Keeps the vtable's functions loaded
+    library: Arc<libloading::Library>,
+}
+
+impl DylibOxi {
+    pub fn load(config: &PluginConfig) -> anyhow::Result<Self> {
+        let path = config.library.as_deref().unwrap_or_default();
+        // SAFETY: loading runs the library's initializers; plugin libraries are
+        // trusted code listed explicitly in oxiflow.yaml.
+        let library = unsafe { libloading::Library::new(path) }.map_err(|e| {
+            anyhow::anyhow!("Failed to load plugin '{}' from {path}: {e}", config.name)
+        })?;
+        // SAFETY: the symbol is declared with `RegisterFn`'s signature by the ABI
+        let vtable = unsafe {
+            let register = library
+                .get::<RegisterFn>(REGISTER_SYMBOL.as_bytes())
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Plugin '{}' ({path}) does not export {REGISTER_SYMBOL}: {e}",
+                        config.name
+                    )
+                })?;
+            register()
+        };
+        if vtable.abi_version != PLUGIN_ABI_VERSION {
+            anyhow::bail!(
+                "Plugin '{}' uses ABI version {}, expected {PLUGIN_ABI_VERSION}",
+                config.name,
+                vtable.abi_version
+            );
+        }
+        Ok(Self {
+            config: config.clone(),
+            vtable,
+            library: Arc::new(library),
+        })
+    }
+
+    /// The library's answer to the `--describe` handshake
+    pub fn describe_library(&self) -> anyhow::Result<PluginDescription> {
+        // SAFETY: `describe` comes from the loaded library's vtable
+        let document = unsafe { take_string(&self.vtable, (self.vtable.describe)()) };
+        serde_json::from_str(&document).map_err(|e| {
+            anyhow::anyhow!(
+                "Plugin '{}' returned an invalid description: {e}",
+                self.config.name
+            )
+        })
+    }
+}
+
+/// Copy a string returned by the library and release the original
+///
+/// # Safety
+///
+/// `value` must be null or a NUL-terminated string returned by one of the
+/// vtable's functions, not yet freed.
+unsafe fn take_string(vtable: &PluginVTable, value: *mut c_char) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    let copy = CStr::from_ptr(value).to_string_lossy().into_owned();
+    (vtable.free_string)(value);
+    copy
+}
+
+#[async_trait]
+impl Oxi for DylibOxi {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Infer
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        plugin_limits(&self.config)
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let request = CString::new(encode_request(&input, config)?).map_err(|e| {
+            OxiError::ExecutionError(format!(
+                "Request for plugin '{}' contains a NUL byte: {e}",
+                self.config.name
+            ))
+        })?;
+        let vtable = self.vtable;
+        let library = self.library.clone();
+        let call = tokio::task::spawn_blocking(move || {
+            let _library = library;
+            // SAFETY: `process` comes from the vtable of the library held above,
+            // and `request` outlives the call
+            unsafe { take_string(&vtable, (vtable.process)(request.as_ptr())) }
+        });
+
+        let started = Instant::now();
+        let response = tokio::time::timeout(self.config.timeout(), call)
+            .await
+            .map_err(|_| timeout_error(&self.config, started.elapsed()))?
+            .map_err(|e| {
+                OxiError::ExecutionError(format!("Plugin '{}' panicked: {e}", self.config.name))
+            })?;
+        decode_response(&self.config.name, &response)
+    }
+}
@@ -0,0 +1,267 @@
+//! Oxis shipped outside the binary, declared under `plugins:` in oxiflow.yaml
+//!
+//! A plugin is either an executable speaking JSON lines over stdio
+//! ([`SubprocessOxi`]) or, with the `dylib-plugins` feature, a cdylib exposing
+//! `oxide_flow_plugin_register`. Both exchange the same JSON documents:
+//!
+//! - request: `{"config": {...}, "data": <json>, "schema": <OxiSchema>}`
+//! - response: `{"data": <json>, "schema": <OxiSchema, optional>}` or `{"error": "..."}`
+//!
+//! Text input is sent as a JSON string, binary input as base64 and empty
+//! input as `null`. A `null` response is empty output. Run with
+//! `--describe`, an executable prints `{"name": ..., "config_schema": ...}`
+//! and exits; `config_schema` uses the same shape as `Oxi::config_schema`.
+
+#[cfg(feature = "dylib-plugins")]
+pub mod dylib;
+pub mod subprocess;
+
+pub use subprocess::SubprocessOxi;
+
+use crate::config::OxiConfigSchema;
+use crate::error::OxiError;
+use crate::types::{Data, OxiConfig, OxiData, OxiSchema, ProcessingLimits};
+use crate::Oxi;
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// One entry of the `plugins:` section in oxiflow.yaml
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct PluginConfig {
+    /// Name pipeline steps use to run the plugin
+    pub name: String,
+    /// Executable implementing the JSON-over-stdio protocol
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Arguments passed to `command` on every start
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Shared library exposing `oxide_flow_plugin_register` (`dylib-plugins` feature)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub library: Option<String>,
+    /// Keep plugin processes running between calls instead of spawning one per call
+    pub keep_alive: bool,
+    /// Fail a call that takes longer than this; defaults to `max_processing_time_ms`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl PluginConfig {
+    /// Time allowed for one call or handshake
+    pub fn timeout(&self) -> Duration {
+        let default_ms = ProcessingLimits::default()
+            .max_processing_time_ms
+            .unwrap_or(30_000);
+        Duration::from_millis(self.timeout_ms.unwrap_or(default_ms))
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.name.is_empty() {
+            bail!("A plugin is missing its 'name'");
+        }
+        if crate::oxis::builtin(&self.name).is_some() {
+            bail!("Plugin '{}' has the name of a built-in Oxi", self.name);
+        }
+        match (&self.command, &self.library) {
+            (Some(_), None) | (None, Some(_)) => Ok(()),
+            _ => bail!(
+                "Plugin '{}' must set exactly one of 'command' or 'library'",
+                self.name
+            ),
+        }
+    }
+}
+
+/// What a plugin reports about itself in the `--describe` handshake
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct PluginDescription {
+    pub name: String,
+    #[serde(default)]
+    pub config_schema: serde_yaml::Value,
+}
+
+impl PluginDescription {
+    pub fn config_schema(&self) -> OxiConfigSchema {
+        OxiConfigSchema::from_yaml_schema(&self.config_schema)
+    }
+}
+
+/// Instantiate every declared plugin, keyed by name
+///
+/// Nothing is started here; subprocess plugins spawn on their first call.
+pub fn load_plugins(
+    configs: &[PluginConfig],
+) -> Result<HashMap<String, Arc<dyn Oxi + Send + Sync>>> {
+    let mut plugins: HashMap<String, Arc<dyn Oxi + Send + Sync>> = HashMap::new();
+    for config in configs {
+        config.check()?;
+        let oxi: Arc<dyn Oxi + Send + Sync> = match &config.library {
+            None => Arc::new(SubprocessOxi::new(config.clone())),
+            #[cfg(feature = "dylib-plugins")]
+            Some(_) => Arc::new(dylib::DylibOxi::load(config)?),
+            #[cfg(not(feature = "dylib-plugins"))]
+            Some(_) => bail!(
+                "Plugin '{}' is a shared library; rebuild with the 'dylib-plugins' feature to load it",
+                config.name
+            ),
+        };
+        if plugins.insert(config.name.clone(), oxi).is_some() {
+            bail!("Plugin '{}' is declared twice", config.name);
+        }
+    }
+    Ok(plugins)
+}
+
+/// Check that a plugin exists and answers the `--describe` handshake
+pub fn describe_plugin(config: &PluginConfig) -> Result<PluginDescription> {
+    config.check()?;
+    let description = match (&config.command, &config.library) {
+        (Some(command), _) => describe_command(command, config)?,
+        #[cfg(feature = "dylib-plugins")]
+        (None, Some(_)) => dylib::DylibOxi::load(config)?.describe_library()?,
+        _ => bail!(
+            "Plugin '{}' is a shared library; rebuild with the 'dylib-plugins' feature to load it",
+            config.name
+        ),
+    };
+    if description.name != config.name {
+        bail!(
+            "Plugin '{}' describes itself as '{}'",
+            config.name,
+            description.name
+        );
+    }
+    Ok(description)
+}
+
+fn describe_command(command: &str, config: &PluginConfig) -> Result<PluginDescription> {
+    let mut child = Command::new(command)
+        .args(&config.args)
+        .arg("--describe")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start plugin '{}' ({command})", config.name))?;
+
+    let deadline = Instant::now() + config.timeout();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!(
+                "Plugin '{}' did not answer --describe within {}ms",
+                config.name,
+                config.timeout().as_millis()
+            );
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    };
+
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    if let Some(mut out) = child.stdout.take() {
+        out.read_to_string(&mut stdout)?;
+    }
+    if let Some(mut err) = child.stderr.take() {
+        err.read_to_string(&mut stderr)?;
+    }
+    if !status.success() {
+        bail!(
+            "Plugin '{}' --describe exited with {status}: {}",
+            config.name,
+            stderr.trim()
+        );
+    }
+    serde_json::from_str(stdout.trim()).with_context(|| {
+        format!(
+            "Plugin '{}' --describe did not print a JSON description",
+            config.name
+        )
+    })
+}
+
+#[derive(Serialize)]
+struct PluginRequest<'a> {
+    config: &'a HashMap<String, serde_yaml::Value>,
+    data: serde_json::Value,
+    schema: &'a OxiSchema,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PluginResponse {
+    Failed {
+        error: String,
+    },
+    Output {
+        data: serde_json::Value,
+        #[serde(default)]
+        schema: Option<OxiSchema>,
+    },
+}
+
+/// Serialize one request document, without a trailing newline
+pub(crate) fn encode_request(input: &OxiData, config: &OxiConfig) -> Result<String, OxiError> {
+    let data = match input.data() {
+        Data::Json(value) => value.clone(),
+        Data::Text(text) => serde_json::Value::String(text.clone()),
+        Data::Binary(bytes) => serde_json::Value::String(
+            base64::engine::general_purpose::STANDARD.encode(bytes.as_slice()),
+        ),
+        Data::Empty => serde_json::Value::Null,
+    };
+    let request = PluginRequest {
+        config: &config.values,
+        data,
+        schema: input.schema(),
+    };
+    Ok(serde_json::to_string(&request)?)
+}
+
+/// Turn a response document into the plugin's output
+pub(crate) fn decode_response(plugin: &str, response: &str) -> Result<OxiData, OxiError> {
+    let response: PluginResponse = serde_json::from_str(response.trim()).map_err(|e| {
+        OxiError::ExecutionError(format!("Plugin '{plugin}' sent an invalid response: {e}"))
+    })?;
+    match response {
+        PluginResponse::Failed { error } => Err(OxiError::ExecutionError(format!(
+            "Plugin '{plugin}' failed: {error}"
+        ))),
+        PluginResponse::Output {
+            data: serde_json::Value::Null,
+            ..
+        } => Ok(OxiData::empty()),
+        PluginResponse::Output {
+            data,
+            schema: Some(schema),
+        } => Ok(OxiData::with_schema(Data::Json(data), schema)),
+        PluginResponse::Output { data, schema: None } => Ok(OxiData::from_json(data)),
+    }
+}
+
+/// Processing limits of a plugin, with its timeout as the time limit
+pub(crate) fn plugin_limits(config: &PluginConfig) -> ProcessingLimits {
+    ProcessingLimits {
+        max_processing_time_ms: Some(config.timeout().as_millis() as u64),
+        ..ProcessingLimits::default()
+    }
+}
+
+pub(crate) fn timeout_error(config: &PluginConfig, elapsed: Duration) -> OxiError {
+    OxiError::ProcessingTimeout {
+        actual_ms: elapsed.as_millis() as u64,
+        max_ms: config.timeout().as_millis() as u64,
+        oxi_name: config.name.clone(),
+    }
+}
@@ -0,0 +1,142 @@
+use super::{decode_response, encode_request, plugin_limits, timeout_error, PluginConfig};
+use crate::oxis::prelude::*;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+
+/// Runs an external executable as an Oxi, one JSON request line per call
+///
+/// Without `keep_alive` every call spawns the executable, writes the request
+/// and reads one response line. With it, idle processes are pooled and reused,
+/// so the plugin must keep answering requests until its stdin closes. A call
+/// that errors or times out kills its process rather than returning it to the pool.
+pub struct SubprocessOxi {
+    config: PluginConfig,
+    idle: Mutex<Vec<PluginProcess>>,
+}
+
+struct PluginProcess {
+    // Dropping the child kills it
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl PluginProcess {
+    fn spawn(config: &PluginConfig) -> Result<Self, OxiError> {
+        let command = config.command.as_deref().unwrap_or_default();
+        let mut child = Command::new(command)
+            .args(&config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                OxiError::ExecutionError(format!(
+                    "Failed to start plugin '{}' ({command}): {e}",
+                    config.name
+                ))
+            })?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(OxiError::ExecutionError(format!(
+                "Plugin '{}' has no stdio pipes",
+                config.name
+            )));
+        };
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+        })
+    }
+
+    fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    async fn call(&mut self, plugin: &str, request: &str) -> Result<String, OxiError> {
+        let pipe_error = |e: std::io::Error| {
+            OxiError::ExecutionError(format!("Lost contact with plugin '{plugin}': {e}"))
+        };
+        self.stdin
+            .write_all(format!("{request}\n").as_bytes())
+            .await
+            .map_err(pipe_error)?;
+        self.stdin.flush().await.map_err(pipe_error)?;
+
+        let mut response = String::new();
+        if self
+            .stdout
+            .read_line(&mut response)
+            .await
+            .map_err(pipe_error)?
+            == 0
+        {
+            let status = self.child.wait().await.map_err(pipe_error)?;
+            return Err(OxiError::ExecutionError(format!(
+                "Plugin '{plugin}' exited with {status} before responding"
+            )));
+        }
+        Ok(response)
+    }
+}
+
+impl SubprocessOxi {
+    pub fn new(config: PluginConfig) -> Self {
+        Self {
+            config,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    async fn checkout(&self) -> Result<PluginProcess, OxiError> {
+        if self.config.keep_alive {
+            let mut idle = self.idle.lock().await;
+            while let Some(mut process) = idle.pop() {
+                if process.is_running() {
+                    return Ok(process);
+                }
+            }
+        }
+        PluginProcess::spawn(&self.config)
+    }
+
+    async fn checkin(&self, process: PluginProcess) {
+        if self.config.keep_alive {
+            self.idle.lock().await.push(process);
+        }
+    }
+}
+
+#[async_trait]
+impl Oxi for SubprocessOxi {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Infer
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        plugin_limits(&self.config)
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let request = encode_request(&input, config)?;
+        let mut process = self.checkout().await?;
+
+        let started = Instant::now();
+        let response = tokio::time::timeout(
+            self.config.timeout(),
+            process.call(&self.config.name, &request),
+        )
+        .await
+        .map_err(|_| timeout_error(&self.config, started.elapsed()))??;
+
+        self.checkin(process).await;
+        decode_response(&self.config.name, &response)
+    }
+}
@@ -0,0 +1,197 @@
+use crate::oxis::prelude::*;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+/// ReadSince reads JSON records from a file, keeping only those newer than a watermark
+///
+/// Pair `since: ${state.last_processed_id}` with state tracking to build
+/// incremental pipelines: each successful run saves the highest `id_field`
+/// it emitted, and the next run starts after it.
+pub struct ReadSince;
+
+#[async_trait]
+impl Oxi for ReadSince {
+    fn name(&self) -> &str {
+        "read_since"
+    }
+
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Json]
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              path:
+                type: string
+                description: "JSON array or newline-delimited JSON file of records"
+                required: true
+              id_field:
+                type: string
+                description: "Record field holding a monotonically increasing id or timestamp"
+                required: true
+              since:
+                type: string
+                description: "Emit only records whose id is greater than this; empty reads everything. Usually ${state.last_processed_id}"
+                default: ""
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Infer
+    }
+
+    // A source ignores its input; splitting would re-read the file per chunk
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    fn watched_paths(&self, config: &OxiConfig) -> Vec<std::path::PathBuf> {
+        config
+            .get_string("path")
+            .map(Into::into)
+            .into_iter()
+            .collect()
+    }
+
+    fn watermark(&self, output: &OxiData, config: &OxiConfig) -> Option<String> {
+        let id_field = config.get_string("id_field").ok()?;
+        output
+            .data()
+            .as_json()
+            .ok()?
+            .as_array()?
+            .iter()
+            .filter_map(|record| record_id(record, &id_field))
+            .max_by(|a, b| compare_ids(a, b))
+    }
+
+    async fn process(&self, _input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let path = config
+            .get_string("path")
+            .map_err(|_| OxiError::MissingConfig("read_since requires 'path'".to_string()))?;
+        let id_field = config
+            .get_string("id_field")
+            .map_err(|_| OxiError::MissingConfig("read_since requires 'id_field'".to_string()))?;
+        let since = config.get_string_or("since", "");
+
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| OxiError::io(format!("Failed to read file '{path}'"), &e))?;
+
+        let records: Vec<Value> =
+            read_records(&content).map_err(|e| OxiError::JsonOperationError {
+                operation: format!("parse records from '{path}'"),
+                details: e.to_string(),
+            })?;
+
+        // No saved watermark means a full read
+        let newer = records
+            .into_iter()
+            .filter(|record| {
+                since.is_empty()
+                    || record_id(record, &id_field)
+                        .is_some_and(|id| compare_ids(&id, &since) == Ordering::Greater)
+            })
+            .collect();
+
+        Ok(OxiData::from_json(Value::Array(newer)))
+    }
+}
+
+/// Parse a JSON array, or one JSON value per non-blank line
+fn read_records(content: &str) -> serde_json::Result<Vec<Value>> {
+    if content.trim_start().starts_with('[') {
+        return serde_json::from_str(content);
+    }
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect()
+}
+
+/// A record's id as a string; numbers keep their JSON representation
+fn record_id(record: &Value, id_field: &str) -> Option<String> {
+    match record.get(id_field)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Compare ids numerically when both are numbers, otherwise as strings
+/// (which orders ISO 8601 timestamps correctly)
+///
+/// Integers compare exactly, so ids beyond 2^53 keep their order; only
+/// decimals fall back to `f64`.
+fn compare_ids(a: &str, b: &str) -> Ordering {
+    if let (Ok(a), Ok(b)) = (a.parse::<i128>(), b.parse::<i128>()) {
+        return a.cmp(&b);
+    }
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) if a.is_finite() && b.is_finite() => {
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        }
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reads_only_records_after_watermark() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.ndjson");
+        std::fs::write(
+            &path,
+            "{\"id\": 1, \"sku\": \"a\"}\n{\"id\": 2, \"sku\": \"b\"}\n{\"id\": 10, \"sku\": \"c\"}\n",
+        )
+        .unwrap();
+
+        let full =
+            OxiConfig::from_yaml_str(&format!("{{path: '{}', id_field: id}}", path.display()));
+        let output = ReadSince.process(OxiData::empty(), &full).await.unwrap();
+        assert_eq!(
+            output.data().as_json().unwrap().as_array().unwrap().len(),
+            3
+        );
+        assert_eq!(ReadSince.watermark(&output, &full).as_deref(), Some("10"));
+
+        // Numeric ids compare as numbers, so "10" is after "2"
+        let incremental = OxiConfig::from_yaml_str(&format!(
+            "{{path: '{}', id_field: id, since: '2'}}",
+            path.display()
+        ));
+        let output = ReadSince
+            .process(OxiData::empty(), &incremental)
+            .await
+            .unwrap();
+        assert_eq!(
+            output.data().as_json().unwrap(),
+            &serde_json::json!([{"id": 10, "sku": "c"}])
+        );
+    }
+
+    #[test]
+    fn test_compare_ids_keeps_large_integers_exact() {
+        // Both round to the same f64
+        assert_eq!(
+            compare_ids("9007199254740993", "9007199254740992"),
+            Ordering::Greater
+        );
+        assert_eq!(compare_ids("10", "9"), Ordering::Greater);
+        assert_eq!(compare_ids("1.5", "10"), Ordering::Less);
+        assert_eq!(
+            compare_ids("2024-02-01T00:00:00Z", "2024-01-31T23:59:59Z"),
+            Ordering::Greater
+        );
+    }
+}
@@ -8,6 +8,10 @@ impl Oxi for ReadStdIn {
         "read_stdin"
     }
 
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Text, OxiDataType::Binary]
+    }
+
     fn config_schema(&self) -> serde_yaml::Value {
         serde_yaml::from_str(
             r#"
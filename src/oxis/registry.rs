@@ -0,0 +1,299 @@
+//! Catalog of the Oxis a pipeline step can name
+//!
+//! [`OxiRegistry::builtins`] holds every Oxi compiled into this binary.
+//! Library code and the CLI copy it with [`OxiRegistry::with_builtins`], add
+//! their own factories or the project's plugins, and hand it to
+//! [`Pipeline::attach_registry`](crate::pipeline::Pipeline::attach_registry).
+
+use crate::types::OxiDescriptor;
+use crate::Oxi;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, OnceLock};
+
+/// Creates a fresh instance of a registered Oxi
+pub type OxiFactory = Arc<dyn Fn() -> Arc<dyn Oxi + Send + Sync> + Send + Sync>;
+
+/// Category of Oxis registered from library code without one
+pub const CUSTOM_CATEGORY: &str = "custom";
+
+/// Category of Oxis loaded from the project's `plugins:`
+pub const PLUGIN_CATEGORY: &str = "plugin";
+
+/// One named entry of an [`OxiRegistry`]
+#[derive(Clone)]
+pub struct RegisteredOxi {
+    pub name: String,
+    /// Grouping shown by `oxi list`, e.g. "io" or "transform"
+    pub category: String,
+    /// One-line summary; empty falls back to the Oxi's own description
+    pub summary: String,
+    factory: OxiFactory,
+}
+
+impl RegisteredOxi {
+    /// Instantiate the Oxi
+    pub fn create(&self) -> Arc<dyn Oxi + Send + Sync> {
+        (self.factory)()
+    }
+
+    /// Documentation of the Oxi, with the registry's summary as its description
+    pub fn describe(&self) -> OxiDescriptor {
+        let mut descriptor = self.create().describe();
+        if !self.summary.is_empty() {
+            descriptor.description = self.summary.clone();
+        }
+        descriptor
+    }
+}
+
+impl std::fmt::Debug for RegisteredOxi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegisteredOxi")
+            .field("name", &self.name)
+            .field("category", &self.category)
+            .finish()
+    }
+}
+
+/// Oxi names mapped to the factories that instantiate them
+#[derive(Clone, Debug, Default)]
+pub struct OxiRegistry {
+    entries: BTreeMap<String, RegisteredOxi>,
+}
+
+impl OxiRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The Oxis compiled into this binary
+    pub fn builtins() -> &'static OxiRegistry {
+        static BUILTINS: OnceLock<OxiRegistry> = OnceLock::new();
+        BUILTINS.get_or_init(register_builtins)
+    }
+
+    /// A registry starting from the built-ins, ready for more registrations
+    pub fn with_builtins() -> Self {
+        Self::builtins().clone()
+    }
+
+    /// Register `factory` under `name` in the custom category
+    ///
+    /// A registration replaces any earlier one with the same name, built-ins included.
+    pub fn register<F>(&mut self, name: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn() -> Arc<dyn Oxi + Send + Sync> + Send + Sync + 'static,
+    {
+        self.register_with(name, CUSTOM_CATEGORY, "", factory)
+    }
+
+    /// Register `factory` under `name` with a category and summary
+    pub fn register_with<F>(
+        &mut self,
+        name: impl Into<String>,
+        category: &str,
+        summary: &str,
+        factory: F,
+    ) -> &mut Self
+    where
+        F: Fn() -> Arc<dyn Oxi + Send + Sync> + Send + Sync + 'static,
+    {
+        let name = name.into();
+        self.entries.insert(
+            name.clone(),
+            RegisteredOxi {
+                name,
+                category: category.to_string(),
+                summary: summary.to_string(),
+                factory: Arc::new(factory),
+            },
+        );
+        self
+    }
+
+    /// Register loaded plugins; every step naming a plugin shares its instance
+    pub fn register_plugins(
+        &mut self,
+        plugins: &HashMap<String, Arc<dyn Oxi + Send + Sync>>,
+    ) -> &mut Self {
+        for (name, plugin) in plugins {
+            let plugin = plugin.clone();
+            self.register_with(name.clone(), PLUGIN_CATEGORY, "", move || plugin.clone());
+        }
+        self
+    }
+
+    /// Instantiate the Oxi registered as `name`
+    pub fn create(&self, name: &str) -> Option<Arc<dyn Oxi + Send + Sync>> {
+        self.entries.get(name).map(RegisteredOxi::create)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RegisteredOxi> {
+        self.entries.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    /// Registered names, sorted
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Registered entries, sorted by name
+    pub fn entries(&self) -> impl Iterator<Item = &RegisteredOxi> {
+        self.entries.values()
+    }
+}
+
+fn register_builtins() -> OxiRegistry {
+    use super::*;
+
+    let mut registry = OxiRegistry::new();
+    registry
+        .register_with("batch", "flow", "Split large inputs into batches", || {
+            Arc::new(batch::oxi::Batch)
+        })
+        .register_with(
+            "coerce_types",
+            "transform",
+            "Convert field values to the types of a target schema",
+            || Arc::new(coerce_types::CoerceTypes),
+        )
+        .register_with(
+            "compute_field",
+            "transform",
+            "Add fields calculated from expressions over each record",
+            || Arc::new(compute_field::ComputeField),
+        )
+        .register_with(
+            "deduplicate",
+            "transform",
+            "Drop records whose content was already seen",
+            || Arc::new(deduplicate::Deduplicate),
+        )
+        .register_with(
+            "diff",
+            "transform",
+            "Report what changed between an earlier payload and the input",
+            || Arc::new(diff::DiffOxi),
+        )
+        .register_with(
+            "explode",
+            "transform",
+            "Turn each element of an array field into its own record",
+            || Arc::new(explode::ExplodeOxi),
+        )
+        .register_with(
+            "flatten",
+            "transform",
+            "Flatten nested objects into dot-notation fields",
+            || Arc::new(flatten::oxi::Flatten),
+        )
+        .register_with(
+            "format_csv",
+            "format",
+            "Render JSON records as CSV text",
+            || Arc::new(csv::oxi::FormatCsv),
+        )
+        .register_with("format_json", "format", "Render data as JSON text", || {
+            Arc::new(format_json::FormatJson)
+        })
+        .register_with(
+            "json_select",
+            "transform",
+            "Select values from JSON data by path",
+            || Arc::new(json_select::JsonSelect),
+        )
+        .register_with(
+            "parse_csv",
+            "format",
+            "Parse CSV text into JSON records",
+            || Arc::new(csv::oxi::ParseCsv),
+        )
+        .register_with(
+            "parse_json",
+            "format",
+            "Parse JSON text into structured data",
+            || Arc::new(parse_json::ParseJson),
+        )
+        .register_with(
+            "partition",
+            "transform",
+            "Route records into named buckets by condition",
+            || Arc::new(partition::Partition),
+        )
+        .register_with("read_file", "io", "Read a file's content", || {
+            Arc::new(file::oxi::ReadFile)
+        })
+        .register_with(
+            "read_since",
+            "io",
+            "Read only records newer than a watermark",
+            || Arc::new(read_since::ReadSince),
+        )
+        .register_with("read_stdin", "io", "Read data from standard input", || {
+            Arc::new(read_stdin::ReadStdIn)
+        })
+        .register_with(
+            "template",
+            "format",
+            "Render records into text with a template",
+            || Arc::new(template::Template),
+        )
+        .register_with(
+            "throttle",
+            "flow",
+            "Rate-limit records passing through",
+            || Arc::new(throttle::Throttle),
+        )
+        .register_with(
+            "union",
+            "transform",
+            "Concatenate the input with earlier step outputs",
+            || Arc::new(union::Union),
+        )
+        .register_with("write_file", "io", "Write data to a file", || {
+            Arc::new(file::oxi::WriteFile)
+        })
+        .register_with(
+            "write_stdout",
+            "io",
+            "Write data to standard output",
+            || Arc::new(write_stdout::WriteStdOut),
+        );
+
+    #[cfg(feature = "parquet")]
+    registry
+        .register_with(
+            "read_parquet",
+            "io",
+            "Read a Parquet file into JSON records",
+            || Arc::new(parquet::oxi::ReadParquet),
+        )
+        .register_with(
+            "write_parquet",
+            "io",
+            "Write JSON records to a Parquet file",
+            || Arc::new(parquet::oxi::WriteParquet),
+        );
+
+    registry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtins_match_the_published_list() {
+        let names: Vec<&str> = OxiRegistry::builtins().names().collect();
+        assert_eq!(names, crate::oxis::BUILTIN_OXIS);
+        for entry in OxiRegistry::builtins().entries() {
+            assert_eq!(entry.create().name(), entry.name);
+            assert!(!entry.summary.is_empty(), "{} has no summary", entry.name);
+        }
+    }
+}
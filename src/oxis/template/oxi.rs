@@ -0,0 +1,485 @@
+use crate::oxis::prelude::*;
+use crate::types::{FieldSchema, FieldType, OxiSchema};
+use async_trait::async_trait;
+use handlebars::Handlebars;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Template renders each input record through a text template
+pub struct Template;
+
+/// Name under which the `variables` config block is exposed to templates
+const VARS_KEY: &str = "vars";
+
+/// Options accepted by [`Template`]
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct TemplateConfig {
+    template: Option<String>,
+    template_file: Option<String>,
+    engine: Engine,
+    output: OutputMode,
+    separator: String,
+    on_missing: OnMissing,
+    variables: Option<Value>,
+}
+
+impl Default for TemplateConfig {
+    fn default() -> Self {
+        Self {
+            template: None,
+            template_file: None,
+            engine: Engine::default(),
+            output: OutputMode::default(),
+            separator: "\n".to_string(),
+            on_missing: OnMissing::default(),
+            variables: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Engine {
+    #[default]
+    Handlebars,
+    Simple,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OutputMode {
+    #[default]
+    Text,
+    Array,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum OnMissing {
+    #[default]
+    Error,
+    Empty,
+}
+
+#[async_trait]
+impl Oxi for Template {
+    fn name(&self) -> &str {
+        "template"
+    }
+
+    fn output_types(&self) -> Vec<OxiDataType> {
+        vec![OxiDataType::Text, OxiDataType::Json]
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Modify {
+            description: "Renders structured records into text using a template".to_string(),
+        }
+    }
+
+    // Text output is joined across all records
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    fn watched_paths(&self, config: &OxiConfig) -> Vec<std::path::PathBuf> {
+        config
+            .get_string("template_file")
+            .map(Into::into)
+            .into_iter()
+            .collect()
+    }
+
+    // Only simple placeholders name record fields directly; handlebars blocks
+    // change the context, so those templates declare no requirements
+    fn required_input_schema(&self, config: &OxiConfig) -> Option<OxiSchema> {
+        let options: TemplateConfig = config.deserialize_into().ok()?;
+        if !matches!(options.engine, Engine::Simple) || options.on_missing != OnMissing::Error {
+            return None;
+        }
+
+        let mut schema = OxiSchema::empty();
+        for caps in placeholder().captures_iter(options.template.as_ref()?) {
+            let path: Vec<&str> = caps[1].split('.').collect();
+            let is_variable = options.variables.is_some() && path[0] == VARS_KEY;
+            if path[0] != "this" && !is_variable {
+                require_path(&mut schema.fields, &path);
+            }
+        }
+        Some(schema)
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        ProcessingLimits {
+            supported_input_types: vec![OxiDataType::Json],
+            ..ProcessingLimits::default()
+        }
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(r#"
+            type: object
+            properties:
+              template:
+                type: string
+                description: "Inline template; ${step.path} references are resolved before rendering"
+              template_file:
+                type: string
+                description: "Path to a template file (used when 'template' is not set)"
+              engine:
+                type: string
+                enum: ["handlebars", "simple"]
+                default: "handlebars"
+                description: "Template engine (handlebars: full syntax, simple: {{field.path}} substitution only)"
+              output:
+                type: string
+                enum: ["text", "array"]
+                default: "text"
+                description: "Emit a single concatenated text or a JSON array of rendered strings"
+              separator:
+                type: string
+                default: "\n"
+                description: "Separator between rendered records in text output"
+              on_missing:
+                type: string
+                enum: ["error", "empty"]
+                default: "error"
+                description: "Fail on missing fields or render them as empty strings"
+              variables:
+                type: object
+                description: "Extra values available to every record under 'vars' (e.g. {{vars.title}})"
+        "#).unwrap()
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let json_data = input.data().as_json().map_err(|_| OxiError::TypeMismatch {
+            expected: "JSON".to_string(),
+            actual: input.data().data_type().to_string(),
+            step: "template".to_string(),
+        })?;
+
+        let options: TemplateConfig = config
+            .deserialize_into()
+            .map_err(|e| OxiError::ConfigError(e.to_string()))?;
+        let template = load_template(&options).await?;
+        let strict = options.on_missing == OnMissing::Error;
+
+        let records: Vec<&Value> = match json_data {
+            Value::Array(items) => items.iter().collect(),
+            single => vec![single],
+        };
+
+        let renderer = Renderer::new(options.engine, &template, strict)?;
+        let mut rendered = Vec::with_capacity(records.len());
+        for (index, record) in records.into_iter().enumerate() {
+            let context = with_variables(record, options.variables.as_ref());
+            let text = renderer.render(&context).map_err(|e| {
+                OxiError::ExecutionError(format!(
+                    "Failed to render template for record {index}: {e}"
+                ))
+            })?;
+            rendered.push(text);
+        }
+
+        match options.output {
+            OutputMode::Text => Ok(OxiData::from_text(rendered.join(&options.separator))),
+            OutputMode::Array => Ok(OxiData::from_json(Value::Array(
+                rendered.into_iter().map(Value::String).collect(),
+            ))),
+        }
+    }
+}
+
+/// Read the template from inline config or from `template_file`
+async fn load_template(options: &TemplateConfig) -> Result<String, OxiError> {
+    if let Some(template) = &options.template {
+        return Ok(template.clone());
+    }
+
+    let path = options.template_file.as_ref().ok_or_else(|| {
+        OxiError::ConfigError("Either 'template' or 'template_file' must be set".to_string())
+    })?;
+
+    tokio::fs::read_to_string(path)
+        .await
+        .map_err(|e| OxiError::ConfigError(format!("Failed to read template file '{path}': {e}")))
+}
+
+/// Attach the shared variables to an object record without shadowing its own fields
+fn with_variables(record: &Value, variables: Option<&Value>) -> Value {
+    match (record, variables) {
+        (Value::Object(map), Some(vars)) if !map.contains_key(VARS_KEY) => {
+            let mut map = map.clone();
+            map.insert(VARS_KEY.to_string(), vars.clone());
+            Value::Object(map)
+        }
+        _ => record.clone(),
+    }
+}
+
+/// Compiled template for one of the supported engines
+enum Renderer<'a> {
+    Handlebars(Box<Handlebars<'a>>),
+    Simple { template: String, strict: bool },
+}
+
+impl Renderer<'_> {
+    const TEMPLATE_NAME: &'static str = "record";
+
+    fn new(engine: Engine, template: &str, strict: bool) -> Result<Self, OxiError> {
+        match engine {
+            Engine::Handlebars => {
+                let mut registry = Handlebars::new();
+                registry.set_strict_mode(strict);
+                // Rendered output is plain text (emails, Markdown, SQL), not HTML
+                registry.register_escape_fn(handlebars::no_escape);
+                registry
+                    .register_template_string(Self::TEMPLATE_NAME, template)
+                    .map_err(|e| OxiError::ConfigError(format!("Invalid template: {e}")))?;
+                Ok(Renderer::Handlebars(Box::new(registry)))
+            }
+            Engine::Simple => Ok(Renderer::Simple {
+                template: template.to_string(),
+                strict,
+            }),
+        }
+    }
+
+    fn render(&self, record: &Value) -> anyhow::Result<String> {
+        match self {
+            Renderer::Handlebars(registry) => Ok(registry.render(Self::TEMPLATE_NAME, record)?),
+            Renderer::Simple { template, strict } => render_simple(template, record, *strict),
+        }
+    }
+}
+
+/// Matches a simple-engine `{{field.path}}` placeholder
+fn placeholder() -> Regex {
+    Regex::new(r"\{\{\s*([A-Za-z0-9_.]+)\s*\}\}").unwrap()
+}
+
+/// Record `path` as required; a null leaf renders empty, but parents must be objects
+fn require_path(fields: &mut HashMap<String, FieldSchema>, path: &[&str]) {
+    let Some((name, rest)) = path.split_first() else {
+        return;
+    };
+    let field = fields.entry(name.to_string()).or_insert_with(|| {
+        let mut field = FieldSchema::new(FieldType::Unknown);
+        field.nullable = true;
+        field
+    });
+    if rest.is_empty() {
+        return;
+    }
+
+    if field.field_type == FieldType::Unknown {
+        field.field_type = FieldType::Object(HashMap::new());
+    }
+    field.nullable = false;
+    if let FieldType::Object(children) = &mut field.field_type {
+        require_path(children, rest);
+    }
+}
+
+/// Replace `{{field.path}}` placeholders with values looked up in the record
+fn render_simple(template: &str, record: &Value, strict: bool) -> anyhow::Result<String> {
+    let mut missing = None;
+
+    let rendered = placeholder().replace_all(template, |caps: &regex::Captures| {
+        let path = &caps[1];
+        let value = if path == "this" {
+            Some(record)
+        } else {
+            path.split('.')
+                .try_fold(record, |current, key| current.get(key))
+        };
+
+        match value {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => {
+                if value.is_none() && missing.is_none() {
+                    missing = Some(path.to_string());
+                }
+                String::new()
+            }
+            Some(other) => other.to_string(),
+        }
+    });
+
+    if strict {
+        if let Some(path) = missing {
+            anyhow::bail!("Field '{}' not found in record", path);
+        }
+    }
+
+    Ok(rendered.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> OxiData {
+        OxiData::from_json(serde_json::json!([
+            {"name": "Alice", "age": 30},
+            {"name": "Bob", "age": 25},
+            {"name": "Carol", "age": 41}
+        ]))
+    }
+
+    #[tokio::test]
+    async fn test_render_records_as_text() {
+        let config = OxiConfig::from_yaml_str(
+            r#"
+            template: "Hello {{name}} ({{age}})"
+            "#,
+        );
+
+        let result = Template.process(records(), &config).await.unwrap();
+        assert_eq!(
+            result.data.as_text().unwrap(),
+            "Hello Alice (30)\nHello Bob (25)\nHello Carol (41)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_render_records_as_array_with_variables() {
+        let config = OxiConfig::from_yaml_str(
+            r#"
+            template: "{{vars.greeting}}, {{name}}"
+            output: array
+            variables:
+              greeting: Hi
+            "#,
+        );
+
+        let result = Template.process(records(), &config).await.unwrap();
+        assert_eq!(
+            result.data.as_json().unwrap(),
+            &serde_json::json!(["Hi, Alice", "Hi, Bob", "Hi, Carol"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_field_errors_by_default() {
+        let config = OxiConfig::from_yaml_str(
+            r#"
+            template: "{{name}} <{{email}}>"
+            "#,
+        );
+
+        let result = Template.process(records(), &config).await;
+        assert!(matches!(result, Err(OxiError::ExecutionError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_missing_field_renders_empty() {
+        for engine in ["handlebars", "simple"] {
+            let config = OxiConfig::from_yaml_str(&format!(
+                r#"
+                template: "{{{{name}}}}:{{{{email}}}}"
+                engine: {engine}
+                on_missing: empty
+                separator: ","
+                "#
+            ));
+
+            let result = Template.process(records(), &config).await.unwrap();
+            assert_eq!(result.data.as_text().unwrap(), "Alice:,Bob:,Carol:");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simple_engine_nested_paths() {
+        let config = OxiConfig::from_yaml_str(
+            r#"
+            template: "{{ user.name }} lives in {{user.address.city}}"
+            engine: simple
+            "#,
+        );
+        let input = OxiData::from_json(serde_json::json!({
+            "user": {"name": "Dana", "address": {"city": "Oslo"}}
+        }));
+
+        let result = Template.process(input, &config).await.unwrap();
+        assert_eq!(result.data.as_text().unwrap(), "Dana lives in Oslo");
+    }
+
+    #[test]
+    fn test_required_input_schema_from_simple_placeholders() {
+        let required = Template
+            .required_input_schema(&OxiConfig::from_yaml_str(
+                r#"
+                template: "{{name}} in {{ user.address.city }} ({{vars.site}}) {{this}}"
+                engine: simple
+                variables: {site: main}
+                "#,
+            ))
+            .unwrap();
+
+        let mut names: Vec<&String> = required.fields.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["name", "user"]);
+        assert!(required.fields["name"].nullable);
+        let FieldType::Object(user) = &required.fields["user"].field_type else {
+            panic!("user should be an object");
+        };
+        assert!(!required.fields["user"].nullable);
+        assert!(matches!(user["address"].field_type, FieldType::Object(_)));
+
+        // Handlebars templates and lenient rendering declare nothing
+        assert!(Template
+            .required_input_schema(&OxiConfig::from_yaml_str("template: \"{{name}}\""))
+            .is_none());
+        assert!(Template
+            .required_input_schema(&OxiConfig::from_yaml_str(
+                "{template: \"{{name}}\", engine: simple, on_missing: empty}"
+            ))
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_step_references_resolved_in_template() {
+        use crate::config_resolver::ConfigResolver;
+        use crate::pipeline::PipelineStep;
+
+        let mut resolver = ConfigResolver::new();
+        resolver.add_step_output(
+            "meta".to_string(),
+            OxiData::from_json(serde_json::json!({"report": {"title": "Weekly"}})),
+        );
+
+        let step: PipelineStep = serde_yaml::from_str(
+            r#"
+            name: template
+            config:
+              template: "${meta.report.title}: {{name}}"
+              output: array
+            "#,
+        )
+        .unwrap();
+        let config = step.to_oxi_config(&resolver).unwrap();
+
+        let result = Template.process(records(), &config).await.unwrap();
+        assert_eq!(
+            result.data.as_json().unwrap(),
+            &serde_json::json!(["Weekly: Alice", "Weekly: Bob", "Weekly: Carol"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_template_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"- {{name}}").unwrap();
+
+        let config = OxiConfig::from_yaml_str(&format!(
+            "template_file: \"{}\"",
+            file.path().to_string_lossy()
+        ));
+
+        let result = Template.process(records(), &config).await.unwrap();
+        assert_eq!(result.data.as_text().unwrap(), "- Alice\n- Bob\n- Carol");
+    }
+}
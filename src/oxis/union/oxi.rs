@@ -0,0 +1,302 @@
+use crate::oxis::prelude::*;
+use crate::types::{MergePolicy, OxiSchema};
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Union concatenates several record sets into one JSON array
+///
+/// The sources are the step input followed by each entry of `inputs`, usually
+/// `${step_id}` references that resolve to an earlier step's output. Records
+/// keep their source order. Records missing a field that another source has
+/// get it as `null`, and the output schema is the sources' schemas merged
+/// with [`MergePolicy::WidenToMixed`].
+pub struct Union;
+
+/// Options accepted by [`Union`]
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct UnionConfig {
+    /// Extra sources, as JSON text or inline YAML
+    inputs: Vec<serde_yaml::Value>,
+    /// Whether the step input is the first source
+    include_input: bool,
+    /// Add missing fields as `null` so every record has the same keys
+    fill_missing: bool,
+}
+
+impl Default for UnionConfig {
+    fn default() -> Self {
+        Self {
+            inputs: Vec::new(),
+            include_input: true,
+            fill_missing: true,
+        }
+    }
+}
+
+/// Records of one source, and the schema they carried or were inferred with
+struct Source {
+    records: Vec<Value>,
+    schema: OxiSchema,
+}
+
+impl Source {
+    fn from_json(value: Value, schema: Option<&OxiSchema>) -> Result<Self, OxiError> {
+        let schema = match schema {
+            Some(schema) if !schema.fields.is_empty() => schema.clone(),
+            _ => OxiSchema::infer_from_data(&Data::Json(value.clone()))?,
+        };
+        let records = match value {
+            Value::Array(records) => records,
+            Value::Null => Vec::new(),
+            record => vec![record],
+        };
+        Ok(Self { records, schema })
+    }
+
+    fn from_input(input: &OxiData) -> Result<Option<Self>, OxiError> {
+        match input.data() {
+            Data::Empty => Ok(None),
+            Data::Json(value) => Self::from_json(value.clone(), Some(input.schema())).map(Some),
+            other => Err(OxiError::TypeMismatch {
+                expected: "JSON array of records".to_string(),
+                actual: other.data_type().to_string(),
+                step: "union".to_string(),
+            }),
+        }
+    }
+
+    /// A configured source: referenced step outputs arrive as JSON text
+    fn from_config(index: usize, value: &serde_yaml::Value) -> Result<Self, OxiError> {
+        let json = match value {
+            serde_yaml::Value::String(text) => serde_json::from_str(text).map_err(|e| {
+                OxiError::ConfigError(format!("union input {index} is not valid JSON: {e}"))
+            })?,
+            other => serde_json::to_value(other).map_err(|e| {
+                OxiError::ConfigError(format!("union input {index} is not JSON-compatible: {e}"))
+            })?,
+        };
+        Self::from_json(json, None)
+    }
+}
+
+#[async_trait]
+impl Oxi for Union {
+    fn name(&self) -> &str {
+        "union"
+    }
+
+    fn config_schema(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(
+            r#"
+            type: object
+            properties:
+              inputs:
+                type: array
+                description: "Sources appended after the step input, typically \"${step_id}\" references to earlier steps"
+              include_input:
+                type: boolean
+                default: true
+                description: "Use the step input as the first source"
+              fill_missing:
+                type: boolean
+                default: true
+                description: "Give records every field seen in any source, set to null where absent"
+        "#,
+        )
+        .unwrap()
+    }
+
+    fn processing_limits(&self) -> ProcessingLimits {
+        ProcessingLimits {
+            supported_input_types: vec![OxiDataType::Json, OxiDataType::Empty],
+            ..ProcessingLimits::default()
+        }
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Modify {
+            description: "Merges the schemas of all sources".to_string(),
+        }
+    }
+
+    // Each chunk would otherwise get its own copy of the referenced sources
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
+    fn output_schema(
+        &self,
+        input_schema: Option<&OxiSchema>,
+        _config: &OxiConfig,
+    ) -> anyhow::Result<OxiSchema> {
+        // The other sources are only known at runtime, so any input field may be absent
+        let mut schema = input_schema.cloned().unwrap_or_else(OxiSchema::empty);
+        for field in schema.fields.values_mut() {
+            field.nullable = true;
+        }
+        Ok(schema)
+    }
+
+    async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let options: UnionConfig = config
+            .deserialize_into()
+            .map_err(|e| OxiError::ConfigError(e.to_string()))?;
+
+        let mut sources = Vec::with_capacity(options.inputs.len() + 1);
+        if options.include_input {
+            sources.extend(Source::from_input(&input)?);
+        }
+        for (index, value) in options.inputs.iter().enumerate() {
+            sources.push(Source::from_config(index, value)?);
+        }
+
+        let counts: Vec<usize> = sources.iter().map(|source| source.records.len()).collect();
+        let mut schema: Option<OxiSchema> = None;
+        let mut records = Vec::with_capacity(counts.iter().sum());
+        for source in sources {
+            schema = Some(match schema {
+                Some(merged) => merged.merge(&source.schema, MergePolicy::WidenToMixed),
+                None => source.schema,
+            });
+            records.extend(source.records);
+        }
+        if options.fill_missing {
+            fill_missing_fields(&mut records);
+        }
+
+        let mut output = OxiData::with_schema(
+            Data::Json(Value::Array(records)),
+            schema.unwrap_or_else(OxiSchema::empty),
+        );
+        output.set_meta("union_source_counts", counts);
+        Ok(output)
+    }
+}
+
+/// Give every object record the superset of keys, in first-seen order
+fn fill_missing_fields(records: &mut [Value]) {
+    let mut seen = HashSet::new();
+    let mut fields = Vec::new();
+    for record in records.iter().filter_map(Value::as_object) {
+        for key in record.keys() {
+            if seen.insert(key.as_str()) {
+                fields.push(key.clone());
+            }
+        }
+    }
+
+    for record in records.iter_mut() {
+        let Value::Object(object) = record else {
+            continue;
+        };
+        if object.len() == fields.len() {
+            continue;
+        }
+        let mut filled = Map::with_capacity(fields.len());
+        for field in &fields {
+            let value = object.remove(field).unwrap_or(Value::Null);
+            filled.insert(field.clone(), value);
+        }
+        *object = filled;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FieldType;
+    use serde_json::json;
+
+    fn config(inputs: Vec<serde_yaml::Value>) -> OxiConfig {
+        let mut config = OxiConfig::default();
+        config
+            .values
+            .insert("inputs".to_string(), serde_yaml::Value::Sequence(inputs));
+        config
+    }
+
+    #[tokio::test]
+    async fn test_union_with_overlapping_fields() {
+        let input = OxiData::from_json(json!([{"id": 1, "amount": 10}]));
+        let other = json!([{"id": 2, "amount": 12.5}]).to_string();
+
+        let output = Union
+            .process(input, &config(vec![other.into()]))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.data().as_json().unwrap(),
+            &json!([{"id": 1, "amount": 10}, {"id": 2, "amount": 12.5}])
+        );
+        let amount = &output.schema().fields["amount"];
+        assert_eq!(amount.field_type, FieldType::Float);
+        assert!(!amount.nullable);
+        assert_eq!(output.get_meta("union_source_counts"), Some(&json!([1, 1])));
+    }
+
+    #[tokio::test]
+    async fn test_union_with_disjoint_fields_null_fills() {
+        let input = OxiData::from_json(json!([{"id": 1, "user": "ada"}]));
+        let other = json!([{"sku": "A-1"}, {"sku": "B-2", "id": 3}]).to_string();
+
+        let output = Union
+            .process(input, &config(vec![other.into()]))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.data().as_json().unwrap(),
+            &json!([
+                {"id": 1, "user": "ada", "sku": null},
+                {"id": null, "user": null, "sku": "A-1"},
+                {"id": 3, "user": null, "sku": "B-2"}
+            ])
+        );
+        let fields = &output.schema().fields;
+        assert!(fields["user"].nullable);
+        assert!(fields["sku"].nullable);
+        assert!(fields["id"].nullable);
+    }
+
+    #[tokio::test]
+    async fn test_union_without_input_or_fill() {
+        let mut config = config(vec![
+            json!({"id": 1}).to_string().into(),
+            serde_yaml::from_str("[{name: grace}]").unwrap(),
+        ]);
+        config
+            .values
+            .insert("include_input".to_string(), false.into());
+        config
+            .values
+            .insert("fill_missing".to_string(), false.into());
+
+        let output = Union
+            .process(OxiData::from_json(json!([{"id": 0}])), &config)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output.data().as_json().unwrap(),
+            &json!([{"id": 1}, {"name": "grace"}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_sources() {
+        let err = Union
+            .process(OxiData::empty(), &config(vec!["not json".into()]))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("union input 0"), "{err}");
+
+        let err = Union
+            .process(OxiData::from_text("a,b".to_string()), &config(Vec::new()))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("JSON array"), "{err}");
+    }
+}
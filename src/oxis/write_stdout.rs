@@ -18,6 +18,10 @@ impl Oxi for WriteStdOut {
                 enum: [auto, text, json, yaml]
                 description: "Output format"
                 default: auto
+              pretty:
+                type: boolean
+                description: "Print JSON data indented rather than compact"
+                default: true
         "#,
         )
         .unwrap()
@@ -54,8 +58,14 @@ impl Oxi for WriteStdOut {
         SchemaStrategy::Passthrough
     }
 
+    // Output is written once, in order
+    fn requires_full_batch(&self) -> bool {
+        true
+    }
+
     async fn process(&self, input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
         let format = config.get_string_or("format", "auto");
+        let pretty = config.get_bool_or("pretty", true);
 
         match format.as_str() {
             "text" => {
@@ -68,25 +78,27 @@ impl Oxi for WriteStdOut {
                 println!("{text}");
             }
             "json" => {
-                let value = input
+                input
                     .data()
                     .as_json()
                     .map_err(|e| OxiError::ValidationError {
                         details: format!("Failed to get JSON data: {e}"),
                     })?;
-                let json = serde_json::to_string_pretty(&value).map_err(|e| {
-                    OxiError::ValidationError {
-                        details: format!("Failed to serialize JSON: {e}"),
-                    }
-                })?;
+                let json =
+                    input
+                        .data()
+                        .to_text_with(pretty)
+                        .map_err(|e| OxiError::ValidationError {
+                            details: format!("Failed to serialize JSON: {e}"),
+                        })?;
                 println!("{json}");
             }
             _ => {
                 // Auto-detect based on input type
                 match &input.data {
                     Data::Text(text) => println!("{text}"),
-                    Data::Json(value) => {
-                        let json = serde_json::to_string_pretty(&value).map_err(|e| {
+                    Data::Json(_) => {
+                        let json = input.data().to_text_with(pretty).map_err(|e| {
                             OxiError::ValidationError {
                                 details: format!("Failed to serialize JSON: {e}"),
                             }
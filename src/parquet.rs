@@ -0,0 +1,203 @@
+//! Parquet files (enabled with the `parquet` feature)
+//!
+//! Builds on the Arrow interop: JSON records become a record batch, which the
+//! `parquet` crate's Arrow writer encodes as a Snappy-compressed file held in
+//! memory. Reading goes the other way and keeps the file's column types as the
+//! `OxiSchema`.
+
+use crate::types::{Data, FieldType, OxiData, OxiSchema};
+use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use ::parquet::arrow::{parquet_to_arrow_schema, ArrowWriter};
+use ::parquet::basic::Compression;
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::schema::types::SchemaDescriptor;
+use anyhow::{anyhow, Context, Result};
+use bytes::Bytes;
+
+impl OxiData {
+    /// Encode a JSON array of records as an in-memory Parquet file
+    ///
+    /// Every record is scanned to find the columns and which of them hold
+    /// nulls. Column types come from this data's schema where it declares
+    /// them (so `coerce_types` can mark timestamps), and from the values
+    /// otherwise. `Mixed` and `Unknown` columns are stored as text.
+    pub fn to_parquet(&self) -> Result<Vec<u8>> {
+        let rows = match self.data.as_json() {
+            Ok(serde_json::Value::Array(rows)) => rows,
+            _ => {
+                return Err(anyhow!(
+                    "Parquet conversion requires a JSON array of records, got {}",
+                    self.data.data_type()
+                ))
+            }
+        };
+
+        let mut schema = OxiSchema::infer_from_data_sampled(&self.data, rows.len().max(1))?;
+        if schema.fields.is_empty() {
+            return Err(anyhow!("Parquet needs at least one column, got no fields"));
+        }
+        for (name, field) in schema.fields.iter_mut() {
+            if let Some(declared) = self.schema.fields.get(name) {
+                field.field_type = declared.field_type.clone();
+                field.nullable |= declared.nullable;
+            }
+        }
+
+        let text_fields: Vec<&String> = schema
+            .fields
+            .iter()
+            .filter(|(_, field)| matches!(field.field_type, FieldType::Mixed | FieldType::Unknown))
+            .map(|(name, _)| name)
+            .collect();
+        let texts;
+        let data = if text_fields.is_empty() {
+            &self.data
+        } else {
+            texts = Data::Json(serde_json::Value::Array(
+                rows.iter()
+                    .map(|row| values_as_text(row, &text_fields))
+                    .collect(),
+            ));
+            &texts
+        };
+
+        let batch = data
+            .to_arrow(&schema.to_arrow_schema())
+            .context("Records do not fit the Parquet columns")?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(buffer)
+    }
+
+    /// Decode a Parquet file into a JSON array of records
+    ///
+    /// The schema is taken from the file's columns rather than inferred.
+    pub fn from_parquet(bytes: &[u8]) -> Result<OxiData> {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(Bytes::copy_from_slice(bytes))
+            .context("Not a readable Parquet file")?;
+        let mut schema = OxiSchema::from_arrow_schema(builder.schema());
+        schema.metadata.created_by = "parquet".to_string();
+
+        let mut rows = Vec::new();
+        for batch in builder.build()? {
+            if let Data::Json(serde_json::Value::Array(batch_rows)) = Data::from_arrow(batch?)? {
+                rows.extend(batch_rows);
+            }
+        }
+
+        Ok(OxiData::with_schema(
+            Data::Json(serde_json::Value::Array(rows)),
+            schema,
+        ))
+    }
+}
+
+/// Copy of `row` with the given fields' non-null values rendered as JSON text
+fn values_as_text(row: &serde_json::Value, fields: &[&String]) -> serde_json::Value {
+    let mut row = row.clone();
+    if let serde_json::Value::Object(values) = &mut row {
+        for name in fields {
+            if let Some(value) = values.get_mut(name.as_str()) {
+                if !value.is_null() && !value.is_string() {
+                    *value = serde_json::Value::String(value.to_string());
+                }
+            }
+        }
+    }
+    row
+}
+
+impl OxiSchema {
+    /// Map the columns of a Parquet file onto Oxi field types
+    ///
+    /// Physical and logical types are resolved the way the Arrow reader
+    /// resolves them, so this matches the schema `OxiData::from_parquet`
+    /// produces for the same file.
+    pub fn from_parquet_schema(schema: &SchemaDescriptor) -> Result<Self> {
+        let arrow = parquet_to_arrow_schema(schema, None)?;
+        let mut oxi_schema = Self::from_arrow_schema(&arrow);
+        oxi_schema.metadata.created_by = "parquet".to_string();
+        Ok(oxi_schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FieldSchema;
+    use ::parquet::file::reader::{FileReader, SerializedFileReader};
+    use serde_json::json;
+
+    fn records() -> serde_json::Value {
+        json!([
+            {"id": 1, "name": "alice", "score": 9.5, "active": true, "seen": "2024-03-01T12:00:00Z"},
+            {"id": 2, "name": null, "score": 7.25, "active": false, "seen": "2024-03-02T08:30:00Z"}
+        ])
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let mut data = OxiData::from_json(records());
+        let mut seen = FieldSchema::new(FieldType::DateTime);
+        seen.nullable = false;
+        data.schema.add_field("seen".to_string(), seen);
+
+        let bytes = data.to_parquet().unwrap();
+        assert_eq!(&bytes[..4], b"PAR1");
+
+        let restored = OxiData::from_parquet(&bytes).unwrap();
+        assert_eq!(restored.data.as_json().unwrap(), &records());
+        assert_eq!(restored.schema.fields["id"].field_type, FieldType::Integer);
+        assert_eq!(restored.schema.fields["score"].field_type, FieldType::Float);
+        assert_eq!(
+            restored.schema.fields["seen"].field_type,
+            FieldType::DateTime
+        );
+        assert!(restored.schema.fields["name"].nullable);
+        assert!(!restored.schema.fields["id"].nullable);
+    }
+
+    #[test]
+    fn test_nulls_beyond_inference_sample() {
+        // The null sits past the default sample, so only a full scan sees it
+        let mut rows: Vec<serde_json::Value> =
+            (0..150).map(|i| json!({"id": i, "note": "ok"})).collect();
+        rows.push(json!({"id": 150, "note": null}));
+        let data = OxiData::from_json(serde_json::Value::Array(rows));
+
+        let restored = OxiData::from_parquet(&data.to_parquet().unwrap()).unwrap();
+        assert_eq!(restored.data.as_json().unwrap()[150]["note"], json!(null));
+        assert!(restored.schema.fields["note"].nullable);
+    }
+
+    #[test]
+    fn test_parquet_schema_mapping_and_errors() {
+        let bytes = OxiData::from_json(records()).to_parquet().unwrap();
+        let reader = SerializedFileReader::new(Bytes::from(bytes)).unwrap();
+        let schema =
+            OxiSchema::from_parquet_schema(reader.metadata().file_metadata().schema_descr())
+                .unwrap();
+        assert_eq!(schema.fields["active"].field_type, FieldType::Boolean);
+        assert_eq!(schema.fields["name"].field_type, FieldType::String);
+        assert_eq!(schema.metadata.created_by, "parquet");
+
+        let mixed = OxiData::from_json(json!([{"v": 1}, {"v": "a"}, {"v": {"k": true}}]));
+        let restored = OxiData::from_parquet(&mixed.to_parquet().unwrap()).unwrap();
+        assert_eq!(
+            restored.data.as_json().unwrap(),
+            &json!([{"v": "1"}, {"v": "a"}, {"v": "{\"k\":true}"}])
+        );
+
+        assert!(OxiData::from_text("id\n1".to_string())
+            .to_parquet()
+            .is_err());
+        assert!(OxiData::from_json(json!([])).to_parquet().is_err());
+        assert!(OxiData::from_parquet(b"not parquet").is_err());
+    }
+}
@@ -1,529 +0,0 @@
-use crate::config_resolver::ConfigResolver;
-use crate::oxis::batch::oxi::Batch;
-use crate::oxis::csv::oxi::FormatCsv;
-use crate::oxis::file::oxi::{ReadFile, WriteFile};
-use crate::oxis::flatten::oxi::Flatten;
-use crate::oxis::format_json::oxi::FormatJson;
-use crate::oxis::json_select::JsonSelect;
-use crate::oxis::parse_json::oxi::ParseJson;
-use crate::oxis::read_stdin::ReadStdIn;
-use crate::oxis::write_stdout::WriteStdOut;
-use crate::state::manager::StateManager;
-use crate::state::pipeline_tracker::PipelineTracker;
-use crate::types::OxiData;
-use crate::Oxi;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
-use tokio::time::{timeout, Duration};
-
-/// Pipeline configuration loaded from YAML
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Pipeline {
-    /// List of pipeline steps
-    pub pipeline: Vec<PipelineStep>,
-
-    /// Pipeline metadata
-    pub metadata: Option<PipelineMetadata>,
-}
-
-/// A single step in the pipeline
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PipelineStep {
-    /// Name of the Oxi to execute
-    pub name: String,
-
-    /// Optional ID for this step (for referencing)
-    pub id: Option<String>,
-
-    /// Configuration for this step
-    #[serde(default)]
-    pub config: HashMap<String, serde_yaml::Value>,
-
-    /// Whether to continue pipeline execution if this step fails
-    #[serde(default)]
-    pub continue_on_error: bool,
-
-    /// Maximum number of retry attempts
-    #[serde(default)]
-    pub retry_attempts: u32,
-
-    /// Timeout in seconds for this step
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub timeout_seconds: Option<u64>,
-}
-
-/// Result of a pipeline step execution
-#[derive(Debug)]
-pub struct StepResult {
-    pub step_id: String,
-    pub success: bool,
-    pub data: Option<OxiData>,
-    pub error: Option<String>,
-    pub retry_count: u32,
-    pub duration_ms: u64,
-}
-
-/// Overall pipeline execution result
-#[derive(Debug)]
-pub struct PipelineResult {
-    pub success: bool,
-    pub steps_executed: u32,
-    pub steps_failed: u32,
-    pub steps_skipped: u32,
-    pub total_duration_ms: u64,
-    pub step_results: Vec<StepResult>,
-    pub final_data: Option<OxiData>,
-    pub pipeline_id: Option<String>,
-    pub run_id: Option<String>,
-    pub state_tracking_enabled: bool,
-}
-
-/// Pipeline metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PipelineMetadata {
-    /// Pipeline name
-    pub name: Option<String>,
-
-    /// Pipeline description
-    pub description: Option<String>,
-
-    /// Pipeline version
-    pub version: Option<String>,
-
-    /// Pipeline author
-    pub author: Option<String>,
-}
-
-impl Pipeline {
-    /// Load a pipeline from a YAML file
-    pub fn load_from_file(path: &str) -> anyhow::Result<Self> {
-        let content = fs::read_to_string(path)
-            .map_err(|e| anyhow::anyhow!("Failed to read pipeline file '{}': {}", path, e))?;
-
-        let pipeline: Pipeline = serde_yaml::from_str(&content)
-            .map_err(|e| anyhow::anyhow!("Failed to parse pipeline YAML '{}': {}", path, e))?;
-
-        Ok(pipeline)
-    }
-
-    /// Get the number of steps in this pipeline
-    pub fn step_count(&self) -> usize {
-        self.pipeline.len()
-    }
-
-    /// Get pipeline name from metadata or default
-    pub fn name(&self) -> String {
-        self.metadata
-            .as_ref()
-            .and_then(|m| m.name.as_ref())
-            .cloned()
-            .unwrap_or_else(|| "Unnamed Pipeline".to_string())
-    }
-
-    /// Get pipeline description from metadata
-    pub fn description(&self) -> Option<String> {
-        self.metadata
-            .as_ref()
-            .and_then(|m| m.description.as_ref())
-            .cloned()
-    }
-
-    /// Execute the entire pipeline with enhanced error handling
-    pub async fn execute_with_retries(
-        &self,
-        initial_data: OxiData,
-        resolver: &ConfigResolver,
-    ) -> PipelineResult {
-        self.execute_with_state_tracking(initial_data, resolver, None)
-            .await
-    }
-
-    /// Execute the pipeline with optional state tracking
-    pub async fn execute_with_state_tracking(
-        &self,
-        initial_data: OxiData,
-        resolver: &ConfigResolver,
-        state_manager: Option<StateManager>,
-    ) -> PipelineResult {
-        let start_time = std::time::Instant::now();
-        let mut current_data = initial_data;
-        let mut step_results = Vec::new();
-        let mut steps_executed = 0;
-        let mut steps_failed = 0;
-        let mut steps_skipped = 0;
-
-        println!("🚀 Starting pipeline execution: {}", self.name());
-
-        // Initialize state tracking if enabled
-        let tracker = if let Some(state_manager) = state_manager {
-            match PipelineTracker::new(state_manager, self).await {
-                Ok(tracker) => {
-                    println!(
-                        "📊 State tracking enabled for pipeline: {}",
-                        tracker.pipeline_id()
-                    );
-                    Some(tracker)
-                }
-                Err(e) => {
-                    println!("⚠️  Failed to initialize state tracking: {e}");
-                    None
-                }
-            }
-        } else {
-            None
-        };
-
-        for (index, step) in self.pipeline.iter().enumerate() {
-            println!(
-                "\n📋 Step {} of {}: '{}'",
-                index + 1,
-                self.pipeline.len(),
-                step.get_id()
-            );
-
-            // Start step tracking
-            if let Some(ref tracker) = tracker {
-                if let Err(e) = tracker.start_step(step.get_id()).await {
-                    println!("⚠️  Failed to start step tracking: {e}");
-                }
-            }
-
-            let step_result = step
-                .execute_with_retries(current_data.clone(), resolver)
-                .await;
-
-            // Complete step tracking
-            if let Some(ref tracker) = tracker {
-                if let Err(e) = tracker.complete_step(&step_result).await {
-                    println!("⚠️  Failed to complete step tracking: {e}");
-                }
-            }
-
-            if step_result.success {
-                if let Some(data) = step_result.data.clone() {
-                    current_data = data;
-                }
-                steps_executed += 1;
-
-                // Create checkpoint every few steps
-                if let Some(ref tracker) = tracker {
-                    if index % 3 == 0 {
-                        // Checkpoint every 3 steps
-                        if let Err(e) = tracker.create_checkpoint(&current_data).await {
-                            println!("⚠️  Failed to create checkpoint: {e}");
-                        }
-                    }
-                }
-            } else {
-                steps_failed += 1;
-
-                if step.continue_on_error {
-                    println!("⚠️  Step failed but continue_on_error is true, continuing...");
-                    // Continue with the same data
-                } else {
-                    println!("💥 Step failed and continue_on_error is false, stopping pipeline");
-                    step_results.push(step_result);
-
-                    // Mark remaining steps as skipped
-                    steps_skipped = self.pipeline.len() - index - 1;
-
-                    let total_duration = start_time.elapsed().as_millis() as u64;
-                    let (pipeline_id, run_id) = if let Some(ref tracker) = tracker {
-                        (
-                            Some(tracker.pipeline_id().to_string()),
-                            Some(tracker.run_id().to_string()),
-                        )
-                    } else {
-                        (None, None)
-                    };
-
-                    let result = PipelineResult {
-                        success: false,
-                        steps_executed,
-                        steps_failed,
-                        steps_skipped: steps_skipped as u32,
-                        total_duration_ms: total_duration,
-                        step_results,
-                        final_data: None,
-                        pipeline_id,
-                        run_id,
-                        state_tracking_enabled: tracker.is_some(),
-                    };
-
-                    // Complete pipeline tracking
-                    if let Some(ref tracker) = tracker {
-                        if let Err(e) = tracker.complete_pipeline(&result).await {
-                            println!("⚠️  Failed to complete pipeline tracking: {e}");
-                        }
-                    }
-
-                    return result;
-                }
-            }
-
-            step_results.push(step_result);
-
-            // Send heartbeat periodically
-            if let Some(ref tracker) = tracker {
-                if let Err(e) = tracker.send_heartbeat().await {
-                    println!("⚠️  Failed to send heartbeat: {e}");
-                }
-            }
-        }
-
-        let total_duration = start_time.elapsed().as_millis() as u64;
-        let success = steps_failed == 0;
-
-        if success {
-            println!("\n🎉 Pipeline completed successfully!");
-        } else {
-            println!("\n⚠️  Pipeline completed with {steps_failed} failed steps");
-        }
-
-        println!(
-            "📊 Summary: {steps_executed} executed, {steps_failed} failed, {steps_skipped} skipped"
-        );
-        println!("⏱️  Total time: {total_duration}ms");
-
-        let (pipeline_id, run_id) = if let Some(ref tracker) = tracker {
-            (
-                Some(tracker.pipeline_id().to_string()),
-                Some(tracker.run_id().to_string()),
-            )
-        } else {
-            (None, None)
-        };
-
-        let result = PipelineResult {
-            success,
-            steps_executed,
-            steps_failed,
-            steps_skipped: steps_skipped as u32,
-            total_duration_ms: total_duration,
-            step_results,
-            final_data: if success { Some(current_data) } else { None },
-            pipeline_id,
-            run_id,
-            state_tracking_enabled: tracker.is_some(),
-        };
-
-        // Complete pipeline tracking
-        if let Some(ref tracker) = tracker {
-            if let Err(e) = tracker.complete_pipeline(&result).await {
-                println!("⚠️  Failed to complete pipeline tracking: {e}");
-            }
-        }
-
-        result
-    }
-}
-
-impl PipelineStep {
-    /// Get the step ID, using the name as fallback
-    pub fn get_id(&self) -> &str {
-        self.id.as_ref().unwrap_or(&self.name)
-    }
-
-    /// Convert config HashMap to OxiConfig with configuration resolution
-    pub fn to_oxi_config(
-        &self,
-        resolver: &ConfigResolver,
-    ) -> anyhow::Result<crate::types::OxiConfig> {
-        let mut oxi_config = crate::types::OxiConfig::default();
-
-        for (key, value) in &self.config {
-            let resolved_value = resolver.resolve_value(value)?;
-            oxi_config.values.insert(key.clone(), resolved_value);
-        }
-
-        Ok(oxi_config)
-    }
-
-    /// Execute this step with enhanced error handling and retries
-    pub async fn execute_with_retries(
-        &self,
-        input: OxiData,
-        resolver: &ConfigResolver,
-    ) -> StepResult {
-        let start_time = std::time::Instant::now();
-        let step_id = self.get_id().to_string();
-
-        for attempt in 0..=self.retry_attempts {
-            println!(
-                "🔄 Executing step '{}' (attempt {} of {})",
-                step_id,
-                attempt + 1,
-                self.retry_attempts + 1
-            );
-
-            let result = if let Some(timeout_secs) = self.timeout_seconds {
-                // Execute with timeout
-                let duration = Duration::from_secs(timeout_secs);
-                match timeout(duration, self.execute_once(input.clone(), resolver)).await {
-                    Ok(result) => result,
-                    Err(_) => Err(anyhow::anyhow!(
-                        "Step timed out after {} seconds",
-                        timeout_secs
-                    )),
-                }
-            } else {
-                // Execute without timeout
-                self.execute_once(input.clone(), resolver).await
-            };
-
-            match result {
-                Ok(data) => {
-                    let duration = start_time.elapsed().as_millis() as u64;
-                    println!("✅ Step '{step_id}' completed successfully");
-                    return StepResult {
-                        step_id,
-                        success: true,
-                        data: Some(data),
-                        error: None,
-                        retry_count: attempt,
-                        duration_ms: duration,
-                    };
-                }
-                Err(e) => {
-                    if attempt < self.retry_attempts {
-                        println!(
-                            "⚠️  Step '{}' failed (attempt {}): {}. Retrying...",
-                            step_id,
-                            attempt + 1,
-                            e
-                        );
-                        tokio::time::sleep(Duration::from_millis(1000 * (attempt + 1) as u64))
-                            .await;
-                    } else {
-                        let duration = start_time.elapsed().as_millis() as u64;
-                        println!(
-                            "❌ Step '{}' failed after {} attempts: {}",
-                            step_id,
-                            attempt + 1,
-                            e
-                        );
-                        return StepResult {
-                            step_id,
-                            success: false,
-                            data: None,
-                            error: Some(e.to_string()),
-                            retry_count: attempt,
-                            duration_ms: duration,
-                        };
-                    }
-                }
-            }
-        }
-
-        unreachable!()
-    }
-
-    /// Execute the step once (internal helper)
-    async fn execute_once(
-        &self,
-        input: OxiData,
-        resolver: &ConfigResolver,
-    ) -> anyhow::Result<OxiData> {
-        let config = self.to_oxi_config(resolver)?;
-
-        // Import and execute the specific Oxi
-        let result = match self.name.as_str() {
-            "batch" => {
-                let oxi = Batch;
-                oxi.process(input, &config).await
-            }
-            "read_file" => {
-                let oxi = ReadFile;
-                oxi.process(input, &config).await
-            }
-            "write_file" => {
-                let oxi = WriteFile;
-                oxi.process(input, &config).await
-            }
-            "parse_json" => {
-                let oxi = ParseJson;
-                oxi.process(input, &config).await
-            }
-            "format_json" => {
-                let oxi = FormatJson;
-                oxi.process(input, &config).await
-            }
-            "format_csv" => {
-                let oxi = FormatCsv;
-                oxi.process(input, &config).await
-            }
-            "read_stdin" => {
-                let oxi = ReadStdIn;
-                oxi.process(input, &config).await
-            }
-            "write_stdout" => {
-                let oxi = WriteStdOut;
-                oxi.process(input, &config).await
-            }
-            "flatten" => {
-                let oxi = Flatten;
-                oxi.process(input, &config).await
-            }
-            "json_select" => {
-                let oxi = JsonSelect;
-                oxi.process(input, &config).await
-            }
-            _ => Err(crate::error::OxiError::UnknownOxi(self.name.clone())),
-        }?;
-
-        Ok(result)
-    }
-
-    /// Convert config HashMap to OxiConfig without resolution
-    pub fn to_oxi_config_simple(&self) -> crate::types::OxiConfig {
-        let mut oxi_config = crate::types::OxiConfig::default();
-        for (key, value) in &self.config {
-            oxi_config.values.insert(key.clone(), value.clone());
-        }
-        oxi_config
-    }
-}
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::Write;
-    use tempfile::NamedTempFile;
-
-    #[test]
-    fn test_load_pipeline() {
-        let yaml_content = r#"
-pipeline:
-  - name: read_file
-    id: reader
-    config:
-      path: "input.json"
-  - name: parse_json
-    id: parser
-  - name: format_csv
-    id: formatter
-    config:
-      headers: true
-      delimiter: ","
-  - name: write_file
-    id: writer
-    config:
-      path: "output.csv"
-
-metadata:
-  name: "Test Pipeline"
-  description: "A test pipeline"
-  version: "1.0.0"
-"#;
-
-        let mut temp_file = NamedTempFile::new().unwrap();
-        temp_file.write_all(yaml_content.as_bytes()).unwrap();
-
-        let pipeline = Pipeline::load_from_file(temp_file.path().to_str().unwrap()).unwrap();
-
-        assert_eq!(pipeline.step_count(), 4);
-        assert_eq!(pipeline.name(), "Test Pipeline");
-        assert_eq!(pipeline.pipeline[0].name, "read_file");
-        assert_eq!(pipeline.pipeline[0].get_id(), "reader");
-    }
-}
@@ -0,0 +1,239 @@
+//! Build pipelines in code instead of loading them from YAML
+//!
+//! A built [`Pipeline`] runs exactly like a loaded one. Steps can name a
+//! built-in Oxi or carry their own instance, which lets an embedding
+//! application run Oxis it never registers:
+//!
+//! ```
+//! use oxide_flow::pipeline::builder::PipelineBuilder;
+//! use oxide_flow::types::OxiConfig;
+//!
+//! let pipeline = PipelineBuilder::new("orders")
+//!     .step("reader", "read_file", OxiConfig::builder().set("path", "orders.json").build())
+//!     .step_with(|s| s.retry(3).timeout_secs(30))
+//!     .step("parser", "parse_json", OxiConfig::default())
+//!     .step("formatter", "format_csv", OxiConfig::default())
+//!     .step_with(|s| s.continue_on_error())
+//!     .build();
+//!
+//! assert_eq!(pipeline.name(), "orders");
+//! assert_eq!(pipeline.pipeline[0].retry_attempts, 3);
+//! assert!(pipeline.to_yaml().unwrap().contains("timeout_seconds: 30"));
+//! ```
+
+use super::resilience::CircuitBreakerConfig;
+use super::{FailureHandler, Pipeline, PipelineMetadata, PipelineStep, StepOxi, StepResult};
+use crate::types::OxiConfig;
+use crate::Oxi;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The Oxi a builder step runs
+#[derive(Clone, Debug)]
+pub enum OxiRef {
+    /// A built-in Oxi, looked up by name when the step runs
+    Named(String),
+    /// An in-process instance, which needs no registration
+    Instance(StepOxi),
+}
+
+impl From<&str> for OxiRef {
+    fn from(name: &str) -> Self {
+        OxiRef::Named(name.to_string())
+    }
+}
+
+impl From<String> for OxiRef {
+    fn from(name: String) -> Self {
+        OxiRef::Named(name)
+    }
+}
+
+impl From<Arc<dyn Oxi + Send + Sync>> for OxiRef {
+    fn from(oxi: Arc<dyn Oxi + Send + Sync>) -> Self {
+        OxiRef::Instance(StepOxi(oxi))
+    }
+}
+
+impl<T: Oxi + Send + Sync + 'static> From<Arc<T>> for OxiRef {
+    fn from(oxi: Arc<T>) -> Self {
+        OxiRef::Instance(StepOxi(oxi))
+    }
+}
+
+/// Retry, timeout and error settings for one step, see [`PipelineBuilder::step_with`]
+#[derive(Debug, Clone, Default)]
+pub struct StepSettings {
+    retry_attempts: u32,
+    timeout_seconds: Option<f64>,
+    continue_on_error: bool,
+    depends_on: Vec<String>,
+}
+
+impl StepSettings {
+    /// Retry a failed step up to `attempts` more times
+    pub fn retry(mut self, attempts: u32) -> Self {
+        self.retry_attempts = attempts;
+        self
+    }
+
+    /// Fail each attempt that runs longer than `seconds`
+    pub fn timeout_secs(mut self, seconds: u64) -> Self {
+        self.timeout_seconds = Some(seconds as f64);
+        self
+    }
+
+    /// Fail each attempt that runs longer than `timeout`, for sub-second limits
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_seconds = Some(timeout.as_secs_f64());
+        self
+    }
+
+    /// Keep running the pipeline, with the previous step's data, if this step fails
+    pub fn continue_on_error(mut self) -> Self {
+        self.continue_on_error = true;
+        self
+    }
+
+    /// Declare a dependency on an earlier step beyond the previous step's output
+    pub fn depends_on(mut self, step_id: &str) -> Self {
+        self.depends_on.push(step_id.to_string());
+        self
+    }
+}
+
+/// Fluent builder for [`Pipeline`]
+#[derive(Debug)]
+pub struct PipelineBuilder {
+    pipeline: Pipeline,
+}
+
+impl PipelineBuilder {
+    /// Start a pipeline; `name` identifies it in state tracking like `metadata.name`
+    pub fn new(name: &str) -> Self {
+        Self {
+            pipeline: Pipeline {
+                pipeline: Vec::new(),
+                metadata: Some(PipelineMetadata {
+                    name: Some(name.to_string()),
+                    description: None,
+                    version: None,
+                    author: None,
+                    tags: Default::default(),
+                }),
+                limits_enforcement: Default::default(),
+                retry_budget: None,
+                circuit_breaker: None,
+                state: None,
+                defaults: Default::default(),
+                on_failure: None,
+                run_context: Default::default(),
+            },
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        if let Some(metadata) = self.pipeline.metadata.as_mut() {
+            metadata.description = Some(description.to_string());
+        }
+        self
+    }
+
+    /// Append a step running `oxi`, a built-in name or an `Arc` of an Oxi
+    ///
+    /// ```
+    /// use oxide_flow::oxis::prelude::*;
+    /// use oxide_flow::pipeline::builder::PipelineBuilder;
+    /// use std::sync::Arc;
+    ///
+    /// struct Tag;
+    ///
+    /// #[async_trait]
+    /// impl Oxi for Tag {
+    ///     fn name(&self) -> &str {
+    ///         "tag"
+    ///     }
+    ///
+    ///     fn schema_strategy(&self) -> SchemaStrategy {
+    ///         SchemaStrategy::Passthrough
+    ///     }
+    ///
+    ///     async fn process(&self, input: OxiData, _config: &OxiConfig) -> Result<OxiData, OxiError> {
+    ///         Ok(input)
+    ///     }
+    /// }
+    ///
+    /// let pipeline = PipelineBuilder::new("tagged")
+    ///     .step("tagger", Arc::new(Tag), OxiConfig::default())
+    ///     .step("writer", "write_stdout", OxiConfig::default())
+    ///     .build();
+    /// assert_eq!(pipeline.pipeline[0].name, "tag");
+    /// assert!(pipeline.pipeline[0].oxi.is_some());
+    /// ```
+    pub fn step(mut self, id: &str, oxi: impl Into<OxiRef>, config: OxiConfig) -> Self {
+        let (name, oxi) = match oxi.into() {
+            OxiRef::Named(name) => (name, None),
+            OxiRef::Instance(instance) => (instance.0.name().to_string(), Some(instance)),
+        };
+        self.pipeline.pipeline.push(PipelineStep {
+            name,
+            id: Some(id.to_string()),
+            config: config.values,
+            continue_on_error: false,
+            retry_attempts: 0,
+            timeout_seconds: None,
+            depends_on: Vec::new(),
+            oxi,
+        });
+        self
+    }
+
+    /// Adjust the settings of the last step added
+    ///
+    /// # Panics
+    ///
+    /// Panics if no step has been added yet.
+    pub fn step_with(mut self, settings: impl FnOnce(StepSettings) -> StepSettings) -> Self {
+        let step = self
+            .pipeline
+            .pipeline
+            .last_mut()
+            .expect("step_with must follow a step");
+        let updated = settings(StepSettings {
+            retry_attempts: step.retry_attempts,
+            timeout_seconds: step.timeout_seconds,
+            continue_on_error: step.continue_on_error,
+            depends_on: step.depends_on.clone(),
+        });
+        step.retry_attempts = updated.retry_attempts;
+        step.timeout_seconds = updated.timeout_seconds;
+        step.continue_on_error = updated.continue_on_error;
+        step.depends_on = updated.depends_on;
+        self
+    }
+
+    /// Call `handler` with the result of every step that fails, after its retries
+    ///
+    /// It runs for steps that `continue_on_error` as well as the one that
+    /// stops the pipeline.
+    pub fn on_failure(mut self, handler: impl Fn(&StepResult) + Send + Sync + 'static) -> Self {
+        self.pipeline.on_failure = Some(FailureHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Cap the retries all steps of a run may use together
+    pub fn retry_budget(mut self, retries: u32) -> Self {
+        self.pipeline.retry_budget = Some(retries);
+        self
+    }
+
+    /// Fail steps fast once they keep failing with network errors
+    pub fn circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.pipeline.circuit_breaker = Some(config);
+        self
+    }
+
+    pub fn build(self) -> Pipeline {
+        self.pipeline
+    }
+}
@@ -0,0 +1,2899 @@
+/// `println!` for run progress; prints to stderr once [`set_progress_to_stderr`](crate::pipeline::set_progress_to_stderr) is on
+#[macro_export]
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if $crate::pipeline::progress_to_stderr() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub mod builder;
+pub mod resilience;
+
+use crate::config_resolver::ConfigResolver;
+use crate::error::OxiError;
+use crate::oxis::registry::OxiRegistry;
+use crate::schema::{CompatibilityIssue, SchemaCompatibility};
+use crate::state::manager::StateManager;
+use crate::state::pipeline_tracker::PipelineTracker;
+use crate::state::types::{ErrorRecord, ErrorType, PipelineState, ResumeCheckpoint};
+use crate::types::{Data, OxiConfig, OxiData, OxiSchema, ProcessingLimits, SchemaStrategy};
+use crate::Oxi;
+use resilience::{CircuitBreakerConfig, CircuitBreakers, RunGuards};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::time::{timeout, Duration};
+use tokio_util::sync::CancellationToken;
+
+static PROGRESS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Print run progress on stderr instead of stdout, keeping stdout for a
+/// machine-readable report such as `run --json`
+pub fn set_progress_to_stderr(enabled: bool) {
+    PROGRESS_TO_STDERR.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether run progress goes to stderr, see [`set_progress_to_stderr`]
+pub fn progress_to_stderr() -> bool {
+    PROGRESS_TO_STDERR.load(Ordering::Relaxed)
+}
+
+/// A step's `config` for `oxi_name` merged over the project's and the pipeline's defaults
+///
+/// Levels merge with [`OxiConfig::merge_three`]: the step's keys win over the
+/// pipeline's `defaults`, which win over the project's, and nested mappings
+/// are merged key by key.
+pub fn config_with_defaults(
+    oxi_name: &str,
+    config: HashMap<String, serde_yaml::Value>,
+    project_defaults: &HashMap<String, serde_yaml::Value>,
+    pipeline_defaults: &HashMap<String, serde_yaml::Value>,
+) -> HashMap<String, serde_yaml::Value> {
+    let level = |defaults: &HashMap<String, serde_yaml::Value>| {
+        defaults
+            .get(oxi_name)
+            .cloned()
+            .map(OxiConfig::from_yaml)
+            .unwrap_or_default()
+    };
+    OxiConfig::merge_three(
+        &level(project_defaults),
+        &level(pipeline_defaults),
+        &OxiConfig { values: config },
+    )
+    .values
+}
+
+/// Pipeline configuration loaded from YAML
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pipeline {
+    /// List of pipeline steps
+    pub pipeline: Vec<PipelineStep>,
+
+    /// Pipeline metadata
+    pub metadata: Option<PipelineMetadata>,
+
+    /// How to react when a step's input exceeds its Oxi's processing limits
+    #[serde(default, skip_serializing_if = "LimitsEnforcement::is_default")]
+    pub limits_enforcement: LimitsEnforcement,
+
+    /// Retries all steps of a run may use together; unlimited when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_budget: Option<u32>,
+
+    /// Fail steps fast after repeated network failures, see [`resilience`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+
+    /// Overrides of the project's `state_manager` settings for this pipeline only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<serde_yaml::Value>,
+
+    /// Config merged under this pipeline's steps, keyed by Oxi name, over the project's `defaults`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub defaults: HashMap<String, serde_yaml::Value>,
+
+    /// Called with the result of each step that fails, after its retries
+    #[serde(skip)]
+    pub on_failure: Option<FailureHandler>,
+
+    /// Environment and default tags of the project the pipeline runs in
+    #[serde(skip)]
+    pub run_context: RunContext,
+}
+
+/// Callback set with [`PipelineBuilder::on_failure`](builder::PipelineBuilder::on_failure)
+#[derive(Clone)]
+pub struct FailureHandler(pub Arc<dyn Fn(&StepResult) + Send + Sync>);
+
+impl std::fmt::Debug for FailureHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("FailureHandler")
+    }
+}
+
+/// An Oxi instance a step runs directly instead of looking up its name
+#[derive(Clone)]
+pub struct StepOxi(pub Arc<dyn Oxi + Send + Sync>);
+
+impl std::fmt::Debug for StepOxi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("StepOxi").field(&self.0.name()).finish()
+    }
+}
+
+/// Pipeline-level handling of [`ProcessingLimits`](crate::types::ProcessingLimits) violations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitsEnforcement {
+    /// Report violations and run the step anyway
+    #[default]
+    Warn,
+    /// Fail the step without running it; inputs near the memory limit are reported
+    Error,
+    /// Skip the checks
+    Off,
+}
+
+impl LimitsEnforcement {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A single step in the pipeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineStep {
+    /// Name of the Oxi to execute
+    pub name: String,
+
+    /// Optional ID for this step (for referencing)
+    pub id: Option<String>,
+
+    /// Configuration for this step
+    #[serde(default)]
+    pub config: HashMap<String, serde_yaml::Value>,
+
+    /// Whether to continue pipeline execution if this step fails
+    #[serde(default)]
+    pub continue_on_error: bool,
+
+    /// Maximum number of retry attempts
+    #[serde(default)]
+    pub retry_attempts: u32,
+
+    /// Timeout in seconds for each attempt of this step; fractions allowed
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_seconds"
+    )]
+    pub timeout_seconds: Option<f64>,
+
+    /// IDs of earlier steps this step depends on, beyond the previous step's output
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+
+    /// In-process Oxi to run instead of the built-in named `name`
+    #[serde(skip)]
+    pub oxi: Option<StepOxi>,
+}
+
+/// Write whole seconds as an integer, so `timeout_seconds: 30` round-trips unchanged
+fn serialize_seconds<S>(seconds: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match seconds {
+        Some(seconds)
+            if seconds.fract() == 0.0 && *seconds >= 0.0 && *seconds <= u64::MAX as f64 =>
+        {
+            serializer.serialize_u64(*seconds as u64)
+        }
+        Some(seconds) => serializer.serialize_f64(*seconds),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Result of a pipeline step execution
+#[derive(Debug)]
+pub struct StepResult {
+    pub step_id: String,
+    pub success: bool,
+    pub data: Option<OxiData>,
+    pub error: Option<String>,
+    pub retry_count: u32,
+    pub duration_ms: u64,
+    /// Classification of `error` for its `ErrorRecord`; `None` records a processing error
+    pub error_type: Option<ErrorType>,
+    /// Whether `error` is worth retrying in a later run
+    pub retryable: bool,
+}
+
+/// Notification passed to `Pipeline::execute_with_observer` after each step
+#[derive(Debug, Clone)]
+pub struct StepCompletion {
+    /// Zero-based position of the step in the pipeline
+    pub index: usize,
+    pub step_id: String,
+    /// Name of the Oxi the step ran
+    pub oxi_name: String,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+    /// The step's output, if it produced one
+    pub output: Option<OxiData>,
+}
+
+impl StepCompletion {
+    /// Compact description of the output, e.g. `JSON, 3 records, ~240 bytes`
+    pub fn summary(&self) -> String {
+        match &self.output {
+            Some(output) => format!(
+                "{}, {} records, ~{} bytes",
+                output.data().data_type(),
+                output.data().batch_size(),
+                output.estimated_memory_usage()
+            ),
+            None => "no output".to_string(),
+        }
+    }
+}
+
+/// Progress report for one chunk of an oversized batch, see [`process_in_chunks`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkProgress {
+    /// Zero-based chunk index
+    pub index: usize,
+    /// Number of chunks the batch was split into
+    pub total: usize,
+    /// Records in this chunk
+    pub records: usize,
+}
+
+/// Run `oxi` over `input`, splitting JSON arrays larger than its `max_batch_size`
+///
+/// Each chunk carries the input's schema and metadata, and `on_chunk` is
+/// awaited after it is processed. Chunk outputs must be JSON arrays; they are
+/// concatenated in order under the first chunk's schema, with metadata from
+/// later chunks winning. Inputs within the limit, non-array inputs and Oxis
+/// that `requires_full_batch` get a single `process` call.
+pub async fn process_in_chunks<O, F, Fut>(
+    oxi: &O,
+    input: OxiData,
+    config: &OxiConfig,
+    mut on_chunk: F,
+) -> Result<OxiData, OxiError>
+where
+    O: Oxi + ?Sized,
+    F: FnMut(ChunkProgress) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let limits = oxi
+        .processing_limits()
+        .tighten(&ProcessingLimits::from_config(config));
+    let limit = match limits.max_batch_size {
+        Some(limit) if limit > 0 && !oxi.requires_full_batch() => limit,
+        _ => return oxi.process(input, config).await,
+    };
+    if !matches!(input.data(), Data::Json(serde_json::Value::Array(items)) if items.len() > limit) {
+        return oxi.process(input, config).await;
+    }
+
+    let schema = input.schema.clone();
+    let metadata = input.metadata.clone();
+    let Data::Json(serde_json::Value::Array(items)) = input.into_data() else {
+        unreachable!("checked above");
+    };
+
+    let total = items.len().div_ceil(limit);
+    let mut records = Vec::with_capacity(items.len());
+    let mut output_schema = None;
+    let mut output_metadata = HashMap::new();
+    let mut items = items.into_iter();
+
+    for index in 0..total {
+        let chunk: Vec<serde_json::Value> = items.by_ref().take(limit).collect();
+        let count = chunk.len();
+        let mut chunk_input =
+            OxiData::with_schema(Data::Json(serde_json::Value::Array(chunk)), schema.clone());
+        chunk_input.metadata = metadata.clone();
+
+        let output = oxi.process(chunk_input, config).await?;
+        output_schema.get_or_insert_with(|| output.schema.clone());
+        output_metadata.extend(output.metadata.clone());
+        match output.into_data() {
+            Data::Json(serde_json::Value::Array(processed)) => records.extend(processed),
+            other => {
+                return Err(OxiError::ExecutionError(format!(
+                    "Oxi '{}' returned {} for chunk {} of {}, but split batches can only be \
+                     reassembled from JSON arrays; override requires_full_batch to opt out",
+                    oxi.name(),
+                    other.data_type(),
+                    index + 1,
+                    total
+                )))
+            }
+        }
+
+        on_chunk(ChunkProgress {
+            index,
+            total,
+            records: count,
+        })
+        .await;
+    }
+
+    let mut result = OxiData::with_schema(
+        Data::Json(serde_json::Value::Array(records)),
+        output_schema.unwrap_or_else(OxiSchema::empty),
+    );
+    result.metadata = output_metadata;
+    Ok(result)
+}
+
+/// Overall pipeline execution result
+#[derive(Debug)]
+pub struct PipelineResult {
+    pub success: bool,
+    pub steps_executed: u32,
+    pub steps_failed: u32,
+    pub steps_skipped: u32,
+    pub total_duration_ms: u64,
+    pub step_results: Vec<StepResult>,
+    pub final_data: Option<OxiData>,
+    pub pipeline_id: Option<String>,
+    pub run_id: Option<String>,
+    pub state_tracking_enabled: bool,
+    /// The run was stopped by its cancellation token; tracked state is `Paused`
+    pub cancelled: bool,
+}
+
+/// Pipeline metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineMetadata {
+    /// Pipeline name
+    pub name: Option<String>,
+
+    /// Pipeline description
+    pub description: Option<String>,
+
+    /// Pipeline version
+    pub version: Option<String>,
+
+    /// Pipeline author
+    pub author: Option<String>,
+
+    /// Tags recorded on the state of each run, as `key: value` pairs or a
+    /// list of `key=value` and bare `key` entries (bare tags have an empty value)
+    #[serde(
+        default,
+        deserialize_with = "deserialize_tags",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub tags: HashMap<String, String>,
+}
+
+/// Read `metadata.tags` from either a mapping or a list of `key=value` entries
+fn deserialize_tags<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tags {
+        Map(HashMap<String, String>),
+        List(Vec<String>),
+    }
+
+    Ok(match Option::<Tags>::deserialize(deserializer)? {
+        None => HashMap::new(),
+        Some(Tags::Map(tags)) => tags,
+        Some(Tags::List(tags)) => tags
+            .into_iter()
+            .map(|tag| match tag.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (tag, String::new()),
+            })
+            .collect(),
+    })
+}
+
+/// Project context recorded on the state of each run, set with [`Pipeline::set_run_context`]
+#[derive(Debug, Clone, Default)]
+pub struct RunContext {
+    /// Active environment, e.g. `prod`
+    pub environment: Option<String>,
+    /// Tags applied to every run; the pipeline's own tags win on conflicts
+    pub default_tags: HashMap<String, String>,
+}
+
+/// Schemas flowing into and out of one pipeline step
+#[derive(Debug, Clone, Serialize)]
+pub struct StepSchema {
+    pub step_id: String,
+    /// Name of the Oxi the step runs
+    pub step_name: String,
+    /// Declared `SchemaStrategy`, or `None` when the Oxi is unknown
+    pub strategy: Option<SchemaStrategy>,
+    /// `None` when an earlier step's output could not be determined
+    pub input_schema: Option<OxiSchema>,
+    pub output_schema: Option<OxiSchema>,
+    /// Fields the step needs in its input (`Oxi::required_input_schema`)
+    pub required_schema: Option<OxiSchema>,
+    /// Why schema computation stopped at this step
+    pub error: Option<String>,
+}
+
+/// Per-step schema flow of a pipeline, in step order
+pub type SchemaTrace = Vec<StepSchema>;
+
+/// Check every step's required input against the schema upstream provides
+///
+/// Steps without requirements, or whose input schema is unknown or has
+/// no fields, are skipped. Returns `(step_id, issue)` pairs in step order.
+pub fn check_schema_compatibility(trace: &[StepSchema]) -> Vec<(String, CompatibilityIssue)> {
+    trace
+        .iter()
+        .filter_map(|step| {
+            let required = step.required_schema.as_ref()?;
+            let provided = step.input_schema.as_ref()?;
+            (!provided.fields.is_empty()).then(|| {
+                SchemaCompatibility::check(provided, required)
+                    .issues
+                    .into_iter()
+                    .map(|issue| (step.step_id.clone(), issue))
+            })
+        })
+        .flatten()
+        .collect()
+}
+
+/// Error type and retryability of a failed attempt
+///
+/// Errors that aren't an [`OxiError`] count as non-retryable processing errors.
+pub(crate) fn classify_failure(error: &anyhow::Error) -> (ErrorType, bool) {
+    match error.downcast_ref::<OxiError>() {
+        Some(error) => (error.error_type(), error.is_retryable()),
+        None => (ErrorType::Processing, false),
+    }
+}
+
+impl Pipeline {
+    /// Load a pipeline from a YAML file
+    pub fn load_from_file(path: &str) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read pipeline file '{}': {}", path, e))?;
+
+        let pipeline: Pipeline = serde_yaml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse pipeline YAML '{}': {}", path, e))?;
+
+        Ok(pipeline)
+    }
+
+    /// Render the pipeline as YAML, e.g. to inspect one built with [`builder::PipelineBuilder`]
+    ///
+    /// In-process Oxis are written by name and failure handlers are left out,
+    /// so such a pipeline only loads back once its Oxis are available by name.
+    pub fn to_yaml(&self) -> anyhow::Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Run steps naming one of `plugins` with that plugin
+    ///
+    /// Steps that already carry an Oxi instance are left alone.
+    pub fn attach_plugins(&mut self, plugins: &HashMap<String, Arc<dyn Oxi + Send + Sync>>) {
+        let mut registry = OxiRegistry::new();
+        registry.register_plugins(plugins);
+        self.attach_registry(&registry);
+    }
+
+    /// Merge each step's config over the project's `defaults` and the pipeline's own
+    ///
+    /// See [`config_with_defaults`]. This runs before any reference is
+    /// resolved, so defaults may use `${...}` just like step config.
+    pub fn apply_defaults(&mut self, defaults: &HashMap<String, serde_yaml::Value>) {
+        for step in &mut self.pipeline {
+            let config = std::mem::take(&mut step.config);
+            step.config = config_with_defaults(&step.name, config, defaults, &self.defaults);
+        }
+    }
+
+    /// Instantiate each step's Oxi from `registry`, by the step's `name`
+    ///
+    /// Steps that already carry an Oxi instance, or whose name `registry`
+    /// doesn't know, are left alone; the latter fall back to the built-ins.
+    pub fn attach_registry(&mut self, registry: &OxiRegistry) {
+        for step in &mut self.pipeline {
+            if step.oxi.is_none() {
+                step.oxi = registry.create(&step.name).map(StepOxi);
+            }
+        }
+    }
+
+    /// Hash identifying this pipeline's definition
+    ///
+    /// Computed from the parsed pipeline with mapping keys sorted, so
+    /// whitespace, comments and key order in the YAML file do not affect it.
+    pub fn content_hash(&self) -> String {
+        let value = serde_yaml::to_value(self).unwrap_or_default();
+        let yaml = serde_yaml::to_string(&crate::types::canonical_yaml(&value)).unwrap_or_default();
+        format!("{:x}", md5::compute(yaml.as_bytes()))
+    }
+
+    /// Get the number of steps in this pipeline
+    pub fn step_count(&self) -> usize {
+        self.pipeline.len()
+    }
+
+    /// `timeout_seconds` of each step that sets one, keyed by step ID
+    ///
+    /// The executor times each attempt of a step out after this duration.
+    /// Steps without one are only timed out when their config sets
+    /// `max_processing_time_ms`. Negative or non-finite values are ignored.
+    pub fn step_timeout_map(&self) -> HashMap<String, Duration> {
+        self.pipeline
+            .iter()
+            .filter_map(|step| {
+                let timeout = Duration::try_from_secs_f64(step.timeout_seconds?).ok()?;
+                Some((step.get_id().to_string(), timeout))
+            })
+            .collect()
+    }
+
+    /// Get pipeline name from metadata or default
+    pub fn name(&self) -> String {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.name.as_ref())
+            .cloned()
+            .unwrap_or_else(|| "Unnamed Pipeline".to_string())
+    }
+
+    /// Record `environment` and the project's `default_tags` on the state of each run
+    pub fn set_run_context(
+        &mut self,
+        environment: Option<String>,
+        default_tags: &HashMap<String, String>,
+    ) {
+        self.run_context = RunContext {
+            environment,
+            default_tags: default_tags.clone(),
+        };
+    }
+
+    /// Tags recorded on the state of a run: the project defaults overlaid with `metadata.tags`
+    pub fn run_tags(&self) -> HashMap<String, String> {
+        let mut tags = self.run_context.default_tags.clone();
+        if let Some(metadata) = &self.metadata {
+            tags.extend(metadata.tags.clone());
+        }
+        tags
+    }
+
+    /// Get pipeline description from metadata
+    pub fn description(&self) -> Option<String> {
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.description.as_ref())
+            .cloned()
+    }
+
+    /// Files read by this pipeline's steps, as declared by `Oxi::watched_paths`
+    ///
+    /// Steps whose config cannot be resolved fall back to their raw config.
+    pub fn watched_paths(&self, resolver: &ConfigResolver) -> Vec<std::path::PathBuf> {
+        let mut paths = Vec::new();
+        for step in &self.pipeline {
+            let Some(oxi) = step.resolve_oxi() else {
+                continue;
+            };
+            let config = step
+                .to_oxi_config(resolver)
+                .unwrap_or_else(|_| step.to_oxi_config_simple());
+            for path in oxi.watched_paths(&config) {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        paths
+    }
+
+    /// Where a resumed run picks up: the index of the step `paused` stopped
+    /// at, with the checkpoint holding its input and the earlier steps' outputs
+    ///
+    /// `None` when there is no checkpoint, the pipeline no longer has that
+    /// step, or any step before it can't be skipped because it did not
+    /// complete or its config changed; the run then starts over from the
+    /// first step.
+    async fn resume_point(
+        &self,
+        tracker: &PipelineTracker,
+        paused: PipelineState,
+        checkpoint: Option<ResumeCheckpoint>,
+        resolver: &ConfigResolver,
+    ) -> Option<(usize, ResumeCheckpoint)> {
+        let index = self
+            .pipeline
+            .iter()
+            .position(|step| step.get_id() == paused.current_step)?;
+        let checkpoint = checkpoint?;
+        for step in &self.pipeline[..index] {
+            let config_hash = step.to_oxi_config(resolver).ok()?.config_hash();
+            if !tracker
+                .can_skip_step(step.get_id(), &config_hash)
+                .await
+                .ok()?
+            {
+                progress!(
+                    "🔄 Step '{}' changed since the run was paused, starting over",
+                    step.get_id()
+                );
+                return None;
+            }
+        }
+        Some((index, checkpoint))
+    }
+
+    /// Ids of steps whose data (not just metadata) a step config references
+    ///
+    /// Only these outputs are kept for `${step_id.field}` references during
+    /// execution, so pipelines that never reference data don't hold a copy
+    /// of every step's output.
+    fn data_referenced_steps(&self) -> std::collections::HashSet<String> {
+        let reference =
+            regex::Regex::new(r"\$\{([a-zA-Z_][a-zA-Z0-9_]*)(?:\.([a-zA-Z0-9_.]+))?\}").unwrap();
+        let ids: std::collections::HashSet<&str> =
+            self.pipeline.iter().map(PipelineStep::get_id).collect();
+
+        let mut referenced = std::collections::HashSet::new();
+        for step in &self.pipeline {
+            let Ok(config) = serde_yaml::to_string(&step.config) else {
+                continue;
+            };
+            for cap in reference.captures_iter(&config) {
+                let metadata_only = cap
+                    .get(2)
+                    .is_some_and(|path| path.as_str().starts_with("metadata."));
+                if ids.contains(&cap[1]) && !metadata_only {
+                    referenced.insert(cap[1].to_string());
+                }
+            }
+        }
+        referenced
+    }
+
+    /// Thread schemas through the pipeline without processing any data
+    ///
+    /// Each step's `Oxi::output_schema` is called with the previous step's
+    /// output schema, starting from `initial_schema`. Unknown Oxis and schema
+    /// errors are recorded on the step rather than aborting the trace, and
+    /// later steps then see an unknown input.
+    pub fn dry_run_schema(
+        &self,
+        initial_schema: OxiSchema,
+        resolver: &ConfigResolver,
+    ) -> anyhow::Result<SchemaTrace> {
+        let mut current = Some(initial_schema);
+        let mut trace = SchemaTrace::with_capacity(self.pipeline.len());
+
+        for step in &self.pipeline {
+            let mut entry = StepSchema {
+                step_id: step.get_id().to_string(),
+                step_name: step.name.clone(),
+                strategy: None,
+                input_schema: current.take(),
+                output_schema: None,
+                required_schema: None,
+                error: None,
+            };
+
+            let Some(oxi) = step.resolve_oxi() else {
+                entry.error = Some("unknown — schema inference skipped".to_string());
+                trace.push(entry);
+                continue;
+            };
+            entry.strategy = Some(oxi.schema_strategy());
+
+            let config = step
+                .to_oxi_config(resolver)
+                .unwrap_or_else(|_| step.to_oxi_config_simple());
+            entry.required_schema = oxi.required_input_schema(&config);
+            match oxi.output_schema(entry.input_schema.as_ref(), &config) {
+                Ok(schema) => {
+                    current = Some(schema.clone());
+                    entry.output_schema = Some(schema);
+                }
+                Err(e) => entry.error = Some(format!("schema inference failed: {e}")),
+            }
+            trace.push(entry);
+        }
+
+        Ok(trace)
+    }
+
+    /// Execute the entire pipeline with enhanced error handling
+    pub async fn execute_with_retries(
+        &self,
+        initial_data: OxiData,
+        resolver: &ConfigResolver,
+    ) -> PipelineResult {
+        self.execute_with_state_tracking(initial_data, resolver, None)
+            .await
+    }
+
+    /// Execute the pipeline with optional state tracking
+    pub async fn execute_with_state_tracking(
+        &self,
+        initial_data: OxiData,
+        resolver: &ConfigResolver,
+        state_manager: Option<StateManager>,
+    ) -> PipelineResult {
+        self.execute_observed(
+            initial_data,
+            resolver,
+            state_manager,
+            None::<fn(StepCompletion) -> std::future::Ready<()>>,
+            CancellationToken::new(),
+        )
+        .await
+    }
+
+    /// Execute the pipeline until it finishes or `cancel` fires
+    ///
+    /// Cancelling abandons the running step and skips the rest. With state
+    /// tracking the run is checkpointed as `Paused` instead of completed, so
+    /// it is not mistaken for a crash and can be resumed.
+    pub async fn execute_cancellable(
+        &self,
+        initial_data: OxiData,
+        resolver: &ConfigResolver,
+        state_manager: Option<StateManager>,
+        cancel: CancellationToken,
+    ) -> PipelineResult {
+        self.execute_observed(
+            initial_data,
+            resolver,
+            state_manager,
+            None::<fn(StepCompletion) -> std::future::Ready<()>>,
+            cancel,
+        )
+        .await
+    }
+
+    /// Execute the pipeline, awaiting `observer` after every step finishes
+    ///
+    /// The observer sees failed steps too, and runs before the pipeline
+    /// decides whether to stop, so host applications can show live progress
+    /// or persist intermediate outputs.
+    pub async fn execute_with_observer<F, Fut>(
+        &self,
+        initial_data: OxiData,
+        resolver: &ConfigResolver,
+        state_manager: Option<StateManager>,
+        observer: F,
+    ) -> PipelineResult
+    where
+        F: FnMut(StepCompletion) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        self.execute_observed(
+            initial_data,
+            resolver,
+            state_manager,
+            Some(observer),
+            CancellationToken::new(),
+        )
+        .await
+    }
+
+    async fn execute_observed<F, Fut>(
+        &self,
+        initial_data: OxiData,
+        resolver: &ConfigResolver,
+        state_manager: Option<StateManager>,
+        mut observer: Option<F>,
+        cancel: CancellationToken,
+    ) -> PipelineResult
+    where
+        F: FnMut(StepCompletion) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let start_time = std::time::Instant::now();
+        let mut current_data = initial_data;
+        let mut step_results = Vec::new();
+        let mut steps_executed = 0;
+        let mut steps_failed = 0;
+        let mut steps_skipped = 0;
+
+        progress!("🚀 Starting pipeline execution: {}", self.name());
+
+        // Initialize state tracking if enabled, continuing a paused run
+        let mut paused = None;
+        let tracker = if let Some(state_manager) = state_manager {
+            let tracker =
+                match PipelineTracker::resume_paused(state_manager.clone(), &self.name()).await {
+                    Ok(Some((tracker, state, checkpoint))) => {
+                        paused = Some((state, checkpoint));
+                        Ok(tracker)
+                    }
+                    Ok(None) => PipelineTracker::new(state_manager, self).await,
+                    Err(e) => Err(e),
+                };
+            match tracker {
+                Ok(tracker) => {
+                    progress!(
+                        "📊 State tracking enabled for pipeline: {}",
+                        tracker.pipeline_id()
+                    );
+                    Some(tracker)
+                }
+                Err(e) => {
+                    progress!("⚠️  Failed to initialize state tracking: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Expose the previous run's watermark as ${state.last_processed_id}
+        let mut resolver = resolver.clone();
+        if let Some(ref tracker) = tracker {
+            match tracker.last_processed_id().await {
+                Ok(id) => resolver.set_state_value("last_processed_id", id),
+                Err(e) => progress!("⚠️  Failed to read last processed id: {e}"),
+            }
+        }
+        let mut watermark = None;
+        let referenced = self.data_referenced_steps();
+        let guards = RunGuards::new(
+            self.retry_budget,
+            self.circuit_breaker.as_ref(),
+            &CircuitBreakers::for_pipeline(&self.name()),
+        );
+        let mut cancelled = false;
+        let mut interrupted_step = None;
+        let step_timeouts = self.step_timeout_map();
+
+        // What completed steps reported, kept for a checkpoint if the run is paused
+        let mut completed_metadata = BTreeMap::new();
+        let mut completed_outputs = BTreeMap::new();
+
+        // A resumed run skips the completed steps before the one it stopped at,
+        // restoring what they reported for later `${step_id.*}` references
+        let mut first_step = 0;
+        if let (Some(tracker), Some((paused, checkpoint))) = (&tracker, paused) {
+            if let Some((index, checkpoint)) = self
+                .resume_point(tracker, paused, checkpoint, &resolver)
+                .await
+            {
+                progress!(
+                    "⏩ Resuming at step '{}', skipping {index} completed steps",
+                    self.pipeline[index].get_id()
+                );
+                first_step = index;
+                steps_skipped = index;
+                for (step_id, metadata) in checkpoint.step_metadata {
+                    resolver
+                        .add_step_metadata(step_id.clone(), metadata.clone().into_iter().collect());
+                    completed_metadata.insert(step_id, metadata);
+                }
+                for (step_id, data) in checkpoint.step_outputs {
+                    resolver.add_step_output(step_id.clone(), OxiData::new(data.clone()));
+                    completed_outputs.insert(step_id, data);
+                }
+                current_data = OxiData::new(checkpoint.input);
+            }
+        }
+
+        for (index, step) in self.pipeline.iter().enumerate().skip(first_step) {
+            if cancel.is_cancelled() {
+                cancelled = true;
+                interrupted_step = Some(step.get_id().to_string());
+                steps_skipped += self.pipeline.len() - index;
+                break;
+            }
+
+            progress!(
+                "\n📋 Step {} of {}: '{}'",
+                index + 1,
+                self.pipeline.len(),
+                step.get_id()
+            );
+
+            // Start step tracking
+            if let Some(ref tracker) = tracker {
+                let config_hash = step
+                    .to_oxi_config(&resolver)
+                    .ok()
+                    .map(|config| config.config_hash());
+                if let Err(e) = tracker
+                    .start_step(step.get_id(), config_hash.as_deref())
+                    .await
+                {
+                    progress!("⚠️  Failed to start step tracking: {e}");
+                }
+            }
+
+            let step_result = tokio::select! {
+                result = step.execute_tracked(
+                    current_data.share(),
+                    &resolver,
+                    tracker.as_ref(),
+                    self.limits_enforcement,
+                    &guards,
+                    step_timeouts.get(step.get_id()).copied(),
+                ) => result,
+                _ = cancel.cancelled() => {
+                    progress!("⏸️  Step '{}' cancelled", step.get_id());
+                    cancelled = true;
+                    interrupted_step = Some(step.get_id().to_string());
+                    steps_skipped += self.pipeline.len() - index - 1;
+                    step_results.push(StepResult {
+                        step_id: step.get_id().to_string(),
+                        success: false,
+                        data: None,
+                        error: Some(OxiError::Cancelled.to_string()),
+                        retry_count: 0,
+                        duration_ms: 0,
+                        error_type: Some(ErrorType::Cancelled),
+                        retryable: false,
+                    });
+                    break;
+                }
+            };
+
+            // Complete step tracking
+            if let Some(ref tracker) = tracker {
+                if let Err(e) = tracker.complete_step(&step_result).await {
+                    progress!("⚠️  Failed to complete step tracking: {e}");
+                }
+            }
+
+            if let Some(observer) = observer.as_mut() {
+                observer(StepCompletion {
+                    index,
+                    step_id: step_result.step_id.clone(),
+                    oxi_name: step.name.clone(),
+                    success: step_result.success,
+                    duration_ms: step_result.duration_ms,
+                    error: step_result.error.clone(),
+                    output: step_result.data.as_ref().map(OxiData::share),
+                })
+                .await;
+            }
+
+            if step_result.success {
+                if let (Some(_), Some(data)) = (&tracker, &step_result.data) {
+                    if let Some(id) = step.watermark(data, &resolver) {
+                        watermark = Some(id);
+                    }
+                }
+                if let Some(data) = step_result.data.as_ref().map(OxiData::share) {
+                    // Later steps can reference what this one reported
+                    resolver.add_step_metadata(step.get_id().to_string(), data.metadata.clone());
+                    completed_metadata.insert(
+                        step.get_id().to_string(),
+                        data.metadata.clone().into_iter().collect(),
+                    );
+                    if referenced.contains(step.get_id()) {
+                        resolver.add_step_output(step.get_id().to_string(), data.share());
+                        completed_outputs.insert(step.get_id().to_string(), data.data.clone());
+                    }
+                    current_data = data;
+                }
+                steps_executed += 1;
+
+                // Create checkpoint every few steps
+                if let Some(ref tracker) = tracker {
+                    if index % 3 == 0 {
+                        // Checkpoint every 3 steps
+                        if let Err(e) = tracker.create_checkpoint(&current_data).await {
+                            progress!("⚠️  Failed to create checkpoint: {e}");
+                        }
+                    }
+                }
+            } else {
+                steps_failed += 1;
+                // Later steps can still see why this one failed fast
+                if let Some(circuit) = guards.circuit_metadata(step.get_id()) {
+                    resolver.add_step_metadata(
+                        step.get_id().to_string(),
+                        HashMap::from([("circuit_breaker".to_string(), circuit)]),
+                    );
+                }
+                if let Some(FailureHandler(handler)) = &self.on_failure {
+                    handler(&step_result);
+                }
+
+                if step.continue_on_error {
+                    progress!("⚠️  Step failed but continue_on_error is true, continuing...");
+                    // Continue with the same data
+                } else {
+                    progress!("💥 Step failed and continue_on_error is false, stopping pipeline");
+                    step_results.push(step_result);
+
+                    // Mark remaining steps as skipped
+                    steps_skipped += self.pipeline.len() - index - 1;
+
+                    let total_duration = start_time.elapsed().as_millis() as u64;
+                    let (pipeline_id, run_id) = if let Some(ref tracker) = tracker {
+                        (
+                            Some(tracker.pipeline_id().to_string()),
+                            Some(tracker.run_id().to_string()),
+                        )
+                    } else {
+                        (None, None)
+                    };
+
+                    let result = PipelineResult {
+                        success: false,
+                        steps_executed,
+                        steps_failed,
+                        steps_skipped: steps_skipped as u32,
+                        total_duration_ms: total_duration,
+                        step_results,
+                        final_data: None,
+                        pipeline_id,
+                        run_id,
+                        state_tracking_enabled: tracker.is_some(),
+                        cancelled: false,
+                    };
+
+                    // Complete pipeline tracking
+                    if let Some(ref tracker) = tracker {
+                        if let Err(e) = tracker.complete_pipeline(&result).await {
+                            progress!("⚠️  Failed to complete pipeline tracking: {e}");
+                        }
+                    }
+
+                    return result;
+                }
+            }
+
+            step_results.push(step_result);
+
+            // Send heartbeat periodically
+            if let Some(ref tracker) = tracker {
+                if let Err(e) = tracker.send_heartbeat().await {
+                    progress!("⚠️  Failed to send heartbeat: {e}");
+                }
+            }
+        }
+
+        let total_duration = start_time.elapsed().as_millis() as u64;
+        let success = steps_failed == 0 && !cancelled;
+
+        if cancelled {
+            progress!("\n⏸️  Pipeline cancelled");
+        } else if success {
+            progress!("\n🎉 Pipeline completed successfully!");
+        } else {
+            progress!("\n⚠️  Pipeline completed with {steps_failed} failed steps");
+        }
+
+        progress!(
+            "📊 Summary: {steps_executed} executed, {steps_failed} failed, {steps_skipped} skipped"
+        );
+        progress!("⏱️  Total time: {total_duration}ms");
+
+        let (pipeline_id, run_id) = if let Some(ref tracker) = tracker {
+            (
+                Some(tracker.pipeline_id().to_string()),
+                Some(tracker.run_id().to_string()),
+            )
+        } else {
+            (None, None)
+        };
+
+        // A cancelled run is checkpointed with the input of the step it stopped
+        // at and what the steps before it reported
+        if let (Some(tracker), true) = (&tracker, cancelled) {
+            let checkpoint = ResumeCheckpoint {
+                input: current_data.data.clone(),
+                step_metadata: completed_metadata,
+                step_outputs: completed_outputs,
+            };
+            if let Err(e) = tracker
+                .pause_pipeline(interrupted_step.as_deref(), &checkpoint)
+                .await
+            {
+                progress!("⚠️  Failed to checkpoint cancelled pipeline: {e}");
+            }
+        }
+
+        let result = PipelineResult {
+            success,
+            steps_executed,
+            steps_failed,
+            steps_skipped: steps_skipped as u32,
+            total_duration_ms: total_duration,
+            step_results,
+            final_data: if success { Some(current_data) } else { None },
+            pipeline_id,
+            run_id,
+            state_tracking_enabled: tracker.is_some(),
+            cancelled,
+        };
+
+        // Complete pipeline tracking; the watermark only advances once every step succeeded
+        if let (Some(tracker), false) = (&tracker, cancelled) {
+            if let (true, Some(id)) = (result.success, &watermark) {
+                if let Err(e) = tracker.commit_watermark(id).await {
+                    progress!("⚠️  Failed to save last processed id: {e}");
+                }
+            }
+            if let Err(e) = tracker.complete_pipeline(&result).await {
+                progress!("⚠️  Failed to complete pipeline tracking: {e}");
+            }
+        }
+
+        result
+    }
+}
+
+impl PipelineStep {
+    /// Get the step ID, using the name as fallback
+    pub fn get_id(&self) -> &str {
+        self.id.as_ref().unwrap_or(&self.name)
+    }
+
+    /// The Oxi this step runs: its attached instance, or the built-in named `name`
+    pub fn resolve_oxi(&self) -> Option<Arc<dyn Oxi + Send + Sync>> {
+        match &self.oxi {
+            Some(StepOxi(oxi)) => Some(oxi.clone()),
+            None => OxiRegistry::builtins().create(&self.name),
+        }
+    }
+
+    /// Watermark reported by this step's Oxi for its output, if it is an incremental source
+    fn watermark(&self, output: &OxiData, resolver: &ConfigResolver) -> Option<String> {
+        let oxi = self.resolve_oxi()?;
+        let config = self.to_oxi_config(resolver).ok()?;
+        oxi.watermark(output, &config)
+    }
+
+    /// Convert config HashMap to OxiConfig with configuration resolution
+    pub fn to_oxi_config(
+        &self,
+        resolver: &ConfigResolver,
+    ) -> anyhow::Result<crate::types::OxiConfig> {
+        resolver.resolve_config(&self.to_oxi_config_simple())
+    }
+
+    /// Execute this step with enhanced error handling and retries
+    pub async fn execute_with_retries(
+        &self,
+        input: OxiData,
+        resolver: &ConfigResolver,
+    ) -> StepResult {
+        let timeout = self
+            .timeout_seconds
+            .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+        self.execute_tracked(
+            input,
+            resolver,
+            None,
+            LimitsEnforcement::default(),
+            &RunGuards::default(),
+            timeout,
+        )
+        .await
+    }
+
+    /// Like `execute_with_retries`, recording chunk progress with `tracker`
+    async fn execute_tracked(
+        &self,
+        input: OxiData,
+        resolver: &ConfigResolver,
+        tracker: Option<&PipelineTracker>,
+        limits_enforcement: LimitsEnforcement,
+        guards: &RunGuards,
+        step_timeout: Option<Duration>,
+    ) -> StepResult {
+        let start_time = std::time::Instant::now();
+        let step_id = self.get_id().to_string();
+
+        if let Err(e) = guards.check_circuit(&step_id) {
+            progress!("⛔ Step '{step_id}' not run: {e}");
+            return StepResult {
+                step_id,
+                success: false,
+                data: None,
+                error: Some(e.to_string()),
+                retry_count: 0,
+                duration_ms: 0,
+                error_type: Some(e.error_type()),
+                retryable: e.is_retryable(),
+            };
+        }
+
+        // Retrying cannot shrink the input, so a limit violation fails the step at once
+        if let Err(e) = self
+            .check_limits(&input, resolver, limits_enforcement, tracker)
+            .await
+        {
+            progress!("❌ Step '{step_id}' not run: {e}");
+            return StepResult {
+                step_id,
+                success: false,
+                data: None,
+                error: Some(e.to_string()),
+                retry_count: 0,
+                duration_ms: start_time.elapsed().as_millis() as u64,
+                error_type: Some(e.error_type()),
+                retryable: e.is_retryable(),
+            };
+        }
+
+        for attempt in 0..=self.retry_attempts {
+            progress!(
+                "🔄 Executing step '{}' (attempt {} of {})",
+                step_id,
+                attempt + 1,
+                self.retry_attempts + 1
+            );
+
+            let result = if let Some(duration) = self.attempt_timeout(step_timeout, resolver) {
+                // Execute with timeout
+                match timeout(
+                    duration,
+                    self.execute_once(input.share(), resolver, tracker),
+                )
+                .await
+                {
+                    Ok(result) => result,
+                    Err(_) => Err(OxiError::Timeout {
+                        step: step_id.clone(),
+                        elapsed_ms: duration.as_millis() as u64,
+                    }
+                    .into()),
+                }
+            } else {
+                // Execute without timeout
+                self.execute_once(input.share(), resolver, tracker).await
+            };
+
+            match result {
+                Ok(mut data) => {
+                    guards.record(&step_id, Ok(()));
+                    if let Some(circuit) = guards.circuit_metadata(&step_id) {
+                        data.set_meta("circuit_breaker", circuit);
+                    }
+                    let duration = start_time.elapsed().as_millis() as u64;
+                    progress!("✅ Step '{step_id}' completed successfully");
+                    return StepResult {
+                        step_id,
+                        success: true,
+                        data: Some(data),
+                        error: None,
+                        retry_count: attempt,
+                        duration_ms: duration,
+                        error_type: None,
+                        retryable: false,
+                    };
+                }
+                Err(e) => {
+                    let opened = guards.record(&step_id, Err(&e));
+                    if opened {
+                        progress!("⛔ Step '{step_id}' circuit opened: {e}");
+                    }
+                    let mut retry = attempt < self.retry_attempts && !opened;
+                    if retry && !guards.take_retry() {
+                        progress!("⚠️  Step '{step_id}' not retried: the pipeline's retry_budget is used up");
+                        retry = false;
+                    }
+                    if retry {
+                        progress!(
+                            "⚠️  Step '{}' failed (attempt {}): {}. Retrying...",
+                            step_id,
+                            attempt + 1,
+                            e
+                        );
+                        tokio::time::sleep(Duration::from_millis(1000 * (attempt + 1) as u64))
+                            .await;
+                    } else {
+                        let duration = start_time.elapsed().as_millis() as u64;
+                        progress!(
+                            "❌ Step '{}' failed after {} attempts: {}",
+                            step_id,
+                            attempt + 1,
+                            e
+                        );
+                        let (error_type, retryable) = classify_failure(&e);
+                        return StepResult {
+                            step_id,
+                            success: false,
+                            data: None,
+                            error: Some(e.to_string()),
+                            retry_count: attempt,
+                            duration_ms: duration,
+                            error_type: Some(error_type),
+                            retryable,
+                        };
+                    }
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    /// The step's Oxi's processing limits, tightened by any set in the step's config
+    ///
+    /// Config that fails to resolve is read as written; the step reports
+    /// the resolution error when it runs.
+    fn effective_limits(
+        &self,
+        oxi: &(dyn Oxi + Send + Sync),
+        resolver: &ConfigResolver,
+    ) -> ProcessingLimits {
+        let config = self
+            .to_oxi_config(resolver)
+            .unwrap_or_else(|_| self.to_oxi_config_simple());
+        oxi.processing_limits()
+            .tighten(&ProcessingLimits::from_config(&config))
+    }
+
+    /// Time allowed for one attempt
+    ///
+    /// The step's entry in `Pipeline::step_timeout_map` when it has one,
+    /// otherwise a `max_processing_time_ms` set in the step's config, which
+    /// cannot exceed the one its Oxi declares. Steps that set neither are
+    /// not timed out.
+    fn attempt_timeout(
+        &self,
+        step_timeout: Option<Duration>,
+        resolver: &ConfigResolver,
+    ) -> Option<Duration> {
+        step_timeout.or_else(|| {
+            let config = self
+                .to_oxi_config(resolver)
+                .unwrap_or_else(|_| self.to_oxi_config_simple());
+            let configured = ProcessingLimits::from_config(&config).max_processing_time_ms?;
+            let declared = self
+                .resolve_oxi()
+                .and_then(|oxi| oxi.processing_limits().max_processing_time_ms);
+            Some(Duration::from_millis(
+                declared.map_or(configured, |declared| declared.min(configured)),
+            ))
+        })
+    }
+
+    /// Check `input` against the step's Oxi's processing limits
+    ///
+    /// Under `Error` the first violation is returned; under `Warn` violations
+    /// are reported and recorded as resource errors. Inputs near the memory
+    /// limit are reported either way.
+    async fn check_limits(
+        &self,
+        input: &OxiData,
+        resolver: &ConfigResolver,
+        enforcement: LimitsEnforcement,
+        tracker: Option<&PipelineTracker>,
+    ) -> Result<(), OxiError> {
+        if enforcement == LimitsEnforcement::Off {
+            return Ok(());
+        }
+        // Unknown Oxis fail when the step runs
+        let Some(oxi) = self.resolve_oxi() else {
+            return Ok(());
+        };
+
+        let step_id = self.get_id();
+        for violation in self
+            .effective_limits(oxi.as_ref(), resolver)
+            .check(input, oxi.requires_full_batch())
+        {
+            if violation.approaching {
+                progress!(
+                    "⚠️  Step '{step_id}' input is near '{}' {}: {} of {}",
+                    oxi.name(),
+                    violation.limit,
+                    violation.observed,
+                    violation.allowed
+                );
+                continue;
+            }
+
+            let error = violation.into_error(oxi.name());
+            if enforcement == LimitsEnforcement::Error {
+                return Err(error);
+            }
+            progress!("⚠️  Step '{step_id}': {error}");
+            if let Some(tracker) = tracker {
+                let record = ErrorRecord::from_oxi_error(
+                    Some(step_id.to_string()),
+                    &error,
+                    "Step ran anyway because limits_enforcement is warn".to_string(),
+                );
+                if let Err(e) = tracker.record_error(record).await {
+                    progress!("⚠️  Failed to record limit violation: {e}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Execute the step once (internal helper)
+    async fn execute_once(
+        &self,
+        input: OxiData,
+        resolver: &ConfigResolver,
+        tracker: Option<&PipelineTracker>,
+    ) -> anyhow::Result<OxiData> {
+        let config = self.to_oxi_config(resolver)?;
+
+        let oxi = self
+            .resolve_oxi()
+            .ok_or_else(|| crate::error::OxiError::UnknownOxi(self.name.clone()))?;
+        let step_id = self.get_id();
+        if oxi.strict_config_validation() {
+            config
+                .validate_strict(&oxi.describe().config_schema)
+                .map_err(|e| OxiError::ConfigError(format!("Step '{step_id}': {e}")))?;
+        }
+        let inherited = input.metadata.clone();
+        let mut result = process_in_chunks(oxi.as_ref(), input, &config, |chunk| async move {
+            progress!(
+                "📦 Step '{step_id}' processed chunk {} of {} ({} records)",
+                chunk.index + 1,
+                chunk.total,
+                chunk.records
+            );
+            if let Some(tracker) = tracker {
+                if let Err(e) = tracker.record_batch(step_id, chunk.records as u64).await {
+                    progress!("⚠️  Failed to record batch progress: {e}");
+                }
+            }
+        })
+        .await?;
+
+        // Input metadata flows through; keys the Oxi reported itself win
+        let reported = std::mem::replace(&mut result.metadata, inherited);
+        result.merge_meta(reported);
+
+        Ok(result)
+    }
+
+    /// Convert config HashMap to OxiConfig without resolution
+    pub fn to_oxi_config_simple(&self) -> crate::types::OxiConfig {
+        let mut oxi_config = crate::types::OxiConfig::default();
+        for (key, value) in &self.config {
+            oxi_config.values.insert(key.clone(), value.clone());
+        }
+        oxi_config
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_load_pipeline() {
+        let yaml_content = r#"
+pipeline:
+  - name: read_file
+    id: reader
+    config:
+      path: "input.json"
+  - name: parse_json
+    id: parser
+  - name: format_csv
+    id: formatter
+    config:
+      headers: true
+      delimiter: ","
+  - name: write_file
+    id: writer
+    config:
+      path: "output.csv"
+
+metadata:
+  name: "Test Pipeline"
+  description: "A test pipeline"
+  version: "1.0.0"
+"#;
+
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(yaml_content.as_bytes()).unwrap();
+
+        let pipeline = Pipeline::load_from_file(temp_file.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(pipeline.step_count(), 4);
+        assert_eq!(pipeline.name(), "Test Pipeline");
+        assert_eq!(pipeline.pipeline[0].name, "read_file");
+        assert_eq!(pipeline.pipeline[0].get_id(), "reader");
+    }
+
+    #[test]
+    fn test_apply_defaults_merges_step_config_over_oxi_defaults() {
+        let mut pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+defaults:
+  http_request: {timeout_seconds: 10, retries: 2}
+pipeline:
+  - name: format_csv
+    id: plain
+  - name: format_csv
+    id: tabbed
+    config: {delimiter: "\t"}
+  - name: http_request
+    id: fetch
+    config:
+      url: https://example.com
+      headers: {Accept: application/json}
+"#,
+        )
+        .unwrap();
+        let defaults = serde_yaml::from_str(
+            r#"
+format_csv: {delimiter: ";", headers: true}
+http_request:
+  timeout_seconds: 5
+  headers: {User-Agent: oxide_flow, Accept: "*/*"}
+"#,
+        )
+        .unwrap();
+
+        pipeline.apply_defaults(&defaults);
+
+        let config = |index: usize, key: &str| pipeline.pipeline[index].config[key].clone();
+        // Applied where the step is silent
+        assert_eq!(config(0, "delimiter"), serde_yaml::Value::from(";"));
+        assert_eq!(config(0, "headers"), serde_yaml::Value::from(true));
+        // The step's own value wins
+        assert_eq!(config(1, "delimiter"), serde_yaml::Value::from("\t"));
+        assert_eq!(config(1, "headers"), serde_yaml::Value::from(true));
+        // The pipeline's defaults win over the project's
+        assert_eq!(config(2, "timeout_seconds"), serde_yaml::Value::from(10));
+        assert_eq!(config(2, "retries"), serde_yaml::Value::from(2));
+        // Nested mappings merge key by key
+        assert_eq!(
+            config(2, "headers"),
+            serde_yaml::from_str::<serde_yaml::Value>(
+                "{User-Agent: oxide_flow, Accept: application/json}"
+            )
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_incremental_read_resumes_from_saved_watermark() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("events.json");
+        std::fs::write(
+            &data_path,
+            r#"[{"id": 1}, {"id": 2}, {"id": 3}, {"id": 4}]"#,
+        )
+        .unwrap();
+
+        let pipeline: Pipeline = serde_yaml::from_str(&format!(
+            r#"
+metadata:
+  name: incremental_events
+pipeline:
+  - name: read_since
+    config:
+      path: "{}"
+      id_field: id
+      since: "${{state.last_processed_id}}"
+"#,
+            data_path.display()
+        ))
+        .unwrap();
+
+        // Pre-seed the watermark as if an earlier run had processed ids 1 and 2
+        let state_manager = StateManager::new_memory();
+        let mut seeded = crate::state::PipelineState::new(
+            "incremental_events".to_string(),
+            "earlier_run".to_string(),
+        );
+        seeded.last_processed_id = "2".to_string();
+        seeded.status = crate::state::PipelineStatus::Completed {
+            completed_at: chrono::Utc::now(),
+        };
+        state_manager.save_state(&seeded).await.unwrap();
+
+        let result = pipeline
+            .execute_with_state_tracking(
+                OxiData::empty(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+        assert!(result.success);
+        assert_eq!(
+            result.final_data.unwrap().data().as_json().unwrap(),
+            &serde_json::json!([{"id": 3}, {"id": 4}])
+        );
+
+        let state = state_manager
+            .load_state("incremental_events")
+            .await
+            .unwrap();
+        assert_eq!(state.last_processed_id, "4");
+
+        // Without state tracking there is no watermark to refer to
+        let result = pipeline
+            .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+            .await;
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn test_run_state_records_tags_and_environment() {
+        let mut pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+metadata:
+  name: ingest_orders
+  tags: [team=ingest, owner=data, nightly]
+pipeline:
+  - name: flatten
+"#,
+        )
+        .unwrap();
+        let project_tags = HashMap::from([
+            ("owner".to_string(), "platform".to_string()),
+            ("cost_center".to_string(), "42".to_string()),
+        ]);
+        pipeline.set_run_context(Some("prod".to_string()), &project_tags);
+
+        let state_manager = StateManager::new_memory();
+        let result = pipeline
+            .execute_with_state_tracking(
+                OxiData::from_json(serde_json::json!([{"id": 1}])),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+        assert!(result.success);
+
+        let state = state_manager.load_state("ingest_orders").await.unwrap();
+        assert_eq!(
+            state.metadata.pipeline_name.as_deref(),
+            Some("ingest_orders")
+        );
+        assert_eq!(state.metadata.environment.as_deref(), Some("prod"));
+        // The pipeline's own tags win over the project defaults
+        let expected: HashMap<String, String> = [
+            ("team", "ingest"),
+            ("owner", "data"),
+            ("nightly", ""),
+            ("cost_center", "42"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        assert_eq!(state.metadata.tags, expected);
+
+        // Mapping tags parse too, and the run context is not part of the definition
+        let mapped: Pipeline =
+            serde_yaml::from_str("metadata:\n  tags: {team: ingest}\npipeline: []\n").unwrap();
+        assert_eq!(mapped.run_tags()["team"], "ingest");
+        let hash = pipeline.content_hash();
+        pipeline.set_run_context(None, &HashMap::new());
+        assert_eq!(pipeline.content_hash(), hash);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_run_is_checkpointed_as_paused() {
+        // A missing file keeps the step in its retry backoff until cancelled
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+metadata:
+  name: interrupted
+pipeline:
+  - name: read_file
+    id: reader
+    retry_attempts: 3
+    config:
+      path: /nonexistent/oxide_flow/input.json
+  - name: flatten
+"#,
+        )
+        .unwrap();
+        let state_manager = StateManager::new_memory();
+        let cancel = CancellationToken::new();
+
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            trigger.cancel();
+        });
+        let result = pipeline
+            .execute_cancellable(
+                OxiData::empty(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+                cancel,
+            )
+            .await;
+
+        assert!(result.cancelled);
+        assert!(!result.success);
+        assert_eq!(result.steps_skipped, 1);
+        assert!(
+            result.total_duration_ms < 1000,
+            "{}",
+            result.total_duration_ms
+        );
+
+        let state = state_manager.load_state("interrupted").await.unwrap();
+        assert!(
+            matches!(state.status, crate::state::PipelineStatus::Paused { .. }),
+            "{:?}",
+            state.status
+        );
+        assert_eq!(
+            state.step_states["reader"].status,
+            crate::state::StepStatus::Pending
+        );
+        let error = state.errors.last().unwrap();
+        assert_eq!(error.error_type, crate::state::ErrorType::Cancelled);
+        assert!(!error.retryable);
+        assert!(state_manager
+            .is_locked("interrupted")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    /// Sleeps 200ms, optionally declaring a tighter processing time limit
+    struct Sleeper {
+        max_processing_time_ms: Option<u64>,
+    }
+
+    #[async_trait::async_trait]
+    impl Oxi for Sleeper {
+        fn name(&self) -> &str {
+            "sleeper"
+        }
+
+        fn schema_strategy(&self) -> crate::types::SchemaStrategy {
+            crate::types::SchemaStrategy::Passthrough
+        }
+
+        fn processing_limits(&self) -> crate::types::ProcessingLimits {
+            crate::types::ProcessingLimits {
+                max_processing_time_ms: self.max_processing_time_ms,
+                ..Default::default()
+            }
+        }
+
+        async fn process(&self, input: OxiData, _config: &OxiConfig) -> Result<OxiData, OxiError> {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok(input)
+        }
+    }
+
+    /// Counts its runs and marks its output
+    struct Counter(Arc<std::sync::atomic::AtomicUsize>);
+
+    #[async_trait::async_trait]
+    impl Oxi for Counter {
+        fn name(&self) -> &str {
+            "counter"
+        }
+
+        fn schema_strategy(&self) -> crate::types::SchemaStrategy {
+            crate::types::SchemaStrategy::Passthrough
+        }
+
+        async fn process(&self, _input: OxiData, _config: &OxiConfig) -> Result<OxiData, OxiError> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(OxiData::from_json(serde_json::json!({"counted": true})))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resumed_run_skips_unchanged_completed_steps() {
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let pipeline = |label: &str| {
+            builder::PipelineBuilder::new("resumable")
+                .step(
+                    "count",
+                    Arc::new(Counter(runs.clone())),
+                    OxiConfig::builder().set("label", label).build(),
+                )
+                .step(
+                    "nap",
+                    Arc::new(Sleeper {
+                        max_processing_time_ms: None,
+                    }),
+                    OxiConfig::default(),
+                )
+                .build()
+        };
+        let state_manager = StateManager::new_memory();
+        let pause = || async {
+            let cancel = CancellationToken::new();
+            let trigger = cancel.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                trigger.cancel();
+            });
+            let result = pipeline("a")
+                .execute_cancellable(
+                    OxiData::empty(),
+                    &ConfigResolver::new(),
+                    Some(state_manager.clone()),
+                    cancel,
+                )
+                .await;
+            assert!(result.cancelled);
+        };
+
+        pause().await;
+        let paused = state_manager.load_state("resumable").await.unwrap();
+        assert_eq!(paused.current_step, "nap");
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // The completed step is skipped and the paused step gets its output
+        let result = pipeline("a")
+            .execute_with_state_tracking(
+                OxiData::empty(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+        assert!(result.success);
+        assert_eq!(result.steps_skipped, 1);
+        assert_eq!(result.steps_executed, 1);
+        assert_eq!(result.run_id.as_deref(), Some(paused.run_id.as_str()));
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(
+            result.final_data.unwrap().data.as_json().unwrap(),
+            &serde_json::json!({"counted": true})
+        );
+        let state = state_manager.load_state("resumable").await.unwrap();
+        assert!(matches!(
+            state.status,
+            crate::state::PipelineStatus::Completed { .. }
+        ));
+        assert!(state.resume_checkpoint.is_none());
+        assert!(state_manager
+            .load_resume_checkpoint("resumable")
+            .await
+            .unwrap()
+            .is_none());
+
+        // A changed config runs the step again
+        pause().await;
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+        let result = pipeline("b")
+            .execute_with_state_tracking(
+                OxiData::empty(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+        assert!(result.success);
+        assert_eq!(result.steps_skipped, 0);
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    /// Outputs its resolved `label` config
+    struct Labeler;
+
+    #[async_trait::async_trait]
+    impl Oxi for Labeler {
+        fn name(&self) -> &str {
+            "labeler"
+        }
+
+        fn schema_strategy(&self) -> crate::types::SchemaStrategy {
+            crate::types::SchemaStrategy::Infer
+        }
+
+        async fn process(&self, _input: OxiData, config: &OxiConfig) -> Result<OxiData, OxiError> {
+            let label = config.get_string_or("label", "");
+            Ok(OxiData::from_json(serde_json::json!({ "label": label })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resumed_run_restores_skipped_step_outputs() {
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let pipeline = builder::PipelineBuilder::new("referencing")
+            .step(
+                "count",
+                Arc::new(Counter(runs.clone())),
+                OxiConfig::default(),
+            )
+            .step(
+                "nap",
+                Arc::new(Sleeper {
+                    max_processing_time_ms: None,
+                }),
+                OxiConfig::default(),
+            )
+            .step(
+                "label",
+                Arc::new(Labeler),
+                OxiConfig::builder()
+                    .set("label", "counted=${count.counted}")
+                    .build(),
+            )
+            .build();
+        let state_manager = StateManager::new_memory();
+
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            trigger.cancel();
+        });
+        let result = pipeline
+            .execute_cancellable(
+                OxiData::empty(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+                cancel,
+            )
+            .await;
+        assert!(result.cancelled);
+
+        // The payload lives beside the state, not in it
+        let paused = state_manager.load_state("referencing").await.unwrap();
+        let checkpoint = state_manager
+            .load_resume_checkpoint("referencing")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            paused.resume_checkpoint,
+            Some(checkpoint.content_hash().unwrap())
+        );
+        assert!(checkpoint.step_outputs.contains_key("count"));
+
+        // The skipped step's output still resolves in the step that references it
+        let result = pipeline
+            .execute_with_state_tracking(
+                OxiData::empty(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+        assert!(result.success);
+        assert_eq!(result.steps_skipped, 1);
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(
+            result.final_data.unwrap().data.as_json().unwrap(),
+            &serde_json::json!({"label": "counted=true"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_step_timeout_fails_slow_step() {
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+pipeline:
+  - name: read_file
+    id: reader
+    timeout_seconds: 30
+  - name: parse_json
+  - name: format_csv
+    id: writer
+    timeout_seconds: 0.5
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            pipeline.step_timeout_map(),
+            HashMap::from([
+                ("reader".to_string(), Duration::from_secs(30)),
+                ("writer".to_string(), Duration::from_millis(500)),
+            ])
+        );
+
+        let pipeline = builder::PipelineBuilder::new("slow")
+            .step(
+                "nap",
+                Arc::new(Sleeper {
+                    max_processing_time_ms: None,
+                }),
+                OxiConfig::default(),
+            )
+            .step_with(|s| s.timeout(Duration::from_millis(100)))
+            .build();
+        let state_manager = StateManager::new_memory();
+        let result = pipeline
+            .execute_with_state_tracking(
+                OxiData::empty(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+
+        assert!(!result.success);
+        let error = result.step_results[0].error.as_deref().unwrap();
+        assert!(
+            error.contains("Timeout after 0.1s in step 'nap'"),
+            "{error}"
+        );
+
+        let state = state_manager.load_state("slow").await.unwrap();
+        assert!(
+            matches!(
+                &state.step_states["nap"].status,
+                crate::state::StepStatus::Failed { error, .. } if error.contains("Timeout after 0.1s")
+            ),
+            "{:?}",
+            state.step_states["nap"].status
+        );
+        assert_eq!(
+            state.errors.last().unwrap().error_type,
+            crate::state::ErrorType::Resource
+        );
+    }
+
+    #[tokio::test]
+    async fn test_declared_time_limit_only_applies_when_configured() {
+        // The Oxi's declared limit alone does not time steps out
+        let pipeline = builder::PipelineBuilder::new("declared")
+            .step(
+                "nap",
+                Arc::new(Sleeper {
+                    max_processing_time_ms: Some(100),
+                }),
+                OxiConfig::default(),
+            )
+            .build();
+        let result = pipeline
+            .execute_with_state_tracking(OxiData::empty(), &ConfigResolver::new(), None)
+            .await;
+        assert!(result.success, "{:?}", result.step_results[0].error);
+
+        // Setting it in the step's config enforces it, capped at the declared value
+        let pipeline = builder::PipelineBuilder::new("configured")
+            .step(
+                "nap",
+                Arc::new(Sleeper {
+                    max_processing_time_ms: Some(100),
+                }),
+                OxiConfig::builder()
+                    .set("max_processing_time_ms", 150)
+                    .build(),
+            )
+            .build();
+        let state_manager = StateManager::new_memory();
+        let result = pipeline
+            .execute_with_state_tracking(
+                OxiData::empty(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+
+        assert!(!result.success);
+        let error = result.step_results[0].error.as_deref().unwrap();
+        assert!(
+            error.contains("Timeout after 0.1s in step 'nap'"),
+            "{error}"
+        );
+        let state = state_manager.load_state("configured").await.unwrap();
+        assert_eq!(
+            state.errors.last().unwrap().error_type,
+            crate::state::ErrorType::Resource
+        );
+    }
+
+    /// `format_json` needs its whole input, so it sees all of these at once
+    fn oversized_batch() -> OxiData {
+        let limit = crate::types::ProcessingLimits::default()
+            .max_batch_size
+            .unwrap();
+        OxiData::from_json(serde_json::Value::Array(
+            (0..=limit)
+                .map(|id| serde_json::json!({"id": id}))
+                .collect(),
+        ))
+    }
+
+    fn format_pipeline(enforcement: &str) -> Pipeline {
+        serde_yaml::from_str(&format!(
+            r#"
+metadata:
+  name: limited
+limits_enforcement: {enforcement}
+pipeline:
+  - name: format_json
+    id: formatter
+    retry_attempts: 2
+    config:
+      pretty: false
+"#
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_limit_violations_fail_the_step_without_retrying() {
+        let state_manager = StateManager::new_memory();
+        let result = format_pipeline("error")
+            .execute_with_state_tracking(
+                oversized_batch(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+
+        assert!(!result.success);
+        let step = &result.step_results[0];
+        assert_eq!(step.retry_count, 0);
+        assert_eq!(step.error_type, Some(ErrorType::Resource));
+        let error = step.error.as_deref().unwrap();
+        assert!(error.contains("max_batch_size"), "{error}");
+        assert!(error.contains("100001 records"), "{error}");
+        assert!(error.contains("batch step"), "{error}");
+
+        let state = state_manager.load_state("limited").await.unwrap();
+        let record = state.errors.last().unwrap();
+        assert_eq!(record.error_type, ErrorType::Resource);
+        assert!(!record.retryable);
+
+        // JSON-only Oxis reject other input types
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+limits_enforcement: error
+pipeline:
+  - name: coerce_types
+    id: typed
+"#,
+        )
+        .unwrap();
+        let result = pipeline
+            .execute_with_retries(
+                OxiData::from_text("id,name".to_string()),
+                &ConfigResolver::new(),
+            )
+            .await;
+        let error = result.step_results[0].error.as_deref().unwrap();
+        assert!(error.contains("supported_input_types"), "{error}");
+        assert!(error.contains("got Text, allowed JSON"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_limits_enforcement_warn_and_off() {
+        let state_manager = StateManager::new_memory();
+        let result = format_pipeline("warn")
+            .execute_with_state_tracking(
+                oversized_batch(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+        assert!(result.success);
+        let state = state_manager.load_state("limited").await.unwrap();
+        assert_eq!(state.errors.len(), 1);
+        assert_eq!(state.errors[0].error_type, ErrorType::Resource);
+        assert_eq!(state.errors[0].step_id.as_deref(), Some("formatter"));
+
+        let state_manager = StateManager::new_memory();
+        let result = format_pipeline("off")
+            .execute_with_state_tracking(
+                oversized_batch(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+        assert!(result.success);
+        let state = state_manager.load_state("limited").await.unwrap();
+        assert!(state.errors.is_empty());
+
+        // Pipelines that do not opt in keep running oversized batches
+        let mut pipeline = format_pipeline("warn");
+        pipeline.limits_enforcement = LimitsEnforcement::default();
+        assert_eq!(pipeline.limits_enforcement, LimitsEnforcement::Warn);
+        let result = pipeline
+            .execute_with_retries(oversized_batch(), &ConfigResolver::new())
+            .await;
+        assert!(result.success);
+
+        assert!(
+            serde_yaml::from_str::<Pipeline>("pipeline: []\nlimits_enforcement: loud").is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_step_config_tightens_processing_limits() {
+        let throttle = |max_processing_time_ms: u64| -> Pipeline {
+            serde_yaml::from_str(&format!(
+                r#"
+pipeline:
+  - name: throttle
+    id: paced
+    config:
+      requests_per_second: 10
+      max_processing_time_ms: {max_processing_time_ms}
+"#
+            ))
+            .unwrap()
+        };
+        let resolver = ConfigResolver::new();
+
+        // throttle declares the default 30s; a step may tighten it but not loosen it
+        assert_eq!(
+            throttle(5_000).pipeline[0].attempt_timeout(None, &resolver),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            throttle(60_000).pipeline[0].attempt_timeout(None, &resolver),
+            Some(Duration::from_secs(30))
+        );
+        // A step timeout replaces the limit rather than tightening it
+        let mut pipeline = throttle(5_000);
+        assert_eq!(
+            pipeline.pipeline[0].attempt_timeout(Some(Duration::from_secs(60)), &resolver),
+            Some(Duration::from_secs(60))
+        );
+        pipeline.pipeline[0].config.remove("max_processing_time_ms");
+        assert_eq!(pipeline.pipeline[0].attempt_timeout(None, &resolver), None);
+
+        // Five records at 10/s take about 400ms
+        let records = OxiData::from_json(serde_json::json!([1, 2, 3, 4, 5]));
+        let result = throttle(100).execute_with_retries(records, &resolver).await;
+        let step = &result.step_results[0];
+        assert!(!step.success);
+        assert_eq!(step.error_type, Some(ErrorType::Resource));
+
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+limits_enforcement: error
+pipeline:
+  - name: format_json
+    id: formatter
+    config:
+      max_batch_size: 2
+      supported_input_types: [json, text]
+"#,
+        )
+        .unwrap();
+        let result = pipeline
+            .execute_with_retries(OxiData::from_json(serde_json::json!([1, 2, 3])), &resolver)
+            .await;
+        let error = result.step_results[0].error.as_deref().unwrap();
+        assert!(error.contains("max_batch_size"), "{error}");
+        assert!(error.contains("allowed 2 records"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_step_metadata_flows_to_later_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("orders.json");
+        std::fs::write(&source, r#"{"id": 1}"#).unwrap();
+
+        let pipeline: Pipeline = serde_yaml::from_str(&format!(
+            r#"
+pipeline:
+  - name: read_file
+    id: reader
+    config:
+      path: "{}"
+  - name: flatten
+  - name: write_file
+    config:
+      path: "${{reader.metadata.source_path}}.copy"
+"#,
+            source.display()
+        ))
+        .unwrap();
+
+        let result = pipeline
+            .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+            .await;
+        assert!(result.success);
+
+        // The writer's path was resolved from what read_file reported
+        assert!(dir.path().join("orders.json.copy").exists());
+
+        // flatten and write_file don't touch metadata, so it passes through
+        let final_data = result.final_data.unwrap();
+        assert_eq!(
+            final_data.get_meta("source_path").unwrap(),
+            source.to_string_lossy().as_ref()
+        );
+        assert_eq!(final_data.get_meta("bytes_read").unwrap(), 9);
+    }
+
+    #[tokio::test]
+    async fn test_step_data_references_resolved_at_runtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let pipeline: Pipeline = serde_yaml::from_str(&format!(
+            r#"
+pipeline:
+  - name: parse_json
+    id: settings
+    config:
+      content: '{{"region": "eu", "batch": {{"label": "nightly"}}}}'
+  - name: parse_json
+    id: records
+    config:
+      content: '[{{"id": 1}}]'
+  - name: write_file
+    config:
+      path: "{dir}/${{settings.region}}-${{settings.batch.label}}.json"
+"#,
+            dir = dir.path().display()
+        ))
+        .unwrap();
+
+        let referenced = pipeline.data_referenced_steps();
+        assert_eq!(
+            referenced,
+            std::collections::HashSet::from(["settings".to_string()])
+        );
+
+        let result = pipeline
+            .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+            .await;
+        assert!(result.success, "{:?}", result.step_results);
+        let written = std::fs::read_to_string(dir.path().join("eu-nightly.json")).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&written).unwrap(),
+            serde_json::json!([{"id": 1}])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_union_step_combines_earlier_outputs() {
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+pipeline:
+  - name: parse_json
+    id: customers
+    config:
+      content: '[{"id": 1, "name": "ada"}]'
+  - name: parse_json
+    id: orders
+    config:
+      content: '[{"id": 7, "total": 12.5}]'
+  - name: union
+    config:
+      inputs: ["${customers}"]
+"#,
+        )
+        .unwrap();
+
+        let result = pipeline
+            .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+            .await;
+        assert!(result.success, "{:?}", result.step_results);
+        assert_eq!(
+            result.final_data.unwrap().data().as_json().unwrap(),
+            &serde_json::json!([
+                {"id": 7, "total": 12.5, "name": null},
+                {"id": 1, "total": null, "name": "ada"}
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_partitions_feed_later_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("orders.json");
+        std::fs::write(
+            &source,
+            r#"[{"id": 1, "amount": 250}, {"id": 2, "amount": 40}, {"id": 3, "amount": 120}]"#,
+        )
+        .unwrap();
+
+        let pipeline: Pipeline = serde_yaml::from_str(&format!(
+            r#"
+pipeline:
+  - name: read_file
+    config:
+      path: "{source}"
+  - name: parse_json
+  - name: partition
+    id: split
+    config:
+      rules:
+        large: amount >= 100
+  - name: write_file
+    config:
+      path: "{dir}/small.json"
+  - name: parse_json
+    id: large_orders
+    config:
+      content: "${{partition.large}}"
+  - name: write_file
+    config:
+      path: "{dir}/large.json"
+"#,
+            source = source.display(),
+            dir = dir.path().display()
+        ))
+        .unwrap();
+
+        let result = pipeline
+            .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+            .await;
+        assert!(result.success);
+
+        let written = |name: &str| -> serde_json::Value {
+            serde_json::from_str(&std::fs::read_to_string(dir.path().join(name)).unwrap()).unwrap()
+        };
+        // The default bucket is the partition step's own output
+        assert_eq!(
+            written("small.json"),
+            serde_json::json!([{"id": 2, "amount": 40}])
+        );
+        assert_eq!(
+            written("large.json"),
+            serde_json::json!([{"id": 1, "amount": 250}, {"id": 3, "amount": 120}])
+        );
+    }
+
+    #[test]
+    fn test_content_hash_ignores_formatting() {
+        let original: Pipeline = serde_yaml::from_str(
+            "metadata:\n  name: hashed\npipeline:\n  - name: read_file\n    config:\n      path: in.json\n      encoding: utf-8\n",
+        )
+        .unwrap();
+        let reformatted: Pipeline = serde_yaml::from_str(
+            "# comment\nmetadata:   {name: hashed}\n\npipeline:\n  -   name: read_file\n      config:\n        encoding: utf-8\n        path:   in.json\n",
+        )
+        .unwrap();
+        let edited: Pipeline = serde_yaml::from_str(
+            "metadata:\n  name: hashed\npipeline:\n  - name: read_file\n    config:\n      path: other.json\n",
+        )
+        .unwrap();
+
+        assert_eq!(original.content_hash(), reformatted.content_hash());
+        assert_ne!(original.content_hash(), edited.content_hash());
+    }
+
+    #[tokio::test]
+    async fn test_state_records_content_hash_and_detects_changes() {
+        let pipeline: Pipeline =
+            serde_yaml::from_str("metadata:\n  name: versioned\npipeline:\n  - name: parse_json\n")
+                .unwrap();
+        let state_manager = StateManager::new_memory();
+
+        pipeline
+            .execute_with_state_tracking(
+                OxiData::from_text("{}".to_string()),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+
+        let state = state_manager.load_state("versioned").await.unwrap();
+        assert_eq!(
+            state.metadata.pipeline_content_hash,
+            Some(pipeline.content_hash())
+        );
+        assert!(!state.pipeline_changed(&pipeline.content_hash()));
+
+        let mut changed = pipeline.clone();
+        changed.pipeline[0].continue_on_error = true;
+        assert!(state.pipeline_changed(&changed.content_hash()));
+    }
+
+    #[tokio::test]
+    async fn test_observer_sees_each_step_as_it_completes() {
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+pipeline:
+  - name: parse_json
+    id: parse
+  - name: not_a_real_oxi
+    id: broken
+    continue_on_error: true
+  - name: format_json
+    id: format
+"#,
+        )
+        .unwrap();
+
+        let completions = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = completions.clone();
+        let result = pipeline
+            .execute_with_observer(
+                OxiData::from_text(r#"[{"a": 1}, {"a": 2}]"#.to_string()),
+                &ConfigResolver::new(),
+                None,
+                move |completion: StepCompletion| {
+                    let recorded = recorded.clone();
+                    async move {
+                        recorded.lock().unwrap().push((
+                            completion.index,
+                            completion.step_id.clone(),
+                            completion.success,
+                            completion.summary(),
+                        ));
+                    }
+                },
+            )
+            .await;
+
+        let completions = completions.lock().unwrap();
+        let order: Vec<(usize, &str, bool)> = completions
+            .iter()
+            .map(|(index, id, success, _)| (*index, id.as_str(), *success))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (0, "parse", true),
+                (1, "broken", false),
+                (2, "format", true)
+            ]
+        );
+        assert!(completions[0].3.starts_with("JSON, 2 records"));
+        assert_eq!(completions[1].3, "no output");
+        assert_eq!(result.step_results.len(), 3);
+    }
+
+    /// Fails every call, with a network error unless `network` is false
+    struct Unreachable {
+        calls: std::sync::atomic::AtomicUsize,
+        network: bool,
+    }
+
+    impl Unreachable {
+        fn new(network: bool) -> Arc<Self> {
+            Arc::new(Self {
+                calls: std::sync::atomic::AtomicUsize::new(0),
+                network,
+            })
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Oxi for Unreachable {
+        fn name(&self) -> &str {
+            "unreachable"
+        }
+
+        fn schema_strategy(&self) -> crate::types::SchemaStrategy {
+            crate::types::SchemaStrategy::Passthrough
+        }
+
+        async fn process(&self, _input: OxiData, _config: &OxiConfig) -> Result<OxiData, OxiError> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(if self.network {
+                OxiError::NetworkError("connection refused".to_string())
+            } else {
+                OxiError::ExecutionError("bad record".to_string())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_trips_and_short_circuits() {
+        let oxi = Unreachable::new(true);
+        let pipeline = builder::PipelineBuilder::new("outage")
+            .circuit_breaker(resilience::CircuitBreakerConfig {
+                failure_threshold: 2,
+                window_seconds: 60,
+                cooldown_seconds: 60,
+            })
+            .step("fetch", oxi.clone(), OxiConfig::default())
+            .step_with(|s| s.retry(5))
+            .build();
+
+        // The second failure opens the circuit, so the other retries are skipped
+        let first = pipeline
+            .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+            .await;
+        assert!(!first.success);
+        assert_eq!(oxi.calls(), 2);
+        assert_eq!(first.step_results[0].retry_count, 1);
+        assert_eq!(
+            CircuitBreakers::for_pipeline("outage").state("fetch"),
+            Some(resilience::CircuitState::Open)
+        );
+
+        // Later runs fail fast without calling the Oxi, even with the pipeline loaded afresh
+        let reloaded = builder::PipelineBuilder::new("outage")
+            .circuit_breaker(resilience::CircuitBreakerConfig {
+                failure_threshold: 2,
+                window_seconds: 60,
+                cooldown_seconds: 60,
+            })
+            .step("fetch", oxi.clone(), OxiConfig::default())
+            .build();
+        let second = reloaded
+            .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+            .await;
+        let step = &second.step_results[0];
+        assert!(!step.success);
+        assert_eq!(oxi.calls(), 2);
+        assert_eq!(step.error_type, Some(ErrorType::Network));
+        assert!(step.error.as_ref().unwrap().contains("Circuit open"));
+
+        // Healthy steps report a closed circuit in their metadata
+        let healthy = builder::PipelineBuilder::new("healthy")
+            .circuit_breaker(Default::default())
+            .step(
+                "parse",
+                "parse_json",
+                OxiConfig::builder().set("content", "[1]").build(),
+            )
+            .build();
+        let result = healthy
+            .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+            .await;
+        assert_eq!(
+            result.final_data.unwrap().get_meta("circuit_breaker"),
+            Some(&serde_json::json!({"state": "closed", "consecutive_failures": 0}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_caps_retries_across_steps() {
+        let oxi = Unreachable::new(false);
+        let pipeline = builder::PipelineBuilder::new("budgeted")
+            .retry_budget(1)
+            .step("first", oxi.clone(), OxiConfig::default())
+            .step_with(|s| s.retry(3).continue_on_error())
+            .step("second", oxi.clone(), OxiConfig::default())
+            .step_with(|s| s.retry(3))
+            .build();
+
+        let result = pipeline
+            .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+            .await;
+
+        assert!(!result.success);
+        // One retry in total, spent by the first step; non-network errors don't trip breakers
+        assert_eq!(oxi.calls(), 3);
+        assert_eq!(result.step_results[0].retry_count, 1);
+        assert_eq!(result.step_results[1].retry_count, 0);
+        assert_eq!(
+            CircuitBreakers::for_pipeline("budgeted").state("first"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_records_carry_the_oxi_error_classification() {
+        let pipeline = builder::PipelineBuilder::new("classified")
+            .step("fetch", Unreachable::new(true), OxiConfig::default())
+            .step_with(|s| s.continue_on_error())
+            .step("transform", Unreachable::new(false), OxiConfig::default())
+            .step_with(|s| s.continue_on_error())
+            .step(
+                "read",
+                "read_file",
+                OxiConfig::builder()
+                    .set("path", "/nonexistent/input.json")
+                    .build(),
+            )
+            .build();
+        let state_manager = StateManager::new_memory();
+
+        let result = pipeline
+            .execute_with_state_tracking(
+                OxiData::empty(),
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+
+        assert!(!result.success);
+        assert!(result.step_results[0].retryable);
+        let state = state_manager.load_state("classified").await.unwrap();
+        let classified: Vec<_> = state
+            .errors
+            .iter()
+            .map(|e| {
+                (
+                    e.step_id.as_deref().unwrap(),
+                    e.error_type.clone(),
+                    e.retryable,
+                )
+            })
+            .collect();
+        assert_eq!(
+            classified,
+            [
+                ("fetch", ErrorType::Network, true),
+                ("transform", ErrorType::Processing, false),
+                ("read", ErrorType::Configuration, false),
+            ]
+        );
+    }
+
+    /// Doubles `n` in every record, counting `process` calls
+    struct CountingOxi {
+        calls: std::sync::atomic::AtomicUsize,
+        limit: usize,
+        full_batch: bool,
+    }
+
+    impl CountingOxi {
+        fn new(limit: usize, full_batch: bool) -> Self {
+            Self {
+                calls: std::sync::atomic::AtomicUsize::new(0),
+                limit,
+                full_batch,
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Oxi for CountingOxi {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn schema_strategy(&self) -> crate::types::SchemaStrategy {
+            crate::types::SchemaStrategy::Passthrough
+        }
+
+        fn processing_limits(&self) -> crate::types::ProcessingLimits {
+            crate::types::ProcessingLimits {
+                max_batch_size: Some(self.limit),
+                ..Default::default()
+            }
+        }
+
+        fn requires_full_batch(&self) -> bool {
+            self.full_batch
+        }
+
+        async fn process(&self, input: OxiData, _config: &OxiConfig) -> Result<OxiData, OxiError> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let records = input.data().as_array().unwrap();
+            let doubled = records
+                .iter()
+                .map(|r| serde_json::json!({"n": r["n"].as_i64().unwrap() * 2}))
+                .collect();
+            let mut output = OxiData::from_json(serde_json::Value::Array(doubled));
+            output.set_meta("last_call", call);
+            Ok(output)
+        }
+    }
+
+    fn numbered(count: i64) -> OxiData {
+        let mut input = OxiData::from_json(serde_json::Value::Array(
+            (0..count).map(|n| serde_json::json!({"n": n})).collect(),
+        ));
+        input.set_meta("source", "numbers");
+        input
+    }
+
+    #[tokio::test]
+    async fn test_oversized_batch_is_processed_in_chunks() {
+        let oxi = CountingOxi::new(4, false);
+        let mut progress = Vec::new();
+
+        let output = process_in_chunks(&oxi, numbered(10), &OxiConfig::default(), |chunk| {
+            progress.push(chunk);
+            std::future::ready(())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(oxi.calls(), 3);
+        let sizes: Vec<usize> = progress.iter().map(|c| c.records).collect();
+        assert_eq!(sizes, vec![4, 4, 2]);
+        assert!(progress.iter().all(|c| c.total == 3));
+
+        let records = output.data().as_array().unwrap();
+        let values: Vec<i64> = records.iter().map(|r| r["n"].as_i64().unwrap()).collect();
+        assert_eq!(values, (0..10).map(|n| n * 2).collect::<Vec<_>>());
+        assert_eq!(output.get_meta("last_call"), Some(&serde_json::json!(2)));
+        assert!(output.schema().fields.contains_key("n"));
+    }
+
+    #[tokio::test]
+    async fn test_batches_within_limit_or_opted_out_are_not_split() {
+        let small = CountingOxi::new(10, false);
+        process_in_chunks(&small, numbered(10), &OxiConfig::default(), |_| async {})
+            .await
+            .unwrap();
+        assert_eq!(small.calls(), 1);
+
+        let whole = CountingOxi::new(3, true);
+        let output = process_in_chunks(&whole, numbered(10), &OxiConfig::default(), |_| async {})
+            .await
+            .unwrap();
+        assert_eq!(whole.calls(), 1);
+        assert_eq!(output.data().batch_size(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_chunked_text_output_is_rejected() {
+        // format_json opts out; a custom Oxi that does not must return arrays
+        struct Stringify;
+
+        #[async_trait::async_trait]
+        impl Oxi for Stringify {
+            fn name(&self) -> &str {
+                "stringify"
+            }
+
+            fn schema_strategy(&self) -> crate::types::SchemaStrategy {
+                crate::types::SchemaStrategy::Passthrough
+            }
+
+            fn processing_limits(&self) -> crate::types::ProcessingLimits {
+                crate::types::ProcessingLimits {
+                    max_batch_size: Some(2),
+                    ..Default::default()
+                }
+            }
+
+            async fn process(
+                &self,
+                input: OxiData,
+                _config: &OxiConfig,
+            ) -> Result<OxiData, OxiError> {
+                Ok(OxiData::from_text(input.data().to_text().unwrap()))
+            }
+        }
+
+        let err = process_in_chunks(&Stringify, numbered(5), &OxiConfig::default(), |_| async {})
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("chunk 1 of 3"), "{err}");
+        assert!(err.to_string().contains("requires_full_batch"), "{err}");
+        assert!(crate::oxis::builtin("format_json")
+            .unwrap()
+            .requires_full_batch());
+    }
+
+    #[test]
+    fn test_dry_run_schema_records_step_errors() {
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+            pipeline:
+              - name: read_stdin
+                id: input
+              - name: coerce_types
+                id: typed
+              - name: write_stdout
+            "#,
+        )
+        .unwrap();
+        let initial =
+            OxiSchema::infer_from_data(&Data::Json(serde_json::json!({"id": 1}))).unwrap();
+
+        let trace = pipeline
+            .dry_run_schema(initial, &ConfigResolver::default())
+            .unwrap();
+
+        assert_eq!(trace.len(), 3);
+        assert_eq!(trace[0].step_id, "input");
+        assert!(trace[0]
+            .input_schema
+            .as_ref()
+            .unwrap()
+            .fields
+            .contains_key("id"));
+        assert!(trace[0].error.is_none());
+
+        assert_eq!(trace[1].step_name, "coerce_types");
+        assert!(trace[1].output_schema.is_none());
+        let error = trace[1].error.as_deref().unwrap();
+        assert!(error.starts_with("schema inference failed"), "{error}");
+
+        // The trace continues past the failure with an unknown input
+        assert!(trace[2].input_schema.is_none());
+        assert!(trace[2].output_schema.is_some());
+    }
+}
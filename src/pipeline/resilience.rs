@@ -0,0 +1,323 @@
+//! Limits on how hard a run retries: a retry budget shared by all steps and
+//! a circuit breaker per step
+//!
+//! A step's breaker counts consecutive network failures, timeouts included.
+//! Once `failure_threshold` of them happen within `window_seconds`, the
+//! circuit opens and the step fails fast with [`OxiError::CircuitOpen`]
+//! instead of calling its Oxi. After `cooldown_seconds` the next call goes
+//! through as a trial: success closes the circuit, failure opens it again.
+//! Breakers are kept per process, keyed by pipeline name and step id, so
+//! they carry over between runs of a pipeline even though every run loads
+//! it afresh, e.g. under `watch`.
+
+use crate::error::OxiError;
+use crate::state::types::ErrorType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// The `circuit_breaker` section of a pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive network failures that open a step's circuit
+    pub failure_threshold: u32,
+    /// Failures further apart than this start a new count
+    pub window_seconds: u64,
+    /// How long an open circuit fails fast before letting a trial call through
+    pub cooldown_seconds: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            window_seconds: 60,
+            cooldown_seconds: 30,
+        }
+    }
+}
+
+/// Where a breaker stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Calls go through
+    Closed,
+    /// Calls fail fast until the cooldown elapses
+    Open,
+    /// The cooldown elapsed; the next call decides
+    HalfOpen,
+}
+
+/// Breaker of a single step
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    consecutive_failures: u32,
+    first_failure: Option<Instant>,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            consecutive_failures: 0,
+            first_failure: None,
+            opened_at: None,
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.state_at(Instant::now())
+    }
+
+    fn state_at(&self, now: Instant) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened) if now.duration_since(opened) < self.cooldown() => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.config.cooldown_seconds)
+    }
+
+    /// Time left before an open circuit lets a call through, `None` if it does now
+    pub fn blocked_for(&self) -> Option<Duration> {
+        self.blocked_for_at(Instant::now())
+    }
+
+    fn blocked_for_at(&self, now: Instant) -> Option<Duration> {
+        let opened = self.opened_at?;
+        self.cooldown()
+            .checked_sub(now.duration_since(opened))
+            .filter(|left| !left.is_zero())
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.first_failure = None;
+        self.opened_at = None;
+    }
+
+    /// Count a network failure; returns whether the circuit is now open
+    pub fn record_failure(&mut self) -> bool {
+        self.record_failure_at(Instant::now())
+    }
+
+    fn record_failure_at(&mut self, now: Instant) -> bool {
+        let window = Duration::from_secs(self.config.window_seconds);
+        if self
+            .first_failure
+            .is_none_or(|first| now.duration_since(first) > window)
+        {
+            self.consecutive_failures = 0;
+            self.first_failure = Some(now);
+        }
+        self.consecutive_failures += 1;
+
+        // A failed trial reopens at once
+        if self.state_at(now) == CircuitState::HalfOpen
+            || self.consecutive_failures >= self.config.failure_threshold
+        {
+            self.opened_at = Some(now);
+        }
+        self.opened_at == Some(now)
+    }
+
+    /// The breaker as step metadata, e.g. `{"state": "open", "consecutive_failures": 5}`
+    pub fn to_metadata(&self) -> serde_json::Value {
+        serde_json::json!({
+            "state": self.state(),
+            "consecutive_failures": self.consecutive_failures,
+        })
+    }
+}
+
+/// Breakers by step id of one pipeline
+#[derive(Debug, Clone, Default)]
+pub struct CircuitBreakers(Arc<Mutex<HashMap<String, CircuitBreaker>>>);
+
+impl CircuitBreakers {
+    /// Breakers of the pipeline named `pipeline`, shared by all its runs in this process
+    pub fn for_pipeline(pipeline: &str) -> Self {
+        static REGISTRY: OnceLock<Mutex<HashMap<String, CircuitBreakers>>> = OnceLock::new();
+        REGISTRY
+            .get_or_init(Mutex::default)
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .entry(pipeline.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Current state of a step's breaker, `None` before its first call
+    pub fn state(&self, step_id: &str) -> Option<CircuitState> {
+        self.lock().get(step_id).map(CircuitBreaker::state)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CircuitBreaker>> {
+        // A panic while holding the lock can't leave a breaker half-updated
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Retry budget and breakers applying to one run's steps
+#[derive(Debug, Default)]
+pub(crate) struct RunGuards {
+    /// Retries left for the run; `None` is unlimited
+    retries_left: Option<AtomicU32>,
+    breakers: Option<(CircuitBreakerConfig, CircuitBreakers)>,
+}
+
+impl RunGuards {
+    pub(crate) fn new(
+        retry_budget: Option<u32>,
+        circuit_breaker: Option<&CircuitBreakerConfig>,
+        breakers: &CircuitBreakers,
+    ) -> Self {
+        Self {
+            retries_left: retry_budget.map(AtomicU32::new),
+            breakers: circuit_breaker.map(|config| (config.clone(), breakers.clone())),
+        }
+    }
+
+    /// Spend one retry from the budget; false once it is used up
+    pub(crate) fn take_retry(&self) -> bool {
+        match &self.retries_left {
+            None => true,
+            Some(left) => left
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok(),
+        }
+    }
+
+    /// Fail with `CircuitOpen` while the step's circuit is open
+    pub(crate) fn check_circuit(&self, step_id: &str) -> Result<(), OxiError> {
+        let Some((_, breakers)) = &self.breakers else {
+            return Ok(());
+        };
+        let breakers = breakers.lock();
+        let Some(breaker) = breakers.get(step_id) else {
+            return Ok(());
+        };
+        match breaker.blocked_for() {
+            None => Ok(()),
+            Some(left) => Err(OxiError::CircuitOpen {
+                step: step_id.to_string(),
+                failures: breaker.consecutive_failures,
+                retry_in_ms: left.as_millis() as u64,
+            }),
+        }
+    }
+
+    /// Feed an attempt's outcome to the step's breaker; returns whether it opened the circuit
+    ///
+    /// Only network failures and timeouts count; any other error leaves the
+    /// breaker as it was.
+    pub(crate) fn record(&self, step_id: &str, outcome: Result<(), &anyhow::Error>) -> bool {
+        let Some((config, breakers)) = &self.breakers else {
+            return false;
+        };
+        let mut breakers = breakers.lock();
+        let breaker = breakers
+            .entry(step_id.to_string())
+            .or_insert_with(|| CircuitBreaker::new(config.clone()));
+        match outcome {
+            Ok(()) => {
+                breaker.record_success();
+                false
+            }
+            Err(error) if is_network_failure(error) => breaker.record_failure(),
+            Err(_) => false,
+        }
+    }
+
+    /// The step's breaker as metadata, if breakers are configured
+    pub(crate) fn circuit_metadata(&self, step_id: &str) -> Option<serde_json::Value> {
+        let (config, breakers) = self.breakers.as_ref()?;
+        let breakers = breakers.lock();
+        Some(match breakers.get(step_id) {
+            Some(breaker) => breaker.to_metadata(),
+            None => CircuitBreaker::new(config.clone()).to_metadata(),
+        })
+    }
+}
+
+/// Network errors and timeouts, including steps that ran out of `timeout_seconds`
+fn is_network_failure(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<crate::error::OxiError>(),
+        Some(crate::error::OxiError::Timeout { .. })
+    ) || matches!(
+        super::classify_failure(error).0,
+        ErrorType::Network | ErrorType::Timeout
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            window_seconds: 10,
+            cooldown_seconds: 30,
+        })
+    }
+
+    #[test]
+    fn test_breaker_opens_after_consecutive_failures_and_recovers() {
+        let start = Instant::now();
+        let mut breaker = breaker();
+
+        assert!(!breaker.record_failure_at(start));
+        assert!(!breaker.record_failure_at(start + Duration::from_secs(1)));
+        assert!(breaker.record_failure_at(start + Duration::from_secs(2)));
+        let opened = start + Duration::from_secs(2);
+        assert_eq!(breaker.state_at(opened), CircuitState::Open);
+        assert_eq!(
+            breaker.blocked_for_at(opened + Duration::from_secs(10)),
+            Some(Duration::from_secs(20))
+        );
+
+        // After the cooldown one trial goes through; its failure reopens the circuit
+        let trial = opened + Duration::from_secs(30);
+        assert_eq!(breaker.state_at(trial), CircuitState::HalfOpen);
+        assert_eq!(breaker.blocked_for_at(trial), None);
+        assert!(breaker.record_failure_at(trial));
+        assert_eq!(breaker.state_at(trial), CircuitState::Open);
+
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_failures_outside_the_window_start_over() {
+        let start = Instant::now();
+        let mut breaker = breaker();
+
+        breaker.record_failure_at(start);
+        breaker.record_failure_at(start + Duration::from_secs(1));
+        assert!(!breaker.record_failure_at(start + Duration::from_secs(20)));
+        assert_eq!(breaker.consecutive_failures, 1);
+    }
+
+    #[test]
+    fn test_retry_budget_is_shared() {
+        let guards = RunGuards::new(Some(2), None, &CircuitBreakers::default());
+        assert!(guards.take_retry());
+        assert!(guards.take_retry());
+        assert!(!guards.take_retry());
+
+        assert!(RunGuards::default().take_retry());
+    }
+}
@@ -0,0 +1,292 @@
+//! Self-contained pipeline archives for `pipeline export --bundle` and `pipeline import`
+//!
+//! A bundle is a gzipped tar holding `manifest.json` and the pipeline YAML
+//! under `pipelines/`. The manifest lists what the pipeline expects from the
+//! project it is imported into: environment variables, secrets and Oxis.
+
+use crate::config_resolver::env_references;
+use crate::oxis::registry::OxiRegistry;
+use crate::pipeline::Pipeline;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Name of the manifest inside a bundle
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Directory of the pipeline YAML inside a bundle
+const PIPELINE_DIR: &str = "pipelines";
+
+/// What a bundled pipeline needs from its target environment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Display name from the pipeline's metadata
+    pub pipeline: String,
+    /// File name of the pipeline YAML, written to the pipeline directory on import
+    pub file: String,
+    /// Version of oxide_flow that created the bundle
+    pub oxiflow_version: String,
+    pub created_at: DateTime<Utc>,
+    /// `${VAR}` references, sorted by name
+    pub env_vars: Vec<EnvRequirement>,
+    /// `${secret:NAME}` references, sorted
+    #[serde(default)]
+    pub secrets: Vec<String>,
+    /// Oxis named by the pipeline's steps, sorted
+    pub oxis: Vec<String>,
+}
+
+/// One environment variable a pipeline references
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvRequirement {
+    pub name: String,
+    /// Fallback from `${VAR:-default}`; `None` when some reference has none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+impl EnvRequirement {
+    /// Whether the variable must be set for the pipeline to run
+    pub fn required(&self) -> bool {
+        self.default.is_none()
+    }
+}
+
+impl BundleManifest {
+    /// Scan a pipeline's YAML for the environment it needs
+    pub fn for_pipeline(file: &str, content: &str) -> Result<Self> {
+        let pipeline: Pipeline =
+            serde_yaml::from_str(content).with_context(|| format!("Failed to parse {file}"))?;
+        let document: serde_yaml::Value = serde_yaml::from_str(content)?;
+
+        let mut strings = Vec::new();
+        collect_strings(&document, &mut strings);
+
+        let name = pipeline
+            .metadata
+            .as_ref()
+            .and_then(|m| m.name.clone())
+            .unwrap_or_else(|| file.trim_end_matches(".yaml").to_string());
+
+        Ok(Self {
+            pipeline: name,
+            file: file.to_string(),
+            oxiflow_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            env_vars: referenced_env_vars(&strings),
+            secrets: referenced_secrets(&strings),
+            oxis: pipeline
+                .pipeline
+                .iter()
+                .map(|step| step.name.clone())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    /// Requirements the current environment does not meet
+    ///
+    /// Reports required environment variables that are unset and Oxis
+    /// `registry` does not know. Secrets are not checked, as they may come
+    /// from a secrets directory rather than the environment.
+    pub fn unmet_requirements(&self, registry: &OxiRegistry) -> Vec<String> {
+        let missing_vars = self
+            .env_vars
+            .iter()
+            .filter(|var| var.required() && std::env::var(&var.name).is_err())
+            .map(|var| format!("Environment variable '{}' is not set", var.name));
+        let unknown_oxis = self
+            .oxis
+            .iter()
+            .filter(|oxi| !registry.contains(oxi))
+            .map(|oxi| format!("Oxi '{oxi}' is not available in this project"));
+        missing_vars.chain(unknown_oxis).collect()
+    }
+}
+
+/// Write a bundle of `manifest` and the pipeline YAML to `path`
+pub fn write_bundle(path: &Path, manifest: &BundleManifest, content: &str) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(
+        file,
+        flate2::Compression::default(),
+    ));
+
+    let manifest_json = serde_json::to_string_pretty(manifest)?;
+    let pipeline_path = format!("{PIPELINE_DIR}/{}", manifest.file);
+    for (name, data) in [
+        (MANIFEST_FILE, manifest_json.as_bytes()),
+        (pipeline_path.as_str(), content.as_bytes()),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(manifest.created_at.timestamp().max(0) as u64);
+        header.set_cksum();
+        archive.append_data(&mut header, name, data)?;
+    }
+
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Read a bundle's manifest and pipeline YAML
+pub fn read_bundle(path: &Path) -> Result<(BundleManifest, String)> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+
+    let mut files = BTreeMap::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .with_context(|| format!("Failed to read {name} from bundle"))?;
+        files.insert(name, content);
+    }
+
+    let manifest: BundleManifest = serde_json::from_str(
+        files
+            .get(MANIFEST_FILE)
+            .with_context(|| format!("Bundle has no {MANIFEST_FILE}"))?,
+    )
+    .context("Invalid bundle manifest")?;
+
+    // The file name becomes a path in the pipeline directory on import
+    if manifest.file.is_empty()
+        || manifest.file.contains(['/', '\\'])
+        || manifest.file.starts_with('.')
+    {
+        anyhow::bail!(
+            "Invalid pipeline file name in manifest: '{}'",
+            manifest.file
+        );
+    }
+
+    let content = files
+        .remove(&format!("{PIPELINE_DIR}/{}", manifest.file))
+        .with_context(|| format!("Bundle does not contain {}", manifest.file))?;
+    Ok((manifest, content))
+}
+
+fn collect_strings<'a>(value: &'a serde_yaml::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_yaml::Value::String(s) => out.push(s),
+        serde_yaml::Value::Sequence(items) => {
+            items.iter().for_each(|item| collect_strings(item, out))
+        }
+        serde_yaml::Value::Mapping(map) => map.values().for_each(|item| collect_strings(item, out)),
+        serde_yaml::Value::Tagged(tagged) => collect_strings(&tagged.value, out),
+        _ => {}
+    }
+}
+
+/// Environment variables referenced by the pipeline, as the config resolver reads them
+fn referenced_env_vars(strings: &[&str]) -> Vec<EnvRequirement> {
+    let mut vars: BTreeMap<String, Option<String>> = BTreeMap::new();
+    for reference in strings.iter().flat_map(|s| env_references(s)) {
+        let default = reference.default.map(str::to_string);
+        vars.entry(reference.name.to_string())
+            // A single reference without a default makes the variable required
+            .and_modify(|existing| {
+                if default.is_none() {
+                    *existing = None;
+                }
+            })
+            .or_insert(default);
+    }
+
+    vars.into_iter()
+        .map(|(name, default)| EnvRequirement { name, default })
+        .collect()
+}
+
+fn referenced_secrets(strings: &[&str]) -> Vec<String> {
+    static SECRET_REF: OnceLock<Regex> = OnceLock::new();
+    let secret_ref =
+        SECRET_REF.get_or_init(|| Regex::new(r"\$\{secret:\s*([^}\s]+)\s*\}").unwrap());
+
+    strings
+        .iter()
+        .flat_map(|s| secret_ref.captures_iter(s))
+        .map(|cap| cap[1].to_string())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIPELINE: &str = r#"
+metadata:
+  name: orders
+pipeline:
+  - name: read_file
+    config:
+      path: "${DATA_DIR}/orders.json"
+  - name: parse_json
+  - name: write_file
+    config:
+      path: "${OUT_DIR:-output}/orders.json"
+      token: "${secret:API_TOKEN}"
+      note: "${read.records}"
+      archive: "${ARCHIVE_DIR:archive}"
+"#;
+
+    #[test]
+    fn test_manifest_lists_env_vars_secrets_and_oxis() {
+        let manifest = BundleManifest::for_pipeline("orders.yaml", PIPELINE).unwrap();
+
+        assert_eq!(manifest.pipeline, "orders");
+        assert_eq!(
+            manifest.env_vars,
+            vec![
+                // `${VAR:value}` has no `-`, so the resolver still requires it
+                EnvRequirement {
+                    name: "ARCHIVE_DIR".to_string(),
+                    default: None
+                },
+                EnvRequirement {
+                    name: "DATA_DIR".to_string(),
+                    default: None
+                },
+                EnvRequirement {
+                    name: "OUT_DIR".to_string(),
+                    default: Some("output".to_string())
+                },
+            ]
+        );
+        assert_eq!(manifest.secrets, vec!["API_TOKEN"]);
+        assert_eq!(manifest.oxis, vec!["parse_json", "read_file", "write_file"]);
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.tar.gz");
+        let manifest = BundleManifest::for_pipeline("orders.yaml", PIPELINE).unwrap();
+
+        write_bundle(&path, &manifest, PIPELINE).unwrap();
+        let (read_manifest, content) = read_bundle(&path).unwrap();
+
+        assert_eq!(read_manifest, manifest);
+        assert_eq!(content, PIPELINE);
+
+        let mut registry = OxiRegistry::new();
+        registry.register("read_file", || crate::oxis::builtin("read_file").unwrap());
+        let unmet = manifest.unmet_requirements(&registry);
+        assert!(unmet.contains(&"Oxi 'write_file' is not available in this project".to_string()));
+        assert!(!unmet.iter().any(|issue| issue.contains("OUT_DIR")));
+    }
+}
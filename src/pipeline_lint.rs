@@ -0,0 +1,710 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Severity of a lint finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintSeverity::Info => write!(f, "info"),
+            LintSeverity::Warning => write!(f, "warning"),
+            LintSeverity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Lint settings from the `lint` section of oxiflow.yaml
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Rules that should never be reported
+    #[serde(default)]
+    pub disabled_rules: Vec<String>,
+
+    /// Per-rule severity overrides (e.g. `missing_timeout: error`)
+    #[serde(default)]
+    pub severity: HashMap<String, LintSeverity>,
+}
+
+/// Built-in lint rules with their default severity and a short description
+pub const LINT_RULES: &[(&str, LintSeverity, &str)] = &[
+    (
+        "missing_timeout",
+        LintSeverity::Warning,
+        "Step has no timeout_seconds",
+    ),
+    (
+        "writer_without_retry",
+        LintSeverity::Warning,
+        "Writer step has no retry_attempts",
+    ),
+    (
+        "missing_description",
+        LintSeverity::Warning,
+        "Pipeline metadata has no description",
+    ),
+    (
+        "missing_tags",
+        LintSeverity::Info,
+        "Pipeline metadata has no tags",
+    ),
+    (
+        "absolute_path",
+        LintSeverity::Warning,
+        "Configuration contains a hard-coded absolute path",
+    ),
+    (
+        "hardcoded_secret",
+        LintSeverity::Error,
+        "Secret-looking key has a literal value instead of a reference",
+    ),
+    (
+        "duplicate_step_id",
+        LintSeverity::Error,
+        "Two steps share the same id",
+    ),
+    (
+        "unused_step_output",
+        LintSeverity::Info,
+        "Step id is never referenced by a later step",
+    ),
+];
+
+/// A single rule violation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub rule: String,
+    pub severity: LintSeverity,
+    pub message: String,
+    pub step_id: Option<String>,
+    /// 1-based line number in the pipeline file, when it can be located
+    pub line: Option<usize>,
+}
+
+/// Lint results for one pipeline file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintReport {
+    pub pipeline_path: PathBuf,
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    /// Whether any finding has error severity
+    pub fn has_errors(&self) -> bool {
+        self.findings
+            .iter()
+            .any(|f| f.severity == LintSeverity::Error)
+    }
+
+    /// Number of findings with the given severity
+    pub fn count(&self, severity: LintSeverity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+}
+
+/// Runs style and robustness rules over pipeline definitions
+pub struct PipelineLinter {
+    config: LintConfig,
+}
+
+impl PipelineLinter {
+    /// Create a linter with the given rule configuration
+    pub fn new(config: LintConfig) -> Self {
+        Self { config }
+    }
+
+    /// Lint a pipeline file on disk
+    pub fn lint_file(&self, path: &Path) -> Result<LintReport> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read pipeline file: {}", path.display()))?;
+        self.lint_str(&content, path)
+    }
+
+    /// Lint pipeline YAML content
+    pub fn lint_str(&self, content: &str, path: &Path) -> Result<LintReport> {
+        let doc: serde_yaml::Value = serde_yaml::from_str(content)
+            .with_context(|| format!("Invalid YAML in {}", path.display()))?;
+        let source = SourceMap::new(content);
+
+        let mut findings = Vec::new();
+        let steps = doc
+            .get("pipeline")
+            .and_then(|p| p.as_sequence())
+            .cloned()
+            .unwrap_or_default();
+
+        self.check_metadata(&doc, &source, &mut findings);
+        self.check_steps(&steps, &source, &mut findings);
+
+        findings.retain(|f| {
+            !self.config.disabled_rules.contains(&f.rule) && !source.is_ignored(f.line, &f.rule)
+        });
+        for finding in &mut findings {
+            if let Some(severity) = self.config.severity.get(&finding.rule) {
+                finding.severity = *severity;
+            }
+        }
+        findings.sort_by_key(|f| f.line);
+
+        Ok(LintReport {
+            pipeline_path: path.to_path_buf(),
+            findings,
+        })
+    }
+
+    fn check_metadata(
+        &self,
+        doc: &serde_yaml::Value,
+        source: &SourceMap,
+        findings: &mut Vec<LintFinding>,
+    ) {
+        let metadata = doc.get("metadata");
+        let line = source.find_top_level_key("metadata");
+
+        let has_text = |key: &str| {
+            metadata
+                .and_then(|m| m.get(key))
+                .and_then(|v| v.as_str())
+                .map(|s| !s.trim().is_empty())
+                .unwrap_or(false)
+        };
+        if !has_text("description") {
+            findings.push(finding(
+                "missing_description",
+                "Add a 'description' to the pipeline metadata".to_string(),
+                None,
+                line,
+            ));
+        }
+
+        let has_tags = metadata
+            .and_then(|m| m.get("tags"))
+            .and_then(|v| v.as_sequence())
+            .map(|tags| !tags.is_empty())
+            .unwrap_or(false);
+        if !has_tags {
+            findings.push(finding(
+                "missing_tags",
+                "Add 'tags' to the pipeline metadata to make it discoverable".to_string(),
+                None,
+                line,
+            ));
+        }
+    }
+
+    fn check_steps(
+        &self,
+        steps: &[serde_yaml::Value],
+        source: &SourceMap,
+        findings: &mut Vec<LintFinding>,
+    ) {
+        let step_lines = source.step_ranges();
+        let mut seen_ids: HashSet<String> = HashSet::new();
+
+        for (index, step) in steps.iter().enumerate() {
+            let range = step_lines.get(index).copied();
+            let start_line = range.map(|(start, _)| start);
+            let name = step.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            let explicit_id = step.get("id").and_then(|v| v.as_str());
+            let step_id = explicit_id.unwrap_or(name).to_string();
+
+            if step.get("timeout_seconds").is_none() {
+                findings.push(finding(
+                    "missing_timeout",
+                    format!("Step '{step_id}' has no timeout_seconds"),
+                    Some(&step_id),
+                    start_line,
+                ));
+            }
+
+            if is_writer(name) && step.get("retry_attempts").is_none() {
+                findings.push(finding(
+                    "writer_without_retry",
+                    format!("Writer step '{step_id}' has no retry_attempts"),
+                    Some(&step_id),
+                    start_line,
+                ));
+            }
+
+            if !seen_ids.insert(step_id.clone()) {
+                let line = source
+                    .find_key_in(range, "id")
+                    .or_else(|| source.find_key_in(range, "name"))
+                    .or(start_line);
+                findings.push(finding(
+                    "duplicate_step_id",
+                    format!("Step id '{step_id}' is used by more than one step"),
+                    Some(&step_id),
+                    line,
+                ));
+            }
+
+            if let Some(config) = step.get("config") {
+                let mut values = Vec::new();
+                collect_string_values(config, None, &mut values);
+
+                for (key, value) in values {
+                    let line = key
+                        .as_deref()
+                        .and_then(|k| source.find_key_in(range, k))
+                        .or(start_line);
+
+                    if looks_like_absolute_path(&value) {
+                        findings.push(finding(
+                            "absolute_path",
+                            format!(
+                                "Step '{step_id}' uses absolute path '{value}'; prefer a relative path or ${{VAR}}"
+                            ),
+                            Some(&step_id),
+                            line,
+                        ));
+                    }
+
+                    if let Some(key) = key.as_deref() {
+                        if is_secret_key(key) && !value.is_empty() && !value.contains("${") {
+                            findings.push(finding(
+                                "hardcoded_secret",
+                                format!(
+                                    "Step '{step_id}' sets '{key}' to a literal value; use an environment reference"
+                                ),
+                                Some(&step_id),
+                                line,
+                            ));
+                        }
+                    }
+                }
+            }
+
+            if let Some(id) = explicit_id {
+                let last = index + 1 == steps.len();
+                if !last && !referenced_later(id, &steps[index + 1..]) {
+                    findings.push(finding(
+                        "unused_step_output",
+                        format!("Output of step '{id}' is never referenced by a later step"),
+                        Some(id),
+                        source.find_key_in(range, "id").or(start_line),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Build a finding with the rule's default severity
+fn finding(rule: &str, message: String, step_id: Option<&str>, line: Option<usize>) -> LintFinding {
+    let severity = LINT_RULES
+        .iter()
+        .find(|(name, _, _)| *name == rule)
+        .map(|(_, severity, _)| *severity)
+        .unwrap_or(LintSeverity::Warning);
+
+    LintFinding {
+        rule: rule.to_string(),
+        severity,
+        message,
+        step_id: step_id.map(|s| s.to_string()),
+        line,
+    }
+}
+
+fn is_writer(oxi_name: &str) -> bool {
+    oxi_name.starts_with("write_") && oxi_name != "write_stdout"
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let secret_regex =
+        Regex::new(r"(?i)(password|passwd|secret|token|api_?key|private_?key|access_?key)")
+            .unwrap();
+    secret_regex.is_match(key)
+}
+
+fn looks_like_absolute_path(value: &str) -> bool {
+    let windows_drive = Regex::new(r"^[A-Za-z]:[\\/]").unwrap();
+    (value.starts_with('/') && !value.starts_with("//"))
+        || value.starts_with("~/")
+        || windows_drive.is_match(value)
+}
+
+/// Collect every string value in a config tree along with its nearest key
+fn collect_string_values(
+    value: &serde_yaml::Value,
+    key: Option<&str>,
+    out: &mut Vec<(Option<String>, String)>,
+) {
+    match value {
+        serde_yaml::Value::String(s) => out.push((key.map(|k| k.to_string()), s.clone())),
+        serde_yaml::Value::Mapping(map) => {
+            for (k, v) in map {
+                collect_string_values(v, k.as_str().or(key), out);
+            }
+        }
+        serde_yaml::Value::Sequence(seq) => {
+            for item in seq {
+                collect_string_values(item, key, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check whether any of the given steps references `${id}` or `${id.path}`
+fn referenced_later(id: &str, later_steps: &[serde_yaml::Value]) -> bool {
+    let reference = Regex::new(&format!(r"\$\{{{}(\.[^}}]*)?\}}", regex::escape(id))).unwrap();
+    later_steps.iter().any(|step| {
+        let mut values = Vec::new();
+        collect_string_values(step, None, &mut values);
+        values.iter().any(|(_, v)| reference.is_match(v))
+    })
+}
+
+/// Line-level view of the pipeline file used to locate findings and ignore comments
+struct SourceMap<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> SourceMap<'a> {
+    fn new(content: &'a str) -> Self {
+        Self {
+            lines: content.lines().collect(),
+        }
+    }
+
+    /// 1-based line of a top-level mapping key
+    fn find_top_level_key(&self, key: &str) -> Option<usize> {
+        let prefix = format!("{key}:");
+        self.lines
+            .iter()
+            .position(|line| line.starts_with(&prefix))
+            .map(|i| i + 1)
+    }
+
+    /// 1-based (start, end) line ranges of each item in the `pipeline` sequence
+    fn step_ranges(&self) -> Vec<(usize, usize)> {
+        let Some(pipeline_line) = self.find_top_level_key("pipeline") else {
+            return Vec::new();
+        };
+
+        let mut starts = Vec::new();
+        let mut item_indent = None;
+        let mut end = self.lines.len();
+
+        for (i, line) in self.lines.iter().enumerate().skip(pipeline_line) {
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let indent = line.len() - trimmed.len();
+            if indent == 0 && !trimmed.starts_with('-') {
+                end = i;
+                break;
+            }
+            if trimmed.starts_with("- ") || trimmed == "-" {
+                match item_indent {
+                    None => {
+                        item_indent = Some(indent);
+                        starts.push(i + 1);
+                    }
+                    Some(expected) if expected == indent => starts.push(i + 1),
+                    _ => {}
+                }
+            }
+        }
+
+        starts
+            .iter()
+            .enumerate()
+            .map(|(n, start)| {
+                let next = starts.get(n + 1).map(|s| s - 1).unwrap_or(end);
+                (*start, next)
+            })
+            .collect()
+    }
+
+    /// 1-based line of `key:` within a step's line range
+    fn find_key_in(&self, range: Option<(usize, usize)>, key: &str) -> Option<usize> {
+        let (start, end) = range?;
+        let pattern = Regex::new(&format!(r"^\s*(-\s+)?{}\s*:", regex::escape(key))).unwrap();
+        (start..=end.min(self.lines.len()))
+            .find(|&line_no| pattern.is_match(self.lines[line_no - 1]))
+    }
+
+    /// Whether `# oxide-lint: ignore rule` appears on the line or on a comment line just above it
+    fn is_ignored(&self, line: Option<usize>, rule: &str) -> bool {
+        let Some(line) = line else {
+            return false;
+        };
+
+        let same_line = self.lines.get(line - 1).copied();
+        let previous = line
+            .checked_sub(2)
+            .and_then(|i| self.lines.get(i))
+            .copied()
+            .filter(|l| l.trim_start().starts_with('#'));
+
+        [same_line, previous]
+            .into_iter()
+            .flatten()
+            .any(|text| ignore_comment_rules(text).iter().any(|r| r == rule))
+    }
+}
+
+/// Rules listed in an `# oxide-lint: ignore a, b` comment
+fn ignore_comment_rules(line: &str) -> Vec<String> {
+    let ignore_regex = Regex::new(r"#\s*oxide-lint:\s*ignore\s+([A-Za-z0-9_,\s]+)").unwrap();
+    ignore_regex
+        .captures(line)
+        .map(|caps| {
+            caps[1]
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Format lint reports as a human-readable table
+pub fn format_lint_reports(reports: &[LintReport]) -> String {
+    let mut output = String::new();
+
+    for report in reports {
+        output.push_str(&format!(
+            "🔍 Linting pipeline: {}\n",
+            report.pipeline_path.display()
+        ));
+
+        if report.findings.is_empty() {
+            output.push_str("   ✅ No issues found\n\n");
+            continue;
+        }
+
+        output.push_str(&format!(
+            "   {:<9} {:<22} {:<6} {}\n",
+            "Severity", "Rule", "Line", "Message"
+        ));
+        output.push_str(&format!("   {:-<80}\n", ""));
+
+        let mut findings = report.findings.clone();
+        findings.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.line.cmp(&b.line)));
+
+        for finding in &findings {
+            let icon = match finding.severity {
+                LintSeverity::Error => "❌",
+                LintSeverity::Warning => "⚠️ ",
+                LintSeverity::Info => "💡",
+            };
+            let line = finding
+                .line
+                .map(|l| l.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            output.push_str(&format!(
+                "{} {:<9} {:<22} {:<6} {}\n",
+                icon,
+                finding.severity.to_string(),
+                finding.rule,
+                line,
+                finding.message
+            ));
+        }
+        output.push('\n');
+    }
+
+    let errors: usize = reports.iter().map(|r| r.count(LintSeverity::Error)).sum();
+    let warnings: usize = reports.iter().map(|r| r.count(LintSeverity::Warning)).sum();
+    let infos: usize = reports.iter().map(|r| r.count(LintSeverity::Info)).sum();
+    output.push_str(&format!(
+        "📊 Summary: {} pipeline(s), {errors} error(s), {warnings} warning(s), {infos} info\n",
+        reports.len()
+    ));
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIPELINE: &str = r#"
+pipeline:
+  - name: read_file
+    id: reader
+    timeout_seconds: 30
+    config:
+      path: "/var/data/input.json"
+  - name: parse_json
+    id: parser
+    timeout_seconds: 30
+  - name: write_file
+    id: parser
+    config:
+      path: "output/result.json"
+      api_token: "abc123"
+
+metadata:
+  name: "Lint Test"
+"#;
+
+    fn lint(content: &str, config: LintConfig) -> LintReport {
+        PipelineLinter::new(config)
+            .lint_str(content, Path::new("lint_test.yaml"))
+            .unwrap()
+    }
+
+    fn rules(report: &LintReport) -> Vec<&str> {
+        report.findings.iter().map(|f| f.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_missing_timeout_and_writer_retry() {
+        let report = lint(PIPELINE, LintConfig::default());
+
+        let timeouts: Vec<_> = report
+            .findings
+            .iter()
+            .filter(|f| f.rule == "missing_timeout")
+            .collect();
+        assert_eq!(timeouts.len(), 1);
+        assert_eq!(timeouts[0].line, Some(11));
+        assert!(rules(&report).contains(&"writer_without_retry"));
+    }
+
+    #[test]
+    fn test_metadata_rules() {
+        let report = lint(PIPELINE, LintConfig::default());
+        assert!(rules(&report).contains(&"missing_description"));
+        assert!(rules(&report).contains(&"missing_tags"));
+
+        let documented = PIPELINE.replace(
+            "  name: \"Lint Test\"",
+            "  name: \"Lint Test\"\n  description: \"Reads\"\n  tags: [etl]",
+        );
+        let report = lint(&documented, LintConfig::default());
+        assert!(!rules(&report).contains(&"missing_description"));
+        assert!(!rules(&report).contains(&"missing_tags"));
+    }
+
+    #[test]
+    fn test_absolute_path_and_secret() {
+        let report = lint(PIPELINE, LintConfig::default());
+
+        let path = report
+            .findings
+            .iter()
+            .find(|f| f.rule == "absolute_path")
+            .unwrap();
+        assert_eq!(path.line, Some(7));
+        assert_eq!(path.step_id.as_deref(), Some("reader"));
+
+        let secret = report
+            .findings
+            .iter()
+            .find(|f| f.rule == "hardcoded_secret")
+            .unwrap();
+        assert_eq!(secret.severity, LintSeverity::Error);
+        assert_eq!(secret.line, Some(15));
+        assert!(report.has_errors());
+
+        let referenced = PIPELINE.replace("\"abc123\"", "\"${API_TOKEN}\"");
+        let report = lint(&referenced, LintConfig::default());
+        assert!(!rules(&report).contains(&"hardcoded_secret"));
+    }
+
+    #[test]
+    fn test_duplicate_step_id() {
+        let report = lint(PIPELINE, LintConfig::default());
+        let duplicate = report
+            .findings
+            .iter()
+            .find(|f| f.rule == "duplicate_step_id")
+            .unwrap();
+        assert_eq!(duplicate.step_id.as_deref(), Some("parser"));
+        assert_eq!(duplicate.line, Some(12));
+    }
+
+    #[test]
+    fn test_unused_step_output() {
+        let report = lint(PIPELINE, LintConfig::default());
+        let unused: Vec<_> = report
+            .findings
+            .iter()
+            .filter(|f| f.rule == "unused_step_output")
+            .filter_map(|f| f.step_id.as_deref())
+            .collect();
+        assert_eq!(unused, vec!["reader", "parser"]);
+
+        let referenced = PIPELINE.replace("output/result.json", "output/${reader.path}.json");
+        let report = lint(&referenced, LintConfig::default());
+        assert!(!report
+            .findings
+            .iter()
+            .any(|f| f.rule == "unused_step_output" && f.step_id.as_deref() == Some("reader")));
+    }
+
+    #[test]
+    fn test_ignore_comments() {
+        let ignored = PIPELINE
+            .replace(
+                "      api_token: \"abc123\"",
+                "      api_token: \"abc123\" # oxide-lint: ignore hardcoded_secret",
+            )
+            .replace(
+                "  - name: write_file",
+                "  # oxide-lint: ignore missing_timeout, writer_without_retry\n  - name: write_file",
+            );
+        let report = lint(&ignored, LintConfig::default());
+
+        assert!(!rules(&report).contains(&"hardcoded_secret"));
+        assert!(!rules(&report).contains(&"missing_timeout"));
+        assert!(!rules(&report).contains(&"writer_without_retry"));
+        // Rules not named in a comment are still reported on the same step
+        assert!(rules(&report).contains(&"duplicate_step_id"));
+    }
+
+    #[test]
+    fn test_config_disables_and_overrides_rules() {
+        let config = LintConfig {
+            disabled_rules: vec![
+                "hardcoded_secret".to_string(),
+                "duplicate_step_id".to_string(),
+            ],
+            severity: HashMap::from([("missing_timeout".to_string(), LintSeverity::Error)]),
+        };
+        let report = lint(PIPELINE, config);
+
+        assert!(!rules(&report).contains(&"hardcoded_secret"));
+        assert!(!rules(&report).contains(&"duplicate_step_id"));
+        let timeout = report
+            .findings
+            .iter()
+            .find(|f| f.rule == "missing_timeout")
+            .unwrap();
+        assert_eq!(timeout.severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn test_lint_config_from_yaml() {
+        let config: LintConfig = serde_yaml::from_str(
+            r#"
+            disabled_rules: [missing_tags]
+            severity:
+              absolute_path: error
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.disabled_rules, vec!["missing_tags"]);
+        assert_eq!(config.severity["absolute_path"], LintSeverity::Error);
+    }
+}
@@ -1,8 +1,21 @@
+use crate::config::OxiConfigSchema;
+use crate::config_resolver::ConfigResolver;
+use crate::oxis::plugin::describe_plugin;
+use crate::oxis::registry::OxiRegistry;
+use crate::pipeline::{check_schema_compatibility, Pipeline, SchemaTrace, StepSchema};
+use crate::pipeline_bundle::{read_bundle, write_bundle, BundleManifest};
+use crate::pipeline_lint::{LintReport, PipelineLinter};
 use crate::project::ProjectConfig;
+use crate::state::manager::{duration_percentile, StateManager};
+use crate::state::types::{PipelineState, StepStatus};
+use crate::types::{OxiConfig, OxiSchema, SchemaStrategy};
 use anyhow::{anyhow, Context, Result};
+use petgraph::graph::{DiGraph, NodeIndex};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Metadata extracted from pipeline YAML files
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,11 +29,117 @@ pub struct PipelineMetadata {
     pub file_path: PathBuf,
     pub step_count: usize,
     pub step_names: Vec<String>,
+    /// Pipelines that must run before this one (`metadata.depends_on`)
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Name used by run/info/test lookups, e.g. `ingest/users_sync`
+    #[serde(default)]
+    pub qualified_name: String,
+    /// Subfolder the pipeline is namespaced under, e.g. `ingest`
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Median duration of past completed runs, when state history is loaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_duration: Option<Duration>,
+}
+
+/// Templates compiled into the binary: (name, description, content)
+const BUILTIN_TEMPLATES: &[(&str, &str, &str)] = &[
+    (
+        "basic",
+        "Simple read → transform → write",
+        include_str!("templates/basic.yaml"),
+    ),
+    (
+        "etl",
+        "Extract, Transform, Load pattern",
+        include_str!("templates/etl.yaml"),
+    ),
+    (
+        "validation",
+        "Data validation and quality checking",
+        include_str!("templates/validation.yaml"),
+    ),
+    (
+        "batch",
+        "Batch processing with error handling",
+        include_str!("templates/batch.yaml"),
+    ),
+    (
+        "api",
+        "API data processing",
+        include_str!("templates/api.yaml"),
+    ),
+    (
+        "streaming",
+        "Streaming data processing",
+        include_str!("templates/streaming.yaml"),
+    ),
+];
+
+/// Where a pipeline template was loaded from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum TemplateSource {
+    /// A `.yaml` file in the project's template directory
+    Project(PathBuf),
+    /// Compiled into the binary
+    Builtin,
+}
+
+/// A pipeline template and the `{{variables}}` it expects
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineTemplate {
+    pub name: String,
+    pub description: String,
+    pub source: TemplateSource,
+    pub content: String,
+}
+
+impl PipelineTemplate {
+    /// Names of all `{{var_name}}` placeholders, in order of first appearance
+    pub fn variables(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for captures in template_variable_regex().captures_iter(&self.content) {
+            let name = captures[1].to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Substitute variables, failing with the list of any left unresolved
+    pub fn render(&self, variables: &HashMap<String, String>) -> Result<String> {
+        let missing: Vec<String> = self
+            .variables()
+            .into_iter()
+            .filter(|name| !variables.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "Template '{}' has unresolved variables: {}. Supply them with --set var=value",
+                self.name,
+                missing.join(", ")
+            ));
+        }
+
+        let rendered = template_variable_regex()
+            .replace_all(&self.content, |caps: &regex::Captures| {
+                variables[&caps[1]].clone()
+            });
+        Ok(rendered.into_owned())
+    }
+}
+
+fn template_variable_regex() -> &'static regex::Regex {
+    static RE: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    RE.get_or_init(|| regex::Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap())
 }
 
 /// Manages pipeline discovery, listing, and metadata extraction
 pub struct PipelineManager {
     project_config: ProjectConfig,
+    template_dir: PathBuf,
 }
 
 impl PipelineManager {
@@ -29,36 +148,30 @@ impl PipelineManager {
         let project_config = ProjectConfig::load()
             .map_err(|e| anyhow!("Failed to load project configuration: {}", e))?;
 
-        Ok(Self { project_config })
+        Ok(Self {
+            project_config,
+            template_dir: PathBuf::from(".oxiflow/templates"),
+        })
     }
 
-    /// Discover all pipelines in the configured pipeline directory
+    /// Discover all pipelines in the configured pipeline directories
     pub fn discover_pipelines(&self) -> Result<Vec<PipelineMetadata>> {
-        let pipeline_dir = self.project_config.get_pipeline_directory();
-
-        if !pipeline_dir.exists() {
-            return Ok(Vec::new());
-        }
-
         let mut pipelines = Vec::new();
 
-        // Read all YAML files in the pipeline directory
-        for entry in fs::read_dir(&pipeline_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            // Only process .yaml and .yml files
-            if let Some(extension) = path.extension() {
-                if extension == "yaml" || extension == "yml" {
-                    if let Ok(metadata) = self.extract_metadata(&path) {
-                        pipelines.push(metadata);
-                    }
-                }
+        for file in self.project_config.pipeline_files()? {
+            if let Ok(mut metadata) = self.extract_metadata(&file.path) {
+                metadata.qualified_name = file.name;
+                metadata.namespace = file.namespace;
+                pipelines.push(metadata);
             }
         }
 
         // Sort pipelines by name for consistent output
-        pipelines.sort_by(|a, b| a.name.cmp(&b.name));
+        pipelines.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+        });
 
         Ok(pipelines)
     }
@@ -123,6 +236,17 @@ impl PipelineManager {
             .or_else(|| yaml_value.get("created").and_then(|v| v.as_str()))
             .map(|s| s.to_string());
 
+        let depends_on = metadata_section
+            .and_then(|m| m.get("depends_on"))
+            .and_then(|v| v.as_sequence())
+            .map(|seq| {
+                seq.iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
         // Count steps and extract step names from the pipeline
         let (step_count, step_names) = yaml_value
             .get("pipeline")
@@ -184,6 +308,10 @@ impl PipelineManager {
             file_path: file_path.to_path_buf(),
             step_count,
             step_names,
+            depends_on,
+            qualified_name: file_stem,
+            namespace: None,
+            estimated_duration: None,
         })
     }
 
@@ -223,8 +351,13 @@ impl PipelineManager {
         pipelines
             .iter()
             .filter(|pipeline| {
-                // Check if keyword matches name
-                if pipeline.name.to_lowercase().contains(&keyword_lower) {
+                // Check if keyword matches name or namespaced file name
+                if pipeline.name.to_lowercase().contains(&keyword_lower)
+                    || pipeline
+                        .qualified_name
+                        .to_lowercase()
+                        .contains(&keyword_lower)
+                {
                     return true;
                 }
 
@@ -261,24 +394,25 @@ impl PipelineManager {
         // Header
         output.push_str(&format!(
             "📂 Available pipelines in {} ({} total):\n\n",
-            self.project_config.get_pipeline_directory().display(),
+            self.project_config.pipeline_directories_display(),
             pipelines.len()
         ));
 
         // Table header
         output.push_str(
-            "┌─────────────────────┬──────────────────────────────┬─────────┬───────────┐\n",
+            "┌─────────────────────┬──────────────┬──────────────────────────────┬─────────┬───────────┐\n",
         );
         output.push_str(
-            "│ Name                │ Description                  │ Version │ Steps     │\n",
+            "│ Name                │ Namespace    │ Description                  │ Version │ Steps     │\n",
         );
         output.push_str(
-            "├─────────────────────┼──────────────────────────────┼─────────┼───────────┤\n",
+            "├─────────────────────┼──────────────┼──────────────────────────────┼─────────┼───────────┤\n",
         );
 
         // Table rows
         for pipeline in pipelines {
             let name = truncate_string(&pipeline.name, 19);
+            let namespace = truncate_string(pipeline.namespace.as_deref().unwrap_or("-"), 12);
             let description = pipeline
                 .description
                 .as_ref()
@@ -292,13 +426,13 @@ impl PipelineManager {
             let steps = format!("{} steps", pipeline.step_count);
 
             output.push_str(&format!(
-                "│ {name:<19} │ {description:<28} │ {version:<7} │ {steps:<9} │\n"
+                "│ {name:<19} │ {namespace:<12} │ {description:<28} │ {version:<7} │ {steps:<9} │\n"
             ));
         }
 
         // Table footer
         output.push_str(
-            "└─────────────────────┴──────────────────────────────┴─────────┴───────────┘\n\n",
+            "└─────────────────────┴──────────────┴──────────────────────────────┴─────────┴───────────┘\n\n",
         );
 
         // Help text
@@ -314,7 +448,7 @@ impl PipelineManager {
 
         output.push_str(&format!(
             "📂 Available pipelines in {} ({} total):\n\n",
-            self.project_config.get_pipeline_directory().display(),
+            self.project_config.pipeline_directories_display(),
             pipelines.len()
         ));
 
@@ -325,6 +459,13 @@ impl PipelineManager {
 
             output.push_str(&format!("📂 Pipeline: {}\n", pipeline.name));
 
+            if let Some(namespace) = &pipeline.namespace {
+                output.push_str(&format!(
+                    "   🗂️  Namespace: {namespace} (run as '{}')\n",
+                    pipeline.qualified_name
+                ));
+            }
+
             if let Some(description) = &pipeline.description {
                 output.push_str(&format!("   📝 Description: {description}\n"));
             }
@@ -369,18 +510,142 @@ impl PipelineManager {
         self.project_config.get_pipeline_directory()
     }
 
-    /// Get available templates
-    pub fn get_available_templates(&self) -> Vec<&'static str> {
-        vec!["basic", "etl", "validation", "batch", "api", "streaming"]
+    /// List all templates; project templates shadow built-ins of the same name
+    pub fn list_templates(&self) -> Result<Vec<PipelineTemplate>> {
+        let mut templates = self.discover_project_templates()?;
+
+        for (name, description, content) in BUILTIN_TEMPLATES {
+            if !templates.iter().any(|t| t.name == *name) {
+                templates.push(PipelineTemplate {
+                    name: name.to_string(),
+                    description: description.to_string(),
+                    source: TemplateSource::Builtin,
+                    content: content.to_string(),
+                });
+            }
+        }
+
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(templates)
+    }
+
+    /// Find a template by name, checking the project template directory first
+    pub fn get_template(&self, name: &str) -> Result<PipelineTemplate> {
+        let templates = self.list_templates()?;
+        let available: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        let available = available.join(", ");
+        templates
+            .into_iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| anyhow!("Unknown template: {} (available: {})", name, available))
+    }
+
+    /// Load `*.yaml` templates from the project's template directory
+    fn discover_project_templates(&self) -> Result<Vec<PipelineTemplate>> {
+        if !self.template_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut templates = Vec::new();
+        for entry in fs::read_dir(&self.template_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("yaml") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read template {}", path.display()))?;
+            // The first comment line doubles as the template's description
+            let description = content
+                .lines()
+                .find_map(|line| line.trim().strip_prefix('#'))
+                .map(|line| line.trim().to_string())
+                .unwrap_or_else(|| "Project template".to_string());
+
+            templates.push(PipelineTemplate {
+                name: name.to_string(),
+                description,
+                source: TemplateSource::Project(path.clone()),
+                content,
+            });
+        }
+
+        Ok(templates)
+    }
+
+    /// Values for the standard placeholders used by the built-in templates
+    fn default_template_variables(
+        &self,
+        name: &str,
+        template: &str,
+        description: Option<&str>,
+        author: Option<&str>,
+    ) -> HashMap<String, String> {
+        let description = description
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Pipeline created from {template} template"));
+        let author = author
+            .map(str::to_string)
+            .unwrap_or_else(|| self.project_config.project.name.clone());
+
+        HashMap::from([
+            ("pipeline_name".to_string(), format_display_name(name)),
+            ("pipeline_description".to_string(), description),
+            ("pipeline_author".to_string(), author),
+            ("input_file".to_string(), "data.json".to_string()),
+            ("output_file".to_string(), "output.csv".to_string()),
+            ("backup_file".to_string(), format!("{name}_backup.csv")),
+        ])
+    }
+
+    /// Template variables that neither the defaults nor `provided` cover
+    pub fn unresolved_template_variables(
+        &self,
+        template: &str,
+        provided: &HashMap<String, String>,
+    ) -> Result<Vec<String>> {
+        let template = self.get_template(template)?;
+        let defaults = self.default_template_variables("", &template.name, None, None);
+        Ok(template
+            .variables()
+            .into_iter()
+            .filter(|v| !provided.contains_key(v) && !defaults.contains_key(v))
+            .collect())
+    }
+
+    /// Prompt on stdin for each template variable not already in `variables`
+    pub fn prompt_template_variables(
+        &self,
+        template: &str,
+        variables: &mut HashMap<String, String>,
+    ) -> Result<()> {
+        use std::io::{self, Write};
+
+        for name in self.unresolved_template_variables(template, variables)? {
+            print!("Enter value for {{{{{name}}}}}: ");
+            io::stdout().flush()?;
+            let mut value = String::new();
+            io::stdin().read_line(&mut value)?;
+            variables.insert(name, value.trim().to_string());
+        }
+
+        Ok(())
     }
 
     /// Create a new pipeline from a template
+    ///
+    /// `variables` override the standard placeholders and fill any custom
+    /// `{{var_name}}` the template declares; unresolved variables are an error.
     pub fn create_pipeline(
         &self,
         name: &str,
         template: &str,
         description: Option<&str>,
         author: Option<&str>,
+        variables: &HashMap<String, String>,
     ) -> Result<PathBuf> {
         // Validate pipeline name (should be snake_case)
         if !is_valid_pipeline_name(name) {
@@ -390,23 +655,11 @@ impl PipelineManager {
             ));
         }
 
-        // Get template content
-        let template_content = self.get_template_content(template)?;
-
-        // Get default values from project config
-        let default_author = self.project_config.project.name.clone();
-        let default_description = format!("Pipeline created from {template} template");
-        let pipeline_description = description.unwrap_or(&default_description);
-        let pipeline_author = author.unwrap_or(&default_author);
+        let template = self.get_template(template)?;
 
-        // Replace template variables
-        let pipeline_content = template_content
-            .replace("{{pipeline_name}}", &format_display_name(name))
-            .replace("{{pipeline_description}}", pipeline_description)
-            .replace("{{pipeline_author}}", pipeline_author)
-            .replace("{{input_file}}", "data.json")
-            .replace("{{output_file}}", "output.csv")
-            .replace("{{backup_file}}", &format!("{name}_backup.csv"));
+        let mut values = self.default_template_variables(name, &template.name, description, author);
+        values.extend(variables.iter().map(|(k, v)| (k.clone(), v.clone())));
+        let pipeline_content = template.render(&values)?;
 
         // Create pipeline file path
         let pipeline_dir = self.project_config.get_pipeline_directory();
@@ -432,21 +685,6 @@ impl PipelineManager {
         Ok(pipeline_path)
     }
 
-    /// Get template content by name
-    fn get_template_content(&self, template: &str) -> Result<String> {
-        let template_content = match template {
-            "basic" => include_str!("templates/basic.yaml"),
-            "etl" => include_str!("templates/etl.yaml"),
-            "validation" => include_str!("templates/validation.yaml"),
-            "batch" => include_str!("templates/batch.yaml"),
-            "api" => include_str!("templates/api.yaml"),
-            "streaming" => include_str!("templates/streaming.yaml"),
-            _ => return Err(anyhow!("Unknown template: {}", template)),
-        };
-
-        Ok(template_content.to_string())
-    }
-
     /// Interactive pipeline creation with prompts
     pub fn create_pipeline_interactive(&self, name: &str) -> Result<PathBuf> {
         use std::io::{self, Write};
@@ -455,18 +693,14 @@ impl PipelineManager {
 
         // Template selection
         println!("🎯 Select template:");
-        let templates = self.get_available_templates();
+        let templates = self.list_templates()?;
         for (i, template) in templates.iter().enumerate() {
-            let description = match *template {
-                "basic" => "Simple read → transform → write",
-                "etl" => "Extract, Transform, Load pattern",
-                "validation" => "Data validation and quality checking",
-                "batch" => "Batch processing with error handling",
-                "api" => "API data processing",
-                "streaming" => "Streaming data processing",
-                _ => "Unknown template",
-            };
-            println!("  {}. {:<12} - {}", i + 1, template, description);
+            println!(
+                "  {}. {:<12} - {}",
+                i + 1,
+                template.name,
+                template.description
+            );
         }
 
         print!("\nEnter choice [1-{}] (default: 1): ", templates.len());
@@ -482,7 +716,10 @@ impl PipelineManager {
             choice.parse::<usize>().unwrap_or(1).saturating_sub(1)
         };
 
-        let selected_template = templates.get(template_index).unwrap_or(&"basic");
+        let selected_template = templates
+            .get(template_index)
+            .map(|t| t.name.as_str())
+            .unwrap_or("basic");
 
         // Get description
         print!("\nEnter description (optional): ");
@@ -509,6 +746,10 @@ impl PipelineManager {
             Some(author)
         };
 
+        // Prompt for any template-specific variables
+        let mut variables = HashMap::new();
+        self.prompt_template_variables(selected_template, &mut variables)?;
+
         println!("\n📋 Pipeline Details:");
         println!("  Name: {}", format_display_name(name));
         println!(
@@ -519,7 +760,8 @@ impl PipelineManager {
         println!("  Template: {selected_template}");
 
         // Create the pipeline
-        let pipeline_path = self.create_pipeline(name, selected_template, description, author)?;
+        let pipeline_path =
+            self.create_pipeline(name, selected_template, description, author, &variables)?;
 
         println!("\n✅ Created pipeline: {}", pipeline_path.display());
         println!("\n💡 Use 'oxide_flow pipeline test {name}' to validate");
@@ -540,18 +782,7 @@ impl PipelineManager {
         schema_only: bool,
     ) -> Result<ValidationResult> {
         // Find the pipeline
-        let pipelines = self.discover_pipelines()?;
-        let pipeline = pipelines
-            .iter()
-            .find(|p| {
-                p.name == pipeline_name
-                    || p.file_path
-                        .file_stem()
-                        .and_then(|stem| stem.to_str())
-                        .map(|stem| stem == pipeline_name)
-                        .unwrap_or(false)
-            })
-            .ok_or_else(|| anyhow!("Pipeline '{}' not found", pipeline_name))?;
+        let pipeline = self.find_pipeline_metadata(pipeline_name)?;
 
         self.validate_pipeline_file(&pipeline.file_path, dry_run, verbose, fix, schema_only)
     }
@@ -581,6 +812,7 @@ impl PipelineManager {
                 result.yaml_valid = false;
                 result.errors.push(ValidationError::YamlSyntax {
                     message: format!("YAML syntax error: {e}"),
+                    line: e.location().map(|location| location.line()),
                 });
                 return Ok(result); // Can't continue without valid YAML
             }
@@ -589,20 +821,26 @@ impl PipelineManager {
         // 2. Pipeline structure validation
         self.validate_pipeline_structure(&yaml_doc, &mut result)?;
 
+        // 3. Declared plugins answer the --describe handshake
+        let plugins = self.validate_plugins(&mut result);
+
+        // Steps are checked as they run, with the project and pipeline defaults merged in
+        let effective_doc = self.with_project_defaults(&yaml_doc);
+
+        // 4. Oxi schema validation, including the schema flow between steps
+        self.validate_oxi_schemas(&effective_doc, &plugins, &mut result)?;
+
         if schema_only {
             return Ok(result);
         }
 
-        // 3. Environment variable checking
-        self.validate_environment_variables(&yaml_doc, &mut result)?;
+        // 5. Environment variable checking
+        self.validate_environment_variables(&effective_doc, &mut result)?;
 
-        // 4. Step reference validation
-        self.validate_step_references(&yaml_doc, &mut result)?;
+        // 6. Step reference validation
+        self.validate_step_references(&effective_doc, &mut result)?;
 
-        // 5. Oxi schema validation
-        self.validate_oxi_schemas(&yaml_doc, &mut result)?;
-
-        // 6. Auto-fix capabilities
+        // 7. Auto-fix capabilities
         if fix && !result.errors.is_empty() {
             self.apply_auto_fixes(&yaml_doc, pipeline_path, &mut result, dry_run)?;
         }
@@ -610,6 +848,52 @@ impl PipelineManager {
         Ok(result)
     }
 
+    /// `yaml_doc` with each step's config merged over the project's and the pipeline's defaults
+    ///
+    /// The YAML counterpart of `Pipeline::apply_defaults`, for checks that
+    /// work on the document rather than the parsed pipeline.
+    fn with_project_defaults(&self, yaml_doc: &serde_yaml::Value) -> serde_yaml::Value {
+        let mut doc = yaml_doc.clone();
+        let pipeline_defaults: HashMap<String, serde_yaml::Value> = doc
+            .get("defaults")
+            .and_then(|defaults| serde_yaml::from_value(defaults.clone()).ok())
+            .unwrap_or_default();
+        let Some(steps) = doc.get_mut("pipeline").and_then(|p| p.as_sequence_mut()) else {
+            return doc;
+        };
+
+        for step in steps.iter_mut() {
+            let Some(name) = step.get("name").and_then(|name| name.as_str()) else {
+                continue;
+            };
+            let config = match step.get("config") {
+                Some(serde_yaml::Value::Mapping(config)) => config
+                    .iter()
+                    .filter_map(|(key, value)| Some((key.as_str()?.to_string(), value.clone())))
+                    .collect(),
+                Some(serde_yaml::Value::Null) | None => HashMap::new(),
+                // Left as written so validation reports it
+                Some(_) => continue,
+            };
+            let merged: BTreeMap<String, serde_yaml::Value> =
+                crate::pipeline::config_with_defaults(
+                    name,
+                    config,
+                    &self.project_config.defaults,
+                    &pipeline_defaults,
+                )
+                .into_iter()
+                .collect();
+            if let Some(step_map) = step.as_mapping_mut() {
+                step_map.insert(
+                    "config".into(),
+                    serde_yaml::to_value(merged).unwrap_or_default(),
+                );
+            }
+        }
+        doc
+    }
+
     /// Validate pipeline structure
     fn validate_pipeline_structure(
         &self,
@@ -642,6 +926,15 @@ impl PipelineManager {
                 }
             }
 
+            // A state block must resolve, together with the project's, to a usable backend
+            if let Some(state) = mapping.get(serde_yaml::Value::String("state".to_string())) {
+                if let Err(e) = self.project_config.state_config_for(Some(state)) {
+                    result.errors.push(ValidationError::Structure {
+                        message: format!("{e:#}"),
+                    });
+                }
+            }
+
             // Validate metadata (optional but recommended)
             if let Some(metadata) = mapping.get(serde_yaml::Value::String("metadata".to_string())) {
                 self.validate_metadata(metadata, result)?;
@@ -789,14 +1082,119 @@ impl PipelineManager {
         Ok(())
     }
 
+    /// Describe every plugin declared in oxiflow.yaml, keyed by name
+    fn validate_plugins(&self, result: &mut ValidationResult) -> HashMap<String, OxiConfigSchema> {
+        let mut plugins = HashMap::new();
+        for plugin in &self.project_config.plugins {
+            match describe_plugin(plugin) {
+                Ok(description) => {
+                    plugins.insert(plugin.name.clone(), description.config_schema());
+                }
+                Err(e) => result.errors.push(ValidationError::Plugin {
+                    message: format!("{e:#}"),
+                }),
+            }
+        }
+        plugins
+    }
+
     /// Validate Oxi schemas
     fn validate_oxi_schemas(
         &self,
-        _yaml_doc: &serde_yaml::Value,
+        yaml_doc: &serde_yaml::Value,
+        plugins: &HashMap<String, OxiConfigSchema>,
         result: &mut ValidationResult,
     ) -> Result<()> {
-        // TODO: Implement schema validation using existing schema module
-        result.schemas_valid = true;
+        let mut schemas_valid = true;
+
+        let steps = yaml_doc
+            .get("pipeline")
+            .and_then(|p| p.as_sequence())
+            .cloned()
+            .unwrap_or_default();
+
+        for (index, step) in steps.iter().enumerate() {
+            let Some(oxi_name) = step.get("name").and_then(|n| n.as_str()) else {
+                continue; // Reported by structure validation
+            };
+            let step_id = step
+                .get("id")
+                .and_then(|id| id.as_str())
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| format!("#{index}"));
+
+            let (config_schema, strict) = match crate::oxis::builtin(oxi_name) {
+                Some(oxi) => (oxi.describe().config_schema, oxi.strict_config_validation()),
+                None => match plugins.get(oxi_name) {
+                    Some(schema) => (schema.clone(), false),
+                    // A declared plugin that failed its handshake is already an error
+                    None if self
+                        .project_config
+                        .plugins
+                        .iter()
+                        .any(|p| p.name == oxi_name) =>
+                    {
+                        continue
+                    }
+                    None => {
+                        result
+                            .warnings
+                            .push(format!("Step '{step_id}' uses unknown Oxi '{oxi_name}'"));
+                        continue;
+                    }
+                },
+            };
+
+            // Check required config keys declared by the Oxi itself
+            let config = step.get("config").and_then(|c| c.as_mapping());
+            for required in &config_schema.required {
+                let present = config
+                    .map(|c| c.contains_key(serde_yaml::Value::String(required.clone())))
+                    .unwrap_or(false);
+                if !present {
+                    schemas_valid = false;
+                    result.errors.push(ValidationError::Schema {
+                        message: format!(
+                            "Step '{step_id}' ({oxi_name}) is missing required config '{required}'"
+                        ),
+                    });
+                }
+            }
+
+            // Strict Oxis also reject keys they do not declare
+            if strict {
+                let mut step_config = OxiConfig::default();
+                for (key, value) in config.into_iter().flatten() {
+                    if let Some(key) = key.as_str() {
+                        step_config.values.insert(key.to_string(), value.clone());
+                    }
+                }
+                for key in step_config.unknown_keys(&config_schema) {
+                    schemas_valid = false;
+                    result.errors.push(ValidationError::Schema {
+                        message: format!(
+                            "Step '{step_id}' ({oxi_name}) has unknown config key '{key}'"
+                        ),
+                    });
+                }
+            }
+        }
+
+        // Each step's required input against what the step before it outputs
+        if let Ok(pipeline) = serde_yaml::from_value::<Pipeline>(yaml_doc.clone()) {
+            let trace = infer_schema_chain(&pipeline, None)?;
+            for (step_id, issue) in check_schema_compatibility(&trace) {
+                let message = issue.describe(&step_id);
+                if issue.is_error() {
+                    schemas_valid = false;
+                    result.errors.push(ValidationError::Schema { message });
+                } else {
+                    result.warnings.push(message);
+                }
+            }
+        }
+
+        result.schemas_valid = schemas_valid;
         Ok(())
     }
 
@@ -817,6 +1215,270 @@ impl PipelineManager {
         Ok(())
     }
 
+    // === PIPELINE LINTING ===
+
+    /// Lint one pipeline by name, or every discovered pipeline when `name` is None
+    pub fn lint_pipelines(&self, name: Option<&str>) -> Result<Vec<LintReport>> {
+        let linter = PipelineLinter::new(self.project_config.lint.clone());
+        let pipelines = self.discover_pipelines()?;
+
+        let selected: Vec<&PipelineMetadata> = match name {
+            Some(name) => {
+                let found = self.find_pipeline_metadata(name)?;
+                pipelines
+                    .iter()
+                    .filter(|p| p.file_path == found.file_path)
+                    .collect()
+            }
+            None => pipelines.iter().collect(),
+        };
+
+        selected
+            .into_iter()
+            .map(|p| linter.lint_file(&p.file_path))
+            .collect()
+    }
+
+    // === PIPELINE GRAPH ===
+
+    /// Find a pipeline by name or file stem and load its step definitions
+    ///
+    /// Step configs come back merged over the project and pipeline defaults, as they run.
+    pub fn load_pipeline(&self, name: &str) -> Result<Pipeline> {
+        let metadata = self.find_pipeline_metadata(name)?;
+        let mut pipeline = Pipeline::load_from_file(&metadata.file_path.to_string_lossy())?;
+        pipeline.apply_defaults(&self.project_config.defaults);
+        Ok(pipeline)
+    }
+
+    /// Find a pipeline by namespaced name, bare name or metadata name
+    ///
+    /// File names are tried first (see `ProjectConfig::resolve_pipeline`),
+    /// then the `metadata.name` shown in listings. Matching more than one
+    /// pipeline is an error that lists the candidates.
+    pub fn find_pipeline_metadata(&self, name: &str) -> Result<PipelineMetadata> {
+        let pipelines = self.discover_pipelines()?;
+
+        if let Some(file) = self.project_config.resolve_pipeline(name)? {
+            if let Some(found) = pipelines.iter().find(|p| p.file_path == file.path) {
+                return Ok(found.clone());
+            }
+        }
+
+        let matches: Vec<PipelineMetadata> =
+            pipelines.into_iter().filter(|p| p.name == name).collect();
+        match matches.len() {
+            0 => Err(anyhow!("Pipeline '{}' not found", name)),
+            1 => Ok(matches.into_iter().next().unwrap()),
+            _ => Err(anyhow!(
+                "Pipeline '{}' is ambiguous. Candidates: {}",
+                name,
+                matches
+                    .iter()
+                    .map(|p| p.qualified_name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    /// Median duration of the pipeline's completed runs in the state store
+    ///
+    /// `pipeline_name` is the state id (`Pipeline::name`). Returns `None` until
+    /// the pipeline has completed at least once.
+    pub async fn estimate_duration(
+        &self,
+        pipeline_name: &str,
+        state_manager: &StateManager,
+    ) -> Result<Option<Duration>> {
+        let durations = state_manager.completed_durations(pipeline_name).await?;
+        Ok(duration_percentile(&durations, 0.5))
+    }
+
+    /// 95th percentile duration of the pipeline's completed runs
+    pub async fn p95_duration(
+        &self,
+        pipeline_name: &str,
+        state_manager: &StateManager,
+    ) -> Result<Option<Duration>> {
+        let durations = state_manager.completed_durations(pipeline_name).await?;
+        Ok(duration_percentile(&durations, 0.95))
+    }
+
+    /// Path for a new pipeline file, refusing names that are invalid or taken
+    fn new_pipeline_path(&self, new_name: &str) -> Result<PathBuf> {
+        if !is_valid_pipeline_name(new_name) {
+            return Err(anyhow!(
+                "Invalid pipeline name '{}'. Use snake_case format (e.g., my_pipeline)",
+                new_name
+            ));
+        }
+
+        let target = self
+            .project_config
+            .get_pipeline_directory()
+            .join(format!("{new_name}.yaml"));
+        if target.exists() || self.find_pipeline_metadata(new_name).is_ok() {
+            return Err(anyhow!("Pipeline '{}' already exists", new_name));
+        }
+
+        Ok(target)
+    }
+
+    /// Delete a pipeline file, returning its metadata so callers can clean up state
+    pub fn delete_pipeline(&self, name: &str) -> Result<PipelineMetadata> {
+        let metadata = self.find_pipeline_metadata(name)?;
+        fs::remove_file(&metadata.file_path)
+            .with_context(|| format!("Failed to delete {}", metadata.file_path.display()))?;
+        Ok(metadata)
+    }
+
+    /// Rename a pipeline file and its `metadata.name`
+    ///
+    /// State is keyed by `metadata.name`, so callers that track state should
+    /// follow up with `StateManager::rename_pipeline(old.name, new.name)`.
+    pub fn rename_pipeline(
+        &self,
+        name: &str,
+        new_name: &str,
+    ) -> Result<(PipelineMetadata, PipelineMetadata)> {
+        let old = self.find_pipeline_metadata(name)?;
+        let target = self.new_pipeline_path(new_name)?;
+
+        let content = fs::read_to_string(&old.file_path)?;
+        let content = set_metadata_field(&content, "name", Some(&format_display_name(new_name)));
+        fs::write(&target, content)?;
+        fs::remove_file(&old.file_path)
+            .with_context(|| format!("Failed to remove {}", old.file_path.display()))?;
+
+        let new = self.extract_metadata(&target)?;
+        Ok((old, new))
+    }
+
+    /// Duplicate a pipeline under a new name with a fresh `created` date and no version
+    pub fn copy_pipeline(&self, name: &str, new_name: &str) -> Result<PathBuf> {
+        let source = self.find_pipeline_metadata(name)?;
+        let target = self.new_pipeline_path(new_name)?;
+
+        let content = fs::read_to_string(&source.file_path)?;
+        let content = set_metadata_field(&content, "name", Some(&format_display_name(new_name)));
+        let content = set_metadata_field(
+            &content,
+            "created",
+            Some(&chrono::Utc::now().format("%Y-%m-%d").to_string()),
+        );
+        let content = set_metadata_field(&content, "version", None);
+        fs::write(&target, content)?;
+
+        Ok(target)
+    }
+
+    /// Package a pipeline's YAML and a manifest of its requirements into `output`
+    pub fn export_bundle(&self, name: &str, output: &Path) -> Result<BundleManifest> {
+        let metadata = self.find_pipeline_metadata(name)?;
+        let content = fs::read_to_string(&metadata.file_path)?;
+        let file = metadata
+            .file_path
+            .file_name()
+            .and_then(|f| f.to_str())
+            .ok_or_else(|| anyhow!("Invalid pipeline path {}", metadata.file_path.display()))?;
+
+        let manifest = BundleManifest::for_pipeline(file, &content)?;
+        write_bundle(output, &manifest, &content)?;
+        Ok(manifest)
+    }
+
+    /// Write a bundled pipeline into the pipeline directory
+    ///
+    /// Fails without writing anything when a required environment variable
+    /// is unset or an Oxi is missing from `registry`, and when the pipeline
+    /// file already exists unless `force` is set.
+    pub fn import_bundle(
+        &self,
+        bundle: &Path,
+        registry: &OxiRegistry,
+        force: bool,
+    ) -> Result<(BundleManifest, PathBuf)> {
+        let (manifest, content) = read_bundle(bundle)?;
+
+        let unmet = manifest.unmet_requirements(registry);
+        if !unmet.is_empty() {
+            return Err(anyhow!(
+                "Bundle requirements not met:\n  - {}",
+                unmet.join("\n  - ")
+            ));
+        }
+
+        let pipeline_dir = self.project_config.get_pipeline_directory();
+        let target = pipeline_dir.join(&manifest.file);
+        if target.exists() && !force {
+            return Err(anyhow!(
+                "Pipeline file {} already exists. Use --force to overwrite",
+                target.display()
+            ));
+        }
+        fs::create_dir_all(&pipeline_dir)?;
+        fs::write(&target, content)?;
+
+        Ok((manifest, target))
+    }
+
+    /// Build a graph of pipelines linked by their `metadata.depends_on` entries
+    ///
+    /// Edges point from a dependency to the pipeline that depends on it.
+    /// Dependencies may name a pipeline by display name or file stem. Unknown
+    /// dependencies and cycles are errors.
+    pub fn build_dependency_graph(&self) -> Result<DiGraph<PipelineMetadata, ()>> {
+        let pipelines = self.discover_pipelines()?;
+        let mut graph = DiGraph::new();
+        let nodes: Vec<NodeIndex> = pipelines
+            .iter()
+            .map(|metadata| graph.add_node(metadata.clone()))
+            .collect();
+
+        let find = |reference: &str| {
+            pipelines.iter().position(|p| {
+                p.name == reference
+                    || p.qualified_name == reference
+                    || p.file_path.file_stem().and_then(|s| s.to_str()) == Some(reference)
+            })
+        };
+
+        for (index, metadata) in pipelines.iter().enumerate() {
+            for dependency in &metadata.depends_on {
+                let target = find(dependency).ok_or_else(|| {
+                    anyhow!(
+                        "Pipeline '{}' depends on unknown pipeline '{}'",
+                        metadata.name,
+                        dependency
+                    )
+                })?;
+                graph.update_edge(nodes[target], nodes[index], ());
+            }
+        }
+
+        if let Some(cycle) = find_dependency_cycle(&graph) {
+            return Err(anyhow!(
+                "Pipeline dependency cycle detected: {}",
+                cycle.join(" -> ")
+            ));
+        }
+
+        Ok(graph)
+    }
+
+    /// Pipelines ordered so that every pipeline follows its dependencies
+    pub fn topological_sort(&self) -> Result<Vec<PipelineMetadata>> {
+        let graph = self.build_dependency_graph()?;
+        let order = petgraph::algo::toposort(&graph, None).map_err(|cycle| {
+            anyhow!(
+                "Pipeline dependency cycle at '{}'",
+                graph[cycle.node_id()].name
+            )
+        })?;
+        Ok(order.into_iter().map(|node| graph[node].clone()).collect())
+    }
+
     /// Format validation results for display
     pub fn format_validation_result(&self, result: &ValidationResult, verbose: bool) -> String {
         let mut output = String::new();
@@ -891,7 +1553,11 @@ impl PipelineManager {
 
         // Warnings
         if !result.warnings.is_empty() {
-            output.push_str("\n⚠️  Warnings:\n");
+            output.push_str(if result.strict {
+                "\n⚠️  Warnings (strict: counted as issues):\n"
+            } else {
+                "\n⚠️  Warnings:\n"
+            });
             for warning in &result.warnings {
                 output.push_str(&format!("   • {warning}\n"));
             }
@@ -937,8 +1603,14 @@ impl PipelineManager {
         } else {
             output.push_str(&format!(
                 "\n❌ Pipeline has {} issues that need to be fixed",
-                result.errors.len()
+                result.failure_count()
             ));
+            if result.strict && !result.warnings.is_empty() {
+                output.push_str(&format!(
+                    " (strict mode escalated {} warning(s) to errors)",
+                    result.warnings.len()
+                ));
+            }
         }
 
         output
@@ -946,7 +1618,7 @@ impl PipelineManager {
 }
 
 /// Validation result for a pipeline
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct ValidationResult {
     pub pipeline_path: PathBuf,
     pub yaml_valid: bool,
@@ -964,6 +1636,8 @@ pub struct ValidationResult {
     pub warnings: Vec<String>,
     pub suggestions: Vec<String>,
     pub fixes_applied: Vec<String>,
+    /// Count warnings as failures, as `validate --strict` does
+    pub strict: bool,
 }
 
 impl ValidationResult {
@@ -985,47 +1659,697 @@ impl ValidationResult {
             warnings: Vec::new(),
             suggestions: Vec::new(),
             fixes_applied: Vec::new(),
+            strict: false,
         }
     }
 
     pub fn is_valid(&self) -> bool {
-        self.errors.is_empty() && self.yaml_valid
+        self.errors.is_empty() && self.yaml_valid && (!self.strict || self.warnings.is_empty())
     }
-}
 
-/// Validation errors for pipeline testing
-#[derive(Debug)]
-pub enum ValidationError {
-    YamlSyntax { message: String },
-    Structure { message: String },
-    Schema { message: String },
-    EnvironmentVariable { message: String },
-    StepReference { message: String },
-}
+    /// Errors, plus warnings in strict mode
+    pub fn failure_count(&self) -> usize {
+        self.errors.len() + if self.strict { self.warnings.len() } else { 0 }
+    }
 
-impl std::fmt::Display for ValidationError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ValidationError::YamlSyntax { message } => write!(f, "YAML Syntax: {message}"),
-            ValidationError::Structure { message } => write!(f, "Structure: {message}"),
-            ValidationError::Schema { message } => write!(f, "Schema: {message}"),
-            ValidationError::EnvironmentVariable { message } => {
-                write!(f, "Environment Variable: {message}")
+    /// The result as JSON, with `valid` set from [`Self::is_valid`]
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        value["valid"] = self.is_valid().into();
+        value
+    }
+
+    /// Render the checks as a JUnit XML `<testsuite>`
+    ///
+    /// Each error category of [`ValidationError::RULES`] and the warnings are
+    /// one `<testcase>`, failing with every message it found. Warnings only
+    /// fail in strict mode. When the YAML does not parse, the other checks
+    /// are reported as skipped.
+    pub fn to_junit_xml(&self) -> String {
+        let suite = self
+            .pipeline_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("pipeline");
+        let mut cases = Vec::new();
+        let mut failures = 0;
+        let mut skipped = 0;
+
+        let checks = ValidationError::RULES
+            .iter()
+            .map(|(id, description)| {
+                let messages: Vec<&str> = self
+                    .errors
+                    .iter()
+                    .filter(|e| e.rule_id() == *id)
+                    .map(|e| e.message())
+                    .collect();
+                (*id, *description, messages, true)
+            })
+            .chain(std::iter::once((
+                SARIF_WARNING_RULE,
+                "Pipeline warning",
+                self.warnings.iter().map(String::as_str).collect(),
+                self.strict,
+            )));
+
+        for (id, description, messages, fails) in checks {
+            let mut case = format!(
+                "  <testcase classname=\"{}\" name=\"{}\"",
+                xml_escape(suite),
+                id
+            );
+            if !self.yaml_valid && id != "yaml-syntax" {
+                skipped += 1;
+                case.push_str(">\n    <skipped message=\"YAML does not parse\"/>\n  </testcase>");
+            } else if messages.is_empty() {
+                case.push_str("/>");
+            } else if fails {
+                failures += 1;
+                case.push_str(&format!(
+                    ">\n    <failure type=\"{id}\" message=\"{}\">{}</failure>\n  </testcase>",
+                    xml_escape(description),
+                    xml_escape(&messages.join("\n"))
+                ));
+            } else {
+                case.push_str(&format!(
+                    ">\n    <system-out>{}</system-out>\n  </testcase>",
+                    xml_escape(&messages.join("\n"))
+                ));
             }
-            ValidationError::StepReference { message } => write!(f, "Step Reference: {message}"),
+            cases.push(case);
         }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuite name=\"{}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" skipped=\"{skipped}\">\n{}\n</testsuite>\n",
+            xml_escape(suite),
+            cases.len(),
+            cases.join("\n")
+        )
     }
-}
 
-/// Truncate a string to a maximum length, adding "..." if truncated
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    /// Render errors and warnings as a SARIF 2.1.0 log for code scanning
+    pub fn to_sarif(&self) -> serde_json::Value {
+        let uri = self.pipeline_path.to_string_lossy().replace('\\', "/");
+        let sarif_result = |rule_id: &str, level: &str, message: &str, line: Option<usize>| {
+            let mut location = serde_json::json!({
+                "physicalLocation": {"artifactLocation": {"uri": uri}}
+            });
+            if let Some(line) = line {
+                location["physicalLocation"]["region"] = serde_json::json!({"startLine": line});
+            }
+            serde_json::json!({
+                "ruleId": rule_id,
+                "level": level,
+                "message": {"text": message},
+                "locations": [location],
+            })
+        };
+
+        let results: Vec<serde_json::Value> = self
+            .errors
+            .iter()
+            .map(|e| sarif_result(e.rule_id(), "error", e.message(), e.line()))
+            .chain(self.warnings.iter().map(|w| {
+                let level = if self.strict { "error" } else { "warning" };
+                sarif_result(SARIF_WARNING_RULE, level, w, None)
+            }))
+            .collect();
+
+        let rules: Vec<serde_json::Value> = ValidationError::RULES
+            .iter()
+            .chain(std::iter::once(&(SARIF_WARNING_RULE, "Pipeline warning")))
+            .map(|(id, description)| {
+                serde_json::json!({"id": id, "shortDescription": {"text": description}})
+            })
+            .collect();
+
+        serde_json::json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "oxide_flow",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }]
+        })
+    }
+}
+
+/// SARIF rule id for validation warnings, which carry no category
+const SARIF_WARNING_RULE: &str = "pipeline-warning";
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Output format for `validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Json,
+    Sarif,
+    /// JUnit XML, one test case per check
+    Junit,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "human" | "text" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            "sarif" => Ok(OutputFormat::Sarif),
+            "junit" => Ok(OutputFormat::Junit),
+            other => Err(anyhow!(
+                "Unsupported output format '{}'. Use 'human', 'json', 'sarif' or 'junit'",
+                other
+            )),
+        }
+    }
+}
+
+/// Validation errors for pipeline testing
+///
+/// Serialized with the error's rule id under `rule`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "rule", rename_all = "kebab-case")]
+pub enum ValidationError {
+    YamlSyntax {
+        message: String,
+        /// 1-based line of the syntax error, when the parser reports one
+        line: Option<usize>,
+    },
+    Structure {
+        message: String,
+    },
+    Schema {
+        message: String,
+    },
+    EnvironmentVariable {
+        message: String,
+    },
+    StepReference {
+        message: String,
+    },
+    Plugin {
+        message: String,
+    },
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::YamlSyntax { message, .. } => write!(f, "YAML Syntax: {message}"),
+            ValidationError::Structure { message } => write!(f, "Structure: {message}"),
+            ValidationError::Schema { message } => write!(f, "Schema: {message}"),
+            ValidationError::EnvironmentVariable { message } => {
+                write!(f, "Environment Variable: {message}")
+            }
+            ValidationError::StepReference { message } => write!(f, "Step Reference: {message}"),
+            ValidationError::Plugin { message } => write!(f, "Plugin: {message}"),
+        }
+    }
+}
+
+impl ValidationError {
+    /// SARIF rule ids and descriptions, one per variant
+    pub const RULES: [(&'static str, &'static str); 6] = [
+        ("yaml-syntax", "Pipeline YAML does not parse"),
+        ("structure", "Pipeline structure is invalid"),
+        ("schema", "Step configuration or schema flow is invalid"),
+        ("environment-variable", "Environment variable is not set"),
+        ("step-reference", "Step reference does not resolve"),
+        (
+            "plugin",
+            "Declared plugin is missing or does not answer --describe",
+        ),
+    ];
+
+    /// SARIF rule id for this error
+    pub fn rule_id(&self) -> &'static str {
+        let index = match self {
+            ValidationError::YamlSyntax { .. } => 0,
+            ValidationError::Structure { .. } => 1,
+            ValidationError::Schema { .. } => 2,
+            ValidationError::EnvironmentVariable { .. } => 3,
+            ValidationError::StepReference { .. } => 4,
+            ValidationError::Plugin { .. } => 5,
+        };
+        Self::RULES[index].0
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            ValidationError::YamlSyntax { message, .. }
+            | ValidationError::Structure { message }
+            | ValidationError::Schema { message }
+            | ValidationError::EnvironmentVariable { message }
+            | ValidationError::StepReference { message }
+            | ValidationError::Plugin { message } => message,
+        }
+    }
+
+    /// Line in the pipeline file the error points at, if known
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ValidationError::YamlSyntax { line, .. } => *line,
+            _ => None,
+        }
+    }
+}
+
+/// Find one dependency cycle, returned as pipeline names with the first repeated at the end
+fn find_dependency_cycle(graph: &DiGraph<PipelineMetadata, ()>) -> Option<Vec<String>> {
+    for component in petgraph::algo::tarjan_scc(graph) {
+        let start = component[0];
+        if component.len() == 1 && graph.find_edge(start, start).is_none() {
+            continue;
+        }
+
+        // Every node of a strongly connected component has a successor inside it,
+        // so walking those successors must eventually revisit a node.
+        let mut path = vec![start];
+        let mut current = start;
+        loop {
+            current = graph
+                .neighbors(current)
+                .find(|next| component.contains(next))?;
+            if let Some(position) = path.iter().position(|&node| node == current) {
+                let mut names: Vec<String> = path[position..]
+                    .iter()
+                    .map(|&node| graph[node].name.clone())
+                    .collect();
+                names.push(graph[current].name.clone());
+                return Some(names);
+            }
+            path.push(current);
+        }
+    }
+    None
+}
+
+/// Render a dependency graph as an ASCII tree rooted at pipelines with no dependencies
+///
+/// A pipeline reached along several paths is expanded once; later
+/// occurrences are marked `(see above)`.
+pub fn dependency_tree(graph: &DiGraph<PipelineMetadata, ()>) -> String {
+    fn walk(
+        graph: &DiGraph<PipelineMetadata, ()>,
+        node: NodeIndex,
+        prefix: &str,
+        seen: &mut HashSet<NodeIndex>,
+        out: &mut String,
+    ) {
+        let mut children: Vec<NodeIndex> = graph.neighbors(node).collect();
+        children.sort_by(|a, b| graph[*a].name.cmp(&graph[*b].name));
+        for (i, child) in children.iter().enumerate() {
+            let last = i + 1 == children.len();
+            let first_visit = seen.insert(*child);
+            out.push_str(&format!(
+                "{prefix}{}{}{}\n",
+                if last { "└── " } else { "├── " },
+                graph[*child].name,
+                if first_visit { "" } else { " (see above)" }
+            ));
+            if first_visit {
+                let prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+                walk(graph, *child, &prefix, seen, out);
+            }
+        }
+    }
+
+    let mut roots: Vec<NodeIndex> = graph
+        .node_indices()
+        .filter(|&node| {
+            graph
+                .neighbors_directed(node, petgraph::Direction::Incoming)
+                .next()
+                .is_none()
+        })
+        .collect();
+    roots.sort_by(|a, b| graph[*a].name.cmp(&graph[*b].name));
+
+    let mut seen: HashSet<NodeIndex> = roots.iter().copied().collect();
+    let mut out = String::new();
+    for root in roots {
+        out.push_str(&format!("{}\n", graph[root].name));
+        walk(graph, root, "", &mut seen, &mut out);
+    }
+    out
+}
+
+/// Render a dependency graph as a Mermaid flowchart
+pub fn dependency_mermaid(graph: &DiGraph<PipelineMetadata, ()>) -> String {
+    let mut out = String::from("flowchart TD\n");
+    for node in graph.node_indices() {
+        out.push_str(&format!(
+            "    p{}[\"{}\"]\n",
+            node.index(),
+            graph[node].name.replace('"', "'")
+        ));
+    }
+    for edge in graph.edge_indices() {
+        if let Some((from, to)) = graph.edge_endpoints(edge) {
+            out.push_str(&format!("    p{} --> p{}\n", from.index(), to.index()));
+        }
+    }
+    out
+}
+
+/// Output format for `pipeline graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(GraphFormat::Dot),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            other => Err(anyhow!(
+                "Unsupported graph format '{}'. Use 'dot' or 'mermaid'",
+                other
+            )),
+        }
+    }
+}
+
+/// Step-level structure of a pipeline, ready to render
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineGraph {
+    pub name: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// A pipeline step in the graph
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    /// Step ID (or Oxi name when the step has no ID)
+    pub step_id: String,
+    /// Oxi executed by the step
+    pub oxi: String,
+    /// Status from the latest recorded run, when requested
+    pub status: Option<StepStatus>,
+}
+
+/// A directed edge between two nodes, by index into `nodes`
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum EdgeKind {
+    /// Output of one step feeds the next
+    DataFlow,
+    /// Explicit `depends_on` reference
+    DependsOn,
+}
+
+/// Build the step graph for a pipeline, optionally annotated with run state
+pub fn build_pipeline_graph(pipeline: &Pipeline, state: Option<&PipelineState>) -> PipelineGraph {
+    let nodes: Vec<GraphNode> = pipeline
+        .pipeline
+        .iter()
+        .map(|step| GraphNode {
+            step_id: step.get_id().to_string(),
+            oxi: step.name.clone(),
+            status: state
+                .and_then(|s| s.step_states.get(step.get_id()))
+                .map(|s| s.status.clone()),
+        })
+        .collect();
+
+    let mut edges: Vec<GraphEdge> = (1..nodes.len())
+        .map(|to| GraphEdge {
+            from: to - 1,
+            to,
+            kind: EdgeKind::DataFlow,
+        })
+        .collect();
+
+    for (to, step) in pipeline.pipeline.iter().enumerate() {
+        for dependency in &step.depends_on {
+            let Some(from) = nodes.iter().position(|n| &n.step_id == dependency) else {
+                continue; // Unknown references are reported by validation, not drawn
+            };
+            let already_linked = edges.iter().any(|e| e.from == from && e.to == to);
+            if !already_linked {
+                edges.push(GraphEdge {
+                    from,
+                    to,
+                    kind: EdgeKind::DependsOn,
+                });
+            }
+        }
+    }
+
+    PipelineGraph {
+        name: pipeline.name(),
+        nodes,
+        edges,
+    }
+}
+
+impl PipelineGraph {
+    /// Render in the requested format
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Render as a Graphviz DOT digraph
+    pub fn to_dot(&self) -> String {
+        let mut output = format!("digraph \"{}\" {{\n", escape_dot(&self.name));
+        output.push_str("    rankdir=LR;\n");
+        output.push_str("    node [shape=box, style=rounded];\n");
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let style = match &node.status {
+                Some(StepStatus::Completed { .. }) => {
+                    ", style=\"rounded,filled\", fillcolor=\"#c8e6c9\""
+                }
+                Some(StepStatus::Failed { .. }) => {
+                    ", style=\"rounded,filled\", fillcolor=\"#ffcdd2\", color=\"#c62828\""
+                }
+                Some(StepStatus::Running { .. }) => {
+                    ", style=\"rounded,filled\", fillcolor=\"#fff9c4\""
+                }
+                Some(StepStatus::Skipped { .. }) => {
+                    ", style=\"rounded,dashed\", fontcolor=\"#757575\""
+                }
+                Some(StepStatus::Pending) | None => "",
+            };
+            output.push_str(&format!(
+                "    s{index} [label=\"{}\\n({})\"{style}];\n",
+                escape_dot(&node.step_id),
+                escape_dot(&node.oxi)
+            ));
+        }
+
+        for edge in &self.edges {
+            let style = match edge.kind {
+                EdgeKind::DataFlow => "",
+                EdgeKind::DependsOn => " [style=dashed, label=\"depends_on\"]",
+            };
+            output.push_str(&format!("    s{} -> s{}{style};\n", edge.from, edge.to));
+        }
+
+        output.push('}');
+        output
+    }
+
+    /// Render as a Mermaid flowchart
+    pub fn to_mermaid(&self) -> String {
+        let mut output = String::from("flowchart LR\n");
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            output.push_str(&format!(
+                "    s{index}[\"{}<br/>({})\"]\n",
+                escape_mermaid(&node.step_id),
+                escape_mermaid(&node.oxi)
+            ));
+        }
+
+        for edge in &self.edges {
+            let arrow = match edge.kind {
+                EdgeKind::DataFlow => "-->",
+                EdgeKind::DependsOn => "-. depends_on .->",
+            };
+            output.push_str(&format!("    s{} {arrow} s{}\n", edge.from, edge.to));
+        }
+
+        let mut classes: Vec<(&str, Vec<String>)> = vec![
+            ("completed", Vec::new()),
+            ("failed", Vec::new()),
+            ("running", Vec::new()),
+            ("skipped", Vec::new()),
+        ];
+        for (index, node) in self.nodes.iter().enumerate() {
+            let class = match &node.status {
+                Some(StepStatus::Completed { .. }) => 0,
+                Some(StepStatus::Failed { .. }) => 1,
+                Some(StepStatus::Running { .. }) => 2,
+                Some(StepStatus::Skipped { .. }) => 3,
+                Some(StepStatus::Pending) | None => continue,
+            };
+            classes[class].1.push(format!("s{index}"));
+        }
+
+        if classes.iter().any(|(_, members)| !members.is_empty()) {
+            output.push_str("    classDef completed fill:#c8e6c9,stroke:#2e7d32\n");
+            output.push_str("    classDef failed fill:#ffcdd2,stroke:#c62828\n");
+            output.push_str("    classDef running fill:#fff9c4,stroke:#f9a825\n");
+            output
+                .push_str("    classDef skipped fill:#eeeeee,stroke:#9e9e9e,stroke-dasharray:4\n");
+            for (class, members) in classes {
+                if !members.is_empty() {
+                    output.push_str(&format!("    class {} {class}\n", members.join(",")));
+                }
+            }
+        }
+
+        output.trim_end().to_string()
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+// === SCHEMA CHAIN ===
+
+/// Thread schemas through a pipeline, starting from `initial` (an empty
+/// schema when `None`) and resolving configs with the default resolver
+pub fn infer_schema_chain(pipeline: &Pipeline, initial: Option<OxiSchema>) -> Result<SchemaTrace> {
+    pipeline.dry_run_schema(
+        initial.unwrap_or_else(OxiSchema::empty),
+        &ConfigResolver::default(),
+    )
+}
+
+/// Render a schema chain as a per-step table of strategy, input and output fields
+pub fn format_schema_chain(chain: &[StepSchema]) -> String {
+    fn fields(schema: Option<&OxiSchema>) -> String {
+        match schema {
+            None => "?".to_string(),
+            Some(schema) if schema.fields.is_empty() => "(none)".to_string(),
+            Some(schema) => {
+                let mut fields: Vec<String> = schema
+                    .fields
+                    .iter()
+                    .map(|(name, field)| {
+                        let nullable = if field.nullable { "?" } else { "" };
+                        format!("{name}: {}{nullable}", field.field_type)
+                    })
+                    .collect();
+                fields.sort();
+                fields.join(", ")
+            }
+        }
+    }
+
+    let mut output = String::new();
+    for (index, step) in chain.iter().enumerate() {
+        let strategy = step.strategy.as_ref().map_or("-", SchemaStrategy::label);
+        output.push_str(&format!(
+            "{:>3}. {} ({}) [{strategy}]\n",
+            index + 1,
+            step.step_id,
+            step.step_name
+        ));
+        if let Some(error) = &step.error {
+            output.push_str(&format!("       {error}\n"));
+        }
+        if step.strategy.is_some() {
+            output.push_str(&format!(
+                "       in:  {}\n",
+                fields(step.input_schema.as_ref())
+            ));
+            output.push_str(&format!(
+                "       out: {}\n",
+                fields(step.output_schema.as_ref())
+            ));
+        }
+    }
+    output
+}
+
+/// Truncate a string to a maximum length, adding "..." if truncated
+fn truncate_string(s: &str, max_len: usize) -> String {
+    if s.len() <= max_len {
         format!("{s:<max_len$}")
     } else {
         format!("{}...", &s[..max_len.saturating_sub(3)])
     }
 }
 
+/// Set (or with `None`, remove) a field in the top-level `metadata:` block.
+///
+/// Edits the text line by line so comments and formatting elsewhere survive.
+fn set_metadata_field(content: &str, field: &str, value: Option<&str>) -> String {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let rendered = |indent: &str, value: &str| format!("{indent}{field}: {value:?}");
+
+    let Some(start) = lines.iter().position(|l| l.trim_end() == "metadata:") else {
+        if let Some(value) = value {
+            lines.push("metadata:".to_string());
+            lines.push(rendered("  ", value));
+        }
+        return lines.join("\n") + "\n";
+    };
+
+    // The block runs until the next non-indented, non-blank line
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| !l.is_empty() && !l.starts_with(' ') && !l.starts_with('#'))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+    let indent = lines[start + 1..end]
+        .iter()
+        .find(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+        .map(|l| l[..l.len() - l.trim_start().len()].to_string())
+        .unwrap_or_else(|| "  ".to_string());
+
+    let prefix = format!("{indent}{field}:");
+    let existing = (start + 1..end).find(|&i| lines[i].starts_with(&prefix));
+
+    match (existing, value) {
+        (Some(i), Some(value)) => lines[i] = rendered(&indent, value),
+        (Some(i), None) => {
+            lines.remove(i);
+        }
+        (None, Some(value)) => lines.insert(start + 1, rendered(&indent, value)),
+        (None, None) => {}
+    }
+
+    lines.join("\n") + "\n"
+}
+
 /// Validate pipeline name (should be snake_case)
 fn is_valid_pipeline_name(name: &str) -> bool {
     if name.is_empty() {
@@ -1067,4 +2391,714 @@ mod tests {
         );
         assert_eq!(truncate_string("exact", 5), "exact");
     }
+
+    fn test_manager() -> PipelineManager {
+        let project_config: ProjectConfig = serde_yaml::from_str(
+            r#"
+            project: {name: test, version: "1.0.0", description: test}
+            oxis: {}
+            settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis}
+            environment: {}
+            "#,
+        )
+        .unwrap();
+        PipelineManager {
+            project_config,
+            template_dir: PathBuf::from(".oxiflow/templates"),
+        }
+    }
+
+    #[test]
+    fn test_strict_oxis_report_unknown_config_keys() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(
+            &mut file,
+            br#"
+pipeline:
+  - name: parse_json
+    id: parse
+    config: {content: "[]", contnet: "ignored by a lenient Oxi"}
+  - name: diff
+    id: compare
+    config: {before: "[]", keyy: id, max_batch_size: 10}
+metadata:
+  name: test
+"#,
+        )
+        .unwrap();
+
+        let result = test_manager()
+            .validate_pipeline_file(file.path(), false, false, false, false)
+            .unwrap();
+
+        let schema_errors: Vec<String> = result
+            .errors
+            .iter()
+            .filter(|e| matches!(e, ValidationError::Schema { .. }))
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(schema_errors.len(), 1, "{schema_errors:?}");
+        assert!(schema_errors[0].contains("'compare' (diff) has unknown config key 'keyy'"));
+    }
+
+    #[test]
+    fn test_validate_oxi_schemas_uses_descriptors() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(
+            &mut file,
+            br#"
+pipeline:
+  - name: read_file
+    id: reader
+  - name: json_select
+    id: selector
+    config:
+      path: "data.items"
+  - name: fetch_magic
+    id: custom
+metadata:
+  name: test
+"#,
+        )
+        .unwrap();
+
+        let result = test_manager()
+            .validate_pipeline_file(file.path(), false, false, false, false)
+            .unwrap();
+
+        assert!(!result.schemas_valid);
+        let schema_errors: Vec<String> = result
+            .errors
+            .iter()
+            .filter(|e| matches!(e, ValidationError::Schema { .. }))
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(schema_errors.len(), 1);
+        assert!(schema_errors[0].contains("'reader' (read_file)"));
+        assert!(schema_errors[0].contains("'path'"));
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.contains("unknown Oxi 'fetch_magic'")));
+    }
+
+    #[test]
+    fn test_validation_result_to_sarif() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"pipeline:\n  - name: [unclosed\n").unwrap();
+        let result = test_manager()
+            .validate_pipeline_file(file.path(), false, false, false, false)
+            .unwrap();
+        let sarif = result.to_sarif();
+        assert_eq!(sarif["version"], "2.1.0");
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "yaml-syntax");
+        assert_eq!(results[0]["level"], "error");
+        assert!(
+            results[0]["locations"][0]["physicalLocation"]["region"]["startLine"]
+                .as_u64()
+                .is_some()
+        );
+
+        let mut result = ValidationResult::new(PathBuf::from("pipelines/etl.yaml"));
+        result.errors.push(ValidationError::StepReference {
+            message: "Unknown step 'nope'".to_string(),
+        });
+        result
+            .warnings
+            .push("No metadata section found".to_string());
+        let sarif = result.to_sarif();
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results[0]["ruleId"], "step-reference");
+        assert_eq!(results[0]["message"]["text"], "Unknown step 'nope'");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "pipelines/etl.yaml");
+        assert!(location.get("region").is_none());
+        assert_eq!(results[1]["level"], "warning");
+        let rules = sarif["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap();
+        assert!(rules.iter().any(|rule| rule["id"] == results[1]["ruleId"]));
+    }
+
+    #[test]
+    fn test_validation_result_to_junit_xml_and_strict() {
+        let mut result = ValidationResult::new(PathBuf::from("pipelines/etl.yaml"));
+        result.yaml_valid = true;
+        result
+            .warnings
+            .push("Step 'x' uses unknown Oxi '<custom>'".to_string());
+        assert!(result.is_valid());
+
+        let xml = result.to_junit_xml();
+        assert!(
+            xml.contains(r#"<testsuite name="etl" tests="7" failures="0""#),
+            "{xml}"
+        );
+        assert!(xml.contains(r#"<testcase classname="etl" name="schema"/>"#));
+        assert!(xml.contains("<system-out>Step &apos;x&apos; uses unknown Oxi &apos;&lt;custom&gt;&apos;</system-out>"));
+
+        result.errors.push(ValidationError::Schema {
+            message: "missing 'path'".to_string(),
+        });
+        result.strict = true;
+        assert!(!result.is_valid());
+        assert_eq!(result.failure_count(), 2);
+        let xml = result.to_junit_xml();
+        assert!(xml.contains(r#"failures="2""#), "{xml}");
+        assert!(xml.contains(r#"<failure type="schema""#));
+        assert!(xml.contains(r#"<failure type="pipeline-warning""#));
+        assert_eq!(result.to_sarif()["runs"][0]["results"][1]["level"], "error");
+
+        let json = result.to_json();
+        assert_eq!(json["valid"], false);
+        assert_eq!(json["errors"][0]["rule"], "schema");
+        assert_eq!(json["errors"][0]["message"], "missing 'path'");
+
+        // Checks after a YAML syntax error never ran
+        let mut broken = ValidationResult::new(PathBuf::from("broken.yaml"));
+        broken.errors.push(ValidationError::YamlSyntax {
+            message: "bad".to_string(),
+            line: Some(2),
+        });
+        let xml = broken.to_junit_xml();
+        assert!(
+            xml.contains(r#"failures="1" errors="0" skipped="6""#),
+            "{xml}"
+        );
+    }
+
+    #[test]
+    fn test_validate_schema_compatibility_between_steps() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(
+            &mut file,
+            br#"
+pipeline:
+  - name: coerce_types
+    id: typed
+    config:
+      schema:
+        name: {type: string, nullable: true}
+        customer: {type: string, nullable: true}
+  - name: template
+    id: greeting
+    config:
+      engine: simple
+      template: "{{name}} ({{customer.id}}) {{email}}"
+metadata:
+  name: test
+"#,
+        )
+        .unwrap();
+
+        // Schema-only testing runs the same checks
+        let result = test_manager()
+            .validate_pipeline_file(file.path(), false, false, false, true)
+            .unwrap();
+
+        assert!(!result.is_valid());
+        let errors: Vec<String> = result.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "Schema: step greeting requires field 'customer: object' but upstream provides 'customer: string'",
+                "Schema: step greeting requires field 'email' but upstream does not provide it",
+            ]
+        );
+        assert_eq!(
+            result.warnings,
+            vec!["step greeting requires field 'customer: object' to be non-null but upstream may provide null"]
+        );
+    }
+
+    fn branching_pipeline() -> Pipeline {
+        serde_yaml::from_str(
+            r#"
+pipeline:
+  - name: read_file
+    id: reader
+    config:
+      path: input.json
+  - name: parse_json
+    id: parser
+  - name: format_csv
+    id: to_csv
+  - name: write_file
+    id: writer
+    depends_on: [parser]
+    config:
+      path: output.csv
+metadata:
+  name: branching
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pipeline_graph_dot() {
+        let graph = build_pipeline_graph(&branching_pipeline(), None);
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.edges.len(), 4);
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph \"branching\" {"));
+        assert!(dot.contains("s0 [label=\"reader\\n(read_file)\"];"));
+        assert!(dot.contains("s0 -> s1;"));
+        assert!(dot.contains("s2 -> s3;"));
+        assert!(dot.contains("s1 -> s3 [style=dashed, label=\"depends_on\"];"));
+        assert!(!dot.contains("fillcolor"));
+    }
+
+    #[test]
+    fn test_pipeline_graph_mermaid() {
+        let mermaid = build_pipeline_graph(&branching_pipeline(), None).to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR"));
+        assert!(mermaid.contains("s3[\"writer<br/>(write_file)\"]"));
+        assert!(mermaid.contains("s2 --> s3"));
+        assert!(mermaid.contains("s1 -. depends_on .-> s3"));
+        assert!(!mermaid.contains("classDef"));
+    }
+
+    #[test]
+    fn test_pipeline_graph_colored_by_state() {
+        use crate::state::types::StepState;
+
+        let mut state = PipelineState::new("branching".to_string(), "run-1".to_string());
+        for (id, oxi) in [("reader", "read_file"), ("parser", "parse_json")] {
+            let mut step = StepState::new(id.to_string(), oxi.to_string());
+            step.status = StepStatus::Completed {
+                completed_at: chrono::Utc::now(),
+            };
+            state.step_states.insert(id.to_string(), step);
+        }
+        let mut failed = StepState::new("to_csv".to_string(), "format_csv".to_string());
+        failed.status = StepStatus::Failed {
+            error: "boom".to_string(),
+            failed_at: chrono::Utc::now(),
+        };
+        state.step_states.insert("to_csv".to_string(), failed);
+
+        let graph = build_pipeline_graph(&branching_pipeline(), Some(&state));
+        assert!(graph.nodes[3].status.is_none());
+
+        let dot = graph.to_dot();
+        assert!(dot.contains(
+            "s2 [label=\"to_csv\\n(format_csv)\", style=\"rounded,filled\", fillcolor=\"#ffcdd2\""
+        ));
+        assert!(dot.contains("s3 [label=\"writer\\n(write_file)\"];"));
+
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.contains("class s0,s1 completed"));
+        assert!(mermaid.contains("class s2 failed"));
+    }
+
+    #[test]
+    fn test_graph_format_parse() {
+        assert_eq!("DOT".parse::<GraphFormat>().unwrap(), GraphFormat::Dot);
+        assert_eq!(
+            "mermaid".parse::<GraphFormat>().unwrap(),
+            GraphFormat::Mermaid
+        );
+        assert!("svg".parse::<GraphFormat>().is_err());
+    }
+
+    /// Manager whose pipeline and template directories live under `root`
+    fn template_test_manager(root: &Path) -> PipelineManager {
+        let mut manager = test_manager();
+        manager.project_config.settings.pipeline_dir =
+            root.join("pipelines").to_string_lossy().into_owned();
+        manager.template_dir = root.join("templates");
+        fs::create_dir_all(&manager.template_dir).unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_project_template_overrides_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = template_test_manager(dir.path());
+        fs::write(
+            manager.template_dir.join("basic.yaml"),
+            "# Company basic pipeline\npipeline:\n  - name: read_stdin\n",
+        )
+        .unwrap();
+
+        let templates = manager.list_templates().unwrap();
+        let basic: Vec<_> = templates.iter().filter(|t| t.name == "basic").collect();
+        assert_eq!(basic.len(), 1);
+        assert_eq!(basic[0].description, "Company basic pipeline");
+        assert!(matches!(basic[0].source, TemplateSource::Project(_)));
+        // Other built-ins remain available
+        assert!(templates
+            .iter()
+            .any(|t| t.name == "etl" && t.source == TemplateSource::Builtin));
+
+        let path = manager
+            .create_pipeline("my_basic", "basic", None, None, &HashMap::new())
+            .unwrap();
+        assert!(fs::read_to_string(path).unwrap().contains("read_stdin"));
+    }
+
+    #[test]
+    fn test_custom_template_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = template_test_manager(dir.path());
+        fs::write(
+            manager.template_dir.join("warehouse.yaml"),
+            "# Warehouse load\npipeline:\n  - name: read_file\n    config:\n      path: \"{{ source_table }}.json\"\nmetadata:\n  name: \"{{pipeline_name}}\"\n  owner: \"{{team}}\"\n",
+        )
+        .unwrap();
+
+        let template = manager.get_template("warehouse").unwrap();
+        assert_eq!(
+            template.variables(),
+            vec!["source_table", "pipeline_name", "team"]
+        );
+        assert_eq!(
+            manager
+                .unresolved_template_variables("warehouse", &HashMap::new())
+                .unwrap(),
+            vec!["source_table", "team"]
+        );
+
+        let variables = HashMap::from([
+            ("source_table".to_string(), "users".to_string()),
+            ("team".to_string(), "data-eng".to_string()),
+        ]);
+        let path = manager
+            .create_pipeline("load_users", "warehouse", None, None, &variables)
+            .unwrap();
+        let content = fs::read_to_string(path).unwrap();
+        assert!(content.contains("path: \"users.json\""));
+        assert!(content.contains("owner: \"data-eng\""));
+        assert!(content.contains("name: \"Load Users\""));
+    }
+
+    #[test]
+    fn test_unresolved_template_variables_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = template_test_manager(dir.path());
+        fs::write(
+            manager.template_dir.join("custom.yaml"),
+            "pipeline:\n  - name: read_file\n    config:\n      path: \"{{input_dir}}/{{input_name}}\"\n",
+        )
+        .unwrap();
+
+        let variables = HashMap::from([("input_dir".to_string(), "data".to_string())]);
+        let err = manager
+            .create_pipeline("custom_run", "custom", None, None, &variables)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unresolved variables: input_name"), "{err}");
+        assert!(!dir.path().join("pipelines/custom_run.yaml").exists());
+    }
+
+    const LIFECYCLE_PIPELINE: &str = r#"# Orders pipeline
+pipeline:
+  - name: read_stdin
+    id: reader
+
+metadata:
+  name: "Orders"
+  version: "2.1.0"
+  created: "2024-01-01"
+"#;
+
+    fn lifecycle_manager(root: &Path) -> PipelineManager {
+        let manager = template_test_manager(root);
+        let pipeline_dir = manager.get_pipeline_directory();
+        fs::create_dir_all(&pipeline_dir).unwrap();
+        fs::write(pipeline_dir.join("orders.yaml"), LIFECYCLE_PIPELINE).unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_delete_pipeline() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = lifecycle_manager(dir.path());
+
+        let deleted = manager.delete_pipeline("orders").unwrap();
+        assert_eq!(deleted.name, "Orders");
+        assert!(!deleted.file_path.exists());
+        assert!(manager.delete_pipeline("orders").is_err());
+    }
+
+    #[test]
+    fn test_rename_pipeline_updates_metadata_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = lifecycle_manager(dir.path());
+
+        let (old, new) = manager.rename_pipeline("orders", "daily_orders").unwrap();
+        assert_eq!(old.name, "Orders");
+        assert_eq!(new.name, "Daily Orders");
+        assert!(!old.file_path.exists());
+
+        let content = fs::read_to_string(&new.file_path).unwrap();
+        assert!(content.starts_with("# Orders pipeline\n"));
+        assert!(content.contains("  name: \"Daily Orders\""));
+        assert!(content.contains("  version: \"2.1.0\""));
+    }
+
+    #[test]
+    fn test_copy_pipeline_resets_version_and_created() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = lifecycle_manager(dir.path());
+
+        let path = manager.copy_pipeline("orders", "orders_backfill").unwrap();
+        let copy = manager.extract_metadata(&path).unwrap();
+        assert_eq!(copy.name, "Orders Backfill");
+        assert_eq!(copy.version, None);
+        assert_eq!(
+            copy.created,
+            Some(chrono::Utc::now().format("%Y-%m-%d").to_string())
+        );
+        // The original is untouched
+        assert!(manager
+            .get_pipeline_directory()
+            .join("orders.yaml")
+            .exists());
+    }
+
+    #[test]
+    fn test_rename_and_copy_refuse_existing_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = lifecycle_manager(dir.path());
+        manager.copy_pipeline("orders", "orders_copy").unwrap();
+
+        let err = manager
+            .rename_pipeline("orders", "orders_copy")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("already exists"), "{err}");
+        assert!(manager.copy_pipeline("orders", "orders_copy").is_err());
+        assert!(manager.copy_pipeline("orders", "Bad-Name").is_err());
+        assert!(manager
+            .get_pipeline_directory()
+            .join("orders.yaml")
+            .exists());
+    }
+
+    fn write_dependent_pipeline(manager: &PipelineManager, file: &str, deps: &[&str]) {
+        let dir = manager.get_pipeline_directory();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(format!("{file}.yaml")),
+            format!(
+                "metadata:\n  name: {file}\n  depends_on: [{}]\npipeline:\n  - name: read_stdin\n",
+                deps.join(", ")
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_topological_sort_orders_dependencies_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = template_test_manager(dir.path());
+        write_dependent_pipeline(&manager, "report", &["transform", "extract"]);
+        write_dependent_pipeline(&manager, "transform", &["extract"]);
+        write_dependent_pipeline(&manager, "extract", &[]);
+
+        let order: Vec<String> = manager
+            .topological_sort()
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(order, vec!["extract", "transform", "report"]);
+
+        let graph = manager.build_dependency_graph().unwrap();
+        assert_eq!(
+            dependency_tree(&graph),
+            "extract\n├── report\n└── transform\n    └── report (see above)\n"
+        );
+        assert!(dependency_mermaid(&graph).starts_with("flowchart TD\n"));
+    }
+
+    #[test]
+    fn test_dependency_cycle_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = template_test_manager(dir.path());
+        write_dependent_pipeline(&manager, "a", &["b"]);
+        write_dependent_pipeline(&manager, "b", &["c"]);
+        write_dependent_pipeline(&manager, "c", &["a"]);
+        write_dependent_pipeline(&manager, "d", &[]);
+
+        let err = manager.build_dependency_graph().unwrap_err().to_string();
+        assert!(err.contains("cycle"), "{err}");
+        for name in ["a", "b", "c"] {
+            assert!(err.contains(name), "{err}");
+        }
+        assert!(manager.topological_sort().is_err());
+
+        write_dependent_pipeline(&manager, "c", &["missing"]);
+        let err = manager.build_dependency_graph().unwrap_err().to_string();
+        assert!(err.contains("unknown pipeline 'missing'"), "{err}");
+    }
+
+    #[test]
+    fn test_schema_chain_threads_output_schemas() {
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+            pipeline:
+              - name: read_file
+                id: reader
+                config: {path: input.json}
+              - name: coerce_types
+                id: typed
+                config:
+                  schema:
+                    id: integer
+                    name: {type: string, nullable: true}
+              - name: write_stdout
+            "#,
+        )
+        .unwrap();
+
+        let chain = infer_schema_chain(&pipeline, None).unwrap();
+        assert_eq!(chain.len(), 3);
+        assert!(chain[0].input_schema.as_ref().unwrap().fields.is_empty());
+        assert!(chain.iter().all(|step| step.error.is_none()));
+
+        let output = chain[2].output_schema.as_ref().unwrap();
+        let mut fields: Vec<&String> = output.fields.keys().collect();
+        fields.sort();
+        assert_eq!(fields, ["id", "name"]);
+        assert_eq!(
+            output.fields["id"].field_type,
+            crate::types::FieldType::Integer
+        );
+
+        let table = format_schema_chain(&chain);
+        assert!(table.contains("typed (coerce_types) [Modify]"), "{table}");
+        assert!(table.contains("out: id: integer, name: string?"), "{table}");
+    }
+
+    #[test]
+    fn test_schema_chain_skips_unknown_oxi() {
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+            pipeline:
+              - name: read_stdin
+              - name: not_a_real_oxi
+                id: mystery
+              - name: write_stdout
+            "#,
+        )
+        .unwrap();
+
+        let chain = infer_schema_chain(&pipeline, None).unwrap();
+        assert_eq!(
+            chain[1].error.as_deref(),
+            Some("unknown — schema inference skipped")
+        );
+        assert!(chain[1].strategy.is_none());
+        assert!(chain[2].input_schema.is_none());
+
+        let table = format_schema_chain(&chain);
+        assert!(table.contains("mystery (not_a_real_oxi) [-]"), "{table}");
+        assert!(table.contains("in:  ?"), "{table}");
+    }
+
+    fn namespaced_manager(root: &Path) -> PipelineManager {
+        let mut manager = template_test_manager(root);
+        manager.project_config.settings.recursive = true;
+        for (path, name) in [
+            ("pipelines/orders.yaml", "Orders"),
+            ("pipelines/ingest/users_sync.yaml", "Users Sync"),
+            ("pipelines/ingest/orders.yaml", "Ingest Orders"),
+            ("pipelines/reports/pipeline.yaml", "Reports"),
+        ] {
+            let file = root.join(path);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(
+                file,
+                format!("metadata:\n  name: \"{name}\"\npipeline:\n  - name: read_stdin\n"),
+            )
+            .unwrap();
+        }
+        manager
+    }
+
+    #[test]
+    fn test_recursive_discovery_namespaces_pipelines() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = namespaced_manager(dir.path());
+
+        let pipelines = manager.discover_pipelines().unwrap();
+        let mut names: Vec<(&str, Option<&str>)> = pipelines
+            .iter()
+            .map(|p| (p.qualified_name.as_str(), p.namespace.as_deref()))
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                ("ingest/orders", Some("ingest")),
+                ("ingest/users_sync", Some("ingest")),
+                ("orders", None),
+                ("reports", None),
+            ]
+        );
+
+        let users = manager.find_pipeline_metadata("users_sync").unwrap();
+        assert_eq!(users.qualified_name, "ingest/users_sync");
+        let nested = manager.find_pipeline_metadata("ingest/orders").unwrap();
+        assert_eq!(nested.name, "Ingest Orders");
+        // An exact match wins over the namespaced one sharing its bare name
+        let top = manager.find_pipeline_metadata("orders").unwrap();
+        assert_eq!(top.name, "Orders");
+    }
+
+    #[test]
+    fn test_ambiguous_bare_name_lists_candidates() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = namespaced_manager(dir.path());
+        fs::create_dir_all(dir.path().join("pipelines/billing")).unwrap();
+        fs::write(
+            dir.path().join("pipelines/billing/users_sync.yaml"),
+            "pipeline:\n  - name: read_stdin\n",
+        )
+        .unwrap();
+        manager.project_config.settings.recursive = true;
+
+        let err = manager
+            .find_pipeline_metadata("users_sync")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("ambiguous"), "{err}");
+        assert!(err.contains("billing/users_sync"), "{err}");
+        assert!(err.contains("ingest/users_sync"), "{err}");
+    }
+
+    #[test]
+    fn test_non_recursive_discovery_across_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manager = namespaced_manager(dir.path());
+        manager.project_config.settings.recursive = false;
+        let shared = dir.path().join("shared");
+        fs::create_dir_all(&shared).unwrap();
+        fs::write(
+            shared.join("audit.yml"),
+            "pipeline:\n  - name: read_stdin\n",
+        )
+        .unwrap();
+        manager
+            .project_config
+            .settings
+            .pipeline_directories
+            .push(shared.to_string_lossy().into_owned());
+
+        let mut names: Vec<String> = manager
+            .discover_pipelines()
+            .unwrap()
+            .into_iter()
+            .map(|p| p.qualified_name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["audit", "orders", "reports"]);
+        assert!(manager.find_pipeline_metadata("users_sync").is_err());
+    }
 }
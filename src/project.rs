@@ -1,3 +1,6 @@
+use crate::oxis::plugin::PluginConfig;
+use crate::pipeline_lint::LintConfig;
+use crate::state::webhook::NotificationSettings;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -14,6 +17,14 @@ pub struct ProjectConfig {
     pub environment: HashMap<String, String>,
     #[serde(default)]
     pub state_manager: Option<StateConfig>,
+    #[serde(default)]
+    pub lint: LintConfig,
+    /// Oxis run from external executables or libraries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<PluginConfig>,
+    /// Config merged under every step that runs the named Oxi
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub defaults: HashMap<String, serde_yaml::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +32,15 @@ pub struct ProjectMetadata {
     pub name: String,
     pub version: String,
     pub description: String,
+    /// Webhooks notified of pipeline state changes
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    /// Environment recorded on run states, e.g. `prod`; `OXIDE_FLOW_ENV` overrides it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    /// Tags recorded on every run's state, under the pipeline's own `metadata.tags`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tags: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,8 +53,102 @@ pub struct OxiSource {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectSettings {
     pub output_dir: String,
+    /// Primary pipeline directory; new pipelines are created here
+    #[serde(default)]
     pub pipeline_dir: String,
+    /// Further pipeline directories searched after `pipeline_dir`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pipeline_directories: Vec<String>,
+    /// Search pipeline directories recursively, namespacing pipelines by subfolder
+    #[serde(default)]
+    pub recursive: bool,
     pub oxis_dir: String,
+    /// Directory of secret files consulted (before env vars) for `${secret:NAME}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets_dir: Option<String>,
+    /// `.env` file loaded into the environment before `${VAR}` references are resolved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_file: Option<String>,
+}
+
+/// A pipeline file found in one of the project's pipeline directories
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineFile {
+    /// Name used by run/info/test, e.g. `users_sync` or `ingest/users_sync`
+    pub name: String,
+    /// Subfolder the pipeline is namespaced under, e.g. `ingest`
+    pub namespace: Option<String>,
+    pub path: PathBuf,
+}
+
+impl PipelineFile {
+    /// Name without its namespace
+    pub fn bare_name(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or(&self.name)
+    }
+}
+
+fn is_pipeline_yaml(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml")
+}
+
+fn collect_pipeline_files(
+    root: &Path,
+    dir: &Path,
+    recursive: bool,
+    out: &mut Vec<PipelineFile>,
+) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if recursive {
+                collect_pipeline_files(root, &path, true, out)?;
+            } else if dir == root {
+                let nested = ["pipeline.yaml", "pipeline.yml"]
+                    .map(|file| path.join(file))
+                    .into_iter()
+                    .find(|file| file.is_file());
+                if let Some(file) = nested {
+                    out.push(pipeline_file(root, &file));
+                }
+            }
+        } else if is_pipeline_yaml(&path) {
+            out.push(pipeline_file(root, &path));
+        }
+    }
+    Ok(())
+}
+
+fn pipeline_file(root: &Path, path: &Path) -> PipelineFile {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let mut segments: Vec<String> = relative
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // `<folder>/pipeline.yaml` is named after its folder
+    if stem != "pipeline" || segments.is_empty() {
+        segments.push(stem);
+    }
+
+    let name = segments.join("/");
+    let namespace = name.rsplit_once('/').map(|(ns, _)| ns.to_string());
+    PipelineFile {
+        name,
+        namespace,
+        path: path.to_path_buf(),
+    }
 }
 
 /// State management configuration
@@ -48,6 +162,10 @@ pub struct StateConfig {
     #[serde(default)]
     pub file: Option<FileStateConfig>,
 
+    /// Memory backend configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryStateConfig>,
+
     /// Heartbeat interval (e.g., "10s", "5m")
     #[serde(default = "default_heartbeat_interval")]
     pub heartbeat_interval: String,
@@ -59,6 +177,194 @@ pub struct StateConfig {
     /// Cleanup interval (e.g., "1h", "24h")
     #[serde(default = "default_cleanup_interval")]
     pub cleanup_interval: String,
+
+    /// How long to wait for a pipeline's lock (e.g., "30s", "5m")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_timeout: Option<String>,
+
+    /// How long a pipeline's lock lasts without renewal (default "60s")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_ttl: Option<String>,
+
+    /// Fail runs whose worker stopped sending heartbeats before each `run`
+    #[serde(default)]
+    pub recover_stale_runs: bool,
+
+    /// Heartbeat age after which a running pipeline counts as lost (default "5m")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_run_threshold: Option<String>,
+
+    /// Reset recovered runs to Pending instead of leaving them Failed
+    #[serde(default)]
+    pub auto_requeue_stale: bool,
+
+    /// Let heartbeat tasks claim runs abandoned by crashed workers
+    #[serde(default)]
+    pub auto_claim_abandoned: bool,
+
+    /// How often heartbeat tasks look for abandoned runs to claim (default "1m")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_claim_interval: Option<String>,
+
+    /// Most errors kept in a pipeline's state before older ones are archived (default 500)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_errors_in_state: Option<usize>,
+
+    /// Runs whose completed steps are kept in full before collapsing to summaries (default 3)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step_state_runs: Option<usize>,
+
+    /// Step summaries kept in a pipeline's state before the oldest are dropped (default 1000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_step_summaries: Option<usize>,
+
+    /// Runs kept in each pipeline's run history before the oldest are deleted (default 50)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_saved_runs: Option<usize>,
+}
+
+/// Backends a `state_manager` section or a pipeline's `state:` block may name
+pub const SUPPORTED_STATE_BACKENDS: &[&str] = &["file", "memory"];
+
+/// Environment variable selecting the environment runs are recorded in
+pub const ENVIRONMENT_VAR: &str = "OXIDE_FLOW_ENV";
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            backend: default_backend(),
+            file: None,
+            memory: None,
+            heartbeat_interval: default_heartbeat_interval(),
+            checkpoint_interval: default_checkpoint_interval(),
+            cleanup_interval: default_cleanup_interval(),
+            lock_timeout: None,
+            lock_ttl: None,
+            recover_stale_runs: false,
+            stale_run_threshold: None,
+            auto_requeue_stale: false,
+            auto_claim_abandoned: false,
+            auto_claim_interval: None,
+            max_errors_in_state: None,
+            step_state_runs: None,
+            max_step_summaries: None,
+            max_saved_runs: None,
+        }
+    }
+}
+
+impl Default for FileStateConfig {
+    fn default() -> Self {
+        Self {
+            base_path: default_state_path(),
+            lock_timeout: default_lock_timeout(),
+            backup_enabled: default_backup_enabled(),
+            backup_retention: default_backup_retention(),
+            write_ahead_log: false,
+        }
+    }
+}
+
+impl StateConfig {
+    /// Reject unknown backends and durations that don't parse
+    pub fn validate(&self) -> Result<()> {
+        if !SUPPORTED_STATE_BACKENDS.contains(&self.backend.as_str()) {
+            anyhow::bail!(
+                "Unknown state backend '{}'; supported backends are: {}",
+                self.backend,
+                SUPPORTED_STATE_BACKENDS.join(", ")
+            );
+        }
+        let durations = [
+            ("heartbeat_interval", Some(&self.heartbeat_interval)),
+            ("lock_timeout", self.lock_timeout.as_ref()),
+            ("lock_ttl", self.lock_ttl.as_ref()),
+            ("stale_run_threshold", self.stale_run_threshold.as_ref()),
+            ("auto_claim_interval", self.auto_claim_interval.as_ref()),
+            (
+                "file.lock_timeout",
+                self.file.as_ref().map(|f| &f.lock_timeout),
+            ),
+        ];
+        for (key, value) in durations {
+            if let Some(value) = value {
+                if parse_duration(value).is_none() {
+                    anyhow::bail!("Invalid duration '{value}' for state {key}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the state manager settings these options describe
+    ///
+    /// An unknown backend falls back to the file backend with a warning; call
+    /// `validate` first to reject it instead.
+    pub fn manager_config(&self) -> crate::state::manager::StateManagerConfig {
+        use crate::state::backend::{BackendConfig, SerializationFormat};
+        use crate::state::manager::StateManagerConfig;
+
+        let backend = match self.backend.as_str() {
+            "memory" => {
+                let memory_config = self.memory.clone().unwrap_or_default();
+                BackendConfig::Memory {
+                    persist_dir: memory_config
+                        .persistent
+                        .then(|| PathBuf::from(memory_config.base_path)),
+                }
+            }
+            backend => {
+                if backend != "file" {
+                    eprintln!("⚠️  Unknown backend type '{backend}', falling back to file");
+                }
+                let file_config = self.file.clone().unwrap_or_default();
+                BackendConfig::File {
+                    base_path: PathBuf::from(&file_config.base_path),
+                    format: SerializationFormat::Json,
+                    atomic_writes: true,
+                    lock_timeout_ms: parse_duration(&file_config.lock_timeout).unwrap_or(30000),
+                    write_ahead_log: file_config.write_ahead_log,
+                }
+            }
+        };
+
+        StateManagerConfig {
+            backend,
+            default_lock_timeout_ms: self
+                .lock_timeout
+                .as_deref()
+                .and_then(parse_duration)
+                .unwrap_or(30000),
+            lock_ttl_ms: self
+                .lock_ttl
+                .as_deref()
+                .and_then(parse_duration)
+                .unwrap_or(60000),
+            worker_id: format!("worker_{}", std::process::id()),
+            heartbeat_interval_ms: parse_duration(&self.heartbeat_interval).unwrap_or(10000),
+            max_retries: 3,
+            cleanup_interval_hours: 24,
+            max_state_age_hours: 168,
+            health_history_size: 100,
+            recover_stale_runs: self.recover_stale_runs,
+            stale_run_threshold_ms: self
+                .stale_run_threshold
+                .as_deref()
+                .and_then(parse_duration)
+                .unwrap_or(300000),
+            auto_requeue_stale: self.auto_requeue_stale,
+            auto_claim_abandoned: self.auto_claim_abandoned,
+            auto_claim_interval_ms: self
+                .auto_claim_interval
+                .as_deref()
+                .and_then(parse_duration)
+                .unwrap_or(60000),
+            max_errors_in_state: self.max_errors_in_state.unwrap_or(500),
+            step_state_runs: self.step_state_runs.unwrap_or(3),
+            max_step_summaries: self.max_step_summaries.unwrap_or(1000),
+            max_saved_runs: self.max_saved_runs.unwrap_or(50),
+        }
+    }
 }
 
 /// File backend specific configuration
@@ -79,6 +385,31 @@ pub struct FileStateConfig {
     /// Backup retention period (e.g., "7d", "30d")
     #[serde(default = "default_backup_retention")]
     pub backup_retention: String,
+
+    /// Log each state change before applying it, so a crash can be recovered
+    #[serde(default)]
+    pub write_ahead_log: bool,
+}
+
+/// Memory backend settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryStateConfig {
+    /// Save states to `{base_path}/memory_state.json` after every change
+    #[serde(default)]
+    pub persistent: bool,
+
+    /// Directory of the persisted states
+    #[serde(default = "default_state_path")]
+    pub base_path: String,
+}
+
+impl Default for MemoryStateConfig {
+    fn default() -> Self {
+        Self {
+            persistent: false,
+            base_path: default_state_path(),
+        }
+    }
 }
 
 // Default functions for serde
@@ -126,24 +457,13 @@ impl ProjectConfig {
         Ok(config)
     }
 
-    /// Find a pipeline by name in the configured pipeline directory
+    /// Find a pipeline by name in the configured pipeline directories
+    ///
+    /// See `resolve_pipeline` for how names are matched.
     pub fn find_pipeline(&self, name: &str) -> Result<PathBuf> {
-        let pipeline_dir = Path::new(&self.settings.pipeline_dir);
-
-        // Try different extensions and exact matches
-        let candidates = vec![
-            format!("{}.yaml", name),
-            format!("{}.yml", name),
-            format!("{name}/pipeline.yaml"),
-            format!("{name}/pipeline.yml"),
-        ];
-
-        for candidate in candidates {
-            let path = pipeline_dir.join(&candidate);
-            if path.exists() && path.is_file() {
-                println!("📋 Found pipeline: {}", path.display());
-                return Ok(path);
-            }
+        if let Some(file) = self.resolve_pipeline(name)? {
+            crate::progress!("📋 Found pipeline: {}", file.path.display());
+            return Ok(file.path);
         }
 
         // If not found, list available pipelines to help the user
@@ -151,60 +471,125 @@ impl ProjectConfig {
         anyhow::bail!(
             "Pipeline '{}' not found in {}",
             name,
-            pipeline_dir.display()
+            self.pipeline_directories_display()
         )
     }
 
-    /// Get the configured pipeline directory as a PathBuf
+    /// Look up a pipeline file by namespaced or bare name
+    ///
+    /// An exact name (`ingest/users_sync`) wins; otherwise a bare name
+    /// (`users_sync`) matches the last segment of namespaced names. Returns
+    /// `Ok(None)` when nothing matches and an error listing the candidates
+    /// when more than one does.
+    pub fn resolve_pipeline(&self, name: &str) -> Result<Option<PipelineFile>> {
+        let files = self.pipeline_files()?;
+
+        let exact: Vec<&PipelineFile> = files.iter().filter(|f| f.name == name).collect();
+        let candidates = if exact.is_empty() {
+            files.iter().filter(|f| f.bare_name() == name).collect()
+        } else {
+            exact
+        };
+
+        match candidates.as_slice() {
+            [] => Ok(None),
+            [file] => Ok(Some((*file).clone())),
+            many => anyhow::bail!(
+                "Pipeline '{}' is ambiguous. Candidates: {}",
+                name,
+                many.iter()
+                    .map(|f| format!("{} ({})", f.name, f.path.display()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// Get the primary pipeline directory as a PathBuf
     pub fn get_pipeline_directory(&self) -> PathBuf {
-        PathBuf::from(&self.settings.pipeline_dir)
+        self.pipeline_directories()
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| PathBuf::from("pipelines"))
+    }
+
+    /// Every configured pipeline directory: `pipeline_dir`, then `pipeline_directories`
+    pub fn pipeline_directories(&self) -> Vec<PathBuf> {
+        let mut directories: Vec<PathBuf> = Vec::new();
+        let configured = std::iter::once(&self.settings.pipeline_dir)
+            .chain(&self.settings.pipeline_directories)
+            .filter(|dir| !dir.is_empty());
+        for dir in configured {
+            let dir = PathBuf::from(dir);
+            if !directories.contains(&dir) {
+                directories.push(dir);
+            }
+        }
+        directories
+    }
+
+    /// All pipeline directories, comma separated, for messages
+    pub fn pipeline_directories_display(&self) -> String {
+        self.pipeline_directories()
+            .iter()
+            .map(|dir| dir.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// All pipeline files in the configured directories, sorted by name
+    ///
+    /// Top-level files are named after their stem. With `recursive`, files in
+    /// subfolders are namespaced by their relative folder (`ingest/users_sync`).
+    /// A `pipeline.yaml` inside a folder is named after the folder, which is
+    /// also picked up one level deep when not recursing.
+    pub fn pipeline_files(&self) -> Result<Vec<PipelineFile>> {
+        let mut files = Vec::new();
+        for root in self.pipeline_directories() {
+            if root.is_dir() {
+                collect_pipeline_files(&root, &root, self.settings.recursive, &mut files)?;
+            }
+        }
+        files.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+        Ok(files)
+    }
+
+    /// Environment runs are recorded in: `OXIDE_FLOW_ENV` when set, else `project.environment`
+    pub fn active_environment(&self) -> Option<String> {
+        std::env::var(ENVIRONMENT_VAR)
+            .ok()
+            .filter(|env| !env.is_empty())
+            .or_else(|| self.project.environment.clone())
+    }
+
+    /// Resolver for `${secret:NAME}`: the secrets directory if configured, then env vars
+    pub fn secret_resolver(&self) -> std::sync::Arc<dyn crate::secrets::SecretResolver> {
+        crate::secrets::project_secret_resolver(self.settings.secrets_dir.as_deref().map(Path::new))
+            .into()
+    }
+
+    /// Names of all pipelines in the configured directories, sorted, without printing
+    pub fn pipeline_names(&self) -> Result<Vec<String>> {
+        let mut names: Vec<String> = self.pipeline_files()?.into_iter().map(|f| f.name).collect();
+        names.dedup();
+        Ok(names)
     }
 
-    /// List all available pipelines in the configured directory
+    /// List all available pipelines in the configured directories
     pub fn list_available_pipelines(&self) -> Result<Vec<String>> {
-        let pipeline_dir = Path::new(&self.settings.pipeline_dir);
+        let directories = self.pipeline_directories_display();
 
-        if !pipeline_dir.exists() {
-            println!(
-                "⚠️  Pipeline directory '{}' does not exist",
-                pipeline_dir.display()
-            );
+        if !self.pipeline_directories().iter().any(|dir| dir.exists()) {
+            println!("⚠️  Pipeline directory '{directories}' does not exist");
             return Ok(vec![]);
         }
 
-        let mut pipelines = Vec::new();
-
-        for entry in fs::read_dir(pipeline_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if extension == "yaml" || extension == "yml" {
-                        if let Some(stem) = path.file_stem() {
-                            if let Some(name) = stem.to_str() {
-                                pipelines.push(name.to_string());
-                            }
-                        }
-                    }
-                }
-            } else if path.is_dir() {
-                // Check for pipeline.yaml in subdirectories
-                let pipeline_file = path.join("pipeline.yaml");
-                let pipeline_file_yml = path.join("pipeline.yml");
-
-                if pipeline_file.exists() || pipeline_file_yml.exists() {
-                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        pipelines.push(name.to_string());
-                    }
-                }
-            }
-        }
+        let pipelines = self.pipeline_names()?;
 
         if pipelines.is_empty() {
-            println!("📂 No pipelines found in {}", pipeline_dir.display());
+            println!("📂 No pipelines found in {directories}");
         } else {
-            println!("📂 Available pipelines in {}:", pipeline_dir.display());
+            println!("📂 Available pipelines in {directories}:");
             for pipeline in &pipelines {
                 println!("  • {pipeline}");
             }
@@ -215,74 +600,40 @@ impl ProjectConfig {
 
     /// Create a StateManagerConfig from the project configuration
     pub fn create_state_manager_config(&self) -> crate::state::manager::StateManagerConfig {
-        use crate::state::backend::{BackendConfig, SerializationFormat};
-        use crate::state::manager::StateManagerConfig;
+        self.state_manager
+            .clone()
+            .unwrap_or_default()
+            .manager_config()
+    }
 
-        let backend = match &self.state_manager {
-            Some(state_config) => match state_config.backend.as_str() {
-                "file" => {
-                    let file_config =
-                        state_config
-                            .file
-                            .as_ref()
-                            .cloned()
-                            .unwrap_or_else(|| FileStateConfig {
-                                base_path: default_state_path(),
-                                lock_timeout: default_lock_timeout(),
-                                backup_enabled: default_backup_enabled(),
-                                backup_retention: default_backup_retention(),
-                            });
-
-                    BackendConfig::File {
-                        base_path: PathBuf::from(&file_config.base_path),
-                        format: SerializationFormat::Json,
-                        atomic_writes: true,
-                        lock_timeout_ms: parse_duration(&file_config.lock_timeout).unwrap_or(30000),
-                    }
-                }
-                "memory" => BackendConfig::Memory { persistent: false },
-                _ => {
-                    eprintln!(
-                        "⚠️  Unknown backend type '{}', falling back to file",
-                        state_config.backend
-                    );
-                    BackendConfig::File {
-                        base_path: PathBuf::from(".oxiflow/state"),
-                        format: SerializationFormat::Json,
-                        atomic_writes: true,
-                        lock_timeout_ms: 30000,
-                    }
-                }
-            },
-            None => {
-                // Default to file backend
-                BackendConfig::File {
-                    base_path: PathBuf::from(".oxiflow/state"),
-                    format: SerializationFormat::Json,
-                    atomic_writes: true,
-                    lock_timeout_ms: 30000,
-                }
-            }
+    /// The project's state settings with a pipeline's `state:` block laid over them
+    ///
+    /// Overrides merge like Oxi defaults with step config: keys the pipeline
+    /// sets win, and nested mappings such as `file:` merge key by key. The
+    /// result is validated, so an unknown backend is an error.
+    pub fn state_config_for(&self, overrides: Option<&serde_yaml::Value>) -> Result<StateConfig> {
+        let project = self.state_manager.clone().unwrap_or_default();
+        let Some(overrides) = overrides else {
+            return Ok(project);
         };
+        let merged = crate::config::merge_yaml_values(&serde_yaml::to_value(&project)?, overrides);
+        let state: StateConfig =
+            serde_yaml::from_value(merged).context("Invalid pipeline 'state' block")?;
+        state.validate()?;
+        Ok(state)
+    }
 
-        StateManagerConfig {
-            backend,
-            default_lock_timeout_ms: 30000,
-            worker_id: format!("worker_{}", std::process::id()),
-            heartbeat_interval_ms: self
-                .state_manager
-                .as_ref()
-                .and_then(|s| parse_duration(&s.heartbeat_interval))
-                .unwrap_or(10000),
-            max_retries: 3,
-            cleanup_interval_hours: 24,
-            max_state_age_hours: 168,
-        }
+    /// Like `create_state_manager_config`, with a pipeline's `state:` overrides applied
+    pub fn create_pipeline_state_manager_config(
+        &self,
+        overrides: Option<&serde_yaml::Value>,
+    ) -> Result<crate::state::manager::StateManagerConfig> {
+        Ok(self.state_config_for(overrides)?.manager_config())
     }
 }
 
 /// Parse duration string (e.g., "30s", "5m", "1h") to milliseconds
-fn parse_duration(duration_str: &str) -> Option<u64> {
+pub(crate) fn parse_duration(duration_str: &str) -> Option<u64> {
     let duration_str = duration_str.trim();
     if duration_str.is_empty() {
         return None;
@@ -317,8 +668,110 @@ fn parse_duration(duration_str: &str) -> Option<u64> {
     Some(milliseconds)
 }
 
+/// Starter layout generated by `init --template`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectTemplate {
+    /// Default JSON→CSV pipeline with a three-record input file
+    Minimal,
+    /// `hello` pipeline over a dozen sample records, with a state directory
+    Example,
+    /// `etl` pipeline that flattens nested orders before writing CSV
+    Etl,
+}
+
+impl ProjectTemplate {
+    pub const ALL: [ProjectTemplate; 3] = [
+        ProjectTemplate::Minimal,
+        ProjectTemplate::Example,
+        ProjectTemplate::Etl,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProjectTemplate::Minimal => "minimal",
+            ProjectTemplate::Example => "example",
+            ProjectTemplate::Etl => "etl",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ProjectTemplate::Minimal => "Bare project with a default JSON→CSV pipeline",
+            ProjectTemplate::Example => {
+                "Runnable hello pipeline (read_file → parse_json → format_csv → write_file) with sample data"
+            }
+            ProjectTemplate::Etl => "Flattens nested order records into a CSV report",
+        }
+    }
+
+    /// Pipeline the generated project is ready to run
+    pub fn pipeline_name(&self) -> &'static str {
+        match self {
+            ProjectTemplate::Minimal => "pipeline",
+            ProjectTemplate::Example => "hello",
+            ProjectTemplate::Etl => "etl",
+        }
+    }
+
+    /// Files to write, relative to the project root, besides `oxiflow.yaml`
+    fn files(&self) -> Vec<(&'static str, String)> {
+        let state_gitignore = (".oxiflow/.gitignore", create_state_gitignore());
+        match self {
+            ProjectTemplate::Minimal => vec![
+                ("pipelines/pipeline.yaml", create_default_pipeline_yaml()),
+                ("input.json", create_sample_input_json()),
+            ],
+            ProjectTemplate::Example => vec![
+                ("pipelines/hello.yaml", create_hello_pipeline_yaml()),
+                ("data/sample.json", create_example_sample_json()),
+                state_gitignore,
+            ],
+            ProjectTemplate::Etl => vec![
+                ("pipelines/etl.yaml", create_etl_pipeline_yaml()),
+                ("data/orders.json", create_etl_orders_json()),
+                state_gitignore,
+            ],
+        }
+    }
+}
+
+impl std::str::FromStr for ProjectTemplate {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        ProjectTemplate::ALL
+            .into_iter()
+            .find(|t| t.name() == s.to_lowercase())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown project template '{}'. Use one of: {}",
+                    s,
+                    ProjectTemplate::ALL.map(|t| t.name()).join(", ")
+                )
+            })
+    }
+}
+
+/// Table of templates for `init --list-templates`
+pub fn format_project_templates() -> String {
+    let mut output = String::from("📦 Project templates:\n");
+    for template in ProjectTemplate::ALL {
+        output.push_str(&format!(
+            "  {:<10} {}\n",
+            template.name(),
+            template.description()
+        ));
+    }
+    output
+}
+
 /// Initialize a new Oxide Flow project
-pub fn init_project(name: Option<String>, directory: Option<String>) -> Result<()> {
+pub fn init_project(
+    name: Option<String>,
+    directory: Option<String>,
+    template: ProjectTemplate,
+    force: bool,
+) -> Result<()> {
     // Get project name
     let project_name = match name {
         Some(name) => name,
@@ -342,23 +795,43 @@ pub fn init_project(name: Option<String>, directory: Option<String>) -> Result<(
     };
 
     // Create project structure
-    create_project_structure(&target_dir, &project_name)?;
+    let created = scaffold_project(&target_dir, &project_name, template, force)?;
 
     println!(
-        "✅ Initialized Oxide Flow project '{}' in {}",
+        "✅ Initialized Oxide Flow project '{}' in {} (template: {})",
         project_name,
-        target_dir.display()
+        target_dir.display(),
+        template.name()
     );
-    println!("📁 Created directories: output/, oxis/, pipelines/");
-    println!("📄 Created files: oxiflow.yaml, pipelines/pipeline.yaml");
+    println!("📄 Created files: {}", created.join(", "));
     println!("\nNext steps:");
-    println!("  cd {}", target_dir.display());
-    println!("  oxiflow run  # Run the default JSON→CSV pipeline");
+    println!(
+        "  cd {} && oxide_flow run {}",
+        target_dir.display(),
+        template.pipeline_name()
+    );
 
     Ok(())
 }
 
-fn create_project_structure(target_dir: &Path, project_name: &str) -> Result<()> {
+/// Write a project from `template` into `target_dir`
+///
+/// Refuses a non-empty directory unless `force` is set, in which case
+/// generated files overwrite existing ones. Returns the created file paths,
+/// relative to `target_dir`.
+pub fn scaffold_project(
+    target_dir: &Path,
+    project_name: &str,
+    template: ProjectTemplate,
+    force: bool,
+) -> Result<Vec<String>> {
+    if !force && target_dir.is_dir() && fs::read_dir(target_dir)?.next().is_some() {
+        anyhow::bail!(
+            "Directory {} is not empty. Use --force to initialize anyway",
+            target_dir.display()
+        );
+    }
+
     // Create main project directory
     fs::create_dir_all(target_dir).with_context(|| {
         format!(
@@ -375,37 +848,22 @@ fn create_project_structure(target_dir: &Path, project_name: &str) -> Result<()>
             .with_context(|| format!("Failed to create directory: {}", dir_path.display()))?;
     }
 
-    // Create oxiflow.yaml
-    let oxiflow_yaml = create_oxiflow_yaml(project_name);
-    let oxiflow_path = target_dir.join("oxiflow.yaml");
-    fs::write(&oxiflow_path, oxiflow_yaml).with_context(|| {
-        format!(
-            "Failed to create oxiflow.yaml at {}",
-            oxiflow_path.display()
-        )
-    })?;
-
-    // Create default pipeline.yaml
-    let pipeline_yaml = create_default_pipeline_yaml();
-    let pipeline_path = target_dir.join("pipelines").join("pipeline.yaml");
-    fs::write(&pipeline_path, pipeline_yaml).with_context(|| {
-        format!(
-            "Failed to create pipeline.yaml at {}",
-            pipeline_path.display()
-        )
-    })?;
+    let mut files = vec![("oxiflow.yaml", create_oxiflow_yaml(project_name))];
+    files.extend(template.files());
 
-    // Create sample input file
-    let sample_json = create_sample_input_json();
-    let sample_path = target_dir.join("input.json");
-    fs::write(&sample_path, sample_json).with_context(|| {
-        format!(
-            "Failed to create sample input.json at {}",
-            sample_path.display()
-        )
-    })?;
+    let mut created = Vec::new();
+    for (relative, content) in files {
+        let path = target_dir.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        created.push(relative.to_string());
+    }
 
-    Ok(())
+    Ok(created)
 }
 
 fn create_oxiflow_yaml(project_name: &str) -> String {
@@ -511,3 +969,268 @@ fn create_sample_input_json() -> String {
 ]"#
     .to_string()
 }
+
+fn create_state_gitignore() -> String {
+    "# Pipeline state and run history are local to each machine\nstate/\nruns/\n".to_string()
+}
+
+fn create_hello_pipeline_yaml() -> String {
+    r#"# Hello pipeline
+# Reads the sample customers, converts them to CSV and writes output/hello.csv
+
+pipeline:
+  - name: read_file
+    id: reader
+    config:
+      path: "data/sample.json"
+
+  - name: parse_json
+    id: parser
+
+  - name: format_csv
+    id: formatter
+    config:
+      include_headers: true
+      delimiter: ","
+
+  - name: write_file
+    id: writer
+    config:
+      path: "output/hello.csv"
+
+metadata:
+  name: "hello"
+  description: "Converts the sample customer list to CSV"
+  version: "1.0.0"
+  author: "Oxide Flow"
+"#
+    .to_string()
+}
+
+fn create_example_sample_json() -> String {
+    let cities = [
+        "New York", "Chicago", "Austin", "Denver", "Seattle", "Boston",
+    ];
+    let names = [
+        "Ada Lovelace",
+        "Alan Turing",
+        "Grace Hopper",
+        "Linus Torvalds",
+        "Margaret Hamilton",
+        "Dennis Ritchie",
+        "Barbara Liskov",
+        "Ken Thompson",
+        "Frances Allen",
+        "Edsger Dijkstra",
+        "Radia Perlman",
+        "John Backus",
+    ];
+    let records: Vec<serde_json::Value> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let handle = name.split(' ').next().unwrap_or(name).to_lowercase();
+            serde_json::json!({
+                "id": i + 1,
+                "name": name,
+                "email": format!("{handle}@example.com"),
+                "age": 25 + (i * 7) % 30,
+                "city": cities[i % cities.len()],
+                "active": i % 4 != 3,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&records).unwrap_or_default()
+}
+
+fn create_etl_pipeline_yaml() -> String {
+    r#"# ETL pipeline
+# Flattens nested order records (customer, shipping) into a CSV report
+
+pipeline:
+  - name: read_file
+    id: extract
+    config:
+      path: "data/orders.json"
+
+  - name: parse_json
+    id: parse
+
+  - name: flatten
+    id: transform
+    config:
+      separator: "_"
+      array_mode: explode
+
+  - name: format_csv
+    id: format
+
+  - name: write_file
+    id: load
+    config:
+      path: "output/orders.csv"
+
+metadata:
+  name: "etl"
+  description: "Flattens nested orders into output/orders.csv"
+  version: "1.0.0"
+  author: "Oxide Flow"
+"#
+    .to_string()
+}
+
+fn create_etl_orders_json() -> String {
+    r#"[
+  {
+    "order_id": "A-1001",
+    "total": 42.5,
+    "customer": {"name": "Ada Lovelace", "email": "ada@example.com"},
+    "shipping": {"city": "London", "express": true},
+    "items": ["notebook", "pen"]
+  },
+  {
+    "order_id": "A-1002",
+    "total": 12.0,
+    "customer": {"name": "Alan Turing", "email": "alan@example.com"},
+    "shipping": {"city": "Manchester", "express": false},
+    "items": ["tape"]
+  },
+  {
+    "order_id": "A-1003",
+    "total": 99.99,
+    "customer": {"name": "Grace Hopper", "email": "grace@example.com"},
+    "shipping": {"city": "Arlington", "express": true},
+    "items": ["compiler manual", "coffee"]
+  }
+]
+"#
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::backend::BackendConfig;
+
+    fn project(state_manager: &str) -> ProjectConfig {
+        let mut config: ProjectConfig = serde_yaml::from_str(&create_oxiflow_yaml("demo")).unwrap();
+        config.state_manager = Some(serde_yaml::from_str(state_manager).unwrap());
+        config
+    }
+
+    #[test]
+    fn test_pipeline_state_overrides_take_precedence() {
+        let config = project(
+            "backend: file\nfile: {base_path: shared_state, lock_timeout: 45s}\nheartbeat_interval: 10s",
+        );
+        let overrides = serde_yaml::from_str(
+            "file: {base_path: dev_state}\nheartbeat_interval: 2s\nlock_timeout: 1m",
+        )
+        .unwrap();
+
+        let manager = config
+            .create_pipeline_state_manager_config(Some(&overrides))
+            .unwrap();
+        match manager.backend {
+            BackendConfig::File {
+                base_path,
+                lock_timeout_ms,
+                ..
+            } => {
+                assert_eq!(base_path, PathBuf::from("dev_state"));
+                // Keys the pipeline leaves out keep the project's value
+                assert_eq!(lock_timeout_ms, 45_000);
+            }
+            other => panic!("expected the file backend, got {other:?}"),
+        }
+        assert_eq!(manager.heartbeat_interval_ms, 2_000);
+        assert_eq!(manager.default_lock_timeout_ms, 60_000);
+        assert_eq!(manager.lock_ttl_ms, 60_000);
+
+        let memory = serde_yaml::from_str("backend: memory").unwrap();
+        let manager = config
+            .create_pipeline_state_manager_config(Some(&memory))
+            .unwrap();
+        assert_eq!(manager.backend, BackendConfig::Memory { persist_dir: None });
+        assert_eq!(manager.heartbeat_interval_ms, 10_000);
+
+        let persistent = serde_yaml::from_str(
+            "backend: memory\nmemory:\n  persistent: true\n  base_path: dev_memory",
+        )
+        .unwrap();
+        let manager = config
+            .create_pipeline_state_manager_config(Some(&persistent))
+            .unwrap();
+        assert_eq!(
+            manager.backend,
+            BackendConfig::Memory {
+                persist_dir: Some(PathBuf::from("dev_memory"))
+            }
+        );
+
+        let unchanged = config.create_pipeline_state_manager_config(None).unwrap();
+        assert_eq!(
+            unchanged.backend,
+            config.create_state_manager_config().backend
+        );
+    }
+
+    #[test]
+    fn test_stale_run_recovery_is_opt_in() {
+        let manager = project("backend: file").create_state_manager_config();
+        assert!(!manager.recover_stale_runs);
+        assert!(!manager.auto_requeue_stale);
+        assert_eq!(manager.stale_run_threshold_ms, 300_000);
+
+        let manager = project(
+            "backend: file\nrecover_stale_runs: true\nstale_run_threshold: 2m\nauto_requeue_stale: true",
+        )
+        .create_state_manager_config();
+        assert!(manager.recover_stale_runs);
+        assert!(manager.auto_requeue_stale);
+        assert_eq!(manager.stale_run_threshold_ms, 120_000);
+
+        let invalid = serde_yaml::from_str("stale_run_threshold: soon").unwrap();
+        assert!(project("backend: file")
+            .state_config_for(Some(&invalid))
+            .is_err());
+    }
+
+    #[test]
+    fn test_auto_claim_settings_round_trip() {
+        let manager = project("backend: file").create_state_manager_config();
+        assert!(!manager.auto_claim_abandoned);
+        assert_eq!(manager.auto_claim_interval_ms, 60_000);
+
+        let config = project("backend: file\nauto_claim_abandoned: true\nauto_claim_interval: 30s");
+        let manager = config.create_state_manager_config();
+        assert!(manager.auto_claim_abandoned);
+        assert_eq!(manager.auto_claim_interval_ms, 30_000);
+
+        // Written back out, the settings read the same
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        let reloaded: ProjectConfig = serde_yaml::from_str(&yaml).unwrap();
+        let state = reloaded.state_manager.unwrap();
+        assert!(state.auto_claim_abandoned);
+        assert_eq!(state.auto_claim_interval.as_deref(), Some("30s"));
+
+        let invalid = serde_yaml::from_str("auto_claim_interval: often").unwrap();
+        assert!(config.state_config_for(Some(&invalid)).is_err());
+    }
+
+    #[test]
+    fn test_pipeline_state_rejects_unknown_backends() {
+        let config = project("backend: file");
+
+        let redis = serde_yaml::from_str("backend: redis").unwrap();
+        let err = config.state_config_for(Some(&redis)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown state backend 'redis'; supported backends are: file, memory"
+        );
+
+        let bad_interval = serde_yaml::from_str("heartbeat_interval: soon").unwrap();
+        let err = config.state_config_for(Some(&bad_interval)).unwrap_err();
+        assert!(err.to_string().contains("heartbeat_interval"), "{err}");
+    }
+}
@@ -0,0 +1,360 @@
+//! Persistent history of pipeline runs
+//!
+//! Every run of `oxiflow run` writes a small JSON report to `.oxiflow/runs`,
+//! recording the content hash of the pipeline that produced it. This is what
+//! `pipeline info --history` lists and what ties a run to the exact pipeline
+//! definition it executed.
+
+use crate::pipeline::{Pipeline, PipelineResult};
+use crate::state::{BackendConfig, PipelineState, StepStatus};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Directory, relative to the project root, holding one report per run
+pub const DEFAULT_RUNS_DIR: &str = ".oxiflow/runs";
+
+/// Summary of a single pipeline run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReport {
+    pub run_id: String,
+    pub pipeline_name: String,
+    /// `Pipeline::content_hash` of the definition that was executed
+    pub pipeline_content_hash: String,
+    pub success: bool,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub steps_executed: u32,
+    pub steps_failed: u32,
+    pub steps_skipped: u32,
+    /// Backend the run's state was tracked in, so `state show` can find it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_backend: Option<BackendConfig>,
+}
+
+impl RunReport {
+    /// Build a report for a finished run
+    ///
+    /// Runs without state tracking have no run id, so a fresh one is assigned.
+    pub fn from_result(pipeline: &Pipeline, result: &PipelineResult) -> Self {
+        let duration = chrono::Duration::milliseconds(result.total_duration_ms as i64);
+        Self {
+            run_id: result
+                .run_id
+                .clone()
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            pipeline_name: pipeline.name(),
+            pipeline_content_hash: pipeline.content_hash(),
+            success: result.success,
+            started_at: Utc::now() - duration,
+            duration_ms: result.total_duration_ms,
+            steps_executed: result.steps_executed,
+            steps_failed: result.steps_failed,
+            steps_skipped: result.steps_skipped,
+            state_backend: None,
+        }
+    }
+
+    /// Human-readable run status
+    pub fn status(&self) -> &'static str {
+        if self.success {
+            "success"
+        } else {
+            "failed"
+        }
+    }
+}
+
+/// Run reports stored as JSON files in a directory
+pub struct RunHistory {
+    dir: PathBuf,
+}
+
+impl RunHistory {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Write a report, returning the path of the new file
+    pub fn record(&self, report: &RunReport) -> Result<PathBuf> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.dir.join(format!(
+            "{}_{}.json",
+            report.started_at.format("%Y%m%dT%H%M%S%.3f"),
+            report.run_id
+        ));
+        fs::write(&path, serde_json::to_string_pretty(report)?)
+            .with_context(|| format!("Failed to write run report {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Reports for one pipeline, newest first
+    ///
+    /// A missing directory means no runs yet; unreadable reports are skipped.
+    pub fn list(&self, pipeline_name: &str) -> Result<Vec<RunReport>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut reports = Vec::new();
+        for entry in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read {}", self.dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Ok(report) = serde_json::from_str::<RunReport>(&content) {
+                if report.pipeline_name == pipeline_name {
+                    reports.push(report);
+                }
+            }
+        }
+
+        reports.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+        Ok(reports)
+    }
+
+    /// Most recent report for a pipeline
+    pub fn latest(&self, pipeline_name: &str) -> Result<Option<RunReport>> {
+        Ok(self.list(pipeline_name)?.into_iter().next())
+    }
+}
+
+/// Table of runs for `pipeline info --history`
+pub fn format_history(reports: &[RunReport]) -> String {
+    if reports.is_empty() {
+        return "   No runs recorded\n".to_string();
+    }
+
+    let mut output = format!(
+        "   {:<24} {:<9} {:>10}  {:<12} {}\n",
+        "STARTED", "STATUS", "DURATION", "HASH", "RUN ID"
+    );
+    for report in reports {
+        output.push_str(&format!(
+            "   {:<24} {:<9} {:>8}ms  {:<12} {}\n",
+            report.started_at.format("%Y-%m-%d %H:%M:%S"),
+            report.status(),
+            report.duration_ms,
+            &report.pipeline_content_hash[..report.pipeline_content_hash.len().min(12)],
+            report.run_id
+        ));
+    }
+    output
+}
+
+/// Timing and throughput of one step in a [`ThroughputSummary`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepThroughput {
+    pub step_id: String,
+    pub processing_time_ms: u64,
+    pub records_processed: u64,
+    /// `None` when the step finished within a millisecond
+    pub records_per_sec: Option<f64>,
+}
+
+impl StepThroughput {
+    fn new(step_id: &str, processing_time_ms: u64, records_processed: u64) -> Self {
+        Self {
+            step_id: step_id.to_string(),
+            processing_time_ms,
+            records_processed,
+            records_per_sec: (processing_time_ms > 0)
+                .then(|| records_processed as f64 * 1000.0 / processing_time_ms as f64),
+        }
+    }
+}
+
+/// Per-step timing printed after `oxiflow run`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThroughputSummary {
+    pub pipeline_name: String,
+    pub total_duration_ms: u64,
+    /// Executed steps in pipeline order
+    pub steps: Vec<StepThroughput>,
+}
+
+impl ThroughputSummary {
+    /// Summarize a tracked run from its step states
+    ///
+    /// Steps that never started, such as those skipped after a failure, are
+    /// left out.
+    pub fn from_state(pipeline: &Pipeline, state: &PipelineState) -> Self {
+        let steps = pipeline
+            .pipeline
+            .iter()
+            .filter_map(|step| state.step_states.get(step.get_id()))
+            .filter(|step| step.status != StepStatus::Pending)
+            .map(|step| {
+                StepThroughput::new(
+                    &step.step_id,
+                    step.processing_time_ms,
+                    step.records_processed,
+                )
+            })
+            .collect();
+
+        Self {
+            pipeline_name: pipeline.name(),
+            total_duration_ms: state.duration_ms(),
+            steps,
+        }
+    }
+
+    /// Summarize an untracked run from its step results
+    ///
+    /// Without step states, a step's record count is the size of its output.
+    pub fn from_result(pipeline: &Pipeline, result: &PipelineResult) -> Self {
+        let steps = result
+            .step_results
+            .iter()
+            .map(|step| {
+                let records = step
+                    .data
+                    .as_ref()
+                    .map_or(0, |data| data.data().batch_size() as u64);
+                StepThroughput::new(&step.step_id, step.duration_ms, records)
+            })
+            .collect();
+
+        Self {
+            pipeline_name: pipeline.name(),
+            total_duration_ms: result.total_duration_ms,
+            steps,
+        }
+    }
+}
+
+/// Table of step timings for the summary printed after `oxiflow run`
+pub fn format_throughput(summary: &ThroughputSummary) -> String {
+    let mut output = format!(
+        "   {:<24} {:>10} {:>10} {:>12}\n",
+        "STEP", "TIME", "RECORDS", "RECORDS/SEC"
+    );
+    for step in &summary.steps {
+        output.push_str(&format!(
+            "   {:<24} {:>8}ms {:>10} {:>12}\n",
+            step.step_id,
+            step.processing_time_ms,
+            step.records_processed,
+            step.records_per_sec
+                .map_or_else(|| "-".to_string(), |rate| format!("{rate:.1}"))
+        ));
+    }
+    output.push_str(&format!(
+        "   {:<24} {:>8}ms\n",
+        "TOTAL", summary.total_duration_ms
+    ));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(run_id: &str, pipeline: &str, minutes_ago: i64, success: bool) -> RunReport {
+        RunReport {
+            run_id: run_id.to_string(),
+            pipeline_name: pipeline.to_string(),
+            pipeline_content_hash: format!("hash-{run_id}"),
+            success,
+            started_at: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            duration_ms: 42,
+            steps_executed: 2,
+            steps_failed: u32::from(!success),
+            steps_skipped: 0,
+            state_backend: None,
+        }
+    }
+
+    #[test]
+    fn test_history_lists_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = RunHistory::new(dir.path());
+
+        history.record(&report("old", "etl", 30, true)).unwrap();
+        history.record(&report("new", "etl", 1, false)).unwrap();
+        history.record(&report("mid", "etl", 10, true)).unwrap();
+        history
+            .record(&report("other", "reports", 5, true))
+            .unwrap();
+
+        let runs = history.list("etl").unwrap();
+        let ids: Vec<&str> = runs.iter().map(|r| r.run_id.as_str()).collect();
+        assert_eq!(ids, vec!["new", "mid", "old"]);
+        assert_eq!(history.latest("etl").unwrap().unwrap().status(), "failed");
+
+        let table = format_history(&runs);
+        assert!(table.find("new").unwrap() < table.find("old").unwrap());
+        assert!(table.contains("hash-mid"));
+    }
+
+    #[test]
+    fn test_missing_history_directory_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = RunHistory::new(dir.path().join("runs"));
+        assert!(history.list("etl").unwrap().is_empty());
+        assert!(format_history(&[]).contains("No runs recorded"));
+    }
+
+    #[tokio::test]
+    async fn test_throughput_summary_lists_executed_steps() {
+        use crate::config_resolver::ConfigResolver;
+        use crate::state::StateManager;
+        use crate::types::OxiData;
+
+        let pipeline: Pipeline = serde_yaml::from_str(
+            r#"
+metadata:
+  name: paced
+pipeline:
+  - name: throttle
+    id: pace_in
+    config:
+      requests_per_second: 200
+  - name: throttle
+    id: pace_out
+    config:
+      requests_per_second: 200
+"#,
+        )
+        .unwrap();
+        let records = OxiData::from_json(serde_json::json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+        let state_manager = StateManager::new_memory();
+
+        let result = pipeline
+            .execute_with_state_tracking(
+                records,
+                &ConfigResolver::new(),
+                Some(state_manager.clone()),
+            )
+            .await;
+        assert!(result.success);
+
+        let state = state_manager.load_state("paced").await.unwrap();
+        let summary = ThroughputSummary::from_state(&pipeline, &state);
+        let ids: Vec<&str> = summary.steps.iter().map(|s| s.step_id.as_str()).collect();
+        assert_eq!(ids, vec!["pace_in", "pace_out"]);
+        for step in &summary.steps {
+            assert!(step.processing_time_ms > 0, "{step:?}");
+            assert_eq!(step.records_processed, 3);
+            assert!(step.records_per_sec.unwrap() > 0.0);
+        }
+        assert!(summary.total_duration_ms > 0);
+
+        let untracked = ThroughputSummary::from_result(&pipeline, &result);
+        assert_eq!(untracked.steps.len(), 2);
+        assert_eq!(untracked.steps[1].records_processed, 3);
+
+        let table = format_throughput(&summary);
+        assert!(table.contains("pace_in") && table.contains("pace_out"));
+        assert!(table.contains("TOTAL"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::types::OxiConfig;
+use crate::types::{FieldSchema, FieldType, OxiConfig};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
@@ -386,6 +386,142 @@ impl Default for SchemaRegistry {
     }
 }
 
+/// One way an upstream schema falls short of what a step requires
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CompatibilityIssue {
+    /// The step requires a field that upstream does not provide
+    MissingField { field: String, required: FieldType },
+    /// Upstream provides the field with a type the step cannot accept
+    TypeMismatch {
+        field: String,
+        required: FieldType,
+        provided: FieldType,
+    },
+    /// Upstream may send null where the step needs a value
+    NullableIntoNonNullable { field: String, required: FieldType },
+}
+
+impl CompatibilityIssue {
+    /// Whether the data is known not to fit, rather than possibly not fitting
+    pub fn is_error(&self) -> bool {
+        !matches!(self, CompatibilityIssue::NullableIntoNonNullable { .. })
+    }
+
+    /// Describe the issue as seen from the consuming step
+    pub fn describe(&self, step_id: &str) -> String {
+        fn label(field: &str, field_type: &FieldType) -> String {
+            match field_type {
+                FieldType::Unknown => field.to_string(),
+                _ => format!("{field}: {field_type}"),
+            }
+        }
+
+        match self {
+            CompatibilityIssue::MissingField { field, required } => format!(
+                "step {step_id} requires field '{}' but upstream does not provide it",
+                label(field, required)
+            ),
+            CompatibilityIssue::TypeMismatch {
+                field,
+                required,
+                provided,
+            } => format!(
+                "step {step_id} requires field '{field}: {required}' but upstream provides '{field}: {provided}'"
+            ),
+            CompatibilityIssue::NullableIntoNonNullable { field, required } => format!(
+                "step {step_id} requires field '{}' to be non-null but upstream may provide null",
+                label(field, required)
+            ),
+        }
+    }
+}
+
+/// Result of checking a producer's output schema against a consumer's requirements
+///
+/// Required fields must be present upstream with an acceptable type. `Unknown`
+/// and `Mixed` requirements accept any type, `Integer` is accepted where
+/// `Float` is required and `DateTime` where `String` is (both are lossless
+/// widenings). Nested objects and array items are checked field by field,
+/// with paths such as `customer.address.zip` and `lines[].sku`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SchemaCompatibility {
+    pub issues: Vec<CompatibilityIssue>,
+}
+
+impl SchemaCompatibility {
+    /// Check `provided` (upstream output) against `required` (consumer input)
+    pub fn check(provided: &crate::types::OxiSchema, required: &crate::types::OxiSchema) -> Self {
+        let mut compatibility = Self::default();
+        compatibility.check_fields("", &provided.fields, &required.fields);
+        compatibility
+    }
+
+    /// True when no issue is an error; nullability hazards are allowed
+    pub fn is_compatible(&self) -> bool {
+        !self.issues.iter().any(CompatibilityIssue::is_error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &CompatibilityIssue> {
+        self.issues.iter().filter(|issue| issue.is_error())
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &CompatibilityIssue> {
+        self.issues.iter().filter(|issue| !issue.is_error())
+    }
+
+    fn check_fields(
+        &mut self,
+        prefix: &str,
+        provided: &HashMap<String, FieldSchema>,
+        required: &HashMap<String, FieldSchema>,
+    ) {
+        let mut names: Vec<&String> = required.keys().collect();
+        names.sort();
+
+        for name in names {
+            let needed = &required[name];
+            let path = format!("{prefix}{name}");
+            match provided.get(name) {
+                None => self.issues.push(CompatibilityIssue::MissingField {
+                    field: path,
+                    required: needed.field_type.clone(),
+                }),
+                Some(field) => {
+                    if field.nullable && !needed.nullable {
+                        self.issues
+                            .push(CompatibilityIssue::NullableIntoNonNullable {
+                                field: path.clone(),
+                                required: needed.field_type.clone(),
+                            });
+                    }
+                    self.check_type(path, &field.field_type, &needed.field_type);
+                }
+            }
+        }
+    }
+
+    fn check_type(&mut self, path: String, provided: &FieldType, required: &FieldType) {
+        match (provided, required) {
+            // Nothing is known about the upstream value, so nothing can be ruled out
+            (FieldType::Unknown, _) | (_, FieldType::Unknown | FieldType::Mixed) => {}
+            (FieldType::Integer, FieldType::Float) | (FieldType::DateTime, FieldType::String) => {}
+            (FieldType::Object(provided), FieldType::Object(required)) => {
+                self.check_fields(&format!("{path}."), provided, required)
+            }
+            (FieldType::Array(provided), FieldType::Array(required)) => {
+                self.check_type(format!("{path}[]"), provided, required)
+            }
+            (provided, required) if provided == required => {}
+            (provided, required) => self.issues.push(CompatibilityIssue::TypeMismatch {
+                field: path,
+                required: required.clone(),
+                provided: provided.clone(),
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -436,4 +572,106 @@ mod tests {
         let result = registry.validate("read_file", &config);
         assert!(result.is_err());
     }
+
+    fn data_schema(fields: &[(&str, FieldType, bool)]) -> crate::types::OxiSchema {
+        let mut schema = crate::types::OxiSchema::empty();
+        for (name, field_type, nullable) in fields {
+            let mut field = FieldSchema::new(field_type.clone());
+            field.nullable = *nullable;
+            schema.add_field(name.to_string(), field);
+        }
+        schema
+    }
+
+    #[test]
+    fn test_compatible_schemas() {
+        let provided = data_schema(&[
+            ("id", FieldType::Integer, false),
+            ("name", FieldType::String, false),
+            ("extra", FieldType::Boolean, true),
+        ]);
+        let required = data_schema(&[
+            ("id", FieldType::Integer, false),
+            ("name", FieldType::Unknown, true),
+        ]);
+
+        let compatibility = SchemaCompatibility::check(&provided, &required);
+        assert!(compatibility.is_compatible());
+        assert!(compatibility.issues.is_empty());
+    }
+
+    #[test]
+    fn test_incompatible_schemas() {
+        let provided = data_schema(&[
+            ("amount", FieldType::String, false),
+            ("note", FieldType::String, true),
+        ]);
+        let required = data_schema(&[
+            ("amount", FieldType::Float, false),
+            ("note", FieldType::String, false),
+            ("sku", FieldType::String, false),
+        ]);
+
+        let compatibility = SchemaCompatibility::check(&provided, &required);
+        assert!(!compatibility.is_compatible());
+        let errors: Vec<String> = compatibility
+            .errors()
+            .map(|issue| issue.describe("csv_writer"))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                "step csv_writer requires field 'amount: float' but upstream provides 'amount: string'",
+                "step csv_writer requires field 'sku: string' but upstream does not provide it",
+            ]
+        );
+        let warnings: Vec<String> = compatibility
+            .warnings()
+            .map(|issue| issue.describe("csv_writer"))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec!["step csv_writer requires field 'note: string' to be non-null but upstream may provide null"]
+        );
+    }
+
+    #[test]
+    fn test_widening_and_nested_types() {
+        let nested = |field_type: FieldType| {
+            let mut fields = HashMap::new();
+            fields.insert("total".to_string(), FieldSchema::new(field_type));
+            FieldType::Object(fields)
+        };
+        let provided = data_schema(&[
+            ("amount", FieldType::Integer, false),
+            ("seen", FieldType::DateTime, false),
+            ("order", nested(FieldType::Integer), false),
+            (
+                "tags",
+                FieldType::Array(Box::new(FieldType::Integer)),
+                false,
+            ),
+        ]);
+        let required = data_schema(&[
+            ("amount", FieldType::Float, false),
+            ("seen", FieldType::String, false),
+            ("order", nested(FieldType::Float), false),
+            ("tags", FieldType::Array(Box::new(FieldType::Float)), false),
+        ]);
+        assert!(SchemaCompatibility::check(&provided, &required)
+            .issues
+            .is_empty());
+
+        // Widening only goes one way
+        let compatibility = SchemaCompatibility::check(&required, &provided);
+        let fields: Vec<&str> = compatibility
+            .issues
+            .iter()
+            .map(|issue| match issue {
+                CompatibilityIssue::TypeMismatch { field, .. } => field.as_str(),
+                other => panic!("unexpected issue {other:?}"),
+            })
+            .collect();
+        assert_eq!(fields, vec!["amount", "order.total", "seen", "tags[]"]);
+    }
 }
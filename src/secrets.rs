@@ -0,0 +1,665 @@
+//! Secret references in Oxi configuration
+//!
+//! A config value tagged `!secret` holds a *reference* to a secret rather
+//! than the secret itself. The reference is resolved on each access through
+//! `OxiConfig::get_secret`, so plaintext never lives in the config struct.
+//!
+//! ```yaml
+//! config:
+//!   api_token: !secret API_TOKEN                       # environment variable
+//!   db_password: !secret {provider: file, path: /run/secrets/db_password}
+//!   signing_key: !secret {provider: vault, addr: "https://vault:8200", path: secret/data/app, key: signing_key}
+//! ```
+//!
+//! A value tagged `!encrypted` is the exception: it holds the secret itself,
+//! encrypted with AES-256-GCM by `OxiConfig::set_secret`, and is decrypted
+//! with the key in `OXIDE_FLOW_ENCRYPTION_KEY`.
+//!
+//! Strings may also embed `${secret:NAME}`, which is substituted while the
+//! config is resolved using the `SecretResolver` installed on the
+//! `ConfigResolver` (environment variables unless the project sets
+//! `settings.secrets_dir`).
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use vaultrs::client::{VaultClient, VaultClientSettingsBuilder};
+
+/// YAML tag marking a config value as a secret reference
+pub const SECRET_TAG: &str = "!secret";
+
+/// Placeholder shown instead of secrets in debug output and logs
+pub const SECRET_MASK: &str = "***";
+
+/// YAML tag marking a config value as a secret encrypted at rest
+pub const ENCRYPTED_TAG: &str = "!encrypted";
+
+/// Environment variable holding the base64-encoded 32-byte key for `!encrypted` values
+pub const ENCRYPTION_KEY_ENV: &str = "OXIDE_FLOW_ENCRYPTION_KEY";
+
+/// AES-GCM nonce size in bytes (96 bits)
+const NONCE_LEN: usize = 12;
+
+/// Encrypt a secret with AES-256-GCM under a fresh random nonce
+///
+/// Returns base64 of the nonce followed by the ciphertext and tag.
+pub fn encrypt_secret(plaintext: &str, key: &[u8; 32]) -> Result<String> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| anyhow!("Failed to encrypt secret"))?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(base64::engine::general_purpose::STANDARD.encode(sealed))
+}
+
+/// Decrypt a value produced by [`encrypt_secret`]
+///
+/// Fails without detail on a wrong key or tampered ciphertext, as AES-GCM
+/// cannot tell the two apart.
+pub fn decrypt_secret(encoded: &str, key: &[u8; 32]) -> Result<String> {
+    let sealed = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .context("Encrypted secret is not valid base64")?;
+    if sealed.len() <= NONCE_LEN {
+        bail!("Encrypted secret is too short");
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("Failed to decrypt secret: wrong key or corrupted value"))?;
+    String::from_utf8(plaintext).context("Decrypted secret is not valid UTF-8")
+}
+
+/// Read the encryption key from `OXIDE_FLOW_ENCRYPTION_KEY`
+///
+/// The variable must hold 32 bytes encoded as base64, e.g. the output of
+/// `openssl rand -base64 32`.
+pub fn encryption_key_from_env() -> Result<[u8; 32]> {
+    let encoded = std::env::var(ENCRYPTION_KEY_ENV)
+        .with_context(|| format!("{ENCRYPTION_KEY_ENV} must be set to use !encrypted values"))?;
+    parse_encryption_key(&encoded)
+}
+
+/// Decode a base64 encryption key as held in `OXIDE_FLOW_ENCRYPTION_KEY`
+pub fn parse_encryption_key(encoded: &str) -> Result<[u8; 32]> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .with_context(|| format!("{ENCRYPTION_KEY_ENV} is not valid base64"))?;
+    bytes.try_into().map_err(|bytes: Vec<u8>| {
+        anyhow!(
+            "{ENCRYPTION_KEY_ENV} must decode to 32 bytes, got {}",
+            bytes.len()
+        )
+    })
+}
+
+/// Where a secret is stored
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum SecretProvider {
+    /// A process environment variable named by the reference key
+    Env,
+    /// A file whose contents (minus the trailing newline) are the secret
+    File { path: PathBuf },
+    /// A HashiCorp Vault KV v2 secret at `<mount>/<secret>`; the reference
+    /// key selects the field. Authenticates with the `VAULT_TOKEN` environment variable.
+    HashicorpVault { addr: String, path: String },
+}
+
+/// A reference to a secret held by a provider
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SecretRef {
+    pub provider: SecretProvider,
+    pub key: String,
+}
+
+impl SecretRef {
+    /// Parse a `!secret`-tagged YAML value, returning `None` for untagged values
+    pub fn from_yaml(value: &serde_yaml::Value) -> Option<Result<Self>> {
+        match value {
+            serde_yaml::Value::Tagged(tagged) if tagged.tag == SECRET_TAG => Some(
+                serde_yaml::from_value(tagged.value.clone())
+                    .map_err(|e| anyhow!("Invalid !secret reference: {e}")),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Fetch the secret's current value from its provider
+    pub fn resolve(&self) -> Result<String> {
+        match &self.provider {
+            SecretProvider::Env => std::env::var(&self.key)
+                .with_context(|| format!("Secret environment variable '{}' is not set", self.key)),
+            SecretProvider::File { path } => {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read secret file {}", path.display()))?;
+                Ok(content
+                    .strip_suffix('\n')
+                    .map(|s| s.strip_suffix('\r').unwrap_or(s))
+                    .unwrap_or(&content)
+                    .to_string())
+            }
+            SecretProvider::HashicorpVault { addr, path } => {
+                resolve_vault_secret(addr, path, &self.key)
+            }
+        }
+    }
+}
+
+/// Accepts either a bare env var name or a `{provider, ...}` mapping
+impl<'de> Deserialize<'de> for SecretRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            EnvName(String),
+            Spec {
+                provider: String,
+                key: Option<String>,
+                path: Option<PathBuf>,
+                addr: Option<String>,
+            },
+        }
+
+        let missing = |field: &str, provider: &str| {
+            serde::de::Error::custom(format!("!secret provider '{provider}' requires '{field}'"))
+        };
+
+        match Raw::deserialize(deserializer)? {
+            Raw::EnvName(key) => Ok(SecretRef {
+                provider: SecretProvider::Env,
+                key,
+            }),
+            Raw::Spec {
+                provider,
+                key,
+                path,
+                addr,
+            } => match provider.as_str() {
+                "env" => Ok(SecretRef {
+                    provider: SecretProvider::Env,
+                    key: key.ok_or_else(|| missing("key", "env"))?,
+                }),
+                "file" => {
+                    let path = path.ok_or_else(|| missing("path", "file"))?;
+                    Ok(SecretRef {
+                        key: key.unwrap_or_else(|| path.display().to_string()),
+                        provider: SecretProvider::File { path },
+                    })
+                }
+                "vault" | "hashicorp_vault" => Ok(SecretRef {
+                    key: key.ok_or_else(|| missing("key", "vault"))?,
+                    provider: SecretProvider::HashicorpVault {
+                        addr: addr.ok_or_else(|| missing("addr", "vault"))?,
+                        path: path
+                            .ok_or_else(|| missing("path", "vault"))?
+                            .display()
+                            .to_string(),
+                    },
+                }),
+                other => Err(serde::de::Error::custom(format!(
+                    "Unknown !secret provider '{other}' (expected env, file or vault)"
+                ))),
+            },
+        }
+    }
+}
+
+/// Looks up secrets by name for `${secret:NAME}` references
+pub trait SecretResolver: Send + Sync {
+    /// Short description used in error messages, e.g. "environment"
+    fn source(&self) -> String;
+
+    /// Fetch a secret, returning `None` if this resolver does not hold it
+    fn lookup(&self, name: &str) -> Result<Option<String>>;
+
+    /// Fetch a secret that must exist
+    fn resolve(&self, name: &str) -> Result<String> {
+        self.lookup(name)?
+            .ok_or_else(|| anyhow!("Secret '{name}' not found in {}", self.source()))
+    }
+}
+
+/// Reads secrets from process environment variables (the default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvSecretResolver;
+
+impl SecretResolver for EnvSecretResolver {
+    fn source(&self) -> String {
+        "environment".to_string()
+    }
+
+    fn lookup(&self, name: &str) -> Result<Option<String>> {
+        Ok(std::env::var(name).ok())
+    }
+}
+
+/// Reads each secret from a file named after it in a secrets directory,
+/// as with Docker and Kubernetes secret mounts
+#[derive(Debug, Clone)]
+pub struct FileSecretResolver {
+    dir: PathBuf,
+}
+
+impl FileSecretResolver {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl SecretResolver for FileSecretResolver {
+    fn source(&self) -> String {
+        format!("secrets directory {}", self.dir.display())
+    }
+
+    fn lookup(&self, name: &str) -> Result<Option<String>> {
+        // Names map straight to file names, so refuse anything that could escape the directory
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            bail!("Invalid secret name '{name}'");
+        }
+
+        let path = self.dir.join(name);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        SecretRef {
+            provider: SecretProvider::File { path },
+            key: name.to_string(),
+        }
+        .resolve()
+        .map(Some)
+    }
+}
+
+/// Tries each resolver in order, using the first that holds the secret
+pub struct ChainedSecretResolver {
+    resolvers: Vec<Box<dyn SecretResolver>>,
+}
+
+impl ChainedSecretResolver {
+    pub fn new(resolvers: Vec<Box<dyn SecretResolver>>) -> Self {
+        Self { resolvers }
+    }
+}
+
+impl SecretResolver for ChainedSecretResolver {
+    fn source(&self) -> String {
+        self.resolvers
+            .iter()
+            .map(|r| r.source())
+            .collect::<Vec<_>>()
+            .join(" or ")
+    }
+
+    fn lookup(&self, name: &str) -> Result<Option<String>> {
+        for resolver in &self.resolvers {
+            if let Some(value) = resolver.lookup(name)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The resolver used when a project sets `settings.secrets_dir`: files first, then env vars
+pub fn project_secret_resolver(secrets_dir: Option<&Path>) -> Box<dyn SecretResolver> {
+    match secrets_dir {
+        Some(dir) => Box::new(ChainedSecretResolver::new(vec![
+            Box::new(FileSecretResolver::new(dir)),
+            Box::new(EnvSecretResolver),
+        ])),
+        None => Box::new(EnvSecretResolver),
+    }
+}
+
+/// Replace every `${secret:NAME}` in `text` with the secret's value
+///
+/// Errors name the missing secret but never include resolved values.
+pub fn substitute_secrets(text: &str, resolver: &dyn SecretResolver) -> Result<String> {
+    let re = Regex::new(r"\$\{secret:([^{}]+)\}").unwrap();
+    if !re.is_match(text) {
+        return Ok(text.to_string());
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut last_end = 0;
+    for cap in re.captures_iter(text) {
+        let full_match = cap.get(0).unwrap();
+        result.push_str(&text[last_end..full_match.start()]);
+        result.push_str(&resolver.resolve(cap[1].trim())?);
+        last_end = full_match.end();
+    }
+    result.push_str(&text[last_end..]);
+    Ok(result)
+}
+
+/// Time a Vault request may take before the secret lookup fails
+const VAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read one field of a Vault KV v2 secret
+fn resolve_vault_secret(addr: &str, path: &str, key: &str) -> Result<String> {
+    let token = std::env::var("VAULT_TOKEN")
+        .context("VAULT_TOKEN must be set to read secrets from Vault")?;
+    fetch_vault_field(addr, path, key, &token, VAULT_TIMEOUT)
+}
+
+/// Split `path` into the KV mount and the secret's path within it
+///
+/// Both `secret/app` and the HTTP API form `secret/data/app` name the
+/// secret `app` on the `secret` mount.
+fn vault_mount_and_path(path: &str) -> Result<(&str, &str)> {
+    let path = path.trim_matches('/');
+    let (mount, secret) = path
+        .split_once('/')
+        .ok_or_else(|| anyhow!("Vault path '{path}' must be '<mount>/<secret>'"))?;
+    Ok((mount, secret.strip_prefix("data/").unwrap_or(secret)))
+}
+
+/// Read `path` from the Vault at `addr` with `vaultrs::kv2::read` and pick out `key`
+///
+/// `get_secret` is synchronous but called from async steps, so the request
+/// runs on its own thread with a single-threaded runtime. That never blocks
+/// inside the caller's runtime, whichever flavor it is.
+fn fetch_vault_field(
+    addr: &str,
+    path: &str,
+    key: &str,
+    token: &str,
+    timeout: Duration,
+) -> Result<String> {
+    let (mount, secret) = vault_mount_and_path(path)?;
+    let settings = VaultClientSettingsBuilder::default()
+        .address(addr)
+        .token(token)
+        .timeout(Some(timeout))
+        .build()
+        .map_err(|e| anyhow!("Invalid Vault settings for '{addr}': {e}"))?;
+    let client = VaultClient::new(settings)
+        .map_err(|e| anyhow!("Failed to create Vault client for '{addr}': {e}"))?;
+
+    let read = || -> Result<HashMap<String, serde_json::Value>> {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(vaultrs::kv2::read(&client, mount, secret))
+            .map_err(|e| anyhow!("Vault request for '{path}' failed: {e}"))
+    };
+    let data = std::thread::scope(|scope| scope.spawn(read).join())
+        .map_err(|_| anyhow!("Vault request for '{path}' panicked"))??;
+
+    match data.get(key) {
+        Some(serde_json::Value::String(s)) => Ok(s.clone()),
+        Some(other) => Ok(other.to_string()),
+        None => bail!("Vault secret '{path}' has no field '{key}'"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OxiConfig;
+
+    #[test]
+    fn test_env_secret() {
+        std::env::set_var("OXIDE_FLOW_TEST_SECRET_ENV", "hunter2");
+        let config = OxiConfig::from_yaml_str(
+            r#"
+            short: !secret OXIDE_FLOW_TEST_SECRET_ENV
+            long: !secret {provider: env, key: OXIDE_FLOW_TEST_SECRET_ENV}
+            "#,
+        );
+
+        assert_eq!(config.get_secret("short").unwrap(), "hunter2");
+        assert_eq!(config.get_secret("long").unwrap(), "hunter2");
+    }
+
+    #[test]
+    fn test_file_secret_strips_trailing_newline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db_password");
+        std::fs::write(&path, "s3cret value\n").unwrap();
+
+        let config = OxiConfig::from_yaml_str(&format!(
+            "password: !secret {{provider: file, path: '{}'}}",
+            path.display()
+        ));
+        assert_eq!(config.get_secret("password").unwrap(), "s3cret value");
+    }
+
+    #[test]
+    fn test_secret_errors() {
+        let config = OxiConfig::from_yaml_str(
+            r#"
+            plain: not-a-secret
+            bad_provider: !secret {provider: keychain, key: x}
+            vault_no_addr: !secret {provider: vault, path: secret/app, key: x}
+            "#,
+        );
+
+        assert!(config.get_secret("plain").is_err());
+        assert!(config.get_secret("missing").is_err());
+        assert!(config
+            .get_secret("bad_provider")
+            .unwrap_err()
+            .to_string()
+            .contains("Unknown !secret provider"));
+        assert!(config
+            .get_secret("vault_no_addr")
+            .unwrap_err()
+            .to_string()
+            .contains("requires 'addr'"));
+    }
+
+    #[test]
+    fn test_secrets_masked_in_debug() {
+        let config = OxiConfig::from_yaml_str(
+            r#"
+            user: admin
+            password: !secret {provider: file, path: /run/secrets/db_password}
+            "#,
+        );
+        let debug = format!("{config:?}");
+
+        assert!(debug.contains("admin"));
+        assert!(debug.contains(SECRET_MASK));
+        assert!(!debug.contains("db_password"));
+    }
+
+    #[test]
+    fn test_encrypted_secret_round_trip() {
+        let key = [7u8; 32];
+        let mut config = OxiConfig::from_yaml_str("user: admin");
+        config.set_secret("password", "p@ss w0rd", &key).unwrap();
+
+        // Survives being written to and read back from a config file
+        let yaml = serde_yaml::to_string(&config.values).unwrap();
+        assert!(yaml.contains("!encrypted"), "{yaml}");
+        assert!(!yaml.contains("p@ss"));
+        let reloaded = OxiConfig::from_yaml(serde_yaml::from_str(&yaml).unwrap());
+        assert_eq!(
+            reloaded.get_secret_decrypted("password", &key).unwrap(),
+            "p@ss w0rd"
+        );
+
+        // Every encryption uses a fresh nonce
+        let mut again = OxiConfig::default();
+        again.set_secret("password", "p@ss w0rd", &key).unwrap();
+        assert_ne!(again.values["password"], config.values["password"]);
+
+        assert!(config.get_secret_decrypted("password", &[8u8; 32]).is_err());
+        assert!(config.get_secret_decrypted("user", &key).is_err());
+
+        let serde_yaml::Value::Tagged(stored) = &config.values["password"] else {
+            panic!("expected a tagged value");
+        };
+        let debug = format!("{config:?}");
+        assert!(debug.contains(SECRET_MASK));
+        assert!(!debug.contains(stored.value.as_str().unwrap()));
+    }
+
+    #[test]
+    fn test_encrypted_secret_key_parsing() {
+        let key = [42u8; 32];
+        let encoded = base64::engine::general_purpose::STANDARD.encode(key);
+        assert_eq!(parse_encryption_key(&format!("{encoded}\n")).unwrap(), key);
+        assert!(parse_encryption_key("bm90IGVub3VnaA==").is_err());
+        assert!(parse_encryption_key("not base64!").is_err());
+
+        let mut config = OxiConfig::default();
+        config.set_secret("token", "abc123", &key).unwrap();
+        assert_eq!(
+            config.get_secret_decrypted("token", &key).unwrap(),
+            "abc123"
+        );
+
+        assert!(decrypt_secret("bm90IGVub3VnaA==", &key).is_err());
+    }
+
+    #[test]
+    fn test_env_secret_resolver() {
+        std::env::set_var("OXIDE_FLOW_TEST_SECRET_REF", "from-env");
+        let resolved = substitute_secrets(
+            "token=${secret:OXIDE_FLOW_TEST_SECRET_REF};",
+            &EnvSecretResolver,
+        )
+        .unwrap();
+        assert_eq!(resolved, "token=from-env;");
+
+        let err = substitute_secrets("${secret:OXIDE_FLOW_TEST_SECRET_UNSET}", &EnvSecretResolver)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("OXIDE_FLOW_TEST_SECRET_UNSET"), "{err}");
+    }
+
+    #[test]
+    fn test_file_secret_resolver() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("db_password"), "p@ss\n").unwrap();
+        std::env::set_var("OXIDE_FLOW_TEST_SECRET_FALLBACK", "env-value");
+
+        let files = FileSecretResolver::new(dir.path());
+        assert_eq!(files.resolve("db_password").unwrap(), "p@ss");
+        assert_eq!(files.lookup("missing").unwrap(), None);
+        assert!(files.lookup("../etc/passwd").is_err());
+
+        let resolver = project_secret_resolver(Some(dir.path()));
+        assert_eq!(
+            substitute_secrets(
+                "${secret:db_password}/${secret:OXIDE_FLOW_TEST_SECRET_FALLBACK}",
+                resolver.as_ref()
+            )
+            .unwrap(),
+            "p@ss/env-value"
+        );
+    }
+
+    /// Answers one request with a KV v2 read of `fields`, returning the address to query
+    fn mock_vault(fields: serde_json::Value) -> String {
+        use std::io::{BufRead, BufReader, Write};
+
+        let body = serde_json::json!({
+            "request_id": "1",
+            "lease_id": "",
+            "lease_duration": 0,
+            "renewable": false,
+            "data": {
+                "data": fields,
+                "metadata": {
+                    "created_time": "2024-01-01T00:00:00Z",
+                    "deletion_time": "",
+                    "destroyed": false,
+                    "version": 1
+                }
+            }
+        })
+        .to_string();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            write!(
+                reader.into_inner(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        });
+        addr
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_vault_secret_read_from_async_step() {
+        let addr = mock_vault(serde_json::json!({"signing_key": "k3y"}));
+        let value = tokio::spawn(async move {
+            fetch_vault_field(
+                &addr,
+                "secret/data/app",
+                "signing_key",
+                "token",
+                Duration::from_secs(5),
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(value, "k3y");
+    }
+
+    #[tokio::test]
+    async fn test_vault_secret_read_on_current_thread_runtime() {
+        let addr = mock_vault(serde_json::json!({"port": 8200}));
+        let value = fetch_vault_field(&addr, "secret/app", "port", "token", Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(value, "8200");
+    }
+
+    #[test]
+    fn test_vault_paths_name_mount_and_secret() {
+        assert_eq!(
+            vault_mount_and_path("secret/data/app").unwrap(),
+            ("secret", "app")
+        );
+        assert_eq!(
+            vault_mount_and_path("/kv/team/app").unwrap(),
+            ("kv", "team/app")
+        );
+        assert!(vault_mount_and_path("secret").is_err());
+    }
+
+    #[test]
+    fn test_unresponsive_vault_times_out() {
+        // Accepts the connection into its backlog but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+
+        let started = std::time::Instant::now();
+        let err = fetch_vault_field(
+            &addr,
+            "secret/app",
+            "key",
+            "token",
+            Duration::from_millis(200),
+        )
+        .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(err
+            .to_string()
+            .contains("Vault request for 'secret/app' failed"));
+        drop(listener);
+    }
+}
@@ -1,16 +1,21 @@
-use crate::state::types::{PipelineState, StateError};
+use crate::state::types::{
+    ErrorRecord, PipelineState, ResumeCheckpoint, StateError, StateQuery, StateSummary,
+};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use fs4::tokio::AsyncFileExt;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 /// Configuration for different state backend types
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BackendConfig {
     /// File-based backend configuration
     File {
@@ -22,12 +27,16 @@ pub enum BackendConfig {
         atomic_writes: bool,
         /// Lock timeout in milliseconds
         lock_timeout_ms: u64,
+        /// Whether to log state and lock changes before applying them
+        #[serde(default)]
+        write_ahead_log: bool,
     },
 
     /// Memory-based backend (for testing)
     Memory {
-        /// Whether to persist state across restarts
-        persistent: bool,
+        /// Directory to persist states in across restarts, if any
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        persist_dir: Option<PathBuf>,
     },
 
     /// Redis backend configuration (future feature)
@@ -56,8 +65,166 @@ pub struct LockInfo {
     pub locked_at: DateTime<Utc>,
     pub expires_at: Option<DateTime<Utc>>,
     pub lock_version: u64,
+    /// Lease length in milliseconds; each renewal moves `expires_at` this far past now
+    #[serde(default)]
+    pub lease_ms: u64,
+    /// Process that took the lock, on the host that took it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// Host that took the lock; `pid` only means something there
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+}
+
+impl LockInfo {
+    /// Extend the lease from now and bump `lock_version`
+    ///
+    /// Locks written before `lease_ms` existed reuse their original lease.
+    fn renew(&mut self) {
+        let lease_ms = match (self.lease_ms, self.expires_at) {
+            (0, Some(expires_at)) => (expires_at - self.locked_at).num_milliseconds().max(0),
+            (lease_ms, _) => lease_ms as i64,
+        };
+        self.expires_at = Some(Utc::now() + chrono::Duration::milliseconds(lease_ms));
+        self.lock_version += 1;
+    }
+
+    /// Whether the lease ran out or the process that took the lock has exited
+    ///
+    /// The process is only checked for locks taken on this host; a holder on
+    /// another host, or one that didn't record its host, keeps the lock until
+    /// its lease runs out.
+    fn is_stale(&self) -> bool {
+        let local = self.hostname.is_some() && self.hostname.as_deref() == host_name();
+        self.expires_at
+            .is_some_and(|expires_at| Utc::now() > expires_at)
+            || (local && self.pid.is_some_and(|pid| !process_alive(pid)))
+    }
+}
+
+/// A change to a pipeline's persisted state or lock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateChangeEvent {
+    pub pipeline_id: String,
+    pub event: StateEvent,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Kinds of state changes reported by `StateBackend::watch_all`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StateEvent {
+    Created,
+    Updated { old_version: u64, new_version: u64 },
+    Deleted,
+    LockAcquired { worker_id: String },
+    LockReleased { worker_id: String },
+}
+
+impl StateChangeEvent {
+    pub fn new(pipeline_id: &str, event: StateEvent) -> Self {
+        Self {
+            pipeline_id: pipeline_id.to_string(),
+            event,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// Contents of a file backend heartbeat sidecar
+#[derive(Debug, Serialize, Deserialize)]
+struct HeartbeatRecord {
+    worker_id: String,
+    timestamp: DateTime<Utc>,
+}
+
+/// Whether a heartbeat from `worker_id` counts for a state owned by `owner`
+fn heartbeat_applies(owner: Option<&str>, worker_id: &str) -> bool {
+    owner.is_none_or(|owner| owner == worker_id)
+}
+
+/// Length and modification time of a state file, telling whether its index entry is current
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct FileStamp {
+    len: u64,
+    modified: Option<std::time::SystemTime>,
+}
+
+impl FileStamp {
+    fn of(metadata: &std::fs::Metadata) -> Self {
+        Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        }
+    }
+}
+
+/// A state in the file backend's index, with the file it was read from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    summary: StateSummary,
+    stamp: FileStamp,
+}
+
+/// The file backend's `index.json`, keyed by pipeline ID
+type StateIndex = BTreeMap<String, IndexEntry>;
+
+/// Pending load of one state in a [`StateStream`]
+type StateLoad<'a> = Pin<Box<dyn Future<Output = Result<PipelineState, StateError>> + Send + 'a>>;
+
+/// Pipeline states loaded one at a time, from [`StateBackend::stream_pipelines`]
+///
+/// The pipeline IDs are listed when the stream is created; each state is
+/// only loaded when [`StateStream::next`] reaches it, so dropping the stream
+/// early skips loading the rest. States deleted after the listing are
+/// skipped, and a state that fails to load is yielded as an error without
+/// ending the stream.
+pub struct StateStream<'a> {
+    pipeline_ids: std::vec::IntoIter<String>,
+    load: Box<dyn Fn(String) -> StateLoad<'a> + Send + Sync + 'a>,
 }
 
+impl<'a> StateStream<'a> {
+    /// Stream the states of `pipeline_ids` in order, loading each with `load`
+    pub(crate) fn new(
+        pipeline_ids: Vec<String>,
+        load: impl Fn(String) -> StateLoad<'a> + Send + Sync + 'a,
+    ) -> Self {
+        Self {
+            pipeline_ids: pipeline_ids.into_iter(),
+            load: Box::new(load),
+        }
+    }
+
+    /// Only stream the pipelines whose ID passes `keep`; the others are never loaded
+    pub fn filter_ids(mut self, keep: impl Fn(&str) -> bool) -> Self {
+        let kept: Vec<String> = self.pipeline_ids.by_ref().filter(|id| keep(id)).collect();
+        self.pipeline_ids = kept.into_iter();
+        self
+    }
+
+    /// Load the next state, or `None` when every listed pipeline has been yielded
+    pub async fn next(&mut self) -> Option<Result<(String, PipelineState), StateError>> {
+        for pipeline_id in self.pipeline_ids.by_ref() {
+            match (self.load)(pipeline_id.clone()).await {
+                Ok(state) => return Some(Ok((pipeline_id, state))),
+                Err(StateError::PipelineNotFound { .. } | StateError::StateFileNotFound { .. }) => {
+                    continue
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
+    }
+
+    /// Pipelines listed but not yet yielded or skipped
+    pub fn remaining(&self) -> usize {
+        self.pipeline_ids.len()
+    }
+}
+
+/// Buffered events per subscriber before slow receivers start lagging
+const WATCH_CHANNEL_CAPACITY: usize = 256;
+
 /// State backend trait for different persistence mechanisms
 #[async_trait]
 pub trait StateBackend: Send + Sync {
@@ -73,20 +240,111 @@ pub trait StateBackend: Send + Sync {
     /// List all pipeline IDs that have state
     async fn list_pipelines(&self) -> Result<Vec<String>, StateError>;
 
+    /// Every pipeline's state, loaded lazily as the stream is advanced
+    ///
+    /// Only the pipeline IDs are read up front, so callers can filter and
+    /// print states one at a time and stop early without loading them all.
+    async fn stream_pipelines(&self) -> Result<StateStream<'_>, StateError> {
+        let pipeline_ids = self.list_pipelines().await?;
+        Ok(StateStream::new(pipeline_ids, move |pipeline_id| {
+            Box::pin(async move { self.load_state(&pipeline_id).await })
+        }))
+    }
+
+    /// List the runs of a pipeline that saved state, sorted by run ID
+    async fn list_runs(&self, pipeline_id: &str) -> Result<Vec<String>, StateError>;
+
+    /// Load the last state saved by a specific run of a pipeline
+    async fn load_run(&self, pipeline_id: &str, run_id: &str) -> Result<PipelineState, StateError>;
+
+    /// Apply `updater` to the saved state if it is still at `expected_version`
+    ///
+    /// The updated state is saved as the next version and returned. If
+    /// another writer saved the state first, nothing is written and
+    /// `StateError::VersionConflict` is returned.
+    async fn atomic_update<'a>(
+        &self,
+        pipeline_id: &str,
+        expected_version: u64,
+        updater: Box<dyn Fn(PipelineState) -> PipelineState + Send + 'a>,
+    ) -> Result<PipelineState, StateError>;
+
     /// Acquire an exclusive lock on pipeline state
+    ///
+    /// Waits up to `timeout_ms` for the current holder to let go; with 0 it
+    /// tries once and fails with `StateError::LockTimeout` without sleeping.
+    /// The lock then lasts `ttl_ms` unless renewed with
+    /// [`StateBackend::renew_lock`].
     async fn acquire_lock(
         &self,
         pipeline_id: &str,
         worker_id: &str,
         timeout_ms: u64,
+        ttl_ms: u64,
     ) -> Result<LockInfo, StateError>;
 
     /// Release a previously acquired lock
     async fn release_lock(&self, pipeline_id: &str, worker_id: &str) -> Result<(), StateError>;
 
+    /// Extend a lock held by `worker_id` by another lease
+    ///
+    /// Fails with `StateError::LockLost` if the lock expired, was released or
+    /// is now held by another worker.
+    async fn renew_lock(&self, pipeline_id: &str, worker_id: &str) -> Result<LockInfo, StateError>;
+
     /// Check if a pipeline is currently locked
     async fn is_locked(&self, pipeline_id: &str) -> Result<Option<LockInfo>, StateError>;
 
+    /// Record that `worker_id` is still running the pipeline at `timestamp`
+    ///
+    /// Only the heartbeat changes: the state's `version` stays the same, so a
+    /// heartbeat never makes a concurrent `atomic_update` conflict or retry.
+    /// `load_state` reports the latest heartbeat of the state's own worker.
+    async fn touch_heartbeat(
+        &self,
+        pipeline_id: &str,
+        worker_id: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), StateError>;
+
+    /// Summaries of the states matching `query`, filtered, ordered and limited by it
+    ///
+    /// Unlike loading every state this only needs each state's
+    /// [`StateSummary`]; the file backend reads them from an index.
+    async fn query_states(&self, query: StateQuery) -> Result<Vec<StateSummary>, StateError>;
+
+    /// Rebuild the index `query_states` reads from the stored states
+    ///
+    /// Returns the number of states indexed. Backends without an index just
+    /// count their states.
+    async fn reindex(&self) -> Result<usize, StateError>;
+
+    /// Append errors evicted from a pipeline's state to its error archive, oldest first
+    async fn append_error_archive(
+        &self,
+        pipeline_id: &str,
+        errors: &[ErrorRecord],
+    ) -> Result<(), StateError>;
+
+    /// Load a pipeline's archived errors in the order they were appended
+    async fn load_error_archive(&self, pipeline_id: &str) -> Result<Vec<ErrorRecord>, StateError>;
+
+    /// Save the checkpoint a paused run resumes from, replacing any earlier one
+    async fn save_resume_checkpoint(
+        &self,
+        pipeline_id: &str,
+        checkpoint: &ResumeCheckpoint,
+    ) -> Result<(), StateError>;
+
+    /// Load a pipeline's resume checkpoint, `None` if none was saved
+    async fn load_resume_checkpoint(
+        &self,
+        pipeline_id: &str,
+    ) -> Result<Option<ResumeCheckpoint>, StateError>;
+
+    /// Remove a pipeline's resume checkpoint, if any
+    async fn delete_resume_checkpoint(&self, pipeline_id: &str) -> Result<(), StateError>;
+
     /// Force release a lock (admin operation)
     async fn force_release_lock(&self, pipeline_id: &str) -> Result<(), StateError>;
 
@@ -118,6 +376,85 @@ pub trait StateBackend: Send + Sync {
 
     /// Verify backend integrity (check all state files)
     async fn verify_integrity(&self) -> Result<IntegrityReport, StateError>;
+
+    /// Subscribe to state and lock changes for every pipeline in this backend
+    async fn watch_all(&self) -> Result<broadcast::Receiver<StateChangeEvent>, StateError>;
+
+    /// Serialize every pipeline's state and current lock into one gzipped JSON archive
+    async fn export_all(&self) -> Result<Vec<u8>, StateError> {
+        let mut archive = StateArchive {
+            format_version: STATE_ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            states: Vec::new(),
+            locks: Vec::new(),
+        };
+        for pipeline_id in self.list_pipelines().await? {
+            archive.states.push(self.load_state(&pipeline_id).await?);
+            archive.locks.extend(self.is_locked(&pipeline_id).await?);
+        }
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        serde_json::to_writer(&mut encoder, &archive)?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Save the states in an archive written by [`StateBackend::export_all`]
+    ///
+    /// Pipelines that already have state are skipped unless `overwrite` is
+    /// set. Locks in the archive are not restored, as their holders belong
+    /// to the exporting store.
+    async fn import_all(
+        &self,
+        data: &[u8],
+        overwrite: bool,
+    ) -> Result<ImportAllResult, StateError> {
+        let archive: StateArchive = serde_json::from_reader(flate2::read::GzDecoder::new(data))
+            .map_err(|e| StateError::SerializationError {
+                details: format!("Invalid state archive: {e}"),
+            })?;
+        if archive.format_version > STATE_ARCHIVE_VERSION {
+            return Err(StateError::SerializationError {
+                details: format!(
+                    "State archive version {} is newer than supported version {STATE_ARCHIVE_VERSION}",
+                    archive.format_version
+                ),
+            });
+        }
+
+        let existing = self.list_pipelines().await?;
+        let mut result = ImportAllResult::default();
+        for state in archive.states {
+            if !overwrite && existing.contains(&state.pipeline_id) {
+                result.skipped_count += 1;
+            } else if self.save_state(&state).await.is_ok() {
+                result.imported_count += 1;
+            } else {
+                result.failed_ids.push(state.pipeline_id);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Version of the archive layout written by [`StateBackend::export_all`]
+const STATE_ARCHIVE_VERSION: u32 = 1;
+
+/// Contents of a whole-store state archive
+#[derive(Debug, Serialize, Deserialize)]
+struct StateArchive {
+    format_version: u32,
+    exported_at: DateTime<Utc>,
+    states: Vec<PipelineState>,
+    locks: Vec<LockInfo>,
+}
+
+/// Result of [`StateBackend::import_all`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportAllResult {
+    pub imported_count: usize,
+    pub skipped_count: usize,
+    /// Pipelines whose state could not be saved
+    pub failed_ids: Vec<String>,
 }
 
 /// Health status of a state backend
@@ -129,6 +466,36 @@ pub struct BackendHealth {
     pub response_time_ms: u64,
     pub error_message: Option<String>,
     pub metrics: HashMap<String, f64>,
+    /// Direction of response times over the latest snapshots (set by the health monitor)
+    #[serde(default)]
+    pub trend: Option<HealthTrend>,
+}
+
+/// Response-time trend across consecutive health snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HealthTrend {
+    Improving,
+    Degrading,
+    Stable,
+}
+
+impl HealthTrend {
+    /// Trend of the last three response times, oldest first
+    ///
+    /// Strictly falling times are improving and strictly rising times are
+    /// degrading; anything else is stable. Needs at least three samples.
+    pub fn from_response_times(times: &[u64]) -> Option<Self> {
+        let [a, b, c] = times.get(times.len().checked_sub(3)?..)? else {
+            return None;
+        };
+        Some(if a > b && b > c {
+            HealthTrend::Improving
+        } else if a < b && b < c {
+            HealthTrend::Degrading
+        } else {
+            HealthTrend::Stable
+        })
+    }
 }
 
 /// Result of a cleanup operation
@@ -219,6 +586,137 @@ pub struct IntegrityReport {
     pub checksum_mismatches: Vec<String>,
     pub repair_recommendations: Vec<String>,
     pub overall_health: f64, // 0.0 to 1.0
+    /// Files no pipeline needs any more, removed by `state gc`
+    #[serde(default)]
+    pub orphaned_files: Vec<OrphanedFile>,
+}
+
+/// A leftover file found by `verify_integrity`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrphanedFile {
+    pub path: String,
+    pub kind: OrphanKind,
+    /// Size on disk; for a backup directory, the total of its files
+    pub size_bytes: u64,
+}
+
+/// Why a file counts as orphaned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanKind {
+    /// Lock of a pipeline without state, unreadable or past its lease
+    Lock,
+    /// Backup directory of a pipeline without state
+    Backup,
+    /// Zero-byte state file
+    EmptyState,
+    /// Temporary file of an atomic write that never got renamed
+    TempFile,
+}
+
+/// Temporary files younger than this may belong to a write in progress
+const TEMP_FILE_GRACE: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// A state change as recorded in the write-ahead log, one JSON line per intent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum WalIntent {
+    SaveState { state: Box<PipelineState> },
+    DeleteState { pipeline_id: String },
+    WriteLock { lock: LockInfo },
+    RemoveLock { pipeline_id: String },
+}
+
+impl WalIntent {
+    fn pipeline_id(&self) -> &str {
+        match self {
+            WalIntent::SaveState { state } => &state.pipeline_id,
+            WalIntent::WriteLock { lock } => &lock.pipeline_id,
+            WalIntent::DeleteState { pipeline_id } | WalIntent::RemoveLock { pipeline_id } => {
+                pipeline_id
+            }
+        }
+    }
+
+    /// Whether this intent and `other` change the same state or lock file
+    fn overlaps(&self, other: &WalIntent) -> bool {
+        let writes_state = |intent: &WalIntent| {
+            matches!(
+                intent,
+                WalIntent::SaveState { .. } | WalIntent::DeleteState { .. }
+            )
+        };
+        let writes_lock = |intent: &WalIntent| !matches!(intent, WalIntent::SaveState { .. });
+        (writes_state(self) && writes_state(other)) || (writes_lock(self) && writes_lock(other))
+    }
+}
+
+/// One line of a pipeline's write-ahead log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+enum WalRecord {
+    /// A change about to be applied
+    Intent { id: String, intent: WalIntent },
+    /// The change logged under `id` was applied, or failed and must not be replayed
+    Commit { id: String },
+}
+
+/// Logs are compacted once they grow past this many bytes with nothing pending
+const WAL_COMPACT_BYTES: u64 = 1024 * 1024;
+
+/// The intents in `log` still waiting for their commit marker, in logged
+/// order, and the number of torn or unreadable lines
+///
+/// An uncommitted intent is dropped once a later intent for the same file
+/// commits, so a change from a crashed process never rolls back a newer one.
+fn pending_intents(log: &[u8]) -> (Vec<WalIntent>, usize) {
+    let mut pending: Vec<(String, WalIntent)> = Vec::new();
+    let mut discarded = 0;
+    let mut lines = log.split(|&byte| byte == b'\n').peekable();
+    while let Some(line) = lines.next() {
+        // Only a newline-terminated record was fully written
+        let complete = lines.peek().is_some();
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_slice::<WalRecord>(line) {
+            Ok(WalRecord::Intent { id, intent }) if complete => pending.push((id, intent)),
+            Ok(WalRecord::Commit { id }) if complete => {
+                if let Some(position) = pending.iter().position(|(logged, _)| *logged == id) {
+                    let (_, committed) = pending.remove(position);
+                    let mut index = 0;
+                    pending.retain(|(_, intent)| {
+                        index += 1;
+                        index > position || !intent.overlaps(&committed)
+                    });
+                }
+            }
+            _ => discarded += 1,
+        }
+    }
+    (
+        pending.into_iter().map(|(_, intent)| intent).collect(),
+        discarded,
+    )
+}
+
+/// A logged intent waiting for its commit marker
+///
+/// Holds the log's file lock until committed, so recovery in another process
+/// never replays a change that is still being applied.
+struct PendingIntent {
+    log: fs::File,
+    path: PathBuf,
+    id: String,
+}
+
+/// Outcome of replaying the write-ahead log on startup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WalRecovery {
+    /// Complete intents that were applied again
+    pub replayed: usize,
+    /// Partially written intents that were dropped
+    pub discarded: usize,
 }
 
 /// File-based state backend implementation
@@ -226,14 +724,238 @@ pub struct FileBackend {
     base_path: PathBuf,
     format: SerializationFormat,
     atomic_writes: bool,
-    #[allow(dead_code)] // Used for future timeout configuration
     lock_timeout_ms: u64,
+    write_ahead_log: bool,
 
     // Performance optimization features
     cache: std::sync::Arc<tokio::sync::RwLock<HashMap<String, CachedState>>>,
     cache_enabled: bool,
     cache_max_size: usize,
     performance_metrics: std::sync::Arc<tokio::sync::RwLock<PerformanceMetrics>>,
+
+    // Directory watcher shared by all `watch_all` subscribers, started on first use
+    watch: std::sync::Mutex<Option<DirectoryWatch>>,
+
+    // pipeline_id -> (worker_id, open lock file); the kernel lock on the file
+    // is held until the lock is released, so it goes away if the process dies
+    held_locks: std::sync::Mutex<HashMap<String, (String, fs::File)>>,
+}
+
+/// A running directory watcher and the channel it publishes to
+struct DirectoryWatch {
+    _watcher: notify::RecommendedWatcher,
+    sender: broadcast::Sender<StateChangeEvent>,
+}
+
+/// Last known on-disk contents, used to turn raw file events into state events
+struct WatchTracker {
+    states_dir: PathBuf,
+    locks_dir: PathBuf,
+    format: SerializationFormat,
+    /// pipeline_id -> (state version, content digest)
+    states: HashMap<String, (u64, md5::Digest)>,
+    /// pipeline_id -> lock holder
+    locks: HashMap<String, LockInfo>,
+    sender: broadcast::Sender<StateChangeEvent>,
+}
+
+impl WatchTracker {
+    /// Record what is on disk right now so later changes are reported relative to it
+    fn scan_existing(&mut self) {
+        for path in std::fs::read_dir(&self.states_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+        {
+            if let Some((id, version, digest)) = self.read_state(&path) {
+                self.states.insert(id, (version, digest));
+            }
+        }
+        for path in std::fs::read_dir(&self.locks_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+        {
+            if let Some(lock) = read_lock(&path) {
+                self.locks.insert(lock.pipeline_id.clone(), lock);
+            }
+        }
+    }
+
+    fn handle(&mut self, event: notify::Event) {
+        if matches!(event.kind, notify::EventKind::Access(_)) {
+            return;
+        }
+
+        for path in &event.paths {
+            if path.parent() == Some(self.states_dir.as_path()) {
+                self.state_changed(path);
+            } else if path.parent() == Some(self.locks_dir.as_path()) {
+                self.lock_changed(path);
+            }
+        }
+    }
+
+    fn state_changed(&mut self, path: &std::path::Path) {
+        let extension = format_extension(&self.format);
+        if path.extension().and_then(|e| e.to_str()) != Some(extension) {
+            return; // Temp files from atomic writes, foreign files
+        }
+        let Some(pipeline_id) = path.file_stem().and_then(|s| s.to_str()) else {
+            return;
+        };
+
+        if !path.exists() {
+            if self.states.remove(pipeline_id).is_some() {
+                self.publish(pipeline_id, StateEvent::Deleted);
+            }
+            return;
+        }
+
+        // Partially written files fail to parse; the final write produces another event
+        let Some((pipeline_id, version, digest)) = self.read_state(path) else {
+            return;
+        };
+        match self.states.insert(pipeline_id.clone(), (version, digest)) {
+            None => self.publish(&pipeline_id, StateEvent::Created),
+            Some((_, old_digest)) if old_digest == digest => {}
+            Some((old_version, _)) => self.publish(
+                &pipeline_id,
+                StateEvent::Updated {
+                    old_version,
+                    new_version: version,
+                },
+            ),
+        }
+    }
+
+    fn lock_changed(&mut self, path: &std::path::Path) {
+        if path.extension().and_then(|e| e.to_str()) != Some("lock") {
+            return;
+        }
+        let Some(pipeline_id) = path.file_stem().and_then(|s| s.to_str()) else {
+            return;
+        };
+
+        if !path.exists() {
+            if let Some(previous) = self.locks.remove(pipeline_id) {
+                self.publish(
+                    pipeline_id,
+                    StateEvent::LockReleased {
+                        worker_id: previous.worker_id,
+                    },
+                );
+            }
+            return;
+        }
+
+        let Some(lock) = read_lock(path) else {
+            return;
+        };
+        let is_new_holder = self.locks.get(pipeline_id).is_none_or(|previous| {
+            previous.worker_id != lock.worker_id || previous.locked_at != lock.locked_at
+        });
+        if is_new_holder {
+            let worker_id = lock.worker_id.clone();
+            self.locks.insert(pipeline_id.to_string(), lock);
+            self.publish(pipeline_id, StateEvent::LockAcquired { worker_id });
+        }
+    }
+
+    fn read_state(&self, path: &std::path::Path) -> Option<(String, u64, md5::Digest)> {
+        let data = std::fs::read(path).ok()?;
+        let state = deserialize_state_bytes(&self.format, &data).ok()?;
+        Some((state.pipeline_id, state.version, md5::compute(&data)))
+    }
+
+    fn publish(&self, pipeline_id: &str, event: StateEvent) {
+        // No subscribers is not an error; events are simply dropped
+        let _ = self.sender.send(StateChangeEvent::new(pipeline_id, event));
+    }
+}
+
+fn read_lock(path: &std::path::Path) -> Option<LockInfo> {
+    let data = std::fs::read(path).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+/// Name of this host, as recorded in the locks it takes
+///
+/// On Linux this is the UTS namespace's name, so containers that share a
+/// state directory but not a process table tell themselves apart.
+fn host_name() -> Option<&'static str> {
+    static HOST_NAME: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
+    HOST_NAME
+        .get_or_init(|| {
+            std::fs::read_to_string("/proc/sys/kernel/hostname")
+                .ok()
+                .or_else(|| std::env::var("HOSTNAME").ok())
+                .or_else(|| std::env::var("COMPUTERNAME").ok())
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+        })
+        .as_deref()
+}
+
+/// Whether a process with this id is running on this host
+///
+/// Only Linux can tell; elsewhere every process counts as alive and a lock
+/// is only stale once it expires.
+fn process_alive(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        Path::new("/proc").join(pid.to_string()).exists()
+    } else {
+        true
+    }
+}
+
+/// Whether two handles refer to the same file, not just the same path
+fn same_file(a: &std::fs::Metadata, b: &std::fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        a.dev() == b.dev() && a.ino() == b.ino()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (a, b);
+        true
+    }
+}
+
+/// Whether `path` is a temporary file written by `write_file_atomic` or WAL compaction
+fn is_temp_file(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("tmp")
+}
+
+fn format_extension(format: &SerializationFormat) -> &'static str {
+    match format {
+        SerializationFormat::Json => "json",
+        SerializationFormat::Yaml => "yaml",
+        SerializationFormat::Bincode => "bin",
+    }
+}
+
+/// Deserialize a state file's contents in the given format
+fn deserialize_state_bytes(
+    format: &SerializationFormat,
+    data: &[u8],
+) -> Result<PipelineState, StateError> {
+    match format {
+        SerializationFormat::Json => serde_json::from_slice(data).map_err(StateError::from),
+        SerializationFormat::Yaml => {
+            let text =
+                String::from_utf8(data.to_vec()).map_err(|e| StateError::SerializationError {
+                    details: format!("Invalid UTF-8: {e}"),
+                })?;
+            serde_yaml::from_str(&text).map_err(StateError::from)
+        }
+        SerializationFormat::Bincode => Err(StateError::SerializationError {
+            details: "Bincode format not yet implemented".to_string(),
+        }),
+    }
 }
 
 /// Cached state with metadata
@@ -261,6 +983,46 @@ struct PerformanceMetrics {
     total_bytes_written: u64,
 }
 
+/// Milliseconds since `start`, with sub-millisecond precision for in-memory operations
+fn elapsed_ms(start: std::time::Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+impl PerformanceMetrics {
+    fn record_read(&mut self, duration_ms: f64, bytes_read: u64) {
+        self.total_reads += 1;
+        self.total_bytes_read += bytes_read;
+
+        // Update running average
+        self.avg_read_time_ms = ((self.avg_read_time_ms * (self.total_reads - 1) as f64)
+            + duration_ms)
+            / self.total_reads as f64;
+    }
+
+    fn record_write(&mut self, duration_ms: f64, bytes_written: u64) {
+        self.total_writes += 1;
+        self.total_bytes_written += bytes_written;
+
+        // Update running average
+        self.avg_write_time_ms = ((self.avg_write_time_ms * (self.total_writes - 1) as f64)
+            + duration_ms)
+            / self.total_writes as f64;
+    }
+
+    /// Add the read and write counters every backend reports to `diagnostics`
+    fn insert_io_metrics(&self, diagnostics: &mut HashMap<String, f64>) {
+        diagnostics.insert("total_reads".to_string(), self.total_reads as f64);
+        diagnostics.insert("total_writes".to_string(), self.total_writes as f64);
+        diagnostics.insert("avg_read_time_ms".to_string(), self.avg_read_time_ms);
+        diagnostics.insert("avg_write_time_ms".to_string(), self.avg_write_time_ms);
+        diagnostics.insert("total_bytes_read".to_string(), self.total_bytes_read as f64);
+        diagnostics.insert(
+            "total_bytes_written".to_string(),
+            self.total_bytes_written as f64,
+        );
+    }
+}
+
 impl FileBackend {
     /// Create a new file backend
     pub fn new(config: BackendConfig) -> Result<Self, StateError> {
@@ -270,37 +1032,355 @@ impl FileBackend {
                 format,
                 atomic_writes,
                 lock_timeout_ms,
-            } => Ok(Self {
-                base_path,
-                format,
-                atomic_writes,
-                lock_timeout_ms,
-                cache: std::sync::Arc::new(tokio::sync::RwLock::new(HashMap::new())),
-                cache_enabled: true, // Enable by default
-                cache_max_size: 100, // Default cache size
-                performance_metrics: std::sync::Arc::new(tokio::sync::RwLock::new(
-                    PerformanceMetrics::default(),
-                )),
-            }),
+                write_ahead_log,
+            } => {
+                let backend = Self {
+                    base_path,
+                    format,
+                    atomic_writes,
+                    lock_timeout_ms,
+                    write_ahead_log,
+                    cache: std::sync::Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+                    cache_enabled: true, // Enable by default
+                    cache_max_size: 100, // Default cache size
+                    performance_metrics: std::sync::Arc::new(tokio::sync::RwLock::new(
+                        PerformanceMetrics::default(),
+                    )),
+                    watch: std::sync::Mutex::new(None),
+                    held_locks: std::sync::Mutex::new(HashMap::new()),
+                };
+                if backend.write_ahead_log {
+                    backend.recover_write_ahead_log()?;
+                }
+                Ok(backend)
+            }
             _ => Err(StateError::InvalidState {
                 details: "FileBackend requires File configuration".to_string(),
             }),
         }
     }
 
+    /// Finish or drop state changes interrupted by a crash
+    ///
+    /// Every complete intent left in the write-ahead log without a commit
+    /// marker is applied again, in the order it was logged; intents are
+    /// idempotent, so re-applying one that had already landed is harmless. A
+    /// partially written last line is discarded, since its change never
+    /// started. Each log is replayed and then emptied under its exclusive
+    /// lock, so a change another process is still applying is waited for
+    /// rather than replayed.
+    pub fn recover_write_ahead_log(&self) -> Result<WalRecovery, StateError> {
+        let mut recovery = WalRecovery::default();
+        let wal_dir = self.base_path.join("wal");
+        if !wal_dir.exists() {
+            return Ok(recovery);
+        }
+
+        for entry in std::fs::read_dir(&wal_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("wal") {
+                continue;
+            }
+
+            let log = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&path)?;
+            fs4::fs_std::FileExt::lock_exclusive(&log)?;
+            let (pending, discarded) = pending_intents(&std::fs::read(&path)?);
+            for intent in &pending {
+                self.replay_intent(intent)?;
+            }
+            recovery.replayed += pending.len();
+            recovery.discarded += discarded;
+            log.set_len(0)?;
+        }
+
+        Ok(recovery)
+    }
+
+    /// Apply a logged intent synchronously, as recovery runs before the backend is used
+    fn replay_intent(&self, intent: &WalIntent) -> Result<(), StateError> {
+        std::fs::create_dir_all(self.base_path.join("states"))?;
+        std::fs::create_dir_all(self.base_path.join("locks"))?;
+
+        let remove = |path: PathBuf| match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+
+        match intent {
+            WalIntent::SaveState { state } => {
+                let data = self.serialize_state(state)?;
+                let run_path = self.run_file_path(&state.pipeline_id, &state.run_id);
+                if let Some(run_dir) = run_path.parent() {
+                    std::fs::create_dir_all(run_dir)?;
+                }
+                for path in [self.state_file_path(&state.pipeline_id), run_path] {
+                    let temp_path = path.with_extension("wal.tmp");
+                    std::fs::write(&temp_path, &data)?;
+                    std::fs::rename(&temp_path, &path)?;
+                }
+            }
+            WalIntent::DeleteState { pipeline_id } => {
+                remove(self.state_file_path(pipeline_id))?;
+                remove(self.lock_file_path(pipeline_id))?;
+                remove(self.heartbeat_file_path(pipeline_id))?;
+                remove(self.error_archive_path(pipeline_id))?;
+                remove(self.resume_checkpoint_path(pipeline_id))?;
+                let run_dir = self.run_dir_path(pipeline_id);
+                if run_dir.exists() {
+                    std::fs::remove_dir_all(run_dir)?;
+                }
+            }
+            WalIntent::WriteLock { lock } => {
+                std::fs::write(
+                    self.lock_file_path(&lock.pipeline_id),
+                    serde_json::to_vec(lock)?,
+                )?;
+            }
+            WalIntent::RemoveLock { pipeline_id } => remove(self.lock_file_path(pipeline_id))?,
+        }
+        Ok(())
+    }
+
+    /// Get the write-ahead log path for a pipeline
+    fn wal_file_path(&self, pipeline_id: &str) -> PathBuf {
+        self.base_path
+            .join("wal")
+            .join(format!("{pipeline_id}.wal"))
+    }
+
+    /// Append `intent` to its pipeline's log and sync it, when the log is enabled
+    ///
+    /// The log stays locked until the returned intent is passed to
+    /// [`FileBackend::commit_intent`].
+    async fn log_intent(&self, intent: WalIntent) -> Result<Option<PendingIntent>, StateError> {
+        if !self.write_ahead_log {
+            return Ok(None);
+        }
+
+        fs::create_dir_all(self.base_path.join("wal")).await?;
+        let path = self.wal_file_path(intent.pipeline_id());
+        let id = Uuid::new_v4().to_string();
+        let mut record = serde_json::to_vec(&WalRecord::Intent {
+            id: id.clone(),
+            intent,
+        })?;
+        record.push(b'\n');
+
+        let mut log = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        self.wait_for_lock(&log).await?;
+        log.write_all(&record).await?;
+        log.sync_data().await?;
+        Ok(Some(PendingIntent { log, path, id }))
+    }
+
+    /// Mark a logged change as applied (or failed), so it is never replayed
+    ///
+    /// A log past `WAL_COMPACT_BYTES` is emptied once none of its intents are
+    /// still pending, e.g. from a process that crashed mid-change.
+    async fn commit_intent(&self, pending: Option<PendingIntent>) -> Result<(), StateError> {
+        let Some(PendingIntent { mut log, path, id }) = pending else {
+            return Ok(());
+        };
+
+        let mut record = serde_json::to_vec(&WalRecord::Commit { id })?;
+        record.push(b'\n');
+        log.write_all(&record).await?;
+        log.sync_data().await?;
+
+        if log.metadata().await?.len() > WAL_COMPACT_BYTES
+            && pending_intents(&fs::read(&path).await?).0.is_empty()
+        {
+            log.set_len(0).await?;
+        }
+        Ok(())
+    }
+
     /// Get the state file path for a pipeline
     fn state_file_path(&self, pipeline_id: &str) -> PathBuf {
-        let extension = match self.format {
-            SerializationFormat::Json => "json",
-            SerializationFormat::Yaml => "yaml",
-            SerializationFormat::Bincode => "bin",
-        };
+        let extension = format_extension(&self.format);
 
         self.base_path
             .join("states")
             .join(format!("{pipeline_id}.{extension}"))
     }
 
+    /// Get the directory holding a pipeline's per-run states
+    fn run_dir_path(&self, pipeline_id: &str) -> PathBuf {
+        self.base_path.join("runs").join(pipeline_id)
+    }
+
+    /// Get the state file path for one run of a pipeline
+    fn run_file_path(&self, pipeline_id: &str, run_id: &str) -> PathBuf {
+        let extension = format_extension(&self.format);
+
+        self.run_dir_path(pipeline_id)
+            .join(format!("{run_id}.{extension}"))
+    }
+
+    /// Delete the least recently saved runs of `state`'s pipeline beyond `limits.max_saved_runs`
+    ///
+    /// Runs are ordered by their files' modification times; `state`'s own run
+    /// is always kept.
+    async fn prune_runs(&self, state: &PipelineState) -> Result<(), StateError> {
+        let current = self.run_file_path(&state.pipeline_id, &state.run_id);
+        let mut runs = Vec::new();
+        let mut entries = fs::read_dir(self.run_dir_path(&state.pipeline_id)).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path == current || is_temp_file(&path) {
+                continue;
+            }
+            let modified = entry.metadata().await?.modified()?;
+            runs.push((modified, path));
+        }
+
+        runs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        let keep = state.limits.max_saved_runs.saturating_sub(1);
+        for (_, path) in runs.into_iter().skip(keep) {
+            fs::remove_file(&path).await?;
+        }
+        Ok(())
+    }
+
+    /// Get the heartbeat sidecar path for a pipeline
+    fn heartbeat_file_path(&self, pipeline_id: &str) -> PathBuf {
+        self.base_path
+            .join("heartbeats")
+            .join(format!("{pipeline_id}.heartbeat"))
+    }
+
+    /// Get the JSONL archive of errors evicted from a pipeline's state
+    fn error_archive_path(&self, pipeline_id: &str) -> PathBuf {
+        self.base_path
+            .join("errors")
+            .join(format!("{pipeline_id}.jsonl"))
+    }
+
+    /// Get the resume checkpoint sidecar path for a pipeline
+    fn resume_checkpoint_path(&self, pipeline_id: &str) -> PathBuf {
+        self.base_path
+            .join("checkpoints")
+            .join(format!("{pipeline_id}.json"))
+    }
+
+    /// Read a pipeline's sidecar heartbeat
+    async fn read_heartbeat(&self, pipeline_id: &str) -> Option<HeartbeatRecord> {
+        // A missing or half-written sidecar just means no newer heartbeat
+        let data = fs::read(self.heartbeat_file_path(pipeline_id)).await.ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    /// Move `state.last_heartbeat` up to its worker's sidecar heartbeat
+    async fn apply_heartbeat(&self, state: &mut PipelineState) {
+        let Some(heartbeat) = self.read_heartbeat(&state.pipeline_id).await else {
+            return;
+        };
+        if heartbeat_applies(state.worker_id.as_deref(), &heartbeat.worker_id)
+            && heartbeat.timestamp > state.last_heartbeat
+        {
+            state.last_heartbeat = heartbeat.timestamp;
+        }
+    }
+
+    /// Get the path of the state index `query_states` reads
+    fn index_path(&self) -> PathBuf {
+        self.base_path.join("index.json")
+    }
+
+    /// Read the state index; `None` when it is missing or unreadable
+    async fn read_index(&self) -> Option<StateIndex> {
+        let data = fs::read(self.index_path()).await.ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    /// Replace the state index, under its file lock
+    async fn write_index(&self, index: &StateIndex) -> Result<(), StateError> {
+        let _lock = self
+            .lock_exclusive(self.base_path.join("index.lock"))
+            .await?;
+        self.write_file_atomic(&self.index_path(), &serde_json::to_vec(index)?)
+            .await
+    }
+
+    /// Apply `change` to the stored state index, under its file lock
+    async fn update_index(&self, change: impl FnOnce(&mut StateIndex)) -> Result<(), StateError> {
+        let _lock = self
+            .lock_exclusive(self.base_path.join("index.lock"))
+            .await?;
+        let mut index = self.read_index().await.unwrap_or_default();
+        change(&mut index);
+        self.write_file_atomic(&self.index_path(), &serde_json::to_vec(&index)?)
+            .await
+    }
+
+    /// The state index, brought up to date with the state files
+    ///
+    /// Entries are checked against their state file's length and modification
+    /// time, so only states saved behind the index's back (another backend
+    /// version, a restore, a WAL replay) are read again. New state files are
+    /// added and deleted ones dropped, and the index is only rewritten when
+    /// something changed.
+    async fn current_index(&self) -> Result<StateIndex, StateError> {
+        self.ensure_directories().await?;
+        let stored = self.read_index().await;
+        let mut changed = stored.is_none();
+        let mut index = stored.unwrap_or_default();
+
+        let extension = format_extension(&self.format);
+        let mut present = HashSet::new();
+        if let Ok(mut entries) = fs::read_dir(self.base_path.join("states")).await {
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some(extension) {
+                    continue;
+                }
+                let Some(pipeline_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                let Ok(metadata) = entry.metadata().await else {
+                    continue;
+                };
+                let stamp = FileStamp::of(&metadata);
+                present.insert(pipeline_id.to_string());
+                if index
+                    .get(pipeline_id)
+                    .is_some_and(|entry| entry.stamp == stamp)
+                {
+                    continue;
+                }
+
+                // Unreadable states are left out, as `list_pipelines` callers skip them
+                let state = match fs::read(&path).await {
+                    Ok(data) => self.deserialize_state(&data).ok(),
+                    Err(_) => None,
+                };
+                match state {
+                    Some(state) => {
+                        let summary = StateSummary::from(&state);
+                        index.insert(pipeline_id.to_string(), IndexEntry { summary, stamp });
+                        changed = true;
+                    }
+                    None => changed |= index.remove(pipeline_id).is_some(),
+                }
+            }
+        }
+        let indexed = index.len();
+        index.retain(|pipeline_id, _| present.contains(pipeline_id));
+        changed |= index.len() != indexed;
+
+        if changed {
+            self.write_index(&index).await?;
+        }
+        Ok(index)
+    }
+
     /// Get the lock file path for a pipeline
     fn lock_file_path(&self, pipeline_id: &str) -> PathBuf {
         self.base_path
@@ -308,6 +1388,115 @@ impl FileBackend {
             .join(format!("{pipeline_id}.lock"))
     }
 
+    /// Get the file locked while `atomic_update` reads, updates and saves a state
+    fn update_lock_path(&self, pipeline_id: &str) -> PathBuf {
+        self.base_path
+            .join("updates")
+            .join(format!("{pipeline_id}.lock"))
+    }
+
+    /// Take the exclusive update lock for a pipeline, held until the file is dropped
+    ///
+    /// This is a kernel file lock rather than a lock file, so it is released
+    /// if the process dies and also excludes other tasks in this process.
+    async fn lock_for_update(&self, pipeline_id: &str) -> Result<fs::File, StateError> {
+        fs::create_dir_all(self.base_path.join("updates")).await?;
+        self.lock_exclusive(self.update_lock_path(pipeline_id))
+            .await
+    }
+
+    /// Take an exclusive kernel lock on the file at `path`, held until the file is dropped
+    async fn lock_exclusive(&self, path: PathBuf) -> Result<fs::File, StateError> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .await?;
+        self.wait_for_lock(&file).await?;
+        Ok(file)
+    }
+
+    /// Wait up to `lock_timeout_ms` for an exclusive kernel lock on `file`
+    async fn wait_for_lock(&self, file: &fs::File) -> Result<(), StateError> {
+        let start_time = std::time::Instant::now();
+        while !file.try_lock_exclusive()? {
+            if start_time.elapsed().as_millis() as u64 >= self.lock_timeout_ms {
+                return Err(StateError::LockTimeout {
+                    timeout_ms: self.lock_timeout_ms,
+                });
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        Ok(())
+    }
+
+    /// Whether `file` is still the lock file at `path`, rather than one
+    /// removed or replaced since it was opened
+    async fn is_current_lock_file(&self, file: &fs::File, path: &Path) -> bool {
+        match (file.metadata().await, fs::metadata(path).await) {
+            (Ok(opened), Ok(current)) => same_file(&opened, &current),
+            _ => false,
+        }
+    }
+
+    /// Replace `stale`, a lock whose holder expired or crashed, with `lock_info`
+    ///
+    /// The new lock is written and locked under a temporary name and renamed
+    /// over the lock file, so readers never see it half written. The update
+    /// lock serializes takeovers; later ones find the lock no longer stale and
+    /// return `None`.
+    async fn steal_lock(
+        &self,
+        stale: &LockInfo,
+        lock_info: &LockInfo,
+    ) -> Result<Option<fs::File>, StateError> {
+        let _update = self.lock_for_update(&lock_info.pipeline_id).await?;
+        let lock_path = self.lock_file_path(&lock_info.pipeline_id);
+        match read_lock(&lock_path) {
+            Some(current)
+                if current.worker_id == stale.worker_id
+                    && current.locked_at == stale.locked_at
+                    && current.is_stale() => {}
+            _ => return Ok(None),
+        }
+
+        self.replace_lock_file(lock_info).await
+    }
+
+    /// Write `lock_info` to a temporary file, lock it and rename it over the lock file
+    ///
+    /// Readers never see a half-written lock, and the returned handle holds
+    /// the kernel lock on the new file. `None` if another writer holds the
+    /// temporary file. Callers hold the update lock.
+    async fn replace_lock_file(
+        &self,
+        lock_info: &LockInfo,
+    ) -> Result<Option<fs::File>, StateError> {
+        let lock_path = self.lock_file_path(&lock_info.pipeline_id);
+        let temp_path = lock_path.with_extension("lock.tmp");
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&temp_path)
+            .await?;
+        if !file.try_lock_exclusive()? {
+            return Ok(None);
+        }
+        file.write_all(&serde_json::to_vec(lock_info)?).await?;
+        file.sync_data().await?;
+        let intent = self
+            .log_intent(WalIntent::WriteLock {
+                lock: lock_info.clone(),
+            })
+            .await?;
+        let renamed = fs::rename(&temp_path, &lock_path).await;
+        self.commit_intent(intent).await?;
+        renamed?;
+        Ok(Some(file))
+    }
+
     /// Serialize state to bytes
     fn serialize_state(&self, state: &PipelineState) -> Result<Vec<u8>, StateError> {
         match self.format {
@@ -323,20 +1512,7 @@ impl FileBackend {
 
     /// Deserialize state from bytes
     fn deserialize_state(&self, data: &[u8]) -> Result<PipelineState, StateError> {
-        match self.format {
-            SerializationFormat::Json => serde_json::from_slice(data).map_err(StateError::from),
-            SerializationFormat::Yaml => {
-                let text = String::from_utf8(data.to_vec()).map_err(|e| {
-                    StateError::SerializationError {
-                        details: format!("Invalid UTF-8: {e}"),
-                    }
-                })?;
-                serde_yaml::from_str(&text).map_err(StateError::from)
-            }
-            SerializationFormat::Bincode => Err(StateError::SerializationError {
-                details: "Bincode format not yet implemented".to_string(),
-            }),
-        }
+        deserialize_state_bytes(&self.format, data)
     }
 
     /// Ensure directories exist
@@ -350,6 +1526,102 @@ impl FileBackend {
         Ok(())
     }
 
+    /// Whether the pipeline has a non-empty state file
+    async fn has_state(&self, pipeline_id: &str) -> bool {
+        fs::metadata(self.state_file_path(pipeline_id))
+            .await
+            .is_ok_and(|metadata| metadata.len() > 0)
+    }
+
+    /// Find files left behind by crashed workers, deleted pipelines and interrupted writes
+    async fn find_orphaned_files(&self) -> Result<Vec<OrphanedFile>, StateError> {
+        let mut orphans = Vec::new();
+        let orphan = |path: &Path, kind, size_bytes| OrphanedFile {
+            path: path.to_string_lossy().to_string(),
+            kind,
+            size_bytes,
+        };
+
+        let mut temp_dirs = vec![
+            self.base_path.join("states"),
+            self.base_path.join("locks"),
+            self.base_path.join("heartbeats"),
+            self.base_path.join("checkpoints"),
+            self.base_path.join("wal"),
+        ];
+        if let Ok(mut runs) = fs::read_dir(self.base_path.join("runs")).await {
+            while let Some(entry) = runs.next_entry().await? {
+                temp_dirs.push(entry.path());
+            }
+        }
+        let now = std::time::SystemTime::now();
+        for dir in temp_dirs {
+            let Ok(mut entries) = fs::read_dir(&dir).await else {
+                continue;
+            };
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                let Ok(metadata) = entry.metadata().await else {
+                    continue;
+                };
+                if is_temp_file(&path) {
+                    let stale = metadata
+                        .modified()
+                        .ok()
+                        .and_then(|modified| now.duration_since(modified).ok())
+                        .is_some_and(|age| age >= TEMP_FILE_GRACE);
+                    if stale {
+                        orphans.push(orphan(&path, OrphanKind::TempFile, metadata.len()));
+                    }
+                } else if metadata.len() == 0
+                    && path.parent() == Some(&self.base_path.join("states"))
+                {
+                    orphans.push(orphan(&path, OrphanKind::EmptyState, 0));
+                }
+            }
+        }
+
+        if let Ok(mut locks) = fs::read_dir(self.base_path.join("locks")).await {
+            while let Some(entry) = locks.next_entry().await? {
+                let path = entry.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("lock") {
+                    continue;
+                }
+                let Some(pipeline_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                // A live lease may belong to a run that hasn't saved its first state yet
+                let live = read_lock(&path)
+                    .and_then(|lock| lock.expires_at)
+                    .is_some_and(|expires_at| expires_at > Utc::now());
+                if !live && !self.has_state(pipeline_id).await {
+                    let size = entry.metadata().await.map_or(0, |m| m.len());
+                    orphans.push(orphan(&path, OrphanKind::Lock, size));
+                }
+            }
+        }
+
+        if let Ok(mut backups) = fs::read_dir(self.base_path.join("backups")).await {
+            while let Some(entry) = backups.next_entry().await? {
+                let path = entry.path();
+                let Some(pipeline_id) = path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                if !entry.file_type().await?.is_dir() || self.has_state(pipeline_id).await {
+                    continue;
+                }
+                let mut size = 0;
+                let mut files = fs::read_dir(&path).await?;
+                while let Some(file) = files.next_entry().await? {
+                    size += file.metadata().await.map_or(0, |m| m.len());
+                }
+                orphans.push(orphan(&path, OrphanKind::Backup, size));
+            }
+        }
+
+        Ok(orphans)
+    }
+
     /// Write data to file atomically (if enabled)
     async fn write_file_atomic(&self, path: &PathBuf, data: &[u8]) -> Result<(), StateError> {
         if self.atomic_writes {
@@ -462,26 +1734,18 @@ impl FileBackend {
 
     /// Record performance metrics
     async fn record_read_metrics(&self, duration_ms: f64, bytes_read: u64) {
-        let mut metrics = self.performance_metrics.write().await;
-        metrics.total_reads += 1;
-        metrics.total_bytes_read += bytes_read;
-
-        // Update running average
-        metrics.avg_read_time_ms = ((metrics.avg_read_time_ms * (metrics.total_reads - 1) as f64)
-            + duration_ms)
-            / metrics.total_reads as f64;
+        self.performance_metrics
+            .write()
+            .await
+            .record_read(duration_ms, bytes_read);
     }
 
     /// Record write performance metrics
     async fn record_write_metrics(&self, duration_ms: f64, bytes_written: u64) {
-        let mut metrics = self.performance_metrics.write().await;
-        metrics.total_writes += 1;
-        metrics.total_bytes_written += bytes_written;
-
-        // Update running average
-        metrics.avg_write_time_ms =
-            ((metrics.avg_write_time_ms * (metrics.total_writes - 1) as f64) + duration_ms)
-                / metrics.total_writes as f64;
+        self.performance_metrics
+            .write()
+            .await
+            .record_write(duration_ms, bytes_written);
     }
 
     /// Record serialization performance metrics
@@ -513,7 +1777,8 @@ impl StateBackend for FileBackend {
         let start_time = std::time::Instant::now();
 
         // Check cache first
-        if let Some(cached_state) = self.get_from_cache(pipeline_id).await {
+        if let Some(mut cached_state) = self.get_from_cache(pipeline_id).await {
+            self.apply_heartbeat(&mut cached_state).await;
             return Ok(cached_state);
         }
 
@@ -543,6 +1808,8 @@ impl StateBackend for FileBackend {
         // Store in cache for future use
         self.store_in_cache(pipeline_id, &state).await;
 
+        let mut state = state;
+        self.apply_heartbeat(&mut state).await;
         Ok(state)
     }
 
@@ -559,7 +1826,27 @@ impl StateBackend for FileBackend {
 
         let bytes_written = data.len() as u64;
 
-        self.write_file_atomic(&file_path, &data).await?;
+        let intent = self
+            .log_intent(WalIntent::SaveState {
+                state: Box::new(state.clone()),
+            })
+            .await?;
+        let run_path = self.run_file_path(&state.pipeline_id, &state.run_id);
+        let new_run = !fs::try_exists(&run_path).await.unwrap_or(false);
+        let written = async {
+            self.write_file_atomic(&file_path, &data).await?;
+
+            // Keep a copy per run so earlier runs can be loaded and compared
+            fs::create_dir_all(self.run_dir_path(&state.pipeline_id)).await?;
+            self.write_file_atomic(&run_path, &data).await
+        }
+        .await;
+        self.commit_intent(intent).await?;
+        written?;
+        if new_run {
+            // Older runs are only history, so failing to prune them doesn't fail the save
+            let _ = self.prune_runs(state).await;
+        }
 
         // Record performance metrics
         let total_duration = start_time.elapsed().as_millis() as f64;
@@ -570,25 +1857,86 @@ impl StateBackend for FileBackend {
         // Update cache with new state
         self.store_in_cache(&state.pipeline_id, state).await;
 
+        // `query_states` repairs the index from the state files, so a failed
+        // index update doesn't fail the save
+        if let Ok(metadata) = fs::metadata(&file_path).await {
+            let entry = IndexEntry {
+                summary: StateSummary::from(state),
+                stamp: FileStamp::of(&metadata),
+            };
+            let _ = self
+                .update_index(|index| {
+                    index.insert(state.pipeline_id.clone(), entry);
+                })
+                .await;
+        }
+
         Ok(())
     }
 
     async fn delete_state(&self, pipeline_id: &str) -> Result<(), StateError> {
-        let file_path = self.state_file_path(pipeline_id);
+        let intent = self
+            .log_intent(WalIntent::DeleteState {
+                pipeline_id: pipeline_id.to_string(),
+            })
+            .await?;
+
+        let removed = async {
+            let file_path = self.state_file_path(pipeline_id);
+            if file_path.exists() {
+                fs::remove_file(&file_path).await?;
+            }
+
+            // Also remove lock file if it exists
+            let lock_path = self.lock_file_path(pipeline_id);
+            if lock_path.exists() {
+                fs::remove_file(&lock_path).await?;
+            }
+
+            let heartbeat_path = self.heartbeat_file_path(pipeline_id);
+            if heartbeat_path.exists() {
+                fs::remove_file(&heartbeat_path).await?;
+            }
+
+            let archive_path = self.error_archive_path(pipeline_id);
+            if archive_path.exists() {
+                fs::remove_file(&archive_path).await?;
+            }
+
+            let checkpoint_path = self.resume_checkpoint_path(pipeline_id);
+            if checkpoint_path.exists() {
+                fs::remove_file(&checkpoint_path).await?;
+            }
+
+            let run_dir = self.run_dir_path(pipeline_id);
+            if run_dir.exists() {
+                fs::remove_dir_all(&run_dir).await?;
+            }
 
-        if file_path.exists() {
-            fs::remove_file(&file_path).await?;
+            let update_lock_path = self.update_lock_path(pipeline_id);
+            if update_lock_path.exists() {
+                fs::remove_file(&update_lock_path).await?;
+            }
+            Ok::<(), StateError>(())
         }
+        .await;
+        self.commit_intent(intent).await?;
+        removed?;
 
-        // Also remove lock file if it exists
-        let lock_path = self.lock_file_path(pipeline_id);
-        if lock_path.exists() {
-            fs::remove_file(&lock_path).await?;
+        let wal_path = self.wal_file_path(pipeline_id);
+        if wal_path.exists() {
+            fs::remove_file(&wal_path).await?;
         }
 
         // Invalidate cache entry
         self.invalidate_cache(pipeline_id).await;
 
+        let _ = self
+            .update_index(|index| {
+                index.remove(pipeline_id);
+            })
+            .await;
+
         Ok(())
     }
 
@@ -613,11 +1961,72 @@ impl StateBackend for FileBackend {
         Ok(pipeline_ids)
     }
 
+    async fn list_runs(&self, pipeline_id: &str) -> Result<Vec<String>, StateError> {
+        let run_dir = self.run_dir_path(pipeline_id);
+        if !run_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let extension = format!(".{}", format_extension(&self.format));
+        let mut run_ids = Vec::new();
+
+        let mut entries = fs::read_dir(&run_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if let Some(run_id) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_suffix(&extension))
+            {
+                run_ids.push(run_id.to_string());
+            }
+        }
+
+        run_ids.sort();
+        Ok(run_ids)
+    }
+
+    async fn load_run(&self, pipeline_id: &str, run_id: &str) -> Result<PipelineState, StateError> {
+        let file_path = self.run_file_path(pipeline_id, run_id);
+        if !file_path.exists() {
+            return Err(StateError::StateFileNotFound {
+                path: file_path.display().to_string(),
+            });
+        }
+
+        let data = fs::read(&file_path).await?;
+        self.deserialize_state(&data)
+    }
+
+    async fn atomic_update<'a>(
+        &self,
+        pipeline_id: &str,
+        expected_version: u64,
+        updater: Box<dyn Fn(PipelineState) -> PipelineState + Send + 'a>,
+    ) -> Result<PipelineState, StateError> {
+        let _update_lock = self.lock_for_update(pipeline_id).await?;
+
+        // Another process may have saved since this one cached the state
+        self.invalidate_cache(pipeline_id).await;
+        let current = self.load_state(pipeline_id).await?;
+        if current.version != expected_version {
+            return Err(StateError::VersionConflict {
+                expected: expected_version,
+                actual: current.version,
+            });
+        }
+
+        let mut updated = updater(current);
+        updated.version = expected_version + 1;
+        self.save_state(&updated).await?;
+        Ok(updated)
+    }
+
     async fn acquire_lock(
         &self,
         pipeline_id: &str,
         worker_id: &str,
         timeout_ms: u64,
+        ttl_ms: u64,
     ) -> Result<LockInfo, StateError> {
         self.ensure_directories().await?;
 
@@ -626,8 +2035,11 @@ impl StateBackend for FileBackend {
             pipeline_id: pipeline_id.to_string(),
             worker_id: worker_id.to_string(),
             locked_at: Utc::now(),
-            expires_at: Some(Utc::now() + chrono::Duration::milliseconds(timeout_ms as i64)),
+            expires_at: Some(Utc::now() + chrono::Duration::milliseconds(ttl_ms as i64)),
             lock_version: 1,
+            lease_ms: ttl_ms,
+            pid: Some(std::process::id()),
+            hostname: host_name().map(str::to_string),
         };
 
         // Try to acquire the lock with timeout
@@ -635,67 +2047,251 @@ impl StateBackend for FileBackend {
         let timeout_duration = std::time::Duration::from_millis(timeout_ms);
 
         loop {
-            // Try to create and lock the file
-            match tokio::fs::OpenOptions::new()
+            // Opened without truncating, so a failed attempt leaves the holder's metadata intact
+            let mut file = fs::OpenOptions::new()
                 .create(true)
+                .truncate(false)
                 .write(true)
-                .truncate(true)
                 .open(&lock_path)
                 .await
-            {
-                Ok(mut file) => {
-                    // Try to acquire exclusive lock
-                    match file.try_lock_exclusive() {
-                        Ok(true) => {
-                            // Write lock info to file
-                            let lock_data = serde_json::to_vec(&lock_info)?;
-                            file.write_all(&lock_data).await?;
-                            file.flush().await?;
-
-                            return Ok(lock_info);
-                        }
-                        Ok(false) | Err(_) => {
-                            // Lock is held by another process or other error
-                            if start_time.elapsed() >= timeout_duration {
-                                return Err(StateError::LockTimeout { timeout_ms });
-                            }
+                .map_err(|e| StateError::IoError {
+                    details: format!("Failed to open lock file: {e}"),
+                })?;
 
-                            // Short delay before retry
-                            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            let acquired = if file.try_lock_exclusive().unwrap_or(false) {
+                // Released or stolen between opening and locking the file
+                let replaced = !self.is_current_lock_file(&file, &lock_path).await;
+                match read_lock(&lock_path) {
+                    _ if replaced => None,
+                    // Left by a holder that did not keep the file locked, e.g. by WAL replay
+                    Some(existing) if !existing.is_stale() => None,
+                    _ => {
+                        let intent = self
+                            .log_intent(WalIntent::WriteLock {
+                                lock: lock_info.clone(),
+                            })
+                            .await?;
+                        let written = async {
+                            file.set_len(0).await?;
+                            file.write_all(&serde_json::to_vec(&lock_info)?).await?;
+                            file.sync_data().await
                         }
+                        .await;
+                        self.commit_intent(intent).await?;
+                        written?;
+                        Some(file)
                     }
                 }
-                Err(e) => {
-                    return Err(StateError::IoError {
-                        details: format!("Failed to open lock file: {e}"),
-                    });
+            } else {
+                // The holder keeps the file locked; take over only if it crashed or expired
+                match read_lock(&lock_path) {
+                    Some(existing) if existing.is_stale() => {
+                        self.steal_lock(&existing, &lock_info).await?
+                    }
+                    _ => None,
                 }
+            };
+
+            if let Some(file) = acquired {
+                self.held_locks
+                    .lock()
+                    .unwrap()
+                    .insert(pipeline_id.to_string(), (worker_id.to_string(), file));
+                return Ok(lock_info);
+            }
+
+            if start_time.elapsed() >= timeout_duration {
+                return Err(StateError::LockTimeout { timeout_ms });
             }
+
+            // Short delay before retry
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
         }
     }
 
     async fn release_lock(&self, pipeline_id: &str, worker_id: &str) -> Result<(), StateError> {
         let lock_path = self.lock_file_path(pipeline_id);
 
-        if !lock_path.exists() {
-            return Ok(()); // Lock already released
+        if lock_path.exists() {
+            // Verify the lock is owned by this worker
+            if let Some(lock_info) = self.is_locked(pipeline_id).await? {
+                if lock_info.worker_id != worker_id {
+                    return Err(StateError::LockAlreadyHeld {
+                        worker_id: lock_info.worker_id,
+                    });
+                }
+            }
+
+            // Remove the lock file
+            let intent = self
+                .log_intent(WalIntent::RemoveLock {
+                    pipeline_id: pipeline_id.to_string(),
+                })
+                .await?;
+            let removed = fs::remove_file(&lock_path).await;
+            self.commit_intent(intent).await?;
+            removed?;
         }
 
-        // Verify the lock is owned by this worker
-        if let Some(lock_info) = self.is_locked(pipeline_id).await? {
-            if lock_info.worker_id != worker_id {
-                return Err(StateError::LockAlreadyHeld {
-                    worker_id: lock_info.worker_id,
-                });
+        // Unlock only once the file is gone, so waiters reopen the path
+        let mut held_locks = self.held_locks.lock().unwrap();
+        if held_locks
+            .get(pipeline_id)
+            .is_some_and(|(holder, _)| holder == worker_id)
+        {
+            held_locks.remove(pipeline_id);
+        }
+
+        Ok(())
+    }
+
+    async fn renew_lock(&self, pipeline_id: &str, worker_id: &str) -> Result<LockInfo, StateError> {
+        let lost = || StateError::LockLost {
+            pipeline_id: pipeline_id.to_string(),
+            worker_id: worker_id.to_string(),
+        };
+        // Serialized with takeovers, so a renewal never overwrites a lock stolen meanwhile
+        let _update = self.lock_for_update(pipeline_id).await?;
+        let mut lock_info = match self.is_locked(pipeline_id).await? {
+            Some(lock_info) if lock_info.worker_id == worker_id => lock_info,
+            _ => return Err(lost()),
+        };
+
+        // Renamed into place rather than rewritten, so readers never see it truncated
+        lock_info.renew();
+        let file = self.replace_lock_file(&lock_info).await?.ok_or_else(lost)?;
+        self.held_locks
+            .lock()
+            .unwrap()
+            .insert(pipeline_id.to_string(), (worker_id.to_string(), file));
+        Ok(lock_info)
+    }
+
+    async fn touch_heartbeat(
+        &self,
+        pipeline_id: &str,
+        worker_id: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), StateError> {
+        if !self.state_file_path(pipeline_id).exists() {
+            return Err(StateError::PipelineNotFound {
+                pipeline_id: pipeline_id.to_string(),
+            });
+        }
+
+        // The state file is left alone; `load_state` merges the sidecar in
+        let path = self.heartbeat_file_path(pipeline_id);
+        fs::create_dir_all(self.base_path.join("heartbeats")).await?;
+        let data = serde_json::to_vec(&HeartbeatRecord {
+            worker_id: worker_id.to_string(),
+            timestamp,
+        })?;
+        // Heartbeats of overlapping tasks must not share a temp file
+        let temp_path = path.with_extension(format!("heartbeat.{}.tmp", Uuid::new_v4()));
+        fs::write(&temp_path, &data).await?;
+        fs::rename(&temp_path, &path).await?;
+        Ok(())
+    }
+
+    async fn query_states(&self, query: StateQuery) -> Result<Vec<StateSummary>, StateError> {
+        let mut matched = Vec::new();
+        for entry in self.current_index().await?.into_values() {
+            if !query.matches(&entry.summary) {
+                continue;
             }
+            // Heartbeats skip the save, and with it the index
+            let mut summary = entry.summary;
+            if let Some(heartbeat) = self.read_heartbeat(&summary.pipeline_id).await {
+                if heartbeat_applies(summary.worker_id.as_deref(), &heartbeat.worker_id)
+                    && heartbeat.timestamp > summary.last_heartbeat
+                {
+                    summary.last_heartbeat = heartbeat.timestamp;
+                }
+            }
+            matched.push(summary);
+        }
+        Ok(query.sort_and_limit(matched))
+    }
+
+    async fn reindex(&self) -> Result<usize, StateError> {
+        match fs::remove_file(self.index_path()).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
         }
+        Ok(self.current_index().await?.len())
+    }
 
-        // Remove the lock file
-        fs::remove_file(&lock_path).await?;
+    async fn append_error_archive(
+        &self,
+        pipeline_id: &str,
+        errors: &[ErrorRecord],
+    ) -> Result<(), StateError> {
+        if errors.is_empty() {
+            return Ok(());
+        }
 
+        let mut lines = Vec::new();
+        for error in errors {
+            serde_json::to_writer(&mut lines, error)?;
+            lines.push(b'\n');
+        }
+        fs::create_dir_all(self.base_path.join("errors")).await?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.error_archive_path(pipeline_id))
+            .await?;
+        // One write per batch, so concurrent appends don't interleave lines
+        file.write_all(&lines).await?;
+        file.sync_data().await?;
         Ok(())
     }
 
+    async fn load_error_archive(&self, pipeline_id: &str) -> Result<Vec<ErrorRecord>, StateError> {
+        let data = match fs::read_to_string(self.error_archive_path(pipeline_id)).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        // A line torn by a crash mid-append is skipped rather than failing the read
+        Ok(data
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    async fn save_resume_checkpoint(
+        &self,
+        pipeline_id: &str,
+        checkpoint: &ResumeCheckpoint,
+    ) -> Result<(), StateError> {
+        fs::create_dir_all(self.base_path.join("checkpoints")).await?;
+        self.write_file_atomic(
+            &self.resume_checkpoint_path(pipeline_id),
+            &serde_json::to_vec(checkpoint)?,
+        )
+        .await
+    }
+
+    async fn load_resume_checkpoint(
+        &self,
+        pipeline_id: &str,
+    ) -> Result<Option<ResumeCheckpoint>, StateError> {
+        match fs::read(self.resume_checkpoint_path(pipeline_id)).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete_resume_checkpoint(&self, pipeline_id: &str) -> Result<(), StateError> {
+        match fs::remove_file(self.resume_checkpoint_path(pipeline_id)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
     async fn is_locked(&self, pipeline_id: &str) -> Result<Option<LockInfo>, StateError> {
         let lock_path = self.lock_file_path(pipeline_id);
 
@@ -703,29 +2299,13 @@ impl StateBackend for FileBackend {
             return Ok(None);
         }
 
-        // Try to read the lock file
+        // Expired, crashed and unreadable locks are left in place: removing
+        // one could delete a lock taken over since it was read, and the next
+        // `acquire_lock` replaces it anyway
         match fs::read(&lock_path).await {
-            Ok(data) => {
-                match serde_json::from_slice::<LockInfo>(&data) {
-                    Ok(lock_info) => {
-                        // Check if lock has expired
-                        if let Some(expires_at) = lock_info.expires_at {
-                            if Utc::now() > expires_at {
-                                // Lock has expired, remove it
-                                let _ = fs::remove_file(&lock_path).await;
-                                return Ok(None);
-                            }
-                        }
-
-                        Ok(Some(lock_info))
-                    }
-                    Err(_) => {
-                        // Invalid lock file, remove it
-                        let _ = fs::remove_file(&lock_path).await;
-                        Ok(None)
-                    }
-                }
-            }
+            Ok(data) => Ok(serde_json::from_slice::<LockInfo>(&data)
+                .ok()
+                .filter(|lock_info| !lock_info.is_stale())),
             Err(_) => Ok(None),
         }
     }
@@ -734,8 +2314,16 @@ impl StateBackend for FileBackend {
         let lock_path = self.lock_file_path(pipeline_id);
 
         if lock_path.exists() {
-            fs::remove_file(&lock_path).await?;
+            let intent = self
+                .log_intent(WalIntent::RemoveLock {
+                    pipeline_id: pipeline_id.to_string(),
+                })
+                .await?;
+            let removed = fs::remove_file(&lock_path).await;
+            self.commit_intent(intent).await?;
+            removed?;
         }
+        self.held_locks.lock().unwrap().remove(pipeline_id);
 
         Ok(())
     }
@@ -776,6 +2364,7 @@ impl StateBackend for FileBackend {
                     response_time_ms,
                     error_message: None,
                     metrics,
+                    trend: None,
                 })
             }
             Err(e) => Ok(BackendHealth {
@@ -785,6 +2374,7 @@ impl StateBackend for FileBackend {
                 response_time_ms,
                 error_message: Some(e.to_string()),
                 metrics: HashMap::new(),
+                trend: None,
             }),
         }
     }
@@ -1046,7 +2636,7 @@ impl StateBackend for FileBackend {
         }
 
         // Sort by creation time, newest first
-        backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
         Ok(backups)
     }
 
@@ -1085,10 +2675,14 @@ impl StateBackend for FileBackend {
         let file_path = self.state_file_path(pipeline_id);
 
         if validation.corruption_detected {
-            // Try to restore from the most recent backup
+            // Restore from the most recent readable backup, skipping the copy
+            // of the corrupted file just taken above
             let backups = self.list_backups(pipeline_id).await?;
+            let latest_backup = backups.iter().find(|b| {
+                b.state_version > 0 && backup_id.as_deref() != Some(b.backup_id.as_str())
+            });
 
-            if let Some(latest_backup) = backups.first() {
+            if let Some(latest_backup) = latest_backup {
                 match self
                     .restore_state(pipeline_id, &latest_backup.backup_id)
                     .await
@@ -1280,10 +2874,7 @@ impl StateBackend for FileBackend {
             },
         );
 
-        performance_metrics.insert("total_reads".to_string(), metrics.total_reads as f64);
-        performance_metrics.insert("total_writes".to_string(), metrics.total_writes as f64);
-        performance_metrics.insert("avg_read_time_ms".to_string(), metrics.avg_read_time_ms);
-        performance_metrics.insert("avg_write_time_ms".to_string(), metrics.avg_write_time_ms);
+        metrics.insert_io_metrics(&mut performance_metrics);
         performance_metrics.insert(
             "avg_serialization_time_ms".to_string(),
             metrics.avg_serialization_time_ms,
@@ -1292,14 +2883,6 @@ impl StateBackend for FileBackend {
             "avg_deserialization_time_ms".to_string(),
             metrics.avg_deserialization_time_ms,
         );
-        performance_metrics.insert(
-            "total_bytes_read".to_string(),
-            metrics.total_bytes_read as f64,
-        );
-        performance_metrics.insert(
-            "total_bytes_written".to_string(),
-            metrics.total_bytes_written as f64,
-        );
 
         // Cache efficiency warnings
         let cache_hit_rate = performance_metrics.get("cache_hit_rate").unwrap_or(&0.0);
@@ -1345,6 +2928,9 @@ impl StateBackend for FileBackend {
         if let Ok(mut entries) = fs::read_dir(&states_dir).await {
             while let Some(entry) = entries.next_entry().await? {
                 let path = entry.path();
+                if is_temp_file(&path) {
+                    continue;
+                }
                 total_files_checked += 1;
 
                 // Extract pipeline ID from filename
@@ -1380,11 +2966,14 @@ impl StateBackend for FileBackend {
             }
         }
 
-        // Calculate overall health score
-        let total_issues = corrupted_files.len()
-            + missing_files.len()
-            + permission_errors.len()
-            + checksum_mismatches.len();
+        // Calculate overall health score from the share of files with any issue
+        let total_issues = corrupted_files
+            .iter()
+            .chain(&missing_files)
+            .chain(&permission_errors)
+            .chain(&checksum_mismatches)
+            .collect::<std::collections::HashSet<_>>()
+            .len();
 
         let overall_health = if total_files_checked == 0 {
             1.0
@@ -1406,6 +2995,14 @@ impl StateBackend for FileBackend {
             repair_recommendations.push("Consider full backup and restoration".to_string());
         }
 
+        let orphaned_files = self.find_orphaned_files().await?;
+        if !orphaned_files.is_empty() {
+            repair_recommendations.push(format!(
+                "Run 'oxide_flow state gc' to remove {} orphaned files",
+                orphaned_files.len()
+            ));
+        }
+
         Ok(IntegrityReport {
             total_files_checked: total_files_checked as u64,
             corrupted_files,
@@ -1414,24 +3011,219 @@ impl StateBackend for FileBackend {
             checksum_mismatches,
             repair_recommendations,
             overall_health,
+            orphaned_files,
         })
     }
+
+    async fn watch_all(&self) -> Result<broadcast::Receiver<StateChangeEvent>, StateError> {
+        use notify::Watcher;
+
+        self.ensure_directories().await?;
+
+        let mut watch = self.watch.lock().map_err(|_| StateError::InvalidState {
+            details: "State watcher mutex poisoned".to_string(),
+        })?;
+        if let Some(existing) = watch.as_ref() {
+            return Ok(existing.sender.subscribe());
+        }
+
+        let (sender, receiver) = broadcast::channel(WATCH_CHANNEL_CAPACITY);
+        let mut tracker = WatchTracker {
+            states_dir: self.base_path.join("states"),
+            locks_dir: self.base_path.join("locks"),
+            format: self.format.clone(),
+            states: HashMap::new(),
+            locks: HashMap::new(),
+            sender: sender.clone(),
+        };
+        tracker.scan_existing();
+
+        let watcher_error = |e: notify::Error| StateError::IoError {
+            details: format!("Failed to watch state directory: {e}"),
+        };
+        let mut watcher =
+            notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+                if let Ok(event) = result {
+                    tracker.handle(event);
+                }
+            })
+            .map_err(watcher_error)?;
+        watcher
+            .watch(
+                &self.base_path.join("states"),
+                notify::RecursiveMode::NonRecursive,
+            )
+            .map_err(watcher_error)?;
+        watcher
+            .watch(
+                &self.base_path.join("locks"),
+                notify::RecursiveMode::NonRecursive,
+            )
+            .map_err(watcher_error)?;
+
+        *watch = Some(DirectoryWatch {
+            _watcher: watcher,
+            sender,
+        });
+        Ok(receiver)
+    }
+}
+
+/// Per-run states of each pipeline, keyed by pipeline ID then run ID
+type RunStates = tokio::sync::RwLock<HashMap<String, HashMap<String, PipelineState>>>;
+
+/// File in its directory that a persistent [`MemoryBackend`] loads and flushes
+const MEMORY_STATE_FILE: &str = "memory_state.json";
+
+/// What a persistent [`MemoryBackend`] keeps across restarts; locks die with the process
+#[derive(Default, Serialize, Deserialize)]
+struct MemorySnapshot {
+    #[serde(default)]
+    states: HashMap<String, PipelineState>,
+    #[serde(default)]
+    runs: HashMap<String, HashMap<String, PipelineState>>,
+    #[serde(default)]
+    checkpoints: HashMap<String, ResumeCheckpoint>,
 }
 
 /// Memory-based backend for testing and development
 pub struct MemoryBackend {
     states: std::sync::Arc<tokio::sync::RwLock<HashMap<String, PipelineState>>>,
+    runs: std::sync::Arc<RunStates>,
+    checkpoints: std::sync::Arc<tokio::sync::RwLock<HashMap<String, ResumeCheckpoint>>>,
     locks: std::sync::Arc<tokio::sync::RwLock<HashMap<String, LockInfo>>>,
+    events: broadcast::Sender<StateChangeEvent>,
+    /// Reads and writes, counted like the file backend's; sizes are estimated
+    performance_metrics: std::sync::Arc<tokio::sync::RwLock<PerformanceMetrics>>,
+    /// Directory of `memory_state.json` for a persistent backend
+    persist_dir: Option<PathBuf>,
+    /// Serializes snapshot writes so two saves don't share the temp file
+    persist_lock: tokio::sync::Mutex<()>,
 }
 
 impl MemoryBackend {
     /// Create a new memory backend
     pub fn new() -> Self {
+        Self::from_snapshot(MemorySnapshot::default(), None)
+    }
+
+    /// Create a memory backend that survives restarts
+    ///
+    /// States and run history are loaded from `{path}/memory_state.json` when
+    /// it exists, and written back after every change and on drop.
+    pub fn new_persistent(path: PathBuf) -> Result<Self, StateError> {
+        let snapshot = match std::fs::read(path.join(MEMORY_STATE_FILE)) {
+            Ok(data) => serde_json::from_slice(&data)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => MemorySnapshot::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self::from_snapshot(snapshot, Some(path)))
+    }
+
+    fn from_snapshot(snapshot: MemorySnapshot, persist_dir: Option<PathBuf>) -> Self {
+        let (events, _) = broadcast::channel(WATCH_CHANNEL_CAPACITY);
         Self {
-            states: std::sync::Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            states: std::sync::Arc::new(tokio::sync::RwLock::new(snapshot.states)),
+            runs: std::sync::Arc::new(tokio::sync::RwLock::new(snapshot.runs)),
+            checkpoints: std::sync::Arc::new(tokio::sync::RwLock::new(snapshot.checkpoints)),
             locks: std::sync::Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            events,
+            performance_metrics: std::sync::Arc::new(tokio::sync::RwLock::new(
+                PerformanceMetrics::default(),
+            )),
+            persist_dir,
+            persist_lock: tokio::sync::Mutex::new(()),
         }
     }
+
+    /// Write states and run history to `memory_state.json`, if the backend is persistent
+    ///
+    /// Doesn't wait for in-flight writes, so it can be called from `Drop`; a
+    /// flush that races one fails with `StateError::BackendError` and can be
+    /// retried.
+    pub fn flush(&self) -> Result<(), StateError> {
+        let Some(dir) = &self.persist_dir else {
+            return Ok(());
+        };
+        let busy = |_| StateError::BackendError {
+            details: "memory backend is being written to".to_string(),
+        };
+        let snapshot = MemorySnapshot {
+            states: self.states.try_read().map_err(busy)?.clone(),
+            runs: self.runs.try_read().map_err(busy)?.clone(),
+            checkpoints: self.checkpoints.try_read().map_err(busy)?.clone(),
+        };
+        let _guard = self.persist_lock.try_lock().map_err(busy)?;
+        write_memory_snapshot(dir, &snapshot)
+    }
+
+    /// Write the current snapshot after a change, waiting for other writers
+    ///
+    /// Called by every mutating operation once its own guards are released,
+    /// so a crash loses at most the change in flight.
+    async fn persist(&self) -> Result<(), StateError> {
+        let Some(dir) = &self.persist_dir else {
+            return Ok(());
+        };
+        let _guard = self.persist_lock.lock().await;
+        let snapshot = MemorySnapshot {
+            states: self.states.read().await.clone(),
+            runs: self.runs.read().await.clone(),
+            checkpoints: self.checkpoints.read().await.clone(),
+        };
+        write_memory_snapshot(dir, &snapshot)
+    }
+
+    async fn record_read(&self, start_time: std::time::Instant, state: &PipelineState) {
+        self.performance_metrics.write().await.record_read(
+            elapsed_ms(start_time),
+            state.estimated_memory_usage() as u64,
+        );
+    }
+
+    async fn record_write(&self, start_time: std::time::Instant, state: &PipelineState) {
+        self.performance_metrics.write().await.record_write(
+            elapsed_ms(start_time),
+            state.estimated_memory_usage() as u64,
+        );
+    }
+
+    fn publish(&self, pipeline_id: &str, event: StateEvent) {
+        // No subscribers is not an error; events are simply dropped
+        let _ = self.events.send(StateChangeEvent::new(pipeline_id, event));
+    }
+}
+
+/// Record `state` as its run's latest state, deleting the least recently
+/// updated runs beyond `limits.max_saved_runs`
+fn insert_run(runs: &mut HashMap<String, PipelineState>, state: &PipelineState) {
+    runs.insert(state.run_id.clone(), state.clone());
+    let excess = runs
+        .len()
+        .saturating_sub(state.limits.max_saved_runs.max(1));
+    if excess == 0 {
+        return;
+    }
+    let mut older: Vec<(DateTime<Utc>, String)> = runs
+        .values()
+        .filter(|run| run.run_id != state.run_id)
+        .map(|run| (run.metadata.updated_at, run.run_id.clone()))
+        .collect();
+    older.sort();
+    for (_, run_id) in older.into_iter().take(excess) {
+        runs.remove(&run_id);
+    }
+}
+
+/// Atomically replace `{dir}/memory_state.json` with `snapshot`
+fn write_memory_snapshot(dir: &Path, snapshot: &MemorySnapshot) -> Result<(), StateError> {
+    let data = serde_json::to_vec_pretty(snapshot)?;
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(MEMORY_STATE_FILE);
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, data)?;
+    std::fs::rename(&temp_path, &path)?;
+    Ok(())
 }
 
 impl Default for MemoryBackend {
@@ -1440,33 +3232,77 @@ impl Default for MemoryBackend {
     }
 }
 
+impl Drop for MemoryBackend {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            log::error!("Failed to persist memory backend state: {e}");
+        }
+    }
+}
+
 #[async_trait]
 impl StateBackend for MemoryBackend {
     async fn load_state(&self, pipeline_id: &str) -> Result<PipelineState, StateError> {
-        let states = self.states.read().await;
-
-        states
+        let start_time = std::time::Instant::now();
+        let state = self
+            .states
+            .read()
+            .await
             .get(pipeline_id)
             .cloned()
             .ok_or_else(|| StateError::PipelineNotFound {
                 pipeline_id: pipeline_id.to_string(),
-            })
+            })?;
+        self.record_read(start_time, &state).await;
+        Ok(state)
     }
 
     async fn save_state(&self, state: &PipelineState) -> Result<(), StateError> {
+        let start_time = std::time::Instant::now();
         let mut states = self.states.write().await;
-        states.insert(state.pipeline_id.clone(), state.clone());
-        Ok(())
+        insert_run(
+            self.runs
+                .write()
+                .await
+                .entry(state.pipeline_id.clone())
+                .or_default(),
+            state,
+        );
+        let event = match states.insert(state.pipeline_id.clone(), state.clone()) {
+            None => StateEvent::Created,
+            Some(previous) => StateEvent::Updated {
+                old_version: previous.version,
+                new_version: state.version,
+            },
+        };
+        drop(states);
+        self.publish(&state.pipeline_id, event);
+        self.record_write(start_time, state).await;
+        self.persist().await
     }
 
     async fn delete_state(&self, pipeline_id: &str) -> Result<(), StateError> {
         let mut states = self.states.write().await;
         let mut locks = self.locks.write().await;
 
-        states.remove(pipeline_id);
-        locks.remove(pipeline_id);
+        let removed_state = states.remove(pipeline_id);
+        self.runs.write().await.remove(pipeline_id);
+        self.checkpoints.write().await.remove(pipeline_id);
+        if let Some(lock) = locks.remove(pipeline_id) {
+            self.publish(
+                pipeline_id,
+                StateEvent::LockReleased {
+                    worker_id: lock.worker_id,
+                },
+            );
+        }
+        if removed_state.is_some() {
+            self.publish(pipeline_id, StateEvent::Deleted);
+        }
+        drop(states);
+        drop(locks);
 
-        Ok(())
+        self.persist().await
     }
 
     async fn list_pipelines(&self) -> Result<Vec<String>, StateError> {
@@ -1476,11 +3312,84 @@ impl StateBackend for MemoryBackend {
         Ok(pipeline_ids)
     }
 
+    async fn list_runs(&self, pipeline_id: &str) -> Result<Vec<String>, StateError> {
+        let runs = self.runs.read().await;
+        let mut run_ids: Vec<String> = runs
+            .get(pipeline_id)
+            .map(|pipeline_runs| pipeline_runs.keys().cloned().collect())
+            .unwrap_or_default();
+        run_ids.sort();
+        Ok(run_ids)
+    }
+
+    async fn load_run(&self, pipeline_id: &str, run_id: &str) -> Result<PipelineState, StateError> {
+        let start_time = std::time::Instant::now();
+        let state = self
+            .runs
+            .read()
+            .await
+            .get(pipeline_id)
+            .and_then(|pipeline_runs| pipeline_runs.get(run_id))
+            .cloned()
+            .ok_or_else(|| StateError::StateFileNotFound {
+                path: format!("{pipeline_id}/{run_id}"),
+            })?;
+        self.record_read(start_time, &state).await;
+        Ok(state)
+    }
+
+    async fn atomic_update<'a>(
+        &self,
+        pipeline_id: &str,
+        expected_version: u64,
+        updater: Box<dyn Fn(PipelineState) -> PipelineState + Send + 'a>,
+    ) -> Result<PipelineState, StateError> {
+        let start_time = std::time::Instant::now();
+        let mut states = self.states.write().await;
+        let current =
+            states
+                .get(pipeline_id)
+                .cloned()
+                .ok_or_else(|| StateError::PipelineNotFound {
+                    pipeline_id: pipeline_id.to_string(),
+                })?;
+        if current.version != expected_version {
+            return Err(StateError::VersionConflict {
+                expected: expected_version,
+                actual: current.version,
+            });
+        }
+
+        let mut updated = updater(current);
+        updated.version = expected_version + 1;
+        insert_run(
+            self.runs
+                .write()
+                .await
+                .entry(updated.pipeline_id.clone())
+                .or_default(),
+            &updated,
+        );
+        states.insert(updated.pipeline_id.clone(), updated.clone());
+        drop(states);
+        self.publish(
+            &updated.pipeline_id,
+            StateEvent::Updated {
+                old_version: expected_version,
+                new_version: updated.version,
+            },
+        );
+        self.record_write(start_time, &updated).await;
+        self.persist().await?;
+        Ok(updated)
+    }
+
     async fn acquire_lock(
         &self,
         pipeline_id: &str,
         worker_id: &str,
         timeout_ms: u64,
+        ttl_ms: u64,
     ) -> Result<LockInfo, StateError> {
         let start_time = std::time::Instant::now();
         let timeout_duration = std::time::Duration::from_millis(timeout_ms);
@@ -1514,13 +3423,20 @@ impl StateBackend for MemoryBackend {
                     pipeline_id: pipeline_id.to_string(),
                     worker_id: worker_id.to_string(),
                     locked_at: Utc::now(),
-                    expires_at: Some(
-                        Utc::now() + chrono::Duration::milliseconds(timeout_ms as i64),
-                    ),
+                    expires_at: Some(Utc::now() + chrono::Duration::milliseconds(ttl_ms as i64)),
                     lock_version: 1,
+                    lease_ms: ttl_ms,
+                    pid: Some(std::process::id()),
+                    hostname: host_name().map(str::to_string),
                 };
 
                 locks.insert(pipeline_id.to_string(), lock_info.clone());
+                self.publish(
+                    pipeline_id,
+                    StateEvent::LockAcquired {
+                        worker_id: worker_id.to_string(),
+                    },
+                );
                 return Ok(lock_info);
             }
         }
@@ -1537,8 +3453,109 @@ impl StateBackend for MemoryBackend {
             }
         }
 
-        locks.remove(pipeline_id);
-        Ok(())
+        if locks.remove(pipeline_id).is_some() {
+            self.publish(
+                pipeline_id,
+                StateEvent::LockReleased {
+                    worker_id: worker_id.to_string(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    async fn renew_lock(&self, pipeline_id: &str, worker_id: &str) -> Result<LockInfo, StateError> {
+        let mut locks = self.locks.write().await;
+
+        match locks.get_mut(pipeline_id) {
+            Some(lock_info)
+                if lock_info.worker_id == worker_id
+                    && lock_info
+                        .expires_at
+                        .is_none_or(|expires_at| Utc::now() <= expires_at) =>
+            {
+                lock_info.renew();
+                Ok(lock_info.clone())
+            }
+            _ => Err(StateError::LockLost {
+                pipeline_id: pipeline_id.to_string(),
+                worker_id: worker_id.to_string(),
+            }),
+        }
+    }
+
+    async fn touch_heartbeat(
+        &self,
+        pipeline_id: &str,
+        worker_id: &str,
+        timestamp: DateTime<Utc>,
+    ) -> Result<(), StateError> {
+        let mut states = self.states.write().await;
+        let state = states
+            .get_mut(pipeline_id)
+            .ok_or_else(|| StateError::PipelineNotFound {
+                pipeline_id: pipeline_id.to_string(),
+            })?;
+        if heartbeat_applies(state.worker_id.as_deref(), worker_id)
+            && timestamp > state.last_heartbeat
+        {
+            state.last_heartbeat = timestamp;
+        }
+        drop(states);
+        self.persist().await
+    }
+
+    async fn query_states(&self, query: StateQuery) -> Result<Vec<StateSummary>, StateError> {
+        let matched = self
+            .states
+            .read()
+            .await
+            .values()
+            .map(StateSummary::from)
+            .filter(|summary| query.matches(summary))
+            .collect();
+        Ok(query.sort_and_limit(matched))
+    }
+
+    async fn reindex(&self) -> Result<usize, StateError> {
+        Ok(self.states.read().await.len())
+    }
+
+    async fn append_error_archive(
+        &self,
+        _pipeline_id: &str,
+        _errors: &[ErrorRecord],
+    ) -> Result<(), StateError> {
+        // Memory states are throwaway, so evicted errors are simply dropped
+        Ok(())
+    }
+
+    async fn load_error_archive(&self, _pipeline_id: &str) -> Result<Vec<ErrorRecord>, StateError> {
+        Ok(Vec::new())
+    }
+
+    async fn save_resume_checkpoint(
+        &self,
+        pipeline_id: &str,
+        checkpoint: &ResumeCheckpoint,
+    ) -> Result<(), StateError> {
+        self.checkpoints
+            .write()
+            .await
+            .insert(pipeline_id.to_string(), checkpoint.clone());
+        self.persist().await
+    }
+
+    async fn load_resume_checkpoint(
+        &self,
+        pipeline_id: &str,
+    ) -> Result<Option<ResumeCheckpoint>, StateError> {
+        Ok(self.checkpoints.read().await.get(pipeline_id).cloned())
+    }
+
+    async fn delete_resume_checkpoint(&self, pipeline_id: &str) -> Result<(), StateError> {
+        self.checkpoints.write().await.remove(pipeline_id);
+        self.persist().await
     }
 
     async fn is_locked(&self, pipeline_id: &str) -> Result<Option<LockInfo>, StateError> {
@@ -1561,7 +3578,14 @@ impl StateBackend for MemoryBackend {
 
     async fn force_release_lock(&self, pipeline_id: &str) -> Result<(), StateError> {
         let mut locks = self.locks.write().await;
-        locks.remove(pipeline_id);
+        if let Some(lock) = locks.remove(pipeline_id) {
+            self.publish(
+                pipeline_id,
+                StateEvent::LockReleased {
+                    worker_id: lock.worker_id,
+                },
+            );
+        }
         Ok(())
     }
 
@@ -1586,6 +3610,7 @@ impl StateBackend for MemoryBackend {
             response_time_ms,
             error_message: None,
             metrics,
+            trend: None,
         })
     }
 
@@ -1772,11 +3797,7 @@ impl StateBackend for MemoryBackend {
             }
         }
 
-        let average_state_size_bytes = if total_states > 0 {
-            total_memory as u64 / total_states
-        } else {
-            0
-        };
+        let average_state_size_bytes = (total_memory as u64).checked_div(total_states).unwrap_or(0);
 
         let mut performance_metrics = HashMap::new();
         performance_metrics.insert(
@@ -1787,6 +3808,10 @@ impl StateBackend for MemoryBackend {
             "avg_state_size_bytes".to_string(),
             average_state_size_bytes as f64,
         );
+        self.performance_metrics
+            .read()
+            .await
+            .insert_io_metrics(&mut performance_metrics);
 
         Ok(BackendDiagnostics {
             backend_type: "memory".to_string(),
@@ -1835,8 +3860,13 @@ impl StateBackend for MemoryBackend {
             checksum_mismatches: Vec::new(), // Memory backend doesn't use checksums
             repair_recommendations,
             overall_health,
+            orphaned_files: Vec::new(), // Memory backend has no files to leave behind
         })
     }
+
+    async fn watch_all(&self) -> Result<broadcast::Receiver<StateChangeEvent>, StateError> {
+        Ok(self.events.subscribe())
+    }
 }
 
 #[cfg(test)]
@@ -1845,6 +3875,47 @@ mod tests {
     use crate::state::types::PipelineState;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn test_persistent_memory_backend_survives_restart() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_path_buf();
+
+        let backend = MemoryBackend::new_persistent(path.clone()).unwrap();
+        backend
+            .save_state(&PipelineState::new("kept".to_string(), "run_1".to_string()))
+            .await
+            .unwrap();
+        backend
+            .acquire_lock("kept", "worker_1", 1000, 60000)
+            .await
+            .unwrap();
+        drop(backend);
+        assert!(path.join(MEMORY_STATE_FILE).exists());
+
+        let restarted = MemoryBackend::new_persistent(path.clone()).unwrap();
+        assert_eq!(restarted.load_state("kept").await.unwrap().run_id, "run_1");
+        assert_eq!(restarted.list_runs("kept").await.unwrap(), ["run_1"]);
+        assert!(restarted.is_locked("kept").await.unwrap().is_none());
+
+        // Each change is written as it happens, without waiting for drop
+        restarted.delete_state("kept").await.unwrap();
+        let reloaded = MemoryBackend::new_persistent(path.clone()).unwrap();
+        assert!(reloaded.list_pipelines().await.unwrap().is_empty());
+        restarted
+            .save_state(&PipelineState::new(
+                "later".to_string(),
+                "run_2".to_string(),
+            ))
+            .await
+            .unwrap();
+        let reloaded = MemoryBackend::new_persistent(path).unwrap();
+        assert_eq!(reloaded.load_state("later").await.unwrap().run_id, "run_2");
+        std::mem::forget(restarted);
+
+        // A plain memory backend never touches the disk
+        MemoryBackend::new().flush().unwrap();
+    }
+
     #[tokio::test]
     async fn test_memory_backend_basic_operations() {
         let backend = MemoryBackend::new();
@@ -1879,7 +3950,7 @@ mod tests {
 
         // Acquire lock
         let lock_info = backend
-            .acquire_lock("test_pipeline", "worker_1", 5000)
+            .acquire_lock("test_pipeline", "worker_1", 5000, 5000)
             .await
             .unwrap();
 
@@ -1891,7 +3962,9 @@ mod tests {
         assert!(is_locked.is_some());
 
         // Try to acquire lock with different worker (should fail)
-        let lock_result = backend.acquire_lock("test_pipeline", "worker_2", 100).await;
+        let lock_result = backend
+            .acquire_lock("test_pipeline", "worker_2", 100, 100)
+            .await;
         assert!(matches!(lock_result, Err(StateError::LockTimeout { .. })));
 
         // Release lock
@@ -1905,6 +3978,57 @@ mod tests {
         assert!(is_locked.is_none());
     }
 
+    #[tokio::test]
+    async fn test_export_all_and_import_all() {
+        let source = MemoryBackend::new();
+        for pipeline_id in ["orders", "customers"] {
+            let mut state = PipelineState::new(pipeline_id.to_string(), "run_1".to_string());
+            state.records_processed = 10;
+            source.save_state(&state).await.unwrap();
+        }
+        source
+            .acquire_lock("orders", "worker_1", 1000, 60_000)
+            .await
+            .unwrap();
+        let archive = source.export_all().await.unwrap();
+
+        let exported: StateArchive =
+            serde_json::from_reader(flate2::read::GzDecoder::new(archive.as_slice())).unwrap();
+        assert_eq!(exported.states.len(), 2);
+        assert_eq!(exported.locks.len(), 1);
+        assert_eq!(exported.locks[0].worker_id, "worker_1");
+
+        let temp_dir = TempDir::new().unwrap();
+        let target = file_backend(temp_dir.path());
+        let mut existing = PipelineState::new("orders".to_string(), "local".to_string());
+        existing.records_processed = 1;
+        target.save_state(&existing).await.unwrap();
+
+        let result = target.import_all(&archive, false).await.unwrap();
+        assert_eq!((result.imported_count, result.skipped_count), (1, 1));
+        assert!(result.failed_ids.is_empty());
+        assert_eq!(target.load_state("orders").await.unwrap().run_id, "local");
+        assert_eq!(
+            target
+                .load_state("customers")
+                .await
+                .unwrap()
+                .records_processed,
+            10
+        );
+        // Locks stay with the store they were taken in
+        assert!(target.is_locked("orders").await.unwrap().is_none());
+
+        let result = target.import_all(&archive, true).await.unwrap();
+        assert_eq!((result.imported_count, result.skipped_count), (2, 0));
+        assert_eq!(target.load_state("orders").await.unwrap().run_id, "run_1");
+
+        assert!(matches!(
+            target.import_all(b"not an archive", true).await,
+            Err(StateError::SerializationError { .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_file_backend_configuration() {
         let temp_dir = TempDir::new().unwrap();
@@ -1914,6 +4038,7 @@ mod tests {
             format: SerializationFormat::Json,
             atomic_writes: true,
             lock_timeout_ms: 5000,
+            write_ahead_log: false,
         };
 
         let backend = FileBackend::new(config).unwrap();
@@ -1931,6 +4056,7 @@ mod tests {
             format: SerializationFormat::Json,
             atomic_writes: true,
             lock_timeout_ms: 5000,
+            write_ahead_log: false,
         };
 
         let backend = FileBackend::new(config).unwrap();
@@ -1952,9 +4078,641 @@ mod tests {
         let pipelines = backend.list_pipelines().await.unwrap();
         assert_eq!(pipelines, vec!["test_pipeline"]);
 
+        // Every run keeps its own copy
+        let mut next_run = PipelineState::new("test_pipeline".to_string(), "run_456".to_string());
+        next_run.records_processed = 7;
+        backend.save_state(&next_run).await.unwrap();
+        assert_eq!(
+            backend.list_runs("test_pipeline").await.unwrap(),
+            vec!["run_123", "run_456"]
+        );
+        let first_run = backend.load_run("test_pipeline", "run_123").await.unwrap();
+        assert_eq!(first_run.records_processed, 0);
+        assert_eq!(
+            backend.load_state("test_pipeline").await.unwrap().run_id,
+            "run_456"
+        );
+        assert!(backend.load_run("test_pipeline", "run_789").await.is_err());
+
         // Delete state
         assert!(backend.delete_state("test_pipeline").await.is_ok());
         assert!(!state_file.exists());
+        assert!(backend.list_runs("test_pipeline").await.unwrap().is_empty());
+    }
+
+    fn file_backend(base_path: &Path) -> FileBackend {
+        FileBackend::new(BackendConfig::File {
+            base_path: base_path.to_path_buf(),
+            format: SerializationFormat::Json,
+            atomic_writes: true,
+            lock_timeout_ms: 1000,
+            write_ahead_log: false,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_run_history_keeps_the_latest_runs() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = file_backend(temp_dir.path());
+        let memory = MemoryBackend::new();
+        let backends: [&dyn StateBackend; 2] = [&file, &memory];
+
+        for backend in backends {
+            for run in 1..=4 {
+                let mut state = PipelineState::new("orders".to_string(), format!("run_{run}"));
+                state.limits.max_saved_runs = 2;
+                backend.save_state(&state).await.unwrap();
+                // Saving a run again doesn't count as another run
+                backend.save_state(&state).await.unwrap();
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+            assert_eq!(
+                backend.list_runs("orders").await.unwrap(),
+                ["run_3", "run_4"]
+            );
+        }
+    }
+
+    /// 50 states: `pipe_NN` for even and `etl_NN` for odd NN, one minute of
+    /// `updated_at` apart, cycling through the statuses, `env` tags and
+    /// recorded environments
+    fn fifty_states() -> Vec<PipelineState> {
+        use crate::state::types::PipelineStatus;
+
+        let base = Utc::now() - chrono::Duration::hours(2);
+        (0..50)
+            .map(|i| {
+                let prefix = if i % 2 == 0 { "pipe" } else { "etl" };
+                let mut state = PipelineState::new(format!("{prefix}_{i:02}"), format!("run_{i}"));
+                state.status = match i % 5 {
+                    0 => PipelineStatus::Pending,
+                    1 => PipelineStatus::Running { started_at: base },
+                    2 => PipelineStatus::Completed { completed_at: base },
+                    3 => PipelineStatus::Failed {
+                        failed_at: base,
+                        error: "boom".to_string(),
+                    },
+                    _ => PipelineStatus::Paused { paused_at: base },
+                };
+                state.metadata.updated_at = base + chrono::Duration::minutes(i);
+                state.records_processed = (i as u64 * 37) % 50;
+                let env = if i % 3 == 0 { "prod" } else { "dev" };
+                state
+                    .metadata
+                    .tags
+                    .insert("env".to_string(), env.to_string());
+                if i % 7 == 0 {
+                    state
+                        .metadata
+                        .tags
+                        .insert("nightly".to_string(), String::new());
+                }
+                let environment = if i % 4 == 0 { "prod" } else { "staging" };
+                state.metadata.environment = Some(environment.to_string());
+                state
+            })
+            .collect()
+    }
+
+    fn ids(summaries: &[StateSummary]) -> Vec<&str> {
+        summaries.iter().map(|s| s.pipeline_id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_query_states_filters() {
+        use crate::state::types::StatusKind;
+
+        let temp_dir = TempDir::new().unwrap();
+        let backends: [Box<dyn StateBackend>; 2] = [
+            Box::new(file_backend(temp_dir.path())),
+            Box::new(MemoryBackend::new()),
+        ];
+        let states = fifty_states();
+        let base = states[0].metadata.updated_at;
+        // Expected IDs, in the default pipeline ID order
+        let expect = |keep: &dyn Fn(usize) -> bool| {
+            let mut ids: Vec<String> = (0..50)
+                .filter(|&i| keep(i))
+                .map(|i| states[i].pipeline_id.clone())
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        for backend in &backends {
+            for state in &states {
+                backend.save_state(state).await.unwrap();
+            }
+
+            let all = backend.query_states(StateQuery::default()).await.unwrap();
+            assert_eq!(ids(&all), expect(&|_| true));
+
+            let query = StateQuery {
+                statuses: vec![StatusKind::Failed, StatusKind::Paused],
+                ..Default::default()
+            };
+            let found = backend.query_states(query).await.unwrap();
+            assert_eq!(ids(&found), expect(&|i| i % 5 >= 3));
+
+            let query = StateQuery {
+                name_prefix: Some("etl_".to_string()),
+                tags: HashMap::from([("env".to_string(), Some("prod".to_string()))]),
+                ..Default::default()
+            };
+            let found = backend.query_states(query).await.unwrap();
+            assert_eq!(ids(&found), expect(&|i| i % 2 == 1 && i % 3 == 0));
+
+            // A tag without a value matches any value, including none
+            let query = StateQuery {
+                tags: HashMap::from([("env".to_string(), None), ("nightly".to_string(), None)]),
+                ..Default::default()
+            };
+            let found = backend.query_states(query).await.unwrap();
+            assert_eq!(ids(&found), expect(&|i| i % 7 == 0));
+
+            let query = StateQuery {
+                environment: Some("prod".to_string()),
+                tags: HashMap::from([("env".to_string(), Some("dev".to_string()))]),
+                ..Default::default()
+            };
+            let found = backend.query_states(query).await.unwrap();
+            assert_eq!(ids(&found), expect(&|i| i % 4 == 0 && i % 3 != 0));
+            assert!(found
+                .iter()
+                .all(|s| s.environment.as_deref() == Some("prod")));
+
+            let query = StateQuery {
+                statuses: vec![StatusKind::Running],
+                updated_after: Some(base + chrono::Duration::seconds(10 * 60 + 30)),
+                updated_before: Some(base + chrono::Duration::seconds(40 * 60 + 30)),
+                ..Default::default()
+            };
+            let found = backend.query_states(query).await.unwrap();
+            assert_eq!(
+                ids(&found),
+                expect(&|i| i % 5 == 1 && (11..=40).contains(&i))
+            );
+            assert!(found.iter().all(|s| s.run_id.starts_with("run_")));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_pipelines_yields_every_state() {
+        let temp_dir = TempDir::new().unwrap();
+        let backends: [Box<dyn StateBackend>; 2] = [
+            Box::new(file_backend(temp_dir.path())),
+            Box::new(MemoryBackend::new()),
+        ];
+        let states = fifty_states();
+
+        for backend in &backends {
+            for state in &states {
+                backend.save_state(state).await.unwrap();
+            }
+
+            let mut stream = backend.stream_pipelines().await.unwrap();
+            assert_eq!(stream.remaining(), 50);
+            let mut streamed = Vec::new();
+            while let Some(next) = stream.next().await {
+                let (pipeline_id, state) = next.unwrap();
+                assert_eq!(pipeline_id, state.pipeline_id);
+                streamed.push((pipeline_id, state.run_id));
+            }
+            streamed.sort();
+
+            let mut expected: Vec<_> = states
+                .iter()
+                .map(|s| (s.pipeline_id.clone(), s.run_id.clone()))
+                .collect();
+            expected.sort();
+            assert_eq!(streamed, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_pipelines_loads_states_lazily() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = file_backend(temp_dir.path());
+        for state in fifty_states() {
+            backend.save_state(&state).await.unwrap();
+        }
+        // A second backend on the same files, without the writer's cache
+        let reader = file_backend(temp_dir.path());
+
+        // Filtering by ID drops pipelines before anything is loaded
+        let stream = reader.stream_pipelines().await.unwrap();
+        let mut etl = stream.filter_ids(|id| id.starts_with("etl_"));
+        assert_eq!(etl.remaining(), 25);
+
+        // Stopping at the first match leaves the rest unread: corrupting them
+        // afterwards only shows up once the stream reaches them
+        let (found, _) = etl.next().await.unwrap().unwrap();
+        for i in 0..50 {
+            let pipeline_id = format!("{}_{i:02}", if i % 2 == 0 { "pipe" } else { "etl" });
+            if pipeline_id != found {
+                std::fs::write(backend.state_file_path(&pipeline_id), "{ not json").unwrap();
+            }
+        }
+        assert_eq!(etl.remaining(), 24);
+        assert!(etl.next().await.unwrap().is_err());
+
+        // Deleted states are skipped rather than reported
+        let mut stream = reader.stream_pipelines().await.unwrap();
+        for i in 0..50 {
+            let pipeline_id = format!("{}_{i:02}", if i % 2 == 0 { "pipe" } else { "etl" });
+            if pipeline_id != found {
+                backend.delete_state(&pipeline_id).await.unwrap();
+            }
+        }
+        let (only, _) = stream.next().await.unwrap().unwrap();
+        assert_eq!(only, found);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_query_states_sort_and_limit() {
+        use crate::state::types::StateSortKey;
+
+        let backend = MemoryBackend::new();
+        let states = fifty_states();
+        for state in &states {
+            backend.save_state(state).await.unwrap();
+        }
+
+        let query = StateQuery {
+            sort: StateSortKey::UpdatedAt,
+            limit: Some(3),
+            ..Default::default()
+        };
+        let oldest = backend.query_states(query).await.unwrap();
+        assert_eq!(ids(&oldest), ["pipe_00", "etl_01", "pipe_02"]);
+
+        let query = StateQuery {
+            sort: StateSortKey::RecordsProcessed,
+            descending: true,
+            limit: Some(5),
+            ..Default::default()
+        };
+        let busiest = backend.query_states(query).await.unwrap();
+        let mut expected: Vec<_> = states
+            .iter()
+            .map(|s| (s.records_processed, s.pipeline_id.as_str()))
+            .collect();
+        expected.sort_by(|a, b| b.cmp(a));
+        let expected: Vec<_> = expected[..5].iter().map(|(_, id)| *id).collect();
+        assert_eq!(ids(&busiest), expected);
+        assert!(busiest
+            .windows(2)
+            .all(|w| w[0].records_processed >= w[1].records_processed));
+
+        let query = StateQuery {
+            limit: Some(0),
+            ..Default::default()
+        };
+        assert!(backend.query_states(query).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_index_rebuilds() {
+        let temp_dir = TempDir::new().unwrap();
+        let backend = file_backend(temp_dir.path());
+        for state in fifty_states() {
+            backend.save_state(&state).await.unwrap();
+        }
+        let index_path = temp_dir.path().join("index.json");
+        let before = backend.query_states(StateQuery::default()).await.unwrap();
+        assert_eq!(before.len(), 50);
+
+        std::fs::remove_file(&index_path).unwrap();
+        let rebuilt = backend.query_states(StateQuery::default()).await.unwrap();
+        assert_eq!(rebuilt, before);
+        assert!(index_path.exists());
+
+        // Corrupt indexes and state files changed behind the index's back are repaired too
+        std::fs::write(&index_path, "{not json").unwrap();
+        std::fs::remove_file(temp_dir.path().join("states/pipe_00.json")).unwrap();
+        let another = file_backend(temp_dir.path());
+        let mut edited = another.load_state("etl_01").await.unwrap();
+        edited.records_processed = 999;
+        std::fs::write(
+            temp_dir.path().join("states/etl_01.json"),
+            serde_json::to_vec(&edited).unwrap(),
+        )
+        .unwrap();
+        let repaired = backend.query_states(StateQuery::default()).await.unwrap();
+        assert_eq!(repaired.len(), 49);
+        assert_eq!(repaired[0].pipeline_id, "etl_01");
+        assert_eq!(repaired[0].records_processed, 999);
+
+        backend.delete_state("etl_03").await.unwrap();
+        assert_eq!(backend.reindex().await.unwrap(), 48);
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_lock_contention_keeps_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        // Separate backends stand in for separate processes
+        let (first, second) = (file_backend(temp_dir.path()), file_backend(temp_dir.path()));
+        let lock_path = first.lock_file_path("etl");
+
+        first
+            .acquire_lock("etl", "worker_1", 5000, 5000)
+            .await
+            .unwrap();
+        assert!(matches!(
+            second.acquire_lock("etl", "worker_2", 100, 100).await,
+            Err(StateError::LockTimeout { .. })
+        ));
+        let holder = read_lock(&lock_path).unwrap();
+        assert_eq!(holder.worker_id, "worker_1");
+        assert_eq!(holder.pid, Some(std::process::id()));
+
+        first.release_lock("etl", "worker_1").await.unwrap();
+        assert!(second
+            .acquire_lock("etl", "worker_2", 100, 100)
+            .await
+            .is_ok());
+        second.release_lock("etl", "worker_2").await.unwrap();
+
+        // Each task must see its own metadata for as long as it holds the lock
+        let tasks = ["worker_a", "worker_b"].map(|worker_id| {
+            let backend = file_backend(temp_dir.path());
+            let lock_path = lock_path.clone();
+            tokio::spawn(async move {
+                for _ in 0..5 {
+                    backend
+                        .acquire_lock("etl", worker_id, 2000, 2000)
+                        .await
+                        .unwrap();
+                    assert_eq!(read_lock(&lock_path).unwrap().worker_id, worker_id);
+                    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                    assert_eq!(read_lock(&lock_path).unwrap().worker_id, worker_id);
+                    backend.release_lock("etl", worker_id).await.unwrap();
+                }
+            })
+        });
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert!(!lock_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_steals_stale_locks() {
+        let temp_dir = TempDir::new().unwrap();
+        let (first, second) = (file_backend(temp_dir.path()), file_backend(temp_dir.path()));
+
+        // Still held open, but the lease ran out
+        first
+            .acquire_lock("expired", "worker_1", 100, 100)
+            .await
+            .unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        second
+            .acquire_lock("expired", "worker_2", 1000, 1000)
+            .await
+            .unwrap();
+        assert_eq!(
+            second
+                .is_locked("expired")
+                .await
+                .unwrap()
+                .unwrap()
+                .worker_id,
+            "worker_2"
+        );
+        assert!(matches!(
+            first.renew_lock("expired", "worker_1").await,
+            Err(StateError::LockLost { .. })
+        ));
+        assert!(matches!(
+            first.release_lock("expired", "worker_1").await,
+            Err(StateError::LockAlreadyHeld { .. })
+        ));
+
+        // Left by processes that are gone or still running, with a long lease
+        let leftover = |pipeline_id: &str, pid: u32| LockInfo {
+            pipeline_id: pipeline_id.to_string(),
+            worker_id: "worker_1".to_string(),
+            locked_at: Utc::now(),
+            expires_at: Some(Utc::now() + chrono::Duration::hours(1)),
+            lock_version: 1,
+            lease_ms: 3_600_000,
+            pid: Some(pid),
+            hostname: host_name().map(str::to_string),
+        };
+        for (pipeline_id, pid) in [("crashed", u32::MAX), ("live", std::process::id())] {
+            std::fs::write(
+                first.lock_file_path(pipeline_id),
+                serde_json::to_vec(&leftover(pipeline_id, pid)).unwrap(),
+            )
+            .unwrap();
+        }
+
+        if cfg!(target_os = "linux") {
+            assert!(second.is_locked("crashed").await.unwrap().is_none());
+            second
+                .acquire_lock("crashed", "worker_2", 100, 100)
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            second.acquire_lock("live", "worker_2", 100, 100).await,
+            Err(StateError::LockTimeout { .. })
+        ));
+        assert_eq!(
+            read_lock(&first.lock_file_path("live")).unwrap().worker_id,
+            "worker_1"
+        );
+
+        // A process id from another host says nothing about the holder there
+        let remote = LockInfo {
+            hostname: Some("elsewhere.example".to_string()),
+            ..leftover("remote", u32::MAX)
+        };
+        std::fs::write(
+            first.lock_file_path("remote"),
+            serde_json::to_vec(&remote).unwrap(),
+        )
+        .unwrap();
+        assert!(second.is_locked("remote").await.unwrap().is_some());
+        assert!(matches!(
+            second.acquire_lock("remote", "worker_2", 100, 100).await,
+            Err(StateError::LockTimeout { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_renewed_lock_is_replaced_whole_and_stays_held() {
+        let temp_dir = TempDir::new().unwrap();
+        let (first, second) = (file_backend(temp_dir.path()), file_backend(temp_dir.path()));
+        first
+            .acquire_lock("etl", "worker_1", 100, 5000)
+            .await
+            .unwrap();
+
+        // Readers racing the renewals only ever see a complete lock
+        let lock_path = first.lock_file_path("etl");
+        let reader = tokio::task::spawn_blocking({
+            let lock_path = lock_path.clone();
+            move || {
+                for _ in 0..2000 {
+                    if let Ok(data) = std::fs::read(&lock_path) {
+                        serde_json::from_slice::<LockInfo>(&data).unwrap();
+                    }
+                }
+            }
+        });
+        for _ in 0..50 {
+            first.renew_lock("etl", "worker_1").await.unwrap();
+        }
+        reader.await.unwrap();
+
+        assert_eq!(read_lock(&lock_path).unwrap().lock_version, 51);
+        assert!(matches!(
+            second.acquire_lock("etl", "worker_2", 100, 100).await,
+            Err(StateError::LockTimeout { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_write_ahead_log_recovers_interrupted_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = BackendConfig::File {
+            base_path: temp_dir.path().to_path_buf(),
+            format: SerializationFormat::Json,
+            atomic_writes: true,
+            lock_timeout_ms: 5000,
+            write_ahead_log: true,
+        };
+
+        let backend = FileBackend::new(config.clone()).unwrap();
+        let mut state = PipelineState::new("etl".to_string(), "run_1".to_string());
+        backend.save_state(&state).await.unwrap();
+        backend
+            .acquire_lock("etl", "worker_1", 5000, 5000)
+            .await
+            .unwrap();
+        // Both changes were logged, and committed once applied
+        let wal_path = backend.wal_file_path("etl");
+        let log = std::fs::read(&wal_path).unwrap();
+        assert!(String::from_utf8_lossy(&log).contains("write_lock"));
+        let (pending, discarded) = pending_intents(&log);
+        assert!(pending.is_empty());
+        assert_eq!(discarded, 0);
+
+        // Crash after logging a save and a lock release, before applying either
+        state.run_id = "run_2".to_string();
+        let saved = backend
+            .log_intent(WalIntent::SaveState {
+                state: Box::new(state.clone()),
+            })
+            .await
+            .unwrap();
+        drop(saved);
+        let released = backend
+            .log_intent(WalIntent::RemoveLock {
+                pipeline_id: "etl".to_string(),
+            })
+            .await
+            .unwrap();
+        drop(released);
+        drop(backend);
+
+        let restarted = FileBackend::new(config.clone()).unwrap();
+        assert_eq!(restarted.load_state("etl").await.unwrap().run_id, "run_2");
+        assert!(restarted.is_locked("etl").await.unwrap().is_none());
+        assert_eq!(std::fs::read(&wal_path).unwrap(), b"");
+
+        // Recovery waits for a change still being applied instead of replaying it
+        let in_flight = restarted
+            .log_intent(WalIntent::RemoveLock {
+                pipeline_id: "etl".to_string(),
+            })
+            .await
+            .unwrap();
+        let recovering = tokio::task::spawn_blocking({
+            let config = config.clone();
+            move || FileBackend::new(config).unwrap()
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(!recovering.is_finished());
+        restarted.commit_intent(in_flight).await.unwrap();
+        recovering.await.unwrap();
+        assert_eq!(std::fs::read(&wal_path).unwrap(), b"");
+
+        // A torn final record never started its change, so it is dropped
+        state.run_id = "run_3".to_string();
+        let mut torn = serde_json::to_vec(&WalRecord::Intent {
+            id: "torn".to_string(),
+            intent: WalIntent::SaveState {
+                state: Box::new(state),
+            },
+        })
+        .unwrap();
+        torn.truncate(torn.len() / 2);
+        std::fs::write(&wal_path, torn).unwrap();
+
+        let recovery = restarted.recover_write_ahead_log().unwrap();
+        assert_eq!(
+            recovery,
+            WalRecovery {
+                replayed: 0,
+                discarded: 1
+            }
+        );
+        let reopened = FileBackend::new(config).unwrap();
+        assert_eq!(reopened.load_state("etl").await.unwrap().run_id, "run_2");
+    }
+
+    #[tokio::test]
+    async fn test_write_ahead_log_skips_intents_superseded_by_later_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = BackendConfig::File {
+            base_path: temp_dir.path().to_path_buf(),
+            format: SerializationFormat::Json,
+            atomic_writes: true,
+            lock_timeout_ms: 5000,
+            write_ahead_log: true,
+        };
+
+        let live = FileBackend::new(config.clone()).unwrap();
+        let wal_path = live.wal_file_path("etl");
+
+        // Another process logs a save and crashes before applying or committing it
+        let crashed = FileBackend::new(config.clone()).unwrap();
+        let stale = PipelineState::new("etl".to_string(), "run_stale".to_string());
+        let intent = crashed
+            .log_intent(WalIntent::SaveState {
+                state: Box::new(stale),
+            })
+            .await
+            .unwrap();
+        drop(intent);
+        drop(crashed);
+
+        // The live process then commits a newer state for the same pipeline,
+        // and leaves a lock release pending, which touches a different file
+        let newer = PipelineState::new("etl".to_string(), "run_new".to_string());
+        live.save_state(&newer).await.unwrap();
+        std::fs::write(live.lock_file_path("etl"), b"{}").unwrap();
+        let release = live
+            .log_intent(WalIntent::RemoveLock {
+                pipeline_id: "etl".to_string(),
+            })
+            .await
+            .unwrap();
+        drop(release);
+        drop(live);
+
+        let (pending, _) = pending_intents(&std::fs::read(&wal_path).unwrap());
+        assert_eq!(pending.len(), 1);
+        assert!(matches!(pending[0], WalIntent::RemoveLock { .. }));
+
+        let restarted = FileBackend::new(config).unwrap();
+        assert_eq!(restarted.load_state("etl").await.unwrap().run_id, "run_new");
+        assert!(!restarted.lock_file_path("etl").exists());
     }
 
     #[tokio::test]
@@ -1968,6 +4726,7 @@ mod tests {
             format: SerializationFormat::Json,
             atomic_writes: false,
             lock_timeout_ms: 1000,
+            write_ahead_log: false,
         };
 
         let json_backend = FileBackend::new(json_config).unwrap();
@@ -1981,6 +4740,7 @@ mod tests {
             format: SerializationFormat::Yaml,
             atomic_writes: false,
             lock_timeout_ms: 1000,
+            write_ahead_log: false,
         };
 
         let yaml_backend = FileBackend::new(yaml_config).unwrap();
@@ -2004,6 +4764,7 @@ mod tests {
             format: SerializationFormat::Json,
             atomic_writes: false,
             lock_timeout_ms: 1000,
+            write_ahead_log: false,
         };
 
         let file_backend = FileBackend::new(config).unwrap();
@@ -2011,4 +4772,130 @@ mod tests {
         assert!(file_health.healthy);
         assert_eq!(file_health.backend_type, "file");
     }
+
+    #[tokio::test]
+    async fn test_memory_backend_counts_reads_and_writes_like_the_file_backend() {
+        let backend = MemoryBackend::new();
+        let mut state = PipelineState::new("counted".to_string(), "run_1".to_string());
+        for version in 1..=5 {
+            state.version = version;
+            backend.save_state(&state).await.unwrap();
+        }
+        for _ in 0..7 {
+            backend.load_state("counted").await.unwrap();
+        }
+        backend.load_run("counted", "run_1").await.unwrap();
+        backend
+            .atomic_update("counted", 5, Box::new(|state| state))
+            .await
+            .unwrap();
+        // Failed reads are not counted
+        assert!(backend.load_state("missing").await.is_err());
+
+        let metrics = backend.get_diagnostics().await.unwrap().performance_metrics;
+        assert_eq!(metrics["total_writes"], 6.0);
+        assert_eq!(metrics["total_reads"], 8.0);
+        assert_eq!(
+            metrics["total_bytes_read"],
+            8.0 * state.estimated_memory_usage() as f64
+        );
+        assert!(metrics["avg_read_time_ms"] >= 0.0);
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_backend = FileBackend::new(BackendConfig::File {
+            base_path: temp_dir.path().to_path_buf(),
+            format: SerializationFormat::Json,
+            atomic_writes: false,
+            lock_timeout_ms: 1000,
+            write_ahead_log: false,
+        })
+        .unwrap();
+        let file_metrics = file_backend
+            .get_diagnostics()
+            .await
+            .unwrap()
+            .performance_metrics;
+        for key in [
+            "total_reads",
+            "total_writes",
+            "avg_read_time_ms",
+            "avg_write_time_ms",
+            "total_bytes_read",
+            "total_bytes_written",
+        ] {
+            assert!(
+                metrics.contains_key(key) && file_metrics.contains_key(key),
+                "{key}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_watch_all() {
+        let backend = MemoryBackend::new();
+        let mut events = backend.watch_all().await.unwrap();
+
+        let mut state = PipelineState::new("watched".to_string(), "run_1".to_string());
+        backend.save_state(&state).await.unwrap();
+        state.version += 1;
+        backend.save_state(&state).await.unwrap();
+        backend
+            .acquire_lock("watched", "worker_a", 10_000, 10_000)
+            .await
+            .unwrap();
+        backend.release_lock("watched", "worker_a").await.unwrap();
+        backend.delete_state("watched").await.unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            assert_eq!(event.pipeline_id, "watched");
+            received.push(event.event);
+        }
+        assert_eq!(
+            received,
+            vec![
+                StateEvent::Created,
+                StateEvent::Updated {
+                    old_version: state.version - 1,
+                    new_version: state.version,
+                },
+                StateEvent::LockAcquired {
+                    worker_id: "worker_a".to_string()
+                },
+                StateEvent::LockReleased {
+                    worker_id: "worker_a".to_string()
+                },
+                StateEvent::Deleted,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_watch_all() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = BackendConfig::File {
+            base_path: temp_dir.path().to_path_buf(),
+            format: SerializationFormat::Json,
+            atomic_writes: true,
+            lock_timeout_ms: 1000,
+            write_ahead_log: false,
+        };
+        let backend = FileBackend::new(config).unwrap();
+        let mut events = backend.watch_all().await.unwrap();
+
+        let state = PipelineState::new("watched".to_string(), "run_1".to_string());
+        backend.save_state(&state).await.unwrap();
+
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                let event = events.recv().await.unwrap();
+                if event.event == StateEvent::Created {
+                    return event;
+                }
+            }
+        })
+        .await
+        .expect("no Created event from file watcher");
+        assert_eq!(event.pipeline_id, "watched");
+    }
 }
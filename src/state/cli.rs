@@ -1,41 +1,117 @@
 use crate::cli::{StateAction, WorkerAction};
-use crate::state::backend::{BackendConfig, SerializationFormat};
-use crate::state::manager::{StateManager, StateManagerConfig};
-use crate::state::types::{PipelineState, PipelineStatus};
+use crate::pipeline_manager::PipelineManager;
+use crate::run_history::{RunHistory, DEFAULT_RUNS_DIR};
+use crate::state::backend::{
+    BackendConfig, BackendHealth, BackupInfo, SerializationFormat, StateChangeEvent, StateEvent,
+};
+use crate::state::manager::{
+    duration_percentile, HeartbeatHandle, StateManager, StateManagerConfig,
+};
+use crate::state::types::{
+    PipelineState, PipelineStatus, StateError, StateQuery, StateSummary, StatusKind,
+};
 use anyhow::Result;
 use chrono::Utc;
 use serde_json;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::broadcast;
 
-/// Handle state management CLI commands
-pub async fn handle_state_command(action: StateAction) -> Result<()> {
+/// Open the project's file-backed state store used by the CLI
+pub async fn open_cli_state_manager() -> Result<StateManager> {
     let config = StateManagerConfig {
         backend: BackendConfig::File {
             base_path: PathBuf::from(".oxiflow/state"),
             format: SerializationFormat::Json,
             atomic_writes: true,
             lock_timeout_ms: 30000,
+            write_ahead_log: false,
         },
         ..Default::default()
     };
-    let state_manager = StateManager::new(config).await?;
+    Ok(StateManager::new(config).await?)
+}
+
+/// Handle state management CLI commands
+pub async fn handle_state_command(action: StateAction) -> Result<()> {
+    let state_manager = open_cli_state_manager().await?;
 
     match action {
         StateAction::Show {
             pipeline,
+            errors_all: true,
+            json,
+            yaml,
+            ..
+        } => {
+            let state_manager = pipeline_state_manager(&state_manager, &pipeline).await?;
+            show_all_errors(&state_manager, &pipeline, json, yaml).await
+        }
+
+        StateAction::Show {
+            pipeline,
+            step: Some(step),
+            json,
+            yaml,
+            ..
+        } => {
+            let state_manager = pipeline_state_manager(&state_manager, &pipeline).await?;
+            let state = match state_manager.load_state(&pipeline).await {
+                Ok(state) => state,
+                Err(_) => {
+                    println!("❌ No state found for pipeline: {pipeline}");
+                    std::process::exit(1);
+                }
+            };
+            println!("{}", render_step_state(&state, &step, json, yaml)?);
+            Ok(())
+        }
+
+        StateAction::Show {
+            pipeline,
+            step: None,
             json,
             yaml,
             verbose,
-        } => show_state(&state_manager, &pipeline, json, yaml, verbose).await,
+            ..
+        } => {
+            let state_manager = pipeline_state_manager(&state_manager, &pipeline).await?;
+            show_state(&state_manager, &pipeline, json, yaml, verbose).await
+        }
 
         StateAction::List {
             active,
             failed,
             completed,
+            prefix,
+            tags,
+            env,
+            sort,
+            desc,
+            limit,
             json,
             verbose,
-        } => list_states(&state_manager, active, failed, completed, json, verbose).await,
+        } => {
+            let query = StateQuery {
+                statuses: listed_statuses(active, failed, completed),
+                name_prefix: prefix,
+                environment: env,
+                tags: tags.into_iter().collect(),
+                sort,
+                descending: desc,
+                limit,
+                ..Default::default()
+            };
+            list_states(&state_manager, query, json, verbose).await
+        }
+
+        StateAction::Reindex => {
+            let indexed = state_manager.reindex().await?;
+            println!("✅ Rebuilt state index: {indexed} pipeline states");
+            Ok(())
+        }
 
         StateAction::Cleanup {
             stale,
@@ -44,6 +120,49 @@ pub async fn handle_state_command(action: StateAction) -> Result<()> {
             force,
         } => cleanup_states(&state_manager, stale, older_than_days, dry_run, force).await,
 
+        StateAction::Purge {
+            older_than,
+            dry_run,
+            force,
+        } => purge_states(&state_manager, older_than, dry_run, force).await,
+
+        StateAction::Gc { dry_run, verbose } => {
+            collect_garbage(&state_manager, dry_run, verbose).await
+        }
+
+        StateAction::Verify {
+            pipeline: Some(pipeline),
+            json,
+        } => {
+            let state_manager = pipeline_state_manager(&state_manager, &pipeline).await?;
+            verify_state(&state_manager, &pipeline, json).await
+        }
+
+        StateAction::Verify {
+            pipeline: None,
+            json,
+        } => verify_all_states(&state_manager, json).await,
+
+        StateAction::Repair {
+            pipeline,
+            dry_run,
+            from_backup,
+        } => {
+            let state_manager = pipeline_state_manager(&state_manager, &pipeline).await?;
+            match from_backup {
+                Some(backup_id) => {
+                    restore_backup(&state_manager, &pipeline, &backup_id, dry_run).await
+                }
+                None => repair_state(&state_manager, &pipeline, dry_run).await,
+            }
+        }
+
+        StateAction::Backups { pipeline } => {
+            let state_manager = pipeline_state_manager(&state_manager, &pipeline).await?;
+            print_backups_table(&pipeline, &state_manager.list_backups(&pipeline).await?);
+            Ok(())
+        }
+
         StateAction::Export {
             pipeline,
             output,
@@ -55,28 +174,164 @@ pub async fn handle_state_command(action: StateAction) -> Result<()> {
             input,
             force,
         } => import_state(&state_manager, &pipeline, &input, force).await,
+
+        StateAction::ExportAll { output } => {
+            let archive = state_manager.export_all().await?;
+            fs::write(&output, archive)?;
+            println!("✅ Exported all pipeline state to {output}");
+            Ok(())
+        }
+
+        StateAction::ImportAll { input, force } => {
+            import_all_states(&state_manager, &input, force).await
+        }
+
+        StateAction::Watch { pipeline, json } => {
+            watch_states(&state_manager, pipeline.as_deref(), json).await
+        }
+
+        StateAction::Stats { pipeline, json } => {
+            let stats = collect_stats(&state_manager, pipeline.as_deref()).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                print_stats_table(&stats);
+            }
+            Ok(())
+        }
+
+        StateAction::Health {
+            history,
+            samples,
+            interval_secs,
+        } => {
+            if history {
+                show_health_history(&state_manager, samples, interval_secs).await
+            } else {
+                print_health(&state_manager.health_check().await?);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The state store a pipeline's latest run was tracked in
+///
+/// A pipeline's `state:` block may point it at another store than the
+/// project's, and the run history records which one each run used. Exits
+/// when that was a memory backend without `persistent`, whose state ended
+/// with the run.
+async fn pipeline_state_manager(default: &StateManager, pipeline: &str) -> Result<StateManager> {
+    let recorded = RunHistory::new(DEFAULT_RUNS_DIR)
+        .latest(pipeline)?
+        .and_then(|report| report.state_backend);
+    match recorded {
+        Some(BackendConfig::Memory { persist_dir: None }) => {
+            println!(
+                "❌ Pipeline '{pipeline}' last ran with the memory state backend; its state was not kept"
+            );
+            std::process::exit(1);
+        }
+        Some(backend) if backend != default.config().backend => {
+            Ok(StateManager::new(StateManagerConfig {
+                backend,
+                ..Default::default()
+            })
+            .await?)
+        }
+        _ => Ok(default.clone()),
+    }
+}
+
+/// Sample backend health with the health monitor and print the recorded series
+async fn show_health_history(
+    state_manager: &StateManager,
+    samples: usize,
+    interval_secs: u64,
+) -> Result<()> {
+    let samples = samples.max(1);
+    println!("📈 Sampling backend health {samples} times, every {interval_secs}s...");
+
+    let monitor = state_manager.start_health_monitor(Duration::from_secs(interval_secs.max(1)));
+    while state_manager.health_history().len() < samples {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    monitor.stop();
+
+    println!(
+        "{:<20} {:<8} {:>10} {:<10} Error",
+        "Checked", "Healthy", "Response", "Trend"
+    );
+    for health in state_manager.health_history().iter().take(samples) {
+        println!(
+            "{:<20} {:<8} {:>8}ms {:<10} {}",
+            health.last_check.format("%Y-%m-%d %H:%M:%S"),
+            if health.healthy { "yes" } else { "no" },
+            health.response_time_ms,
+            health
+                .trend
+                .map(|t| format!("{t:?}"))
+                .unwrap_or_else(|| "-".to_string()),
+            health.error_message.as_deref().unwrap_or("")
+        );
+    }
+    Ok(())
+}
+
+/// Print a single health snapshot with its metrics
+fn print_health(health: &BackendHealth) {
+    let icon = if health.healthy { "✅" } else { "❌" };
+    println!("{icon} {} backend", health.backend_type);
+    println!("  Response time: {}ms", health.response_time_ms);
+    if let Some(error) = &health.error_message {
+        println!("  Error: {error}");
+    }
+    let mut metrics: Vec<_> = health.metrics.iter().collect();
+    metrics.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, value) in metrics {
+        println!("  {name}: {value}");
     }
 }
 
 /// Handle worker management CLI commands
 pub async fn handle_worker_command(action: WorkerAction) -> Result<()> {
-    let config = StateManagerConfig {
-        backend: BackendConfig::File {
-            base_path: PathBuf::from(".oxiflow/state"),
-            format: SerializationFormat::Json,
-            atomic_writes: true,
-            lock_timeout_ms: 30000,
-        },
-        ..Default::default()
-    };
-    let state_manager = StateManager::new(config).await?;
+    let state_manager = open_cli_state_manager().await?;
 
     match action {
         WorkerAction::List {
             pipeline,
             json,
+            locks: true,
+            ..
+        } => list_lock_holders(&state_manager, pipeline.as_deref(), json).await,
+
+        WorkerAction::List {
+            pipeline,
+            active_only,
+            stale_minutes,
+            json,
             verbose,
-        } => list_workers(&state_manager, pipeline.as_deref(), json, verbose).await,
+            locks: false,
+        } => {
+            list_workers(
+                &state_manager,
+                pipeline.as_deref(),
+                active_only,
+                stale_minutes,
+                json,
+                verbose,
+            )
+            .await
+        }
+
+        WorkerAction::Heartbeat {
+            pipeline,
+            worker_id,
+            interval_secs,
+        } => {
+            let state_manager = pipeline_state_manager(&state_manager, &pipeline).await?;
+            worker_heartbeat(&state_manager, &pipeline, &worker_id, interval_secs).await
+        }
 
         WorkerAction::Stop { worker_id, force } => {
             stop_worker(&state_manager, &worker_id, force).await
@@ -84,6 +339,122 @@ pub async fn handle_worker_command(action: WorkerAction) -> Result<()> {
     }
 }
 
+/// Register `worker_id` as the pipeline's worker and start its heartbeat task
+async fn start_worker_heartbeat(
+    state_manager: &StateManager,
+    pipeline: &str,
+    worker_id: &str,
+    interval: Duration,
+) -> Result<HeartbeatHandle, StateError> {
+    let worker = state_manager.for_worker(worker_id, interval.as_millis() as u64);
+    worker
+        .update_state(pipeline, |state| {
+            state.worker_id = Some(worker_id.to_string());
+            state.last_heartbeat = Utc::now();
+        })
+        .await?;
+    Ok(worker.start_heartbeat(pipeline.to_string()).await)
+}
+
+/// Send heartbeats for an out-of-process worker until interrupted
+async fn worker_heartbeat(
+    state_manager: &StateManager,
+    pipeline: &str,
+    worker_id: &str,
+    interval_secs: u64,
+) -> Result<()> {
+    let interval = Duration::from_secs(interval_secs);
+    let heartbeat = match start_worker_heartbeat(state_manager, pipeline, worker_id, interval).await
+    {
+        Ok(heartbeat) => heartbeat,
+        Err(StateError::PipelineNotFound { .. }) => {
+            println!("❌ No state found for pipeline: {pipeline}");
+            std::process::exit(1);
+        }
+        Err(e) => return Err(e.into()),
+    };
+    println!(
+        "💓 Worker {worker_id} registered for pipeline {pipeline}, heartbeat every {interval_secs}s (Ctrl+C to stop)"
+    );
+
+    // The task ends on its own when a heartbeat fails, e.g. once the state is deleted
+    while heartbeat.is_running() {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                heartbeat.stop();
+                println!("👋 Stopped heartbeat for worker {worker_id}");
+                return Ok(());
+            }
+            _ = tokio::time::sleep(Duration::from_millis(500)) => {}
+        }
+    }
+    anyhow::bail!("Heartbeat for pipeline '{pipeline}' stopped")
+}
+
+/// Print state change events until interrupted
+async fn watch_states(
+    state_manager: &StateManager,
+    pipeline: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let mut events = state_manager.watch_all().await?;
+
+    if !json {
+        match pipeline {
+            Some(id) => println!("👀 Watching state changes for pipeline '{id}' (Ctrl+C to stop)"),
+            None => println!("👀 Watching state changes for all pipelines (Ctrl+C to stop)"),
+        }
+    }
+
+    loop {
+        let event = tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            event = events.recv() => event,
+        };
+
+        let event = match event {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                eprintln!("⚠️  Missed {skipped} events (watcher fell behind)");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        if pipeline.is_some_and(|id| id != event.pipeline_id) {
+            continue;
+        }
+
+        if json {
+            println!("{}", serde_json::to_string(&event)?);
+        } else {
+            print_event_human(&event);
+        }
+    }
+
+    Ok(())
+}
+
+/// Print a state change event in human-readable format
+fn print_event_human(event: &StateChangeEvent) {
+    let time = event.timestamp.format("%H:%M:%S");
+    let id = &event.pipeline_id;
+    match &event.event {
+        StateEvent::Created => println!("[{time}] 🆕 {id}: state created"),
+        StateEvent::Updated {
+            old_version,
+            new_version,
+        } => println!("[{time}] 🔄 {id}: updated (v{old_version} → v{new_version})"),
+        StateEvent::Deleted => println!("[{time}] 🗑️  {id}: state deleted"),
+        StateEvent::LockAcquired { worker_id } => {
+            println!("[{time}] 🔒 {id}: locked by {worker_id}")
+        }
+        StateEvent::LockReleased { worker_id } => {
+            println!("[{time}] 🔓 {id}: released by {worker_id}")
+        }
+    }
+}
+
 /// Show the state of a specific pipeline
 async fn show_state(
     state_manager: &StateManager,
@@ -100,6 +471,7 @@ async fn show_state(
                 println!("{}", serde_yaml::to_string(&state)?);
             } else {
                 print_state_human(&state, verbose);
+                warn_if_pipeline_changed(&state, pipeline);
             }
         }
         Err(_) => {
@@ -110,38 +482,39 @@ async fn show_state(
     Ok(())
 }
 
-/// List all pipeline states with optional filtering
+/// Warn when the pipeline file no longer matches the definition of the last run
+fn warn_if_pipeline_changed(state: &PipelineState, pipeline: &str) {
+    let Ok(current) = PipelineManager::new().and_then(|m| m.load_pipeline(pipeline)) else {
+        return;
+    };
+    if state.pipeline_changed(&current.content_hash()) {
+        println!("\n⚠️  Pipeline changed since last run");
+    }
+}
+
+/// Statuses `state list` shows for its status flags; every status when none is set
+fn listed_statuses(active: bool, failed: bool, completed: bool) -> Vec<StatusKind> {
+    let mut statuses = Vec::new();
+    if active {
+        statuses.extend([StatusKind::Running, StatusKind::Paused, StatusKind::Pending]);
+    }
+    if failed {
+        statuses.push(StatusKind::Failed);
+    }
+    if completed {
+        statuses.push(StatusKind::Completed);
+    }
+    statuses
+}
+
+/// List the pipeline states matching `query`
 async fn list_states(
     state_manager: &StateManager,
-    active: bool,
-    failed: bool,
-    completed: bool,
+    query: StateQuery,
     json: bool,
     verbose: bool,
 ) -> Result<()> {
-    let pipeline_ids = state_manager.list_pipelines().await?;
-    let mut states = Vec::new();
-
-    for pipeline_id in pipeline_ids {
-        if let Ok(state) = state_manager.load_state(&pipeline_id).await {
-            // Apply filters
-            let include = if active || failed || completed {
-                match &state.status {
-                    PipelineStatus::Running { .. } => active,
-                    PipelineStatus::Failed { .. } => failed,
-                    PipelineStatus::Completed { .. } => completed,
-                    PipelineStatus::Paused { .. } => active,
-                    PipelineStatus::Pending => active,
-                }
-            } else {
-                true // No filter, include all
-            };
-
-            if include {
-                states.push(state);
-            }
-        }
-    }
+    let states = state_manager.query_states(query).await?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&states)?);
@@ -160,11 +533,11 @@ async fn cleanup_states(
     dry_run: bool,
     force: bool,
 ) -> Result<()> {
-    let pipeline_ids = state_manager.list_pipelines().await?;
+    let mut states = state_manager.stream_pipelines().await?;
     let mut to_clean = Vec::new();
 
-    for pipeline_id in pipeline_ids {
-        if let Ok(state) = state_manager.load_state(&pipeline_id).await {
+    while let Some(next) = states.next().await {
+        if let Ok((_, state)) = next {
             let mut should_clean = false;
 
             if stale {
@@ -230,6 +603,245 @@ async fn cleanup_states(
     Ok(())
 }
 
+/// Delete completed states, previewing them first unless `force` is set
+async fn purge_states(
+    state_manager: &StateManager,
+    older_than_days: Option<u32>,
+    dry_run: bool,
+    force: bool,
+) -> Result<()> {
+    let older_than = older_than_days.map(|days| Duration::from_secs(u64::from(days) * 86_400));
+    let preview = state_manager.purge_completed(older_than, true).await?;
+
+    for pipeline_id in &preview.skipped_ids {
+        println!("🔒 Skipping locked state: {pipeline_id}");
+    }
+    for error in &preview.errors {
+        println!("❌ {error}");
+    }
+    if preview.deleted_ids.is_empty() {
+        println!("✅ No completed states to purge");
+        return Ok(());
+    }
+
+    println!(
+        "🧹 Found {} completed states to purge ({} bytes):",
+        preview.deleted_ids.len(),
+        preview.freed_bytes
+    );
+    for pipeline_id in &preview.deleted_ids {
+        println!("   {pipeline_id}");
+    }
+
+    if dry_run {
+        println!("\n🔍 Dry run - no states were actually removed");
+        return Ok(());
+    }
+
+    if !force {
+        print!("\n❓ Are you sure you want to delete these states? (y/N): ");
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if !input.trim().to_lowercase().starts_with('y') {
+            println!("❌ Purge cancelled");
+            return Ok(());
+        }
+    }
+
+    let result = state_manager.purge_completed(older_than, false).await?;
+    for error in &result.errors {
+        println!("❌ {error}");
+    }
+    println!(
+        "🎉 Purged {} states, freed {} bytes",
+        result.deleted_ids.len(),
+        result.freed_bytes
+    );
+    Ok(())
+}
+
+/// Remove orphaned files from the state directory and summarize what was freed
+async fn collect_garbage(state_manager: &StateManager, dry_run: bool, verbose: bool) -> Result<()> {
+    let result = state_manager.collect_garbage(dry_run).await?;
+
+    for error in &result.errors {
+        println!("❌ {error}");
+    }
+    if result.removed.is_empty() {
+        println!("✅ No orphaned files found");
+        return Ok(());
+    }
+    if verbose || dry_run {
+        for orphan in &result.removed {
+            println!(
+                "   {:?}: {} ({} bytes)",
+                orphan.kind, orphan.path, orphan.size_bytes
+            );
+        }
+    }
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    println!(
+        "🧹 {verb} {} orphaned locks, {} orphaned backups, {} temp files and {} empty states",
+        result.orphaned_locks_removed,
+        result.orphaned_backups_removed,
+        result.temp_files_removed,
+        result.empty_states_removed
+    );
+    if dry_run {
+        println!("🔍 Dry run - {} bytes would be freed", result.freed_bytes);
+    } else {
+        println!("🎉 Freed {} bytes", result.freed_bytes);
+    }
+    Ok(())
+}
+
+/// Validate one pipeline's state file, exiting nonzero when it is invalid
+async fn verify_state(state_manager: &StateManager, pipeline: &str, json: bool) -> Result<()> {
+    let validation = match state_manager.validate_state(pipeline).await {
+        Ok(validation) => validation,
+        Err(StateError::PipelineNotFound { .. }) => {
+            println!("❌ No state found for pipeline: {pipeline}");
+            std::process::exit(1);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&validation)?);
+    } else if validation.valid {
+        println!(
+            "✅ State for {pipeline} is valid ({} bytes, modified {})",
+            validation.file_size_bytes,
+            validation.last_modified.format("%Y-%m-%d %H:%M:%S")
+        );
+    } else {
+        let kind = if validation.corruption_detected {
+            "corrupted"
+        } else {
+            "invalid"
+        };
+        println!("❌ State for {pipeline} is {kind}:");
+        for error in &validation.validation_errors {
+            println!("   • {error}");
+        }
+        println!("💡 Run 'oxide_flow state repair {pipeline}' to fix it");
+    }
+
+    if !validation.valid {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Check every state file and print a health report, exiting nonzero on issues
+async fn verify_all_states(state_manager: &StateManager, json: bool) -> Result<()> {
+    let report = state_manager.verify_integrity().await?;
+    let healthy = report.corrupted_files.is_empty()
+        && report.missing_files.is_empty()
+        && report.permission_errors.is_empty()
+        && report.checksum_mismatches.is_empty();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        let icon = if healthy { "✅" } else { "❌" };
+        println!(
+            "{icon} Overall health: {:.0}% ({} state files checked)",
+            report.overall_health * 100.0,
+            report.total_files_checked
+        );
+        for file in &report.corrupted_files {
+            println!("   Corrupted: {file}");
+        }
+        // Corrupted files also count as checksum mismatches
+        for file in &report.checksum_mismatches {
+            if !report.corrupted_files.contains(file) {
+                println!("   Failed validation: {file}");
+            }
+        }
+        for file in &report.missing_files {
+            println!("   Missing: {file}");
+        }
+        for file in &report.permission_errors {
+            println!("   Permission denied: {file}");
+        }
+        for orphan in &report.orphaned_files {
+            println!("   Orphaned {:?}: {}", orphan.kind, orphan.path);
+        }
+        for recommendation in &report.repair_recommendations {
+            println!("💡 {recommendation}");
+        }
+    }
+
+    if !healthy {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Repair a pipeline's state in place, exiting nonzero when it needs manual work
+async fn repair_state(state_manager: &StateManager, pipeline: &str, dry_run: bool) -> Result<()> {
+    if dry_run {
+        let validation = state_manager.validate_state(pipeline).await?;
+        if validation.valid {
+            println!("✅ State for {pipeline} is valid - nothing to repair");
+        } else {
+            println!("🔍 Dry run - would repair {pipeline}:");
+            for error in &validation.validation_errors {
+                println!("   • {error}");
+            }
+            if validation.corruption_detected {
+                println!("   The state is unreadable and would be restored from the newest backup");
+            }
+        }
+        return Ok(());
+    }
+
+    let result = state_manager.repair_state(pipeline).await?;
+    if let Some(backup_id) = &result.backup_id {
+        println!("💾 Backed up current state as {backup_id}");
+    }
+    for issue in &result.issues_found {
+        println!("⚠️  {issue}");
+    }
+    for repair in &result.repairs_made {
+        println!("🔧 {repair}");
+    }
+
+    if result.manual_intervention_required {
+        println!("❌ Could not repair {pipeline} - manual intervention required");
+        std::process::exit(1);
+    }
+    println!("✅ Repaired state for {pipeline}");
+    Ok(())
+}
+
+/// Replace a pipeline's state with one of its backups
+async fn restore_backup(
+    state_manager: &StateManager,
+    pipeline: &str,
+    backup_id: &str,
+    dry_run: bool,
+) -> Result<()> {
+    if dry_run {
+        let backups = state_manager.list_backups(pipeline).await?;
+        if !backups.iter().any(|b| b.backup_id == backup_id) {
+            anyhow::bail!("Backup not found for {pipeline}: {backup_id}");
+        }
+        println!("🔍 Dry run - would restore {pipeline} from {backup_id}");
+        return Ok(());
+    }
+
+    state_manager.restore_state(pipeline, backup_id).await?;
+    println!("✅ Restored state for {pipeline} from {backup_id}");
+    Ok(())
+}
+
 /// Export a pipeline state to a file
 async fn export_state(
     state_manager: &StateManager,
@@ -292,59 +904,264 @@ async fn import_state(
     Ok(())
 }
 
+/// Import every state in an archive written by `state export-all`
+async fn import_all_states(state_manager: &StateManager, input: &str, force: bool) -> Result<()> {
+    let data = fs::read(input).map_err(|e| anyhow::anyhow!("Failed to read {input}: {e}"))?;
+    let result = state_manager.import_all(&data, force).await?;
+
+    println!(
+        "✅ Imported {} pipeline state(s) from {input}",
+        result.imported_count
+    );
+    if result.skipped_count > 0 {
+        println!(
+            "⏭️  Skipped {} pipeline(s) that already have state; use --force to overwrite",
+            result.skipped_count
+        );
+    }
+    if !result.failed_ids.is_empty() {
+        eprintln!(
+            "❌ Failed to import state for: {}",
+            result.failed_ids.join(", ")
+        );
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
 /// List all active workers
 async fn list_workers(
     state_manager: &StateManager,
     pipeline_filter: Option<&str>,
+    active_only: bool,
+    stale_minutes: u32,
     json: bool,
     verbose: bool,
 ) -> Result<()> {
-    let pipeline_ids = state_manager.list_pipelines().await?;
+    let workers =
+        collect_workers(state_manager, pipeline_filter, active_only, stale_minutes).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&workers)?);
+    } else {
+        print_workers_table(&workers, verbose);
+    }
+
+    Ok(())
+}
+
+/// List the workers holding pipeline locks, longest held first
+async fn list_lock_holders(
+    state_manager: &StateManager,
+    pipeline_filter: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    let now = Utc::now();
+    let holders: Vec<_> = state_manager
+        .lock_holders()
+        .await?
+        .into_iter()
+        .filter(|lock| pipeline_filter.is_none_or(|p| lock.pipeline_id == p))
+        .collect();
+
+    if json {
+        let holders: Vec<_> = holders
+            .iter()
+            .map(|lock| {
+                serde_json::json!({
+                    "pipeline_id": lock.pipeline_id,
+                    "worker_id": lock.worker_id,
+                    "locked_at": lock.locked_at,
+                    "expires_at": lock.expires_at,
+                    "held_secs": (now - lock.locked_at).num_seconds(),
+                    "pid": lock.pid,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&holders)?);
+        return Ok(());
+    }
+
+    if holders.is_empty() {
+        println!("🔓 No pipeline locks held");
+        return Ok(());
+    }
+
+    println!("🔒 Held Locks ({}):", holders.len());
+    println!(
+        "{:<20} {:<25} {:<12} Expires In",
+        "Pipeline", "Worker ID", "Held For"
+    );
+    println!("{:-<70}", "");
+    for lock in &holders {
+        let held = (now - lock.locked_at).num_seconds();
+        let expires_in = lock.expires_at.map_or_else(
+            || "never".to_string(),
+            |expires_at| format!("{}s", (expires_at - now).num_seconds().max(0)),
+        );
+        println!(
+            "{:<20} {:<25} {:<12} {}",
+            lock.pipeline_id,
+            lock.worker_id,
+            format!("{held}s"),
+            expires_in
+        );
+    }
+    Ok(())
+}
+
+/// Gather worker records from pipeline states, marking those without a
+/// heartbeat in the last `stale_minutes` as inactive
+async fn collect_workers(
+    state_manager: &StateManager,
+    pipeline_filter: Option<&str>,
+    active_only: bool,
+    stale_minutes: u32,
+) -> Result<Vec<serde_json::Value>> {
+    // Skip pipelines the filter doesn't match before loading their state
+    let mut states = state_manager
+        .stream_pipelines()
+        .await?
+        .filter_ids(|pipeline_id| {
+            pipeline_filter.is_none_or(|filter| pipeline_id.contains(filter))
+        });
+    let now = Utc::now();
     let mut workers = Vec::new();
 
-    for pipeline_id in pipeline_ids {
-        // Skip if pipeline filter doesn't match
-        if let Some(filter) = pipeline_filter {
-            if !pipeline_id.contains(filter) {
+    while let Some(next) = states.next().await {
+        let Ok((pipeline_id, state)) = next else {
+            continue;
+        };
+        if let Some(worker_id) = &state.worker_id {
+            let heartbeat_age_secs = (now - state.last_heartbeat).num_seconds().max(0);
+            let is_active = heartbeat_age_secs < i64::from(stale_minutes) * 60;
+
+            if active_only && !is_active {
                 continue;
             }
+
+            workers.push(serde_json::json!({
+                "worker_id": worker_id,
+                "pipeline_id": pipeline_id,
+                "status": format!("{:?}", state.status),
+                "last_heartbeat": state.last_heartbeat,
+                "heartbeat_age_secs": heartbeat_age_secs,
+                "active": is_active,
+                "current_step": state.current_step,
+            }));
         }
+    }
 
-        if let Ok(state) = state_manager.load_state(&pipeline_id).await {
-            if let Some(worker_id) = &state.worker_id {
-                // Check if worker is still active (recent heartbeat)
-                let active_threshold = Utc::now() - chrono::Duration::minutes(5);
-                let is_active = state.last_heartbeat > active_threshold;
-
-                workers.push(serde_json::json!({
-                    "worker_id": worker_id,
-                    "pipeline_id": pipeline_id,
-                    "status": format!("{:?}", state.status),
-                    "last_heartbeat": state.last_heartbeat,
-                    "active": is_active,
-                    "current_step": state.current_step,
-                }));
-            }
+    Ok(workers)
+}
+
+/// Per-pipeline run counts with median and p95 durations of completed runs
+///
+/// States are grouped by `metadata.pipeline_name`, falling back to the state id.
+async fn collect_stats(
+    state_manager: &StateManager,
+    pipeline_filter: Option<&str>,
+) -> Result<Vec<serde_json::Value>> {
+    #[derive(Default)]
+    struct RunCounts {
+        runs: u64,
+        completed: u64,
+        failed: u64,
+        other: u64,
+    }
+
+    let mut counts: BTreeMap<String, RunCounts> = BTreeMap::new();
+
+    let mut states = state_manager.stream_pipelines().await?;
+    while let Some(next) = states.next().await {
+        let Ok((pipeline_id, state)) = next else {
+            continue;
+        };
+        let name = state.metadata.pipeline_name.clone().unwrap_or(pipeline_id);
+        if pipeline_filter.is_some_and(|filter| filter != name) {
+            continue;
+        }
+
+        let entry = counts.entry(name).or_default();
+        entry.runs += 1;
+        match state.status {
+            PipelineStatus::Completed { .. } => entry.completed += 1,
+            PipelineStatus::Failed { .. } => entry.failed += 1,
+            _ => entry.other += 1,
         }
     }
 
-    if json {
-        println!("{}", serde_json::to_string_pretty(&workers)?);
-    } else {
-        print_workers_table(&workers, verbose);
+    let mut stats = Vec::new();
+    for (name, count) in counts {
+        let durations = state_manager.completed_durations(&name).await?;
+        let millis =
+            |percentile| duration_percentile(&durations, percentile).map(|d| d.as_millis() as u64);
+        stats.push(serde_json::json!({
+            "pipeline": name,
+            "runs": count.runs,
+            "completed": count.completed,
+            "failed": count.failed,
+            "other": count.other,
+            "median_duration_ms": millis(0.5),
+            "p95_duration_ms": millis(0.95),
+        }));
     }
 
-    Ok(())
+    Ok(stats)
+}
+
+fn print_stats_table(stats: &[serde_json::Value]) {
+    if stats.is_empty() {
+        println!("📭 No pipeline states found");
+        return;
+    }
+
+    let duration = |value: &serde_json::Value| {
+        value
+            .as_u64()
+            .map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    println!("📊 Pipeline Statistics ({}):", stats.len());
+    println!("{:-<80}", "");
+    println!(
+        "{:<25} {:>6} {:>10} {:>8} {:>12} {:>12}",
+        "Pipeline", "Runs", "Completed", "Failed", "Median", "p95"
+    );
+    println!("{:-<80}", "");
+
+    for entry in stats {
+        println!(
+            "{:<25} {:>6} {:>10} {:>8} {:>12} {:>12}",
+            entry["pipeline"].as_str().unwrap_or(""),
+            entry["runs"].as_u64().unwrap_or(0),
+            entry["completed"].as_u64().unwrap_or(0),
+            entry["failed"].as_u64().unwrap_or(0),
+            duration(&entry["median_duration_ms"]),
+            duration(&entry["p95_duration_ms"])
+        );
+    }
+}
+
+/// Render an age in seconds as a short relative time, e.g. "2m ago"
+fn format_age(secs: i64) -> String {
+    match secs {
+        s if s < 60 => format!("{s}s ago"),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s => format!("{}d ago", s / 86400),
+    }
 }
 
 /// Stop a specific worker
 async fn stop_worker(state_manager: &StateManager, worker_id: &str, force: bool) -> Result<()> {
     // Find the pipeline with this worker
-    let pipeline_ids = state_manager.list_pipelines().await?;
+    let mut states = state_manager.stream_pipelines().await?;
     let mut found = false;
 
-    for pipeline_id in pipeline_ids {
-        if let Ok(state) = state_manager.load_state(&pipeline_id).await {
+    while let Some(next) = states.next().await {
+        if let Ok((pipeline_id, state)) = next {
             if let Some(state_worker_id) = &state.worker_id {
                 if state_worker_id == worker_id {
                     found = true;
@@ -388,6 +1205,47 @@ async fn stop_worker(state_manager: &StateManager, worker_id: &str, force: bool)
     Ok(())
 }
 
+/// Print a pipeline's archived errors followed by those still in its state
+async fn show_all_errors(
+    state_manager: &StateManager,
+    pipeline: &str,
+    json: bool,
+    yaml: bool,
+) -> Result<()> {
+    let state = match state_manager.load_state(pipeline).await {
+        Ok(state) => state,
+        Err(_) => {
+            println!("❌ No state found for pipeline: {pipeline}");
+            std::process::exit(1);
+        }
+    };
+    let mut errors = state_manager.load_error_archive(pipeline).await?;
+    let archived = errors.len();
+    errors.extend(state.errors);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&errors)?);
+    } else if yaml {
+        println!("{}", serde_yaml::to_string(&errors)?);
+    } else if errors.is_empty() {
+        println!("✅ No errors recorded for pipeline: {pipeline}");
+    } else {
+        println!(
+            "❌ Errors for {pipeline} ({} total, {archived} archived):",
+            errors.len()
+        );
+        for error in &errors {
+            println!(
+                "  • {} {:?}: {}",
+                error.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+                error.error_type,
+                error.message
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Print a pipeline state in human-readable format
 fn print_state_human(state: &PipelineState, verbose: bool) {
     println!("📊 Pipeline State: {}", state.pipeline_id);
@@ -415,6 +1273,13 @@ fn print_state_human(state: &PipelineState, verbose: bool) {
             }
         }
 
+        if !state.step_summaries.is_empty() {
+            println!(
+                "📦 Completed steps of earlier runs: {}",
+                state.step_summaries.len()
+            );
+        }
+
         if !state.errors.is_empty() {
             println!("\n❌ Errors ({}):", state.errors.len());
             for error in &state.errors {
@@ -424,6 +1289,60 @@ fn print_state_human(state: &PipelineState, verbose: bool) {
     }
 }
 
+/// One step of a pipeline state as JSON, YAML or human-readable text
+fn render_step_state(
+    state: &PipelineState,
+    step_id: &str,
+    json: bool,
+    yaml: bool,
+) -> Result<String> {
+    let Some(step) = state.step_states.get(step_id) else {
+        let mut known: Vec<&str> = state.step_states.keys().map(String::as_str).collect();
+        known.sort_unstable();
+        anyhow::bail!(
+            "Step '{}' not found in state for pipeline '{}' (known steps: {})",
+            step_id,
+            state.pipeline_id,
+            if known.is_empty() {
+                "none".to_string()
+            } else {
+                known.join(", ")
+            }
+        );
+    };
+
+    if json {
+        return Ok(serde_json::to_string_pretty(step)?);
+    }
+    if yaml {
+        return Ok(serde_yaml::to_string(step)?);
+    }
+
+    let mut lines = vec![
+        format!("🔧 Step State: {} ({})", step.step_id, state.pipeline_id),
+        format!("📈 Status: {:?}", step.status),
+        format!("✅ Records Processed: {}", step.records_processed),
+        format!("⏱️  Processing Time: {}ms", step.processing_time_ms),
+        format!("🔁 Retries: {}", step.retry_count),
+        format!("❌ Errors: {}", step.error_count),
+        format!(
+            "🔑 Config Hash: {}",
+            step.config_hash.as_deref().unwrap_or("-")
+        ),
+        format!(
+            "💓 Last Heartbeat: {}",
+            step.last_heartbeat.format("%Y-%m-%d %H:%M:%S UTC")
+        ),
+    ];
+    if !step.last_processed_id.is_empty() {
+        lines.push(format!("📍 Last Processed ID: {}", step.last_processed_id));
+    }
+    if let Some(worker) = &step.worker_id {
+        lines.push(format!("👷 Worker: {worker}"));
+    }
+    Ok(lines.join("\n"))
+}
+
 /// Print a summary line for a state
 fn print_state_summary(state: &PipelineState) {
     println!(
@@ -436,7 +1355,7 @@ fn print_state_summary(state: &PipelineState) {
 }
 
 /// Print states in a table format
-fn print_states_table(states: &[PipelineState], verbose: bool) {
+fn print_states_table(states: &[StateSummary], verbose: bool) {
     if states.is_empty() {
         println!("📭 No pipeline states found");
         return;
@@ -447,7 +1366,7 @@ fn print_states_table(states: &[PipelineState], verbose: bool) {
 
     if verbose {
         println!(
-            "{:<20} {:<12} {:<15} {:<20} {:<10}",
+            "{:<20} {:<12} {:<15} {:<20} {:<12} Tags",
             "Pipeline", "Run ID", "Status", "Started", "Progress"
         );
         println!("{:-<80}", "");
@@ -461,14 +1380,19 @@ fn print_states_table(states: &[PipelineState], verbose: bool) {
                 PipelineStatus::Pending => "Pending",
             };
 
+            let progress = format!(
+                "{}/{}",
+                state.records_processed,
+                state.records_processed + state.records_failed
+            );
             println!(
-                "{:<20} {:<12} {:<15} {:<20} {}/{}",
+                "{:<20} {:<12} {:<15} {:<20} {:<12} {}",
                 state.pipeline_id,
                 &state.run_id[..8],
                 status_str,
                 state.started_at.format("%m-%d %H:%M"),
-                state.records_processed,
-                state.records_processed + state.records_failed
+                progress,
+                format_tags(state)
             );
         }
     } else {
@@ -494,6 +1418,60 @@ fn print_states_table(states: &[PipelineState], verbose: bool) {
     }
 }
 
+/// A state's environment and tags for listings, e.g. `env=prod, team=data, etl`
+///
+/// Tags are sorted by key; tags without a value are shown by key alone.
+fn format_tags(state: &StateSummary) -> String {
+    let mut tags: Vec<(&String, &String)> = state.tags.iter().collect();
+    tags.sort();
+    let environment = state.environment.iter().map(|env| format!("env={env}"));
+    let tags = tags.into_iter().map(|(key, value)| {
+        if value.is_empty() {
+            key.clone()
+        } else {
+            format!("{key}={value}")
+        }
+    });
+    let formatted: Vec<String> = environment.chain(tags).collect();
+    if formatted.is_empty() {
+        "-".to_string()
+    } else {
+        formatted.join(", ")
+    }
+}
+
+/// Print a pipeline's backups, newest first
+fn print_backups_table(pipeline: &str, backups: &[BackupInfo]) {
+    if backups.is_empty() {
+        println!("📭 No backups found for pipeline: {pipeline}");
+        return;
+    }
+
+    println!("💾 Backups of {pipeline} ({}):", backups.len());
+    println!("{:-<80}", "");
+    println!(
+        "{:<30} {:<20} {:>10} {:>8}  Type",
+        "Backup ID", "Created", "Size", "Version"
+    );
+    println!("{:-<80}", "");
+
+    for backup in backups {
+        // Unreadable backups report version 0
+        let version = match backup.state_version {
+            0 => "corrupt".to_string(),
+            v => v.to_string(),
+        };
+        println!(
+            "{:<30} {:<20} {:>10} {:>8}  {:?}",
+            backup.backup_id,
+            backup.created_at.format("%Y-%m-%d %H:%M:%S"),
+            backup.file_size_bytes,
+            version,
+            backup.backup_type
+        );
+    }
+}
+
 /// Print workers in a table format
 fn print_workers_table(workers: &[serde_json::Value], verbose: bool) {
     if workers.is_empty() {
@@ -502,18 +1480,21 @@ fn print_workers_table(workers: &[serde_json::Value], verbose: bool) {
     }
 
     println!("👥 Active Workers ({}):", workers.len());
-    println!("{:-<80}", "");
+    println!("{:-<90}", "");
+
+    let heartbeat_age =
+        |worker: &serde_json::Value| format_age(worker["heartbeat_age_secs"].as_i64().unwrap_or(0));
 
     if verbose {
         println!(
-            "{:<15} {:<20} {:<15} {:<20} {:<10}",
-            "Worker ID", "Pipeline", "Status", "Last Heartbeat", "Step"
+            "{:<15} {:<20} {:<15} {:<20} {:<10} {:<10}",
+            "Worker ID", "Pipeline", "Status", "Last Heartbeat", "Age", "Step"
         );
-        println!("{:-<80}", "");
+        println!("{:-<90}", "");
 
         for worker in workers {
             println!(
-                "{:<15} {:<20} {:<15} {:<20} {}",
+                "{:<15} {:<20} {:<15} {:<20} {:<10} {}",
                 worker["worker_id"].as_str().unwrap_or(""),
                 worker["pipeline_id"].as_str().unwrap_or(""),
                 worker["status"].as_str().unwrap_or(""),
@@ -521,12 +1502,16 @@ fn print_workers_table(workers: &[serde_json::Value], verbose: bool) {
                     .as_str()
                     .map(|s| &s[11..19])
                     .unwrap_or(""),
+                heartbeat_age(worker),
                 worker["current_step"].as_str().unwrap_or("")
             );
         }
     } else {
-        println!("{:<15} {:<20} {:<10}", "Worker ID", "Pipeline", "Active");
-        println!("{:-<50}", "");
+        println!(
+            "{:<15} {:<20} {:<10} {:<10}",
+            "Worker ID", "Pipeline", "Active", "Heartbeat"
+        );
+        println!("{:-<60}", "");
 
         for worker in workers {
             let active_icon = if worker["active"].as_bool().unwrap_or(false) {
@@ -536,11 +1521,188 @@ fn print_workers_table(workers: &[serde_json::Value], verbose: bool) {
             };
 
             println!(
-                "{:<15} {:<20} {}",
+                "{:<15} {:<20} {:<10} {}",
                 worker["worker_id"].as_str().unwrap_or(""),
                 worker["pipeline_id"].as_str().unwrap_or(""),
-                active_icon
+                active_icon,
+                heartbeat_age(worker)
             );
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn memory_state_manager() -> StateManager {
+        StateManager::new(StateManagerConfig {
+            backend: BackendConfig::Memory { persist_dir: None },
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_worker_heartbeat_registers_and_ticks() {
+        let manager = memory_state_manager().await;
+        let state = PipelineState::new("orders".to_string(), "run_1".to_string());
+        manager.save_state(&state).await.unwrap();
+
+        let heartbeat = start_worker_heartbeat(
+            &manager,
+            "orders",
+            "external-runner",
+            Duration::from_millis(20),
+        )
+        .await
+        .unwrap();
+
+        let registered = manager.load_state("orders").await.unwrap();
+        assert_eq!(registered.worker_id.as_deref(), Some("external-runner"));
+        let mut last_heartbeat = registered.last_heartbeat;
+        for _ in 0..2 {
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            let state = manager.load_state("orders").await.unwrap();
+            assert!(state.last_heartbeat > last_heartbeat);
+            assert_eq!(state.version, registered.version);
+            last_heartbeat = state.last_heartbeat;
+        }
+        assert!(heartbeat.is_running());
+        heartbeat.stop();
+    }
+
+    #[tokio::test]
+    async fn test_worker_heartbeat_requires_state() {
+        let manager = memory_state_manager().await;
+        let err = start_worker_heartbeat(&manager, "missing", "w", Duration::from_millis(20))
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, StateError::PipelineNotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_render_step_state_shows_only_requested_step() {
+        use crate::state::types::{StepState, StepStatus};
+
+        let manager = memory_state_manager().await;
+        let mut state = PipelineState::new("orders".to_string(), "run_1".to_string());
+        let mut reader = StepState::new("reader".to_string(), "read_file".to_string());
+        reader.status = StepStatus::Completed {
+            completed_at: Utc::now(),
+        };
+        reader.records_processed = 120;
+        reader.config_hash = Some("hash-reader".to_string());
+        let mut writer = StepState::new("writer".to_string(), "write_file".to_string());
+        writer.status = StepStatus::Failed {
+            error: "disk full".to_string(),
+            failed_at: Utc::now(),
+        };
+        writer.retry_count = 2;
+        writer.error_count = 3;
+        writer.config_hash = Some("hash-writer".to_string());
+        state.step_states.insert("reader".to_string(), reader);
+        state.step_states.insert("writer".to_string(), writer);
+        manager.save_state(&state).await.unwrap();
+        let state = manager.load_state("orders").await.unwrap();
+
+        let text = render_step_state(&state, "writer", false, false).unwrap();
+        assert!(text.contains("Step State: writer (orders)"));
+        assert!(text.contains("disk full"));
+        assert!(text.contains("Retries: 2"));
+        assert!(text.contains("Errors: 3"));
+        assert!(text.contains("hash-writer"));
+        assert!(!text.contains("reader"));
+        assert!(!text.contains("hash-reader"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_step_state(&state, "reader", true, false).unwrap())
+                .unwrap();
+        assert_eq!(json["step_id"], "reader");
+        assert_eq!(json["records_processed"], 120);
+        assert!(json.get("step_states").is_none());
+
+        let yaml = render_step_state(&state, "reader", false, true).unwrap();
+        assert!(yaml.contains("step_id: reader"));
+        assert!(!yaml.contains("writer"));
+
+        let err = render_step_state(&state, "transform", false, false).unwrap_err();
+        assert!(err.to_string().contains("known steps: reader, writer"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_workers_filters_stale() {
+        let manager = memory_state_manager().await;
+
+        let mut fresh = PipelineState::new("fresh_pipeline".to_string(), "run_1".to_string());
+        fresh.worker_id = Some("worker_fresh".to_string());
+        fresh.last_heartbeat = Utc::now() - chrono::Duration::seconds(30);
+        manager.save_state(&fresh).await.unwrap();
+
+        let mut stale = PipelineState::new("stale_pipeline".to_string(), "run_2".to_string());
+        stale.worker_id = Some("worker_stale".to_string());
+        stale.last_heartbeat = Utc::now() - chrono::Duration::minutes(20);
+        manager.save_state(&stale).await.unwrap();
+
+        let all = collect_workers(&manager, None, false, 5).await.unwrap();
+        assert_eq!(all.len(), 2);
+        let stale_record = all
+            .iter()
+            .find(|w| w["worker_id"] == "worker_stale")
+            .unwrap();
+        assert_eq!(stale_record["active"], false);
+        let age = stale_record["heartbeat_age_secs"].as_i64().unwrap();
+        assert!((1200..1210).contains(&age));
+
+        let active = collect_workers(&manager, None, true, 5).await.unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0]["worker_id"], "worker_fresh");
+        assert!(active[0]["heartbeat_age_secs"].as_i64().unwrap() < 60);
+
+        // A wider window keeps the older worker
+        let lenient = collect_workers(&manager, None, true, 30).await.unwrap();
+        assert_eq!(lenient.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_collect_stats_groups_by_pipeline_name() {
+        let manager = memory_state_manager().await;
+
+        for (id, name, secs) in [("etl_a", "etl", 60), ("etl_b", "etl", 120)] {
+            let mut state = PipelineState::new(id.to_string(), "run".to_string());
+            state.metadata.pipeline_name = Some(name.to_string());
+            state.status = PipelineStatus::Completed {
+                completed_at: state.started_at + chrono::Duration::seconds(secs),
+            };
+            manager.save_state(&state).await.unwrap();
+        }
+        let mut running = PipelineState::new("reports".to_string(), "run".to_string());
+        running.status = PipelineStatus::Running {
+            started_at: running.started_at,
+        };
+        manager.save_state(&running).await.unwrap();
+
+        let stats = collect_stats(&manager, None).await.unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0]["pipeline"], "etl");
+        assert_eq!(stats[0]["runs"], 2);
+        assert_eq!(stats[0]["median_duration_ms"], 90_000);
+        assert_eq!(stats[0]["p95_duration_ms"], 117_000);
+        assert_eq!(stats[1]["pipeline"], "reports");
+        assert_eq!(stats[1]["other"], 1);
+        assert!(stats[1]["median_duration_ms"].is_null());
+
+        let filtered = collect_stats(&manager, Some("reports")).await.unwrap();
+        assert_eq!(filtered.len(), 1);
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(42), "42s ago");
+        assert_eq!(format_age(150), "2m ago");
+        assert_eq!(format_age(7200), "2h ago");
+        assert_eq!(format_age(172800), "2d ago");
+    }
+}
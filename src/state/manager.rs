@@ -1,13 +1,186 @@
 use crate::state::backend::{
-    BackendConfig, BackendHealth, CleanupResult, FileBackend, LockInfo, MemoryBackend, StateBackend,
+    BackendConfig, BackendHealth, BackupInfo, CleanupResult, FileBackend, HealthTrend,
+    ImportAllResult, IntegrityReport, LockInfo, MemoryBackend, OrphanKind, OrphanedFile,
+    RepairResult, StateBackend, StateChangeEvent, StateStream, ValidationResult,
+};
+use crate::state::types::{
+    ErrorRecord, ErrorType, PipelineState, PipelineStatus, ResumeCheckpoint, StateError,
+    StateLimits, StateQuery, StateSummary, StepState, StepStatus,
 };
-use crate::state::types::{ErrorRecord, PipelineState, StateError, StepState};
 use async_trait::async_trait;
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::Duration;
 use uuid::Uuid;
 
+/// Percentile (0.0..=1.0) of durations sorted shortest first
+///
+/// Interpolates between neighbouring samples, so the median of an even
+/// number of runs is the mean of the middle two.
+pub fn duration_percentile(sorted: &[Duration], percentile: f64) -> Option<Duration> {
+    let last = sorted.len().checked_sub(1)?;
+    let rank = percentile.clamp(0.0, 1.0) * last as f64;
+    let (lower, upper) = (sorted[rank.floor() as usize], sorted[rank.ceil() as usize]);
+    Some(lower + (upper.saturating_sub(lower)).mul_f64(rank.fract()))
+}
+
+/// Compact `state` to `limits`, returning the errors it evicted for archival
+fn compact_state(state: &mut PipelineState, limits: StateLimits) -> Vec<ErrorRecord> {
+    state.limits = limits;
+    state.compact();
+    std::mem::take(&mut state.evicted_errors)
+}
+
+/// Outcome of [`StateManager::purge_completed`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PurgeResult {
+    /// States deleted, or that would be deleted in a dry run
+    pub deleted_ids: Vec<String>,
+    /// Completed states kept because a worker still holds their lock
+    pub skipped_ids: Vec<String>,
+    /// Serialized size of the deleted states
+    pub freed_bytes: u64,
+    /// Per-pipeline failures; one bad state does not stop the purge
+    pub errors: Vec<String>,
+}
+
+/// Outcome of [`StateManager::recover_stale_runs`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryResult {
+    /// Stale runs marked `Failed`
+    pub failed_ids: Vec<String>,
+    /// Stale runs reset to `Pending`, with `auto_requeue_stale` on
+    pub requeued_ids: Vec<String>,
+    /// Stale runs left alone because another worker holds their lock
+    pub skipped_ids: Vec<String>,
+    /// Per-pipeline failures; one bad state does not stop the recovery
+    pub errors: Vec<String>,
+}
+
+/// Outcome of [`StateManager::collect_garbage`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GcResult {
+    pub orphaned_locks_removed: u64,
+    pub orphaned_backups_removed: u64,
+    pub temp_files_removed: u64,
+    pub empty_states_removed: u64,
+    pub freed_bytes: u64,
+    /// Files removed, or that would be removed in a dry run
+    pub removed: Vec<OrphanedFile>,
+    /// Files that could not be removed; the rest are still collected
+    pub errors: Vec<String>,
+}
+
+/// Outcome of [`StateManager::compare_runs`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunComparison {
+    pub pipeline_id: String,
+    pub run_a: RunSummary,
+    pub run_b: RunSummary,
+    /// One entry per step seen in either run, sorted by step ID
+    pub steps: Vec<StepComparison>,
+    /// Errors recorded by run A but not by run B, as `step: message`
+    pub errors_only_in_a: Vec<String>,
+    /// Errors recorded by run B but not by run A, as `step: message`
+    pub errors_only_in_b: Vec<String>,
+}
+
+impl RunComparison {
+    /// Steps whose status or record count differs between the runs
+    pub fn changed_steps(&self) -> impl Iterator<Item = &StepComparison> {
+        self.steps.iter().filter(|step| step.changed())
+    }
+
+    /// Records processed by run B minus those processed by run A
+    pub fn records_delta(&self) -> i64 {
+        self.run_b.records_processed as i64 - self.run_a.records_processed as i64
+    }
+
+    /// How much longer run B took than run A, when both have finished
+    pub fn duration_delta_ms(&self) -> Option<i64> {
+        Some(self.run_b.duration_ms? as i64 - self.run_a.duration_ms? as i64)
+    }
+}
+
+/// Totals of one run in a [`RunComparison`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub run_id: String,
+    pub status: String,
+    pub records_processed: u64,
+    pub records_failed: u64,
+    /// Time from start to completion or failure; `None` while the run is unfinished
+    pub duration_ms: Option<u64>,
+    pub error_count: usize,
+}
+
+impl RunSummary {
+    fn from_state(state: &PipelineState) -> Self {
+        let finished_at = match &state.status {
+            PipelineStatus::Completed { completed_at } => Some(*completed_at),
+            PipelineStatus::Failed { failed_at, .. } => Some(*failed_at),
+            _ => None,
+        };
+        let status = match state.status {
+            PipelineStatus::Pending => "pending",
+            PipelineStatus::Running { .. } => "running",
+            PipelineStatus::Completed { .. } => "completed",
+            PipelineStatus::Failed { .. } => "failed",
+            PipelineStatus::Paused { .. } => "paused",
+        };
+
+        Self {
+            run_id: state.run_id.clone(),
+            status: status.to_string(),
+            records_processed: state.records_processed,
+            records_failed: state.records_failed,
+            duration_ms: finished_at.and_then(|finished_at| {
+                u64::try_from((finished_at - state.started_at).num_milliseconds()).ok()
+            }),
+            error_count: state.errors.len(),
+        }
+    }
+}
+
+/// One step of a [`RunComparison`]; `None` fields mean the run never reached the step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepComparison {
+    pub step_id: String,
+    pub status_a: Option<String>,
+    pub status_b: Option<String>,
+    pub records_a: Option<u64>,
+    pub records_b: Option<u64>,
+    pub duration_ms_a: Option<u64>,
+    pub duration_ms_b: Option<u64>,
+}
+
+impl StepComparison {
+    /// Whether the step ended differently or processed a different number of records
+    pub fn changed(&self) -> bool {
+        self.status_a != self.status_b || self.records_a != self.records_b
+    }
+}
+
+fn step_status_name(status: &StepStatus) -> &'static str {
+    match status {
+        StepStatus::Pending => "pending",
+        StepStatus::Running { .. } => "running",
+        StepStatus::Completed { .. } => "completed",
+        StepStatus::Failed { .. } => "failed",
+        StepStatus::Skipped { .. } => "skipped",
+    }
+}
+
+fn describe_error(error: &ErrorRecord) -> String {
+    format!(
+        "{}: {}",
+        error.step_id.as_deref().unwrap_or("pipeline"),
+        error.message
+    )
+}
+
 /// Configuration for the StateManager
 #[derive(Debug, Clone)]
 pub struct StateManagerConfig {
@@ -17,6 +190,11 @@ pub struct StateManagerConfig {
     /// Default lock timeout in milliseconds
     pub default_lock_timeout_ms: u64,
 
+    /// How long a lock lasts without renewal, in milliseconds
+    ///
+    /// Independent of the time a caller is willing to wait for the lock.
+    pub lock_ttl_ms: u64,
+
     /// Worker ID for this instance
     pub worker_id: String,
 
@@ -31,26 +209,71 @@ pub struct StateManagerConfig {
 
     /// Maximum age for state files in hours before cleanup
     pub max_state_age_hours: u64,
+
+    /// Number of snapshots kept by the health monitor
+    pub health_history_size: usize,
+
+    /// Fail runs of crashed workers before starting a run, see [`StateManager::recover_stale_runs`]
+    pub recover_stale_runs: bool,
+
+    /// Heartbeat age in milliseconds after which a running pipeline counts as lost
+    pub stale_run_threshold_ms: u64,
+
+    /// Reset recovered runs to `Pending` instead of leaving them `Failed`
+    pub auto_requeue_stale: bool,
+
+    /// Let heartbeat tasks also claim abandoned runs, see [`StateManager::claim_abandoned`]
+    pub auto_claim_abandoned: bool,
+
+    /// How often heartbeat tasks look for abandoned runs, in milliseconds
+    pub auto_claim_interval_ms: u64,
+
+    /// Most errors kept in a pipeline's state; older ones move to the error archive
+    pub max_errors_in_state: usize,
+
+    /// Runs whose completed steps are kept in full; older ones are collapsed to summaries
+    pub step_state_runs: usize,
+
+    /// Most step summaries kept in a pipeline's state; older ones are dropped
+    pub max_step_summaries: usize,
+
+    /// Most runs kept in a pipeline's run history; the least recently saved are deleted
+    pub max_saved_runs: usize,
 }
 
 impl Default for StateManagerConfig {
     fn default() -> Self {
         Self {
-            backend: BackendConfig::Memory { persistent: false },
+            backend: BackendConfig::Memory { persist_dir: None },
             default_lock_timeout_ms: 30000, // 30 seconds
+            lock_ttl_ms: 60000,             // 1 minute
             worker_id: format!("worker_{}", Uuid::new_v4()),
             heartbeat_interval_ms: 5000, // 5 seconds
             max_retries: 3,
             cleanup_interval_hours: 24, // Daily cleanup
             max_state_age_hours: 168,   // 7 days
+            health_history_size: 100,
+            recover_stale_runs: false,
+            stale_run_threshold_ms: 300000, // 5 minutes
+            auto_requeue_stale: false,
+            auto_claim_abandoned: false,
+            auto_claim_interval_ms: 60000, // 1 minute
+            max_errors_in_state: 500,
+            step_state_runs: 3,
+            max_step_summaries: 1000,
+            max_saved_runs: 50,
         }
     }
 }
 
 /// High-level state manager providing pipeline state management operations
+#[derive(Clone)]
 pub struct StateManager {
     backend: Arc<dyn StateBackend>,
     config: StateManagerConfig,
+    health_history: Arc<std::sync::Mutex<VecDeque<BackendHealth>>>,
+    /// Told about every state save, new error and lock change made through this manager
+    observers: Vec<Arc<dyn StateObserver>>,
 }
 
 impl StateManager {
@@ -58,7 +281,10 @@ impl StateManager {
     pub async fn new(config: StateManagerConfig) -> Result<Self, StateError> {
         let backend: Arc<dyn StateBackend> = match &config.backend {
             BackendConfig::File { .. } => Arc::new(FileBackend::new(config.backend.clone())?),
-            BackendConfig::Memory { .. } => Arc::new(MemoryBackend::new()),
+            BackendConfig::Memory {
+                persist_dir: Some(dir),
+            } => Arc::new(MemoryBackend::new_persistent(dir.clone())?),
+            BackendConfig::Memory { persist_dir: None } => Arc::new(MemoryBackend::new()),
             BackendConfig::Redis { .. } => {
                 return Err(StateError::BackendError {
                     details: "Redis backend not yet implemented".to_string(),
@@ -66,19 +292,43 @@ impl StateManager {
             }
         };
 
-        Ok(Self { backend, config })
+        Ok(Self {
+            backend,
+            config,
+            health_history: Arc::default(),
+            observers: Vec::new(),
+        })
     }
 
     /// Create a new StateManager with memory backend (for testing)
     pub fn new_memory() -> Self {
         let config = StateManagerConfig {
-            backend: BackendConfig::Memory { persistent: false },
+            backend: BackendConfig::Memory { persist_dir: None },
             ..Default::default()
         };
 
         Self {
             backend: Arc::new(MemoryBackend::new()),
             config,
+            health_history: Arc::default(),
+            observers: Vec::new(),
+        }
+    }
+
+    /// A manager on the same backend acting as another worker
+    ///
+    /// Its heartbeats and locks are recorded for `worker_id`, and its
+    /// heartbeat task ticks every `heartbeat_interval_ms`.
+    pub fn for_worker(&self, worker_id: &str, heartbeat_interval_ms: u64) -> Self {
+        Self {
+            backend: Arc::clone(&self.backend),
+            config: StateManagerConfig {
+                worker_id: worker_id.to_string(),
+                heartbeat_interval_ms,
+                ..self.config.clone()
+            },
+            health_history: Arc::default(),
+            observers: self.observers.clone(),
         }
     }
 
@@ -102,24 +352,184 @@ impl StateManager {
 
     /// Load pipeline state by ID
     pub async fn load_state(&self, pipeline_id: &str) -> Result<PipelineState, StateError> {
-        self.backend.load_state(pipeline_id).await
+        let mut state = self.backend.load_state(pipeline_id).await?;
+        state.limits = self.limits();
+        Ok(state)
     }
 
     /// Save pipeline state with retry logic
+    ///
+    /// A copy of the state is compacted to the configured limits before it is
+    /// written, and the errors evicted from it, including those `add_error`
+    /// set aside, are moved to the pipeline's error archive. `state` itself is
+    /// left as is; use [`StateManager::save_state_compacted`] for a state that
+    /// is kept and saved again, so its evicted errors are archived only once.
     pub async fn save_state(&self, state: &PipelineState) -> Result<(), StateError> {
+        self.save_state_compacted(&mut state.clone()).await
+    }
+
+    /// Compact `state` in place, archive the errors evicted from it, then save it
+    pub async fn save_state_compacted(&self, state: &mut PipelineState) -> Result<(), StateError> {
+        self.write_state(state).await?;
+        self.notify_state_change(None, state).await;
+        Ok(())
+    }
+
+    /// `save_state_compacted` without telling observers
+    async fn write_state(&self, state: &mut PipelineState) -> Result<(), StateError> {
+        let evicted = compact_state(state, self.limits());
+        // Archived first: a failed save then repeats errors rather than losing them
+        if let Err(e) = self
+            .backend
+            .append_error_archive(&state.pipeline_id, &evicted)
+            .await
+        {
+            state.evicted_errors = evicted;
+            return Err(e);
+        }
+        let state = &*state;
         self.retry_operation(|| async { self.backend.save_state(state).await })
             .await
     }
 
-    /// Update pipeline state with a closure
-    pub async fn update_state<F, R>(&self, pipeline_id: &str, updater: F) -> Result<R, StateError>
+    /// Summaries of the states matching `query`, see [`StateBackend::query_states`]
+    pub async fn query_states(&self, query: StateQuery) -> Result<Vec<StateSummary>, StateError> {
+        self.backend.query_states(query).await
+    }
+
+    /// Rebuild the backend's state index, returning the number of states indexed
+    pub async fn reindex(&self) -> Result<usize, StateError> {
+        self.backend.reindex().await
+    }
+
+    /// Errors archived from a pipeline's state, oldest first
+    pub async fn load_error_archive(
+        &self,
+        pipeline_id: &str,
+    ) -> Result<Vec<ErrorRecord>, StateError> {
+        self.backend.load_error_archive(pipeline_id).await
+    }
+
+    /// Save the checkpoint a paused run resumes from, see [`StateBackend::save_resume_checkpoint`]
+    pub async fn save_resume_checkpoint(
+        &self,
+        pipeline_id: &str,
+        checkpoint: &ResumeCheckpoint,
+    ) -> Result<(), StateError> {
+        self.backend
+            .save_resume_checkpoint(pipeline_id, checkpoint)
+            .await
+    }
+
+    /// Load a pipeline's resume checkpoint, `None` if none was saved
+    pub async fn load_resume_checkpoint(
+        &self,
+        pipeline_id: &str,
+    ) -> Result<Option<ResumeCheckpoint>, StateError> {
+        self.backend.load_resume_checkpoint(pipeline_id).await
+    }
+
+    /// Remove a pipeline's resume checkpoint, if any
+    pub async fn delete_resume_checkpoint(&self, pipeline_id: &str) -> Result<(), StateError> {
+        self.backend.delete_resume_checkpoint(pipeline_id).await
+    }
+
+    /// Growth caps applied to the states this manager loads and saves
+    pub fn limits(&self) -> StateLimits {
+        StateLimits {
+            max_errors_in_state: self.config.max_errors_in_state,
+            step_state_runs: self.config.step_state_runs,
+            max_step_summaries: self.config.max_step_summaries,
+            max_saved_runs: self.config.max_saved_runs,
+        }
+    }
+
+    /// Tell `observer` about the state saves, new errors and lock changes made from now on
+    pub fn add_observer(&mut self, observer: Arc<dyn StateObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Wait for observers to finish handling the events they were given,
+    /// e.g. before the process exits
+    pub async fn flush_observers(&self) {
+        for observer in &self.observers {
+            observer.flush().await;
+        }
+    }
+
+    /// A copy of `state` to compare the saved state with, when anyone is listening
+    fn observed(&self, state: &PipelineState) -> Option<PipelineState> {
+        (!self.observers.is_empty()).then(|| state.clone())
+    }
+
+    /// Report a saved state, and the errors it gained since `old_state`, to observers
+    async fn notify_state_change(&self, old_state: Option<&PipelineState>, state: &PipelineState) {
+        for observer in &self.observers {
+            observer
+                .on_state_change(&state.pipeline_id, old_state, state)
+                .await;
+        }
+        let Some(old_state) = old_state else {
+            return;
+        };
+        for error in state.errors.iter().filter(|error| {
+            !old_state
+                .errors
+                .iter()
+                .any(|old| old.error_id == error.error_id)
+        }) {
+            for observer in &self.observers {
+                observer.on_error(&state.pipeline_id, error).await;
+            }
+        }
+    }
+
+    /// Update pipeline state with a closure, without taking the pipeline lock
+    ///
+    /// The update goes through `StateBackend::atomic_update`. When another
+    /// writer saves the state first, `updater` is run again on the newer
+    /// state, up to `max_retries` times before the `VersionConflict` is
+    /// returned.
+    pub async fn update_state<F, R>(
+        &self,
+        pipeline_id: &str,
+        mut updater: F,
+    ) -> Result<R, StateError>
     where
-        F: FnOnce(&mut PipelineState) -> R,
+        F: FnMut(&mut PipelineState) -> R + Send,
+        R: Send,
     {
-        let mut state = self.load_state(pipeline_id).await?;
-        let result = updater(&mut state);
-        self.save_state(&state).await?;
-        Ok(result)
+        let limits = self.limits();
+        let mut conflicts = 0;
+
+        loop {
+            let mut state = self.load_state(pipeline_id).await?;
+            let expected_version = state.version;
+            let previous = self.observed(&state);
+            let result = updater(&mut state);
+            let evicted = compact_state(&mut state, limits);
+            // Archived before the save, as in `save_state`: a conflict or a
+            // failed save then repeats errors rather than losing them
+            self.backend
+                .append_error_archive(pipeline_id, &evicted)
+                .await?;
+
+            let updated = self
+                .backend
+                .atomic_update(pipeline_id, expected_version, Box::new(|_| state.clone()))
+                .await;
+
+            match updated {
+                Ok(_) => {
+                    self.notify_state_change(previous.as_ref(), &state).await;
+                    return Ok(result);
+                }
+                Err(StateError::VersionConflict { .. }) if conflicts < self.config.max_retries => {
+                    conflicts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
     /// Update pipeline state with locking
@@ -131,15 +541,27 @@ impl StateManager {
     where
         F: FnOnce(&mut PipelineState) -> R,
     {
-        let _lock = self
+        let lock = self
             .acquire_lock(pipeline_id, self.config.default_lock_timeout_ms)
             .await?;
 
-        let mut state = self.load_state(pipeline_id).await?;
-        let result = updater(&mut state);
-        self.save_state(&state).await?;
+        let updated = async {
+            let mut state = self.load_state(pipeline_id).await?;
+            let previous = self.observed(&state);
+            let result = updater(&mut state);
+            // Lets a concurrent `update_state` notice this write
+            state.increment_version();
+            self.write_state(&mut state).await?;
+            self.notify_state_change(previous.as_ref(), &state).await;
+            Ok(result)
+        }
+        .await;
 
-        Ok(result)
+        // Release before returning, so a process exiting right after leaves no lock behind
+        if let Err(e) = lock.release().await {
+            log::warn!("Failed to release lock for pipeline {pipeline_id}: {e}");
+        }
+        updated
     }
 
     /// Delete pipeline state
@@ -147,27 +569,211 @@ impl StateManager {
         self.backend.delete_state(pipeline_id).await
     }
 
+    /// Move a pipeline's state to a new ID, e.g. after the pipeline is renamed
+    pub async fn rename_pipeline(&self, old_id: &str, new_id: &str) -> Result<(), StateError> {
+        if let Some(lock) = self.is_locked(old_id).await? {
+            return Err(StateError::LockAlreadyHeld {
+                worker_id: lock.worker_id,
+            });
+        }
+        if self.list_pipelines().await?.iter().any(|id| id == new_id) {
+            return Err(StateError::InvalidState {
+                details: format!("State for pipeline '{new_id}' already exists"),
+            });
+        }
+
+        let mut state = self.load_state(old_id).await?;
+        // Carry the run history over before the current state
+        for run_id in self.backend.list_runs(old_id).await? {
+            if run_id != state.run_id {
+                let mut run = self.backend.load_run(old_id, &run_id).await?;
+                run.pipeline_id = new_id.to_string();
+                self.save_state(&run).await?;
+            }
+        }
+        state.pipeline_id = new_id.to_string();
+        state.metadata.updated_at = Utc::now();
+        self.save_state(&state).await?;
+        self.delete_state(old_id).await
+    }
+
     /// List all pipeline IDs
     pub async fn list_pipelines(&self) -> Result<Vec<String>, StateError> {
         self.backend.list_pipelines().await
     }
 
-    /// Acquire a lock on pipeline state
+    /// Every pipeline's state, loaded one at a time like [`StateManager::load_state`]
+    ///
+    /// See [`StateBackend::stream_pipelines`]; prefer this over loading each
+    /// listed pipeline when scanning many states.
+    pub async fn stream_pipelines(&self) -> Result<StateStream<'_>, StateError> {
+        let pipeline_ids = self.list_pipelines().await?;
+        Ok(StateStream::new(pipeline_ids, move |pipeline_id| {
+            Box::pin(async move { self.load_state(&pipeline_id).await })
+        }))
+    }
+
+    /// List the runs of a pipeline that saved state
+    pub async fn list_runs(&self, pipeline_id: &str) -> Result<Vec<String>, StateError> {
+        self.backend.list_runs(pipeline_id).await
+    }
+
+    /// Load the last state saved by one run of a pipeline
+    pub async fn load_run(
+        &self,
+        pipeline_id: &str,
+        run_id: &str,
+    ) -> Result<PipelineState, StateError> {
+        self.backend.load_run(pipeline_id, run_id).await
+    }
+
+    /// Compare the saved states of two runs of a pipeline
+    ///
+    /// Steps are listed in step ID order, including steps only one of the
+    /// runs reached. Errors are matched on step and message.
+    pub async fn compare_runs(
+        &self,
+        pipeline_id: &str,
+        run_id_a: &str,
+        run_id_b: &str,
+    ) -> anyhow::Result<RunComparison> {
+        let a = self.load_run(pipeline_id, run_id_a).await?;
+        let b = self.load_run(pipeline_id, run_id_b).await?;
+
+        let mut step_ids: Vec<&String> = a.step_states.keys().chain(b.step_states.keys()).collect();
+        step_ids.sort();
+        step_ids.dedup();
+        let steps = step_ids
+            .into_iter()
+            .map(|step_id| {
+                let (step_a, step_b) = (a.step_states.get(step_id), b.step_states.get(step_id));
+                StepComparison {
+                    step_id: step_id.clone(),
+                    status_a: step_a.map(|step| step_status_name(&step.status).to_string()),
+                    status_b: step_b.map(|step| step_status_name(&step.status).to_string()),
+                    records_a: step_a.map(|step| step.records_processed),
+                    records_b: step_b.map(|step| step.records_processed),
+                    duration_ms_a: step_a.map(|step| step.processing_time_ms),
+                    duration_ms_b: step_b.map(|step| step.processing_time_ms),
+                }
+            })
+            .collect();
+
+        let errors_a: Vec<String> = a.errors.iter().map(describe_error).collect();
+        let errors_b: Vec<String> = b.errors.iter().map(describe_error).collect();
+
+        Ok(RunComparison {
+            pipeline_id: pipeline_id.to_string(),
+            errors_only_in_a: errors_a
+                .iter()
+                .filter(|error| !errors_b.contains(error))
+                .cloned()
+                .collect(),
+            errors_only_in_b: errors_b
+                .iter()
+                .filter(|error| !errors_a.contains(error))
+                .cloned()
+                .collect(),
+            run_a: RunSummary::from_state(&a),
+            run_b: RunSummary::from_state(&b),
+            steps,
+        })
+    }
+
+    /// Acquire a lock on pipeline state, waiting up to `timeout_ms` for it
+    ///
+    /// The lock lasts `lock_ttl_ms` and is renewed in the background while
+    /// the guard is held.
     pub async fn acquire_lock(
         &self,
         pipeline_id: &str,
         timeout_ms: u64,
     ) -> Result<StateManagerLock, StateError> {
+        let ttl_ms = self.config.lock_ttl_ms;
         let lock_info = self
             .backend
-            .acquire_lock(pipeline_id, &self.config.worker_id, timeout_ms)
+            .acquire_lock(pipeline_id, &self.config.worker_id, timeout_ms, ttl_ms)
             .await?;
+        let lease = Arc::new(std::sync::Mutex::new(Some(lock_info)));
+        let renewal = Self::start_lease_renewal(
+            Arc::clone(&self.backend),
+            pipeline_id.to_string(),
+            self.config.worker_id.clone(),
+            Arc::clone(&lease),
+            Duration::from_millis((ttl_ms / 3).max(1)),
+        );
+
+        for observer in &self.observers {
+            observer
+                .on_lock_acquired(pipeline_id, &self.config.worker_id)
+                .await;
+        }
 
         Ok(StateManagerLock {
             pipeline_id: pipeline_id.to_string(),
             worker_id: self.config.worker_id.clone(),
             backend: Arc::clone(&self.backend),
-            lock_info,
+            lease,
+            renewal,
+            released: false,
+            observers: self.observers.clone(),
+        })
+    }
+
+    /// Take a pipeline's lock if it is free, without waiting
+    ///
+    /// Returns `None` at once when another worker holds the lock, for
+    /// callers that would rather skip the work than queue behind it.
+    pub async fn try_acquire_lock(
+        &self,
+        pipeline_id: &str,
+    ) -> Result<Option<StateManagerLock>, StateError> {
+        match self.acquire_lock(pipeline_id, 0).await {
+            Ok(lock) => Ok(Some(lock)),
+            Err(StateError::LockTimeout { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Current holders of pipeline locks, longest held first
+    ///
+    /// Only pipelines with saved state are checked.
+    pub async fn lock_holders(&self) -> Result<Vec<LockInfo>, StateError> {
+        let mut holders = Vec::new();
+        for pipeline_id in self.backend.list_pipelines().await? {
+            holders.extend(self.backend.is_locked(&pipeline_id).await?);
+        }
+        holders.sort_by_key(|lock| lock.locked_at);
+        Ok(holders)
+    }
+
+    /// Renew a lock every `interval` until the guard drops or the lock is lost
+    ///
+    /// A lost lock is reported on stderr and clears the shared lease, so
+    /// `StateManagerLock::is_valid` turns false instead of silently expiring.
+    fn start_lease_renewal(
+        backend: Arc<dyn StateBackend>,
+        pipeline_id: String,
+        worker_id: String,
+        lease: Arc<std::sync::Mutex<Option<LockInfo>>>,
+        every: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            interval.tick().await; // The first tick completes immediately
+
+            loop {
+                interval.tick().await;
+
+                match backend.renew_lock(&pipeline_id, &worker_id).await {
+                    Ok(renewed) => *lease.lock().unwrap() = Some(renewed),
+                    Err(e) => {
+                        log::error!("Lock renewal failed for pipeline {pipeline_id}: {e}");
+                        *lease.lock().unwrap() = None;
+                        break;
+                    }
+                }
+            }
         })
     }
 
@@ -181,18 +787,27 @@ impl StateManager {
         self.backend.force_release_lock(pipeline_id).await
     }
 
+    /// Subscribe to state and lock changes across all pipelines
+    pub async fn watch_all(
+        &self,
+    ) -> Result<tokio::sync::broadcast::Receiver<StateChangeEvent>, StateError> {
+        self.backend.watch_all().await
+    }
+
     /// Update heartbeat for a pipeline
+    ///
+    /// Goes through [`StateBackend::touch_heartbeat`], so the state's version
+    /// is not bumped and concurrent state updates are neither lost nor retried.
     pub async fn update_heartbeat(&self, pipeline_id: &str) -> Result<(), StateError> {
-        self.update_state(pipeline_id, |state| {
-            state.update_heartbeat();
-        })
-        .await
+        self.backend
+            .touch_heartbeat(pipeline_id, &self.config.worker_id, Utc::now())
+            .await
     }
 
     /// Add an error to pipeline state
     pub async fn add_error(&self, pipeline_id: &str, error: ErrorRecord) -> Result<(), StateError> {
         self.update_state(pipeline_id, |state| {
-            state.add_error(error);
+            state.add_error(error.clone());
         })
         .await
     }
@@ -205,8 +820,9 @@ impl StateManager {
         step_state: StepState,
     ) -> Result<(), StateError> {
         self.update_state(pipeline_id, |state| {
-            state.step_states.insert(step_id.to_string(), step_state);
-            state.increment_version();
+            state
+                .step_states
+                .insert(step_id.to_string(), step_state.clone());
         })
         .await
     }
@@ -233,33 +849,331 @@ impl StateManager {
             state.records_processed += records_processed;
             state.data_size_processed += data_size_processed;
 
-            if let Some(id) = last_processed_id {
-                state.last_processed_id = id;
+            if let Some(id) = &last_processed_id {
+                state.last_processed_id = id.clone();
             }
 
             state.last_success_timestamp = Utc::now();
-            state.increment_version();
         })
         .await
     }
 
+    /// Refuse to start a run while another run of the pipeline is still alive
+    ///
+    /// A `Running` state whose heartbeat is older than `stale_threshold_ms`
+    /// belongs to a crashed worker and may be superseded.
+    pub async fn ensure_no_active_run(
+        &self,
+        pipeline_id: &str,
+        stale_threshold_ms: u64,
+    ) -> Result<(), StateError> {
+        let state = match self.load_state(pipeline_id).await {
+            Ok(state) => state,
+            Err(_) => return Ok(()), // Never run before
+        };
+
+        if matches!(state.status, PipelineStatus::Running { .. })
+            && !state.is_stale(stale_threshold_ms)
+        {
+            return Err(StateError::RunInProgress {
+                pipeline_id: pipeline_id.to_string(),
+                worker_id: state.worker_id.unwrap_or_else(|| "unknown".to_string()),
+                run_id: state.run_id,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Check for stale pipelines and clean them up
+    ///
+    /// Goes through [`StateManager::query_states`], so no state is loaded in
+    /// full. Heartbeats from [`StateManager::update_heartbeat`] count, since
+    /// the backend merges them into the summaries it returns.
     pub async fn find_stale_pipelines(
         &self,
         stale_threshold_ms: u64,
     ) -> Result<Vec<String>, StateError> {
-        let pipeline_ids = self.list_pipelines().await?;
-        let mut stale_pipelines = Vec::new();
+        Ok(self
+            .query_states(StateQuery::default())
+            .await?
+            .into_iter()
+            .filter(|summary| summary.is_stale(stale_threshold_ms))
+            .map(|summary| summary.pipeline_id)
+            .collect())
+    }
+
+    /// Fail `Running` pipelines whose worker stopped sending heartbeats
+    ///
+    /// A run is lost once its heartbeat is older than `stale_run_threshold_ms`.
+    /// Its lock is released if it expired or still belongs to the lost
+    /// worker; a run whose lock another worker now holds is skipped. Each
+    /// lost run gets a "worker lost" error and becomes `Failed`, or `Pending`
+    /// when `auto_requeue_stale` is set.
+    pub async fn recover_stale_runs(&self) -> Result<RecoveryResult, StateError> {
+        let mut result = RecoveryResult::default();
+
+        for pipeline_id in self
+            .find_stale_pipelines(self.config.stale_run_threshold_ms)
+            .await?
+        {
+            let Ok(state) = self.load_state(&pipeline_id).await else {
+                continue;
+            };
+            if !matches!(state.status, PipelineStatus::Running { .. }) {
+                continue;
+            }
 
-        for pipeline_id in pipeline_ids {
-            if let Ok(state) = self.load_state(&pipeline_id).await {
-                if state.is_stale(stale_threshold_ms) {
-                    stale_pipelines.push(pipeline_id);
+            match self.lock_abandoned(&state).await {
+                Ok(true) => {
+                    if let Err(e) = self.force_release_lock(&pipeline_id).await {
+                        result.errors.push(format!("{pipeline_id}: {e}"));
+                        continue;
+                    }
+                }
+                Ok(false) => {
+                    result.skipped_ids.push(pipeline_id);
+                    continue;
+                }
+                Err(e) => {
+                    result.errors.push(format!("{pipeline_id}: {e}"));
+                    continue;
                 }
             }
+
+            let requeue = self.config.auto_requeue_stale;
+            let threshold_ms = self.config.stale_run_threshold_ms;
+            let updated = self
+                .update_state(&pipeline_id, |state| {
+                    let worker = state.worker_id.as_deref().unwrap_or("unknown");
+                    state.add_error(ErrorRecord::new(
+                        None,
+                        ErrorType::Unknown,
+                        "worker lost".to_string(),
+                        format!("No heartbeat from {worker} for over {threshold_ms}ms"),
+                        true,
+                    ));
+                    state.status = if requeue {
+                        PipelineStatus::Pending
+                    } else {
+                        PipelineStatus::Failed {
+                            failed_at: Utc::now(),
+                            error: "worker lost".to_string(),
+                        }
+                    };
+                    state.worker_id = None;
+                })
+                .await;
+
+            match updated {
+                Ok(()) if requeue => result.requeued_ids.push(pipeline_id),
+                Ok(()) => result.failed_ids.push(pipeline_id),
+                Err(e) => result.errors.push(format!("{pipeline_id}: {e}")),
+            }
         }
 
-        Ok(stale_pipelines)
+        Ok(result)
+    }
+
+    /// Take over `Running` pipelines of workers that stopped sending heartbeats
+    ///
+    /// A run is abandoned once its heartbeat is older than `stale_threshold_ms`
+    /// and its lock expired or still belongs to the lost worker. Claiming sets
+    /// `worker_id` to this manager's worker and refreshes the heartbeat in one
+    /// atomic update, so when several workers race for a run only one wins;
+    /// the winner then releases the old lock. Returns the claimed pipelines.
+    pub async fn claim_abandoned(
+        &self,
+        stale_threshold_ms: u64,
+    ) -> Result<Vec<String>, StateError> {
+        let mut claimed = Vec::new();
+
+        for pipeline_id in self.find_stale_pipelines(stale_threshold_ms).await? {
+            let Ok(state) = self.load_state(&pipeline_id).await else {
+                continue;
+            };
+            if !matches!(state.status, PipelineStatus::Running { .. })
+                || state.worker_id.as_ref() == Some(&self.config.worker_id)
+                || !self.lock_abandoned(&state).await?
+            {
+                continue;
+            }
+
+            let lost_worker = state.worker_id;
+            let worker_id = self.config.worker_id.clone();
+            let won = self
+                .update_state(&pipeline_id, |state| {
+                    // Another worker may have claimed the run since it was loaded
+                    let still_abandoned = matches!(state.status, PipelineStatus::Running { .. })
+                        && state.is_stale(stale_threshold_ms)
+                        && state.worker_id == lost_worker;
+                    if still_abandoned {
+                        state.worker_id = Some(worker_id.clone());
+                        state.update_heartbeat();
+                    }
+                    still_abandoned
+                })
+                .await;
+
+            match won {
+                Ok(true) => {}
+                // Lost the race to workers that kept updating the state
+                Ok(false) | Err(StateError::VersionConflict { .. }) => continue,
+                Err(e) => return Err(e),
+            }
+            self.force_release_lock(&pipeline_id).await?;
+            claimed.push(pipeline_id);
+        }
+
+        Ok(claimed)
+    }
+
+    /// Whether a stale run's lock lets another worker take the run over
+    ///
+    /// Expired locks are already gone; a live lock only counts while it
+    /// still belongs to the run's own worker.
+    async fn lock_abandoned(&self, state: &PipelineState) -> Result<bool, StateError> {
+        Ok(match self.is_locked(&state.pipeline_id).await? {
+            None => true,
+            Some(lock) => Some(&lock.worker_id) == state.worker_id.as_ref(),
+        })
+    }
+
+    /// Durations of completed runs recorded for a pipeline, shortest first
+    ///
+    /// States are matched on their id or `metadata.pipeline_name`. Every run
+    /// in a matching state's history counts, not just the latest; failed and
+    /// in-flight runs are ignored.
+    pub async fn completed_durations(
+        &self,
+        pipeline_name: &str,
+    ) -> Result<Vec<Duration>, StateError> {
+        let mut durations = Vec::new();
+
+        let mut states = self.stream_pipelines().await?;
+        while let Some(next) = states.next().await {
+            let Ok((pipeline_id, latest)) = next else {
+                continue;
+            };
+            if latest.pipeline_id != pipeline_name
+                && latest.metadata.pipeline_name.as_deref() != Some(pipeline_name)
+            {
+                continue;
+            }
+
+            let run_ids = self.list_runs(&pipeline_id).await?;
+            let mut runs = Vec::with_capacity(run_ids.len().max(1));
+            for run_id in &run_ids {
+                // A run file that can't be read shouldn't hide the others
+                if let Ok(run) = self.load_run(&pipeline_id, run_id).await {
+                    runs.push(run);
+                }
+            }
+            if run_ids.is_empty() {
+                runs.push(latest);
+            }
+
+            for run in runs {
+                if let PipelineStatus::Completed { completed_at } = run.status {
+                    if let Ok(duration) = (completed_at - run.started_at).to_std() {
+                        durations.push(duration);
+                    }
+                }
+            }
+        }
+
+        durations.sort();
+        Ok(durations)
+    }
+
+    /// Delete completed pipeline states, optionally only those older than `older_than`
+    ///
+    /// Age is measured from `completed_at`. Running, failed and paused states
+    /// are never touched, and neither is a completed state whose lock is still
+    /// held. With `dry_run` the result lists what would be deleted.
+    pub async fn purge_completed(
+        &self,
+        older_than: Option<Duration>,
+        dry_run: bool,
+    ) -> anyhow::Result<PurgeResult> {
+        let cutoff = match older_than {
+            Some(age) => Some(Utc::now() - chrono::Duration::from_std(age)?),
+            None => None,
+        };
+        let mut result = PurgeResult::default();
+
+        for pipeline_id in self.list_pipelines().await? {
+            let state = match self.load_state(&pipeline_id).await {
+                Ok(state) => state,
+                Err(e) => {
+                    result.errors.push(format!("{pipeline_id}: {e}"));
+                    continue;
+                }
+            };
+            let PipelineStatus::Completed { completed_at } = state.status else {
+                continue;
+            };
+            if cutoff.is_some_and(|cutoff| completed_at > cutoff) {
+                continue;
+            }
+
+            match self.is_locked(&pipeline_id).await {
+                Ok(Some(_)) => {
+                    result.skipped_ids.push(pipeline_id);
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    result.errors.push(format!("{pipeline_id}: {e}"));
+                    continue;
+                }
+            }
+
+            let size = serde_json::to_vec_pretty(&state).map_or(0, |bytes| bytes.len() as u64);
+            if !dry_run {
+                if let Err(e) = self.delete_state(&pipeline_id).await {
+                    result.errors.push(format!("{pipeline_id}: {e}"));
+                    continue;
+                }
+            }
+            result.freed_bytes += size;
+            result.deleted_ids.push(pipeline_id);
+        }
+
+        Ok(result)
+    }
+
+    /// Delete the orphaned files reported by the backend's integrity check
+    ///
+    /// Stale lock files and backups of pipelines without state, zero-byte
+    /// state files and temporary files of interrupted writes are removed.
+    /// With `dry_run` the result lists what would be removed.
+    pub async fn collect_garbage(&self, dry_run: bool) -> Result<GcResult, StateError> {
+        let report = self.backend.verify_integrity().await?;
+        let mut result = GcResult::default();
+
+        for orphan in report.orphaned_files {
+            if !dry_run {
+                let removed = match orphan.kind {
+                    OrphanKind::Backup => tokio::fs::remove_dir_all(&orphan.path).await,
+                    _ => tokio::fs::remove_file(&orphan.path).await,
+                };
+                if let Err(e) = removed {
+                    result.errors.push(format!("{}: {e}", orphan.path));
+                    continue;
+                }
+            }
+            match orphan.kind {
+                OrphanKind::Lock => result.orphaned_locks_removed += 1,
+                OrphanKind::Backup => result.orphaned_backups_removed += 1,
+                OrphanKind::TempFile => result.temp_files_removed += 1,
+                OrphanKind::EmptyState => result.empty_states_removed += 1,
+            }
+            result.freed_bytes += orphan.size_bytes;
+            result.removed.push(orphan);
+        }
+
+        Ok(result)
     }
 
     /// Perform health check on the backend
@@ -267,29 +1181,149 @@ impl StateManager {
         self.backend.health_check().await
     }
 
+    /// Check that a pipeline's stored state can be read and passes validation
+    pub async fn validate_state(&self, pipeline_id: &str) -> Result<ValidationResult, StateError> {
+        self.backend.validate_state(pipeline_id).await
+    }
+
+    /// Check every stored state and report corrupted and orphaned files
+    pub async fn verify_integrity(&self) -> Result<IntegrityReport, StateError> {
+        self.backend.verify_integrity().await
+    }
+
+    /// Back up a pipeline's state, then fix what validation found
+    ///
+    /// Unreadable state is replaced by the newest readable backup.
+    pub async fn repair_state(&self, pipeline_id: &str) -> Result<RepairResult, StateError> {
+        self.backend.repair_state(pipeline_id).await
+    }
+
+    /// Backups of a pipeline's state, newest first
+    pub async fn list_backups(&self, pipeline_id: &str) -> Result<Vec<BackupInfo>, StateError> {
+        self.backend.list_backups(pipeline_id).await
+    }
+
+    /// Replace a pipeline's state with one of its backups
+    pub async fn restore_state(
+        &self,
+        pipeline_id: &str,
+        backup_id: &str,
+    ) -> Result<(), StateError> {
+        self.backend.restore_state(pipeline_id, backup_id).await
+    }
+
+    /// Archive every pipeline's state and lock, see [`StateBackend::export_all`]
+    pub async fn export_all(&self) -> Result<Vec<u8>, StateError> {
+        self.backend.export_all().await
+    }
+
+    /// Save the states in an archive from `export_all`, see [`StateBackend::import_all`]
+    pub async fn import_all(
+        &self,
+        data: &[u8],
+        overwrite: bool,
+    ) -> Result<ImportAllResult, StateError> {
+        self.backend.import_all(data, overwrite).await
+    }
+
+    /// Periodically run `health_check` and record the results in `health_history`
+    ///
+    /// Failed checks are recorded as unhealthy snapshots rather than stopping
+    /// the monitor. The history keeps the latest `health_history_size` entries.
+    pub fn start_health_monitor(&self, interval: Duration) -> HealthMonitorHandle {
+        let manager = self.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+
+            loop {
+                interval.tick().await;
+
+                let health = match manager.health_check().await {
+                    Ok(health) => health,
+                    Err(e) => BackendHealth {
+                        backend_type: "unknown".to_string(),
+                        healthy: false,
+                        last_check: Utc::now(),
+                        response_time_ms: 0,
+                        error_message: Some(e.to_string()),
+                        metrics: HashMap::new(),
+                        trend: None,
+                    },
+                };
+                manager.record_health(health);
+            }
+        });
+
+        HealthMonitorHandle { handle }
+    }
+
+    /// Health snapshots recorded by the health monitor, oldest first
+    pub fn health_history(&self) -> Vec<BackendHealth> {
+        self.health_history
+            .lock()
+            .map(|history| history.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Append a snapshot to the bounded history, tagging it with the current trend
+    fn record_health(&self, mut health: BackendHealth) {
+        let Ok(mut history) = self.health_history.lock() else {
+            return;
+        };
+
+        let mut times: Vec<u64> = history
+            .iter()
+            .rev()
+            .take(2)
+            .map(|h| h.response_time_ms)
+            .collect();
+        times.reverse();
+        times.push(health.response_time_ms);
+        health.trend = HealthTrend::from_response_times(&times);
+
+        history.push_back(health);
+        while history.len() > self.config.health_history_size.max(1) {
+            history.pop_front();
+        }
+    }
+
     /// Cleanup old state and expired locks
     pub async fn cleanup(&self) -> Result<CleanupResult, StateError> {
         self.backend.cleanup(self.config.max_state_age_hours).await
     }
 
     /// Start automatic heartbeat for a pipeline
+    ///
+    /// With `auto_claim_abandoned` the task also runs `claim_abandoned` every
+    /// `auto_claim_interval_ms`, using `stale_run_threshold_ms`.
     pub async fn start_heartbeat(&self, pipeline_id: String) -> HeartbeatHandle {
-        let manager = StateManager {
-            backend: Arc::clone(&self.backend),
-            config: self.config.clone(),
-        };
+        let manager = self.clone();
 
         let interval_ms = self.config.heartbeat_interval_ms;
+        let mut claims = self.config.auto_claim_abandoned.then(|| {
+            tokio::time::interval(Duration::from_millis(
+                self.config.auto_claim_interval_ms.max(1),
+            ))
+        });
 
         let handle = tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
 
             loop {
-                interval.tick().await;
-
-                if let Err(e) = manager.update_heartbeat(&pipeline_id).await {
-                    eprintln!("Heartbeat failed for pipeline {pipeline_id}: {e}");
-                    break;
+                tokio::select! {
+                    _ = interval.tick() => {
+                        if let Err(e) = manager.update_heartbeat(&pipeline_id).await {
+                            log::warn!("Heartbeat failed for pipeline {pipeline_id}: {e}");
+                            break;
+                        }
+                    }
+                    _ = next_tick(&mut claims) => {
+                        let threshold_ms = manager.config.stale_run_threshold_ms;
+                        if let Err(e) = manager.claim_abandoned(threshold_ms).await {
+                            log::warn!("Claiming abandoned pipelines failed: {e}");
+                        }
+                    }
                 }
             }
         });
@@ -331,17 +1365,28 @@ impl StateManager {
 }
 
 /// RAII lock guard for pipeline state
+///
+/// The lease is renewed in the background at a third of the lock TTL, so
+/// the lock survives steps that outlast it.
 pub struct StateManagerLock {
     pipeline_id: String,
     worker_id: String,
     backend: Arc<dyn StateBackend>,
-    lock_info: LockInfo,
+    /// Latest lease from the backend; `None` once renewal found the lock lost
+    lease: Arc<std::sync::Mutex<Option<LockInfo>>>,
+    renewal: tokio::task::JoinHandle<()>,
+    /// Set by `release`, so dropping the guard does not release a second time
+    released: bool,
+    /// Observers of the manager that took the lock, told when it is released
+    observers: Vec<Arc<dyn StateObserver>>,
 }
 
 impl StateManagerLock {
-    /// Get the lock information
-    pub fn lock_info(&self) -> &LockInfo {
-        &self.lock_info
+    /// Get the lock information as of the last renewal
+    ///
+    /// Returns `None` if the lock has been lost.
+    pub fn lock_info(&self) -> Option<LockInfo> {
+        self.lease.lock().unwrap().clone()
     }
 
     /// Get the pipeline ID
@@ -350,30 +1395,85 @@ impl StateManagerLock {
     }
 
     /// Check if the lock is still valid
+    ///
+    /// A lock is valid until its TTL runs out since it was taken or last
+    /// renewed, and stops being valid once renewal finds it lost.
     pub fn is_valid(&self) -> bool {
-        if let Some(expires_at) = self.lock_info.expires_at {
-            Utc::now() < expires_at
-        } else {
-            true // No expiration means valid
+        match self.lease.lock().unwrap().as_ref() {
+            Some(lock_info) => lock_info
+                .expires_at
+                .is_none_or(|expires_at| Utc::now() < expires_at),
+            None => false,
+        }
+    }
+
+    /// Renew the lease now rather than waiting for the background renewal
+    ///
+    /// As with a failed background renewal, an error such as
+    /// `StateError::LockLost` invalidates the guard.
+    pub async fn renew(&self) -> Result<LockInfo, StateError> {
+        let renewed = self
+            .backend
+            .renew_lock(&self.pipeline_id, &self.worker_id)
+            .await;
+        *self.lease.lock().unwrap() = renewed.as_ref().ok().cloned();
+        renewed
+    }
+
+    /// Release the lock now and wait for the backend to confirm
+    ///
+    /// Dropping the guard also releases the lock, but in a background task
+    /// that may not run if the process exits first.
+    pub async fn release(mut self) -> Result<(), StateError> {
+        self.renewal.abort();
+        self.released = true;
+        self.backend
+            .release_lock(&self.pipeline_id, &self.worker_id)
+            .await?;
+        for observer in &self.observers {
+            observer
+                .on_lock_released(&self.pipeline_id, &self.worker_id)
+                .await;
         }
+        Ok(())
     }
 }
 
 impl Drop for StateManagerLock {
     fn drop(&mut self) {
+        self.renewal.abort();
+        if self.released {
+            return;
+        }
+
         // Release lock on drop (fire and forget)
         let backend = Arc::clone(&self.backend);
         let pipeline_id = self.pipeline_id.clone();
         let worker_id = self.worker_id.clone();
+        let observers = std::mem::take(&mut self.observers);
 
         tokio::spawn(async move {
             if let Err(e) = backend.release_lock(&pipeline_id, &worker_id).await {
-                eprintln!("Failed to release lock for pipeline {pipeline_id}: {e}");
+                log::warn!("Failed to release lock for pipeline {pipeline_id}: {e}");
+                return;
+            }
+            for observer in &observers {
+                observer.on_lock_released(&pipeline_id, &worker_id).await;
             }
         });
     }
 }
 
+/// Wait for the next tick of an optional interval; never completes without one
+async fn next_tick(interval: &mut Option<tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
 /// Handle for a background heartbeat task
 pub struct HeartbeatHandle {
     handle: tokio::task::JoinHandle<()>,
@@ -391,6 +1491,23 @@ impl HeartbeatHandle {
     }
 }
 
+/// Handle for the background health monitor
+pub struct HealthMonitorHandle {
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl HealthMonitorHandle {
+    /// Stop the health monitor
+    pub fn stop(self) {
+        self.handle.abort();
+    }
+
+    /// Check if the health monitor is still running
+    pub fn is_running(&self) -> bool {
+        !self.handle.is_finished()
+    }
+}
+
 /// Trait for state change observers
 #[async_trait]
 pub trait StateObserver: Send + Sync {
@@ -410,6 +1527,9 @@ pub trait StateObserver: Send + Sync {
 
     /// Called when a pipeline lock is released
     async fn on_lock_released(&self, pipeline_id: &str, worker_id: &str);
+
+    /// Finish handling the events received so far, e.g. deliveries still queued
+    async fn flush(&self) {}
 }
 
 /// StateManager with observer support
@@ -484,6 +1604,389 @@ mod tests {
         assert!(manager.health_check().await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_completed_durations_and_percentiles() {
+        let manager = StateManager::new_memory();
+        for (id, secs, completed) in [
+            ("orders_a", 10, true),
+            ("orders_b", 30, true),
+            ("orders_c", 20, true),
+            ("orders_d", 40, true),
+            ("orders_e", 99, false),
+        ] {
+            let mut state = PipelineState::new(id.to_string(), "run".to_string());
+            state.metadata.pipeline_name = Some("orders".to_string());
+            state.status = if completed {
+                PipelineStatus::Completed {
+                    completed_at: state.started_at + chrono::Duration::seconds(secs),
+                }
+            } else {
+                PipelineStatus::Failed {
+                    failed_at: state.started_at + chrono::Duration::seconds(secs),
+                    error: "boom".to_string(),
+                }
+            };
+            manager.save_state(&state).await.unwrap();
+        }
+
+        // Earlier runs of a pipeline count, not only its latest state
+        let mut earlier = PipelineState::new("orders_a".to_string(), "earlier".to_string());
+        earlier.metadata.pipeline_name = Some("orders".to_string());
+        earlier.status = PipelineStatus::Completed {
+            completed_at: earlier.started_at + chrono::Duration::seconds(50),
+        };
+        manager.save_state(&earlier).await.unwrap();
+
+        let durations = manager.completed_durations("orders").await.unwrap();
+        assert_eq!(durations.len(), 5);
+        assert_eq!(
+            duration_percentile(&durations, 0.5),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            duration_percentile(&durations, 0.95),
+            Some(Duration::from_secs(48))
+        );
+        assert!(manager
+            .completed_durations("other")
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(duration_percentile(&[], 0.5), None);
+    }
+
+    #[tokio::test]
+    async fn test_purge_completed_respects_age_status_and_locks() {
+        let manager = StateManager::new_memory();
+        let now = Utc::now();
+        for (id, days_ago, completed) in [
+            ("old_done", 10, true),
+            ("recent_done", 1, true),
+            ("locked_done", 10, true),
+            ("old_failed", 10, false),
+        ] {
+            let mut state = PipelineState::new(id.to_string(), "run".to_string());
+            state.started_at =
+                now - chrono::Duration::days(days_ago) - chrono::Duration::minutes(5);
+            state.status = if completed {
+                PipelineStatus::Completed {
+                    completed_at: now - chrono::Duration::days(days_ago),
+                }
+            } else {
+                PipelineStatus::Failed {
+                    failed_at: now - chrono::Duration::days(days_ago),
+                    error: "boom".to_string(),
+                }
+            };
+            manager.save_state(&state).await.unwrap();
+        }
+        let _lock = manager.acquire_lock("locked_done", 60_000).await.unwrap();
+        let week = Some(Duration::from_secs(7 * 86_400));
+
+        let preview = manager.purge_completed(week, true).await.unwrap();
+        assert_eq!(preview.deleted_ids, vec!["old_done"]);
+        assert_eq!(preview.skipped_ids, vec!["locked_done"]);
+        assert!(preview.freed_bytes > 0);
+        assert!(preview.errors.is_empty());
+        assert_eq!(manager.list_pipelines().await.unwrap().len(), 4);
+
+        let purged = manager.purge_completed(week, false).await.unwrap();
+        assert_eq!(purged, preview);
+        assert!(manager.load_state("old_done").await.is_err());
+
+        let everything = manager.purge_completed(None, false).await.unwrap();
+        assert_eq!(everything.deleted_ids, vec!["recent_done"]);
+        let mut remaining = manager.list_pipelines().await.unwrap();
+        remaining.sort();
+        assert_eq!(remaining, vec!["locked_done", "old_failed"]);
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_removes_only_orphans() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        let manager = StateManager::new(StateManagerConfig {
+            backend: BackendConfig::File {
+                base_path: base.to_path_buf(),
+                format: crate::state::backend::SerializationFormat::Json,
+                atomic_writes: true,
+                lock_timeout_ms: 5000,
+                write_ahead_log: false,
+            },
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        manager
+            .save_state(&PipelineState::new("kept".to_string(), "run".to_string()))
+            .await
+            .unwrap();
+
+        let lock = |pipeline_id: &str, expires_in: i64| LockInfo {
+            pipeline_id: pipeline_id.to_string(),
+            worker_id: "crashed".to_string(),
+            locked_at: Utc::now(),
+            expires_at: Some(Utc::now() + chrono::Duration::minutes(expires_in)),
+            lock_version: 1,
+            lease_ms: 60_000,
+            pid: None,
+            hostname: None,
+        };
+        for (pipeline_id, expires_in) in [("gone", -5), ("starting", 5), ("kept", -5)] {
+            std::fs::write(
+                base.join(format!("locks/{pipeline_id}.lock")),
+                serde_json::to_vec(&lock(pipeline_id, expires_in)).unwrap(),
+            )
+            .unwrap();
+        }
+        for pipeline_id in ["gone", "kept"] {
+            std::fs::create_dir_all(base.join("backups").join(pipeline_id)).unwrap();
+            std::fs::write(base.join(format!("backups/{pipeline_id}/b1.json")), "abc").unwrap();
+        }
+        std::fs::write(base.join("states/empty.json"), "").unwrap();
+        let interrupted = std::fs::File::create(base.join("states/kept.json.tmp")).unwrap();
+        interrupted
+            .set_modified(std::time::SystemTime::now() - Duration::from_secs(600))
+            .unwrap();
+        std::fs::write(base.join("states/writing.json.tmp"), "{").unwrap();
+
+        let preview = manager.collect_garbage(true).await.unwrap();
+        assert_eq!(preview.orphaned_locks_removed, 1);
+        assert_eq!(preview.orphaned_backups_removed, 1);
+        assert_eq!(preview.temp_files_removed, 1);
+        assert_eq!(preview.empty_states_removed, 1);
+        assert!(preview.errors.is_empty());
+        assert!(base.join("locks/gone.lock").exists());
+
+        let result = manager.collect_garbage(false).await.unwrap();
+        assert_eq!(result, preview);
+        for removed in [
+            "locks/gone.lock",
+            "backups/gone",
+            "states/empty.json",
+            "states/kept.json.tmp",
+        ] {
+            assert!(!base.join(removed).exists(), "{removed} was kept");
+        }
+        for kept in [
+            "locks/starting.lock",
+            "locks/kept.lock",
+            "backups/kept/b1.json",
+            "states/writing.json.tmp",
+        ] {
+            assert!(base.join(kept).exists(), "{kept} was removed");
+        }
+        assert_eq!(
+            manager.collect_garbage(false).await.unwrap(),
+            GcResult::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ensure_no_active_run() {
+        let manager = StateManager::new_memory();
+        assert!(manager.ensure_no_active_run("orders", 60_000).await.is_ok());
+
+        let mut state = PipelineState::new("orders".to_string(), "run_1".to_string());
+        state.worker_id = Some("worker_a".to_string());
+        state.status = PipelineStatus::Running {
+            started_at: Utc::now(),
+        };
+        manager.save_state(&state).await.unwrap();
+
+        // Fresh heartbeat blocks a second run
+        let err = manager
+            .ensure_no_active_run("orders", 60_000)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            &err,
+            StateError::RunInProgress { worker_id, run_id, .. }
+                if worker_id == "worker_a" && run_id == "run_1"
+        ));
+
+        // A stale heartbeat can be superseded
+        state.last_heartbeat = Utc::now() - chrono::Duration::minutes(10);
+        manager.save_state(&state).await.unwrap();
+        assert!(manager.ensure_no_active_run("orders", 60_000).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_recover_stale_runs() {
+        let run = |pipeline: &str, worker: &str, heartbeat_minutes_ago: i64| {
+            let mut state = PipelineState::new(pipeline.to_string(), format!("{pipeline}_run"));
+            state.worker_id = Some(worker.to_string());
+            state.status = PipelineStatus::Running {
+                started_at: Utc::now(),
+            };
+            state.last_heartbeat = Utc::now() - chrono::Duration::minutes(heartbeat_minutes_ago);
+            state
+        };
+
+        for requeue in [false, true] {
+            let manager = StateManager::new(StateManagerConfig {
+                recover_stale_runs: true,
+                stale_run_threshold_ms: 60_000,
+                auto_requeue_stale: requeue,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+            for state in [
+                run("lost", "worker_a", 10),
+                run("alive", "worker_b", 0),
+                run("taken", "worker_c", 10),
+            ] {
+                manager.save_state(&state).await.unwrap();
+            }
+            // The lost worker's lease outlived its heartbeats
+            manager
+                .backend
+                .acquire_lock("lost", "worker_a", 1000, 600_000)
+                .await
+                .unwrap();
+            manager
+                .backend
+                .acquire_lock("taken", "worker_d", 1000, 600_000)
+                .await
+                .unwrap();
+
+            let result = manager.recover_stale_runs().await.unwrap();
+            let (recovered, other) = if requeue {
+                (&result.requeued_ids, &result.failed_ids)
+            } else {
+                (&result.failed_ids, &result.requeued_ids)
+            };
+            assert_eq!(recovered, &vec!["lost".to_string()]);
+            assert!(other.is_empty());
+            assert_eq!(result.skipped_ids, vec!["taken"]);
+            assert!(result.errors.is_empty());
+
+            let lost = manager.load_state("lost").await.unwrap();
+            if requeue {
+                assert_eq!(lost.status, PipelineStatus::Pending);
+            } else {
+                assert!(matches!(
+                    &lost.status,
+                    PipelineStatus::Failed { error, .. } if error == "worker lost"
+                ));
+            }
+            let error = lost.errors.last().unwrap();
+            assert_eq!(error.error_type, ErrorType::Unknown);
+            assert_eq!(error.message, "worker lost");
+            assert!(manager.is_locked("lost").await.unwrap().is_none());
+
+            assert!(matches!(
+                manager.load_state("alive").await.unwrap().status,
+                PipelineStatus::Running { .. }
+            ));
+            assert!(manager.is_locked("taken").await.unwrap().is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compare_runs() {
+        let manager = StateManager::new_memory();
+        let started_at = Utc::now() - chrono::Duration::minutes(10);
+        let step = |status: StepStatus, records: u64| {
+            let mut step = StepState::new("load".to_string(), "Load".to_string());
+            step.status = status;
+            step.records_processed = records;
+            step
+        };
+
+        let mut first = PipelineState::new("orders".to_string(), "run-a".to_string());
+        first.started_at = started_at;
+        first.records_processed = 100;
+        first.status = PipelineStatus::Completed {
+            completed_at: started_at + chrono::Duration::seconds(30),
+        };
+        first.step_states.insert(
+            "load".to_string(),
+            step(
+                StepStatus::Completed {
+                    completed_at: Utc::now(),
+                },
+                100,
+            ),
+        );
+        manager.save_state(&first).await.unwrap();
+
+        let mut second = PipelineState::new("orders".to_string(), "run-b".to_string());
+        second.started_at = started_at;
+        second.records_processed = 40;
+        second.status = PipelineStatus::Failed {
+            failed_at: started_at + chrono::Duration::seconds(45),
+            error: "disk full".to_string(),
+        };
+        second.step_states.insert(
+            "load".to_string(),
+            step(
+                StepStatus::Failed {
+                    error: "disk full".to_string(),
+                    failed_at: Utc::now(),
+                },
+                40,
+            ),
+        );
+        second.errors.push(ErrorRecord::new(
+            Some("load".to_string()),
+            crate::state::types::ErrorType::Resource,
+            "disk full".to_string(),
+            String::new(),
+            false,
+        ));
+        manager.save_state(&second).await.unwrap();
+
+        assert_eq!(
+            manager.list_runs("orders").await.unwrap(),
+            vec!["run-a", "run-b"]
+        );
+
+        let comparison = manager
+            .compare_runs("orders", "run-a", "run-b")
+            .await
+            .unwrap();
+        assert_eq!(comparison.run_a.status, "completed");
+        assert_eq!(comparison.run_b.status, "failed");
+        assert_eq!(comparison.records_delta(), -60);
+        assert_eq!(comparison.duration_delta_ms(), Some(15_000));
+        assert_eq!(comparison.changed_steps().count(), 1);
+        assert_eq!(comparison.steps[0].status_b.as_deref(), Some("failed"));
+        assert!(comparison.errors_only_in_a.is_empty());
+        assert_eq!(comparison.errors_only_in_b, vec!["load: disk full"]);
+
+        assert!(manager
+            .compare_runs("orders", "run-a", "missing")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rename_pipeline_state() {
+        let manager = StateManager::new_memory();
+        manager.initialize_pipeline("Orders", None).await.unwrap();
+        manager.initialize_pipeline("Archive", None).await.unwrap();
+
+        // Refuses to overwrite existing state
+        assert!(manager.rename_pipeline("Orders", "Archive").await.is_err());
+
+        manager
+            .rename_pipeline("Orders", "Daily Orders")
+            .await
+            .unwrap();
+        let renamed = manager.load_state("Daily Orders").await.unwrap();
+        assert_eq!(renamed.pipeline_id, "Daily Orders");
+        assert!(manager.load_state("Orders").await.is_err());
+
+        // Refuses while a worker holds the lock
+        let _lock = manager.acquire_lock("Archive", 1000).await.unwrap();
+        assert!(manager
+            .rename_pipeline("Archive", "Old Archive")
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_pipeline_initialization() {
         let manager = StateManager::new_memory();
@@ -525,18 +2028,212 @@ mod tests {
         assert!(updated_state.version > state.version);
     }
 
+    #[tokio::test]
+    async fn test_claim_abandoned_claims_each_run_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_manager = |worker: usize| {
+            StateManager::new(StateManagerConfig {
+                backend: BackendConfig::File {
+                    base_path: temp_dir.path().to_path_buf(),
+                    format: crate::state::backend::SerializationFormat::Json,
+                    atomic_writes: true,
+                    lock_timeout_ms: 5000,
+                    write_ahead_log: false,
+                },
+                worker_id: format!("worker_{worker}"),
+                max_retries: 100,
+                ..Default::default()
+            })
+        };
+        let memory = StateManager::new_memory();
+        let memory_worker = |worker: usize| StateManager {
+            config: StateManagerConfig {
+                worker_id: format!("worker_{worker}"),
+                max_retries: 100,
+                ..Default::default()
+            },
+            ..memory.clone()
+        };
+        let mut file_managers = Vec::new();
+        for worker in 0..4 {
+            file_managers.push(file_manager(worker).await.unwrap());
+        }
+        let groups = vec![file_managers, (0..4).map(memory_worker).collect()];
+
+        for managers in &groups {
+            let pipelines: Vec<String> = (0..6).map(|i| format!("pipeline_{i}")).collect();
+            for pipeline_id in &pipelines {
+                let mut state = PipelineState::new(pipeline_id.clone(), "run".to_string());
+                state.worker_id = Some("crashed".to_string());
+                state.status = PipelineStatus::Running {
+                    started_at: Utc::now(),
+                };
+                state.last_heartbeat = Utc::now() - chrono::Duration::minutes(10);
+                managers[0].save_state(&state).await.unwrap();
+                managers[0]
+                    .backend
+                    .acquire_lock(pipeline_id, "crashed", 1000, 600_000)
+                    .await
+                    .unwrap();
+            }
+
+            let tasks: Vec<_> = managers
+                .iter()
+                .map(|manager| {
+                    let manager = manager.clone();
+                    tokio::spawn(async move {
+                        let claimed = manager.claim_abandoned(60_000).await.unwrap();
+                        (manager.config().worker_id.clone(), claimed)
+                    })
+                })
+                .collect();
+            let mut claims = HashMap::new();
+            for task in tasks {
+                let (worker_id, claimed) = task.await.unwrap();
+                for pipeline_id in claimed {
+                    // No pipeline is claimed by two workers
+                    assert!(claims.insert(pipeline_id, worker_id.clone()).is_none());
+                }
+            }
+
+            assert_eq!(claims.len(), pipelines.len());
+            // A file manager caches what it saved, so check through a manager that saved nothing
+            let observer = match managers[0].config().backend {
+                BackendConfig::File { .. } => file_manager(99).await.unwrap(),
+                _ => managers[0].clone(),
+            };
+            for pipeline_id in &pipelines {
+                let state = observer.load_state(pipeline_id).await.unwrap();
+                assert_eq!(state.worker_id.as_ref(), claims.get(pipeline_id));
+                assert!(!state.is_stale(60_000));
+                assert!(observer.is_locked(pipeline_id).await.unwrap().is_none());
+            }
+            for manager in managers {
+                assert!(manager.claim_abandoned(60_000).await.unwrap().is_empty());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_updates_are_not_lost() {
+        let temp_dir = TempDir::new().unwrap();
+        // Two managers on one directory stand in for two processes
+        let file_manager = || {
+            StateManager::new(StateManagerConfig {
+                backend: BackendConfig::File {
+                    base_path: temp_dir.path().to_path_buf(),
+                    format: crate::state::backend::SerializationFormat::Json,
+                    atomic_writes: true,
+                    lock_timeout_ms: 5000,
+                    write_ahead_log: false,
+                },
+                max_retries: 100,
+                ..Default::default()
+            })
+        };
+        let groups = vec![
+            vec![file_manager().await.unwrap(), file_manager().await.unwrap()],
+            vec![StateManager::new_memory()],
+        ];
+
+        for managers in &groups {
+            let pipeline_id = "contended";
+            let manager = &managers[0];
+            let initial = manager
+                .initialize_pipeline(pipeline_id, None)
+                .await
+                .unwrap();
+
+            let tasks: Vec<_> = (0..20)
+                .map(|i| {
+                    let manager = managers[i % managers.len()].clone();
+                    tokio::spawn(async move {
+                        manager
+                            .update_state(pipeline_id, |state| state.records_processed += 1)
+                            .await
+                    })
+                })
+                .collect();
+            for task in tasks {
+                task.await.unwrap().unwrap();
+            }
+
+            // A stale version is refused and nothing is written. This also
+            // refreshes the file backend's cache, which the other manager's
+            // writes left behind.
+            let conflict = manager
+                .backend
+                .atomic_update(
+                    pipeline_id,
+                    initial.version,
+                    Box::new(|mut state| {
+                        state.records_processed = 0;
+                        state
+                    }),
+                )
+                .await;
+            assert!(matches!(
+                conflict,
+                Err(StateError::VersionConflict { expected, actual })
+                    if expected == initial.version && actual == initial.version + 20
+            ));
+
+            let state = manager.load_state(pipeline_id).await.unwrap();
+            assert_eq!(state.records_processed, initial.records_processed + 20);
+            assert_eq!(state.version, initial.version + 20);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_try_acquire_lock_and_lock_holders() {
+        let backend: Arc<dyn StateBackend> = Arc::new(MemoryBackend::new());
+        let manager = |worker_id: &str| StateManager {
+            backend: Arc::clone(&backend),
+            config: StateManagerConfig {
+                worker_id: worker_id.to_string(),
+                ..Default::default()
+            },
+            health_history: Arc::default(),
+            observers: Vec::new(),
+        };
+        let (first, second) = (manager("worker_1"), manager("worker_2"));
+        for pipeline_id in ["orders", "customers", "idle"] {
+            first.initialize_pipeline(pipeline_id, None).await.unwrap();
+        }
+
+        let held = first.try_acquire_lock("orders").await.unwrap().unwrap();
+        assert!(held.is_valid());
+        let started = std::time::Instant::now();
+        assert!(second.try_acquire_lock("orders").await.unwrap().is_none());
+        assert!(started.elapsed() < Duration::from_millis(10));
+        let _customers = second.try_acquire_lock("customers").await.unwrap().unwrap();
+
+        let holders = first.lock_holders().await.unwrap();
+        let held_by: Vec<_> = holders
+            .iter()
+            .map(|lock| (lock.pipeline_id.as_str(), lock.worker_id.as_str()))
+            .collect();
+        assert_eq!(
+            held_by,
+            vec![("orders", "worker_1"), ("customers", "worker_2")]
+        );
+
+        held.release().await.unwrap();
+        assert!(second.try_acquire_lock("orders").await.unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn test_state_locking() {
         let backend: Arc<dyn StateBackend> = Arc::new(MemoryBackend::new());
 
         let config1 = StateManagerConfig {
-            backend: BackendConfig::Memory { persistent: false },
+            backend: BackendConfig::Memory { persist_dir: None },
             worker_id: "worker_1".to_string(),
             ..Default::default()
         };
 
         let config2 = StateManagerConfig {
-            backend: BackendConfig::Memory { persistent: false },
+            backend: BackendConfig::Memory { persist_dir: None },
             worker_id: "worker_2".to_string(),
             ..Default::default()
         };
@@ -544,11 +2241,15 @@ mod tests {
         let manager1 = StateManager {
             backend: Arc::clone(&backend),
             config: config1,
+            health_history: Arc::default(),
+            observers: Vec::new(),
         };
 
         let manager2 = StateManager {
             backend: Arc::clone(&backend),
             config: config2,
+            health_history: Arc::default(),
+            observers: Vec::new(),
         };
 
         manager1
@@ -577,6 +2278,118 @@ mod tests {
         let _lock2 = manager2.acquire_lock("test_pipeline", 1000).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_lock_ttl_is_independent_of_wait_timeout() {
+        let manager = StateManager {
+            backend: Arc::new(MemoryBackend::new()),
+            config: StateManagerConfig {
+                lock_ttl_ms: 2_000,
+                ..Default::default()
+            },
+            health_history: Arc::default(),
+            observers: Vec::new(),
+        };
+
+        // Willing to wait only 100ms, but the lock lasts its full TTL
+        let lock = manager.acquire_lock("etl", 100).await.unwrap();
+        let acquired = lock.lock_info().unwrap();
+        assert_eq!(acquired.lease_ms, 2_000);
+        assert_eq!(
+            (acquired.expires_at.unwrap() - acquired.locked_at).num_milliseconds(),
+            2_000
+        );
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(lock.is_valid());
+        assert!(manager.is_locked("etl").await.unwrap().is_some());
+
+        let renewed = lock.renew().await.unwrap();
+        assert!(renewed.expires_at > acquired.expires_at);
+        assert_eq!(renewed.lock_version, acquired.lock_version + 1);
+        assert_eq!(lock.lock_info().unwrap().expires_at, renewed.expires_at);
+
+        manager.force_release_lock("etl").await.unwrap();
+        assert!(matches!(
+            lock.renew().await,
+            Err(StateError::LockLost { .. })
+        ));
+        assert!(!lock.is_valid());
+    }
+
+    #[tokio::test]
+    async fn test_lock_lease_renewed_past_timeout() {
+        let backend: Arc<dyn StateBackend> = Arc::new(MemoryBackend::new());
+        let manager = |worker_id: &str| StateManager {
+            backend: Arc::clone(&backend),
+            config: StateManagerConfig {
+                worker_id: worker_id.to_string(),
+                lock_ttl_ms: 150,
+                ..Default::default()
+            },
+            health_history: Arc::default(),
+            observers: Vec::new(),
+        };
+        let (owner, rival) = (manager("worker_1"), manager("worker_2"));
+
+        let lock = owner.acquire_lock("long_step", 150).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // Held for over three timeouts, still ours
+        assert!(lock.is_valid());
+        assert!(lock.lock_info().unwrap().lock_version > 1);
+        let held = owner.is_locked("long_step").await.unwrap().unwrap();
+        assert_eq!(held.worker_id, "worker_1");
+        assert!(matches!(
+            rival.acquire_lock("long_step", 50).await,
+            Err(StateError::LockTimeout { .. })
+        ));
+
+        // Losing the lock stops renewal and invalidates the guard
+        owner.force_release_lock("long_step").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!lock.is_valid());
+        assert!(lock.lock_info().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_renew_lock() {
+        let dir = TempDir::new().unwrap();
+        let backend = FileBackend::new(BackendConfig::File {
+            base_path: dir.path().to_path_buf(),
+            format: crate::state::backend::SerializationFormat::Json,
+            atomic_writes: true,
+            lock_timeout_ms: 1000,
+            write_ahead_log: false,
+        })
+        .unwrap();
+
+        let acquired = backend
+            .acquire_lock("etl", "worker_1", 1000, 1000)
+            .await
+            .unwrap();
+        let renewed = backend.renew_lock("etl", "worker_1").await.unwrap();
+        assert_eq!(renewed.lock_version, acquired.lock_version + 1);
+        assert!(renewed.expires_at >= acquired.expires_at);
+        assert_eq!(
+            backend
+                .is_locked("etl")
+                .await
+                .unwrap()
+                .unwrap()
+                .lock_version,
+            2
+        );
+
+        assert!(matches!(
+            backend.renew_lock("etl", "worker_2").await,
+            Err(StateError::LockLost { .. })
+        ));
+        backend.release_lock("etl", "worker_1").await.unwrap();
+        assert!(matches!(
+            backend.renew_lock("etl", "worker_1").await,
+            Err(StateError::LockLost { .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_error_handling() {
         let manager = StateManager::new_memory();
@@ -650,6 +2463,146 @@ mod tests {
         assert!(updated_state.last_heartbeat > initial_heartbeat);
     }
 
+    fn file_config(temp_dir: &TempDir) -> StateManagerConfig {
+        StateManagerConfig {
+            backend: BackendConfig::File {
+                base_path: temp_dir.path().to_path_buf(),
+                format: crate::state::backend::SerializationFormat::Json,
+                atomic_writes: true,
+                lock_timeout_ms: 5000,
+                write_ahead_log: false,
+            },
+            max_retries: 100,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_errors_over_cap_move_to_archive_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = StateManager::new(file_config(&temp_dir)).await.unwrap();
+        let mut state = manager.initialize_pipeline("noisy", None).await.unwrap();
+
+        for i in 0..600 {
+            state.add_error(ErrorRecord::config_error(
+                format!("error {i}"),
+                String::new(),
+            ));
+        }
+        manager.save_state_compacted(&mut state).await.unwrap();
+
+        let saved = manager.load_state("noisy").await.unwrap();
+        assert_eq!(saved.errors.len(), 500);
+        assert_eq!(saved.errors[0].message, "error 100");
+        let archive = manager.load_error_archive("noisy").await.unwrap();
+        let archived: Vec<_> = archive.iter().map(|e| e.message.as_str()).collect();
+        let expected: Vec<_> = (0..100).map(|i| format!("error {i}")).collect();
+        assert_eq!(archived, expected);
+
+        // Saving again archives nothing new
+        assert!(state.evicted_errors.is_empty());
+        manager.save_state_compacted(&mut state).await.unwrap();
+        let archive = manager.load_error_archive("noisy").await.unwrap();
+        assert_eq!(archive.len(), 100);
+
+        // Errors added through the manager keep archiving past the cap
+        manager
+            .add_error(
+                "noisy",
+                ErrorRecord::config_error("error 600".to_string(), String::new()),
+            )
+            .await
+            .unwrap();
+        let saved = manager.load_state("noisy").await.unwrap();
+        assert_eq!(saved.errors.len(), 500);
+        assert_eq!(saved.errors.last().unwrap().message, "error 600");
+        let archive = manager.load_error_archive("noisy").await.unwrap();
+        assert_eq!(archive.len(), 101);
+        assert_eq!(archive[100].message, "error 100");
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_keeps_version_and_staleness() {
+        let temp_dir = TempDir::new().unwrap();
+        let managers = [
+            StateManager::new(file_config(&temp_dir)).await.unwrap(),
+            StateManager::new_memory(),
+        ];
+
+        for manager in &managers {
+            manager.initialize_pipeline("beating", None).await.unwrap();
+            manager
+                .update_state("beating", |state| {
+                    state.last_heartbeat = Utc::now() - chrono::Duration::seconds(10);
+                })
+                .await
+                .unwrap();
+            let silent = manager.load_state("beating").await.unwrap();
+            assert_eq!(
+                manager.find_stale_pipelines(5000).await.unwrap(),
+                ["beating"]
+            );
+
+            // Another worker's heartbeat doesn't keep this run alive
+            manager
+                .backend
+                .touch_heartbeat("beating", "worker_lost", Utc::now())
+                .await
+                .unwrap();
+            assert_eq!(
+                manager.find_stale_pipelines(5000).await.unwrap(),
+                ["beating"]
+            );
+
+            manager.update_heartbeat("beating").await.unwrap();
+            let state = manager.load_state("beating").await.unwrap();
+            assert_eq!(state.version, silent.version);
+            assert!(state.last_heartbeat > silent.last_heartbeat);
+            assert!(manager.find_stale_pipelines(5000).await.unwrap().is_empty());
+        }
+
+        assert!(matches!(
+            managers[0].update_heartbeat("unknown").await,
+            Err(StateError::PipelineNotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeats_and_progress_updates_both_land() {
+        let temp_dir = TempDir::new().unwrap();
+        let managers = [
+            StateManager::new(file_config(&temp_dir)).await.unwrap(),
+            StateManager::new_memory(),
+        ];
+
+        for manager in managers {
+            let initial = manager.initialize_pipeline("busy", None).await.unwrap();
+            let started = Utc::now();
+
+            let tasks: Vec<_> = (0..40)
+                .map(|i| {
+                    let manager = manager.clone();
+                    tokio::spawn(async move {
+                        if i % 2 == 0 {
+                            manager.update_progress("busy", 1, 10, None).await
+                        } else {
+                            manager.update_heartbeat("busy").await
+                        }
+                    })
+                })
+                .collect();
+            for task in tasks {
+                task.await.unwrap().unwrap();
+            }
+
+            let state = manager.load_state("busy").await.unwrap();
+            assert_eq!(state.records_processed, initial.records_processed + 20);
+            assert_eq!(state.data_size_processed, initial.data_size_processed + 200);
+            assert_eq!(state.version, initial.version + 20);
+            assert!(state.last_heartbeat >= started);
+        }
+    }
+
     #[tokio::test]
     async fn test_stale_pipeline_detection() {
         let manager = StateManager::new_memory();
@@ -685,6 +2638,7 @@ mod tests {
                 format: crate::state::backend::SerializationFormat::Json,
                 atomic_writes: true,
                 lock_timeout_ms: 5000,
+                write_ahead_log: false,
             },
             ..Default::default()
         };
@@ -707,6 +2661,7 @@ mod tests {
                 format: crate::state::backend::SerializationFormat::Json,
                 atomic_writes: true,
                 lock_timeout_ms: 5000,
+                write_ahead_log: false,
             },
             ..Default::default()
         };
@@ -773,4 +2728,122 @@ mod tests {
         assert_eq!(observer.state_changes.load(Ordering::SeqCst), 1);
         assert_eq!(observer.errors.load(Ordering::SeqCst), 1);
     }
+
+    #[tokio::test]
+    async fn test_manager_observers_see_saves_errors_and_locks() {
+        #[derive(Default)]
+        struct Recorder(std::sync::Mutex<Vec<String>>);
+
+        #[async_trait]
+        impl StateObserver for Recorder {
+            async fn on_state_change(
+                &self,
+                _pipeline_id: &str,
+                old_state: Option<&PipelineState>,
+                _new_state: &PipelineState,
+            ) {
+                let event = if old_state.is_some() {
+                    "update"
+                } else {
+                    "save"
+                };
+                self.0.lock().unwrap().push(event.to_string());
+            }
+
+            async fn on_error(&self, _pipeline_id: &str, error: &ErrorRecord) {
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("error {}", error.message));
+            }
+
+            async fn on_lock_acquired(&self, _pipeline_id: &str, _worker_id: &str) {
+                self.0.lock().unwrap().push("lock".to_string());
+            }
+
+            async fn on_lock_released(&self, _pipeline_id: &str, _worker_id: &str) {
+                self.0.lock().unwrap().push("unlock".to_string());
+            }
+        }
+
+        let mut manager = StateManager::new_memory();
+        let recorder = Arc::new(Recorder::default());
+        manager.add_observer(recorder.clone());
+
+        manager.initialize_pipeline("observed", None).await.unwrap();
+        manager
+            .add_error(
+                "observed",
+                ErrorRecord::config_error("bad".to_string(), String::new()),
+            )
+            .await
+            .unwrap();
+        manager
+            .update_state_locked("observed", |state| state.records_processed = 1)
+            .await
+            .unwrap();
+        manager.flush_observers().await;
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            ["save", "update", "error bad", "lock", "update", "unlock"]
+        );
+    }
+
+    #[test]
+    fn test_health_trend_from_response_times() {
+        assert_eq!(HealthTrend::from_response_times(&[5, 3]), None);
+        assert_eq!(
+            HealthTrend::from_response_times(&[9, 5, 3]),
+            Some(HealthTrend::Improving)
+        );
+        assert_eq!(
+            HealthTrend::from_response_times(&[1, 2, 3, 4]),
+            Some(HealthTrend::Degrading)
+        );
+        assert_eq!(
+            HealthTrend::from_response_times(&[2, 5, 3]),
+            Some(HealthTrend::Stable)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_history_is_bounded_and_trended() {
+        let manager = StateManager::new(StateManagerConfig {
+            health_history_size: 3,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        for time in [1, 2, 3, 4] {
+            manager.record_health(BackendHealth {
+                backend_type: "memory".to_string(),
+                healthy: true,
+                last_check: Utc::now(),
+                response_time_ms: time,
+                error_message: None,
+                metrics: HashMap::new(),
+                trend: None,
+            });
+        }
+        let history = manager.health_history();
+        assert_eq!(
+            history
+                .iter()
+                .map(|h| h.response_time_ms)
+                .collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+        assert_eq!(history[0].trend, None);
+        assert_eq!(history[2].trend, Some(HealthTrend::Degrading));
+
+        let monitored = StateManager::new_memory();
+        let monitor = monitored.start_health_monitor(Duration::from_millis(10));
+        while monitored.health_history().len() < 3 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        monitor.stop();
+        assert!(monitored.health_history()[2].trend.is_some());
+    }
 }
@@ -3,17 +3,22 @@ pub mod cli;
 pub mod manager;
 pub mod pipeline_tracker;
 pub mod types;
+pub mod webhook;
 
 // Re-export common types for convenience
 pub use backend::{
-    BackendConfig, BackendHealth, CleanupResult, FileBackend, LockInfo, MemoryBackend,
-    SerializationFormat, StateBackend,
+    BackendConfig, BackendHealth, CleanupResult, FileBackend, HealthTrend, LockInfo, MemoryBackend,
+    OrphanKind, OrphanedFile, SerializationFormat, StateBackend, StateChangeEvent, StateEvent,
+    StateStream,
 };
 pub use manager::{
-    HeartbeatHandle, ObservableStateManager, StateManager, StateManagerConfig, StateManagerLock,
-    StateObserver,
+    duration_percentile, GcResult, HealthMonitorHandle, HeartbeatHandle, ObservableStateManager,
+    PurgeResult, RunComparison, RunSummary, StateManager, StateManagerConfig, StateManagerLock,
+    StateObserver, StepComparison,
 };
 pub use types::{
-    ErrorRecord, ErrorType, PipelineState, PipelineStatus, StateError, StateMetadata, StepState,
-    StepStatus,
+    ErrorRecord, ErrorType, PipelineState, PipelineStatus, ResumeCheckpoint, StateError,
+    StateLimits, StateMetadata, StateQuery, StateSortKey, StateSummary, StatusKind, StepState,
+    StepStatus, StepSummary,
 };
+pub use webhook::{NotificationSettings, RetryPolicy, WebhookNotifier};
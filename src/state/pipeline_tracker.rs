@@ -1,8 +1,10 @@
+use crate::error::OxiError;
 use crate::pipeline::{Pipeline, PipelineResult, StepResult};
 use crate::state::{
-    manager::StateManager,
+    manager::{duration_percentile, StateManager},
     types::{
-        ErrorRecord, ErrorType, PipelineState, PipelineStatus, StateMetadata, StepState, StepStatus,
+        ErrorRecord, ErrorType, PipelineState, PipelineStatus, ResumeCheckpoint, StateMetadata,
+        StepState, StepStatus,
     },
 };
 use crate::types::OxiData;
@@ -45,25 +47,45 @@ impl PipelineTracker {
     }
 
     /// Initialize the pipeline state for a new execution
+    ///
+    /// The previous run's `last_processed_id` is carried over so incremental
+    /// sources can pick up where it stopped, and the median of past completed
+    /// runs becomes the new run's `estimated_completion`.
     async fn initialize_state(&self, pipeline: &Pipeline) -> Result<()> {
         let now = Utc::now();
+        let last_processed_id = self
+            .state_manager
+            .load_state(&self.pipeline_id)
+            .await
+            .map(|previous| previous.last_processed_id)
+            .unwrap_or_default();
+        let estimated_completion = self
+            .state_manager
+            .completed_durations(&self.pipeline_id)
+            .await
+            .ok()
+            .and_then(|durations| duration_percentile(&durations, 0.5))
+            .and_then(|median| chrono::Duration::from_std(median).ok())
+            .map(|median| self.started_at + median);
         let state = PipelineState {
             pipeline_id: self.pipeline_id.clone(),
             run_id: self.run_id.clone(),
             version: 1,
-            last_processed_id: String::new(),
+            last_processed_id,
             batch_number: 0,
             records_processed: 0,
             records_failed: 0,
             data_size_processed: 0,
             current_step: String::new(),
             step_states: std::collections::HashMap::new(),
+            resume_checkpoint: None,
+            step_summaries: Vec::new(),
             status: PipelineStatus::Running {
                 started_at: self.started_at,
             },
             started_at: self.started_at,
             last_success_timestamp: self.started_at,
-            estimated_completion: None,
+            estimated_completion,
             errors: Vec::new(),
             retry_count: 0,
             worker_id: Some(format!("worker-{}", std::process::id())),
@@ -77,9 +99,12 @@ impl PipelineTracker {
                 last_checkpoint_at: now,
                 pipeline_name: Some(pipeline.name()),
                 pipeline_version: pipeline.metadata.as_ref().and_then(|m| m.version.clone()),
-                environment: None,
-                tags: std::collections::HashMap::new(),
+                pipeline_content_hash: Some(pipeline.content_hash()),
+                environment: pipeline.run_context.environment.clone(),
+                tags: pipeline.run_tags(),
             },
+            limits: Default::default(),
+            evicted_errors: Vec::new(),
         };
 
         self.state_manager.save_state(&state).await?;
@@ -87,7 +112,10 @@ impl PipelineTracker {
     }
 
     /// Start tracking a step
-    pub async fn start_step(&self, step_id: &str) -> Result<()> {
+    ///
+    /// `config_hash` is the step's `OxiConfig::config_hash` and is compared on
+    /// resume to detect config drift.
+    pub async fn start_step(&self, step_id: &str, config_hash: Option<&str>) -> Result<()> {
         self.state_manager
             .update_state_locked(&self.pipeline_id, |state| {
                 state.current_step = step_id.to_string();
@@ -107,7 +135,8 @@ impl PipelineTracker {
                     last_heartbeat: Utc::now(),
                     retry_count: 0,
                     error_count: 0,
-                    config_hash: None,
+                    config_hash: config_hash.map(|h| h.to_string()),
+                    run_id: Some(state.run_id.clone()),
                 };
 
                 state.step_states.insert(step_id.to_string(), step_state);
@@ -134,6 +163,12 @@ impl PipelineTracker {
                         }
                     };
                     step_state.processing_time_ms = step_result.duration_ms;
+                    // Chunked steps count records as they go; otherwise count the output
+                    if let Some(data) = &step_result.data {
+                        step_state.records_processed = step_state
+                            .records_processed
+                            .max(data.data().batch_size() as u64);
+                    }
                     step_state.last_heartbeat = now;
                     step_state.retry_count = step_result.retry_count as u64;
                     if !step_result.success {
@@ -154,14 +189,17 @@ impl PipelineTracker {
                         let error_record = ErrorRecord {
                             error_id: Uuid::new_v4().to_string(),
                             step_id: Some(step_result.step_id.clone()),
-                            error_type: ErrorType::Processing,
+                            error_type: step_result
+                                .error_type
+                                .clone()
+                                .unwrap_or(ErrorType::Processing),
                             message: error_msg.clone(),
                             context: format!(
                                 "Step failed after {} retries",
                                 step_result.retry_count
                             ),
                             timestamp: Utc::now(),
-                            retryable: step_result.retry_count < 3, // Simplified logic
+                            retryable: step_result.retryable,
                             stack_trace: None,
                         };
                         state.errors.push(error_record);
@@ -227,17 +265,114 @@ impl PipelineTracker {
         Ok(())
     }
 
-    /// Send heartbeat to indicate the pipeline is still running
-    pub async fn send_heartbeat(&self) -> Result<()> {
+    /// Checkpoint a cancelled run as `Paused` so it can be resumed
+    ///
+    /// The interrupted step goes back to `Pending`, since its work was
+    /// abandoned, and a `Cancelled` error is recorded against it. `checkpoint`
+    /// holds the data the interrupted step was given and what the steps before
+    /// it produced; it is saved beside the state so
+    /// [`PipelineTracker::resume_paused`] can start from that step.
+    pub async fn pause_pipeline(
+        &self,
+        interrupted_step: Option<&str>,
+        checkpoint: &ResumeCheckpoint,
+    ) -> Result<()> {
+        let checkpoint_hash = match interrupted_step {
+            Some(_) => {
+                self.state_manager
+                    .save_resume_checkpoint(&self.pipeline_id, checkpoint)
+                    .await?;
+                Some(checkpoint.content_hash()?)
+            }
+            None => None,
+        };
         self.state_manager
             .update_state_locked(&self.pipeline_id, |state| {
-                state.last_heartbeat = Utc::now();
+                let now = Utc::now();
+                state.status = PipelineStatus::Paused { paused_at: now };
+                if let Some(step_id) = interrupted_step {
+                    state.current_step = step_id.to_string();
+                    state.resume_checkpoint = checkpoint_hash;
+                }
+                if let Some(step_state) =
+                    interrupted_step.and_then(|step_id| state.step_states.get_mut(step_id))
+                {
+                    step_state.status = StepStatus::Pending;
+                    step_state.last_heartbeat = now;
+                }
+                state.errors.push(ErrorRecord::from_oxi_error(
+                    interrupted_step.map(str::to_string),
+                    &OxiError::Cancelled,
+                    "Run interrupted".to_string(),
+                ));
+
+                state.last_heartbeat = now;
+                state.metadata.updated_at = now;
+                state.metadata.checkpoint_count += 1;
+                state.metadata.last_checkpoint_at = now;
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Watermark saved by the last successful run (empty if there is none)
+    pub async fn last_processed_id(&self) -> Result<String> {
+        Ok(self
+            .get_state()
+            .await?
+            .map(|state| state.last_processed_id)
+            .unwrap_or_default())
+    }
+
+    /// Save the watermark of an incremental source for the next run
+    pub async fn commit_watermark(&self, last_processed_id: &str) -> Result<()> {
+        self.state_manager
+            .update_state_locked(&self.pipeline_id, |state| {
+                state.last_processed_id = last_processed_id.to_string();
                 state.metadata.updated_at = Utc::now();
             })
             .await?;
         Ok(())
     }
 
+    /// Record one processed chunk of an oversized batch
+    ///
+    /// Bumps the pipeline's `batch_number` and adds `records` to the step's
+    /// `records_processed`.
+    pub async fn record_batch(&self, step_id: &str, records: u64) -> Result<()> {
+        self.state_manager
+            .update_state_locked(&self.pipeline_id, |state| {
+                let now = Utc::now();
+                state.batch_number += 1;
+                if let Some(step_state) = state.step_states.get_mut(step_id) {
+                    step_state.records_processed += records;
+                    step_state.last_heartbeat = now;
+                }
+                state.last_heartbeat = now;
+                state.metadata.updated_at = now;
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Record an error that did not fail its step
+    pub async fn record_error(&self, record: ErrorRecord) -> Result<()> {
+        self.state_manager
+            .update_state_locked(&self.pipeline_id, |state| {
+                state.errors.push(record);
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Send heartbeat to indicate the pipeline is still running
+    pub async fn send_heartbeat(&self) -> Result<()> {
+        self.state_manager
+            .update_heartbeat(&self.pipeline_id)
+            .await?;
+        Ok(())
+    }
+
     /// Get the current pipeline state
     pub async fn get_state(&self) -> Result<Option<PipelineState>> {
         match self.state_manager.load_state(&self.pipeline_id).await {
@@ -280,6 +415,64 @@ impl PipelineTracker {
         Ok(None)
     }
 
+    /// Resume a run that was paused, taking it back to `Running`
+    ///
+    /// Returns `None` unless the pipeline's saved state is `Paused`. Otherwise
+    /// the tracker continues the paused run, and the state as it was paused is
+    /// returned alongside it, with the checkpoint it refers to; its
+    /// `current_step` and the checkpoint say where to pick up. The checkpoint
+    /// is `None` when it is missing or doesn't match the state's
+    /// `resume_checkpoint` hash. Either way it is removed from the backend.
+    pub async fn resume_paused(
+        state_manager: StateManager,
+        pipeline_id: &str,
+    ) -> Result<Option<(Self, PipelineState, Option<ResumeCheckpoint>)>> {
+        let Ok(paused) = state_manager.load_state(pipeline_id).await else {
+            return Ok(None);
+        };
+        if !matches!(paused.status, PipelineStatus::Paused { .. }) {
+            return Ok(None);
+        }
+
+        let checkpoint = match &paused.resume_checkpoint {
+            Some(hash) => state_manager
+                .load_resume_checkpoint(pipeline_id)
+                .await?
+                .filter(|checkpoint| checkpoint.content_hash().ok().as_ref() == Some(hash)),
+            None => None,
+        };
+        state_manager
+            .update_state_locked(pipeline_id, |state| {
+                let now = Utc::now();
+                state.status = PipelineStatus::Running { started_at: now };
+                state.resume_checkpoint = None;
+                state.last_heartbeat = now;
+                state.metadata.updated_at = now;
+            })
+            .await?;
+        state_manager.delete_resume_checkpoint(pipeline_id).await?;
+        let tracker = Self {
+            state_manager,
+            pipeline_id: pipeline_id.to_string(),
+            run_id: paused.run_id.clone(),
+            start_time: Instant::now(),
+            started_at: paused.started_at,
+        };
+        Ok(Some((tracker, paused, checkpoint)))
+    }
+
+    /// Whether a step completed in the tracked run can be skipped on resume
+    ///
+    /// Returns `false` when the step never completed or its config hash no
+    /// longer matches, so a drifted step is always re-executed.
+    pub async fn can_skip_step(&self, step_id: &str, config_hash: &str) -> Result<bool> {
+        let state = self.state_manager.load_state(&self.pipeline_id).await?;
+        Ok(state
+            .step_states
+            .get(step_id)
+            .is_some_and(|step| step.is_reusable(config_hash)))
+    }
+
     /// Get pipeline ID
     pub fn pipeline_id(&self) -> &str {
         &self.pipeline_id
@@ -306,13 +499,21 @@ mod tests {
                 description: Some("Test pipeline".to_string()),
                 version: Some("1.0.0".to_string()),
                 author: Some("test".to_string()),
+                tags: Default::default(),
             }),
+            limits_enforcement: Default::default(),
+            retry_budget: None,
+            circuit_breaker: None,
+            state: None,
+            defaults: Default::default(),
+            on_failure: None,
+            run_context: Default::default(),
         }
     }
 
     async fn create_test_state_manager() -> StateManager {
         let config = StateManagerConfig {
-            backend: BackendConfig::Memory { persistent: false },
+            backend: BackendConfig::Memory { persist_dir: None },
             ..Default::default()
         };
         StateManager::new(config).await.unwrap()
@@ -346,7 +547,7 @@ mod tests {
             .unwrap();
 
         // Start a step
-        tracker.start_step("test_step").await.unwrap();
+        tracker.start_step("test_step", None).await.unwrap();
 
         let state = tracker.get_state().await.unwrap().unwrap();
         assert_eq!(state.current_step, "test_step");
@@ -364,6 +565,8 @@ mod tests {
             error: None,
             retry_count: 0,
             duration_ms: 100,
+            error_type: None,
+            retryable: false,
         };
 
         tracker.complete_step(&step_result).await.unwrap();
@@ -376,6 +579,51 @@ mod tests {
         assert_eq!(state.records_processed, 1);
     }
 
+    #[tokio::test]
+    async fn test_record_batch_updates_progress() {
+        let state_manager = create_test_state_manager().await;
+        let pipeline = create_test_pipeline();
+        let tracker = PipelineTracker::new(state_manager, &pipeline)
+            .await
+            .unwrap();
+
+        tracker.start_step("test_step", None).await.unwrap();
+        tracker.record_batch("test_step", 1000).await.unwrap();
+        tracker.record_batch("test_step", 250).await.unwrap();
+
+        let state = tracker.get_state().await.unwrap().unwrap();
+        assert_eq!(state.batch_number, 2);
+        assert_eq!(state.step_states["test_step"].records_processed, 1250);
+    }
+
+    #[tokio::test]
+    async fn test_estimated_completion_from_previous_runs() {
+        let state_manager = create_test_state_manager().await;
+        let pipeline = create_test_pipeline();
+
+        // No history yet
+        let tracker = PipelineTracker::new(state_manager.clone(), &pipeline)
+            .await
+            .unwrap();
+        let state = tracker.get_state().await.unwrap().unwrap();
+        assert!(state.estimated_completion.is_none());
+
+        let mut previous = state;
+        previous.status = PipelineStatus::Completed {
+            completed_at: previous.started_at + chrono::Duration::seconds(90),
+        };
+        state_manager.save_state(&previous).await.unwrap();
+
+        let tracker = PipelineTracker::new(state_manager, &pipeline)
+            .await
+            .unwrap();
+        let state = tracker.get_state().await.unwrap().unwrap();
+        assert_eq!(
+            state.estimated_completion,
+            Some(state.started_at + chrono::Duration::seconds(90))
+        );
+    }
+
     #[tokio::test]
     async fn test_pipeline_resume() {
         let state_manager = create_test_state_manager().await;
@@ -404,4 +652,50 @@ mod tests {
             .unwrap();
         assert!(resumed_tracker.is_none());
     }
+
+    #[test]
+    fn test_config_hash_is_deterministic() {
+        let a = crate::types::OxiConfig::from_yaml(
+            serde_yaml::from_str("path: in.csv\noptions:\n  delimiter: ','\n  headers: true\n")
+                .unwrap(),
+        );
+        let b = crate::types::OxiConfig::from_yaml(
+            serde_yaml::from_str("options:\n  headers: true\n  delimiter: ','\npath: in.csv\n")
+                .unwrap(),
+        );
+        assert_eq!(a.config_hash(), b.config_hash());
+
+        let mut changed = a.clone();
+        changed.set("path", "other.csv").unwrap();
+        assert_ne!(a.config_hash(), changed.config_hash());
+    }
+
+    #[tokio::test]
+    async fn test_config_drift_forces_reexecution() {
+        let state_manager = create_test_state_manager().await;
+        let pipeline = create_test_pipeline();
+        let tracker = PipelineTracker::new(state_manager, &pipeline)
+            .await
+            .unwrap();
+
+        tracker.start_step("read", Some("abc")).await.unwrap();
+        assert!(!tracker.can_skip_step("read", "abc").await.unwrap());
+
+        tracker
+            .complete_step(&StepResult {
+                step_id: "read".to_string(),
+                success: true,
+                data: None,
+                error: None,
+                retry_count: 0,
+                duration_ms: 5,
+                error_type: None,
+                retryable: false,
+            })
+            .await
+            .unwrap();
+        assert!(tracker.can_skip_step("read", "abc").await.unwrap());
+        assert!(!tracker.can_skip_step("read", "def").await.unwrap());
+        assert!(!tracker.can_skip_step("missing", "abc").await.unwrap());
+    }
 }
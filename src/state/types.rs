@@ -1,6 +1,8 @@
+use crate::error::OxiError;
+use crate::types::Data;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -58,6 +60,13 @@ pub struct PipelineState {
     // Execution state
     pub current_step: String,
     pub step_states: HashMap<String, StepState>,
+    /// Content hash of the [`ResumeCheckpoint`] saved beside the state when
+    /// the run was paused, so a resumed run can skip the steps before `current_step`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_checkpoint: Option<String>,
+    /// Completed steps of older runs, collapsed by [`PipelineState::compact`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub step_summaries: Vec<StepSummary>,
     pub status: PipelineStatus,
 
     // Timing and metadata
@@ -75,6 +84,73 @@ pub struct PipelineState {
 
     // Metadata
     pub metadata: StateMetadata,
+
+    /// Growth caps, set from `StateManagerConfig` when the manager loads or saves the state
+    #[serde(skip)]
+    pub limits: StateLimits,
+    /// Errors evicted from `errors` that the next save appends to the error archive
+    #[serde(skip)]
+    pub evicted_errors: Vec<ErrorRecord>,
+}
+
+/// Caps on how large a [`PipelineState`] grows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateLimits {
+    /// Most errors kept in the state; older ones are evicted for archival
+    pub max_errors_in_state: usize,
+    /// Runs whose completed steps stay in `step_states`, the current run included
+    pub step_state_runs: usize,
+    /// Most entries kept in `step_summaries`; the oldest are dropped
+    pub max_step_summaries: usize,
+    /// Runs kept in the backend's run history; the least recently saved are deleted
+    pub max_saved_runs: usize,
+}
+
+impl Default for StateLimits {
+    fn default() -> Self {
+        Self {
+            max_errors_in_state: 500,
+            step_state_runs: 3,
+            max_step_summaries: 1000,
+            max_saved_runs: 50,
+        }
+    }
+}
+
+/// What is left of a completed step once its run has been compacted away
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepSummary {
+    pub step_id: String,
+    pub run_id: String,
+    pub records_processed: u64,
+    pub processing_time_ms: u64,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// What a paused run needs to pick up where it stopped
+///
+/// Stored by the backend beside the state rather than in it, so a large
+/// batch doesn't bloat every state write.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumeCheckpoint {
+    /// Input of the step the run stopped at
+    pub input: Data,
+    /// Metadata reported by the completed steps before it, for `${step_id.metadata.key}`
+    #[serde(default)]
+    pub step_metadata: BTreeMap<String, BTreeMap<String, serde_json::Value>>,
+    /// Outputs of those steps that later steps reference as `${step_id.field}`
+    #[serde(default)]
+    pub step_outputs: BTreeMap<String, Data>,
+}
+
+impl ResumeCheckpoint {
+    /// Hash of the serialized checkpoint, recorded in the state that refers to it
+    ///
+    /// Ordered maps keep the serialization, and so the hash, stable.
+    pub fn content_hash(&self) -> Result<String, StateError> {
+        let data = serde_json::to_vec(self)?;
+        Ok(format!("{:x}", md5::compute(data)))
+    }
 }
 
 /// State information for an individual pipeline step
@@ -93,6 +169,9 @@ pub struct StepState {
     pub retry_count: u64,
     pub error_count: u64,
     pub config_hash: Option<String>, // Hash of step configuration
+    /// Run that last executed the step
+    #[serde(default)]
+    pub run_id: Option<String>,
 }
 
 /// Error record for tracking pipeline and step failures
@@ -119,10 +198,172 @@ pub enum ErrorType {
     Processing,
     /// Resource exhaustion (memory, disk, etc.)
     Resource,
+    /// A step ran past its time limit
+    Timeout,
+    /// Execution was stopped on request, e.g. on shutdown
+    Cancelled,
     /// Unknown or unexpected error
     Unknown,
 }
 
+/// The variant of a [`PipelineStatus`], without its timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusKind {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Paused,
+}
+
+impl PipelineStatus {
+    /// Which variant this status is
+    pub fn kind(&self) -> StatusKind {
+        match self {
+            PipelineStatus::Pending => StatusKind::Pending,
+            PipelineStatus::Running { .. } => StatusKind::Running,
+            PipelineStatus::Completed { .. } => StatusKind::Completed,
+            PipelineStatus::Failed { .. } => StatusKind::Failed,
+            PipelineStatus::Paused { .. } => StatusKind::Paused,
+        }
+    }
+}
+
+/// The fields of a [`PipelineState`] that listing and filtering need
+///
+/// Returned by `StateBackend::query_states`, which can answer from an index
+/// instead of deserializing every state.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StateSummary {
+    pub pipeline_id: String,
+    pub run_id: String,
+    pub status: PipelineStatus,
+    pub worker_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub last_heartbeat: DateTime<Utc>,
+    pub records_processed: u64,
+    pub records_failed: u64,
+    /// Environment the run was started in, see `StateMetadata::environment`
+    #[serde(default)]
+    pub environment: Option<String>,
+    pub tags: HashMap<String, String>,
+}
+
+impl StateSummary {
+    /// Check if the state is stale, like [`PipelineState::is_stale`]
+    pub fn is_stale(&self, stale_threshold_ms: u64) -> bool {
+        let stale_threshold = chrono::Duration::milliseconds(stale_threshold_ms as i64);
+        Utc::now() - self.last_heartbeat > stale_threshold
+    }
+}
+
+impl From<&PipelineState> for StateSummary {
+    fn from(state: &PipelineState) -> Self {
+        Self {
+            pipeline_id: state.pipeline_id.clone(),
+            run_id: state.run_id.clone(),
+            status: state.status.clone(),
+            worker_id: state.worker_id.clone(),
+            started_at: state.started_at,
+            updated_at: state.metadata.updated_at,
+            last_heartbeat: state.last_heartbeat,
+            records_processed: state.records_processed,
+            records_failed: state.records_failed,
+            environment: state.metadata.environment.clone(),
+            tags: state.metadata.tags.clone(),
+        }
+    }
+}
+
+/// Field [`StateQuery`] results are ordered by; ties go by pipeline ID
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StateSortKey {
+    #[default]
+    PipelineId,
+    StartedAt,
+    UpdatedAt,
+    LastHeartbeat,
+    RecordsProcessed,
+}
+
+/// Filter, order and limit for `StateBackend::query_states`
+///
+/// Unset filters match every state, so the default query lists all of them
+/// by pipeline ID.
+#[derive(Debug, Clone, Default)]
+pub struct StateQuery {
+    /// Only states with one of these statuses; any status when empty
+    pub statuses: Vec<StatusKind>,
+    /// Only states last updated before this time
+    pub updated_before: Option<DateTime<Utc>>,
+    /// Only states last updated after this time
+    pub updated_after: Option<DateTime<Utc>>,
+    /// Only pipelines whose ID starts with this
+    pub name_prefix: Option<String>,
+    /// Only states recorded in this environment
+    pub environment: Option<String>,
+    /// Only states with every one of these tags, at the given value or any value for `None`
+    pub tags: HashMap<String, Option<String>>,
+    pub sort: StateSortKey,
+    pub descending: bool,
+    /// Most summaries to return, after sorting
+    pub limit: Option<usize>,
+}
+
+impl StateQuery {
+    /// Whether `summary` passes every filter of the query
+    pub fn matches(&self, summary: &StateSummary) -> bool {
+        (self.statuses.is_empty() || self.statuses.contains(&summary.status.kind()))
+            && self
+                .updated_before
+                .is_none_or(|before| summary.updated_at < before)
+            && self
+                .updated_after
+                .is_none_or(|after| summary.updated_at > after)
+            && self
+                .name_prefix
+                .as_deref()
+                .is_none_or(|prefix| summary.pipeline_id.starts_with(prefix))
+            && self
+                .environment
+                .as_ref()
+                .is_none_or(|env| summary.environment.as_ref() == Some(env))
+            && self
+                .tags
+                .iter()
+                .all(|(key, value)| match (summary.tags.get(key), value) {
+                    (Some(tagged), Some(value)) => tagged == value,
+                    (tagged, None) => tagged.is_some(),
+                    (None, Some(_)) => false,
+                })
+    }
+
+    /// Order matching summaries by the query's sort key and apply its limit
+    pub fn sort_and_limit(&self, mut summaries: Vec<StateSummary>) -> Vec<StateSummary> {
+        summaries.sort_by(|a, b| {
+            let order = match self.sort {
+                StateSortKey::PipelineId => std::cmp::Ordering::Equal,
+                StateSortKey::StartedAt => a.started_at.cmp(&b.started_at),
+                StateSortKey::UpdatedAt => a.updated_at.cmp(&b.updated_at),
+                StateSortKey::LastHeartbeat => a.last_heartbeat.cmp(&b.last_heartbeat),
+                StateSortKey::RecordsProcessed => a.records_processed.cmp(&b.records_processed),
+            }
+            .then_with(|| a.pipeline_id.cmp(&b.pipeline_id));
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        if let Some(limit) = self.limit {
+            summaries.truncate(limit);
+        }
+        summaries
+    }
+}
+
 /// Metadata about the state itself
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateMetadata {
@@ -136,7 +377,12 @@ pub struct StateMetadata {
     // Optional fields for enhanced metadata
     pub pipeline_name: Option<String>,
     pub pipeline_version: Option<String>,
+    /// `Pipeline::content_hash` of the definition that produced this state
+    #[serde(default)]
+    pub pipeline_content_hash: Option<String>,
+    /// Environment the run was started in (`project.environment` or `OXIDE_FLOW_ENV`)
     pub environment: Option<String>,
+    /// Project default tags overlaid with the pipeline's `metadata.tags`
     pub tags: HashMap<String, String>,
 }
 
@@ -155,6 +401,12 @@ pub enum StateError {
     #[error("Lock acquisition timeout after {timeout_ms}ms")]
     LockTimeout { timeout_ms: u64 },
 
+    #[error("Lock on {pipeline_id} is no longer held by worker {worker_id}")]
+    LockLost {
+        pipeline_id: String,
+        worker_id: String,
+    },
+
     #[error("Version conflict: expected {expected}, found {actual}")]
     VersionConflict { expected: u64, actual: u64 },
 
@@ -204,6 +456,13 @@ pub enum StateError {
 
     #[error("Maximum retries exceeded: {max_retries} for operation: {operation}")]
     MaxRetriesExceeded { max_retries: u32, operation: String },
+
+    #[error("Pipeline '{pipeline_id}' is already running on worker {worker_id} (run {run_id})")]
+    RunInProgress {
+        pipeline_id: String,
+        worker_id: String,
+        run_id: String,
+    },
 }
 
 impl PipelineState {
@@ -222,6 +481,8 @@ impl PipelineState {
             data_size_processed: 0,
             current_step: String::new(),
             step_states: HashMap::new(),
+            resume_checkpoint: None,
+            step_summaries: Vec::new(),
             status: PipelineStatus::Pending,
             started_at: now,
             last_success_timestamp: now,
@@ -239,12 +500,25 @@ impl PipelineState {
                 last_checkpoint_at: now,
                 pipeline_name: None,
                 pipeline_version: None,
+                pipeline_content_hash: None,
                 environment: None,
                 tags: HashMap::new(),
             },
+            limits: StateLimits::default(),
+            evicted_errors: Vec::new(),
         }
     }
 
+    /// Whether the pipeline definition differs from the one this state was recorded for
+    ///
+    /// States written before content hashes were recorded never report a change.
+    pub fn pipeline_changed(&self, current_hash: &str) -> bool {
+        self.metadata
+            .pipeline_content_hash
+            .as_deref()
+            .is_some_and(|recorded| recorded != current_hash)
+    }
+
     /// Update the state version for optimistic concurrency control
     pub fn increment_version(&mut self) {
         self.version += 1;
@@ -252,11 +526,89 @@ impl PipelineState {
     }
 
     /// Add an error to the state
+    ///
+    /// Once there are more than `limits.max_errors_in_state` errors the
+    /// oldest move to `evicted_errors`.
     pub fn add_error(&mut self, error: ErrorRecord) {
         self.errors.push(error);
+        self.evict_errors();
         self.increment_version();
     }
 
+    /// Move the errors over the cap, oldest first, to `evicted_errors`
+    fn evict_errors(&mut self) -> bool {
+        let excess = self
+            .errors
+            .len()
+            .saturating_sub(self.limits.max_errors_in_state);
+        self.evicted_errors.extend(self.errors.drain(..excess));
+        excess > 0
+    }
+
+    /// Bring the state back within its `limits`
+    ///
+    /// Evicts errors over the cap and collapses the completed steps of all but
+    /// the latest `limits.step_state_runs` runs into `step_summaries`, keeping
+    /// at most `limits.max_step_summaries` of them. Runs are ordered by their
+    /// steps' last heartbeat, and the current run always counts as the
+    /// latest. Returns whether anything changed, so a second call right after
+    /// is a no-op. The version is left to the save that follows.
+    pub fn compact(&mut self) -> bool {
+        let evicted = self.evict_errors();
+
+        let mut runs: HashMap<&str, DateTime<Utc>> = HashMap::new();
+        for step in self.step_states.values() {
+            if let Some(run_id) = step.run_id.as_deref().filter(|id| *id != self.run_id) {
+                let latest = runs.entry(run_id).or_insert(step.last_heartbeat);
+                *latest = (*latest).max(step.last_heartbeat);
+            }
+        }
+        let mut runs: Vec<_> = runs.into_iter().collect();
+        runs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let expired: HashSet<String> = runs
+            .into_iter()
+            .skip(self.limits.step_state_runs.saturating_sub(1))
+            .map(|(run_id, _)| run_id.to_string())
+            .collect();
+
+        let collapsed: Vec<String> = self
+            .step_states
+            .iter()
+            .filter(|(_, step)| {
+                matches!(step.status, StepStatus::Completed { .. })
+                    && step.run_id.as_ref().is_some_and(|id| expired.contains(id))
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        let mut summaries: Vec<StepSummary> = collapsed
+            .iter()
+            .filter_map(|key| self.step_states.remove(key))
+            .filter_map(|step| match (step.status, step.run_id) {
+                (StepStatus::Completed { completed_at }, Some(run_id)) => Some(StepSummary {
+                    step_id: step.step_id,
+                    run_id,
+                    records_processed: step.records_processed,
+                    processing_time_ms: step.processing_time_ms,
+                    completed_at,
+                }),
+                _ => None,
+            })
+            .collect();
+        summaries.sort_by(|a, b| {
+            a.completed_at
+                .cmp(&b.completed_at)
+                .then_with(|| a.step_id.cmp(&b.step_id))
+        });
+        self.step_summaries.extend(summaries);
+        let dropped = self
+            .step_summaries
+            .len()
+            .saturating_sub(self.limits.max_step_summaries);
+        self.step_summaries.drain(..dropped);
+
+        evicted || !collapsed.is_empty() || dropped > 0
+    }
+
     /// Update the heartbeat timestamp
     pub fn update_heartbeat(&mut self) {
         self.last_heartbeat = Utc::now();
@@ -355,12 +707,10 @@ impl PipelineState {
                         ));
                     }
                 }
-                StepStatus::Failed { failed_at, .. } => {
-                    if failed_at > &Utc::now() {
-                        errors.push(format!(
-                            "Step '{step_id}' failure time cannot be in the future"
-                        ));
-                    }
+                StepStatus::Failed { failed_at, .. } if failed_at > &Utc::now() => {
+                    errors.push(format!(
+                        "Step '{step_id}' failure time cannot be in the future"
+                    ));
                 }
                 _ => {}
             }
@@ -445,9 +795,19 @@ impl StepState {
             retry_count: 0,
             error_count: 0,
             config_hash: None,
+            run_id: None,
         }
     }
 
+    /// Whether a previous run's result for this step can be reused
+    ///
+    /// Only completed steps whose recorded config hash matches the current
+    /// one qualify; a changed (or unrecorded) config forces re-execution.
+    pub fn is_reusable(&self, config_hash: &str) -> bool {
+        matches!(self.status, StepStatus::Completed { .. })
+            && self.config_hash.as_deref() == Some(config_hash)
+    }
+
     /// Mark the step as started
     pub fn start(&mut self) {
         self.status = StepStatus::Running {
@@ -536,6 +896,19 @@ impl ErrorRecord {
     pub fn network_error(step_id: String, message: String, context: String) -> Self {
         Self::new(Some(step_id), ErrorType::Network, message, context, true)
     }
+
+    /// Record an Oxi failure with the error's own classification
+    ///
+    /// See [`OxiError::error_type`] and [`OxiError::is_retryable`].
+    pub fn from_oxi_error(step_id: Option<String>, error: &OxiError, context: String) -> Self {
+        Self::new(
+            step_id,
+            error.error_type(),
+            error.to_string(),
+            context,
+            error.is_retryable(),
+        )
+    }
 }
 
 // Implement From for common error types
@@ -634,7 +1007,7 @@ mod tests {
             "pipeline validation".to_string(),
         );
 
-        assert!(error.error_id.len() > 0);
+        assert!(!error.error_id.is_empty());
         assert_eq!(error.step_id, None);
         assert_eq!(error.error_type, ErrorType::Configuration);
         assert!(!error.retryable);
@@ -651,6 +1024,35 @@ mod tests {
         assert!(processing_error.retryable);
     }
 
+    #[test]
+    fn test_error_record_from_timeout_and_cancellation() {
+        let timeout = OxiError::Timeout {
+            step: "fetch".to_string(),
+            elapsed_ms: 30_000,
+        };
+        let error = ErrorRecord::from_oxi_error(
+            Some("fetch".to_string()),
+            &timeout,
+            "attempt 1".to_string(),
+        );
+        assert_eq!(error.error_type, ErrorType::Resource);
+        assert!(error.retryable);
+        assert!(error.message.contains("Timeout after 30s in step 'fetch'"));
+
+        let error = ErrorRecord::from_oxi_error(None, &OxiError::Cancelled, String::new());
+        assert_eq!(error.error_type, ErrorType::Cancelled);
+        assert!(!error.retryable);
+        assert!(error.message.contains("cancelled"));
+
+        let error = ErrorRecord::from_oxi_error(
+            None,
+            &OxiError::MissingConfig("path".to_string()),
+            String::new(),
+        );
+        assert_eq!(error.error_type, ErrorType::Configuration);
+        assert!(!error.retryable);
+    }
+
     #[test]
     fn test_pipeline_state_add_error() {
         let mut state = PipelineState::new("test".to_string(), "run".to_string());
@@ -665,6 +1067,49 @@ mod tests {
         assert_eq!(state.errors[0].message, "Test error");
     }
 
+    #[test]
+    fn test_compact_is_idempotent() {
+        let mut state = PipelineState::new("test".to_string(), "run_4".to_string());
+        state.limits = StateLimits {
+            max_errors_in_state: 2,
+            step_state_runs: 2,
+            max_step_summaries: 10,
+            ..StateLimits::default()
+        };
+        state.errors = (0..5)
+            .map(|i| ErrorRecord::config_error(format!("error {i}"), String::new()))
+            .collect();
+        for run in 1..=4 {
+            let mut step = StepState::new(format!("step_{run}"), format!("step_{run}"));
+            step.complete();
+            step.last_heartbeat = Utc::now() - chrono::Duration::minutes(10 - run);
+            step.run_id = Some(format!("run_{run}"));
+            state.step_states.insert(step.step_id.clone(), step);
+        }
+
+        assert!(state.compact());
+        let evicted: Vec<_> = state.evicted_errors.iter().map(|e| &e.message).collect();
+        assert_eq!(evicted, ["error 0", "error 1", "error 2"]);
+        assert_eq!(state.errors.len(), 2);
+        // The current run and the latest other run keep their steps
+        let mut kept: Vec<_> = state.step_states.keys().cloned().collect();
+        kept.sort();
+        assert_eq!(kept, ["step_3", "step_4"]);
+        let collapsed: Vec<_> = state.step_summaries.iter().map(|s| &s.run_id).collect();
+        assert_eq!(collapsed, ["run_1", "run_2"]);
+
+        let compacted = serde_json::to_value(&state).unwrap();
+        assert!(!state.compact());
+        assert_eq!(serde_json::to_value(&state).unwrap(), compacted);
+        assert_eq!(state.evicted_errors.len(), 3);
+
+        // Only the newest summaries are kept
+        state.limits.max_step_summaries = 1;
+        assert!(state.compact());
+        let kept: Vec<_> = state.step_summaries.iter().map(|s| &s.run_id).collect();
+        assert_eq!(kept, ["run_2"]);
+    }
+
     #[test]
     fn test_state_staleness() {
         let mut state = PipelineState::new("test".to_string(), "run".to_string());
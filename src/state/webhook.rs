@@ -0,0 +1,509 @@
+//! Webhook delivery of pipeline state events
+//!
+//! `WebhookNotifier` is a `StateObserver` that POSTs every event it sees as
+//! JSON. With a shared secret configured, the body is signed with
+//! HMAC-SHA256 and the signature sent as `X-Oxide-Signature: sha256=<hex>`,
+//! so receivers can verify that the event came from this project.
+//!
+//! Notifiers are declared in `oxiflow.yaml`:
+//!
+//! ```yaml
+//! project:
+//!   name: orders
+//!   notifications:
+//!     webhooks:
+//!       - endpoint: https://hooks.example.com/oxide
+//!         secret: !secret WEBHOOK_SECRET
+//!         retry: {max_attempts: 5, initial_backoff_ms: 200}
+//! ```
+
+use crate::state::manager::{StateManager, StateObserver};
+use crate::state::types::{ErrorRecord, PipelineState};
+use crate::types::OxiConfig;
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Header carrying the HMAC-SHA256 signature of the request body
+pub const SIGNATURE_HEADER: &str = "X-Oxide-Signature";
+
+/// `notifications` block of the project section in `oxiflow.yaml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Webhook definitions, each read by `WebhookNotifier::from_config`
+    #[serde(default)]
+    pub webhooks: Vec<serde_yaml::Value>,
+}
+
+/// How often, and how patiently, a failed delivery is retried
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    /// Factor applied to the backoff after every failed attempt
+    pub backoff_multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            backoff_multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0 for the first retry)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .mul_f64(self.backoff_multiplier.max(1.0).powi(retry as i32))
+    }
+}
+
+/// Events waiting for delivery to one endpoint; further events are dropped
+const DELIVERY_QUEUE_CAPACITY: usize = 256;
+
+/// Longest `flush` waits for queued events before giving up on them
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Queue feeding a notifier's background delivery task
+struct Delivery {
+    sender: mpsc::Sender<serde_json::Value>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+/// Observer that POSTs state events to an HTTP endpoint
+///
+/// Events are queued and delivered in order by a background task, so a slow
+/// or unreachable endpoint never holds up the state save that raised them.
+pub struct WebhookNotifier {
+    pub endpoint: String,
+    pub secret: Option<String>,
+    pub retry_policy: RetryPolicy,
+    agent: ureq::Agent,
+    /// Started on the first event, once the notifier is fully configured
+    delivery: Mutex<Option<Delivery>>,
+}
+
+impl WebhookNotifier {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            secret: None,
+            retry_policy: RetryPolicy::default(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(10))
+                .build(),
+            delivery: Mutex::new(None),
+        }
+    }
+
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Build a notifier from a webhook config block
+    ///
+    /// `endpoint` is required. `secret` may be a plain string or a `!secret`
+    /// reference. `retry` accepts `max_attempts`, `initial_backoff_ms` and
+    /// `backoff_multiplier`; `timeout_ms` bounds each request.
+    pub fn from_config(config: &OxiConfig) -> Result<Self> {
+        let endpoint = config
+            .get_string("endpoint")
+            .context("Webhook config requires an 'endpoint'")?;
+        if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+            bail!("Webhook endpoint '{endpoint}' must be an http(s) URL");
+        }
+
+        let mut notifier = Self::new(endpoint);
+        if config.has_key("secret") {
+            notifier.secret = Some(
+                config
+                    .get_secret("secret")
+                    .or_else(|_| config.get_string("secret"))?,
+            );
+        }
+
+        let retry = config.get_nested_or("retry");
+        let defaults = RetryPolicy::default();
+        let max_attempts = retry.get_i64_or("max_attempts", defaults.max_attempts as i64);
+        if max_attempts < 1 {
+            bail!("Webhook retry.max_attempts must be at least 1");
+        }
+        notifier.retry_policy = RetryPolicy {
+            max_attempts: max_attempts as u32,
+            initial_backoff: Duration::from_millis(retry.get_i64_or(
+                "initial_backoff_ms",
+                defaults.initial_backoff.as_millis() as i64,
+            ) as u64),
+            backoff_multiplier: retry
+                .get_number_or("backoff_multiplier", defaults.backoff_multiplier),
+        };
+
+        if config.has_key("timeout_ms") {
+            let timeout = config.get_i64("timeout_ms")?;
+            notifier.agent = ureq::AgentBuilder::new()
+                .timeout(Duration::from_millis(timeout.max(1) as u64))
+                .build();
+        }
+
+        Ok(notifier)
+    }
+
+    /// `sha256=<hex>` signature of `body`, if a secret is configured
+    pub fn signature(&self, body: &[u8]) -> Option<String> {
+        self.secret
+            .as_ref()
+            .map(|secret| format!("sha256={}", to_hex(&hmac_sha256(secret.as_bytes(), body))))
+    }
+
+    /// POST `event` to the endpoint, retrying according to the retry policy
+    pub async fn send(&self, event: &serde_json::Value) -> Result<()> {
+        let body = serde_json::to_vec(event)?;
+        let signature = self.signature(&body);
+        let mut last_error = anyhow!("no delivery attempted");
+
+        for attempt in 0..self.retry_policy.max_attempts {
+            if attempt > 0 {
+                tokio::time::sleep(self.retry_policy.backoff(attempt - 1)).await;
+            }
+
+            let request = self
+                .agent
+                .post(&self.endpoint)
+                .set("Content-Type", "application/json");
+            let request = match &signature {
+                Some(signature) => request.set(SIGNATURE_HEADER, signature),
+                None => request,
+            };
+            let payload = body.clone();
+            // Reduce the error to its status code inside the task; ureq errors are large
+            let outcome = tokio::task::spawn_blocking(move || {
+                request
+                    .send_bytes(&payload)
+                    .map(|_| ())
+                    .map_err(|e| match e {
+                        ureq::Error::Status(code, _) => (Some(code), e.to_string()),
+                        other => (None, other.to_string()),
+                    })
+            })
+            .await
+            .context("Webhook delivery task panicked")?;
+
+            match outcome {
+                Ok(()) => return Ok(()),
+                Err((Some(code), _)) if !is_retryable_status(code) => {
+                    bail!(
+                        "Webhook {} rejected the event with HTTP {code}",
+                        self.endpoint
+                    );
+                }
+                Err((_, e)) => {
+                    last_error = anyhow!("Webhook delivery to {} failed: {e}", self.endpoint)
+                }
+            }
+        }
+
+        Err(last_error.context(format!(
+            "Giving up after {} attempts",
+            self.retry_policy.max_attempts
+        )))
+    }
+
+    /// Queue an event for delivery, reporting rather than propagating failures
+    async fn notify(&self, event: serde_json::Value) {
+        let mut delivery = self.delivery.lock().unwrap();
+        let delivery = delivery.get_or_insert_with(|| {
+            let (sender, mut receiver) = mpsc::channel(DELIVERY_QUEUE_CAPACITY);
+            let notifier = Self {
+                endpoint: self.endpoint.clone(),
+                secret: self.secret.clone(),
+                retry_policy: self.retry_policy.clone(),
+                agent: self.agent.clone(),
+                delivery: Mutex::new(None),
+            };
+            let task = tokio::spawn(async move {
+                while let Some(event) = receiver.recv().await {
+                    if let Err(e) = notifier.send(&event).await {
+                        log::warn!("{e:#}");
+                    }
+                }
+            });
+            Delivery { sender, task }
+        });
+        if delivery.sender.try_send(event).is_err() {
+            log::warn!(
+                "Webhook {} is falling behind, dropping an event",
+                self.endpoint
+            );
+        }
+    }
+}
+
+impl StateManager {
+    /// Register a `WebhookNotifier` for every webhook in `settings`
+    ///
+    /// Returns the number of notifiers added.
+    pub fn register_notifications(&mut self, settings: &NotificationSettings) -> Result<usize> {
+        for (index, webhook) in settings.webhooks.iter().enumerate() {
+            let notifier = WebhookNotifier::from_config(&OxiConfig::from_yaml(webhook.clone()))
+                .with_context(|| format!("Invalid webhook #{}", index + 1))?;
+            self.add_observer(Arc::new(notifier));
+        }
+        Ok(settings.webhooks.len())
+    }
+}
+
+#[async_trait]
+impl StateObserver for WebhookNotifier {
+    async fn on_state_change(
+        &self,
+        pipeline_id: &str,
+        old_state: Option<&PipelineState>,
+        new_state: &PipelineState,
+    ) {
+        self.notify(serde_json::json!({
+            "event": "state_change",
+            "pipeline_id": pipeline_id,
+            "timestamp": Utc::now(),
+            "previous_status": old_state.map(|s| &s.status),
+            "state": new_state,
+        }))
+        .await;
+    }
+
+    async fn on_error(&self, pipeline_id: &str, error: &ErrorRecord) {
+        self.notify(serde_json::json!({
+            "event": "error",
+            "pipeline_id": pipeline_id,
+            "timestamp": Utc::now(),
+            "error": error,
+        }))
+        .await;
+    }
+
+    async fn on_lock_acquired(&self, pipeline_id: &str, worker_id: &str) {
+        self.notify(serde_json::json!({
+            "event": "lock_acquired",
+            "pipeline_id": pipeline_id,
+            "timestamp": Utc::now(),
+            "worker_id": worker_id,
+        }))
+        .await;
+    }
+
+    async fn on_lock_released(&self, pipeline_id: &str, worker_id: &str) {
+        self.notify(serde_json::json!({
+            "event": "lock_released",
+            "pipeline_id": pipeline_id,
+            "timestamp": Utc::now(),
+            "worker_id": worker_id,
+        }))
+        .await;
+    }
+
+    /// Deliver the queued events, waiting up to `FLUSH_TIMEOUT`
+    async fn flush(&self) {
+        let Some(Delivery { sender, task }) = self.delivery.lock().unwrap().take() else {
+            return;
+        };
+        // The task ends once the closed queue is drained
+        drop(sender);
+        if tokio::time::timeout(FLUSH_TIMEOUT, task).await.is_err() {
+            log::warn!(
+                "Webhook {} did not take its remaining events within {}s",
+                self.endpoint,
+                FLUSH_TIMEOUT.as_secs()
+            );
+        }
+    }
+}
+
+/// Server errors, timeouts and rate limiting are worth another attempt
+fn is_retryable_status(code: u16) -> bool {
+    code >= 500 || code == 408 || code == 429
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::manager::StateManager;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Minimal HTTP server answering with `statuses` in turn, reporting each request
+    fn serve(statuses: Vec<u16>) -> (String, mpsc::Receiver<(Vec<String>, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    headers.push(line.trim().to_string());
+                }
+                let length = headers
+                    .iter()
+                    .find_map(|h| {
+                        h.to_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                )
+                .unwrap();
+                sender
+                    .send((headers, String::from_utf8(body).unwrap()))
+                    .unwrap();
+            }
+        });
+
+        (endpoint, receiver)
+    }
+
+    fn quick_retries(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            backoff_multiplier: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha256_matches_rfc_4231() {
+        assert_eq!(
+            to_hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_events_are_posted_with_signature() {
+        let (endpoint, requests) = serve(vec![200]);
+        let mut manager = StateManager::new_memory();
+        let settings: NotificationSettings = serde_yaml::from_str(&format!(
+            "webhooks:\n  - endpoint: {endpoint}\n    secret: s3cret\n"
+        ))
+        .unwrap();
+        assert_eq!(manager.register_notifications(&settings).unwrap(), 1);
+
+        let state = PipelineState::new("orders".to_string(), "run_1".to_string());
+        manager.save_state(&state).await.unwrap();
+        manager.flush_observers().await;
+
+        // The server reports a request once it has answered it
+        let (headers, body) = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+        let event: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(event["event"], "state_change");
+        assert_eq!(event["pipeline_id"], "orders");
+        assert_eq!(event["state"]["run_id"], "run_1");
+
+        let expected = format!(
+            "x-oxide-signature: sha256={}",
+            to_hex(&hmac_sha256(b"s3cret", body.as_bytes()))
+        );
+        assert!(headers.iter().any(|h| h.to_lowercase() == expected));
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_endpoint_does_not_hold_up_saves() {
+        // Accepts connections into its backlog but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/hook", listener.local_addr().unwrap());
+        let mut manager = StateManager::new_memory();
+        manager.add_observer(Arc::new(WebhookNotifier::new(endpoint)));
+
+        let started = std::time::Instant::now();
+        for run in 0..3 {
+            let state = PipelineState::new("orders".to_string(), format!("run_{run}"));
+            manager.save_state(&state).await.unwrap();
+        }
+        assert!(started.elapsed() < Duration::from_secs(1));
+        drop(listener);
+    }
+
+    #[tokio::test]
+    async fn test_failed_deliveries_are_retried() {
+        let (endpoint, requests) = serve(vec![503, 500, 200]);
+        let notifier = WebhookNotifier::new(endpoint).with_retry_policy(quick_retries(3));
+
+        notifier
+            .send(&serde_json::json!({"event": "ping"}))
+            .await
+            .unwrap();
+        assert_eq!(requests.try_iter().count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let (endpoint, requests) = serve(vec![400]);
+        let notifier = WebhookNotifier::new(endpoint).with_retry_policy(quick_retries(3));
+
+        let error = notifier
+            .send(&serde_json::json!({"event": "ping"}))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("HTTP 400"));
+        assert_eq!(requests.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_from_config_validates_and_reads_retry_policy() {
+        let config = OxiConfig::from_yaml(
+            serde_yaml::from_str(
+                "endpoint: https://hooks.example.com\nretry:\n  max_attempts: 5\n  initial_backoff_ms: 100\n",
+            )
+            .unwrap(),
+        );
+        let notifier = WebhookNotifier::from_config(&config).unwrap();
+        assert_eq!(notifier.retry_policy.max_attempts, 5);
+        assert_eq!(notifier.retry_policy.backoff(2), Duration::from_millis(400));
+        assert!(notifier.signature(b"body").is_none());
+
+        let missing = OxiConfig::from_yaml(serde_yaml::from_str("secret: x").unwrap());
+        assert!(WebhookNotifier::from_config(&missing).is_err());
+        let not_http = OxiConfig::from_yaml(serde_yaml::from_str("endpoint: ftp://x").unwrap());
+        assert!(WebhookNotifier::from_config(&not_http).is_err());
+    }
+}
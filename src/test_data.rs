@@ -0,0 +1,419 @@
+//! Synthetic records that satisfy an `OxiSchema`, for unit-testing Oxis
+//!
+//! Generation is seeded, so a schema, record count and seed always produce
+//! the same data.
+
+use crate::types::{Data, FieldConstraint, FieldSchema, FieldType, OxiData, OxiSchema};
+use anyhow::{anyhow, bail, Result};
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Chance that a nullable field is generated as `null`
+const NULL_PROBABILITY: f64 = 0.2;
+
+/// Chance that a field with `examples` takes one of them
+const EXAMPLE_PROBABILITY: f64 = 0.5;
+
+/// Draws per value before constraints a draw can miss are given up on
+const MAX_ATTEMPTS: usize = 100;
+
+/// Width of a numeric range bounded on one side only, or not at all
+const DEFAULT_NUMBER_SPAN: f64 = 1000.0;
+
+/// Extra repetitions `*`, `+` and `{n,}` may produce in a pattern
+const MAX_PATTERN_REPEAT: u32 = 8;
+
+/// Generated timestamps fall between 2000-01-01 and 2030-01-01
+const DATETIME_RANGE_SECS: std::ops::Range<i64> = 946_684_800..1_893_456_000;
+
+impl OxiSchema {
+    /// Generate `count` JSON records that pass [`OxiSchema::validate_data`]
+    ///
+    /// Each field gets a value of its type that meets its `MinValue`,
+    /// `MaxValue`, `MinLength`, `MaxLength`, `Pattern`, `OneOf` and
+    /// `UniqueValues` constraints. A nullable field is `null` one time in
+    /// five, and a field with `examples` uses one of them half the time.
+    /// Fails if a field's constraints can't be met, e.g. a `MinValue` above
+    /// its `MaxValue`.
+    pub fn generate_test_data(&self, count: usize, seed: u64) -> Result<OxiData> {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut names: Vec<&String> = self.fields.keys().collect();
+        names.sort();
+
+        let mut seen: Vec<HashSet<String>> = vec![HashSet::new(); names.len()];
+        let mut records = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut record = Map::new();
+            for (index, name) in names.iter().enumerate() {
+                let field = &self.fields[*name];
+                let value = if field.constraints.contains(&FieldConstraint::UniqueValues) {
+                    generate_unique(field, name, &mut seen[index], &mut rng)?
+                } else {
+                    generate_value(field, name, &mut rng)?
+                };
+                record.insert((*name).clone(), value);
+            }
+            records.push(Value::Object(record));
+        }
+
+        Ok(OxiData::with_schema(
+            Data::Json(Value::Array(records)),
+            self.clone(),
+        ))
+    }
+}
+
+/// A value for `field` that no earlier record used
+fn generate_unique(
+    field: &FieldSchema,
+    path: &str,
+    seen: &mut HashSet<String>,
+    rng: &mut SmallRng,
+) -> Result<Value> {
+    for _ in 0..MAX_ATTEMPTS {
+        let value = generate_value(field, path, rng)?;
+        // Nulls don't count as duplicates
+        if value.is_null() || seen.insert(value.to_string()) {
+            return Ok(value);
+        }
+    }
+    bail!("Field '{path}' ran out of unique values")
+}
+
+/// A value for `field`, which may be null, an example or a fresh draw
+fn generate_value(field: &FieldSchema, path: &str, rng: &mut SmallRng) -> Result<Value> {
+    if field.nullable && rng.gen_bool(NULL_PROBABILITY) {
+        return Ok(Value::Null);
+    }
+
+    let one_of = field
+        .constraints
+        .iter()
+        .find_map(|constraint| match constraint {
+            FieldConstraint::OneOf(values) => Some(values),
+            _ => None,
+        });
+    if let Some(values) = one_of {
+        return values
+            .choose(rng)
+            .cloned()
+            .ok_or_else(|| anyhow!("Field '{path}' has an empty OneOf constraint"));
+    }
+
+    if !field.examples.is_empty() && rng.gen_bool(EXAMPLE_PROBABILITY) {
+        let example = field.examples.choose(rng).cloned().unwrap_or(Value::Null);
+        // Examples aren't checked when a schema is built
+        if field.validate_value(&example, path).is_ok() {
+            return Ok(example);
+        }
+    }
+
+    generate_typed(field, &field.field_type, path, rng)
+}
+
+/// A non-null value of `field_type` meeting the constraints of `field`
+fn generate_typed(
+    field: &FieldSchema,
+    field_type: &FieldType,
+    path: &str,
+    rng: &mut SmallRng,
+) -> Result<Value> {
+    match field_type {
+        FieldType::Integer => {
+            let (min, max) = number_range(field, path)?;
+            let (min, max) = (min.ceil(), max.floor());
+            if min > max {
+                bail!("Field '{path}' has no whole number between its MinValue and MaxValue");
+            }
+            Ok(Value::from(rng.gen_range(min as i64..=max as i64)))
+        }
+        FieldType::Float => {
+            let (min, max) = number_range(field, path)?;
+            Ok(Value::from(rng.gen_range(min..=max)))
+        }
+        FieldType::Boolean => Ok(Value::Bool(rng.gen())),
+        FieldType::DateTime => {
+            let secs = rng.gen_range(DATETIME_RANGE_SECS);
+            let timestamp = DateTime::from_timestamp(secs, 0)
+                .ok_or_else(|| anyhow!("Field '{path}' drew an invalid timestamp"))?;
+            Ok(Value::String(
+                timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            ))
+        }
+        FieldType::Binary => {
+            let bytes: Vec<u8> = (0..rng.gen_range(4..=16)).map(|_| rng.gen()).collect();
+            Ok(Value::String(
+                base64::engine::general_purpose::STANDARD.encode(bytes),
+            ))
+        }
+        FieldType::Array(item_type) => {
+            let item = FieldSchema::new((**item_type).clone());
+            let items = (0..rng.gen_range(0..=3))
+                .map(|index| generate_typed(&item, item_type, &format!("{path}[{index}]"), rng))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Value::Array(items))
+        }
+        FieldType::Object(fields) => {
+            let mut names: Vec<&String> = fields.keys().collect();
+            names.sort();
+            let mut object = Map::new();
+            for name in names {
+                let value = generate_value(&fields[name], &format!("{path}.{name}"), rng)?;
+                object.insert(name.clone(), value);
+            }
+            Ok(Value::Object(object))
+        }
+        // Strings satisfy whatever the untyped field constrains
+        FieldType::String | FieldType::Unknown | FieldType::Mixed => {
+            generate_string(field, path, rng).map(Value::String)
+        }
+    }
+}
+
+/// The inclusive range numbers of `field` are drawn from
+fn number_range(field: &FieldSchema, path: &str) -> Result<(f64, f64)> {
+    let mut min = None;
+    let mut max = None;
+    for constraint in &field.constraints {
+        match constraint {
+            FieldConstraint::MinValue(value) => {
+                min = Some(min.map_or(*value, |m: f64| m.max(*value)))
+            }
+            FieldConstraint::MaxValue(value) => {
+                max = Some(max.map_or(*value, |m: f64| m.min(*value)))
+            }
+            _ => {}
+        }
+    }
+    let (min, max) = match (min, max) {
+        (Some(min), Some(max)) => (min, max),
+        (Some(min), None) => (min, min + DEFAULT_NUMBER_SPAN),
+        (None, Some(max)) => (max - DEFAULT_NUMBER_SPAN, max),
+        (None, None) => (0.0, DEFAULT_NUMBER_SPAN),
+    };
+    if min > max {
+        bail!("Field '{path}' has a MinValue {min} above its MaxValue {max}");
+    }
+    Ok((min, max))
+}
+
+/// A string meeting the length and pattern constraints of `field`
+fn generate_string(field: &FieldSchema, path: &str, rng: &mut SmallRng) -> Result<String> {
+    let mut min_len = 0;
+    let mut max_len = None;
+    let mut pattern = None;
+    for constraint in &field.constraints {
+        match constraint {
+            FieldConstraint::MinLength(len) => min_len = min_len.max(*len),
+            FieldConstraint::MaxLength(len) => {
+                max_len = Some(max_len.map_or(*len, |m: usize| m.min(*len)))
+            }
+            FieldConstraint::Pattern(pattern_str) => pattern = Some(pattern_str),
+            _ => {}
+        }
+    }
+    if max_len.is_some_and(|max_len| max_len < min_len) {
+        bail!("Field '{path}' has a MinLength above its MaxLength");
+    }
+
+    let Some(pattern) = pattern else {
+        let max_len = max_len.unwrap_or(min_len.max(12));
+        let len = rng.gen_range(min_len.max(1).min(max_len)..=max_len);
+        return Ok((0..len)
+            .map(|_| rng.sample(rand::distributions::Alphanumeric) as char)
+            .collect());
+    };
+
+    let generator = rand_regex::Regex::compile(strip_anchors(pattern), MAX_PATTERN_REPEAT)
+        .map_err(|e| anyhow!("Field '{path}' pattern '{pattern}' can't be generated: {e}"))?;
+    for _ in 0..MAX_ATTEMPTS {
+        let value: String = rng.sample(&generator);
+        if value.len() >= min_len && max_len.is_none_or(|max_len| value.len() <= max_len) {
+            return Ok(value);
+        }
+    }
+    bail!("Field '{path}' pattern '{pattern}' produced no string of an allowed length")
+}
+
+/// `pattern` without a leading `^` or trailing `$`, which the generator rejects
+///
+/// A generated string is a whole match, so it still matches the anchored pattern.
+fn strip_anchors(pattern: &str) -> &str {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+    match pattern.strip_suffix('$') {
+        Some(rest) if !rest.ends_with('\\') => rest,
+        _ => pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(field_type: FieldType, constraints: Vec<FieldConstraint>) -> FieldSchema {
+        let mut field = FieldSchema::new(field_type);
+        field.constraints = constraints;
+        field
+    }
+
+    fn orders_schema() -> OxiSchema {
+        let mut schema = OxiSchema::empty();
+        schema.add_field(
+            "id".to_string(),
+            field(
+                FieldType::Integer,
+                vec![
+                    FieldConstraint::MinValue(1.0),
+                    FieldConstraint::MaxValue(100.0),
+                    FieldConstraint::UniqueValues,
+                ],
+            ),
+        );
+        schema.add_field(
+            "code".to_string(),
+            field(
+                FieldType::String,
+                vec![FieldConstraint::Pattern(
+                    "^[A-Z]{3}-[0-9]{2,4}$".to_string(),
+                )],
+            ),
+        );
+        schema.add_field(
+            "customer".to_string(),
+            field(
+                FieldType::String,
+                vec![FieldConstraint::MinLength(3), FieldConstraint::MaxLength(8)],
+            ),
+        );
+        schema.add_field(
+            "status".to_string(),
+            field(
+                FieldType::String,
+                vec![FieldConstraint::OneOf(vec![
+                    json!("open"),
+                    json!("shipped"),
+                ])],
+            ),
+        );
+        let mut discount = field(
+            FieldType::Float,
+            vec![
+                FieldConstraint::MinValue(0.0),
+                FieldConstraint::MaxValue(0.5),
+            ],
+        );
+        discount.nullable = true;
+        schema.add_field("discount".to_string(), discount);
+        let mut express = field(FieldType::Boolean, Vec::new());
+        express.examples = vec![json!(true), json!("not a boolean")];
+        schema.add_field("express".to_string(), express);
+        schema.add_field(
+            "placed_at".to_string(),
+            field(FieldType::DateTime, Vec::new()),
+        );
+        schema.add_field(
+            "tags".to_string(),
+            field(FieldType::Array(Box::new(FieldType::String)), Vec::new()),
+        );
+        schema.add_field(
+            "address".to_string(),
+            field(
+                FieldType::Object(
+                    [(
+                        "city".to_string(),
+                        field(FieldType::String, vec![FieldConstraint::MinLength(2)]),
+                    )]
+                    .into(),
+                ),
+                Vec::new(),
+            ),
+        );
+        schema
+    }
+
+    #[test]
+    fn test_generated_data_validates_against_its_schema() {
+        let schema = orders_schema();
+
+        let data = schema.generate_test_data(50, 7).unwrap();
+
+        let records = data.data().as_json().unwrap().as_array().unwrap();
+        assert_eq!(records.len(), 50);
+        schema.validate_data(data.data()).unwrap();
+        assert!(records.iter().any(|record| record["discount"].is_null()));
+        assert!(records.iter().any(|record| record["discount"].is_f64()));
+        assert!(records.iter().all(|record| record["express"].is_boolean()));
+        assert!(data.schema().structurally_eq(&schema));
+    }
+
+    #[test]
+    fn test_imported_json_schema_generates_valid_records() {
+        let (schema, _) = OxiSchema::from_json_schema(&json!({
+            "type": "object",
+            "required": ["status", "links"],
+            "properties": {
+                "status": {"type": "string", "pattern": "^[1-5][0-9]{2}$"},
+                "links": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["href"],
+                        "properties": {"href": {"type": "string", "pattern": "^/"}}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let data = schema.generate_test_data(20, 3).unwrap();
+
+        schema.validate_data(data.data()).unwrap();
+    }
+
+    #[test]
+    fn test_generation_is_seeded() {
+        let schema = orders_schema();
+        let json = |seed| {
+            schema
+                .generate_test_data(10, seed)
+                .unwrap()
+                .data()
+                .as_json()
+                .unwrap()
+                .clone()
+        };
+
+        assert_eq!(json(42), json(42));
+        assert_ne!(json(42), json(43));
+    }
+
+    #[test]
+    fn test_unsatisfiable_constraints_fail() {
+        let mut schema = OxiSchema::empty();
+        schema.add_field(
+            "qty".to_string(),
+            field(
+                FieldType::Integer,
+                vec![
+                    FieldConstraint::MinValue(10.0),
+                    FieldConstraint::MaxValue(5.0),
+                ],
+            ),
+        );
+        assert!(schema.generate_test_data(1, 0).is_err());
+
+        let mut schema = OxiSchema::empty();
+        schema.add_field(
+            "flag".to_string(),
+            field(FieldType::Boolean, vec![FieldConstraint::UniqueValues]),
+        );
+        let err = schema.generate_test_data(3, 0).unwrap_err();
+        assert!(err.to_string().contains("unique"), "{err}");
+    }
+}
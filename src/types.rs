@@ -1,10 +1,15 @@
 use crate::config::{ConfigError, OxiConfigSchema, PropertySchema};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Schema strategies that Oxis use to handle schema evolution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum SchemaStrategy {
     /// Schema passes through unchanged (filters, validators)
     Passthrough,
@@ -14,6 +19,17 @@ pub enum SchemaStrategy {
     Infer,
 }
 
+impl SchemaStrategy {
+    /// Name of the strategy, without a `Modify` description
+    pub fn label(&self) -> &'static str {
+        match self {
+            SchemaStrategy::Passthrough => "Passthrough",
+            SchemaStrategy::Modify { .. } => "Modify",
+            SchemaStrategy::Infer => "Infer",
+        }
+    }
+}
+
 /// Data represents the actual data payload flowing between Oxis in the pipeline.
 /// Uses JSON as the primary internal data format for structured data exchange.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,7 +41,10 @@ pub enum Data {
     Text(String),
 
     /// Binary data (files, images, etc.) - for binary operations
-    Binary(Vec<u8>),
+    ///
+    /// Reference counted, so cloning the payload between steps does not copy
+    /// the bytes.
+    Binary(Arc<Vec<u8>>),
 
     /// Empty data (used for initialization)
     Empty,
@@ -49,7 +68,7 @@ impl Data {
 
     /// Create a new Data from binary data
     pub fn from_binary(data: Vec<u8>) -> Self {
-        Data::Binary(data)
+        Data::Binary(Arc::new(data))
     }
 
     /// Check if this is empty data
@@ -81,6 +100,25 @@ impl Data {
         }
     }
 
+    /// One-line description for console output, e.g. `Binary data (12 bytes)`
+    ///
+    /// Text longer than `max_chars` characters is cut off, followed by its length.
+    pub fn summary(&self, max_chars: usize) -> String {
+        match self {
+            Data::Text(text) => match text.char_indices().nth(max_chars) {
+                Some((cut, _)) => format!(
+                    "Text data - {}... ({} characters)",
+                    &text[..cut],
+                    text.chars().count()
+                ),
+                None => format!("Text data - {text}"),
+            },
+            Data::Json(_) => "JSON data".to_string(),
+            Data::Binary(data) => format!("Binary data ({} bytes)", data.len()),
+            Data::Empty => "Empty data".to_string(),
+        }
+    }
+
     /// Get the type of data for error messages
     pub fn data_type(&self) -> &'static str {
         match self {
@@ -91,30 +129,75 @@ impl Data {
         }
     }
 
-    /// Convert to text representation
+    /// SHA-256 of the payload, hex encoded, independent of formatting
+    ///
+    /// JSON is hashed with object keys sorted and no whitespace, and floats
+    /// with an integral value hash like the integer (`1.0` like `1`). The
+    /// data type is part of the hash, so the text `"1"` and the number `1` differ.
+    pub fn compute_hash_stable(&self) -> String {
+        let mut hasher = Sha256::new();
+        match self {
+            Data::Json(value) => {
+                hasher.update(b"json:");
+                hasher.update(canonical_json(value).as_bytes());
+            }
+            Data::Text(text) => {
+                hasher.update(b"text:");
+                hasher.update(text.as_bytes());
+            }
+            Data::Binary(bytes) => {
+                hasher.update(b"binary:");
+                hasher.update(bytes.as_slice());
+            }
+            Data::Empty => hasher.update(b"empty"),
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Convert to text representation, with JSON pretty-printed
     pub fn to_text(&self) -> anyhow::Result<String> {
+        self.to_text_with(true)
+    }
+
+    /// Convert to text representation, with JSON pretty-printed or compact
+    ///
+    /// Compact JSON has no whitespace between tokens, which suits files and
+    /// machine consumers; pretty JSON is for people reading it.
+    pub fn to_text_with(&self, pretty: bool) -> anyhow::Result<String> {
         match self {
             Data::Text(text) => Ok(text.clone()),
-            Data::Json(json) => Ok(serde_json::to_string_pretty(json)?),
+            Data::Json(json) if pretty => Ok(serde_json::to_string_pretty(json)?),
+            Data::Json(json) => Ok(serde_json::to_string(json)?),
             Data::Binary(data) => {
                 // Convert binary to base64 string for text representation
                 use base64::Engine;
-                Ok(base64::engine::general_purpose::STANDARD.encode(data))
+                Ok(base64::engine::general_purpose::STANDARD.encode(data.as_slice()))
             }
             Data::Empty => Ok(String::new()),
         }
     }
 
     /// Convert to binary representation
+    ///
+    /// Always returns an owned copy; use [`Data::shared_binary`] to get binary
+    /// payloads without copying them.
     pub fn to_binary(&self) -> anyhow::Result<Vec<u8>> {
         match self {
             Data::Text(text) => Ok(text.as_bytes().to_vec()),
             Data::Json(json) => Ok(serde_json::to_string(json)?.as_bytes().to_vec()),
-            Data::Binary(data) => Ok(data.clone()),
+            Data::Binary(data) => Ok(data.as_ref().clone()),
             Data::Empty => Ok(Vec::new()),
         }
     }
 
+    /// Convert to binary, sharing rather than copying an existing binary payload
+    pub fn shared_binary(&self) -> anyhow::Result<Arc<Vec<u8>>> {
+        match self {
+            Data::Binary(data) => Ok(Arc::clone(data)),
+            _ => self.to_binary().map(Arc::new),
+        }
+    }
+
     /// Convert to JSON with fallback parsing
     pub fn to_json(&self) -> anyhow::Result<serde_json::Value> {
         match self {
@@ -156,7 +239,7 @@ impl Data {
         match self {
             Data::Json(value) => {
                 // Rough estimate: JSON string length * 2 for overhead
-                value.to_string().len() * 2
+                json_text_len(value) * 2
             }
             Data::Text(text) => text.len(),
             Data::Binary(bytes) => bytes.len(),
@@ -173,6 +256,82 @@ impl Data {
             Data::Empty => OxiDataType::Empty,
         }
     }
+
+    /// Format a single JSON object as one CSV row, without a line terminator
+    ///
+    /// Columns follow `headers`; missing fields and nulls become empty cells,
+    /// nested values are written as JSON. Cells are quoted per RFC 4180.
+    pub fn to_csv_record(&self, headers: &[String], delimiter: char) -> anyhow::Result<String> {
+        let Data::Json(serde_json::Value::Object(record)) = self else {
+            anyhow::bail!(
+                "CSV records require a JSON object, got {}",
+                self.data_type()
+            );
+        };
+
+        let cells: Vec<String> = headers
+            .iter()
+            .map(|header| {
+                let text = match record.get(header) {
+                    None | Some(serde_json::Value::Null) => String::new(),
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(other) => other.to_string(),
+                };
+                csv_escape(&text, delimiter)
+            })
+            .collect();
+        Ok(cells.join(&delimiter.to_string()))
+    }
+}
+
+/// Quote a CSV cell if it contains the delimiter, a quote or a line break
+///
+/// Embedded quotes are doubled, as RFC 4180 requires.
+pub fn csv_escape(value: &str, delimiter: char) -> String {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Length of `value` as compact JSON text, computed without serializing it
+///
+/// String escapes are not counted, so strings with quotes or control
+/// characters come out slightly short.
+fn json_text_len(value: &serde_json::Value) -> usize {
+    use serde_json::Value;
+
+    /// Counts formatted bytes instead of storing them
+    struct Counter(usize);
+    impl fmt::Write for Counter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+
+    match value {
+        Value::Null => 4,
+        Value::Bool(true) => 4,
+        Value::Bool(false) => 5,
+        Value::Number(number) => {
+            let mut counter = Counter(0);
+            let _ = fmt::Write::write_fmt(&mut counter, format_args!("{number}"));
+            counter.0
+        }
+        Value::String(text) => text.len() + 2,
+        Value::Array(items) => {
+            2 + items.len().saturating_sub(1) + items.iter().map(json_text_len).sum::<usize>()
+        }
+        Value::Object(fields) => {
+            2 + fields.len().saturating_sub(1)
+                + fields
+                    .iter()
+                    .map(|(key, value)| key.len() + 3 + json_text_len(value))
+                    .sum::<usize>()
+        }
+    }
 }
 
 impl fmt::Display for Data {
@@ -196,7 +355,7 @@ impl fmt::Display for Data {
 }
 
 /// Data types that can be processed by Oxis
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OxiDataType {
     Json,
     Text,
@@ -240,13 +399,435 @@ impl Default for ProcessingLimits {
     }
 }
 
+/// Share of `max_memory_mb` at which an input is reported as approaching the limit
+pub const MEMORY_WARNING_RATIO: f64 = 0.8;
+
+/// Step config keys read by [`ProcessingLimits::from_config`], accepted by every Oxi
+pub const LIMIT_CONFIG_KEYS: &[&str] = &[
+    "max_batch_size",
+    "max_memory_mb",
+    "max_processing_time_ms",
+    "supported_input_types",
+];
+
+impl ProcessingLimits {
+    /// Step-level limits from the [`LIMIT_CONFIG_KEYS`] of a step's config
+    ///
+    /// Missing, zero or malformed keys leave that limit unset, and no
+    /// `supported_input_types` means no restriction. The result is meant to
+    /// be passed to [`ProcessingLimits::tighten`], not used on its own.
+    pub fn from_config(config: &OxiConfig) -> ProcessingLimits {
+        let limit = |key: &str| {
+            config
+                .get_i64(key)
+                .ok()
+                .and_then(|value| u64::try_from(value).ok())
+                .filter(|value| *value > 0)
+        };
+        let supported_input_types = config
+            .get_sequence_or("supported_input_types")
+            .iter()
+            .filter_map(|value| match value.as_str()?.to_lowercase().as_str() {
+                "json" => Some(OxiDataType::Json),
+                "text" => Some(OxiDataType::Text),
+                "binary" => Some(OxiDataType::Binary),
+                "empty" => Some(OxiDataType::Empty),
+                _ => None,
+            })
+            .collect();
+
+        ProcessingLimits {
+            max_batch_size: limit("max_batch_size").map(|value| value as usize),
+            max_memory_mb: limit("max_memory_mb").map(|value| value as usize),
+            max_processing_time_ms: limit("max_processing_time_ms"),
+            supported_input_types,
+        }
+    }
+
+    /// These limits narrowed by `step`'s, taking the stricter of each
+    ///
+    /// A step can tighten an Oxi's limits but never loosen them: numeric
+    /// limits take the minimum and input types the intersection.
+    pub fn tighten(&self, step: &ProcessingLimits) -> ProcessingLimits {
+        fn min<T: Ord + Copy>(declared: Option<T>, step: Option<T>) -> Option<T> {
+            match (declared, step) {
+                (Some(declared), Some(step)) => Some(declared.min(step)),
+                (declared, step) => declared.or(step),
+            }
+        }
+
+        let supported_input_types = if step.supported_input_types.is_empty() {
+            self.supported_input_types.clone()
+        } else {
+            self.supported_input_types
+                .iter()
+                .filter(|t| step.supported_input_types.contains(t))
+                .cloned()
+                .collect()
+        };
+
+        ProcessingLimits {
+            max_batch_size: min(self.max_batch_size, step.max_batch_size),
+            max_memory_mb: min(self.max_memory_mb, step.max_memory_mb),
+            max_processing_time_ms: min(self.max_processing_time_ms, step.max_processing_time_ms),
+            supported_input_types,
+        }
+    }
+
+    /// Check the input of one `process` call against these limits
+    ///
+    /// Unless `full_batch` (the Oxi's `requires_full_batch`) is set, JSON
+    /// arrays over `max_batch_size` are split into chunks, so the batch limit
+    /// cannot be exceeded and memory is estimated for a single chunk.
+    pub fn check(&self, input: &OxiData, full_batch: bool) -> Vec<LimitViolation> {
+        let mut violations = Vec::new();
+
+        let input_type = input.data().get_data_type();
+        if !self.supported_input_types.contains(&input_type) {
+            violations.push(LimitViolation {
+                limit: "supported_input_types",
+                observed: input_type.to_string(),
+                allowed: self
+                    .supported_input_types
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                approaching: false,
+            });
+        }
+
+        let records = input.data().batch_size();
+        let mut records_per_call = records;
+        if let Some(max) = self.max_batch_size.filter(|max| *max > 0 && records > *max) {
+            if full_batch {
+                violations.push(LimitViolation {
+                    limit: "max_batch_size",
+                    observed: format!("{records} records"),
+                    allowed: format!("{max} records"),
+                    approaching: false,
+                });
+            } else {
+                records_per_call = max;
+            }
+        }
+
+        if let Some(max_mb) = self.max_memory_mb {
+            let bytes = input.estimated_memory_usage() as f64 * records_per_call as f64
+                / records.max(1) as f64;
+            let max_bytes = (max_mb * 1024 * 1024) as f64;
+            if bytes >= max_bytes * MEMORY_WARNING_RATIO {
+                violations.push(LimitViolation {
+                    limit: "max_memory_mb",
+                    observed: format!("{:.1}MB", bytes / (1024.0 * 1024.0)),
+                    allowed: format!("{max_mb}MB"),
+                    approaching: bytes < max_bytes,
+                });
+            }
+        }
+
+        violations
+    }
+}
+
+/// An input that breaks, or is close to breaking, one of an Oxi's [`ProcessingLimits`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitViolation {
+    /// Name of the `ProcessingLimits` field
+    pub limit: &'static str,
+    pub observed: String,
+    pub allowed: String,
+    /// Past the warning threshold but still within the limit
+    pub approaching: bool,
+}
+
+impl LimitViolation {
+    /// What the pipeline author can do about it
+    pub fn hint(&self) -> &'static str {
+        match self.limit {
+            "supported_input_types" => "convert the data with a parse or format step first",
+            "max_batch_size" => {
+                "split the input with a batch step first, or use an Oxi that processes chunks"
+            }
+            _ => "reduce the input with a batch or json_select step first",
+        }
+    }
+
+    pub fn into_error(self, oxi_name: &str) -> crate::error::OxiError {
+        let hint = self.hint().to_string();
+        crate::error::OxiError::LimitExceeded {
+            oxi_name: oxi_name.to_string(),
+            limit: self.limit.to_string(),
+            observed: self.observed,
+            allowed: self.allowed,
+            hint,
+        }
+    }
+}
+
+/// Machine-readable documentation for an Oxi, produced by `Oxi::describe`
+#[derive(Debug, Clone, Serialize)]
+pub struct OxiDescriptor {
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    pub description: String,
+    pub config_schema: OxiConfigSchema,
+    pub input_types: Vec<OxiDataType>,
+    pub output_types: Vec<OxiDataType>,
+    pub schema_strategy: SchemaStrategy,
+    pub example_config: OxiConfig,
+}
+
+impl OxiDescriptor {
+    /// Build a descriptor from the Oxi's declared schema, limits and types
+    pub fn from_oxi<O: crate::Oxi + ?Sized>(oxi: &O) -> Self {
+        let raw_schema = oxi.config_schema();
+        let config_schema = OxiConfigSchema::from_yaml_schema(&raw_schema);
+        let schema_strategy = oxi.schema_strategy();
+
+        let description = match (&config_schema.description, &schema_strategy) {
+            (Some(description), _) => description.clone(),
+            (None, SchemaStrategy::Modify { description }) => description.clone(),
+            _ => String::new(),
+        };
+
+        OxiDescriptor {
+            name: oxi.name().to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            author: "Oxide Flow".to_string(),
+            description,
+            example_config: OxiConfig::example_for(&config_schema),
+            config_schema,
+            input_types: oxi.processing_limits().supported_input_types,
+            output_types: oxi.output_types(),
+            schema_strategy,
+        }
+    }
+
+    /// Render a human-readable summary card
+    pub fn format_card(&self) -> String {
+        let mut output = self.format_header();
+
+        output.push_str("\n⚙️  Configuration:\n");
+        if self.config_schema.properties.is_empty() {
+            output.push_str("   (no options)\n");
+        } else {
+            let mut keys: Vec<&String> = self.config_schema.properties.keys().collect();
+            keys.sort();
+            for key in keys {
+                let property = &self.config_schema.properties[key];
+                let required = if self.config_schema.required.contains(key) {
+                    " (required)"
+                } else {
+                    ""
+                };
+                output.push_str(&format!("   {key}: {}{required}\n", property.property_type));
+                if let Some(description) = &property.description {
+                    output.push_str(&format!("      {description}\n"));
+                }
+                if let Some(default) = &property.default {
+                    output.push_str(&format!("      default: {}\n", inline_yaml(default)));
+                }
+            }
+        }
+
+        output.push_str(&self.format_example());
+        output
+    }
+
+    /// Render the full configuration reference, one table row per property
+    ///
+    /// Required properties come first, then optional ones, each alphabetically.
+    pub fn format_reference(&self) -> String {
+        let mut output = self.format_header();
+
+        output.push_str("\n⚙️  Configuration:\n");
+        if self.config_schema.properties.is_empty() {
+            output.push_str("   (no options)\n");
+        } else {
+            let mut keys: Vec<&String> = self.config_schema.properties.keys().collect();
+            keys.sort_by_key(|key| (!self.config_schema.required.contains(key), *key));
+            output.push_str(&format!(
+                "   {:<20} {:<8} {:<9} {:<14} {}\n",
+                "Property", "Type", "Required", "Default", "Description"
+            ));
+            for key in keys {
+                let property = &self.config_schema.properties[key];
+                let required = if self.config_schema.required.contains(key) {
+                    "yes"
+                } else {
+                    "no"
+                };
+                let default = property
+                    .default
+                    .as_ref()
+                    .map(inline_yaml)
+                    .unwrap_or_else(|| "-".to_string());
+                output.push_str(&format!(
+                    "   {key:<20} {:<8} {required:<9} {default:<14} {}\n",
+                    property.property_type,
+                    property.description.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+
+        output.push_str(&self.format_example());
+        output
+    }
+
+    fn format_header(&self) -> String {
+        let join_types = |types: &[OxiDataType]| {
+            types
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut output = format!("🧩 Oxi: {} (v{})\n", self.name, self.version);
+        if !self.description.is_empty() {
+            output.push_str(&format!("   {}\n", self.description));
+        }
+        output.push_str(&format!("   Author: {}\n", self.author));
+        output.push_str(&format!("   Input: {}\n", join_types(&self.input_types)));
+        output.push_str(&format!("   Output: {}\n", join_types(&self.output_types)));
+        output.push_str(&format!(
+            "   Schema strategy: {}\n",
+            self.schema_strategy.label()
+        ));
+        output
+    }
+
+    fn format_example(&self) -> String {
+        let mut output = "\n📄 Example:\n".to_string();
+        output.push_str(&format!("- name: {}\n", self.name));
+        if !self.example_config.values.is_empty() {
+            let sorted: std::collections::BTreeMap<_, _> =
+                self.example_config.values.iter().collect();
+            output.push_str("  config:\n");
+            for (key, value) in sorted {
+                output.push_str(&format!("    {key}: {}\n", inline_yaml(value)));
+            }
+        }
+        output
+    }
+}
+
+/// Render a YAML value on a single line (JSON flow syntax is valid YAML)
+fn inline_yaml(value: &serde_yaml::Value) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "~".to_string())
+}
+
 /// Configuration for an Oxi
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct OxiConfig {
     /// Key-value pairs for configuration
     pub values: HashMap<String, serde_yaml::Value>,
 }
 
+/// Debug output masks `!secret` references and `!encrypted` values so logs reveal neither
+impl fmt::Debug for OxiConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let masked: std::collections::BTreeMap<&String, serde_yaml::Value> = self
+            .values
+            .iter()
+            .map(|(key, value)| (key, mask_secrets(value)))
+            .collect();
+        f.debug_struct("OxiConfig")
+            .field("values", &masked)
+            .finish()
+    }
+}
+
+/// Replace secret references anywhere in a value with the mask placeholder
+fn mask_secrets(value: &serde_yaml::Value) -> serde_yaml::Value {
+    match value {
+        serde_yaml::Value::Tagged(tagged)
+            if tagged.tag == crate::secrets::SECRET_TAG
+                || tagged.tag == crate::secrets::ENCRYPTED_TAG =>
+        {
+            serde_yaml::Value::String(crate::secrets::SECRET_MASK.to_string())
+        }
+        serde_yaml::Value::Mapping(map) => serde_yaml::Value::Mapping(
+            map.iter()
+                .map(|(k, v)| (k.clone(), mask_secrets(v)))
+                .collect(),
+        ),
+        serde_yaml::Value::Sequence(seq) => {
+            serde_yaml::Value::Sequence(seq.iter().map(mask_secrets).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// A level of Oxi config, from lowest to highest precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigLevel {
+    /// The project's `defaults` for the Oxi
+    GlobalDefaults,
+    /// The pipeline's defaults for the Oxi
+    PipelineDefaults,
+    /// The step's own `config`
+    Step,
+}
+
+impl fmt::Display for ConfigLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigLevel::GlobalDefaults => write!(f, "global defaults"),
+            ConfigLevel::PipelineDefaults => write!(f, "pipeline defaults"),
+            ConfigLevel::Step => write!(f, "step config"),
+        }
+    }
+}
+
+/// Where a merged config value came from, see [`OxiConfig::precedence_trace`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrecedenceTrace {
+    /// The traced key or dotted path
+    pub key: String,
+    /// Level whose value was used; `None` when the merged config lacks the key
+    pub source: Option<ConfigLevel>,
+    /// Value of the key in the merged config
+    pub value: Option<serde_yaml::Value>,
+    /// Each level that sets the key with its value there, lowest precedence first
+    pub levels: Vec<(ConfigLevel, serde_yaml::Value)>,
+}
+
+/// Fluent builder for [`OxiConfig`]
+///
+/// ```
+/// use oxide_flow::types::OxiConfig;
+///
+/// let config = OxiConfig::builder()
+///     .set("path", "data/input.json")
+///     .set("create_dirs", true)
+///     .build();
+/// assert_eq!(config.get_string("path").unwrap(), "data/input.json");
+/// ```
+#[derive(Debug, Default)]
+pub struct OxiConfigBuilder {
+    config: OxiConfig,
+}
+
+impl OxiConfigBuilder {
+    /// Set a top-level key
+    ///
+    /// Panics if `value` cannot be represented as YAML.
+    pub fn set<T: Serialize>(mut self, key: &str, value: T) -> Self {
+        self.config
+            .set(key, value)
+            .unwrap_or_else(|e| panic!("Cannot set config key '{key}': {e}"));
+        self
+    }
+
+    pub fn build(self) -> OxiConfig {
+        self.config
+    }
+}
+
 impl OxiConfig {
     /// Create a new OxiConfig from a YAML value
     pub fn from_yaml(value: serde_yaml::Value) -> Self {
@@ -388,6 +969,176 @@ impl OxiConfig {
         }
     }
 
+    /// Resolve a `!secret`-tagged value from its provider
+    ///
+    /// The plaintext is fetched on every call and never cached in the config.
+    /// `!encrypted` values are decrypted with the key from
+    /// `OXIDE_FLOW_ENCRYPTION_KEY`.
+    pub fn get_secret(&self, key: &str) -> anyhow::Result<String> {
+        let value = self
+            .values
+            .get(key)
+            .ok_or_else(|| anyhow::anyhow!("Configuration key '{}' not found", key))?;
+        if matches!(value, serde_yaml::Value::Tagged(tagged) if tagged.tag == crate::secrets::ENCRYPTED_TAG)
+        {
+            return self.get_secret_decrypted(key, &crate::secrets::encryption_key_from_env()?);
+        }
+        crate::secrets::SecretRef::from_yaml(value)
+            .ok_or_else(|| anyhow::anyhow!("Value for key '{}' is not a !secret reference", key))??
+            .resolve()
+            .map_err(|e| e.context(format!("Failed to resolve secret '{key}'")))
+    }
+
+    /// Store `plaintext` encrypted with AES-256-GCM as an `!encrypted` value
+    ///
+    /// Only the ciphertext is kept, so the config can be saved to a file
+    /// without exposing the secret.
+    pub fn set_secret(
+        &mut self,
+        key: &str,
+        plaintext: &str,
+        encryption_key: &[u8; 32],
+    ) -> anyhow::Result<()> {
+        let sealed = crate::secrets::encrypt_secret(plaintext, encryption_key)?;
+        self.values.insert(
+            key.to_string(),
+            serde_yaml::Value::Tagged(Box::new(serde_yaml::value::TaggedValue {
+                tag: serde_yaml::value::Tag::new(crate::secrets::ENCRYPTED_TAG),
+                value: serde_yaml::Value::String(sealed),
+            })),
+        );
+        Ok(())
+    }
+
+    /// Decrypt an `!encrypted` value written by [`OxiConfig::set_secret`]
+    pub fn get_secret_decrypted(
+        &self,
+        key: &str,
+        encryption_key: &[u8; 32],
+    ) -> anyhow::Result<String> {
+        match self.values.get(key) {
+            Some(serde_yaml::Value::Tagged(tagged))
+                if tagged.tag == crate::secrets::ENCRYPTED_TAG =>
+            {
+                let sealed = tagged.value.as_str().ok_or_else(|| {
+                    anyhow::anyhow!("!encrypted value for key '{key}' must be a string")
+                })?;
+                crate::secrets::decrypt_secret(sealed, encryption_key)
+                    .map_err(|e| e.context(format!("Failed to decrypt secret '{key}'")))
+            }
+            Some(_) => anyhow::bail!("Value for key '{}' is not an !encrypted secret", key),
+            None => anyhow::bail!("Configuration key '{}' not found", key),
+        }
+    }
+
+    /// Deserialize the whole configuration into a typed struct
+    ///
+    /// Lets an Oxi declare its options as a serde struct (with `#[serde(default)]`
+    /// for optional keys) instead of reading them one by one. Unknown keys are
+    /// ignored unless the struct opts into `deny_unknown_fields`.
+    pub fn deserialize_into<T: DeserializeOwned>(&self) -> Result<T, ConfigError> {
+        let mapping: serde_yaml::Mapping = self
+            .values
+            .iter()
+            .map(|(key, value)| (serde_yaml::Value::String(key.clone()), value.clone()))
+            .collect();
+        // Round-tripping through text makes serde_yaml report the offending key path
+        let yaml = serde_yaml::to_string(&mapping)?;
+        serde_yaml::from_str(&yaml)
+            .map_err(|e| ConfigError::ValidationError(format!("Invalid configuration: {e}")))
+    }
+
+    /// Look up a value by dotted path (`source.auth.user`)
+    ///
+    /// A top-level key containing dots wins over nested lookup. Numeric
+    /// segments index into sequences (`targets.0.url`).
+    pub fn get_path(&self, path: &str) -> Option<&serde_yaml::Value> {
+        if let Some(value) = self.values.get(path) {
+            return Some(value);
+        }
+
+        let mut segments = path.split('.');
+        let mut current = self.values.get(segments.next()?)?;
+        for segment in segments {
+            current = match current {
+                serde_yaml::Value::Mapping(map) => map.get(segment)?,
+                serde_yaml::Value::Sequence(seq) => seq.get(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    /// Get a duration written as `"500ms"`, `"30s"`, `"5m"`, `"1h"` or `"2d"`
+    ///
+    /// Bare numbers are read as seconds, matching the project config.
+    pub fn get_duration(&self, key: &str) -> Result<Duration, ConfigError> {
+        let value = self
+            .get_path(key)
+            .ok_or_else(|| ConfigError::MissingField(key.to_string()))?;
+        let text = match value {
+            serde_yaml::Value::String(s) => s.clone(),
+            serde_yaml::Value::Number(n) => n.to_string(),
+            _ => String::new(),
+        };
+        crate::project::parse_duration(&text)
+            .map(Duration::from_millis)
+            .ok_or_else(|| {
+                ConfigError::ValidationError(format!(
+                    "Property '{key}' must be a duration such as \"500ms\", \"30s\" or \"5m\", got {}",
+                    inline_yaml(value)
+                ))
+            })
+    }
+
+    /// Parse a string value into `T`, listing `allowed` in the error message
+    pub fn get_enum<T: FromStr>(&self, key: &str, allowed: &[&str]) -> Result<T, ConfigError> {
+        let value = self
+            .get_path(key)
+            .ok_or_else(|| ConfigError::MissingField(key.to_string()))?;
+        value
+            .as_str()
+            .and_then(|s| s.parse::<T>().ok())
+            .ok_or_else(|| {
+                ConfigError::ValidationError(format!(
+                    "Property '{key}' must be one of: {}, got {}",
+                    allowed.join(", "),
+                    inline_yaml(value)
+                ))
+            })
+    }
+
+    /// Start building a configuration in code, mainly for tests
+    pub fn builder() -> OxiConfigBuilder {
+        OxiConfigBuilder::default()
+    }
+
+    /// Parse a configuration from YAML text, panicking if it is invalid
+    #[cfg(test)]
+    pub(crate) fn from_yaml_str(yaml: &str) -> Self {
+        Self::from_yaml(serde_yaml::from_str(yaml).unwrap())
+    }
+
+    /// Deterministic hash of the resolved configuration
+    ///
+    /// Mapping keys are sorted recursively before the YAML is hashed, so key
+    /// order in the pipeline file does not change the result.
+    pub fn config_hash(&self) -> String {
+        let mut keys: Vec<&String> = self.values.keys().collect();
+        keys.sort();
+        let canonical: serde_yaml::Mapping = keys
+            .into_iter()
+            .map(|k| {
+                (
+                    serde_yaml::Value::String(k.clone()),
+                    canonical_yaml(&self.values[k]),
+                )
+            })
+            .collect();
+        let yaml = serde_yaml::to_string(&canonical).unwrap_or_default();
+        format!("{:x}", md5::compute(yaml.as_bytes()))
+    }
+
     /// Set a configuration value
     pub fn set<T: Serialize>(&mut self, key: &str, value: T) -> anyhow::Result<()> {
         let yaml_value = serde_yaml::to_value(value)?;
@@ -395,6 +1146,121 @@ impl OxiConfig {
         Ok(())
     }
 
+    /// Whether a top-level key is present
+    pub fn has_key(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// All top-level keys, sorted
+    pub fn list_all_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.values.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Dotted paths of every leaf value under `prefix`, sorted
+    ///
+    /// Nested mappings are expanded (`source.auth.user`); sequences and
+    /// scalars are leaves. An empty prefix lists the whole config, otherwise
+    /// only `prefix` itself and paths below it (`prefix.*`) are returned.
+    pub fn list_nested_keys(&self, prefix: &str) -> Vec<String> {
+        fn collect(path: String, value: &serde_yaml::Value, out: &mut Vec<String>) {
+            match value {
+                serde_yaml::Value::Mapping(map) if !map.is_empty() => {
+                    for (key, child) in map {
+                        let key = match key {
+                            serde_yaml::Value::String(s) => s.clone(),
+                            other => serde_yaml::to_string(other)
+                                .unwrap_or_default()
+                                .trim_end()
+                                .to_string(),
+                        };
+                        collect(format!("{path}.{key}"), child, out);
+                    }
+                }
+                _ => out.push(path),
+            }
+        }
+
+        let mut keys = Vec::new();
+        for (key, value) in &self.values {
+            collect(key.clone(), value, &mut keys);
+        }
+        if !prefix.is_empty() {
+            let nested = format!("{prefix}.");
+            keys.retain(|k| k == prefix || k.starts_with(&nested));
+        }
+        keys.sort();
+        keys
+    }
+
+    /// Remove a top-level key, returning its value
+    pub fn remove(&mut self, key: &str) -> Option<serde_yaml::Value> {
+        self.values.remove(key)
+    }
+
+    /// Keep only the top-level entries for which `f` returns true
+    pub fn retain<F: Fn(&str, &serde_yaml::Value) -> bool>(&mut self, f: F) {
+        self.values.retain(|key, value| f(key, value));
+    }
+
+    /// Merge global Oxi defaults, pipeline Oxi defaults and step config, later levels winning
+    ///
+    /// A key set at a higher level replaces the lower one, except that nested
+    /// mappings are merged key by key. `Pipeline::apply_defaults` layers the
+    /// project's `defaults`, the pipeline's `defaults` and each step's config
+    /// this way.
+    pub fn merge_three(global: &OxiConfig, pipeline: &OxiConfig, step: &OxiConfig) -> OxiConfig {
+        global.clone().merged_with(pipeline).merged_with(step)
+    }
+
+    /// Explain which level of a [`OxiConfig::merge_three`] provided `key`
+    ///
+    /// `key` may be a dotted path, as in [`OxiConfig::get_path`]. Since nested
+    /// mappings combine, tracing `headers` names the highest level that set
+    /// any of it; trace `headers.auth` to see where one entry came from.
+    pub fn precedence_trace(
+        key: &str,
+        global: &OxiConfig,
+        pipeline: &OxiConfig,
+        step: &OxiConfig,
+    ) -> PrecedenceTrace {
+        let levels: Vec<(ConfigLevel, serde_yaml::Value)> = [
+            (ConfigLevel::GlobalDefaults, global),
+            (ConfigLevel::PipelineDefaults, pipeline),
+            (ConfigLevel::Step, step),
+        ]
+        .into_iter()
+        .filter_map(|(level, config)| Some((level, config.get_path(key)?.clone())))
+        .collect();
+        let value = Self::merge_three(global, pipeline, step)
+            .get_path(key)
+            .cloned();
+        // A higher level that replaced the whole parent mapping leaves no value
+        let source = value
+            .as_ref()
+            .and_then(|_| levels.last().map(|(level, _)| *level));
+
+        PrecedenceTrace {
+            key: key.to_string(),
+            source,
+            value,
+            levels,
+        }
+    }
+
+    /// This config with `overlay` merged over it
+    fn merged_with(mut self, overlay: &OxiConfig) -> OxiConfig {
+        for (key, value) in &overlay.values {
+            let merged = match self.values.get(key) {
+                Some(base) => crate::config::merge_yaml_values(base, value),
+                None => value.clone(),
+            };
+            self.values.insert(key.clone(), merged);
+        }
+        self
+    }
+
     /// Validate this configuration against a schema
     pub fn validate_against_schema(&self, schema: &OxiConfigSchema) -> Result<(), ConfigError> {
         // Check required fields
@@ -414,6 +1280,40 @@ impl OxiConfig {
         Ok(())
     }
 
+    /// Like `validate_against_schema`, also rejecting keys the schema does not declare
+    ///
+    /// The processing limit keys every step accepts ([`LIMIT_CONFIG_KEYS`])
+    /// are always allowed.
+    pub fn validate_strict(&self, schema: &OxiConfigSchema) -> Result<(), ConfigError> {
+        self.validate_against_schema(schema)?;
+
+        let unknown = self.unknown_keys(schema);
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        let mut known: Vec<&str> = schema.properties.keys().map(String::as_str).collect();
+        known.sort();
+        Err(ConfigError::ValidationError(format!(
+            "Unknown config keys: {} (expected: {})",
+            unknown.join(", "),
+            known.join(", ")
+        )))
+    }
+
+    /// Top-level keys `schema` does not declare, sorted
+    pub fn unknown_keys(&self, schema: &OxiConfigSchema) -> Vec<String> {
+        let mut unknown: Vec<String> = self
+            .values
+            .keys()
+            .filter(|key| {
+                !schema.properties.contains_key(*key) && !LIMIT_CONFIG_KEYS.contains(&key.as_str())
+            })
+            .cloned()
+            .collect();
+        unknown.sort();
+        unknown
+    }
+
     /// Validate a specific property value against its schema
     fn validate_property_value(
         key: &str,
@@ -460,6 +1360,8 @@ impl OxiConfig {
                     )));
                 }
             }
+            // Untyped properties accept any value
+            "any" => {}
             "object" => {
                 if !value.is_mapping() {
                     return Err(ConfigError::ValidationError(format!(
@@ -506,6 +1408,28 @@ impl OxiConfig {
         }
     }
 
+    /// Build an example configuration: schema defaults plus placeholders for required keys
+    pub fn example_for(schema: &OxiConfigSchema) -> Self {
+        let mut config = OxiConfig::default();
+        config.apply_defaults(schema);
+
+        for key in &schema.required {
+            if config.values.contains_key(key) {
+                continue;
+            }
+            let placeholder = match schema.properties.get(key).map(|p| p.property_type.as_str()) {
+                Some("number") | Some("integer") => serde_yaml::Value::Number(1.into()),
+                Some("boolean") => serde_yaml::Value::Bool(true),
+                Some("array") => serde_yaml::Value::Sequence(Vec::new()),
+                Some("object") => serde_yaml::Value::Mapping(serde_yaml::Mapping::new()),
+                _ => serde_yaml::Value::String(format!("<{key}>")),
+            };
+            config.values.insert(key.clone(), placeholder);
+        }
+
+        config
+    }
+
     /// Validate configuration against schema
     pub fn validate(
         &self,
@@ -529,6 +1453,37 @@ impl OxiConfig {
     }
 }
 
+/// Copy of `value` with every mapping's keys sorted, recursively
+///
+/// Used wherever YAML is hashed, so that key order does not matter.
+pub(crate) fn canonical_yaml(value: &serde_yaml::Value) -> serde_yaml::Value {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            let mut entries: Vec<_> = map
+                .iter()
+                .map(|(k, v)| (serde_yaml::to_string(k).unwrap_or_default(), k, v))
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            serde_yaml::Value::Mapping(
+                entries
+                    .into_iter()
+                    .map(|(_, k, v)| (k.clone(), canonical_yaml(v)))
+                    .collect(),
+            )
+        }
+        serde_yaml::Value::Sequence(seq) => {
+            serde_yaml::Value::Sequence(seq.iter().map(canonical_yaml).collect())
+        }
+        serde_yaml::Value::Tagged(tagged) => {
+            serde_yaml::Value::Tagged(Box::new(serde_yaml::value::TaggedValue {
+                tag: tagged.tag.clone(),
+                value: canonical_yaml(&tagged.value),
+            }))
+        }
+        other => other.clone(),
+    }
+}
+
 /// Validate a property value against its schema
 fn validate_property(
     key: &str,
@@ -645,14 +1600,62 @@ impl OxiSchema {
         self.fields.insert(name, field);
     }
 
-    /// Infer schema from data
+    /// Field names in column order (sorted, since fields are stored unordered)
+    pub fn field_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.fields.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// CSV header row for `field_names`, without a line terminator
+    pub fn csv_header(&self, delimiter: char) -> String {
+        self.field_names()
+            .iter()
+            .map(|name| csv_escape(name, delimiter))
+            .collect::<Vec<_>>()
+            .join(&delimiter.to_string())
+    }
+
+    /// Infer schema from data, sampling up to `DEFAULT_SAMPLE_SIZE` array elements
     pub fn infer_from_data(data: &Data) -> Result<Self, crate::error::OxiError> {
+        Self::infer_from_data_with(data, InferenceOptions::default())
+    }
+
+    /// Infer schema from data, merging the schemas of up to `sample_size`
+    /// elements of every array
+    ///
+    /// Fields missing from some sampled records are nullable, conflicting
+    /// types become `Mixed` (integers and floats widen to `Float`), and nested
+    /// objects and arrays are inferred recursively.
+    pub fn infer_from_data_sampled(
+        data: &Data,
+        sample_size: usize,
+    ) -> Result<Self, crate::error::OxiError> {
+        Self::infer_from_data_with(
+            data,
+            InferenceOptions {
+                sample_size,
+                ..InferenceOptions::default()
+            },
+        )
+    }
+
+    /// Infer schema from data with explicit inference settings
+    pub fn infer_from_data_with(
+        data: &Data,
+        options: InferenceOptions,
+    ) -> Result<Self, crate::error::OxiError> {
         let mut schema = Self::empty();
         schema.metadata.created_by = "oxide_flow_schema_inference".to_string();
+        let options = InferenceOptions {
+            sample_size: options.sample_size.max(1),
+            ..options
+        };
 
         match data {
             Data::Json(json_value) => {
-                schema.infer_from_json_value(json_value, "root")?;
+                schema.infer_from_json_value(json_value, &options)?;
+                default_null_types(&mut schema.fields);
             }
             Data::Text(_) => {
                 // Text data gets a simple "value" field schema
@@ -665,6 +1668,7 @@ impl OxiSchema {
                         constraints: vec![],
                         description: Some("Text content".to_string()),
                         examples: vec![],
+                        datetime_formats: None,
                     },
                 );
             }
@@ -679,6 +1683,7 @@ impl OxiSchema {
                         constraints: vec![],
                         description: Some("Binary content".to_string()),
                         examples: vec![],
+                        datetime_formats: None,
                     },
                 );
             }
@@ -690,74 +1695,156 @@ impl OxiSchema {
         Ok(schema)
     }
 
-    fn infer_from_json_value(
-        &mut self,
-        value: &serde_json::Value,
-        _path: &str,
-    ) -> Result<(), crate::error::OxiError> {
-        match value {
-            serde_json::Value::Object(obj) => {
-                for (key, val) in obj {
-                    let field_type = match val {
-                        serde_json::Value::String(_) => FieldType::String,
-                        serde_json::Value::Number(n) => {
-                            if n.is_i64() {
-                                FieldType::Integer
-                            } else {
-                                FieldType::Float
-                            }
-                        }
-                        serde_json::Value::Bool(_) => FieldType::Boolean,
-                        serde_json::Value::Array(_) => {
-                            FieldType::Array(Box::new(FieldType::Unknown))
-                        }
-                        serde_json::Value::Object(_) => FieldType::Object(HashMap::new()),
-                        serde_json::Value::Null => FieldType::String, // Default for null
-                    };
+    /// Build a schema from a draft-07 JSON Schema document
+    ///
+    /// The root may be an object schema, an array of object records, or a
+    /// `oneOf`/`anyOf`/`allOf` of object schemas; a primitive root becomes a
+    /// single `value` field. `type`, `format: date-time`, `minimum`,
+    /// `maximum`, `minLength`, `maxLength`, `pattern`, `enum`, `const` and
+    /// `required` are mapped; properties not listed in `required` are
+    /// nullable. `$ref`s must point into the same document.
+    pub fn infer_from_json_schema(schema: &serde_json::Value) -> anyhow::Result<Self> {
+        Self::from_json_schema(schema).map(|(schema, _)| schema)
+    }
 
-                    self.add_field(
-                        key.clone(),
-                        FieldSchema {
-                            field_type,
-                            nullable: val.is_null(),
-                            max_size: None,
-                            constraints: vec![],
-                            description: None,
-                            examples: vec![val.clone()],
-                        },
-                    );
-                }
-            }
-            serde_json::Value::Array(arr) => {
-                // For arrays, merge schemas from sample elements
-                if let Some(first_element) = arr.first() {
-                    self.infer_from_json_value(first_element, "array_element")?;
-                }
+    /// Inverse of `to_json_schema`, also accepting draft-07 documents
+    ///
+    /// Maps the same keywords as `infer_from_json_schema`, plus
+    /// `contentEncoding: base64`, `maxItems` and the `x-unique-values` and
+    /// `x-custom` extensions. Keywords with no `OxiSchema` equivalent are
+    /// ignored and reported in the returned warnings, one per occurrence,
+    /// prefixed with the field path. Structural problems such as an
+    /// unresolvable `$ref` are still errors.
+    pub fn from_json_schema(schema: &serde_json::Value) -> anyhow::Result<(Self, Vec<String>)> {
+        let mut importer = JsonSchemaImporter {
+            root: schema,
+            expanding: Vec::new(),
+            path: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let mut root = importer.deref(schema)?;
+        if let Some(items) = root.get("items") {
+            if json_schema_types(root).contains(&"array") {
+                importer.check_keywords(root, JSON_SCHEMA_FIELD_KEYWORDS);
+                importer.path.push("[]".to_string());
+                root = importer.deref(items)?;
             }
-            _ => {
-                // Single value gets a "value" field
-                let field_type = match value {
-                    serde_json::Value::String(_) => FieldType::String,
-                    serde_json::Value::Number(n) => {
-                        if n.is_i64() {
-                            FieldType::Integer
-                        } else {
-                            FieldType::Float
+        }
+
+        let mut result = Self::empty();
+        result.metadata.created_by = "oxide_flow_json_schema_import".to_string();
+
+        if is_object_schema(root) {
+            importer.check_keywords(root, JSON_SCHEMA_FIELD_KEYWORDS);
+            result.fields = importer.object_fields(root)?;
+        } else if json_schema_types(root).is_empty() {
+            anyhow::bail!("JSON Schema root has no type or properties to import");
+        } else {
+            result.add_field("value".to_string(), importer.field_schema(root, true)?);
+        }
+
+        Ok((result, importer.warnings))
+    }
+
+    /// Export as a JSON Schema (draft 2020-12) describing one record
+    ///
+    /// Non-nullable fields are `required`, nullable ones also admit `null`.
+    /// Constraints map onto the standard keywords and an array field's
+    /// `max_size` onto `maxItems`; `UniqueValues` and `Custom` rules, which
+    /// JSON Schema cannot express, use the `x-unique-values` and `x-custom`
+    /// extension keywords. `from_json_schema` reads all of these back.
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        let mut schema = serde_json::Map::new();
+        schema.insert(
+            "$schema".to_string(),
+            serde_json::Value::from("https://json-schema.org/draft/2020-12/schema"),
+        );
+        if let serde_json::Value::Object(record) = object_json_schema(&self.fields) {
+            schema.extend(record);
+        }
+        serde_json::Value::Object(schema)
+    }
+
+    /// Union of two schemas' fields, resolving type conflicts with `policy`
+    ///
+    /// A field present in only one schema becomes nullable; fields present in
+    /// both merge their types, nullability and examples, and keep constraints
+    /// only when both agree. The metadata comes from the newer schema, with
+    /// the row-count hints added together.
+    pub fn merge(&self, other: &OxiSchema, policy: MergePolicy) -> OxiSchema {
+        OxiSchema {
+            fields: merge_fields(&self.fields, &other.fields, policy),
+            metadata: self.metadata.merge(&other.metadata),
+        }
+    }
+
+    /// Canonical form of this schema for comparing schemas by structure
+    ///
+    /// Fields are sorted by name, and whatever depends on when or from which
+    /// records the schema was made is dropped: the metadata other than
+    /// `version`, and each field's `examples`, nested fields included.
+    pub fn normalize(&self) -> NormalizedSchema {
+        NormalizedSchema {
+            version: self.metadata.version.clone(),
+            fields: self
+                .fields
+                .iter()
+                .map(|(name, field)| (name.clone(), normalize_field(field)))
+                .collect(),
+        }
+    }
+
+    /// Whether two schemas describe the same fields, see [`OxiSchema::normalize`]
+    pub fn structurally_eq(&self, other: &OxiSchema) -> bool {
+        self.normalize() == other.normalize()
+    }
+
+    fn infer_from_json_value(
+        &mut self,
+        value: &serde_json::Value,
+        options: &InferenceOptions,
+    ) -> Result<(), crate::error::OxiError> {
+        match value {
+            serde_json::Value::Object(obj) => {
+                self.fields.extend(infer_object_fields(obj, options));
+            }
+            serde_json::Value::Array(arr) => {
+                // Records can differ, so merge the schemas of several elements
+                let mut merged: Option<OxiSchema> = None;
+                for element in arr.iter().take(options.sample_size) {
+                    let mut sample = OxiSchema::empty();
+                    sample.infer_from_json_value(element, options)?;
+                    merged = Some(match merged {
+                        Some(merged) => merged.merge(&sample, MergePolicy::WidenToMixed),
+                        None => sample,
+                    });
+                }
+                if let Some(mut merged) = merged {
+                    // Merging drops constraints the records disagree on, so
+                    // derive them from all sampled values at once
+                    if options.infer_constraints {
+                        let sampled = &arr[..arr.len().min(options.sample_size)];
+                        for (name, field) in merged.fields.iter_mut() {
+                            let values = sampled.iter().filter_map(|record| record.get(name));
+                            field.constraints = infer_field_constraints(field, values);
                         }
                     }
-                    serde_json::Value::Bool(_) => FieldType::Boolean,
-                    _ => FieldType::String,
-                };
-
+                    self.fields.extend(merged.fields);
+                }
+            }
+            _ => {
+                // Single value gets a "value" field
                 self.add_field(
                     "value".to_string(),
                     FieldSchema {
-                        field_type,
+                        field_type: infer_field_type(value, options),
                         nullable: value.is_null(),
                         max_size: None,
                         constraints: vec![],
                         description: Some("Inferred value field".to_string()),
                         examples: vec![value.clone()],
+                        datetime_formats: None,
                     },
                 );
             }
@@ -831,7 +1918,7 @@ impl OxiSchema {
                     let item_path = format!("{path}[{i}]");
                     self.validate_json_value(item, &item_path)?;
                 }
-                Ok(())
+                validate_unique_fields(&self.fields, arr, path)
             }
             _ => {
                 // Single value - check if schema has a "value" field
@@ -870,6 +1957,9 @@ pub struct FieldSchema {
     pub description: Option<String>,
     /// Examples of valid values
     pub examples: Vec<serde_json::Value>,
+    /// Extra strftime formats a `DateTime` value may be written in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datetime_formats: Option<Vec<String>>,
 }
 
 impl FieldSchema {
@@ -882,6 +1972,7 @@ impl FieldSchema {
             constraints: Vec::new(),
             description: None,
             examples: Vec::new(),
+            datetime_formats: None,
         }
     }
 
@@ -900,7 +1991,13 @@ impl FieldSchema {
             return Ok(());
         }
 
-        if !self.field_type.matches_value(value) {
+        let matches = match (&self.field_type, &self.datetime_formats) {
+            (FieldType::DateTime, Some(formats)) => value.as_str().is_some_and(|text| {
+                crate::datetime::parse_datetime_str(text.trim(), formats).is_some()
+            }),
+            (field_type, _) => field_type.matches_value(value),
+        };
+        if !matches {
             return Err(crate::error::OxiError::ValidationError {
                 details: format!(
                     "Field '{}' type mismatch: expected {:?}, got {}",
@@ -916,7 +2013,7 @@ impl FieldSchema {
             constraint.validate_value(value, path)?;
         }
 
-        Ok(())
+        validate_nested(&self.field_type, value, path)
     }
 
     fn value_type_name(&self, value: &serde_json::Value) -> &'static str {
@@ -957,6 +2054,23 @@ pub enum FieldType {
     Mixed,   // For fields that contain multiple types
 }
 
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldType::String => write!(f, "string"),
+            FieldType::Integer => write!(f, "integer"),
+            FieldType::Float => write!(f, "float"),
+            FieldType::Boolean => write!(f, "boolean"),
+            FieldType::DateTime => write!(f, "datetime"),
+            FieldType::Binary => write!(f, "binary"),
+            FieldType::Array(inner) => write!(f, "array<{inner}>"),
+            FieldType::Object(_) => write!(f, "object"),
+            FieldType::Unknown => write!(f, "unknown"),
+            FieldType::Mixed => write!(f, "mixed"),
+        }
+    }
+}
+
 impl FieldType {
     /// Check if a JSON value matches this field type
     pub fn matches_value(&self, value: &serde_json::Value) -> bool {
@@ -967,13 +2081,11 @@ impl FieldType {
             }
             FieldType::Float => value.is_number(),
             FieldType::Boolean => value.is_boolean(),
-            FieldType::DateTime => {
-                // Try to parse as ISO 8601 datetime
-                value.is_string()
-                    && value
-                        .as_str()
-                        .is_some_and(|s| chrono::DateTime::parse_from_rfc3339(s).is_ok())
-            }
+            // Timestamp strings only; a bare number is not assumed to be an
+            // epoch, `coerce_types` converts those explicitly
+            FieldType::DateTime => value.as_str().is_some_and(|text| {
+                crate::datetime::parse_datetime_str(text.trim(), &[]).is_some()
+            }),
             FieldType::Binary => {
                 // For JSON, binary data is typically base64 encoded strings
                 value.is_string()
@@ -1001,7 +2113,14 @@ pub enum FieldConstraint {
     OneOf(Vec<serde_json::Value>),
 
     // Custom validation
-    Custom { name: String, rule: String },
+    Custom {
+        name: String,
+        rule: String,
+    },
+
+    // Row-level constraints, checked across the records of an array
+    /// No two records share a (non-null) value for this field
+    UniqueValues,
 }
 
 impl FieldConstraint {
@@ -1068,9 +2187,12 @@ impl FieldConstraint {
             }
             FieldConstraint::Pattern(pattern) => {
                 if let Some(s) = value.as_str() {
-                    // For now, just check if pattern is a simple substring
-                    // In a full implementation, you'd use regex crate
-                    if !s.contains(pattern) {
+                    // Patterns that aren't valid regexes fall back to a substring check
+                    let matches = match cached_regex(pattern) {
+                        Some(regex) => regex.is_match(s),
+                        None => s.contains(pattern.as_str()),
+                    };
+                    if !matches {
                         return Err(crate::error::OxiError::ValidationError {
                             details: format!(
                                 "Field '{path}' value '{s}' does not match pattern '{pattern}'"
@@ -1092,8 +2214,105 @@ impl FieldConstraint {
                 // Custom validation would be implemented here
                 Ok(())
             }
+            // A single value is always unique; the enclosing array checks its rows
+            FieldConstraint::UniqueValues => Ok(()),
+        }
+    }
+}
+
+/// Duplicate values reported by a failed `UniqueValues` check
+const MAX_REPORTED_DUPLICATES: usize = 5;
+
+/// Check the `UniqueValues` constraints of `fields` across `rows`
+fn validate_unique_fields(
+    fields: &HashMap<String, FieldSchema>,
+    rows: &[serde_json::Value],
+    path: &str,
+) -> Result<(), crate::error::OxiError> {
+    let mut unique: Vec<&String> = fields
+        .iter()
+        .filter(|(_, field)| field.constraints.contains(&FieldConstraint::UniqueValues))
+        .map(|(name, _)| name)
+        .collect();
+    unique.sort();
+
+    for name in unique {
+        let field_path = if path == "root" {
+            name.clone()
+        } else {
+            format!("{path}.{name}")
+        };
+        check_unique_values(name, rows, &field_path)?;
+    }
+    Ok(())
+}
+
+/// Fail if two rows share a value for `field`; nulls and missing values are ignored
+fn check_unique_values(
+    field: &str,
+    rows: &[serde_json::Value],
+    path: &str,
+) -> Result<(), crate::error::OxiError> {
+    let mut indices: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut repeated: Vec<String> = Vec::new();
+
+    for (index, row) in rows.iter().enumerate() {
+        let Some(value) = row.get(field).filter(|v| !v.is_null()) else {
+            continue;
+        };
+        let key = value.to_string();
+        let seen = indices.entry(key.clone()).or_default();
+        seen.push(index);
+        if seen.len() == 2 {
+            repeated.push(key);
         }
     }
+
+    if repeated.is_empty() {
+        return Ok(());
+    }
+
+    let mut listed: Vec<String> = repeated
+        .iter()
+        .take(MAX_REPORTED_DUPLICATES)
+        .map(|key| {
+            let rows = &indices[key];
+            let mut shown: Vec<String> = rows
+                .iter()
+                .take(MAX_REPORTED_DUPLICATES)
+                .map(usize::to_string)
+                .collect();
+            if rows.len() > MAX_REPORTED_DUPLICATES {
+                shown.push("...".to_string());
+            }
+            format!("{key} at rows [{}]", shown.join(", "))
+        })
+        .collect();
+    if repeated.len() > MAX_REPORTED_DUPLICATES {
+        listed.push(format!(
+            "and {} more",
+            repeated.len() - MAX_REPORTED_DUPLICATES
+        ));
+    }
+
+    Err(crate::error::OxiError::ValidationError {
+        details: format!(
+            "Field '{path}' must be unique, found {} duplicated values: {}",
+            repeated.len(),
+            listed.join("; ")
+        ),
+    })
+}
+
+/// Compiled regex for a `FieldConstraint::Pattern`, compiled once per pattern
+fn cached_regex(pattern: &str) -> Option<regex::Regex> {
+    static CACHE: std::sync::OnceLock<std::sync::Mutex<HashMap<String, Option<regex::Regex>>>> =
+        std::sync::OnceLock::new();
+    let mut cache = CACHE.get_or_init(Default::default).lock().unwrap();
+    cache
+        .entry(pattern.to_string())
+        .or_insert_with(|| regex::Regex::new(pattern).ok())
+        .clone()
 }
 
 /// Schema metadata and hints
@@ -1116,6 +2335,135 @@ impl Default for SchemaMetadata {
     }
 }
 
+impl SchemaMetadata {
+    /// Metadata for the merge of two schemas: the newer one's, covering the rows of both
+    fn merge(&self, other: &SchemaMetadata) -> SchemaMetadata {
+        let newest = if other.created_at > self.created_at {
+            other
+        } else {
+            self
+        };
+        let row_count_hint = match (self.row_count_hint, other.row_count_hint) {
+            (Some(a), Some(b)) => Some(a.saturating_add(b)),
+            (a, b) => a.or(b),
+        };
+        SchemaMetadata {
+            row_count_hint,
+            ..newest.clone()
+        }
+    }
+}
+
+/// An [`OxiSchema`] reduced to its structure by [`OxiSchema::normalize`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NormalizedSchema {
+    pub version: String,
+    pub fields: std::collections::BTreeMap<String, FieldSchema>,
+}
+
+fn normalize_field(field: &FieldSchema) -> FieldSchema {
+    FieldSchema {
+        field_type: normalize_field_type(&field.field_type),
+        examples: Vec::new(),
+        ..field.clone()
+    }
+}
+
+fn normalize_field_type(field_type: &FieldType) -> FieldType {
+    match field_type {
+        FieldType::Array(items) => FieldType::Array(Box::new(normalize_field_type(items))),
+        FieldType::Object(fields) => FieldType::Object(
+            fields
+                .iter()
+                .map(|(name, field)| (name.clone(), normalize_field(field)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// How [`OxiSchema::merge`] resolves a field whose types conflict
+///
+/// Every policy keeps the known type over `Unknown`, recurses into objects and
+/// array items, and makes the field nullable if either side is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergePolicy {
+    /// Keep the type from the schema `merge` is called on
+    PreferLeft,
+    /// Keep the type from the other schema
+    PreferRight,
+    /// Widen compatible types (`Integer` and `Float` to `Float`, `DateTime`
+    /// and `String` to `String`) and anything else to `Mixed`
+    #[default]
+    WidenToMixed,
+}
+
+/// Payload encodings read by [`OxiData::from_reader`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    /// A single JSON document
+    Json,
+    /// One JSON document per line, read as an array; blank lines are skipped
+    Jsonl,
+    /// UTF-8 text
+    Text,
+    /// Raw bytes
+    Binary,
+}
+
+impl DataFormat {
+    /// Format implied by a file extension, or `None` for unknown extensions
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "json" => Some(DataFormat::Json),
+            "jsonl" | "ndjson" => Some(DataFormat::Jsonl),
+            "txt" | "csv" | "tsv" | "log" | "md" | "yaml" | "yml" | "xml" | "html" => {
+                Some(DataFormat::Text)
+            }
+            "bin" | "gz" | "zip" | "png" | "jpg" | "jpeg" | "gif" | "pdf" | "parquet" => {
+                Some(DataFormat::Binary)
+            }
+            _ => None,
+        }
+    }
+
+    /// Format named by a `format` config value, or `None` for unknown names
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "json" => Some(DataFormat::Json),
+            "jsonl" | "ndjson" => Some(DataFormat::Jsonl),
+            "text" => Some(DataFormat::Text),
+            "binary" => Some(DataFormat::Binary),
+            _ => None,
+        }
+    }
+
+    /// Format of a file's contents, from its extension or else its bytes
+    ///
+    /// Without a known extension, content that is not UTF-8 or contains NUL
+    /// bytes is binary, and text starting with `{` or `[` that parses as JSON
+    /// is JSON. Everything else is text.
+    pub fn detect(path: &std::path::Path, bytes: &[u8]) -> Self {
+        if let Some(format) = Self::from_path(path) {
+            return format;
+        }
+        let Ok(text) = std::str::from_utf8(bytes) else {
+            return DataFormat::Binary;
+        };
+        if text.contains('\0') {
+            return DataFormat::Binary;
+        }
+        let trimmed = text.trim_start();
+        if (trimmed.starts_with('{') || trimmed.starts_with('['))
+            && serde_json::from_str::<serde_json::Value>(text).is_ok()
+        {
+            return DataFormat::Json;
+        }
+        DataFormat::Text
+    }
+}
+
 /// OxiData represents unified schema-aware data flowing between Oxis in the pipeline.
 /// Every piece of data includes both the payload and its schema information.
 #[derive(Debug, Clone)]
@@ -1124,18 +2472,27 @@ pub struct OxiData {
     pub data: Data,
     /// Schema information (always present, may be inferred or empty)
     pub schema: OxiSchema,
+    /// Side-channel facts reported by Oxis (e.g. `source_path`, `rows_skipped`)
+    ///
+    /// The executor carries metadata from a step's input onto its output, so
+    /// later steps can read it as `${step_id.metadata.key}`.
+    pub metadata: HashMap<String, serde_json::Value>,
 }
 
 impl OxiData {
     /// Create new OxiData with inferred schema
     pub fn new(data: Data) -> Self {
         let schema = OxiSchema::infer_from_data(&data).unwrap_or_default();
-        Self { data, schema }
+        Self::with_schema(data, schema)
     }
 
     /// Create OxiData with explicit schema
     pub fn with_schema(data: Data, schema: OxiSchema) -> Self {
-        Self { data, schema }
+        Self {
+            data,
+            schema,
+            metadata: HashMap::new(),
+        }
     }
 
     /// Create empty OxiData
@@ -1155,7 +2512,78 @@ impl OxiData {
 
     /// Create from binary data
     pub fn from_binary(data: Vec<u8>) -> Self {
-        Self::new(Data::Binary(data))
+        Self::new(Data::from_binary(data))
+    }
+
+    /// Create from any serializable value, inferring the schema
+    pub fn from_serialize<T: Serialize>(value: &T) -> anyhow::Result<Self> {
+        Ok(Self::from_json(serde_json::to_value(value)?))
+    }
+
+    /// Read a whole payload in the given format
+    pub fn from_reader<R: std::io::Read>(
+        mut reader: R,
+        format: DataFormat,
+    ) -> anyhow::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        match format {
+            DataFormat::Json => Ok(Self::from_json(serde_json::from_slice(&bytes)?)),
+            DataFormat::Jsonl => {
+                let text = String::from_utf8(bytes)?;
+                let records = text
+                    .lines()
+                    .enumerate()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(index, line)| {
+                        serde_json::from_str(line).map_err(|e| {
+                            anyhow::anyhow!("Invalid JSON on line {}: {}", index + 1, e)
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                Ok(Self::from_json(serde_json::Value::Array(records)))
+            }
+            DataFormat::Text => Ok(Self::from_text(String::from_utf8(bytes)?)),
+            DataFormat::Binary => Ok(Self::from_binary(bytes)),
+        }
+    }
+
+    /// Read a file, choosing the format from its extension
+    ///
+    /// Files with an unknown extension are sniffed with
+    /// [`DataFormat::detect`]. The path is recorded as the `source_path`
+    /// metadata entry, as `read_file` does.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        use anyhow::Context;
+
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read file '{}'", path.display()))?;
+        let format = DataFormat::detect(path, &bytes);
+
+        let mut data = Self::from_reader(bytes.as_slice(), format)
+            .with_context(|| format!("Failed to parse '{}' as {:?}", path.display(), format))?;
+        data.set_meta("source_path", path.display().to_string());
+        Ok(data)
+    }
+
+    /// Deserialize the payload into a typed value, e.g. after a pipeline run
+    ///
+    /// Text is parsed as JSON, falling back to a JSON string so `String`
+    /// targets work. Empty data deserializes from `null`, which suits
+    /// `Option` targets. Binary data is rejected.
+    pub fn try_into_deserialize<T: DeserializeOwned>(self) -> anyhow::Result<T> {
+        let value = match self.data {
+            Data::Json(value) => value,
+            Data::Text(text) => {
+                serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text))
+            }
+            Data::Empty => serde_json::Value::Null,
+            Data::Binary(_) => anyhow::bail!("Cannot deserialize binary data"),
+        };
+        serde_json::from_value(value)
+            .map_err(|e| anyhow::anyhow!("Failed to deserialize data: {e}"))
     }
 
     /// Convenience method to access the data
@@ -1168,6 +2596,18 @@ impl OxiData {
         &self.schema
     }
 
+    /// Stable SHA-256 of the payload, for detecting duplicates
+    ///
+    /// With `fields`, only those top-level fields of a JSON object are hashed,
+    /// a missing field counting as `null`. Other payloads are always hashed whole.
+    /// See [`Data::compute_hash_stable`].
+    pub fn compute_hash(&self, fields: Option<&[&str]>) -> String {
+        match (&self.data, fields) {
+            (Data::Json(value), Some(fields)) => json_hash(value, Some(fields)),
+            (data, _) => data.compute_hash_stable(),
+        }
+    }
+
     /// Update the schema while keeping the same data
     pub fn with_updated_schema(mut self, new_schema: OxiSchema) -> Self {
         self.schema = new_schema;
@@ -1184,10 +2624,1257 @@ impl OxiData {
         self.data.estimated_memory_usage()
     }
 
+    /// A copy for another consumer, such as the next step and the step results
+    ///
+    /// Binary payloads are shared rather than copied; JSON and text are
+    /// still cloned.
+    pub fn share(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            schema: self.schema.clone(),
+            metadata: self.metadata.clone(),
+        }
+    }
+
     /// Extract just the data (for backward compatibility)
     pub fn into_data(self) -> Data {
         self.data
     }
+
+    /// Look up a metadata entry
+    pub fn get_meta(&self, key: &str) -> Option<&serde_json::Value> {
+        self.metadata.get(key)
+    }
+
+    /// Set a metadata entry, replacing any previous value
+    pub fn set_meta(&mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) {
+        self.metadata.insert(key.into(), value.into());
+    }
+
+    /// Copy every entry of `other` into this metadata; `other` wins on conflicts
+    pub fn merge_meta(&mut self, other: HashMap<String, serde_json::Value>) {
+        self.metadata.extend(other);
+    }
+
+    /// Flatten nested JSON objects into single-level maps
+    ///
+    /// Nested keys are joined with `separator` and array elements become
+    /// `key[0]`, `key[1]`, ... An array of records is flattened record by
+    /// record; elements that are not objects, such as the items of a scalar
+    /// array, are kept as they are. Non-JSON data is returned unchanged. The
+    /// schema is re-inferred so it lists the flattened field names.
+    pub fn flatten(&self, separator: &str) -> OxiData {
+        let Data::Json(value) = &self.data else {
+            return self.clone();
+        };
+
+        let flatten_record = |record: &serde_json::Value| {
+            if !record.is_object() {
+                return record.clone();
+            }
+            let mut flat = serde_json::Map::new();
+            flatten_into(record, "", separator, &mut flat);
+            serde_json::Value::Object(flat)
+        };
+        let flattened = match value {
+            serde_json::Value::Array(records) => {
+                serde_json::Value::Array(records.iter().map(flatten_record).collect())
+            }
+            other => flatten_record(other),
+        };
+
+        let mut result = OxiData::from_json(flattened);
+        result.metadata = self.metadata.clone();
+        result
+    }
+
+    /// Rebuild nested objects from keys produced by `flatten`
+    ///
+    /// Fails when two keys conflict, e.g. `a` holding a value (even `null`)
+    /// while `a.b` also exists. Keys that originally contained the separator
+    /// cannot be told apart from nesting and come back nested.
+    pub fn unflatten(&self, separator: &str) -> anyhow::Result<OxiData> {
+        let Data::Json(value) = &self.data else {
+            return Ok(self.clone());
+        };
+
+        let unflatten_record = |record: &serde_json::Value| -> anyhow::Result<serde_json::Value> {
+            let serde_json::Value::Object(flat) = record else {
+                return Ok(record.clone());
+            };
+            let mut nested = serde_json::Value::Object(serde_json::Map::new());
+            // Nulls go last, longest key first: until then a null in `nested` only
+            // marks a slot not yet filled, so an explicit null that meets a nested
+            // key is reported as a conflict instead of being merged into
+            let (mut nulls, values): (Vec<_>, Vec<_>) =
+                flat.iter().partition(|(_, val)| val.is_null());
+            nulls.sort_by_key(|(key, _)| std::cmp::Reverse(key.len()));
+            for (key, val) in values.into_iter().chain(nulls) {
+                insert_path(&mut nested, &parse_flat_key(key, separator)?, val.clone())
+                    .map_err(|e| anyhow::anyhow!("Cannot unflatten key '{key}': {e}"))?;
+            }
+            Ok(nested)
+        };
+        let unflattened = match value {
+            serde_json::Value::Array(records) => serde_json::Value::Array(
+                records
+                    .iter()
+                    .map(unflatten_record)
+                    .collect::<anyhow::Result<_>>()?,
+            ),
+            other => unflatten_record(other)?,
+        };
+
+        let mut result = OxiData::from_json(unflattened);
+        result.metadata = self.metadata.clone();
+        Ok(result)
+    }
+
+    /// Structural diff from `before` to `after`, matching records by position
+    pub fn diff(before: &OxiData, after: &OxiData) -> DataDiff {
+        Self::diff_by_key(before, after, None)
+    }
+
+    /// Structural diff from `before` to `after`
+    ///
+    /// JSON arrays are compared record by record, matched on the value of
+    /// the `key` field when given and by position otherwise. Field paths are
+    /// dotted and start with the record, e.g. `[id=7].customer.name` or
+    /// `[2].total`; a record found on one side only is listed whole. Nested
+    /// arrays compare as single values, and non-JSON payloads as `$`.
+    pub fn diff_by_key(before: &OxiData, after: &OxiData, key: Option<&str>) -> DataDiff {
+        let mut diff = DataDiff {
+            record_count_delta: diff_record_count(&after.data) - diff_record_count(&before.data),
+            ..DataDiff::default()
+        };
+
+        match (diff_value(&before.data), diff_value(&after.data)) {
+            (serde_json::Value::Array(before), serde_json::Value::Array(after)) => {
+                let label = |index: usize, record: &serde_json::Value| match key
+                    .and_then(|key| Some((key, record.get(key)?)))
+                {
+                    Some((key, serde_json::Value::String(value))) => format!("[{key}={value}]"),
+                    Some((key, value)) => format!("[{key}={value}]"),
+                    None => format!("[{index}]"),
+                };
+                let labels: Vec<String> = before
+                    .iter()
+                    .enumerate()
+                    .map(|(index, record)| label(index, record))
+                    .collect();
+                // Records sharing a label are matched in order
+                let mut by_label: HashMap<&str, std::collections::VecDeque<usize>> = HashMap::new();
+                for (index, label) in labels.iter().enumerate() {
+                    by_label.entry(label).or_default().push_back(index);
+                }
+                let mut matched = vec![false; before.len()];
+
+                for (index, record) in after.iter().enumerate() {
+                    let path = label(index, record);
+                    match by_label
+                        .get_mut(path.as_str())
+                        .and_then(|indexes| indexes.pop_front())
+                    {
+                        Some(found) => {
+                            matched[found] = true;
+                            diff_json(&path, &before[found], record, &mut diff);
+                        }
+                        None => diff.added_fields.push(path),
+                    }
+                }
+                diff.removed_fields.extend(
+                    labels
+                        .into_iter()
+                        .zip(matched)
+                        .filter(|(_, matched)| !matched)
+                        .map(|(label, _)| label),
+                );
+            }
+            (before, after) => diff_json("", &before, &after, &mut diff),
+        }
+        diff
+    }
+}
+
+/// Changes between two payloads, from [`OxiData::diff`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataDiff {
+    /// Paths present only in the later payload
+    pub added_fields: Vec<String>,
+    /// Paths present only in the earlier payload
+    pub removed_fields: Vec<String>,
+    /// Paths whose value changed, with the earlier and later value
+    pub modified_fields: Vec<(String, serde_json::Value, serde_json::Value)>,
+    /// Records in the later payload minus records in the earlier one
+    pub record_count_delta: i64,
+}
+
+impl DataDiff {
+    /// Whether the two payloads are the same
+    pub fn is_empty(&self) -> bool {
+        self.added_fields.is_empty()
+            && self.removed_fields.is_empty()
+            && self.modified_fields.is_empty()
+            && self.record_count_delta == 0
+    }
+
+    /// One line for logs, e.g. `1 added, 0 removed, 2 modified, +1 records`
+    pub fn summary_line(&self) -> String {
+        if self.is_empty() {
+            return "no changes".to_string();
+        }
+        format!(
+            "{} added, {} removed, {} modified, {:+} records",
+            self.added_fields.len(),
+            self.removed_fields.len(),
+            self.modified_fields.len(),
+            self.record_count_delta
+        )
+    }
+}
+
+/// Records a payload holds for `DataDiff::record_count_delta`
+fn diff_record_count(data: &Data) -> i64 {
+    match data {
+        Data::Json(serde_json::Value::Array(records)) => records.len() as i64,
+        Data::Empty => 0,
+        _ => 1,
+    }
+}
+
+/// A payload as the JSON value the diff compares; binary data by size and digest
+fn diff_value(data: &Data) -> serde_json::Value {
+    match data {
+        Data::Json(value) => value.clone(),
+        Data::Text(text) => serde_json::Value::String(text.clone()),
+        Data::Binary(bytes) => serde_json::Value::String(format!(
+            "<{} bytes, md5 {:x}>",
+            bytes.len(),
+            md5::compute(bytes.as_slice())
+        )),
+        Data::Empty => serde_json::Value::Null,
+    }
+}
+
+/// Add the differences between two values at `path` to `diff`, descending into objects
+fn diff_json(
+    path: &str,
+    before: &serde_json::Value,
+    after: &serde_json::Value,
+    diff: &mut DataDiff,
+) {
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+
+    match (before, after) {
+        (serde_json::Value::Object(before), serde_json::Value::Object(after)) => {
+            for (key, value) in before {
+                match after.get(key) {
+                    Some(new_value) => diff_json(&child(key), value, new_value, diff),
+                    None => diff.removed_fields.push(child(key)),
+                }
+            }
+            diff.added_fields.extend(
+                after
+                    .keys()
+                    .filter(|key| !before.contains_key(*key))
+                    .map(|key| child(key)),
+            );
+        }
+        (before, after) if before != after => {
+            let path = if path.is_empty() { "$" } else { path };
+            diff.modified_fields
+                .push((path.to_string(), before.clone(), after.clone()));
+        }
+        _ => {}
+    }
+}
+
+/// Check the fields of nested objects and the items of arrays against their declared types
+fn validate_nested(
+    field_type: &FieldType,
+    value: &serde_json::Value,
+    path: &str,
+) -> Result<(), crate::error::OxiError> {
+    match (field_type, value) {
+        (FieldType::Object(fields), serde_json::Value::Object(obj)) => {
+            for (name, field) in fields {
+                let field_path = format!("{path}.{name}");
+                match obj.get(name) {
+                    Some(field_value) => field.validate_value(field_value, &field_path)?,
+                    None if !field.nullable => {
+                        return Err(crate::error::OxiError::ValidationError {
+                            details: format!("Required field '{field_path}' is missing"),
+                        });
+                    }
+                    None => {}
+                }
+            }
+            Ok(())
+        }
+        (FieldType::Array(item_type), serde_json::Value::Array(items)) => {
+            for (i, item) in items.iter().enumerate() {
+                let item_path = format!("{path}[{i}]");
+                if !item.is_null() && !item_type.matches_value(item) {
+                    return Err(crate::error::OxiError::ValidationError {
+                        details: format!(
+                            "Field '{item_path}' type mismatch: expected {item_type:?}"
+                        ),
+                    });
+                }
+                validate_nested(item_type, item, &item_path)?;
+            }
+            match item_type.as_ref() {
+                FieldType::Object(fields) => validate_unique_fields(fields, items, path),
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// JSON Schema for an object with the given fields
+fn object_json_schema(fields: &HashMap<String, FieldSchema>) -> serde_json::Value {
+    let properties: serde_json::Map<String, serde_json::Value> = fields
+        .iter()
+        .map(|(name, field)| (name.clone(), field_json_schema(field)))
+        .collect();
+    let mut required: Vec<&String> = fields
+        .iter()
+        .filter(|(_, field)| !field.nullable)
+        .map(|(name, _)| name)
+        .collect();
+    required.sort();
+
+    let mut schema = serde_json::json!({"type": "object", "properties": properties});
+    if !required.is_empty() {
+        schema["required"] = serde_json::json!(required);
+    }
+    schema
+}
+
+/// JSON Schema for a bare field type; `Unknown` accepts anything and
+/// `Mixed` any non-null value
+fn type_json_schema(field_type: &FieldType) -> serde_json::Value {
+    match field_type {
+        FieldType::String => serde_json::json!({"type": "string"}),
+        FieldType::Integer => serde_json::json!({"type": "integer"}),
+        FieldType::Float => serde_json::json!({"type": "number"}),
+        FieldType::Boolean => serde_json::json!({"type": "boolean"}),
+        FieldType::DateTime => serde_json::json!({"type": "string", "format": "date-time"}),
+        FieldType::Binary => serde_json::json!({"type": "string", "contentEncoding": "base64"}),
+        FieldType::Array(items) => {
+            serde_json::json!({"type": "array", "items": type_json_schema(items)})
+        }
+        FieldType::Object(fields) => object_json_schema(fields),
+        FieldType::Mixed => {
+            serde_json::json!({"type": ["array", "boolean", "number", "object", "string"]})
+        }
+        FieldType::Unknown => serde_json::json!({}),
+    }
+}
+
+fn field_json_schema(field: &FieldSchema) -> serde_json::Value {
+    let mut schema = type_json_schema(&field.field_type);
+    if field.nullable {
+        match schema.get_mut("type") {
+            Some(serde_json::Value::String(name)) => {
+                schema["type"] = serde_json::json!([name, "null"]);
+            }
+            Some(serde_json::Value::Array(names)) => names.push(serde_json::json!("null")),
+            _ => {}
+        }
+    }
+    if let (FieldType::Array(_), Some(max)) = (&field.field_type, field.max_size) {
+        schema["maxItems"] = serde_json::json!(max);
+    }
+
+    let mut custom = serde_json::Map::new();
+    for constraint in &field.constraints {
+        match constraint {
+            FieldConstraint::MinValue(min) => schema["minimum"] = serde_json::json!(min),
+            FieldConstraint::MaxValue(max) => schema["maximum"] = serde_json::json!(max),
+            FieldConstraint::MinLength(len) => schema["minLength"] = serde_json::json!(len),
+            FieldConstraint::MaxLength(len) => schema["maxLength"] = serde_json::json!(len),
+            FieldConstraint::Pattern(pattern) => schema["pattern"] = serde_json::json!(pattern),
+            FieldConstraint::OneOf(values) => schema["enum"] = serde_json::json!(values),
+            FieldConstraint::Custom { name, rule } => {
+                custom.insert(name.clone(), serde_json::json!(rule));
+            }
+            FieldConstraint::UniqueValues => schema["x-unique-values"] = serde_json::json!(true),
+        }
+    }
+    if !custom.is_empty() {
+        schema["x-custom"] = serde_json::Value::Object(custom);
+    }
+    if let Some(description) = &field.description {
+        schema["description"] = serde_json::json!(description);
+    }
+    if !field.examples.is_empty() {
+        schema["examples"] = serde_json::json!(field.examples);
+    }
+    schema
+}
+
+/// Values of a JSON Schema `type` keyword, which may be a string or a list
+fn json_schema_types(schema: &serde_json::Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(serde_json::Value::String(name)) => vec![name.as_str()],
+        Some(serde_json::Value::Array(names)) => {
+            names.iter().filter_map(serde_json::Value::as_str).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Whether a (dereferenced) JSON Schema describes an object
+fn is_object_schema(schema: &serde_json::Value) -> bool {
+    let types = json_schema_types(schema);
+    types.contains(&"object")
+        || (types.is_empty()
+            && ["properties", "allOf", "oneOf", "anyOf"]
+                .iter()
+                .any(|keyword| schema.get(keyword).is_some()))
+}
+
+/// Keywords `OxiSchema::from_json_schema` maps or can safely skip on a field
+const JSON_SCHEMA_FIELD_KEYWORDS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$ref",
+    "$defs",
+    "definitions",
+    "$comment",
+    "type",
+    "properties",
+    "required",
+    "items",
+    "format",
+    "contentEncoding",
+    "minimum",
+    "maximum",
+    "minLength",
+    "maxLength",
+    "maxItems",
+    "pattern",
+    "enum",
+    "const",
+    "title",
+    "description",
+    "examples",
+    "oneOf",
+    "anyOf",
+    "allOf",
+    "x-unique-values",
+    "x-custom",
+];
+
+/// Keywords mapped on array items, which only carry a type
+const JSON_SCHEMA_ITEM_KEYWORDS: &[&str] = &[
+    "$ref",
+    "$comment",
+    "type",
+    "properties",
+    "required",
+    "items",
+    "format",
+    "contentEncoding",
+    "title",
+    "description",
+    "oneOf",
+    "anyOf",
+    "allOf",
+];
+
+/// Walks a JSON Schema document for `OxiSchema::from_json_schema`
+struct JsonSchemaImporter<'a> {
+    root: &'a serde_json::Value,
+    /// `$ref`s being expanded, so recursive definitions stop instead of looping
+    expanding: Vec<&'a str>,
+    /// Field names leading to the schema being read, `[]` for array items
+    path: Vec<String>,
+    /// Keywords that were ignored, with where they appeared
+    warnings: Vec<String>,
+}
+
+impl<'a> JsonSchemaImporter<'a> {
+    /// Dotted field path for warnings, e.g. `orders[].total`
+    fn location(&self) -> String {
+        let mut location = String::new();
+        for segment in &self.path {
+            if !location.is_empty() && segment != "[]" {
+                location.push('.');
+            }
+            location.push_str(segment);
+        }
+        if location.is_empty() {
+            location.push_str("(root)");
+        }
+        location
+    }
+
+    /// Record a warning for every keyword of `schema` outside `supported`,
+    /// and for `format`/`contentEncoding` values with no field type
+    fn check_keywords(&mut self, schema: &serde_json::Value, supported: &[&str]) {
+        let Some(keywords) = schema.as_object() else {
+            return;
+        };
+        for (keyword, value) in keywords {
+            let ignored = if !supported.contains(&keyword.as_str()) {
+                format!("unsupported keyword '{keyword}' ignored")
+            } else if keyword == "format" && value.as_str() != Some("date-time") {
+                format!("format {value} is not supported, read as a plain string")
+            } else if keyword == "contentEncoding" && value.as_str() != Some("base64") {
+                format!("contentEncoding {value} is not supported, read as a plain string")
+            } else {
+                continue;
+            };
+            self.warnings
+                .push(format!("{}: {ignored}", self.location()));
+        }
+    }
+
+    /// Follow `$ref` until reaching a schema without one
+    fn deref(&self, mut schema: &'a serde_json::Value) -> anyhow::Result<&'a serde_json::Value> {
+        for _ in 0..64 {
+            let Some(reference) = schema.get("$ref").and_then(serde_json::Value::as_str) else {
+                return Ok(schema);
+            };
+            schema = self.resolve_ref(reference)?;
+        }
+        anyhow::bail!("$ref chain is too deep or circular")
+    }
+
+    fn resolve_ref(&self, reference: &str) -> anyhow::Result<&'a serde_json::Value> {
+        let pointer = reference.strip_prefix('#').ok_or_else(|| {
+            anyhow::anyhow!(
+                "Only references within the same document are supported, got '{reference}'"
+            )
+        })?;
+        self.root
+            .pointer(pointer)
+            .ok_or_else(|| anyhow::anyhow!("Unresolved $ref '{reference}'"))
+    }
+
+    /// Run `expand` on the schema behind a `$ref`, or `None` if that
+    /// reference is already being expanded further up
+    fn expand_ref<T>(
+        &mut self,
+        schema: &'a serde_json::Value,
+        expand: impl FnOnce(&mut Self, &'a serde_json::Value) -> anyhow::Result<T>,
+    ) -> anyhow::Result<Option<T>> {
+        let Some(reference) = schema.get("$ref").and_then(serde_json::Value::as_str) else {
+            return expand(self, schema).map(Some);
+        };
+        if self.expanding.contains(&reference) {
+            return Ok(None);
+        }
+
+        let target = self.resolve_ref(reference)?;
+        self.expanding.push(reference);
+        let result = self.expand_ref(target, expand);
+        self.expanding.pop();
+        result
+    }
+
+    fn field_schema(
+        &mut self,
+        schema: &'a serde_json::Value,
+        required: bool,
+    ) -> anyhow::Result<FieldSchema> {
+        let (field_type, nullable) = self
+            .expand_ref(schema, |importer, schema| importer.field_type(schema))?
+            .unwrap_or((Some(FieldType::Object(HashMap::new())), false));
+        let schema = self.deref(schema)?;
+        self.check_keywords(schema, JSON_SCHEMA_FIELD_KEYWORDS);
+
+        let number = |keyword: &str| schema.get(keyword).and_then(serde_json::Value::as_f64);
+        let length = |keyword: &str| {
+            schema
+                .get(keyword)
+                .and_then(serde_json::Value::as_u64)
+                .map(|n| n as usize)
+        };
+
+        let mut constraints = Vec::new();
+        if let Some(min) = number("minimum") {
+            constraints.push(FieldConstraint::MinValue(min));
+        }
+        if let Some(max) = number("maximum") {
+            constraints.push(FieldConstraint::MaxValue(max));
+        }
+        if let Some(min) = length("minLength") {
+            constraints.push(FieldConstraint::MinLength(min));
+        }
+        if let Some(max) = length("maxLength") {
+            constraints.push(FieldConstraint::MaxLength(max));
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(serde_json::Value::as_str) {
+            constraints.push(FieldConstraint::Pattern(pattern.to_string()));
+        }
+        if let Some(values) = schema.get("enum").and_then(serde_json::Value::as_array) {
+            constraints.push(FieldConstraint::OneOf(values.clone()));
+        }
+        if let Some(value) = schema.get("const") {
+            constraints.push(FieldConstraint::OneOf(vec![value.clone()]));
+        }
+        if schema.get("x-unique-values") == Some(&serde_json::Value::Bool(true)) {
+            constraints.push(FieldConstraint::UniqueValues);
+        }
+        if let Some(custom) = schema
+            .get("x-custom")
+            .and_then(serde_json::Value::as_object)
+        {
+            for (name, rule) in custom {
+                match rule.as_str() {
+                    Some(rule) => constraints.push(FieldConstraint::Custom {
+                        name: name.clone(),
+                        rule: rule.to_string(),
+                    }),
+                    None => self.warnings.push(format!(
+                        "{}: x-custom rule '{name}' is not a string, ignored",
+                        self.location()
+                    )),
+                }
+            }
+        }
+
+        Ok(FieldSchema {
+            field_type: field_type.unwrap_or(FieldType::Unknown),
+            nullable: nullable || !required,
+            max_size: length("maxLength").or_else(|| length("maxItems")),
+            constraints,
+            description: schema
+                .get("description")
+                .or_else(|| schema.get("title"))
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string),
+            examples: schema
+                .get("examples")
+                .and_then(serde_json::Value::as_array)
+                .cloned()
+                .unwrap_or_default(),
+            datetime_formats: None,
+        })
+    }
+
+    /// Type of a dereferenced schema and whether it admits null
+    ///
+    /// A schema that only admits null has no type.
+    fn field_type(
+        &mut self,
+        schema: &'a serde_json::Value,
+    ) -> anyhow::Result<(Option<FieldType>, bool)> {
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(variants) = schema.get(keyword).and_then(serde_json::Value::as_array) {
+                return self.variant_type(variants);
+            }
+        }
+
+        let types = json_schema_types(schema);
+        let enum_has_null = schema
+            .get("enum")
+            .and_then(serde_json::Value::as_array)
+            .is_some_and(|values| values.contains(&serde_json::Value::Null));
+        let nullable = types.contains(&"null") || enum_has_null;
+        let named: Vec<&str> = types.into_iter().filter(|t| *t != "null").collect();
+
+        let field_type = match named.as_slice() {
+            [] if nullable && schema.get("enum").is_none() => return Ok((None, true)),
+            [] if is_object_schema(schema) => self.named_type("object", schema)?,
+            [] if schema.get("items").is_some() => self.named_type("array", schema)?,
+            [] => FieldType::Unknown,
+            [name] => self.named_type(name, schema)?,
+            names if names.iter().all(|n| *n == "integer" || *n == "number") => FieldType::Float,
+            _ => FieldType::Mixed,
+        };
+        Ok((Some(field_type), nullable))
+    }
+
+    fn named_type(
+        &mut self,
+        name: &str,
+        schema: &'a serde_json::Value,
+    ) -> anyhow::Result<FieldType> {
+        Ok(match name {
+            "string"
+                if schema.get("format").and_then(serde_json::Value::as_str)
+                    == Some("date-time") =>
+            {
+                FieldType::DateTime
+            }
+            "string"
+                if schema
+                    .get("contentEncoding")
+                    .and_then(serde_json::Value::as_str)
+                    == Some("base64") =>
+            {
+                FieldType::Binary
+            }
+            "string" => FieldType::String,
+            "integer" => FieldType::Integer,
+            "number" => FieldType::Float,
+            "boolean" => FieldType::Boolean,
+            "object" => FieldType::Object(self.object_fields(schema)?),
+            "array" => {
+                let item_type = match schema.get("items") {
+                    Some(items) if items.is_object() => {
+                        self.path.push("[]".to_string());
+                        let items_schema = self.deref(items)?;
+                        self.check_keywords(items_schema, JSON_SCHEMA_ITEM_KEYWORDS);
+                        let item_type =
+                            self.expand_ref(items, |importer, items| importer.field_type(items));
+                        self.path.pop();
+                        item_type?
+                            .and_then(|(item_type, _)| item_type)
+                            .unwrap_or(FieldType::Unknown)
+                    }
+                    _ => FieldType::Unknown,
+                };
+                FieldType::Array(Box::new(item_type))
+            }
+            _ => FieldType::Unknown,
+        })
+    }
+
+    /// Combine `oneOf`/`anyOf` variants into one type
+    ///
+    /// Object variants merge into one object whose fields are only required
+    /// when every variant requires them; unrelated variants become `Mixed`.
+    fn variant_type(
+        &mut self,
+        variants: &'a [serde_json::Value],
+    ) -> anyhow::Result<(Option<FieldType>, bool)> {
+        let mut nullable = false;
+        let mut types: Vec<FieldType> = Vec::new();
+        for variant in variants {
+            let (variant_type, variant_nullable) = self
+                .expand_ref(variant, |importer, variant| importer.field_type(variant))?
+                .unwrap_or((Some(FieldType::Object(HashMap::new())), false));
+            nullable |= variant_nullable;
+            if let Some(variant_type) = variant_type {
+                if !types.contains(&variant_type) {
+                    types.push(variant_type);
+                }
+            }
+        }
+
+        let field_type = match types.as_slice() {
+            [] => None,
+            [single] => Some(single.clone()),
+            _ if types.iter().all(|t| matches!(t, FieldType::Object(_))) => {
+                let variants = types.into_iter().map(|t| match t {
+                    FieldType::Object(fields) => fields,
+                    _ => unreachable!(),
+                });
+                Some(FieldType::Object(merge_variant_fields(variants)))
+            }
+            _ if types
+                .iter()
+                .all(|t| matches!(t, FieldType::Integer | FieldType::Float)) =>
+            {
+                Some(FieldType::Float)
+            }
+            _ => Some(FieldType::Mixed),
+        };
+        Ok((field_type, nullable))
+    }
+
+    /// Fields of an object schema, including those contributed by
+    /// `allOf` (all apply) and `oneOf`/`anyOf` (any one applies)
+    fn object_fields(
+        &mut self,
+        schema: &'a serde_json::Value,
+    ) -> anyhow::Result<HashMap<String, FieldSchema>> {
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(serde_json::Value::as_array)
+            .map(|names| names.iter().filter_map(serde_json::Value::as_str).collect())
+            .unwrap_or_default();
+
+        let mut fields = HashMap::new();
+        if let Some(properties) = schema
+            .get("properties")
+            .and_then(serde_json::Value::as_object)
+        {
+            for (name, property) in properties {
+                self.path.push(name.clone());
+                let field = self.field_schema(property, required.contains(&name.as_str()));
+                self.path.pop();
+                fields.insert(name.clone(), field?);
+            }
+        }
+
+        let nested = |importer: &mut Self, keyword: &str| -> anyhow::Result<Vec<_>> {
+            let mut parts = Vec::new();
+            if let Some(subschemas) = schema.get(keyword).and_then(serde_json::Value::as_array) {
+                for subschema in subschemas {
+                    parts.push(
+                        importer
+                            .expand_ref(subschema, |importer, subschema| {
+                                importer.object_fields(subschema)
+                            })?
+                            .unwrap_or_default(),
+                    );
+                }
+            }
+            Ok(parts)
+        };
+
+        for part in nested(self, "allOf")? {
+            for (name, field) in part {
+                fields.entry(name).or_insert(field);
+            }
+        }
+        for keyword in ["oneOf", "anyOf"] {
+            let variants = nested(self, keyword)?;
+            if !variants.is_empty() {
+                for (name, field) in merge_variant_fields(variants) {
+                    fields.entry(name).or_insert(field);
+                }
+            }
+        }
+
+        // A field `required` here but only defined in a subschema is still required
+        let own = schema
+            .get("properties")
+            .and_then(serde_json::Value::as_object);
+        for name in required {
+            if own.is_some_and(|own| own.contains_key(name)) {
+                continue;
+            }
+            if let Some(field) = fields.get_mut(name) {
+                field.nullable = false;
+            }
+        }
+
+        Ok(fields)
+    }
+}
+
+/// Array elements inspected per array during schema inference
+pub const DEFAULT_SAMPLE_SIZE: usize = 100;
+
+/// Settings for `OxiSchema::infer_from_data_with`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InferenceOptions {
+    /// Array elements inspected per array
+    pub sample_size: usize,
+    /// Type string values as `DateTime` when the field name looks like a
+    /// timestamp (see `datetime::is_datetime_name`) and the value parses.
+    /// Off by default; numbers are never promoted, since an integer field
+    /// named `response_time` is a duration, not an epoch.
+    pub detect_datetimes: bool,
+    /// Add constraints observed in sampled records: `MinValue`/`MaxValue` for
+    /// numbers, `MaxLength` for strings and `OneOf` for strings with few
+    /// repeated values. Off by default, since a sample's range is often
+    /// narrower than what later data legitimately holds.
+    pub infer_constraints: bool,
+}
+
+impl Default for InferenceOptions {
+    fn default() -> Self {
+        Self {
+            sample_size: DEFAULT_SAMPLE_SIZE,
+            detect_datetimes: false,
+            infer_constraints: false,
+        }
+    }
+}
+
+/// Most distinct values a string field may have to be inferred as `OneOf`
+const MAX_INFERRED_ENUM_VALUES: usize = 10;
+
+/// Constraints a field's sampled values satisfy, see `InferenceOptions::infer_constraints`
+///
+/// A string field only becomes an enum when some value repeats, so a
+/// column of unique names is not mistaken for a fixed set.
+fn infer_field_constraints<'a>(
+    field: &FieldSchema,
+    values: impl Iterator<Item = &'a serde_json::Value>,
+) -> Vec<FieldConstraint> {
+    let values: Vec<&serde_json::Value> = values.filter(|v| !v.is_null()).collect();
+    match field.field_type {
+        FieldType::Integer | FieldType::Float => {
+            let numbers = values.iter().filter_map(|v| v.as_f64());
+            let (min, max) = numbers.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), n| {
+                (min.min(n), max.max(n))
+            });
+            if min > max {
+                return vec![];
+            }
+            vec![
+                FieldConstraint::MinValue(min),
+                FieldConstraint::MaxValue(max),
+            ]
+        }
+        FieldType::String => {
+            let strings: Vec<&str> = values.iter().filter_map(|v| v.as_str()).collect();
+            let Some(max_length) = strings.iter().map(|s| s.len()).max() else {
+                return vec![];
+            };
+            let mut constraints = vec![FieldConstraint::MaxLength(max_length)];
+
+            let distinct: std::collections::BTreeSet<&str> = strings.iter().copied().collect();
+            if distinct.len() <= MAX_INFERRED_ENUM_VALUES && distinct.len() < strings.len() {
+                constraints.push(FieldConstraint::OneOf(
+                    distinct.into_iter().map(serde_json::Value::from).collect(),
+                ));
+            }
+            constraints
+        }
+        _ => vec![],
+    }
+}
+
+/// Examples kept per field when inferred schemas are merged
+const MAX_INFERRED_EXAMPLES: usize = 3;
+
+/// Type of a sample value; null is `Unknown` until merged with a real type
+fn infer_field_type(value: &serde_json::Value, options: &InferenceOptions) -> FieldType {
+    match value {
+        serde_json::Value::String(_) => FieldType::String,
+        serde_json::Value::Number(n) if n.is_i64() => FieldType::Integer,
+        serde_json::Value::Number(_) => FieldType::Float,
+        serde_json::Value::Bool(_) => FieldType::Boolean,
+        serde_json::Value::Null => FieldType::Unknown,
+        serde_json::Value::Object(map) => FieldType::Object(infer_object_fields(map, options)),
+        serde_json::Value::Array(items) => FieldType::Array(Box::new(
+            items
+                .iter()
+                .take(options.sample_size)
+                .map(|item| infer_field_type(item, options))
+                .reduce(|a, b| merge_field_types(a, b, MergePolicy::WidenToMixed))
+                .unwrap_or(FieldType::Unknown),
+        )),
+    }
+}
+
+fn infer_object_fields(
+    map: &serde_json::Map<String, serde_json::Value>,
+    options: &InferenceOptions,
+) -> HashMap<String, FieldSchema> {
+    map.iter()
+        .map(|(key, val)| {
+            let mut field_type = infer_field_type(val, options);
+            if options.detect_datetimes
+                && field_type == FieldType::String
+                && crate::datetime::is_datetime_name(key)
+                && crate::datetime::parse_datetime(val, &[]).is_some()
+            {
+                field_type = FieldType::DateTime;
+            }
+
+            let field = FieldSchema {
+                field_type,
+                nullable: val.is_null(),
+                max_size: None,
+                constraints: vec![],
+                description: None,
+                examples: vec![val.clone()],
+                datetime_formats: None,
+            };
+            (key.clone(), field)
+        })
+        .collect()
+}
+
+/// Fields that were only ever null are typed as nullable strings
+fn default_null_types(fields: &mut HashMap<String, FieldSchema>) {
+    for field in fields.values_mut() {
+        if field.field_type == FieldType::Unknown && field.nullable {
+            field.field_type = FieldType::String;
+        } else if let FieldType::Object(nested) = &mut field.field_type {
+            default_null_types(nested);
+        }
+    }
+}
+
+fn merge_field_types(a: FieldType, b: FieldType, policy: MergePolicy) -> FieldType {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (FieldType::Unknown, other) | (other, FieldType::Unknown) => other,
+        (FieldType::Object(a), FieldType::Object(b)) => {
+            FieldType::Object(merge_fields(&a, &b, policy))
+        }
+        (FieldType::Array(a), FieldType::Array(b)) => {
+            FieldType::Array(Box::new(merge_field_types(*a, *b, policy)))
+        }
+        (a, _) if policy == MergePolicy::PreferLeft => a,
+        (_, b) if policy == MergePolicy::PreferRight => b,
+        (FieldType::Integer, FieldType::Float) | (FieldType::Float, FieldType::Integer) => {
+            FieldType::Float
+        }
+        // Some values of the field did not parse as timestamps
+        (FieldType::DateTime, FieldType::String) | (FieldType::String, FieldType::DateTime) => {
+            FieldType::String
+        }
+        _ => FieldType::Mixed,
+    }
+}
+
+/// Field-wise union used by `OxiSchema::merge`
+fn merge_fields(
+    a: &HashMap<String, FieldSchema>,
+    b: &HashMap<String, FieldSchema>,
+    policy: MergePolicy,
+) -> HashMap<String, FieldSchema> {
+    let mut merged = HashMap::with_capacity(a.len().max(b.len()));
+    for (name, field) in a {
+        let field = match b.get(name) {
+            Some(other) => merge_field_schema(field, other, policy),
+            None => FieldSchema {
+                nullable: true,
+                ..field.clone()
+            },
+        };
+        merged.insert(name.clone(), field);
+    }
+    for (name, field) in b {
+        if !a.contains_key(name) {
+            merged.insert(
+                name.clone(),
+                FieldSchema {
+                    nullable: true,
+                    ..field.clone()
+                },
+            );
+        }
+    }
+    merged
+}
+
+fn merge_field_schema(a: &FieldSchema, b: &FieldSchema, policy: MergePolicy) -> FieldSchema {
+    let mut examples = a.examples.clone();
+    for example in &b.examples {
+        if examples.len() >= MAX_INFERRED_EXAMPLES {
+            break;
+        }
+        if !examples.contains(example) {
+            examples.push(example.clone());
+        }
+    }
+    examples.truncate(MAX_INFERRED_EXAMPLES);
+
+    FieldSchema {
+        field_type: merge_field_types(a.field_type.clone(), b.field_type.clone(), policy),
+        nullable: a.nullable || b.nullable,
+        max_size: a.max_size.zip(b.max_size).map(|(x, y)| x.max(y)),
+        constraints: if a.constraints == b.constraints {
+            a.constraints.clone()
+        } else {
+            Vec::new()
+        },
+        description: a.description.clone().or_else(|| b.description.clone()),
+        examples,
+        datetime_formats: a
+            .datetime_formats
+            .clone()
+            .or_else(|| b.datetime_formats.clone()),
+    }
+}
+
+/// Union of alternative field sets; a field stays required only if every
+/// alternative requires it
+fn merge_variant_fields(
+    variants: impl IntoIterator<Item = HashMap<String, FieldSchema>>,
+) -> HashMap<String, FieldSchema> {
+    let variants: Vec<_> = variants.into_iter().collect();
+    let mut merged: HashMap<String, FieldSchema> = HashMap::new();
+    for variant in &variants {
+        for (name, field) in variant {
+            let in_all = variants.iter().all(|v| v.contains_key(name));
+            merged
+                .entry(name.clone())
+                .and_modify(|existing| {
+                    if existing.field_type != field.field_type {
+                        existing.field_type = FieldType::Mixed;
+                    }
+                    // A constraint from one alternative doesn't bind the others
+                    if existing.constraints != field.constraints {
+                        existing.constraints.clear();
+                    }
+                    existing.nullable |= field.nullable;
+                })
+                .or_insert_with(|| FieldSchema {
+                    nullable: field.nullable || !in_all,
+                    ..field.clone()
+                });
+        }
+    }
+    merged
+}
+
+/// Stable hash of a JSON value, or of just `fields` when it is an object
+pub(crate) fn json_hash(value: &serde_json::Value, fields: Option<&[&str]>) -> String {
+    match (value, fields) {
+        (serde_json::Value::Object(map), Some(fields)) => {
+            let selected: serde_json::Map<String, serde_json::Value> = fields
+                .iter()
+                .map(|field| {
+                    let value = map.get(*field).cloned().unwrap_or_default();
+                    (field.to_string(), value)
+                })
+                .collect();
+            Data::Json(serde_json::Value::Object(selected)).compute_hash_stable()
+        }
+        _ => Data::Json(value.clone()).compute_hash_stable(),
+    }
+}
+
+/// Compact JSON with sorted object keys and integral floats written as integers
+fn canonical_json(value: &serde_json::Value) -> String {
+    let mut out = String::new();
+    write_canonical_json(value, &mut out);
+    out
+}
+
+fn write_canonical_json(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(value, out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        serde_json::Value::Number(n) => match n.as_f64() {
+            // Beyond 2^53 floats can't tell neighbouring integers apart
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 => {
+                out.push_str(&(f as i64).to_string())
+            }
+            _ => out.push_str(&n.to_string()),
+        },
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Write the leaves of `value` into `out`, keyed by their path from the root
+fn flatten_into(
+    value: &serde_json::Value,
+    prefix: &str,
+    separator: &str,
+    out: &mut serde_json::Map<String, serde_json::Value>,
+) {
+    match value {
+        serde_json::Value::Object(map) if !map.is_empty() => {
+            for (key, val) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}{separator}{key}")
+                };
+                flatten_into(val, &path, separator, out);
+            }
+        }
+        serde_json::Value::Array(items) if !items.is_empty() && !prefix.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                flatten_into(item, &format!("{prefix}[{i}]"), separator, out);
+            }
+        }
+        // Leaves, plus empty containers so they survive a round trip
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+/// One step of a flattened key path
+enum FlatSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Split `a.b[0][1].c` into keys and array indices
+fn parse_flat_key(key: &str, separator: &str) -> anyhow::Result<Vec<FlatSegment>> {
+    let mut segments = Vec::new();
+    let parts: Vec<&str> = if separator.is_empty() {
+        vec![key]
+    } else {
+        key.split(separator).collect()
+    };
+
+    for part in parts {
+        let (name, mut rest) = match part.find('[') {
+            Some(start) if part.ends_with(']') => (&part[..start], &part[start..]),
+            _ => (part, ""),
+        };
+        segments.push(FlatSegment::Key(name.to_string()));
+        while let Some(stripped) = rest.strip_prefix('[') {
+            let end = stripped
+                .find(']')
+                .ok_or_else(|| anyhow::anyhow!("unterminated index in '{key}'"))?;
+            let index = stripped[..end]
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid index '{}' in '{key}'", &stripped[..end]))?;
+            segments.push(FlatSegment::Index(index));
+            rest = &stripped[end + 1..];
+        }
+    }
+    Ok(segments)
+}
+
+/// Set `value` at `path` inside `target`, creating objects and arrays on the way
+fn insert_path(
+    target: &mut serde_json::Value,
+    path: &[FlatSegment],
+    value: serde_json::Value,
+) -> anyhow::Result<()> {
+    let Some((segment, rest)) = path.split_first() else {
+        if !target.is_null() {
+            anyhow::bail!("value already set");
+        }
+        *target = value;
+        return Ok(());
+    };
+
+    // The container this segment indexes into is decided by the segment kind
+    let child = match segment {
+        FlatSegment::Key(key) => {
+            if target.is_null() {
+                *target = serde_json::Value::Object(serde_json::Map::new());
+            }
+            let serde_json::Value::Object(map) = target else {
+                anyhow::bail!("'{key}' conflicts with a non-object value");
+            };
+            map.entry(key.clone()).or_insert(serde_json::Value::Null)
+        }
+        FlatSegment::Index(index) => {
+            if target.is_null() {
+                *target = serde_json::Value::Array(Vec::new());
+            }
+            let serde_json::Value::Array(items) = target else {
+                anyhow::bail!("index [{index}] conflicts with a non-array value");
+            };
+            if items.len() <= *index {
+                items.resize(index + 1, serde_json::Value::Null);
+            }
+            &mut items[*index]
+        }
+    };
+    insert_path(child, rest, value)
 }
 
 impl From<Data> for OxiData {
@@ -1195,3 +3882,371 @@ impl From<Data> for OxiData {
         Self::new(data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compute_hash() {
+        let a = OxiData::from_json(serde_json::from_str(r#"{"b": [1.0, 2], "a": "x"}"#).unwrap());
+        let b = OxiData::from_json(json!({"a": "x", "b": [1, 2]}));
+        assert_eq!(a.compute_hash(None), b.compute_hash(None));
+        assert_eq!(a.compute_hash(None).len(), 64);
+        assert_ne!(
+            a.compute_hash(None),
+            OxiData::from_json(json!({"a": "x", "b": [1, 2.5]})).compute_hash(None)
+        );
+
+        // Only the selected fields count, and a missing field is null
+        let c = OxiData::from_json(json!({"a": "x", "b": "other"}));
+        assert_eq!(a.compute_hash(Some(&["a"])), c.compute_hash(Some(&["a"])));
+        assert_ne!(
+            a.compute_hash(Some(&["a", "b"])),
+            c.compute_hash(Some(&["a", "b"]))
+        );
+        assert_eq!(
+            c.compute_hash(Some(&["a", "missing"])),
+            OxiData::from_json(json!({"missing": null, "a": "x"})).compute_hash(None)
+        );
+
+        // The data type is part of the hash
+        assert_ne!(
+            Data::Text("1".to_string()).compute_hash_stable(),
+            Data::Json(json!(1)).compute_hash_stable()
+        );
+        assert_eq!(
+            Data::Json(json!(-0.0)).compute_hash_stable(),
+            Data::Json(json!(0)).compute_hash_stable()
+        );
+    }
+
+    #[test]
+    fn test_processing_limits_check() {
+        let limits = ProcessingLimits {
+            max_batch_size: Some(10),
+            max_memory_mb: Some(1),
+            max_processing_time_ms: None,
+            supported_input_types: vec![OxiDataType::Json],
+        };
+        let records = |count: usize, width: usize| {
+            OxiData::from_json(serde_json::Value::Array(
+                (0..count).map(|_| json!("x".repeat(width))).collect(),
+            ))
+        };
+
+        assert!(limits.check(&records(10, 10), true).is_empty());
+
+        let violations = limits.check(&OxiData::from_text("a".repeat(10)), false);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].limit, "supported_input_types");
+        assert_eq!(violations[0].observed, "Text");
+
+        // Oversized batches are only a violation when they cannot be chunked
+        assert!(limits.check(&records(20, 10), false).is_empty());
+        let violations = limits.check(&records(20, 10), true);
+        assert_eq!(violations[0].limit, "max_batch_size");
+        assert!(!violations[0].approaching);
+
+        // ~0.9MB of one ~1.05MB record warns, ~2.1MB fails
+        let violations = limits.check(&records(1, 450 * 1024), true);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].limit, "max_memory_mb");
+        assert!(violations[0].approaching);
+        let violations = limits.check(&records(1, 1024 * 1024), true);
+        assert!(!violations[0].approaching);
+        let error = violations[0].clone().into_error("wide");
+        assert!(matches!(
+            error,
+            crate::error::OxiError::LimitExceeded { .. }
+        ));
+        assert!(error.to_string().contains("allowed 1MB"), "{error}");
+
+        // Chunked inputs are measured per chunk
+        assert!(limits.check(&records(40, 30 * 1024), false).is_empty());
+        assert_eq!(limits.check(&records(40, 30 * 1024), true).len(), 2);
+    }
+
+    #[test]
+    fn test_diff_matches_records_by_key_or_position() {
+        let before = OxiData::from_json(json!([
+            {"id": 1, "total": 10, "customer": {"name": "Ada", "tier": "gold"}},
+            {"id": 2, "total": 20}
+        ]));
+        let after = OxiData::from_json(json!([
+            {"id": 2, "total": 25},
+            {"id": 1, "total": 10, "customer": {"name": "Ada"}, "note": "rush"},
+            {"id": 3, "total": 5}
+        ]));
+
+        let diff = OxiData::diff_by_key(&before, &after, Some("id"));
+        assert_eq!(diff.added_fields, ["[id=1].note", "[id=3]"]);
+        assert_eq!(diff.removed_fields, ["[id=1].customer.tier"]);
+        assert_eq!(
+            diff.modified_fields,
+            [("[id=2].total".to_string(), json!(20), json!(25))]
+        );
+        assert_eq!(diff.record_count_delta, 1);
+        assert_eq!(
+            diff.summary_line(),
+            "2 added, 1 removed, 1 modified, +1 records"
+        );
+
+        // By position, the reordered records differ throughout
+        let diff = OxiData::diff(&before, &after);
+        assert!(diff
+            .modified_fields
+            .contains(&("[0].id".to_string(), json!(1), json!(2))));
+        assert_eq!(diff.added_fields.last().map(String::as_str), Some("[2]"));
+    }
+
+    #[test]
+    fn test_diff_of_equal_and_non_json_payloads() {
+        let record = OxiData::from_json(json!({"id": 1, "tags": ["a", "b"]}));
+        let diff = OxiData::diff(&record, &record.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.summary_line(), "no changes");
+
+        let diff = OxiData::diff(
+            &OxiData::from_text("a,b".to_string()),
+            &OxiData::from_text("a,c".to_string()),
+        );
+        assert_eq!(
+            diff.modified_fields,
+            [("$".to_string(), json!("a,b"), json!("a,c"))]
+        );
+
+        let diff = OxiData::diff(&OxiData::empty(), &OxiData::from_json(json!([1, 2])));
+        assert_eq!(diff.record_count_delta, 2);
+    }
+
+    #[test]
+    fn test_processing_limits_from_config_only_tighten() {
+        let config = OxiConfig::from_yaml(
+            serde_yaml::from_str(
+                r#"
+max_batch_size: 500
+max_memory_mb: 4096
+max_processing_time_ms: 5000
+supported_input_types: [JSON, binary, video]
+"#,
+            )
+            .unwrap(),
+        );
+        let step = ProcessingLimits::from_config(&config);
+        assert_eq!(step.max_batch_size, Some(500));
+        assert_eq!(
+            step.supported_input_types,
+            vec![OxiDataType::Json, OxiDataType::Binary]
+        );
+
+        let declared = ProcessingLimits {
+            max_batch_size: None,
+            supported_input_types: vec![OxiDataType::Json, OxiDataType::Text],
+            ..ProcessingLimits::default()
+        };
+        let limits = declared.tighten(&step);
+        assert_eq!(limits.max_batch_size, Some(500));
+        assert_eq!(limits.max_memory_mb, Some(512));
+        assert_eq!(limits.max_processing_time_ms, Some(5_000));
+        assert_eq!(limits.supported_input_types, vec![OxiDataType::Json]);
+
+        // Config without limit keys leaves the declared limits alone
+        let limits = declared.tighten(&ProcessingLimits::from_config(&OxiConfig::default()));
+        assert_eq!(limits.max_processing_time_ms, Some(30_000));
+        assert_eq!(limits.supported_input_types, declared.supported_input_types);
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Order {
+        id: u32,
+        customer: String,
+        total: f64,
+        note: Option<String>,
+    }
+
+    fn orders() -> Vec<Order> {
+        vec![
+            Order {
+                id: 1,
+                customer: "ada".to_string(),
+                total: 9.5,
+                note: None,
+            },
+            Order {
+                id: 2,
+                customer: "bob".to_string(),
+                total: 12.0,
+                note: Some("gift".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_validate_strict_rejects_undeclared_keys() {
+        let schema = OxiConfigSchema::from_yaml_schema(
+            &serde_yaml::from_str(
+                "properties: {path: {type: string}, before: {}}\nrequired: [path]",
+            )
+            .unwrap(),
+        );
+        let config = |yaml: &str| OxiConfig::from_yaml(serde_yaml::from_str(yaml).unwrap());
+
+        let valid = config("path: a.json\nbefore: [1, 2]\nmax_batch_size: 10");
+        assert!(valid.validate_strict(&schema).is_ok());
+
+        let typos = config("path: a.json\npaht: b.json\nbefor: 1");
+        assert!(typos.validate_against_schema(&schema).is_ok());
+        assert_eq!(typos.unknown_keys(&schema), vec!["befor", "paht"]);
+        let err = typos.validate_strict(&schema).unwrap_err().to_string();
+        assert!(
+            err.contains("Unknown config keys: befor, paht (expected: before, path)"),
+            "{err}"
+        );
+
+        // Schema violations are still reported first
+        assert!(matches!(
+            config("paht: b.json").validate_strict(&schema),
+            Err(ConfigError::MissingField(field)) if field == "path"
+        ));
+    }
+
+    #[test]
+    fn test_from_serialize_infers_schema() {
+        let data = OxiData::from_serialize(&orders()).unwrap();
+        assert_eq!(data.data().batch_size(), 2);
+        assert_eq!(data.schema.fields["id"].field_type, FieldType::Integer);
+        assert_eq!(data.schema.fields["total"].field_type, FieldType::Float);
+        assert!(data.schema.fields["note"].nullable);
+    }
+
+    #[test]
+    fn test_from_reader_formats() {
+        let json = OxiData::from_reader(&br#"{"a": 1}"#[..], DataFormat::Json).unwrap();
+        assert_eq!(json.data().as_json().unwrap(), &json!({"a": 1}));
+
+        let lines =
+            OxiData::from_reader(&b"{\"a\": 1}\n\n{\"a\": 2}\n"[..], DataFormat::Jsonl).unwrap();
+        assert_eq!(
+            lines.data().as_json().unwrap(),
+            &json!([{"a": 1}, {"a": 2}])
+        );
+        let err = OxiData::from_reader(&b"{\"a\": 1}\nnot json"[..], DataFormat::Jsonl)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line 2"), "{err}");
+
+        let text = OxiData::from_reader(&b"hello"[..], DataFormat::Text).unwrap();
+        assert_eq!(text.data().as_text().unwrap(), "hello");
+        assert!(OxiData::from_reader(&[0xff, 0xfe][..], DataFormat::Text).is_err());
+
+        let binary = OxiData::from_reader(&[0u8, 159, 146][..], DataFormat::Binary).unwrap();
+        assert_eq!(binary.data().as_binary().unwrap(), &vec![0u8, 159, 146]);
+    }
+
+    #[test]
+    fn test_from_file_sniffs_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, bytes: &[u8]| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            path
+        };
+
+        let json = OxiData::from_file(write("orders.JSON", br#"[{"id": 1}]"#)).unwrap();
+        assert_eq!(json.data().as_json().unwrap(), &json!([{"id": 1}]));
+        assert!(json
+            .get_meta("source_path")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .ends_with("orders.JSON"));
+
+        let lines =
+            OxiData::from_file(write("events.ndjson", b"{\"id\": 1}\n{\"id\": 2}")).unwrap();
+        assert_eq!(lines.data().batch_size(), 2);
+
+        let text = OxiData::from_file(write("data.csv", b"id\n1\n")).unwrap();
+        assert_eq!(text.data().as_text().unwrap(), "id\n1\n");
+
+        // Unknown extensions are sniffed by content
+        let notes = OxiData::from_file(write("notes.cfg", b"key=value")).unwrap();
+        assert_eq!(notes.data().data_type(), "Text");
+        let blob = OxiData::from_file(write("blob.dat", &[0xff, 0x00, 0xfe])).unwrap();
+        assert_eq!(blob.data().data_type(), "Binary");
+
+        assert!(OxiData::from_file(write("broken.json", b"{")).is_err());
+        assert!(OxiData::from_file(dir.path().join("missing.json")).is_err());
+    }
+
+    #[test]
+    fn test_try_into_deserialize() {
+        let text = OxiData::from_text("[1, 2, 3]".to_string());
+        assert_eq!(
+            text.try_into_deserialize::<Vec<u8>>().unwrap(),
+            vec![1, 2, 3]
+        );
+
+        let plain = OxiData::from_text("not json".to_string());
+        assert_eq!(plain.try_into_deserialize::<String>().unwrap(), "not json");
+
+        let empty = OxiData::empty();
+        assert_eq!(empty.try_into_deserialize::<Option<Order>>().unwrap(), None);
+
+        assert!(OxiData::from_binary(vec![1, 2])
+            .try_into_deserialize::<Vec<u8>>()
+            .is_err());
+        assert!(OxiData::from_json(json!({"id": "x"}))
+            .try_into_deserialize::<Order>()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_struct_round_trip_through_pipeline() {
+        let pipeline: crate::pipeline::Pipeline = serde_yaml::from_str("pipeline: []").unwrap();
+        let input = OxiData::from_serialize(&orders()).unwrap();
+
+        let result = pipeline
+            .execute_with_retries(input, &crate::config_resolver::ConfigResolver::new())
+            .await;
+        assert!(result.success);
+        let output: Vec<Order> = result.final_data.unwrap().try_into_deserialize().unwrap();
+        assert_eq!(output, orders());
+    }
+
+    #[test]
+    fn test_data_summary() {
+        assert_eq!(Data::from_text("héllo").summary(10), "Text data - héllo");
+        assert_eq!(
+            Data::from_text("héllo world").summary(2),
+            "Text data - hé... (11 characters)"
+        );
+        assert_eq!(
+            Data::from_binary(vec![0; 3]).summary(10),
+            "Binary data (3 bytes)"
+        );
+        assert_eq!(Data::Empty.summary(10), "Empty data");
+    }
+
+    #[test]
+    fn test_compact_json_text_round_trips() {
+        let value = serde_json::json!({"id": 1, "tags": ["a", "b c"], "nested": {"ok": true}});
+        let data = Data::Json(value.clone());
+
+        let compact = data.to_text_with(false).unwrap();
+        assert_eq!(
+            compact,
+            r#"{"id":1,"nested":{"ok":true},"tags":["a","b c"]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+            value
+        );
+
+        // Text meant for people stays indented
+        let pretty = data.to_text().unwrap();
+        assert!(pretty.contains("\n  \"id\": 1"), "{pretty}");
+        assert_eq!(data.to_string(), pretty);
+    }
+}
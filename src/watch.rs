@@ -0,0 +1,176 @@
+use crate::config_resolver::ConfigResolver;
+use crate::pipeline::Pipeline;
+use anyhow::{Context, Result};
+use notify::Watcher;
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Quiet period after the last file event before a re-run starts
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Files that should trigger a re-run: the pipeline YAML plus every path its steps read
+///
+/// If the pipeline no longer parses, only the YAML itself is watched so that
+/// fixing it triggers the next run.
+pub fn watch_targets(pipeline_path: &Path) -> Vec<PathBuf> {
+    let mut targets = vec![pipeline_path.to_path_buf()];
+    if let Ok(pipeline) = Pipeline::load_from_file(&pipeline_path.to_string_lossy()) {
+        targets.extend(pipeline.watched_paths(&ConfigResolver::default()));
+    }
+    targets
+}
+
+/// Run `run` once, then again each time a watched file changes, until `shutdown` completes
+///
+/// Parent directories are watched rather than the files themselves so that
+/// editors which save by replacing the file are still picked up. Events that
+/// arrive within `debounce` of each other collapse into one re-run. The watch
+/// targets are recomputed before every run, so editing paths in the pipeline
+/// YAML takes effect immediately. Returns the number of runs performed.
+pub async fn watch_pipeline<F, Fut, S>(
+    pipeline_path: &Path,
+    debounce: Duration,
+    shutdown: S,
+    mut run: F,
+) -> Result<u64>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+    S: Future<Output = ()>,
+{
+    tokio::pin!(shutdown);
+    let mut runs = 0;
+
+    loop {
+        let targets = resolve_targets(&watch_targets(pipeline_path));
+        let (sender, mut events) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+                if let Ok(event) = result {
+                    if !matches!(event.kind, notify::EventKind::Access(_)) {
+                        let _ = sender.send(event.paths);
+                    }
+                }
+            })
+            .context("Failed to create file watcher")?;
+
+        let directories: HashSet<&Path> = targets.iter().filter_map(|t| t.parent()).collect();
+        for directory in directories {
+            watcher
+                .watch(directory, notify::RecursiveMode::NonRecursive)
+                .with_context(|| format!("Failed to watch {}", directory.display()))?;
+        }
+
+        tokio::select! {
+            _ = &mut shutdown => return Ok(runs),
+            _ = run() => runs += 1,
+        }
+
+        // Wait for a change to one of the targets, then for things to settle
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => return Ok(runs),
+                paths = events.recv() => match paths {
+                    Some(paths) if paths.iter().any(|p| targets.contains(p)) => break,
+                    Some(_) => continue,
+                    None => return Ok(runs),
+                },
+            }
+        }
+        while let Ok(Some(_)) = tokio::time::timeout(debounce, events.recv()).await {}
+    }
+}
+
+/// Absolute paths for the targets, resolved through their parent directory
+///
+/// The file itself may not exist yet (or may be mid-replace), but notify
+/// reports events under the canonical directory path.
+fn resolve_targets(targets: &[PathBuf]) -> Vec<PathBuf> {
+    targets
+        .iter()
+        .filter_map(|target| {
+            let parent = match target.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+            let parent = parent.canonicalize().ok()?;
+            Some(parent.join(target.file_name()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_watch_targets_include_reader_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let pipeline_path = dir.path().join("pipeline.yaml");
+        std::fs::write(
+            &pipeline_path,
+            "pipeline:\n  - name: read_file\n    config:\n      path: input.json\n  - name: write_stdout\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            watch_targets(&pipeline_path),
+            vec![pipeline_path.clone(), PathBuf::from("input.json")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_change_triggers_rerun() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.json");
+        std::fs::write(&input, "{}").unwrap();
+        let pipeline_path = dir.path().join("pipeline.yaml");
+        std::fs::write(
+            &pipeline_path,
+            format!(
+                "pipeline:\n  - name: read_file\n    config:\n      path: {}\n",
+                input.display()
+            ),
+        )
+        .unwrap();
+
+        let runs = Arc::new(AtomicU64::new(0));
+        let counter = runs.clone();
+        let watched = runs.clone();
+        let shutdown = async move {
+            while watched.load(Ordering::SeqCst) < 2 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        };
+
+        let toucher = tokio::spawn(async move {
+            // Keep touching until the watcher picks it up; the first write may
+            // land before the watch is registered
+            for i in 0..100 {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                std::fs::write(&input, format!("{{\"n\": {i}}}")).unwrap();
+            }
+        });
+
+        let total = tokio::time::timeout(
+            Duration::from_secs(10),
+            watch_pipeline(&pipeline_path, Duration::from_millis(20), shutdown, || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            }),
+        )
+        .await
+        .expect("watcher never re-ran")
+        .unwrap();
+        toucher.abort();
+
+        assert!(total >= 2);
+    }
+}
@@ -65,7 +65,7 @@ async fn test_batch_memory_strategy() {
 
     // Should create batches based on memory limits
     if let Data::Json(serde_json::Value::Array(batches)) = result.data() {
-        assert!(batches.len() > 0);
+        assert!(!batches.is_empty());
         // Memory strategy should create multiple batches due to size
         println!("Created {} batches with memory strategy", batches.len());
     } else {
@@ -236,6 +236,6 @@ async fn test_batch_config_schema() {
     assert!(schema.is_mapping());
 
     if let serde_yaml::Value::Mapping(map) = schema {
-        assert!(map.contains_key(&serde_yaml::Value::String("properties".to_string())));
+        assert!(map.contains_key(serde_yaml::Value::String("properties".to_string())));
     }
 }
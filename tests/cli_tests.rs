@@ -1,8 +1,718 @@
-// CLI tests placeholder
+// CLI tests
 #[cfg(test)]
 mod tests {
+    use std::process::Command;
+
+    fn oxide_flow() -> Command {
+        Command::new(env!("CARGO_BIN_EXE_oxide_flow"))
+    }
+
     #[test]
     fn placeholder_test() {
         // TODO: Add CLI tests
     }
+
+    #[test]
+    fn test_complete_pipelines_lists_project_pipelines() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("oxiflow.yaml"),
+            r#"
+project: {name: demo, version: "1.0.0", description: demo}
+oxis: {}
+settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis}
+environment: {}
+"#,
+        )
+        .unwrap();
+        let pipelines = dir.path().join("pipelines");
+        std::fs::create_dir_all(pipelines.join("nested")).unwrap();
+        std::fs::write(pipelines.join("orders.yaml"), "pipeline: []\n").unwrap();
+        std::fs::write(pipelines.join("customers.yml"), "pipeline: []\n").unwrap();
+        std::fs::write(pipelines.join("nested/pipeline.yaml"), "pipeline: []\n").unwrap();
+        std::fs::write(pipelines.join("notes.txt"), "not a pipeline\n").unwrap();
+
+        let output = oxide_flow()
+            .arg("__complete-pipelines")
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "customers\nnested\norders\n"
+        );
+    }
+
+    #[test]
+    fn test_complete_pipelines_outside_project_is_silent() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = oxide_flow()
+            .arg("__complete-pipelines")
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        assert!(output.stdout.is_empty());
+    }
+
+    #[test]
+    fn test_strict_validation_fails_on_warnings() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("oxiflow.yaml"),
+            r#"
+project: {name: demo, version: "1.0.0", description: demo}
+oxis: {}
+settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis}
+environment: {}
+"#,
+        )
+        .unwrap();
+        let pipelines = dir.path().join("pipelines");
+        std::fs::create_dir_all(&pipelines).unwrap();
+        // No metadata section, which is only a warning
+        std::fs::write(
+            pipelines.join("orders.yaml"),
+            "pipeline:\n  - name: read_stdin\n    id: reader\n",
+        )
+        .unwrap();
+
+        let validate = |extra: &[&str]| {
+            oxide_flow()
+                .args(["validate", "orders", "--format", "junit"])
+                .args(extra)
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+        };
+
+        let lenient = validate(&[]);
+        assert!(lenient.status.success());
+        let xml = String::from_utf8(lenient.stdout).unwrap();
+        assert!(xml.contains(r#"<testsuite name="orders""#), "{xml}");
+        assert!(xml.contains(r#"failures="0""#), "{xml}");
+
+        let strict = validate(&["--strict"]);
+        assert!(!strict.status.success());
+        let xml = String::from_utf8(strict.stdout).unwrap();
+        assert!(xml.contains(r#"<failure type="pipeline-warning""#), "{xml}");
+
+        let test = |extra: &[&str]| {
+            oxide_flow()
+                .args(["pipeline", "test", "orders"])
+                .args(extra)
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+        };
+        let lenient = test(&[]);
+        assert!(lenient.status.success());
+        let out = String::from_utf8(lenient.stdout).unwrap();
+        assert!(out.contains("Pipeline is ready for execution"), "{out}");
+
+        let strict = test(&["--strict"]);
+        assert!(!strict.status.success());
+        let out = String::from_utf8(strict.stdout).unwrap();
+        assert!(
+            out.contains("Warnings (strict: counted as issues)"),
+            "{out}"
+        );
+        assert!(
+            out.contains("strict mode escalated 1 warning(s) to errors"),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn test_pipeline_state_block_overrides_project_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("oxiflow.yaml"),
+            r#"
+project: {name: demo, version: "1.0.0", description: demo}
+oxis: {}
+settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis}
+environment: {}
+"#,
+        )
+        .unwrap();
+        let pipelines = dir.path().join("pipelines");
+        std::fs::create_dir_all(&pipelines).unwrap();
+        let pipeline = |state: &str| {
+            format!(
+                "metadata: {{name: orders}}\nstate: {state}\npipeline:\n  - name: parse_json\n    id: parse\n    config: {{content: '[1, 2]'}}\n"
+            )
+        };
+        std::fs::write(
+            pipelines.join("orders.yaml"),
+            pipeline("{backend: file, file: {base_path: dev_state}}"),
+        )
+        .unwrap();
+
+        let run = oxide_flow()
+            .args(["run", "orders"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(
+            run.status.success(),
+            "{}",
+            String::from_utf8_lossy(&run.stderr)
+        );
+        assert!(dir.path().join("dev_state/states/orders.json").exists());
+
+        // The run history tells `state show` where the state went
+        let show = oxide_flow()
+            .args(["state", "show", "orders", "--json"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(show.status.success());
+        assert!(String::from_utf8(show.stdout)
+            .unwrap()
+            .contains("\"pipeline_id\": \"orders\""));
+
+        std::fs::write(pipelines.join("orders.yaml"), pipeline("{backend: redis}")).unwrap();
+        let validate = oxide_flow()
+            .args(["validate", "orders", "--format", "json"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(!validate.status.success());
+        let report = String::from_utf8(validate.stdout).unwrap();
+        assert!(
+            report.contains("supported backends are: file, memory"),
+            "{report}"
+        );
+    }
+
+    #[test]
+    fn test_state_verify_and_repair_corrupted_state() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("oxiflow.yaml"),
+            r#"
+project: {name: demo, version: "1.0.0", description: demo}
+oxis: {}
+settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis}
+environment: {}
+"#,
+        )
+        .unwrap();
+        let pipelines = dir.path().join("pipelines");
+        std::fs::create_dir_all(&pipelines).unwrap();
+        std::fs::write(
+            pipelines.join("orders.yaml"),
+            "metadata: {name: orders}\nstate: {backend: file}\npipeline:\n  - name: parse_json\n    id: parse\n    config: {content: '[1, 2]'}\n",
+        )
+        .unwrap();
+        let state = |args: &[&str]| {
+            let output = oxide_flow()
+                .arg("state")
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .unwrap();
+            (
+                output.status.success(),
+                String::from_utf8(output.stdout).unwrap(),
+            )
+        };
+
+        let run = oxide_flow()
+            .args(["run", "orders"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(run.status.success());
+        let state_file = dir.path().join(".oxiflow/state/states/orders.json");
+        let backup_dir = dir.path().join(".oxiflow/state/backups/orders");
+        std::fs::create_dir_all(&backup_dir).unwrap();
+        std::fs::copy(&state_file, backup_dir.join("backup_good.json")).unwrap();
+        assert!(state(&["verify"]).0);
+
+        std::fs::write(&state_file, "{ not json").unwrap();
+        let (ok, out) = state(&["verify"]);
+        assert!(!ok);
+        assert!(out.contains("Overall health: 0%"), "{out}");
+        assert!(out.contains("Corrupted:"), "{out}");
+        let (ok, out) = state(&["verify", "orders"]);
+        assert!(!ok);
+        assert!(out.contains("State for orders is corrupted"), "{out}");
+
+        let (ok, out) = state(&["backups", "orders"]);
+        assert!(ok);
+        assert!(out.contains("backup_good"), "{out}");
+
+        let (ok, out) = state(&["repair", "orders", "--dry-run"]);
+        assert!(ok);
+        assert!(
+            out.contains("would be restored from the newest backup"),
+            "{out}"
+        );
+        assert_eq!(std::fs::read_to_string(&state_file).unwrap(), "{ not json");
+
+        let (ok, out) = state(&["repair", "orders"]);
+        assert!(ok, "{out}");
+        assert!(out.contains("Restored from backup: backup_good"), "{out}");
+        assert!(state(&["verify", "orders"]).0);
+
+        // Without a readable backup the repair needs a human
+        std::fs::write(&state_file, "{ not json").unwrap();
+        std::fs::remove_dir_all(&backup_dir).unwrap();
+        let (ok, out) = state(&["repair", "orders"]);
+        assert!(!ok);
+        assert!(out.contains("No backups available"), "{out}");
+        assert!(out.contains("manual intervention required"), "{out}");
+    }
+
+    #[test]
+    fn test_state_export_all_and_import_all() {
+        let project = |dir: &std::path::Path| {
+            std::fs::write(
+                dir.join("oxiflow.yaml"),
+                r#"
+project: {name: demo, version: "1.0.0", description: demo}
+oxis: {}
+settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis}
+environment: {}
+"#,
+            )
+            .unwrap();
+        };
+        let (source, target) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        project(source.path());
+        project(target.path());
+        let pipelines = source.path().join("pipelines");
+        std::fs::create_dir_all(&pipelines).unwrap();
+        std::fs::write(
+            pipelines.join("orders.yaml"),
+            "metadata: {name: orders}\nstate: {backend: file}\npipeline:\n  - name: parse_json\n    id: parse\n    config: {content: '[1, 2]'}\n",
+        )
+        .unwrap();
+        let state = |dir: &std::path::Path, args: &[&str]| {
+            let output = oxide_flow()
+                .arg("state")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            (
+                output.status.success(),
+                String::from_utf8(output.stdout).unwrap(),
+            )
+        };
+
+        let run = oxide_flow()
+            .args(["run", "orders"])
+            .current_dir(source.path())
+            .output()
+            .unwrap();
+        assert!(run.status.success());
+        let archive = target.path().join("state.json.gz");
+        let (ok, out) = state(
+            source.path(),
+            &["export-all", "--output", archive.to_str().unwrap()],
+        );
+        assert!(ok, "{out}");
+
+        let import = ["import-all", "--input", archive.to_str().unwrap()];
+        let (ok, out) = state(target.path(), &import);
+        assert!(ok, "{out}");
+        assert!(out.contains("Imported 1 pipeline state(s)"), "{out}");
+        let (ok, out) = state(target.path(), &["show", "orders"]);
+        assert!(ok, "{out}");
+
+        let (ok, out) = state(target.path(), &import);
+        assert!(ok, "{out}");
+        assert!(out.contains("Imported 0 pipeline state(s)"), "{out}");
+        assert!(out.contains("Skipped 1 pipeline(s)"), "{out}");
+        let (ok, out) = state(target.path(), &[&import[..], &["--force"]].concat());
+        assert!(ok, "{out}");
+        assert!(out.contains("Imported 1 pipeline state(s)"), "{out}");
+    }
+
+    #[test]
+    fn test_worker_list_locks_shows_holders() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("oxiflow.yaml"),
+            r#"
+project: {name: demo, version: "1.0.0", description: demo}
+oxis: {}
+settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis}
+environment: {}
+"#,
+        )
+        .unwrap();
+        let pipelines = dir.path().join("pipelines");
+        std::fs::create_dir_all(&pipelines).unwrap();
+        std::fs::write(
+            pipelines.join("orders.yaml"),
+            "metadata: {name: orders}\nstate: {backend: file}\npipeline:\n  - name: parse_json\n    id: parse\n    config: {content: '[1, 2]'}\n",
+        )
+        .unwrap();
+        let run = oxide_flow()
+            .args(["run", "orders"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(run.status.success());
+        let list = |extra: &[&str]| {
+            let output = oxide_flow()
+                .args(["worker", "list", "--locks"])
+                .args(extra)
+                .current_dir(dir.path())
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap()
+        };
+
+        let out = list(&[]);
+        assert!(out.contains("No pipeline locks held"), "{out}");
+
+        // A lock held by this (live) test process
+        let now = chrono::Utc::now();
+        std::fs::write(
+            dir.path().join(".oxiflow/state/locks/orders.lock"),
+            serde_json::json!({
+                "pipeline_id": "orders",
+                "worker_id": "worker_busy",
+                "locked_at": now - chrono::Duration::seconds(90),
+                "expires_at": now + chrono::Duration::minutes(5),
+                "lock_version": 1,
+                "lease_ms": 60000,
+                "pid": std::process::id(),
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let out = list(&[]);
+        assert!(out.contains("Held Locks (1)"), "{out}");
+        assert!(out.contains("worker_busy"), "{out}");
+        let holders: serde_json::Value = serde_json::from_str(&list(&["--json"])).unwrap();
+        assert_eq!(holders[0]["pipeline_id"], "orders");
+        assert!(holders[0]["held_secs"].as_i64().unwrap() >= 90);
+    }
+
+    #[test]
+    fn test_project_env_file_feeds_pipeline_references() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("oxiflow.yaml"),
+            r#"
+project: {name: demo, version: "1.0.0", description: demo}
+oxis: {}
+settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis, env_file: .env}
+environment: {}
+"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join(".env"), "GREETING=from-dotenv\n").unwrap();
+        let pipelines = dir.path().join("pipelines");
+        std::fs::create_dir_all(&pipelines).unwrap();
+        std::fs::write(
+            pipelines.join("hello.yaml"),
+            "pipeline:\n  - name: parse_json\n    id: parse\n    config: {content: '{\"greeting\": \"${GREETING}\"}'}\n  - name: write_stdout\n    id: out\n",
+        )
+        .unwrap();
+
+        let run = |greeting: Option<&str>| {
+            let mut command = oxide_flow();
+            command
+                .args(["run", "hello"])
+                .current_dir(dir.path())
+                .env_remove("GREETING");
+            if let Some(greeting) = greeting {
+                command.env("GREETING", greeting);
+            }
+            String::from_utf8(command.output().unwrap().stdout).unwrap()
+        };
+
+        assert!(run(None).contains("from-dotenv"));
+        assert!(run(Some("from-shell")).contains("from-shell"));
+    }
+
+    #[test]
+    fn test_project_defaults_apply_to_runs_tests_and_effective_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("oxiflow.yaml"),
+            r#"
+project: {name: demo, version: "1.0.0", description: demo}
+oxis: {}
+settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis}
+environment: {}
+defaults:
+  read_file: {path: data.json}
+  format_csv: {delimiter: ";", headers: false}
+"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("data.json"), r#"[{"a": 1, "b": 2}]"#).unwrap();
+        let pipelines = dir.path().join("pipelines");
+        std::fs::create_dir_all(&pipelines).unwrap();
+        std::fs::write(
+            pipelines.join("report.yaml"),
+            r#"
+metadata: {name: report}
+defaults:
+  format_csv: {delimiter: "|"}
+pipeline:
+  - name: read_file
+    id: read
+  - name: parse_json
+    id: parse
+  - name: format_csv
+    id: csv
+    config: {headers: true}
+  - name: write_stdout
+    id: out
+"#,
+        )
+        .unwrap();
+        let run = |args: &[&str]| {
+            oxide_flow()
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+        };
+
+        // read_file's required path comes from the project's defaults, and
+        // the pipeline's own defaults win over the project's delimiter
+        assert!(run(&["pipeline", "test", "report"]).status.success());
+
+        let output = run(&["run", "report"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("a|b\n1|2"));
+
+        let output = run(&[
+            "pipeline",
+            "info",
+            "report",
+            "--show-effective-config",
+            "csv",
+        ]);
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "delimiter: '|'\nheaders: true\n"
+        );
+    }
+
+    #[test]
+    fn test_completions_command_prints_script() {
+        let output = oxide_flow().args(["completions", "zsh"]).output().unwrap();
+
+        assert!(output.status.success());
+        let script = String::from_utf8(output.stdout).unwrap();
+        assert!(script.starts_with("#compdef oxide_flow"));
+    }
+
+    #[test]
+    fn test_init_example_template_runs_end_to_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("demo");
+
+        let output = oxide_flow()
+            .args([
+                "init",
+                "--name",
+                "demo",
+                "--template",
+                "example",
+                "--directory",
+            ])
+            .arg(&project)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("oxide_flow run hello"));
+        assert!(project.join(".oxiflow/.gitignore").exists());
+
+        let output = oxide_flow()
+            .args(["run", "hello"])
+            .current_dir(&project)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stdout)
+        );
+
+        let csv = std::fs::read_to_string(project.join("output/hello.csv")).unwrap();
+        // Header plus a dozen records
+        assert_eq!(csv.lines().count(), 13);
+        assert!(csv.contains("Grace Hopper"));
+    }
+
+    #[test]
+    fn test_init_refuses_non_empty_directory_without_force() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "keep me").unwrap();
+
+        let init = |force: bool| {
+            let mut command = oxide_flow();
+            command
+                .args(["init", "--name", "demo", "--directory"])
+                .arg(dir.path());
+            if force {
+                command.arg("--force");
+            }
+            command.output().unwrap()
+        };
+
+        let refused = init(false);
+        assert!(!refused.status.success());
+        assert!(String::from_utf8_lossy(&refused.stderr).contains("--force"));
+        assert!(!dir.path().join("oxiflow.yaml").exists());
+
+        assert!(init(true).status.success());
+        assert!(dir.path().join("oxiflow.yaml").exists());
+        assert!(dir.path().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_init_list_templates() {
+        let output = oxide_flow()
+            .args(["init", "--list-templates"])
+            .output()
+            .unwrap();
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        for template in ["minimal", "example", "etl"] {
+            assert!(stdout.contains(template));
+        }
+    }
+
+    #[test]
+    fn test_oxi_list_and_describe() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = oxide_flow()
+            .args(["oxi", "list"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+
+        assert!(list.status.success());
+        let stdout = String::from_utf8(list.stdout).unwrap();
+        for name in oxide_flow::oxis::BUILTIN_OXIS {
+            assert!(
+                stdout
+                    .lines()
+                    .any(|line| line.trim_start().starts_with(name)),
+                "{name} missing from:\n{stdout}"
+            );
+        }
+
+        let describe = oxide_flow()
+            .args(["oxi", "describe", "read_file"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+
+        assert!(describe.status.success());
+        let stdout = String::from_utf8(describe.stdout).unwrap();
+        let row = |property: &str| {
+            stdout
+                .lines()
+                .find(|line| line.trim_start().starts_with(property))
+                .unwrap_or_else(|| panic!("{property} missing from:\n{stdout}"))
+                .split_whitespace()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(row("path")[..3], ["path", "string", "yes"]);
+        assert_eq!(row("encoding")[..3], ["encoding", "string", "no"]);
+        assert!(stdout.contains("- name: read_file"));
+
+        let unknown = oxide_flow()
+            .args(["oxi", "describe", "nope"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(!unknown.status.success());
+    }
+
+    #[test]
+    fn test_pipeline_export_bundle_and_import() {
+        let project = |dir: &std::path::Path| {
+            std::fs::write(
+                dir.join("oxiflow.yaml"),
+                r#"
+project: {name: demo, version: "1.0.0", description: demo}
+oxis: {}
+settings: {output_dir: output, pipeline_dir: pipelines, oxis_dir: oxis}
+environment: {}
+"#,
+            )
+            .unwrap();
+        };
+        let source = tempfile::tempdir().unwrap();
+        project(source.path());
+        std::fs::create_dir_all(source.path().join("pipelines")).unwrap();
+        std::fs::write(
+            source.path().join("pipelines/orders.yaml"),
+            "metadata: {name: orders}\npipeline:\n  - name: read_file\n    id: read\n    config: {path: '${SOURCE_DIR}/orders.json'}\n  - name: write_file\n    config: {path: '${OUT_DIR:-output}/orders.json'}\n",
+        )
+        .unwrap();
+
+        let export = oxide_flow()
+            .args(["pipeline", "export", "orders", "--bundle"])
+            .current_dir(source.path())
+            .output()
+            .unwrap();
+        assert!(
+            export.status.success(),
+            "{}",
+            String::from_utf8_lossy(&export.stderr)
+        );
+        let bundle = source.path().join("orders.tar.gz");
+        let (manifest, _) = oxide_flow::pipeline_bundle::read_bundle(&bundle).unwrap();
+        let vars: Vec<(&str, bool)> = manifest
+            .env_vars
+            .iter()
+            .map(|v| (v.name.as_str(), v.required()))
+            .collect();
+        assert_eq!(vars, vec![("OUT_DIR", false), ("SOURCE_DIR", true)]);
+        assert_eq!(manifest.oxis, vec!["read_file", "write_file"]);
+
+        // The target environment must provide SOURCE_DIR
+        let target = tempfile::tempdir().unwrap();
+        project(target.path());
+        let import = || {
+            let mut command = oxide_flow();
+            command
+                .args(["pipeline", "import"])
+                .arg(&bundle)
+                .current_dir(target.path());
+            command
+        };
+        let missing = import().env_remove("SOURCE_DIR").output().unwrap();
+        assert!(!missing.status.success());
+        assert!(String::from_utf8_lossy(&missing.stderr)
+            .contains("Environment variable 'SOURCE_DIR' is not set"));
+        assert!(!target.path().join("pipelines/orders.yaml").exists());
+
+        let imported = import().env("SOURCE_DIR", "data").output().unwrap();
+        assert!(
+            imported.status.success(),
+            "{}",
+            String::from_utf8_lossy(&imported.stderr)
+        );
+        assert_eq!(
+            std::fs::read_to_string(target.path().join("pipelines/orders.yaml")).unwrap(),
+            std::fs::read_to_string(source.path().join("pipelines/orders.yaml")).unwrap()
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use oxide_flow::config::{process_env_vars_in_yaml, substitute_env_vars, Config};
+use oxide_flow::config::{load_env_file, process_env_vars_in_yaml, substitute_env_vars, Config};
 use std::env;
 
 #[test]
@@ -27,6 +27,28 @@ fn test_env_var_substitution() {
     env::remove_var("NESTED_VAR");
 }
 
+#[test]
+fn test_env_file_values_resolve_but_do_not_override_real_env_vars() {
+    let dir = tempfile::tempdir().unwrap();
+    let env_file = dir.path().join(".env");
+    std::fs::write(
+        &env_file,
+        "# local settings\nDOTENV_API_HOST=api.internal\nDOTENV_REGION=\"eu-west\"\n",
+    )
+    .unwrap();
+    env::set_var("DOTENV_REGION", "us-east");
+
+    assert_eq!(load_env_file(&env_file).unwrap(), 1);
+    let result = substitute_env_vars("https://${DOTENV_API_HOST}/${DOTENV_REGION}").unwrap();
+    assert_eq!(result, "https://api.internal/us-east");
+
+    assert!(load_env_file(dir.path().join("missing.env")).is_err());
+
+    // Clean up
+    env::remove_var("DOTENV_API_HOST");
+    env::remove_var("DOTENV_REGION");
+}
+
 #[test]
 fn test_yaml_env_var_processing() {
     // Set test environment variable
@@ -0,0 +1,36 @@
+"""Plugin fixture: upper-cases `field` in every record, tagged with its pid.
+
+Config `mode: fail` answers with an error and `mode: hang` never answers.
+"""
+import json
+import os
+import sys
+import time
+
+if "--describe" in sys.argv:
+    print(json.dumps({
+        "name": "upper",
+        "config_schema": {
+            "description": "Upper-case one field",
+            "properties": {"field": {"type": "string"}},
+            "required": ["field"],
+        },
+    }))
+    sys.exit(0)
+
+for line in sys.stdin:
+    request = json.loads(line)
+    config = request["config"]
+    mode = config.get("mode")
+    if mode == "fail":
+        response = {"error": "refusing to upper-case"}
+    elif mode == "hang":
+        time.sleep(60)
+        continue
+    else:
+        field = config["field"]
+        response = {"data": [
+            dict(record, **{field: record[field].upper(), "pid": os.getpid()})
+            for record in request["data"]
+        ]}
+    print(json.dumps(response), flush=True)
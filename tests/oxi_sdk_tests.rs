@@ -1,6 +1,10 @@
+use oxide_flow::config_resolver::ConfigResolver;
 use oxide_flow::oxis::prelude::*;
+use oxide_flow::oxis::registry::OxiRegistry;
+use oxide_flow::pipeline::Pipeline;
 use oxide_flow::Oxi;
 use serde_json::json;
+use std::sync::Arc;
 
 // Test Oxi for testing the SDK foundation
 struct TestOxi {
@@ -253,3 +257,410 @@ async fn test_oxi_data_schema_access() {
     let _text_schema = text_data.schema();
     // Text data should also have schema
 }
+
+#[test]
+fn test_default_describe() {
+    let oxi = TestOxi::new(ProcessingLimits {
+        supported_input_types: vec![OxiDataType::Json],
+        ..ProcessingLimits::default()
+    });
+
+    let descriptor = oxi.describe();
+    assert_eq!(descriptor.name, "test_oxi");
+    assert_eq!(descriptor.input_types, vec![OxiDataType::Json]);
+    // Output types default to the accepted input types
+    assert_eq!(descriptor.output_types, vec![OxiDataType::Json]);
+    assert!(descriptor.config_schema.properties.is_empty());
+    assert!(descriptor.example_config.values.is_empty());
+}
+
+#[test]
+fn test_builtin_descriptors() {
+    for name in oxide_flow::oxis::BUILTIN_OXIS {
+        let oxi = oxide_flow::oxis::builtin(name).expect("built-in Oxi should resolve");
+        assert_eq!(oxi.describe().name, *name);
+    }
+
+    let descriptor = oxide_flow::oxis::builtin("read_file").unwrap().describe();
+    assert_eq!(descriptor.config_schema.required, vec!["path".to_string()]);
+    assert_eq!(
+        descriptor.output_types,
+        vec![OxiDataType::Json, OxiDataType::Text, OxiDataType::Binary]
+    );
+    assert_eq!(
+        descriptor.example_config.values["encoding"],
+        serde_yaml::Value::String("utf-8".to_string())
+    );
+    assert_eq!(
+        descriptor.example_config.values["path"],
+        serde_yaml::Value::String("<path>".to_string())
+    );
+
+    let json = serde_json::to_value(&descriptor).unwrap();
+    assert_eq!(json["schema_strategy"], "Infer");
+    assert!(descriptor.format_card().contains("path: string (required)"));
+}
+
+#[tokio::test]
+async fn test_registered_oxi_runs_in_pipeline() {
+    let mut registry = OxiRegistry::with_builtins();
+    registry.register("test_oxi", || {
+        Arc::new(TestOxi::new(ProcessingLimits::default()))
+    });
+    assert_eq!(registry.get("test_oxi").unwrap().category, "custom");
+
+    let mut pipeline: Pipeline = serde_yaml::from_str(
+        r#"
+pipeline:
+  - name: parse_json
+    config:
+      content: '[{"id": 1}]'
+  - name: test_oxi
+"#,
+    )
+    .unwrap();
+    // Without the registry the step's Oxi is unknown
+    assert!(pipeline.pipeline[1].resolve_oxi().is_none());
+
+    pipeline.attach_registry(&registry);
+    let result = pipeline
+        .execute_with_retries(OxiData::empty(), &ConfigResolver::new())
+        .await;
+    assert!(result.success, "{:?}", result.step_results);
+    assert_eq!(
+        result.final_data.unwrap().data().as_json().unwrap(),
+        &json!([{"id": 1}])
+    );
+}
+
+#[test]
+fn test_config_key_inspection() {
+    let mut config = OxiConfig::from_yaml(
+        serde_yaml::from_str(
+            r#"
+            source:
+              auth:
+                user: admin
+                token: secret
+              url: https://example.com
+            retries: 3
+            tags: [a, b]
+            "#,
+        )
+        .unwrap(),
+    );
+
+    assert_eq!(config.list_all_keys(), vec!["retries", "source", "tags"]);
+    assert!(config.has_key("source"));
+    assert!(!config.has_key("source.url"));
+
+    assert_eq!(
+        config.list_nested_keys(""),
+        vec![
+            "retries",
+            "source.auth.token",
+            "source.auth.user",
+            "source.url",
+            "tags"
+        ]
+    );
+    assert_eq!(
+        config.list_nested_keys("source.auth"),
+        vec!["source.auth.token", "source.auth.user"]
+    );
+    assert!(config.list_nested_keys("sour").is_empty());
+
+    assert_eq!(config.remove("retries"), Some(serde_yaml::Value::from(3)));
+    assert_eq!(config.remove("retries"), None);
+
+    config.retain(|_, value| value.is_mapping());
+    assert_eq!(config.list_all_keys(), vec!["source"]);
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+#[serde(default)]
+struct HttpOptions {
+    url: String,
+    retries: u32,
+    verify_tls: bool,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            retries: 3,
+            verify_tls: true,
+        }
+    }
+}
+
+#[test]
+fn test_config_deserialize_into_struct() {
+    let config = OxiConfig::builder()
+        .set("url", "https://example.com")
+        .set("verify_tls", false)
+        .set("unrelated", 1)
+        .build();
+
+    let options: HttpOptions = config.deserialize_into().unwrap();
+    assert_eq!(
+        options,
+        HttpOptions {
+            url: "https://example.com".to_string(),
+            retries: 3,
+            verify_tls: false,
+        }
+    );
+
+    let bad = OxiConfig::builder().set("retries", "many").build();
+    let err = bad.deserialize_into::<HttpOptions>().unwrap_err();
+    let message = err.to_string();
+    assert!(message.starts_with("Validation error: Invalid configuration"));
+    assert!(message.contains("retries"), "{message}");
+}
+
+#[test]
+fn test_config_get_path() {
+    let config = OxiConfig::from_yaml(
+        serde_yaml::from_str(
+            r#"
+            source:
+              auth:
+                user: admin
+            targets:
+              - url: https://a.example.com
+              - url: https://b.example.com
+            "dotted.key": literal
+            "#,
+        )
+        .unwrap(),
+    );
+
+    assert_eq!(
+        config.get_path("source.auth.user"),
+        Some(&serde_yaml::Value::from("admin"))
+    );
+    assert_eq!(
+        config.get_path("targets.1.url"),
+        Some(&serde_yaml::Value::from("https://b.example.com"))
+    );
+    assert_eq!(
+        config.get_path("dotted.key"),
+        Some(&serde_yaml::Value::from("literal"))
+    );
+    assert!(config.get_path("source.auth.password").is_none());
+    assert!(config.get_path("source.auth.user.name").is_none());
+    assert!(config.get_path("targets.x").is_none());
+}
+
+#[test]
+fn test_config_merge_three_and_precedence_trace() {
+    use oxide_flow::types::ConfigLevel;
+
+    let yaml = |text: &str| OxiConfig::from_yaml(serde_yaml::from_str(text).unwrap());
+    let global = yaml("{timeout: 30s, retries: 3, headers: {agent: oxide, auth: none}}");
+    let pipeline = yaml("{retries: 5, headers: {auth: token}, format: json}");
+    let step = yaml("{format: csv, path: out.csv}");
+
+    let merged = OxiConfig::merge_three(&global, &pipeline, &step);
+    assert_eq!(merged.get_string("timeout").unwrap(), "30s");
+    assert_eq!(merged.get_i64("retries").unwrap(), 5);
+    assert_eq!(merged.get_string("format").unwrap(), "csv");
+    assert_eq!(merged.get_string("path").unwrap(), "out.csv");
+    assert_eq!(
+        merged.get_path("headers.agent"),
+        Some(&serde_yaml::Value::from("oxide"))
+    );
+    assert_eq!(
+        merged.get_path("headers.auth"),
+        Some(&serde_yaml::Value::from("token"))
+    );
+
+    let trace = OxiConfig::precedence_trace("format", &global, &pipeline, &step);
+    assert_eq!(trace.source, Some(ConfigLevel::Step));
+    assert_eq!(trace.value, Some(serde_yaml::Value::from("csv")));
+    assert_eq!(
+        trace.levels,
+        [
+            (
+                ConfigLevel::PipelineDefaults,
+                serde_yaml::Value::from("json")
+            ),
+            (ConfigLevel::Step, serde_yaml::Value::from("csv")),
+        ]
+    );
+
+    let trace = OxiConfig::precedence_trace("headers.agent", &global, &pipeline, &step);
+    assert_eq!(trace.source, Some(ConfigLevel::GlobalDefaults));
+    assert_eq!(
+        OxiConfig::precedence_trace("retries", &global, &pipeline, &step).source,
+        Some(ConfigLevel::PipelineDefaults)
+    );
+
+    let missing = OxiConfig::precedence_trace("missing", &global, &pipeline, &step);
+    assert_eq!(missing.source, None);
+    assert!(missing.levels.is_empty());
+
+    // A step value replacing the whole mapping hides the defaults' entries
+    let step = yaml("{headers: none}");
+    let trace = OxiConfig::precedence_trace("headers.auth", &global, &pipeline, &step);
+    assert_eq!(trace.source, None);
+    assert_eq!(trace.value, None);
+    assert_eq!(trace.levels.len(), 2);
+}
+
+#[test]
+fn test_config_get_duration() {
+    let config = OxiConfig::builder()
+        .set("timeout", "30s")
+        .set("poll", "500ms")
+        .set("window", "5m")
+        .set("retry_after", 2)
+        .set("bad", "soon")
+        .set(
+            "nested",
+            serde_yaml::from_str::<serde_yaml::Value>("{ttl: 1h}").unwrap(),
+        )
+        .build();
+
+    use std::time::Duration;
+    assert_eq!(
+        config.get_duration("timeout").unwrap(),
+        Duration::from_secs(30)
+    );
+    assert_eq!(
+        config.get_duration("poll").unwrap(),
+        Duration::from_millis(500)
+    );
+    assert_eq!(
+        config.get_duration("window").unwrap(),
+        Duration::from_secs(300)
+    );
+    assert_eq!(
+        config.get_duration("retry_after").unwrap(),
+        Duration::from_secs(2)
+    );
+    assert_eq!(
+        config.get_duration("nested.ttl").unwrap(),
+        Duration::from_secs(3600)
+    );
+
+    let message = config.get_duration("bad").unwrap_err().to_string();
+    assert!(message.contains("'bad'"), "{message}");
+    assert!(message.contains("\"soon\""), "{message}");
+    assert!(message.contains("500ms"), "{message}");
+
+    let missing = config.get_duration("missing").unwrap_err().to_string();
+    assert_eq!(missing, "Missing required field: missing");
+}
+
+#[derive(Debug, PartialEq)]
+enum Compression {
+    None,
+    Gzip,
+}
+
+impl std::str::FromStr for Compression {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Compression::None),
+            "gzip" => Ok(Compression::Gzip),
+            _ => Err(()),
+        }
+    }
+}
+
+#[test]
+fn test_config_get_enum() {
+    const ALLOWED: &[&str] = &["none", "gzip"];
+    let config = OxiConfig::builder()
+        .set("compression", "gzip")
+        .set("fallback", "none")
+        .set("bad", "zstd")
+        .set("numeric", 7)
+        .build();
+
+    assert_eq!(
+        config
+            .get_enum::<Compression>("compression", ALLOWED)
+            .unwrap(),
+        Compression::Gzip
+    );
+    assert_eq!(
+        config.get_enum::<Compression>("fallback", ALLOWED).unwrap(),
+        Compression::None
+    );
+
+    let message = config
+        .get_enum::<Compression>("bad", ALLOWED)
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        message,
+        "Validation error: Property 'bad' must be one of: none, gzip, got \"zstd\""
+    );
+    let message = config
+        .get_enum::<Compression>("numeric", ALLOWED)
+        .unwrap_err()
+        .to_string();
+    assert!(message.contains("got 7"), "{message}");
+    assert!(config.get_enum::<Compression>("missing", ALLOWED).is_err());
+}
+
+#[test]
+fn test_config_builder() {
+    let config = OxiConfig::builder()
+        .set("path", "out.csv")
+        .set("append", true)
+        .set("columns", vec!["id", "name"])
+        .build();
+
+    assert_eq!(config.get_string("path").unwrap(), "out.csv");
+    assert!(config.get_bool("append").unwrap());
+    assert_eq!(config.get_sequence("columns").unwrap().len(), 2);
+    assert_eq!(config.list_all_keys(), vec!["append", "columns", "path"]);
+}
+
+#[test]
+fn test_csv_helpers_quote_special_characters() {
+    let record = Data::from_json(json!({
+        "id": 7,
+        "note": "said \"hi\", then left",
+        "address": "1 Main St\nSpringfield",
+        "missing": null,
+        "tags": ["a", "b"]
+    }));
+    let schema = OxiData::from_json(record.to_json().unwrap())
+        .schema()
+        .clone();
+    let headers = schema.field_names();
+
+    assert_eq!(schema.csv_header(','), "address,id,missing,note,tags");
+    assert_eq!(
+        record.to_csv_record(&headers, ',').unwrap(),
+        "\"1 Main St\nSpringfield\",7,,\"said \"\"hi\"\", then left\",\"[\"\"a\"\",\"\"b\"\"]\""
+    );
+
+    // With a different delimiter, commas no longer force quoting
+    let row = Data::from_json(json!({"a": "x,y", "b": "p;q"}));
+    let headers = vec!["a".to_string(), "b".to_string()];
+    assert_eq!(row.to_csv_record(&headers, ';').unwrap(), "x,y;\"p;q\"");
+
+    // The output parses back to the original cells
+    let csv_text = format!(
+        "{}\n{}\n",
+        schema.csv_header(','),
+        record.to_csv_record(&schema.field_names(), ',').unwrap()
+    );
+    let mut reader = ::csv::Reader::from_reader(csv_text.as_bytes());
+    let parsed = reader.records().next().unwrap().unwrap();
+    assert_eq!(&parsed[0], "1 Main St\nSpringfield");
+    assert_eq!(&parsed[3], "said \"hi\", then left");
+
+    assert!(Data::from_text("plain")
+        .to_csv_record(&headers, ',')
+        .is_err());
+}
@@ -0,0 +1,84 @@
+//! Bytes allocated while large payloads are copied between pipeline steps
+//!
+//! A counting global allocator measures each operation. Everything runs in a
+//! single test so parallel tests cannot inflate the counts.
+
+use oxide_flow::types::{Data, OxiData};
+use serde_json::json;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const PAYLOAD_BYTES: usize = 100 * 1024 * 1024;
+
+/// Run `f` and return its result with the bytes allocated meanwhile
+fn allocated_by<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    let result = f();
+    (result, ALLOCATED.load(Ordering::SeqCst) - before)
+}
+
+#[test]
+fn test_large_payload_allocations() {
+    // Schema inference must not copy the payload
+    let (data, bytes) = allocated_by(|| OxiData::from_binary(vec![7u8; PAYLOAD_BYTES]));
+    assert!(bytes < PAYLOAD_BYTES + 1024 * 1024, "{bytes} bytes");
+
+    // An owned copy, which is what every clone used to cost
+    let (copy, copied) = allocated_by(|| data.data().to_binary().unwrap());
+    assert!(copied >= PAYLOAD_BYTES, "{copied} bytes");
+    drop(copy);
+
+    // Handing the payload to another step shares it instead
+    let (shared, shared_bytes) = allocated_by(|| (data.share(), data.clone()));
+    assert!(shared_bytes < 64 * 1024, "{shared_bytes} bytes");
+    assert_eq!(shared.0.data().as_binary().unwrap().len(), PAYLOAD_BYTES);
+
+    let (bytes, accessed) = allocated_by(|| data.data().shared_binary().unwrap());
+    assert!(accessed < 1024, "{accessed} bytes");
+    assert!(
+        matches!(data.data(), Data::Binary(payload) if std::sync::Arc::ptr_eq(payload, &bytes))
+    );
+
+    // Measuring JSON walks the tree rather than rendering it
+    let records: Vec<_> = (0..100_000)
+        .map(|i| json!({"id": i, "name": format!("record-{i}"), "score": 0.5, "tags": ["a", null, true]}))
+        .collect();
+    let json = OxiData::with_schema(
+        Data::Json(serde_json::Value::Array(records)),
+        Default::default(),
+    );
+    let (estimate, measured) = allocated_by(|| json.estimated_memory_usage());
+    assert!(measured < 1024, "{measured} bytes");
+    assert_eq!(
+        estimate,
+        json.data().as_json().unwrap().to_string().len() * 2
+    );
+}
@@ -0,0 +1,127 @@
+use oxide_flow::config_resolver::ConfigResolver;
+use oxide_flow::oxis::prelude::*;
+use oxide_flow::pipeline::builder::PipelineBuilder;
+use oxide_flow::pipeline::Pipeline;
+use oxide_flow::state::{StateManager, StepStatus};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+/// An Oxi backed by a closure over the input's JSON
+struct FnOxi<F> {
+    name: String,
+    transform: F,
+}
+
+impl<F> FnOxi<F>
+where
+    F: Fn(serde_json::Value) -> Result<serde_json::Value, OxiError> + Send + Sync + 'static,
+{
+    fn new(name: &str, transform: F) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            transform,
+        })
+    }
+}
+
+#[async_trait]
+impl<F> Oxi for FnOxi<F>
+where
+    F: Fn(serde_json::Value) -> Result<serde_json::Value, OxiError> + Send + Sync + 'static,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn schema_strategy(&self) -> SchemaStrategy {
+        SchemaStrategy::Infer
+    }
+
+    async fn process(&self, input: OxiData, _config: &OxiConfig) -> Result<OxiData, OxiError> {
+        let json = input
+            .data()
+            .as_json()
+            .map_err(|e| OxiError::ExecutionError(e.to_string()))?
+            .clone();
+        Ok(OxiData::from_json((self.transform)(json)?))
+    }
+}
+
+#[tokio::test]
+async fn test_builder_pipeline_with_custom_oxi_runs_with_state_tracking() {
+    let double = FnOxi::new("double_totals", |records| {
+        let doubled = records
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| json!({"id": r["id"], "total": r["total"].as_i64().unwrap() * 2}))
+            .collect();
+        Ok(serde_json::Value::Array(doubled))
+    });
+    let pipeline = PipelineBuilder::new("builder_orders")
+        .step("double", double, OxiConfig::default())
+        .step_with(|s| s.retry(1))
+        .step(
+            "select",
+            "json_select",
+            OxiConfig::builder().set("path", "[1]").build(),
+        )
+        .build();
+
+    let state_manager = StateManager::new_memory();
+    let result = pipeline
+        .execute_with_state_tracking(
+            OxiData::from_json(json!([{"id": 1, "total": 5}, {"id": 2, "total": 7}])),
+            &ConfigResolver::new(),
+            Some(state_manager.clone()),
+        )
+        .await;
+
+    assert!(result.success, "{:?}", result.step_results);
+    assert_eq!(
+        result.final_data.unwrap().data().as_json().unwrap(),
+        &json!({"id": 2, "total": 14})
+    );
+    let state = state_manager.load_state("builder_orders").await.unwrap();
+    for step_id in ["double", "select"] {
+        assert!(matches!(
+            state.step_states[step_id].status,
+            StepStatus::Completed { .. }
+        ));
+    }
+
+    // The YAML names the custom Oxi and keeps the step settings
+    let yaml = pipeline.to_yaml().unwrap();
+    let reloaded: Pipeline = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(reloaded.pipeline[0].name, "double_totals");
+    assert_eq!(reloaded.pipeline[0].retry_attempts, 1);
+    assert_eq!(reloaded.pipeline[1].config["path"], "[1]");
+    assert_eq!(reloaded.content_hash(), pipeline.content_hash());
+}
+
+#[tokio::test]
+async fn test_builder_on_failure_sees_failed_steps() {
+    let failures = Arc::new(Mutex::new(Vec::new()));
+    let seen = failures.clone();
+    let pipeline = PipelineBuilder::new("builder_failures")
+        .step(
+            "reject",
+            FnOxi::new("reject", |_| {
+                Err(OxiError::ValidationError {
+                    details: "rejected".to_string(),
+                })
+            }),
+            OxiConfig::default(),
+        )
+        .step_with(|s| s.continue_on_error())
+        .step("missing", "no_such_oxi", OxiConfig::default())
+        .on_failure(move |step| seen.lock().unwrap().push(step.step_id.clone()))
+        .build();
+
+    let result = pipeline
+        .execute_with_retries(OxiData::from_json(json!([])), &ConfigResolver::new())
+        .await;
+
+    assert!(!result.success);
+    assert_eq!(*failures.lock().unwrap(), vec!["reject", "missing"]);
+}
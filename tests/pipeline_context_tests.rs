@@ -75,3 +75,20 @@ path: "/tmp/test.csv"
     let result = context.resolve_step_references(input);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_pipeline_context_oxi_output_metadata() {
+    let mut output = oxide_flow::types::OxiData::from_json(serde_json::json!({"rows": 3}));
+    output.set_meta("source_path", "/data/orders.csv");
+    output.set_meta("rows_skipped", 2);
+
+    let mut context = PipelineContext::new();
+    context.add_oxi_output("reader", &output);
+
+    let result = context
+        .resolve_step_references(
+            "${reader.metadata.source_path}: ${reader.rows} rows, ${reader.metadata.rows_skipped} skipped",
+        )
+        .unwrap();
+    assert_eq!(result, "/data/orders.csv: 3 rows, 2 skipped");
+}
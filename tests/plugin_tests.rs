@@ -0,0 +1,134 @@
+use oxide_flow::config_resolver::ConfigResolver;
+use oxide_flow::oxis::plugin::{describe_plugin, load_plugins, PluginConfig, SubprocessOxi};
+use oxide_flow::oxis::prelude::*;
+use oxide_flow::pipeline::Pipeline;
+use serde_json::json;
+
+fn upper_plugin() -> PluginConfig {
+    PluginConfig {
+        name: "upper".to_string(),
+        command: Some("python3".to_string()),
+        args: vec![concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/upper_plugin.py"
+        )
+        .to_string()],
+        ..PluginConfig::default()
+    }
+}
+
+fn records() -> OxiData {
+    OxiData::from_json(json!([{"name": "ada"}, {"name": "grace"}]))
+}
+
+fn pids(output: &OxiData) -> Vec<i64> {
+    let records = output.data().as_json().unwrap().as_array().unwrap().clone();
+    records.iter().map(|r| r["pid"].as_i64().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_subprocess_plugin_runs_as_pipeline_step() {
+    let yaml = r#"
+pipeline:
+  - name: upper
+    id: shout
+    config:
+      field: name
+"#;
+    let mut pipeline: Pipeline = serde_yaml::from_str(yaml).unwrap();
+    pipeline.attach_plugins(&load_plugins(&[upper_plugin()]).unwrap());
+
+    let result = pipeline
+        .execute_with_retries(records(), &ConfigResolver::new())
+        .await;
+
+    assert!(result.success, "{:?}", result.step_results);
+    let output = result.final_data.unwrap();
+    let names: Vec<_> = output
+        .data()
+        .as_json()
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].clone())
+        .collect();
+    assert_eq!(names, vec![json!("ADA"), json!("GRACE")]);
+}
+
+#[tokio::test]
+async fn test_keep_alive_reuses_the_plugin_process() {
+    let config = OxiConfig::builder().set("field", "name").build();
+
+    let per_call = SubprocessOxi::new(upper_plugin());
+    let first = per_call.process(records(), &config).await.unwrap();
+    let second = per_call.process(records(), &config).await.unwrap();
+    assert_ne!(pids(&first)[0], pids(&second)[0]);
+
+    let pooled = SubprocessOxi::new(PluginConfig {
+        keep_alive: true,
+        ..upper_plugin()
+    });
+    let first = pooled.process(records(), &config).await.unwrap();
+    let second = pooled.process(records(), &config).await.unwrap();
+    assert_eq!(pids(&first)[0], pids(&second)[0]);
+}
+
+#[tokio::test]
+async fn test_plugin_error_and_timeout() {
+    let plugin = SubprocessOxi::new(PluginConfig {
+        timeout_ms: Some(300),
+        keep_alive: true,
+        ..upper_plugin()
+    });
+
+    let failed = plugin
+        .process(records(), &OxiConfig::builder().set("mode", "fail").build())
+        .await
+        .unwrap_err();
+    assert!(
+        failed.to_string().contains("refusing to upper-case"),
+        "{failed}"
+    );
+
+    let hung = plugin
+        .process(records(), &OxiConfig::builder().set("mode", "hang").build())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(hung, OxiError::ProcessingTimeout { max_ms: 300, .. }),
+        "{hung}"
+    );
+
+    // The hung process was discarded, so the next call gets a fresh one
+    let config = OxiConfig::builder().set("field", "name").build();
+    assert!(plugin.process(records(), &config).await.is_ok());
+}
+
+#[test]
+fn test_describe_handshake() {
+    let description = describe_plugin(&upper_plugin()).unwrap();
+    assert_eq!(description.name, "upper");
+    assert_eq!(description.config_schema().required, vec!["field"]);
+
+    let renamed = PluginConfig {
+        name: "shout".to_string(),
+        ..upper_plugin()
+    };
+    let error = describe_plugin(&renamed).unwrap_err().to_string();
+    assert!(error.contains("describes itself as 'upper'"), "{error}");
+
+    let missing = PluginConfig {
+        command: Some("/nonexistent/plugin".to_string()),
+        args: Vec::new(),
+        ..upper_plugin()
+    };
+    assert!(describe_plugin(&missing).is_err());
+
+    // Plugins can't shadow built-in Oxis
+    let shadowing = PluginConfig {
+        name: "parse_json".to_string(),
+        ..upper_plugin()
+    };
+    assert!(load_plugins(&[shadowing]).is_err());
+}
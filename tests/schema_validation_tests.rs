@@ -1,5 +1,9 @@
 use oxide_flow::config::{OxiConfigSchema, PropertySchema};
-use oxide_flow::types::OxiConfig;
+use oxide_flow::types::{
+    Data, FieldConstraint, FieldSchema, FieldType, InferenceOptions, MergePolicy, OxiConfig,
+    OxiSchema,
+};
+use serde_json::json;
 use std::collections::HashMap;
 
 #[test]
@@ -177,3 +181,911 @@ fn test_apply_defaults() {
     // Check that existing values weren't overwritten
     assert_eq!(config.get_string("custom_field").unwrap(), "custom_value");
 }
+
+/// GeoJSON Feature schema (geojson.org/schema/Feature.json), trimmed to the
+/// Point and LineString geometries and with those moved into `definitions`
+fn geojson_feature_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "$id": "https://geojson.org/schema/Feature.json",
+        "title": "GeoJSON Feature",
+        "type": "object",
+        "required": ["type", "properties", "geometry"],
+        "properties": {
+            "type": {"type": "string", "enum": ["Feature"]},
+            "id": {"oneOf": [{"type": "number"}, {"type": "string"}]},
+            "properties": {"oneOf": [{"type": "null"}, {"type": "object"}]},
+            "geometry": {
+                "oneOf": [
+                    {"type": "null"},
+                    {"$ref": "#/definitions/Point"},
+                    {"$ref": "#/definitions/LineString"}
+                ]
+            },
+            "bbox": {"type": "array", "minItems": 4, "items": {"type": "number"}}
+        },
+        "definitions": {
+            "Point": {
+                "title": "GeoJSON Point",
+                "type": "object",
+                "required": ["type", "coordinates"],
+                "properties": {
+                    "type": {"type": "string", "enum": ["Point"]},
+                    "coordinates": {"type": "array", "minItems": 2, "items": {"type": "number"}}
+                }
+            },
+            "LineString": {
+                "title": "GeoJSON LineString",
+                "type": "object",
+                "required": ["type", "coordinates"],
+                "properties": {
+                    "type": {"type": "string", "enum": ["LineString"]},
+                    "coordinates": {
+                        "type": "array",
+                        "minItems": 2,
+                        "items": {"type": "array", "minItems": 2, "items": {"type": "number"}}
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// JSON:API document schema (jsonapi.org/schema), trimmed to the success
+/// and failure branches
+fn json_api_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "http://json-schema.org/draft-06/schema#",
+        "title": "JSON:API Schema",
+        "oneOf": [
+            {"$ref": "#/definitions/success"},
+            {"$ref": "#/definitions/failure"}
+        ],
+        "definitions": {
+            "success": {
+                "type": "object",
+                "required": ["data"],
+                "properties": {
+                    "data": {"$ref": "#/definitions/data"},
+                    "included": {
+                        "type": "array",
+                        "items": {"$ref": "#/definitions/resource"},
+                        "uniqueItems": true
+                    },
+                    "meta": {"$ref": "#/definitions/meta"},
+                    "jsonapi": {"$ref": "#/definitions/jsonapi"}
+                },
+                "additionalProperties": false
+            },
+            "failure": {
+                "type": "object",
+                "required": ["errors"],
+                "properties": {
+                    "errors": {
+                        "type": "array",
+                        "items": {"$ref": "#/definitions/error"},
+                        "uniqueItems": true
+                    },
+                    "meta": {"$ref": "#/definitions/meta"},
+                    "jsonapi": {"$ref": "#/definitions/jsonapi"}
+                },
+                "additionalProperties": false
+            },
+            "meta": {"type": "object", "additionalProperties": true},
+            "data": {
+                "description": "The document's \"primary data\" is a representation of the resource or collection of resources targeted by a request.",
+                "oneOf": [
+                    {"$ref": "#/definitions/resource"},
+                    {
+                        "type": "array",
+                        "items": {"$ref": "#/definitions/resource"},
+                        "uniqueItems": true
+                    },
+                    {"type": "null"}
+                ]
+            },
+            "resource": {
+                "type": "object",
+                "required": ["type", "id"],
+                "properties": {
+                    "type": {"type": "string"},
+                    "id": {"type": "string"},
+                    "attributes": {"type": "object"},
+                    "meta": {"$ref": "#/definitions/meta"}
+                }
+            },
+            "error": {
+                "type": "object",
+                "properties": {
+                    "id": {"type": "string"},
+                    "status": {"type": "string", "pattern": "^[1-5][0-9]{2}$"},
+                    "code": {"type": "string"},
+                    "title": {"type": "string"},
+                    "detail": {"type": "string"},
+                    "source": {
+                        "type": "object",
+                        "properties": {
+                            "pointer": {"type": "string", "pattern": "^/"},
+                            "parameter": {"type": "string"}
+                        }
+                    }
+                }
+            },
+            "jsonapi": {
+                "type": "object",
+                "properties": {
+                    "version": {"type": "string"},
+                    "meta": {"$ref": "#/definitions/meta"}
+                }
+            }
+        }
+    })
+}
+
+#[test]
+fn test_json_schema_import_geojson_feature() {
+    use oxide_flow::types::{FieldConstraint, FieldType, OxiData, OxiSchema};
+
+    let schema = OxiSchema::infer_from_json_schema(&geojson_feature_schema()).unwrap();
+
+    let kind = &schema.fields["type"];
+    assert_eq!(kind.field_type, FieldType::String);
+    assert!(!kind.nullable);
+    assert_eq!(
+        kind.constraints,
+        vec![FieldConstraint::OneOf(vec![serde_json::json!("Feature")])]
+    );
+    assert_eq!(schema.fields["id"].field_type, FieldType::Mixed);
+    assert!(schema.fields["id"].nullable, "id is not required");
+    assert_eq!(
+        schema.fields["bbox"].field_type,
+        FieldType::Array(Box::new(FieldType::Float))
+    );
+
+    // Point and LineString merge into one geometry object
+    let geometry = &schema.fields["geometry"];
+    assert!(geometry.nullable);
+    let FieldType::Object(geometry_fields) = &geometry.field_type else {
+        panic!(
+            "geometry should be an object, got {:?}",
+            geometry.field_type
+        );
+    };
+    assert_eq!(geometry_fields["coordinates"].field_type, FieldType::Mixed);
+    assert!(!geometry_fields["type"].nullable);
+
+    let point = serde_json::json!({
+        "type": "Feature",
+        "geometry": {"type": "Point", "coordinates": [102.0, 0.5]},
+        "properties": {"prop0": "value0"}
+    });
+    let line = serde_json::json!({
+        "type": "Feature",
+        "id": 7,
+        "geometry": {"type": "LineString", "coordinates": [[102.0, 0.0], [103.0, 1.0]]},
+        "properties": null
+    });
+    OxiData::with_schema(
+        oxide_flow::types::Data::Json(serde_json::json!([point, line])),
+        schema.clone(),
+    )
+    .validate()
+    .unwrap();
+
+    let not_a_feature =
+        serde_json::json!({"type": "FeatureCollection", "geometry": null, "properties": {}});
+    assert!(schema
+        .validate_data(&oxide_flow::types::Data::Json(not_a_feature))
+        .is_err());
+    let bad_geometry = serde_json::json!({
+        "type": "Feature",
+        "geometry": {"type": 5, "coordinates": [0, 0]},
+        "properties": {}
+    });
+    let err = schema
+        .validate_data(&oxide_flow::types::Data::Json(bad_geometry))
+        .unwrap_err();
+    assert!(err.to_string().contains("geometry.type"), "{err}");
+}
+
+#[test]
+fn test_json_schema_import_json_api() {
+    use oxide_flow::types::{Data, FieldType, OxiSchema};
+
+    let schema = OxiSchema::infer_from_json_schema(&json_api_schema()).unwrap();
+
+    let mut names = schema.field_names();
+    names.sort();
+    assert_eq!(names, vec!["data", "errors", "included", "jsonapi", "meta"]);
+    // Each branch requires its own top-level member, so neither is required overall
+    assert!(schema.fields["data"].nullable);
+    assert!(schema.fields["errors"].nullable);
+    assert_eq!(schema.fields["data"].field_type, FieldType::Mixed);
+    assert!(schema.fields["data"]
+        .description
+        .as_deref()
+        .unwrap()
+        .contains("primary data"));
+
+    let success = serde_json::json!({
+        "data": {"type": "articles", "id": "1", "attributes": {"title": "JSON:API"}},
+        "jsonapi": {"version": "1.1"}
+    });
+    schema.validate_data(&Data::Json(success)).unwrap();
+
+    let failure = serde_json::json!({
+        "errors": [{
+            "status": "422",
+            "source": {"pointer": "/data/attributes/firstName"},
+            "title": "Invalid Attribute"
+        }]
+    });
+    schema.validate_data(&Data::Json(failure)).unwrap();
+
+    // `pattern` is checked as a regex, inside nested arrays and objects
+    let bad_pointer = serde_json::json!({
+        "errors": [{"status": "422", "source": {"pointer": "data/attributes"}}]
+    });
+    let err = schema.validate_data(&Data::Json(bad_pointer)).unwrap_err();
+    assert!(
+        err.to_string().contains("errors[0].source.pointer"),
+        "{err}"
+    );
+    let bad_status = serde_json::json!({"errors": [{"status": "abc"}]});
+    assert!(schema.validate_data(&Data::Json(bad_status)).is_err());
+}
+
+#[test]
+fn test_json_schema_import_refs() {
+    use oxide_flow::types::{FieldType, OxiSchema};
+
+    // Recursive definitions stop expanding instead of looping
+    let tree = serde_json::json!({
+        "$ref": "#/definitions/node",
+        "definitions": {
+            "node": {
+                "type": "object",
+                "required": ["name"],
+                "properties": {
+                    "name": {"type": "string", "minLength": 1, "maxLength": 64},
+                    "children": {"type": "array", "items": {"$ref": "#/definitions/node"}}
+                }
+            }
+        }
+    });
+    let schema = OxiSchema::infer_from_json_schema(&tree).unwrap();
+    assert_eq!(schema.fields["name"].max_size, Some(64));
+    assert!(matches!(
+        schema.fields["children"].field_type,
+        FieldType::Array(_)
+    ));
+
+    // Arrays of records import the record schema
+    let records = serde_json::json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "properties": {"at": {"type": "string", "format": "date-time"}}
+        }
+    });
+    let schema = OxiSchema::infer_from_json_schema(&records).unwrap();
+    assert_eq!(schema.fields["at"].field_type, FieldType::DateTime);
+
+    let external = serde_json::json!({"properties": {"a": {"$ref": "other.json#/a"}}});
+    assert!(OxiSchema::infer_from_json_schema(&external).is_err());
+    let dangling = serde_json::json!({"properties": {"a": {"$ref": "#/definitions/missing"}}});
+    let err = OxiSchema::infer_from_json_schema(&dangling).unwrap_err();
+    assert!(err.to_string().contains("#/definitions/missing"), "{err}");
+}
+
+#[test]
+fn test_inference_marks_optional_fields_nullable() {
+    let data = Data::Json(json!([
+        {"id": 1, "email": "a@example.com"},
+        {"id": 2},
+        {"id": 3, "email": null}
+    ]));
+    let schema = OxiSchema::infer_from_data(&data).unwrap();
+
+    assert!(!schema.fields["id"].nullable);
+    assert_eq!(schema.fields["id"].field_type, FieldType::Integer);
+    assert!(schema.fields["email"].nullable);
+    assert_eq!(schema.fields["email"].field_type, FieldType::String);
+    assert!(schema.validate_data(&data).is_ok());
+}
+
+#[test]
+fn test_inference_conflicting_types_become_mixed() {
+    let data = Data::Json(json!([
+        {"code": 7, "score": 1},
+        {"code": "A7", "score": 2.5}
+    ]));
+    let schema = OxiSchema::infer_from_data(&data).unwrap();
+
+    assert_eq!(schema.fields["code"].field_type, FieldType::Mixed);
+    assert_eq!(schema.fields["score"].field_type, FieldType::Float);
+    assert!(schema.validate_data(&data).is_ok());
+}
+
+#[test]
+fn test_inference_recurses_into_nested_values() {
+    let data = Data::Json(json!([
+        {"user": {"name": "Ann", "age": 30}, "tags": ["a", "b"], "orders": [{"total": 5}]},
+        {"user": {"name": "Bo", "vip": true}, "tags": [], "orders": [{"total": 7.5, "note": "gift"}]}
+    ]));
+    let schema = OxiSchema::infer_from_data(&data).unwrap();
+
+    let FieldType::Object(user) = &schema.fields["user"].field_type else {
+        panic!("user should be an object");
+    };
+    assert_eq!(user["name"].field_type, FieldType::String);
+    assert!(!user["name"].nullable);
+    assert_eq!(user["age"].field_type, FieldType::Integer);
+    assert!(user["age"].nullable);
+    assert_eq!(user["vip"].field_type, FieldType::Boolean);
+
+    assert_eq!(
+        schema.fields["tags"].field_type,
+        FieldType::Array(Box::new(FieldType::String))
+    );
+    let FieldType::Array(order) = &schema.fields["orders"].field_type else {
+        panic!("orders should be an array");
+    };
+    let FieldType::Object(order) = order.as_ref() else {
+        panic!("orders should hold objects");
+    };
+    assert_eq!(order["total"].field_type, FieldType::Float);
+    assert!(order["note"].nullable);
+}
+
+#[test]
+fn test_inference_sample_size_caps_inspected_records() {
+    let mut records: Vec<serde_json::Value> = (0..150).map(|id| json!({"id": id})).collect();
+    records[120] = json!({"id": 120, "late": "only here"});
+    let data = Data::Json(serde_json::Value::Array(records));
+
+    let default = OxiSchema::infer_from_data(&data).unwrap();
+    assert!(!default.fields.contains_key("late"));
+
+    let small = OxiSchema::infer_from_data_sampled(&data, 10).unwrap();
+    assert_eq!(small.field_names(), vec!["id"]);
+
+    let full = OxiSchema::infer_from_data_sampled(&data, 200).unwrap();
+    assert!(full.fields["late"].nullable);
+    assert!(!full.fields["id"].nullable);
+}
+
+#[test]
+fn test_inference_detects_datetimes_by_field_name() {
+    let data = Data::Json(json!([
+        {"created_at": "2024-01-05 13:00:00", "event_time": 1704459600000_i64,
+         "birthdate": "1990-04-01", "updated_at": "2024-01-05T13:00:00Z",
+         "label": "2024-01-05", "response_time": 250, "start_date": "soon"},
+        {"created_at": "2024-01-06 09:30:00", "event_time": 1704531000000_i64,
+         "birthdate": null, "updated_at": "2024-01-06T09:30:00Z",
+         "label": "2024-01-06", "response_time": 3, "start_date": "2024-02-01"}
+    ]));
+
+    let detecting = InferenceOptions {
+        detect_datetimes: true,
+        ..InferenceOptions::default()
+    };
+    let schema = OxiSchema::infer_from_data_with(&data, detecting).unwrap();
+    for name in ["created_at", "birthdate", "updated_at"] {
+        assert_eq!(
+            schema.fields[name].field_type,
+            FieldType::DateTime,
+            "{name}"
+        );
+    }
+    // No name hint, a value that does not parse, or a number
+    assert_eq!(schema.fields["label"].field_type, FieldType::String);
+    assert_eq!(schema.fields["start_date"].field_type, FieldType::String);
+    assert_eq!(schema.fields["event_time"].field_type, FieldType::Integer);
+    assert_eq!(
+        schema.fields["response_time"].field_type,
+        FieldType::Integer
+    );
+    assert!(schema.validate_data(&data).is_ok());
+
+    // Detection is opt-in
+    let plain = OxiSchema::infer_from_data(&data).unwrap();
+    assert_eq!(plain.fields["created_at"].field_type, FieldType::String);
+    assert_eq!(plain.fields["event_time"].field_type, FieldType::Integer);
+}
+
+fn constraint_options() -> InferenceOptions {
+    InferenceOptions {
+        infer_constraints: true,
+        ..InferenceOptions::default()
+    }
+}
+
+#[test]
+fn test_inference_infers_numeric_ranges_when_enabled() {
+    let data = Data::Json(json!([
+        {"age": 34, "score": 7.5, "name": "Ada"},
+        {"age": 19, "score": 9.25, "name": "Grace"},
+        {"age": 61, "score": null, "name": "Barbara"}
+    ]));
+
+    // Off by default
+    let plain = OxiSchema::infer_from_data(&data).unwrap();
+    assert!(plain.fields.values().all(|f| f.constraints.is_empty()));
+
+    let schema = OxiSchema::infer_from_data_with(&data, constraint_options()).unwrap();
+    assert_eq!(
+        schema.fields["age"].constraints,
+        vec![
+            FieldConstraint::MinValue(19.0),
+            FieldConstraint::MaxValue(61.0)
+        ]
+    );
+    assert_eq!(
+        schema.fields["score"].constraints,
+        vec![
+            FieldConstraint::MinValue(7.5),
+            FieldConstraint::MaxValue(9.25)
+        ]
+    );
+    // Unique names get a length bound but are not an enum
+    assert_eq!(
+        schema.fields["name"].constraints,
+        vec![FieldConstraint::MaxLength(7)]
+    );
+
+    assert!(schema.validate_data(&data).is_ok());
+    let out_of_range = Data::Json(json!([{"age": 70, "score": 8.0, "name": "Ada"}]));
+    assert!(schema.validate_data(&out_of_range).is_err());
+}
+
+#[test]
+fn test_inference_infers_small_enums_when_enabled() {
+    let statuses = ["active", "inactive", "pending"];
+    let records: Vec<serde_json::Value> = (0..30)
+        .map(|i| json!({"id": format!("user-{i}"), "status": statuses[i % 3]}))
+        .collect();
+    let data = Data::Json(serde_json::Value::Array(records));
+
+    let schema = OxiSchema::infer_from_data_with(&data, constraint_options()).unwrap();
+    assert_eq!(
+        schema.fields["status"].constraints,
+        vec![
+            FieldConstraint::MaxLength(8),
+            FieldConstraint::OneOf(vec![json!("active"), json!("inactive"), json!("pending")]),
+        ]
+    );
+    // 30 distinct ids are too many for an enum
+    assert_eq!(
+        schema.fields["id"].constraints,
+        vec![FieldConstraint::MaxLength(7)]
+    );
+
+    assert!(schema.validate_data(&data).is_ok());
+    let unknown = Data::Json(json!([{"id": "user-1", "status": "banned"}]));
+    assert!(schema.validate_data(&unknown).is_err());
+}
+
+#[test]
+fn test_inferred_max_length_counts_bytes() {
+    // "Zoë" and "Ørsted" are 4 and 7 bytes, as the validator measures them
+    let data = Data::Json(json!([{"name": "Zoë"}, {"name": "Ørsted"}]));
+
+    let schema = OxiSchema::infer_from_data_with(&data, constraint_options()).unwrap();
+    assert_eq!(
+        schema.fields["name"].constraints,
+        vec![FieldConstraint::MaxLength(7)]
+    );
+    assert!(schema.validate_data(&data).is_ok());
+}
+
+#[test]
+fn test_datetime_validation_accepts_formats() {
+    let field = FieldSchema::new(FieldType::DateTime);
+    for value in [
+        json!("2024-01-05T13:00:00Z"),
+        json!("Fri, 05 Jan 2024 13:00:00 +0000"),
+        json!("2024-01-05 13:00:00"),
+    ] {
+        assert!(field.validate_value(&value, "seen").is_ok(), "{value}");
+    }
+    for value in [
+        json!("05/01/2024"),
+        json!(true),
+        json!(1704459600),
+        json!(1704459600000_i64),
+    ] {
+        assert!(field.validate_value(&value, "seen").is_err(), "{value}");
+    }
+
+    let field = FieldSchema {
+        datetime_formats: Some(vec!["%d/%m/%Y".to_string()]),
+        ..FieldSchema::new(FieldType::DateTime)
+    };
+    assert!(field.validate_value(&json!("05/01/2024"), "seen").is_ok());
+    assert!(field.validate_value(&json!(1704459600), "seen").is_err());
+
+    // Formats survive a serialization round trip and are omitted when unset
+    let json = serde_json::to_value(&field).unwrap();
+    assert_eq!(json["datetime_formats"], json!(["%d/%m/%Y"]));
+    assert_eq!(serde_json::from_value::<FieldSchema>(json).unwrap(), field);
+    let plain = serde_json::to_value(FieldSchema::new(FieldType::DateTime)).unwrap();
+    assert!(plain.get("datetime_formats").is_none());
+}
+
+#[test]
+fn test_schema_merge() {
+    let a = OxiSchema::infer_from_data(&Data::Json(json!({"id": 1, "name": "x"}))).unwrap();
+    let b = OxiSchema::infer_from_data(&Data::Json(json!({"id": "1", "active": true}))).unwrap();
+
+    let merged = a.merge(&b, MergePolicy::WidenToMixed);
+    assert_eq!(merged.field_names(), vec!["active", "id", "name"]);
+    assert_eq!(merged.fields["id"].field_type, FieldType::Mixed);
+    assert!(!merged.fields["id"].nullable);
+    assert!(merged.fields["name"].nullable);
+    assert!(merged.fields["active"].nullable);
+    assert_eq!(merged.fields["id"].examples, vec![json!(1), json!("1")]);
+}
+
+#[test]
+fn test_schema_merge_policies() {
+    let mut left = OxiSchema::empty();
+    left.add_field("id".to_string(), FieldSchema::new(FieldType::Integer));
+    left.metadata.row_count_hint = Some(10);
+    let mut right = OxiSchema::empty();
+    right.add_field(
+        "id".to_string(),
+        FieldSchema {
+            nullable: true,
+            ..FieldSchema::new(FieldType::String)
+        },
+    );
+    right.metadata.row_count_hint = Some(5);
+    right.metadata.version = "2.0".to_string();
+    right.metadata.created_at = left.metadata.created_at + chrono::Duration::seconds(1);
+
+    for (policy, expected) in [
+        (MergePolicy::PreferLeft, FieldType::Integer),
+        (MergePolicy::PreferRight, FieldType::String),
+        (MergePolicy::WidenToMixed, FieldType::Mixed),
+    ] {
+        let merged = left.merge(&right, policy);
+        assert_eq!(merged.fields["id"].field_type, expected, "{policy:?}");
+        assert!(merged.fields["id"].nullable, "{policy:?}");
+        assert_eq!(merged.metadata.row_count_hint, Some(15));
+        assert_eq!(merged.metadata.version, "2.0");
+        assert_eq!(merged.metadata.created_at, right.metadata.created_at);
+    }
+
+    // Unknown yields to the known type under every policy
+    let mut unknown = OxiSchema::empty();
+    unknown.add_field("id".to_string(), FieldSchema::new(FieldType::Unknown));
+    let merged = unknown.merge(&left, MergePolicy::PreferLeft);
+    assert_eq!(merged.fields["id"].field_type, FieldType::Integer);
+}
+
+#[test]
+fn test_schema_normalize_ignores_order_time_and_examples() {
+    let first = OxiSchema::infer_from_data(&Data::Json(json!([
+        {"id": 1, "name": "ada", "tags": {"team": "core"}}
+    ])))
+    .unwrap();
+    let mut second = OxiSchema::infer_from_data(&Data::Json(json!([
+        {"tags": {"team": "infra"}, "name": "grace", "id": 2}
+    ])))
+    .unwrap();
+    second.metadata.created_at = first.metadata.created_at + chrono::Duration::hours(1);
+    second.metadata.row_count_hint = Some(1_000);
+
+    assert_ne!(first, second);
+    assert!(first.structurally_eq(&second));
+    let normalized = first.normalize();
+    assert_eq!(
+        normalized.fields.keys().collect::<Vec<_>>(),
+        ["id", "name", "tags"]
+    );
+    assert!(normalized.fields["id"].examples.is_empty());
+
+    second.fields.get_mut("id").unwrap().nullable = true;
+    assert!(!first.structurally_eq(&second));
+
+    let mut renumbered = first.clone();
+    renumbered.metadata.version = "2.0".to_string();
+    assert!(!first.structurally_eq(&renumbered));
+}
+
+fn unique_id_schema() -> OxiSchema {
+    use oxide_flow::types::{FieldConstraint, FieldSchema};
+
+    let mut schema = OxiSchema::empty();
+    schema.add_field(
+        "id".to_string(),
+        FieldSchema {
+            constraints: vec![FieldConstraint::UniqueValues],
+            ..FieldSchema::new(FieldType::Integer)
+        },
+    );
+    schema.add_field("name".to_string(), FieldSchema::new(FieldType::String));
+    schema
+}
+
+fn id_records(count: usize) -> Vec<serde_json::Value> {
+    (0..count)
+        .map(|i| json!({"id": i, "name": format!("user-{i}")}))
+        .collect()
+}
+
+#[test]
+fn test_unique_values_accepts_distinct_ids() {
+    let data = Data::Json(serde_json::Value::Array(id_records(10_000)));
+    assert!(unique_id_schema().validate_data(&data).is_ok());
+}
+
+#[test]
+fn test_unique_values_reports_first_five_duplicates() {
+    let mut records = id_records(10_000);
+    // Seven duplicated ids; 42 appears three times
+    for (index, id) in [
+        (100, 42),
+        (200, 42),
+        (300, 7),
+        (400, 8),
+        (500, 9),
+        (600, 10),
+        (700, 11),
+        (800, 12),
+    ] {
+        records[index]["id"] = json!(id);
+    }
+    let data = Data::Json(serde_json::Value::Array(records));
+
+    let err = unique_id_schema().validate_data(&data).unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("Field 'id' must be unique, found 7 duplicated values"),
+        "{message}"
+    );
+    assert!(message.contains("42 at rows [42, 100, 200]"), "{message}");
+    assert!(message.contains("7 at rows [7, 300]"), "{message}");
+    assert!(message.contains("10 at rows [10, 600]"), "{message}");
+    assert!(!message.contains("11 at rows"), "{message}");
+    assert!(message.ends_with("and 2 more"), "{message}");
+}
+
+#[test]
+fn test_unique_values_ignores_nulls_and_checks_nested_arrays() {
+    use oxide_flow::types::{FieldConstraint, FieldSchema};
+
+    let nullable =
+        Data::Json(json!([{"id": null, "name": "a"}, {"name": "b"}, {"id": null, "name": "c"}]));
+    let mut schema = unique_id_schema();
+    schema.fields.get_mut("id").unwrap().nullable = true;
+    assert!(schema.validate_data(&nullable).is_ok());
+
+    let mut order = OxiSchema::empty();
+    order.add_field(
+        "lines".to_string(),
+        FieldSchema::new(FieldType::Array(Box::new(FieldType::Object(
+            unique_id_schema().fields,
+        )))),
+    );
+    let ok = Data::Json(json!({"lines": [{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]}));
+    assert!(order.validate_data(&ok).is_ok());
+    let dup = Data::Json(json!({"lines": [{"id": 1, "name": "a"}, {"id": 1, "name": "b"}]}));
+    let message = order.validate_data(&dup).unwrap_err().to_string();
+    assert!(message.contains("'lines.id'"), "{message}");
+    assert!(message.contains("1 at rows [0, 1]"), "{message}");
+
+    let single = FieldConstraint::UniqueValues.validate_value(&json!(5), "id");
+    assert!(single.is_ok());
+}
+
+#[test]
+fn test_unique_values_in_json_schema() {
+    use oxide_flow::types::FieldConstraint;
+
+    let exported = unique_id_schema().to_json_schema();
+    assert_eq!(
+        exported["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    assert_eq!(exported["properties"]["id"]["type"], "integer");
+    assert_eq!(exported["properties"]["id"]["x-unique-values"], true);
+    assert!(exported["properties"]["name"]
+        .get("x-unique-values")
+        .is_none());
+    assert_eq!(exported["required"], json!(["id", "name"]));
+
+    let imported = OxiSchema::infer_from_json_schema(&exported).unwrap();
+    assert_eq!(
+        imported.fields["id"].constraints,
+        vec![FieldConstraint::UniqueValues]
+    );
+}
+
+/// A schema using every `FieldType` and `FieldConstraint`
+fn every_type_schema() -> OxiSchema {
+    use oxide_flow::types::{FieldConstraint, FieldSchema};
+
+    let mut address = HashMap::new();
+    address.insert("city".to_string(), FieldSchema::new(FieldType::String));
+    address.insert(
+        "zip".to_string(),
+        FieldSchema {
+            nullable: true,
+            ..FieldSchema::new(FieldType::String)
+        },
+    );
+
+    let mut schema = OxiSchema::empty();
+    schema.add_field(
+        "id".to_string(),
+        FieldSchema {
+            constraints: vec![
+                FieldConstraint::MinValue(1.0),
+                FieldConstraint::MaxValue(1_000_000.0),
+                FieldConstraint::UniqueValues,
+            ],
+            description: Some("Record id".to_string()),
+            ..FieldSchema::new(FieldType::Integer)
+        },
+    );
+    schema.add_field(
+        "name".to_string(),
+        FieldSchema {
+            max_size: Some(40),
+            constraints: vec![
+                FieldConstraint::MinLength(1),
+                FieldConstraint::MaxLength(40),
+                FieldConstraint::Pattern("^[A-Z]".to_string()),
+            ],
+            examples: vec![json!("Ada")],
+            ..FieldSchema::new(FieldType::String)
+        },
+    );
+    schema.add_field(
+        "status".to_string(),
+        FieldSchema {
+            constraints: vec![
+                FieldConstraint::OneOf(vec![json!("active"), json!("closed")]),
+                FieldConstraint::Custom {
+                    name: "lowercase".to_string(),
+                    rule: "value == lower(value)".to_string(),
+                },
+            ],
+            ..FieldSchema::new(FieldType::String)
+        },
+    );
+    schema.add_field(
+        "score".to_string(),
+        FieldSchema {
+            nullable: true,
+            ..FieldSchema::new(FieldType::Float)
+        },
+    );
+    schema.add_field("active".to_string(), FieldSchema::new(FieldType::Boolean));
+    schema.add_field("created".to_string(), FieldSchema::new(FieldType::DateTime));
+    schema.add_field("avatar".to_string(), FieldSchema::new(FieldType::Binary));
+    schema.add_field(
+        "tags".to_string(),
+        FieldSchema {
+            max_size: Some(10),
+            ..FieldSchema::new(FieldType::Array(Box::new(FieldType::String)))
+        },
+    );
+    schema.add_field(
+        "addresses".to_string(),
+        FieldSchema::new(FieldType::Array(Box::new(FieldType::Object(address)))),
+    );
+    schema.add_field(
+        "extra".to_string(),
+        FieldSchema {
+            nullable: true,
+            ..FieldSchema::new(FieldType::Unknown)
+        },
+    );
+    schema.add_field(
+        "value".to_string(),
+        FieldSchema {
+            nullable: true,
+            ..FieldSchema::new(FieldType::Mixed)
+        },
+    );
+    schema
+}
+
+#[test]
+fn test_json_schema_round_trip_every_type() {
+    let original = every_type_schema();
+
+    let exported = original.to_json_schema();
+    assert_eq!(
+        exported["properties"]["avatar"]["contentEncoding"],
+        "base64"
+    );
+    assert_eq!(exported["properties"]["tags"]["maxItems"], 10);
+    assert_eq!(
+        exported["properties"]["value"]["type"],
+        json!(["array", "boolean", "number", "object", "string", "null"])
+    );
+    assert_eq!(exported["properties"]["extra"], json!({}));
+
+    let (imported, warnings) = OxiSchema::from_json_schema(&exported).unwrap();
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(imported.fields.len(), original.fields.len());
+    for (name, field) in &original.fields {
+        assert_eq!(&imported.fields[name], field, "field '{name}'");
+    }
+
+    // A second export is identical, so the mapping is stable
+    assert_eq!(imported.to_json_schema(), exported);
+}
+
+#[test]
+fn test_json_schema_import_handwritten_with_warnings() {
+    use oxide_flow::types::FieldConstraint;
+
+    let document = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Order",
+        "type": "object",
+        "additionalProperties": false,
+        "required": ["order_id", "lines"],
+        "properties": {
+            "order_id": {"type": "string", "format": "uuid"},
+            "placed_at": {"type": "string", "format": "date-time"},
+            "total": {"type": "number", "minimum": 0, "exclusiveMaximum": 100000},
+            "lines": {
+                "type": "array",
+                "minItems": 1,
+                "items": {
+                    "type": "object",
+                    "required": ["sku"],
+                    "properties": {
+                        "sku": {"type": "string", "default": "n/a"},
+                        "qty": {"type": "integer", "minimum": 1}
+                    }
+                }
+            }
+        }
+    });
+
+    let (schema, warnings) = OxiSchema::from_json_schema(&document).unwrap();
+
+    assert_eq!(schema.fields["order_id"].field_type, FieldType::String);
+    assert!(!schema.fields["order_id"].nullable);
+    assert_eq!(schema.fields["placed_at"].field_type, FieldType::DateTime);
+    assert!(schema.fields["placed_at"].nullable);
+    assert_eq!(
+        schema.fields["total"].constraints,
+        vec![FieldConstraint::MinValue(0.0)]
+    );
+    let FieldType::Array(items) = &schema.fields["lines"].field_type else {
+        panic!("lines should be an array");
+    };
+    let FieldType::Object(line) = items.as_ref() else {
+        panic!("lines should hold objects");
+    };
+    assert!(!line["sku"].nullable);
+    assert_eq!(
+        line["qty"].constraints,
+        vec![FieldConstraint::MinValue(1.0)]
+    );
+
+    let mut warnings = warnings;
+    warnings.sort();
+    assert_eq!(
+        warnings,
+        [
+            "(root): unsupported keyword 'additionalProperties' ignored",
+            "lines: unsupported keyword 'minItems' ignored",
+            "lines[].sku: unsupported keyword 'default' ignored",
+            "order_id: format \"uuid\" is not supported, read as a plain string",
+            "total: unsupported keyword 'exclusiveMaximum' ignored",
+        ]
+    );
+
+    // The lenient importer keeps its signature and drops the warnings
+    let lenient = OxiSchema::infer_from_json_schema(&document).unwrap();
+    assert_eq!(lenient.fields, schema.fields);
+}